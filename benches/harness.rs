@@ -3,7 +3,7 @@
 use cgmath::*;
 use rand::distributions::uniform::SampleUniform;
 use rand::*;
-use spade::*;
+use spade::SpadeNum;
 
 pub fn uniform_points_in_range<S: SpadeNum + SampleUniform + BaseNum, R: Rng>(
     range: S,
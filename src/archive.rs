@@ -0,0 +1,731 @@
+// Copyright 2017 The Spade Developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Zero-copy archives of r-trees and triangulations.
+//!
+//! `StaticRTree` and `StaticTriangulation` are flat, read-only snapshots of an
+//! `RTree` or a triangulation that can be written with `rkyv`. The archived
+//! forms, `ArchivedStaticRTree` and `ArchivedStaticTriangulation`, are queried
+//! directly from the byte buffer they were loaded from, nothing needs to be
+//! deserialized. Coordinates are stored as `f64`.
+//! Requires the `rkyv` feature.
+//!
+//! # Example
+//!
+//! ```
+//! # extern crate rkyv;
+//! # extern crate spade;
+//! use spade::archive::{ArchivedStaticRTree, StaticRTree};
+//! use spade::rtree::RTree;
+//! use spade::Point2;
+//!
+//! # fn main() {
+//! let mut tree = RTree::new();
+//! tree.insert(Point2::new(0.0, 0.0));
+//! tree.insert(Point2::new(3.0, 1.0));
+//! let bytes = rkyv::to_bytes::<rkyv::rancor::Error>(&StaticRTree::from_rtree(&tree)).unwrap();
+//!
+//! // Later, for example after reading or mapping `bytes` from a file:
+//! let archived =
+//!     rkyv::access::<ArchivedStaticRTree<Point2<f64>>, rkyv::rancor::Error>(&bytes).unwrap();
+//! let nearest = archived.nearest_neighbor(&[2.5, 0.5]).unwrap();
+//! assert_eq!(nearest.x.to_native(), 3.0);
+//! # }
+//! ```
+
+use crate::delaunay::{
+    ConstrainedDelaunayTriangulation, DelaunayLocateStructure, DelaunayTriangulation, FaceHandle,
+    VertexHandle,
+};
+use crate::exactpred::orient2d;
+use crate::kernels::DelaunayKernel;
+use crate::point::Point2;
+use crate::point_traits::{PointN, TwoDimensional};
+use crate::rtree::{RTree, RTreeNode};
+use crate::traits::{HasPosition2D, SpatialObject};
+use alloc::collections::{BinaryHeap, VecDeque};
+use alloc::{vec, vec::Vec};
+use core::cmp::Ordering;
+use num::ToPrimitive;
+use rkyv::Archive;
+
+/// Marks a missing neighbor of a triangle, i.e. an edge on the convex hull.
+const NO_NEIGHBOR: u32 = u32::MAX;
+
+fn to_f64_point<P>(point: &P) -> [f64; 2]
+where
+    P: TwoDimensional,
+    P::Scalar: ToPrimitive,
+{
+    [
+        point.nth(0).to_f64().unwrap_or(f64::NAN),
+        point.nth(1).to_f64().unwrap_or(f64::NAN),
+    ]
+}
+
+fn to_index(index: usize) -> u32 {
+    u32::try_from(index).expect("Archives are limited to u32::MAX elements")
+}
+
+/// A node of a `StaticRTree`.
+///
+/// The children of a node are stored contiguously, `first_node..first_node + node_count`
+/// in the node list and `first_item..first_item + item_count` in the item list.
+#[derive(Clone, Copy, Debug, PartialEq, rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)]
+struct StaticNode {
+    lower: [f64; 2],
+    upper: [f64; 2],
+    first_node: u32,
+    node_count: u32,
+    first_item: u32,
+    item_count: u32,
+}
+
+impl StaticNode {
+    fn new(lower: [f64; 2], upper: [f64; 2]) -> StaticNode {
+        StaticNode {
+            lower,
+            upper,
+            first_node: 0,
+            node_count: 0,
+            first_item: 0,
+            item_count: 0,
+        }
+    }
+}
+
+/// A read-only snapshot of an `RTree` that can be archived with `rkyv`.
+///
+/// The tree's structure is kept as is, the nodes are laid out breadth first.
+/// All queries are implemented on the archived form, `ArchivedStaticRTree`.
+#[derive(Clone, Debug, PartialEq, rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)]
+pub struct StaticRTree<T> {
+    nodes: Vec<StaticNode>,
+    items: Vec<T>,
+    item_bounds: Vec<[[f64; 2]; 2]>,
+}
+
+impl<T> StaticRTree<T>
+where
+    T: SpatialObject + Clone,
+    T::Point: TwoDimensional,
+    <T::Point as PointN>::Scalar: ToPrimitive,
+{
+    /// Creates a snapshot of an r-tree.
+    ///
+    /// # Panics
+    /// Panics if the tree contains more than `u32::MAX` nodes or objects.
+    pub fn from_rtree(tree: &RTree<T>) -> StaticRTree<T> {
+        let mut result = StaticRTree {
+            nodes: Vec::new(),
+            items: Vec::with_capacity(tree.size()),
+            item_bounds: Vec::with_capacity(tree.size()),
+        };
+        let root = tree.root();
+        if tree.size() == 0 {
+            return result;
+        }
+        let mbr = root.mbr();
+        result.nodes.push(StaticNode::new(
+            to_f64_point(&mbr.lower()),
+            to_f64_point(&mbr.upper()),
+        ));
+        let mut queue = VecDeque::new();
+        queue.push_back((root, 0));
+        while let Some((data, index)) = queue.pop_front() {
+            let first_node = result.nodes.len();
+            let first_item = result.items.len();
+            for child in data.children() {
+                let mbr = child.mbr();
+                let (lower, upper) = (to_f64_point(&mbr.lower()), to_f64_point(&mbr.upper()));
+                match child {
+                    RTreeNode::DirectoryNode(data) => {
+                        queue.push_back((data, result.nodes.len()));
+                        result.nodes.push(StaticNode::new(lower, upper));
+                    }
+                    RTreeNode::Leaf(item) => {
+                        result.items.push(item.clone());
+                        result.item_bounds.push([lower, upper]);
+                    }
+                }
+            }
+            let (node_count, item_count) = (
+                result.nodes.len() - first_node,
+                result.items.len() - first_item,
+            );
+            let node = &mut result.nodes[index];
+            node.first_node = to_index(first_node);
+            node.node_count = to_index(node_count);
+            node.first_item = to_index(first_item);
+            node.item_count = to_index(item_count);
+        }
+        result
+    }
+}
+
+impl<T> StaticRTree<T> {
+    /// Returns the number of objects contained in this tree.
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Returns `true` if this tree contains no objects.
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Returns all objects, in no particular order.
+    pub fn items(&self) -> &[T] {
+        &self.items
+    }
+}
+
+/// An entry of the priority queue used by the nearest neighbor search.
+struct QueueEntry {
+    distance2: f64,
+    index: usize,
+    is_item: bool,
+}
+
+impl PartialEq for QueueEntry {
+    fn eq(&self, other: &QueueEntry) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for QueueEntry {}
+
+impl PartialOrd for QueueEntry {
+    fn partial_cmp(&self, other: &QueueEntry) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for QueueEntry {
+    fn cmp(&self, other: &QueueEntry) -> Ordering {
+        // Reversed, the binary heap pops the closest entry first.
+        other
+            .distance2
+            .total_cmp(&self.distance2)
+            .then_with(|| self.is_item.cmp(&other.is_item))
+    }
+}
+
+fn box_distance2(lower: [f64; 2], upper: [f64; 2], point: &[f64; 2]) -> f64 {
+    let mut result = 0.0;
+    for i in 0..2 {
+        let diff = if point[i] < lower[i] {
+            lower[i] - point[i]
+        } else if point[i] > upper[i] {
+            point[i] - upper[i]
+        } else {
+            0.0
+        };
+        result += diff * diff;
+    }
+    result
+}
+
+fn boxes_intersect(lower: [f64; 2], upper: [f64; 2], other: &[[f64; 2]; 2]) -> bool {
+    (0..2).all(|i| lower[i] <= other[1][i] && other[0][i] <= upper[i])
+}
+
+impl ArchivedStaticNode {
+    fn bounds(&self) -> ([f64; 2], [f64; 2]) {
+        (
+            [self.lower[0].to_native(), self.lower[1].to_native()],
+            [self.upper[0].to_native(), self.upper[1].to_native()],
+        )
+    }
+
+    fn nodes(&self) -> core::ops::Range<usize> {
+        let first = self.first_node.to_native() as usize;
+        first..first + self.node_count.to_native() as usize
+    }
+
+    fn items(&self) -> core::ops::Range<usize> {
+        let first = self.first_item.to_native() as usize;
+        first..first + self.item_count.to_native() as usize
+    }
+}
+
+impl<T: Archive> ArchivedStaticRTree<T> {
+    /// Returns the number of objects contained in this tree.
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Returns `true` if this tree contains no objects.
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Returns all archived objects, in no particular order.
+    pub fn items(&self) -> &[T::Archived] {
+        self.items.as_slice()
+    }
+
+    fn item_bounds(&self, index: usize) -> ([f64; 2], [f64; 2]) {
+        let [lower, upper] = &self.item_bounds[index];
+        (
+            [lower[0].to_native(), lower[1].to_native()],
+            [upper[0].to_native(), upper[1].to_native()],
+        )
+    }
+
+    /// Returns the object whose bounding rectangle is closest to a point.
+    ///
+    /// For point objects this is the nearest neighbor. Returns `None` if the
+    /// tree is empty.
+    pub fn nearest_neighbor(&self, point: &[f64; 2]) -> Option<&T::Archived> {
+        self.nearest_n_neighbors(point, 1).pop()
+    }
+
+    /// Returns the `n` objects whose bounding rectangles are closest to a point.
+    ///
+    /// The objects are sorted by their distance, closest first. Fewer objects are
+    /// returned if the tree contains less than `n` objects.
+    pub fn nearest_n_neighbors(&self, point: &[f64; 2], n: usize) -> Vec<&T::Archived> {
+        let mut result = Vec::with_capacity(n.min(self.len()));
+        let mut queue = BinaryHeap::new();
+        if n > 0 && !self.nodes.is_empty() {
+            queue.push(QueueEntry {
+                distance2: 0.0,
+                index: 0,
+                is_item: false,
+            });
+        }
+        while let Some(entry) = queue.pop() {
+            if entry.is_item {
+                result.push(&self.items[entry.index]);
+                if result.len() == n {
+                    break;
+                }
+                continue;
+            }
+            let node = &self.nodes[entry.index];
+            for index in node.nodes() {
+                let (lower, upper) = self.nodes[index].bounds();
+                queue.push(QueueEntry {
+                    distance2: box_distance2(lower, upper, point),
+                    index,
+                    is_item: false,
+                });
+            }
+            for index in node.items() {
+                let (lower, upper) = self.item_bounds(index);
+                queue.push(QueueEntry {
+                    distance2: box_distance2(lower, upper, point),
+                    index,
+                    is_item: true,
+                });
+            }
+        }
+        result
+    }
+
+    fn lookup<F>(&self, intersects: F) -> Vec<&T::Archived>
+    where
+        F: Fn([f64; 2], [f64; 2]) -> bool,
+    {
+        let mut result = Vec::new();
+        if self.nodes.is_empty() {
+            return result;
+        }
+        let mut stack = vec![0];
+        while let Some(index) = stack.pop() {
+            let node = &self.nodes[index];
+            let (lower, upper) = node.bounds();
+            if !intersects(lower, upper) {
+                continue;
+            }
+            stack.extend(node.nodes());
+            for index in node.items() {
+                let (lower, upper) = self.item_bounds(index);
+                if intersects(lower, upper) {
+                    result.push(&self.items[index]);
+                }
+            }
+        }
+        result
+    }
+
+    /// Returns all objects whose bounding rectangle intersects a rectangle.
+    ///
+    /// The rectangle is given by its lower and upper corner.
+    pub fn lookup_in_rectangle(&self, lower: &[f64; 2], upper: &[f64; 2]) -> Vec<&T::Archived> {
+        let query = [*lower, *upper];
+        self.lookup(|lower, upper| boxes_intersect(lower, upper, &query))
+    }
+
+    /// Returns all objects whose bounding rectangle is closer than `sqrt(radius2)` to a point.
+    ///
+    /// For point objects these are exactly the points contained in the circle.
+    pub fn lookup_in_circle(&self, center: &[f64; 2], radius2: f64) -> Vec<&T::Archived> {
+        self.lookup(|lower, upper| box_distance2(lower, upper, center) < radius2)
+    }
+}
+
+/// A read-only snapshot of a triangulation that can be archived with `rkyv`.
+///
+/// Stores the vertices, their positions and the inner triangles of the
+/// triangulation. Triangles are oriented counterclockwise and know their
+/// neighbors, which allows point location on the archived form,
+/// `ArchivedStaticTriangulation`.
+#[derive(Clone, Debug, PartialEq, rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)]
+pub struct StaticTriangulation<V> {
+    vertices: Vec<V>,
+    positions: Vec<[f64; 2]>,
+    triangles: Vec<[u32; 3]>,
+    // neighbors[t][i] is the triangle opposite to the i-th vertex of triangle t.
+    neighbors: Vec<[u32; 3]>,
+}
+
+impl<V> StaticTriangulation<V>
+where
+    V: HasPosition2D + Clone,
+    V::Point: TwoDimensional,
+    <V::Point as PointN>::Scalar: ToPrimitive,
+{
+    /// Creates a snapshot of a Delaunay triangulation.
+    ///
+    /// # Panics
+    /// Panics if the triangulation contains more than `u32::MAX` vertices or triangles.
+    pub fn from_triangulation<K, L>(
+        triangulation: &DelaunayTriangulation<V, K, L>,
+    ) -> StaticTriangulation<V>
+    where
+        K: DelaunayKernel<<V::Point as PointN>::Scalar>,
+        L: DelaunayLocateStructure<V::Point>,
+    {
+        Self::from_handles(triangulation.vertices(), triangulation.triangles())
+    }
+
+    /// Creates a snapshot of a constrained Delaunay triangulation.
+    ///
+    /// Constraint edges are not stored.
+    ///
+    /// # Panics
+    /// Panics if the triangulation contains more than `u32::MAX` vertices or triangles.
+    pub fn from_cdt<K, L>(cdt: &ConstrainedDelaunayTriangulation<V, K, L>) -> StaticTriangulation<V>
+    where
+        K: DelaunayKernel<<V::Point as PointN>::Scalar>,
+        L: DelaunayLocateStructure<V::Point>,
+    {
+        Self::from_handles(cdt.vertices(), cdt.triangles())
+    }
+
+    fn from_handles<'a, E, IV, IF>(vertices: IV, faces: IF) -> StaticTriangulation<V>
+    where
+        V: 'a,
+        E: Default + Copy + 'a,
+        IV: IntoIterator<Item = VertexHandle<'a, V, E>>,
+        IF: IntoIterator<Item = FaceHandle<'a, V, E>>,
+    {
+        let mut result = StaticTriangulation {
+            vertices: Vec::new(),
+            positions: Vec::new(),
+            triangles: Vec::new(),
+            neighbors: Vec::new(),
+        };
+        // Maps fixed vertex handles to archived indices
+        let mut indices = Vec::new();
+        for vertex in vertices {
+            if indices.len() <= vertex.fix() {
+                indices.resize(vertex.fix() + 1, u32::MAX);
+            }
+            indices[vertex.fix()] = to_index(result.vertices.len());
+            result.positions.push(to_f64_point(&vertex.position()));
+            result.vertices.push((*vertex).clone());
+        }
+        for face in faces {
+            let [v0, v1, v2] = face.as_triangle().map(|v| indices[v.fix()]);
+            let [p0, p1, p2] = [v0, v1, v2].map(|v| result.position(v as usize));
+            if orient2d(&p0, &p1, &p2) < 0.0 {
+                result.triangles.push([v0, v2, v1]);
+            } else {
+                result.triangles.push([v0, v1, v2]);
+            }
+        }
+        // Two triangles are neighbors if they share an edge. Each inner edge appears once
+        // in each orientation.
+        let mut edges = Vec::with_capacity(result.triangles.len() * 3);
+        for (index, triangle) in result.triangles.iter().enumerate() {
+            for i in 0..3 {
+                edges.push(((triangle[(i + 1) % 3], triangle[(i + 2) % 3]), index));
+            }
+        }
+        edges.sort_unstable();
+        result.neighbors = result
+            .triangles
+            .iter()
+            .map(|triangle| {
+                let mut neighbors = [NO_NEIGHBOR; 3];
+                for (i, neighbor) in neighbors.iter_mut().enumerate() {
+                    let edge = (triangle[(i + 2) % 3], triangle[(i + 1) % 3]);
+                    if let Ok(position) = edges.binary_search_by_key(&edge, |(edge, _)| *edge) {
+                        *neighbor = to_index(edges[position].1);
+                    }
+                }
+                neighbors
+            })
+            .collect();
+        result
+    }
+}
+
+impl<V> StaticTriangulation<V> {
+    fn position(&self, index: usize) -> Point2<f64> {
+        let [x, y] = self.positions[index];
+        Point2::new(x, y)
+    }
+
+    /// Returns the number of vertices.
+    pub fn num_vertices(&self) -> usize {
+        self.vertices.len()
+    }
+
+    /// Returns the number of inner triangles.
+    pub fn num_triangles(&self) -> usize {
+        self.triangles.len()
+    }
+}
+
+impl<V: Archive> ArchivedStaticTriangulation<V> {
+    /// Returns the number of vertices.
+    pub fn num_vertices(&self) -> usize {
+        self.vertices.len()
+    }
+
+    /// Returns the number of inner triangles.
+    pub fn num_triangles(&self) -> usize {
+        self.triangles.len()
+    }
+
+    /// Returns an archived vertex.
+    ///
+    /// # Panics
+    /// Panics if `index` is not smaller than `num_vertices()`.
+    pub fn vertex(&self, index: usize) -> &V::Archived {
+        &self.vertices[index]
+    }
+
+    /// Returns the position of a vertex.
+    ///
+    /// # Panics
+    /// Panics if `index` is not smaller than `num_vertices()`.
+    pub fn position(&self, index: usize) -> [f64; 2] {
+        let [x, y] = &self.positions[index];
+        [x.to_native(), y.to_native()]
+    }
+
+    fn point(&self, index: usize) -> Point2<f64> {
+        let [x, y] = self.position(index);
+        Point2::new(x, y)
+    }
+
+    /// Returns the vertex indices of a triangle, in counterclockwise order.
+    ///
+    /// # Panics
+    /// Panics if `index` is not smaller than `num_triangles()`.
+    pub fn triangle(&self, index: usize) -> [usize; 3] {
+        self.triangles[index]
+            .each_ref()
+            .map(|v| v.to_native() as usize)
+    }
+
+    /// Returns the indices of the triangles adjacent to a triangle.
+    ///
+    /// The `i`-th neighbor lies opposite to the `i`-th vertex of the triangle.
+    /// It is `None` if that edge is part of the convex hull.
+    pub fn neighbors(&self, index: usize) -> [Option<usize>; 3] {
+        self.neighbors[index]
+            .each_ref()
+            .map(|n| match n.to_native() {
+                NO_NEIGHBOR => None,
+                n => Some(n as usize),
+            })
+    }
+
+    /// Returns the index of a triangle that contains a point.
+    ///
+    /// Points on an edge may be assigned to either adjacent triangle. Returns
+    /// `None` if the point lies outside of the convex hull.
+    pub fn locate(&self, point: &[f64; 2]) -> Option<usize> {
+        if self.triangles.is_empty() {
+            return None;
+        }
+        let point = Point2::new(point[0], point[1]);
+        let mut current = self.triangles.len() / 2;
+        // A visibility walk always terminates on Delaunay triangulations. The step
+        // limit and the linear fallback only guard against degenerate input.
+        'walk: for _ in 0..self.triangles.len() {
+            let triangle = self.triangle(current);
+            for i in 0..3 {
+                let from = self.point(triangle[(i + 1) % 3]);
+                let to = self.point(triangle[(i + 2) % 3]);
+                if orient2d(&from, &to, &point) < 0.0 {
+                    current = self.neighbors(current)[i]?;
+                    continue 'walk;
+                }
+            }
+            return Some(current);
+        }
+        (0..self.triangles.len()).find(|&index| {
+            let triangle = self.triangle(index);
+            (0..3).all(|i| {
+                let from = self.point(triangle[(i + 1) % 3]);
+                let to = self.point(triangle[(i + 2) % 3]);
+                orient2d(&from, &to, &point) >= 0.0
+            })
+        })
+    }
+
+    /// Performs a barycentric interpolation of a value given at each vertex.
+    ///
+    /// Returns `None` if the point lies outside of the convex hull.
+    pub fn barycentric_interpolation<F>(&self, point: &[f64; 2], f: F) -> Option<f64>
+    where
+        F: Fn(&V::Archived) -> f64,
+    {
+        let index = self.locate(point)?;
+        let triangle = self.triangle(index);
+        let [p0, p1, p2] = triangle.map(|v| self.point(v));
+        let point = Point2::new(point[0], point[1]);
+        let total = orient2d(&p0, &p1, &p2);
+        let weights = [
+            orient2d(&p1, &p2, &point),
+            orient2d(&p2, &p0, &point),
+            orient2d(&p0, &p1, &point),
+        ];
+        Some(
+            triangle
+                .iter()
+                .zip(weights.iter())
+                .map(|(&v, &weight)| f(self.vertex(v)) * weight / total)
+                .sum(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{
+        ArchivedStaticRTree, ArchivedStaticTriangulation, StaticRTree, StaticTriangulation,
+    };
+    use crate::delaunay::{FloatCDT, FloatDelaunayTriangulation, PositionInTriangulation};
+    use crate::rtree::RTree;
+    use crate::testutils::random_points_with_seed;
+    use crate::Point2;
+    use rkyv::rancor::Error;
+
+    fn random_points(size: usize, seed: &[u8; 32]) -> Vec<Point2<f64>> {
+        random_points_with_seed::<f64>(size, seed)
+            .into_iter()
+            .map(|p| Point2::new(p.x, p.y))
+            .collect()
+    }
+
+    #[test]
+    fn test_static_rtree_queries() {
+        let points = random_points(500, b"archive_rtree_01________________");
+        let tree = RTree::bulk_load(points.clone());
+        let bytes = rkyv::to_bytes::<Error>(&StaticRTree::from_rtree(&tree)).unwrap();
+        let archived = rkyv::access::<ArchivedStaticRTree<Point2<f64>>, Error>(&bytes).unwrap();
+        assert_eq!(archived.len(), 500);
+        let to_point = |p: &<Point2<f64> as rkyv::Archive>::Archived| {
+            Point2::new(p.x.to_native(), p.y.to_native())
+        };
+        for query in random_points(50, b"archive_rtree_02________________") {
+            let expected = tree.nearest_neighbor(&query).unwrap();
+            let found = archived.nearest_neighbor(&[query.x, query.y]).unwrap();
+            assert_eq!(to_point(found), *expected);
+            let expected = tree.nearest_n_neighbors(&query, 5);
+            let found = archived.nearest_n_neighbors(&[query.x, query.y], 5);
+            assert_eq!(found.into_iter().map(to_point).collect::<Vec<_>>(), {
+                expected.into_iter().cloned().collect::<Vec<_>>()
+            });
+            let mut expected = tree.lookup_in_circle(&query, &0.1);
+            let mut found: Vec<_> = archived
+                .lookup_in_circle(&[query.x, query.y], 0.1)
+                .into_iter()
+                .map(to_point)
+                .collect();
+            expected.sort_by(|a, b| a.x.total_cmp(&b.x));
+            found.sort_by(|a, b| a.x.total_cmp(&b.x));
+            assert_eq!(found, expected.into_iter().cloned().collect::<Vec<_>>());
+        }
+        let found = archived.lookup_in_rectangle(&[-0.5, -0.5], &[0.5, 0.5]);
+        let expected = points
+            .iter()
+            .filter(|p| p.x.abs() <= 0.5 && p.y.abs() <= 0.5)
+            .count();
+        assert_eq!(found.len(), expected);
+    }
+
+    #[test]
+    fn test_empty_static_rtree() {
+        let tree: RTree<Point2<f64>> = RTree::new();
+        let bytes = rkyv::to_bytes::<Error>(&StaticRTree::from_rtree(&tree)).unwrap();
+        let archived = rkyv::access::<ArchivedStaticRTree<Point2<f64>>, Error>(&bytes).unwrap();
+        assert!(archived.is_empty());
+        assert!(archived.nearest_neighbor(&[0.0, 0.0]).is_none());
+        assert!(archived.lookup_in_circle(&[0.0, 0.0], 1.0).is_empty());
+    }
+
+    #[test]
+    fn test_static_triangulation_interpolation() {
+        let mut delaunay = FloatDelaunayTriangulation::with_walk_locate();
+        for point in random_points(200, b"archive_delaun01________________") {
+            delaunay.insert(point);
+        }
+        let snapshot = StaticTriangulation::from_triangulation(&delaunay);
+        assert_eq!(snapshot.num_triangles(), delaunay.num_triangles());
+        let bytes = rkyv::to_bytes::<Error>(&snapshot).unwrap();
+        let archived =
+            rkyv::access::<ArchivedStaticTriangulation<Point2<f64>>, Error>(&bytes).unwrap();
+        assert_eq!(archived.num_vertices(), 200);
+        let height = |x: f64, y: f64| 2.0 * x - y + 1.0;
+        for query in random_points(100, b"archive_delaun02________________") {
+            let expected = delaunay.barycentric_interpolation(&query, |p| height(p.x, p.y));
+            let found = archived.barycentric_interpolation(&[query.x, query.y], |p| {
+                height(p.x.to_native(), p.y.to_native())
+            });
+            let inside = !matches!(
+                delaunay.locate(&query),
+                PositionInTriangulation::OutsideConvexHull(_)
+            );
+            assert_eq!(found.is_some(), inside);
+            if inside {
+                assert!((found.unwrap() - expected.unwrap()).abs() < 1e-9);
+            }
+        }
+        assert!(archived.locate(&[10.0, 10.0]).is_none());
+    }
+
+    #[test]
+    fn test_static_cdt() {
+        let mut cdt = FloatCDT::with_walk_locate();
+        let v0 = cdt.insert(Point2::new(0.0, 0.0));
+        let v1 = cdt.insert(Point2::new(4.0, 0.0));
+        cdt.insert(Point2::new(4.0, 4.0));
+        cdt.insert(Point2::new(0.0, 4.0));
+        cdt.insert(Point2::new(2.0, 1.0));
+        cdt.add_constraint(v0, v1);
+        let snapshot = StaticTriangulation::from_cdt(&cdt);
+        let bytes = rkyv::to_bytes::<Error>(&snapshot).unwrap();
+        let archived =
+            rkyv::access::<ArchivedStaticTriangulation<Point2<f64>>, Error>(&bytes).unwrap();
+        assert_eq!(archived.num_triangles(), 4);
+        let index = archived.locate(&[2.0, 3.5]).unwrap();
+        let neighbors = archived.neighbors(index);
+        assert_eq!(neighbors.iter().filter(|n| n.is_none()).count(), 1);
+        let value = archived.barycentric_interpolation(&[1.0, 1.0], |p| p.y.to_native());
+        assert!((value.unwrap() - 1.0).abs() < 1e-12);
+    }
+}
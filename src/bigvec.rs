@@ -8,11 +8,13 @@
 
 use crate::point_traits::{PointN, TwoDimensional};
 use crate::traits::SpadeNum;
+#[cfg(feature = "cgmath")]
 use cgmath as cg;
+use core::ops::{Add, Div, Index, IndexMut, Mul, Neg, Rem, Sub};
+#[cfg(feature = "nalgebra")]
 use nalgebra as na;
 use num::bigint::ToBigInt;
 use num::{BigInt, Integer, Num, One, Signed, ToPrimitive, Zero};
-use std::ops::{Add, Div, Index, IndexMut, Mul, Neg, Rem, Sub};
 
 /// BigVec2 is a two dimensional vector that does not _require_ it's
 /// internal scalar type to be `Copy`.
@@ -100,6 +102,7 @@ impl<N: SpadeNum> IndexMut<usize> for BigVec2<N> {
     }
 }
 
+#[cfg(feature = "cgmath")]
 impl<I> From<cg::Point2<I>> for BigVec2<BigInt>
 where
     I: cg::BaseNum + ToBigInt,
@@ -109,6 +112,7 @@ where
     }
 }
 
+#[cfg(feature = "nalgebra")]
 impl<I> From<na::Point2<I>> for BigVec2<BigInt>
 where
     I: ToBigInt + na::Scalar,
@@ -163,8 +167,8 @@ impl AdaptiveInt {
 }
 
 impl Num for AdaptiveInt {
-    type FromStrRadixErr = ::std::num::ParseIntError;
-    fn from_str_radix(s: &str, radix: u32) -> Result<Self, ::std::num::ParseIntError> {
+    type FromStrRadixErr = ::core::num::ParseIntError;
+    fn from_str_radix(s: &str, radix: u32) -> Result<Self, ::core::num::ParseIntError> {
         i64::from_str_radix(s, radix).map(AdaptiveInt::LowRes)
     }
 }
@@ -442,7 +446,7 @@ impl PartialEq for AdaptiveInt {
 impl Eq for AdaptiveInt {}
 
 impl PartialOrd for AdaptiveInt {
-    fn partial_cmp(&self, rhs: &AdaptiveInt) -> Option<::std::cmp::Ordering> {
+    fn partial_cmp(&self, rhs: &AdaptiveInt) -> Option<::core::cmp::Ordering> {
         use crate::bigvec::AdaptiveInt::*;
         match (self, rhs) {
             (&HighRes(ref l), &HighRes(ref r)) => l.partial_cmp(r),
@@ -454,7 +458,7 @@ impl PartialOrd for AdaptiveInt {
 }
 
 impl Ord for AdaptiveInt {
-    fn cmp(&self, rhs: &AdaptiveInt) -> ::std::cmp::Ordering {
+    fn cmp(&self, rhs: &AdaptiveInt) -> ::core::cmp::Ordering {
         use crate::bigvec::AdaptiveInt::*;
         match (self, rhs) {
             (&HighRes(ref l), &HighRes(ref r)) => l.cmp(r),
@@ -465,9 +469,10 @@ impl Ord for AdaptiveInt {
     }
 }
 
+#[cfg(feature = "cgmath")]
 impl<I> From<cg::Point2<I>> for BigVec2<AdaptiveInt>
 where
-    I: ::std::convert::Into<i64> + Copy,
+    I: ::core::convert::Into<i64> + Copy,
 {
     fn from(v: cg::Point2<I>) -> Self {
         BigVec2::new(
@@ -477,9 +482,10 @@ where
     }
 }
 
+#[cfg(feature = "nalgebra")]
 impl<I> From<na::Point2<I>> for BigVec2<AdaptiveInt>
 where
-    I: ::std::convert::Into<i64> + na::Scalar + Copy,
+    I: ::core::convert::Into<i64> + na::Scalar + Copy,
 {
     fn from(v: na::Point2<I>) -> Self {
         BigVec2::new(
@@ -7,8 +7,9 @@
 // except according to those terms.
 
 use crate::misc::max_inline;
-use crate::point_traits::{PointN, PointNExtensions};
-use crate::traits::SpatialObject;
+use crate::point_traits::{PointN, PointNExtensions, TwoDimensional};
+use crate::traits::{SpadeFloat, SpatialObject};
+use crate::transform::AffineTransform2;
 use num::{one, zero, Signed};
 
 /// An axis aligned minimal bounding rectangle.
@@ -153,6 +154,45 @@ where
             && self.upper.all_comp_wise(&other.lower(), |l, r| l >= r)
     }
 
+    /// Returns the intersection of this and another bounding rectangle.
+    ///
+    /// Returns `None` if the rectangles do not intersect. Rectangles that just
+    /// touch each other intersect in a rectangle with zero area.
+    pub fn intersection(&self, other: &BoundingRect<V>) -> Option<BoundingRect<V>> {
+        if self.intersects(other) {
+            Some(self.intersect(other))
+        } else {
+            None
+        }
+    }
+
+    /// Returns the smallest bounding rectangle that contains this and another rectangle.
+    pub fn union(&self, other: &BoundingRect<V>) -> BoundingRect<V> {
+        let mut result = self.clone();
+        result.add_rect(other);
+        result
+    }
+
+    /// Returns this rectangle, enlarged by a margin in every direction.
+    ///
+    /// A negative margin shrinks the rectangle. A dimension that would become
+    /// negative collapses to the rectangle's center.
+    pub fn expanded_by(&self, margin: V::Scalar) -> BoundingRect<V> {
+        let margin = V::from_value(margin);
+        let center = self.center();
+        BoundingRect {
+            lower: self.lower.sub(&margin).min_point(&center),
+            upper: self.upper.add(&margin).max_point(&center),
+        }
+    }
+
+    /// Returns the area in which this and another bounding rectangle overlap.
+    pub fn overlap_area(&self, other: &BoundingRect<V>) -> V::Scalar {
+        self.intersection(other)
+            .map(|rect| rect.area())
+            .unwrap_or_else(zero)
+    }
+
     #[doc(hidden)]
     pub fn min_point(&self, point: &V) -> V {
         self.upper.min_point(&self.lower.max_point(&point))
@@ -201,6 +241,30 @@ where
     }
 }
 
+impl<V> BoundingRect<V>
+where
+    V: TwoDimensional,
+    V::Scalar: SpadeFloat,
+{
+    /// Applies an affine transformation to this rectangle.
+    ///
+    /// Returns the bounding rectangle of the transformed rectangle.
+    pub fn transform(&self, transform: &AffineTransform2<V::Scalar>) -> BoundingRect<V> {
+        let (lower, upper) = (&self.lower, &self.upper);
+        let mut corner = lower.clone();
+        *corner.nth_mut(0) = *upper.nth(0);
+        let mut result = BoundingRect::from_corners(
+            &transform.transform_point(lower),
+            &transform.transform_point(upper),
+        );
+        result.add_point(transform.transform_point(&corner));
+        *corner.nth_mut(0) = *lower.nth(0);
+        *corner.nth_mut(1) = *upper.nth(1);
+        result.add_point(transform.transform_point(&corner));
+        result
+    }
+}
+
 impl<V> SpatialObject for BoundingRect<V>
 where
     V: PointN,
@@ -224,6 +288,7 @@ where
 mod test {
     use super::BoundingRect;
     use crate::traits::SpatialObject;
+    use crate::transform::AffineTransform2;
 
     #[test]
     fn test_add_points() {
@@ -250,4 +315,61 @@ mod test {
         assert_eq!(rect.distance2(&[2.0, 2.0]), 2.0);
         assert_eq!(rect.distance2(&[2.0, 0.5]), 1.0);
     }
+
+    #[test]
+    fn test_set_operations() {
+        let r1 = BoundingRect::from_corners(&[0.0, 0.0f64], &[2.0, 2.0]);
+        let r2 = BoundingRect::from_corners(&[1.0, -1.0], &[4.0, 1.5]);
+        let r3 = BoundingRect::from_corners(&[3.0, 3.0], &[4.0, 4.0]);
+        assert_eq!(
+            r1.intersection(&r2),
+            Some(BoundingRect::from_corners(&[1.0, 0.0], &[2.0, 1.5]))
+        );
+        assert_eq!(r1.intersection(&r3), None);
+        assert_eq!(r1.overlap_area(&r2), 1.5);
+        assert_eq!(r2.overlap_area(&r1), 1.5);
+        assert_eq!(r1.overlap_area(&r3), 0.0);
+        assert_eq!(
+            r1.union(&r3),
+            BoundingRect::from_corners(&[0.0, 0.0], &[4.0, 4.0])
+        );
+        assert_eq!(r1.union(&r3).area(), 16.0);
+
+        // Touching rectangles
+        let r4 = BoundingRect::from_corners(&[2.0, 0.0], &[3.0, 1.0]);
+        assert!(r1.intersects(&r4));
+        assert_eq!(r1.overlap_area(&r4), 0.0);
+
+        assert_eq!(
+            r1.expanded_by(0.5),
+            BoundingRect::from_corners(&[-0.5, -0.5], &[2.5, 2.5])
+        );
+        assert_eq!(
+            r1.expanded_by(-0.5),
+            BoundingRect::from_corners(&[0.5, 0.5], &[1.5, 1.5])
+        );
+        assert_eq!(
+            r2.expanded_by(-1.0),
+            BoundingRect::from_corners(&[2.0, 0.0], &[3.0, 0.5])
+        );
+        assert_eq!(
+            r2.expanded_by(-1.5),
+            BoundingRect::from_corners(&[2.5, 0.25], &[2.5, 0.25])
+        );
+    }
+
+    #[test]
+    fn test_transform() {
+        let rect = BoundingRect::from_corners(&[0.0, 0.0f64], &[2.0, 1.0]);
+        let rotated = rect.transform(&AffineTransform2::rotation(::std::f64::consts::FRAC_PI_4));
+        let sqrt_half = 0.5f64.sqrt();
+        assert_relative_eq!(rotated.lower()[0], -sqrt_half);
+        assert_relative_eq!(rotated.lower()[1], 0.0);
+        assert_relative_eq!(rotated.upper()[0], 2.0 * sqrt_half);
+        assert_relative_eq!(rotated.upper()[1], 3.0 * sqrt_half);
+        assert_eq!(
+            rect.transform(&AffineTransform2::translation(1.0, 1.0)),
+            BoundingRect::from_corners(&[1.0, 1.0], &[3.0, 2.0])
+        );
+    }
 }
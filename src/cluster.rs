@@ -0,0 +1,500 @@
+// Copyright 2017 The Spade Developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Density based clustering of point sets.
+//!
+//! `dbscan` groups objects that are densely packed together and marks
+//! objects in sparse regions as noise. `optics` orders the objects such that
+//! clusters of any density can be read off afterwards, which helps if the
+//! density varies across the data set. The neighborhood queries are answered
+//! by an `RTree`, which is built internally from the objects' 2D positions.
+//!
+//! # Example
+//!
+//! ```
+//! use spade::cluster::dbscan;
+//! use spade::Point2;
+//!
+//! let points = vec![
+//!     Point2::new(0.0, 0.0),
+//!     Point2::new(0.5, 0.0),
+//!     Point2::new(0.0, 0.5),
+//!     Point2::new(10.0, 10.0),
+//!     Point2::new(10.5, 10.0),
+//!     Point2::new(10.0, 10.5),
+//!     Point2::new(5.0, 5.0),
+//! ];
+//! let clustering = dbscan(&points, &1.0, 3);
+//! assert_eq!(clustering.cluster_count(), 2);
+//! assert_eq!(clustering.label(0), Some(0));
+//! assert_eq!(clustering.label(4), Some(1));
+//! // The point in the middle is too far away from both clusters
+//! assert_eq!(clustering.noise(), vec![6]);
+//! ```
+
+use crate::point_traits::{PointN, PointNExtensions, TwoDimensional};
+use crate::rtree::{QueryScratch, RTree};
+use crate::traits::{HasPosition, SpadeNum};
+use alloc::collections::BinaryHeap;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use num::zero;
+
+/// The result of a clustering.
+///
+/// Contains a label for each clustered object, in the order in which the
+/// objects were given. Clusters are numbered from `0` to
+/// `cluster_count() - 1` in the order in which they were found.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Clustering {
+    labels: Vec<Option<usize>>,
+    cluster_count: usize,
+}
+
+impl Clustering {
+    /// Returns the cluster of each object, `None` marks noise.
+    pub fn labels(&self) -> &[Option<usize>] {
+        &self.labels
+    }
+
+    /// Returns the cluster of an object or `None` if it is noise.
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn label(&self, index: usize) -> Option<usize> {
+        self.labels[index]
+    }
+
+    /// Returns the number of clusters.
+    pub fn cluster_count(&self) -> usize {
+        self.cluster_count
+    }
+
+    /// Returns the indices of the objects of each cluster.
+    pub fn clusters(&self) -> Vec<Vec<usize>> {
+        let mut clusters = vec![Vec::new(); self.cluster_count];
+        for (index, label) in self.labels.iter().enumerate() {
+            if let Some(cluster) = label {
+                clusters[*cluster].push(index);
+            }
+        }
+        clusters
+    }
+
+    /// Returns the indices of all objects that belong to no cluster.
+    pub fn noise(&self) -> Vec<usize> {
+        self.labels
+            .iter()
+            .enumerate()
+            .filter(|(_, label)| label.is_none())
+            .map(|(index, _)| index)
+            .collect()
+    }
+}
+
+/// Clusters objects with DBSCAN.
+///
+/// An object is a core object if at least `min_points` objects, including
+/// itself, lie within its neighborhood. The neighborhood is a circle around
+/// the object's position, `radius2` is its squared radius. Objects on the
+/// circle's boundary are not part of the neighborhood. A cluster consists
+/// of core objects that are reachable from each other through their
+/// neighborhoods, and of all objects within their neighborhoods. All other
+/// objects are noise.
+///
+/// Objects on the border of two clusters are assigned to the cluster that was
+/// found first. Building the tree takes `O(n log n)` time, the clustering
+/// itself performs a neighborhood query for each object.
+///
+/// Panics if there are more than `u32::MAX` objects.
+pub fn dbscan<T>(
+    objects: &[T],
+    radius2: &<T::Point as PointN>::Scalar,
+    min_points: usize,
+) -> Clustering
+where
+    T: HasPosition,
+    T::Point: TwoDimensional,
+{
+    let tree = RTree::bulk_load_indices(objects);
+    let mut scratch = QueryScratch::new();
+    let mut neighbors = Vec::new();
+    let mut find_neighbors = |index: usize, neighbors: &mut Vec<usize>| {
+        neighbors.clear();
+        tree.lookup_in_circle_with_scratch(
+            &objects[index].position(),
+            radius2,
+            &mut scratch,
+            |object| neighbors.push(object.index()),
+        );
+    };
+
+    let mut labels = vec![None; objects.len()];
+    let mut visited = vec![false; objects.len()];
+    let mut cluster_count = 0;
+    let mut queue = Vec::new();
+    for start in 0..objects.len() {
+        if visited[start] {
+            continue;
+        }
+        visited[start] = true;
+        find_neighbors(start, &mut neighbors);
+        if neighbors.len() < min_points {
+            // Noise for now, may become a border object of a later cluster
+            continue;
+        }
+        let cluster = cluster_count;
+        cluster_count += 1;
+        labels[start] = Some(cluster);
+        queue.append(&mut neighbors);
+        while let Some(index) = queue.pop() {
+            if labels[index].is_none() {
+                labels[index] = Some(cluster);
+            }
+            if visited[index] {
+                continue;
+            }
+            visited[index] = true;
+            find_neighbors(index, &mut neighbors);
+            if neighbors.len() >= min_points {
+                queue.append(&mut neighbors);
+            }
+        }
+    }
+    Clustering {
+        labels,
+        cluster_count,
+    }
+}
+
+/// The reachability ordering computed by `optics`.
+///
+/// All distances are squared distances.
+#[derive(Clone, Debug)]
+pub struct Optics<S> {
+    ordering: Vec<usize>,
+    reachability2: Vec<Option<S>>,
+    core_distance2: Vec<Option<S>>,
+}
+
+impl<S: SpadeNum> Optics<S> {
+    /// Returns the indices of all objects in the order in which they were visited.
+    pub fn ordering(&self) -> &[usize] {
+        &self.ordering
+    }
+
+    /// Returns an object's squared reachability distance.
+    ///
+    /// This is the smallest squared distance at which the object becomes
+    /// density reachable from one of the objects before it in the ordering.
+    /// Returns `None` if there is no such distance smaller than the maximum
+    /// radius, e.g. for the first object of each cluster.
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn reachability2(&self, index: usize) -> Option<&S> {
+        self.reachability2[index].as_ref()
+    }
+
+    /// Returns an object's squared core distance.
+    ///
+    /// This is the smallest squared radius for which the object would be a
+    /// core object. Returns `None` if it is no core object for the maximum
+    /// radius.
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn core_distance2(&self, index: usize) -> Option<&S> {
+        self.core_distance2[index].as_ref()
+    }
+
+    /// Returns the squared reachability distances in the visiting order.
+    ///
+    /// Clusters show up as valleys in this reachability plot, a dense
+    /// cluster forms a deep valley.
+    pub fn reachability_plot(&self) -> Vec<Option<&S>> {
+        self.ordering
+            .iter()
+            .map(|index| self.reachability2(*index))
+            .collect()
+    }
+
+    /// Extracts the clustering that `dbscan` would compute for a smaller radius.
+    ///
+    /// `radius2` must not be larger than the maximum radius that was passed
+    /// to `optics`. The result equals the result of `dbscan` for all core
+    /// objects, up to the numbering of the clusters. Objects on the border of
+    /// a cluster may be marked as noise if they were visited before the core
+    /// objects they are reachable from.
+    pub fn extract_dbscan(&self, radius2: &S) -> Clustering {
+        let mut labels = vec![None; self.ordering.len()];
+        let mut cluster_count: usize = 0;
+        for index in &self.ordering {
+            match &self.reachability2[*index] {
+                Some(reachability2) if reachability2 < radius2 => {
+                    labels[*index] = cluster_count.checked_sub(1);
+                }
+                _ => {
+                    if self.core_distance2[*index]
+                        .as_ref()
+                        .is_some_and(|core_distance2| core_distance2 < radius2)
+                    {
+                        labels[*index] = Some(cluster_count);
+                        cluster_count += 1;
+                    }
+                }
+            }
+        }
+        Clustering {
+            labels,
+            cluster_count,
+        }
+    }
+}
+
+/// An object waiting to be visited, ordered such that `BinaryHeap` yields
+/// the smallest reachability first.
+struct Seed<S> {
+    reachability2: S,
+    index: usize,
+}
+
+impl<S: SpadeNum> PartialEq for Seed<S> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<S: SpadeNum> Eq for Seed<S> {}
+
+impl<S: SpadeNum> PartialOrd for Seed<S> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<S: SpadeNum> Ord for Seed<S> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .reachability2
+            .partial_cmp(&self.reachability2)
+            .unwrap_or(Ordering::Equal)
+            .then(other.index.cmp(&self.index))
+    }
+}
+
+/// Computes the OPTICS reachability ordering of a set of objects.
+///
+/// OPTICS generalizes DBSCAN: instead of a clustering for a single radius it
+/// computes an ordering from which the clusterings for all radii up to
+/// `max_radius2` can be extracted, see `Optics::extract_dbscan`. The
+/// reachability plot shows clusters of different densities as valleys of
+/// different depths. `max_radius2` bounds the squared radius of the
+/// neighborhood queries, a smaller value speeds up the computation.
+/// `min_points` has the same meaning as for `dbscan`.
+///
+/// Panics if there are more than `u32::MAX` objects.
+///
+/// # Example
+///
+/// ```
+/// use spade::cluster::optics;
+/// use spade::Point2;
+///
+/// // A dense and a sparse cluster
+/// let mut points = Vec::new();
+/// for i in 0..5 {
+///     points.push(Point2::new(0.1 * f64::from(i), 0.0));
+///     points.push(Point2::new(10.0 + 2.0 * f64::from(i), 0.0));
+/// }
+/// let optics = optics(&points, &100.0, 3);
+/// let dense = optics.extract_dbscan(&0.05);
+/// assert_eq!(dense.cluster_count(), 1);
+/// assert_eq!(dense.noise().len(), 5);
+/// let both = optics.extract_dbscan(&5.0);
+/// assert_eq!(both.cluster_count(), 2);
+/// ```
+pub fn optics<T>(
+    objects: &[T],
+    max_radius2: &<T::Point as PointN>::Scalar,
+    min_points: usize,
+) -> Optics<<T::Point as PointN>::Scalar>
+where
+    T: HasPosition,
+    T::Point: TwoDimensional,
+{
+    let tree = RTree::bulk_load_indices(objects);
+    let mut scratch = QueryScratch::new();
+    let mut neighbors = Vec::new();
+    let mut distances = Vec::new();
+    // Finds all neighbors and their squared distances and returns the
+    // squared core distance
+    let mut find_neighbors = |index: usize, neighbors: &mut Vec<(usize, _)>| {
+        neighbors.clear();
+        let position = objects[index].position();
+        tree.lookup_in_circle_with_scratch(&position, max_radius2, &mut scratch, |object| {
+            let distance2 = objects[object.index()].position().sub(&position).length2();
+            neighbors.push((object.index(), distance2));
+        });
+        if min_points == 0 || neighbors.len() < min_points {
+            return None;
+        }
+        distances.clear();
+        distances.extend(neighbors.iter().map(|(_, distance2)| distance2.clone()));
+        ::pdqselect::select_by(&mut distances, min_points - 1, |l, r| {
+            l.partial_cmp(r).unwrap_or(Ordering::Equal)
+        });
+        Some(distances[min_points - 1].clone())
+    };
+
+    let mut ordering = Vec::with_capacity(objects.len());
+    let mut reachability2: Vec<Option<<T::Point as PointN>::Scalar>> = vec![None; objects.len()];
+    let mut core_distance2 = vec![None; objects.len()];
+    let mut processed = vec![false; objects.len()];
+    let mut seeds = BinaryHeap::new();
+    for start in 0..objects.len() {
+        if processed[start] {
+            continue;
+        }
+        seeds.push(Seed {
+            reachability2: zero(),
+            index: start,
+        });
+        while let Some(Seed { index, .. }) = seeds.pop() {
+            if processed[index] {
+                // An outdated entry, the object was reached on a shorter path
+                continue;
+            }
+            processed[index] = true;
+            ordering.push(index);
+            core_distance2[index] = find_neighbors(index, &mut neighbors);
+            let core = match &core_distance2[index] {
+                Some(core) => core.clone(),
+                None => continue,
+            };
+            for (neighbor, distance2) in neighbors.drain(..) {
+                if processed[neighbor] {
+                    continue;
+                }
+                let candidate = if distance2 > core {
+                    distance2
+                } else {
+                    core.clone()
+                };
+                if reachability2[neighbor]
+                    .as_ref()
+                    .is_none_or(|current| candidate < *current)
+                {
+                    reachability2[neighbor] = Some(candidate.clone());
+                    seeds.push(Seed {
+                        reachability2: candidate,
+                        index: neighbor,
+                    });
+                }
+            }
+        }
+    }
+    Optics {
+        ordering,
+        reachability2,
+        core_distance2,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{dbscan, optics};
+    use crate::testutils::random_points_with_seed;
+    use cgmath::{MetricSpace, Point2};
+
+    const SEED: &[u8; 32] = b"\x1c\x7d\x0a\x33\x91\x5e\x02\xf8\x6b\x44\xc0\x19\xa2\x7e\x3d\x58\
+                              \x0f\xe1\x26\x97\x4b\x6c\xd5\x80\x11\x3a\xbe\x62\x0c\xf4\x95\x27";
+
+    fn brute_force_neighbors(points: &[Point2<f64>], index: usize, radius2: f64) -> Vec<usize> {
+        (0..points.len())
+            .filter(|other| points[index].distance2(points[*other]) < radius2)
+            .collect()
+    }
+
+    #[test]
+    fn test_dbscan_invariants() {
+        let points = random_points_with_seed::<f64>(1000, SEED);
+        let radius2 = 0.002;
+        let min_points = 4;
+        let clustering = dbscan(&points, &radius2, min_points);
+        assert!(clustering.cluster_count() > 1);
+        let is_core: Vec<_> = (0..points.len())
+            .map(|i| brute_force_neighbors(&points, i, radius2).len() >= min_points)
+            .collect();
+        for (index, point_is_core) in is_core.iter().enumerate() {
+            let neighbors = brute_force_neighbors(&points, index, radius2);
+            if *point_is_core {
+                // Core objects share their cluster with all core neighbors
+                let label = clustering.label(index);
+                assert!(label.is_some());
+                for neighbor in neighbors {
+                    if is_core[neighbor] {
+                        assert_eq!(clustering.label(neighbor), label);
+                    }
+                }
+            } else {
+                // Border objects belong to the cluster of a core neighbor
+                match clustering.label(index) {
+                    Some(label) => assert!(neighbors
+                        .iter()
+                        .any(|n| is_core[*n] && clustering.label(*n) == Some(label))),
+                    None => assert!(neighbors.iter().all(|n| !is_core[*n])),
+                }
+            }
+        }
+        let clusters = clustering.clusters();
+        assert_eq!(clusters.len(), clustering.cluster_count());
+        assert_eq!(
+            clusters.iter().map(Vec::len).sum::<usize>() + clustering.noise().len(),
+            points.len()
+        );
+    }
+
+    #[test]
+    fn test_dbscan_edge_cases() {
+        let empty: Vec<Point2<f64>> = Vec::new();
+        assert_eq!(dbscan(&empty, &1.0, 2).cluster_count(), 0);
+        let points = vec![Point2::new(0.0, 0.0); 3];
+        let clustering = dbscan(&points, &0.1, 3);
+        assert_eq!(clustering.labels(), &[Some(0), Some(0), Some(0)]);
+        let clustering = dbscan(&points, &0.1, 4);
+        assert_eq!(clustering.noise(), vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn test_optics_matches_dbscan() {
+        let points = random_points_with_seed::<f64>(800, SEED);
+        let min_points = 5;
+        let optics = optics(&points, &0.01, min_points);
+        let mut ordering = optics.ordering().to_vec();
+        ordering.sort_unstable();
+        assert_eq!(ordering, (0..points.len()).collect::<Vec<_>>());
+        assert_eq!(optics.reachability_plot().len(), points.len());
+        for radius2 in [0.001, 0.003, 0.01] {
+            let expected = dbscan(&points, &radius2, min_points);
+            let extracted = optics.extract_dbscan(&radius2);
+            assert_eq!(extracted.cluster_count(), expected.cluster_count());
+            let core: Vec<_> = (0..points.len())
+                .filter(|i| brute_force_neighbors(&points, *i, radius2).len() >= min_points)
+                .collect();
+            // Both clusterings group the core objects in the same way
+            for a in &core {
+                assert!(extracted.label(*a).is_some());
+                for b in &core {
+                    assert_eq!(
+                        extracted.label(*a) == extracted.label(*b),
+                        expected.label(*a) == expected.label(*b)
+                    );
+                }
+            }
+        }
+    }
+}
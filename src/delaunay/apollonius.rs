@@ -0,0 +1,263 @@
+// Copyright 2017 The Spade Developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use crate::boundingrect::BoundingRect;
+use crate::point_traits::{PointN, PointNExtensions, TwoDimensional};
+use crate::traits::SpadeFloat;
+use alloc::vec::Vec;
+use num::{zero, Float, NumCast};
+
+/// An additively weighted Voronoi diagram, also known as Apollonius diagram.
+///
+/// Each site is a point with an additive weight, which can be thought of as a
+/// circle with the weight as radius. A point `x` belongs to the cell of the site
+/// `(p, w)` that minimizes `|x - p| - w`. Cell boundaries are hyperbolic arcs.
+/// A site may have an empty cell if its circle is contained in another site's
+/// circle, such sites are called _hidden_.
+///
+/// Cells are star shaped in respect to their site. They are polygonized by
+/// shooting a fixed number of rays from each site and calculating where each
+/// ray leaves the cell. Unbounded cells are cut off at a given bounding rectangle.
+///
+/// # Example
+///
+/// ```
+/// # extern crate nalgebra;
+/// # extern crate spade;
+/// use nalgebra::Point2;
+/// use spade::BoundingRect;
+/// use spade::delaunay::ApolloniusDiagram;
+///
+/// # fn main() {
+/// let sites = vec![(Point2::new(0.0, 0.0), 2.0), (Point2::new(4.0, 0.0), 0.0)];
+/// let bounds = BoundingRect::from_corners(&Point2::new(-10.0, -10.0), &Point2::new(10.0, 10.0));
+/// let diagram = ApolloniusDiagram::new(sites, &bounds, 64);
+/// // The stronger site covers more than half of the distance
+/// assert_eq!(diagram.nearest_site(&Point2::new(2.5, 0.0)), Some(0));
+/// assert_eq!(diagram.cell(0).unwrap().len(), 64);
+/// # }
+/// ```
+pub struct ApolloniusDiagram<P: PointN> {
+    sites: Vec<(P, P::Scalar)>,
+    cells: Vec<Option<Vec<P>>>,
+}
+
+impl<P> ApolloniusDiagram<P>
+where
+    P: TwoDimensional,
+    P::Scalar: SpadeFloat,
+{
+    /// Creates the diagram of a set of sites, given as positions and weights.
+    ///
+    /// Each cell is polygonized with `num_directions` vertices. Sites should lie
+    /// within `bounds`.
+    ///
+    /// # Panics
+    /// Panics if `num_directions` is smaller than three.
+    pub fn new(
+        sites: Vec<(P, P::Scalar)>,
+        bounds: &BoundingRect<P>,
+        num_directions: usize,
+    ) -> Self {
+        assert!(
+            num_directions >= 3,
+            "At least three directions are required"
+        );
+        let two_pi: P::Scalar = NumCast::from(2.0 * ::core::f64::consts::PI).unwrap();
+        let directions: Vec<P> = (0..num_directions)
+            .map(|i| {
+                let index: P::Scalar = NumCast::from(i).unwrap();
+                let count: P::Scalar = NumCast::from(num_directions).unwrap();
+                let (sin, cos) = (two_pi * index / count).sin_cos();
+                let mut direction = P::new();
+                *direction.nth_mut(0) = cos;
+                *direction.nth_mut(1) = sin;
+                direction
+            })
+            .collect();
+        let cells = (0..sites.len())
+            .map(|index| calculate_cell(&sites, index, bounds, &directions))
+            .collect();
+        ApolloniusDiagram { sites, cells }
+    }
+
+    /// Returns all sites with their weights.
+    pub fn sites(&self) -> &[(P, P::Scalar)] {
+        &self.sites
+    }
+
+    /// Returns the polygonized cell of a site in counterclockwise order.
+    ///
+    /// Returns `None` if the site is hidden.
+    pub fn cell(&self, site: usize) -> Option<&[P]> {
+        self.cells[site].as_deref()
+    }
+
+    /// Returns `true` if a site's cell is empty.
+    pub fn is_hidden(&self, site: usize) -> bool {
+        self.cells[site].is_none()
+    }
+
+    /// Returns the index of the site whose cell contains a point.
+    ///
+    /// Returns `None` if the diagram has no sites.
+    pub fn nearest_site(&self, point: &P) -> Option<usize> {
+        let mut result = None;
+        let mut min_distance = zero();
+        for (index, site) in self.sites.iter().enumerate() {
+            let distance = weighted_distance(site, point);
+            if result.is_none() || distance < min_distance {
+                result = Some(index);
+                min_distance = distance;
+            }
+        }
+        result
+    }
+}
+
+fn weighted_distance<P>((position, weight): &(P, P::Scalar), point: &P) -> P::Scalar
+where
+    P: TwoDimensional,
+    P::Scalar: SpadeFloat,
+{
+    position.sub(point).length2().sqrt() - *weight
+}
+
+fn calculate_cell<P>(
+    sites: &[(P, P::Scalar)],
+    index: usize,
+    bounds: &BoundingRect<P>,
+    directions: &[P],
+) -> Option<Vec<P>>
+where
+    P: TwoDimensional,
+    P::Scalar: SpadeFloat,
+{
+    let (ref position, weight) = sites[index];
+    let two: P::Scalar = NumCast::from(2.0).unwrap();
+    let mut cell = Vec::with_capacity(directions.len());
+    for direction in directions {
+        let mut max_t = ray_exit(position, direction, bounds);
+        for (other_index, (other, other_weight)) in sites.iter().enumerate() {
+            if other_index == index {
+                continue;
+            }
+            let d = position.sub(other);
+            let c = *other_weight - weight;
+            if c >= zero() && c * c >= d.length2() {
+                // The site's circle lies within the other site's circle. Of two
+                // identical sites, only the first one is kept.
+                if c > zero() || other_index < index {
+                    return None;
+                }
+                continue;
+            }
+            // Solve |d + t * direction| = t + c for t
+            let denominator = c - d.dot(direction);
+            if denominator <= zero() {
+                continue;
+            }
+            let t = (d.length2() - c * c) / (two * denominator);
+            if t + c >= zero() && t < max_t {
+                max_t = t;
+            }
+        }
+        cell.push(position.add(&direction.mul(max_t)));
+    }
+    Some(cell)
+}
+
+/// Returns the distance along a ray until it leaves a bounding rectangle.
+fn ray_exit<P>(origin: &P, direction: &P, bounds: &BoundingRect<P>) -> P::Scalar
+where
+    P: TwoDimensional,
+    P::Scalar: SpadeFloat,
+{
+    let (lower, upper) = (bounds.lower(), bounds.upper());
+    let mut result = P::Scalar::infinity();
+    for dim in 0..2 {
+        let delta = *direction.nth(dim);
+        let limit = if delta > zero() {
+            *upper.nth(dim)
+        } else if delta < zero() {
+            *lower.nth(dim)
+        } else {
+            continue;
+        };
+        let t = (limit - *origin.nth(dim)) / delta;
+        if t < result {
+            result = t;
+        }
+    }
+    if result > zero() {
+        result
+    } else {
+        zero()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::ApolloniusDiagram;
+    use crate::boundingrect::BoundingRect;
+    use cgmath::Point2;
+
+    fn bounds() -> BoundingRect<Point2<f64>> {
+        BoundingRect::from_corners(&Point2::new(-10.0, -10.0), &Point2::new(10.0, 10.0))
+    }
+
+    #[test]
+    fn test_equal_weights() {
+        let sites = vec![(Point2::new(-1.0, 0.0), 1.0), (Point2::new(1.0, 0.0), 1.0)];
+        let diagram = ApolloniusDiagram::new(sites, &bounds(), 32);
+        let left = diagram.cell(0).unwrap();
+        assert_eq!(left.len(), 32);
+        for p in left {
+            assert!(p.x <= 1e-10);
+            assert!(p.x >= -10.0 && p.y.abs() <= 10.0);
+        }
+        // The ray pointing towards the other site ends at the bisector
+        assert_relative_eq!(left[0].x, 0.0, epsilon = 1e-10);
+        assert_relative_eq!(left[0].y, 0.0, epsilon = 1e-10);
+        assert!(!diagram.is_hidden(0));
+    }
+
+    #[test]
+    fn test_weighted_boundary() {
+        let sites = vec![
+            (Point2::new(0.0, 0.0), 2.0),
+            (Point2::new(4.0, 1.0), 0.5),
+            (Point2::new(-3.0, 4.0), 1.0),
+        ];
+        let diagram = ApolloniusDiagram::new(sites.clone(), &bounds(), 48);
+        for site in 0..3 {
+            for p in diagram.cell(site).unwrap() {
+                let own = super::weighted_distance(&sites[site], p);
+                for other in &sites {
+                    assert!(own <= super::weighted_distance(other, p) + 1e-10);
+                }
+            }
+        }
+        assert_eq!(diagram.nearest_site(&Point2::new(2.0, 0.5)), Some(0));
+        assert_eq!(diagram.nearest_site(&Point2::new(3.5, 1.0)), Some(1));
+    }
+
+    #[test]
+    fn test_hidden_site() {
+        let sites = vec![(Point2::new(0.0, 0.0), 3.0), (Point2::new(1.0, 0.0), 1.0)];
+        let diagram = ApolloniusDiagram::new(sites, &bounds(), 16);
+        assert!(diagram.is_hidden(1));
+        assert!(diagram.cell(1).is_none());
+        // The remaining cell covers the whole bounding rectangle
+        for p in diagram.cell(0).unwrap() {
+            assert_relative_eq!(p.x.abs().max(p.y.abs()), 10.0, epsilon = 1e-10);
+        }
+        let empty = ApolloniusDiagram::<Point2<f64>>::new(Vec::new(), &bounds(), 16);
+        assert_eq!(empty.nearest_site(&Point2::new(0.0, 0.0)), None);
+    }
+}
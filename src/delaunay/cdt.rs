@@ -7,14 +7,16 @@
 // except according to those terms.
 
 use self::dcel::*;
-use self::delaunay_basic::{BasicDelaunaySubdivision, HasSubdivision};
+use self::delaunay_basic::{BasicDelaunaySubdivision, HasSubdivision, TransactionStart};
 use self::line_intersection_iterator::*;
 use crate::delaunay::*;
 use crate::kernels::{DelaunayKernel, FloatKernel};
 use crate::point_traits::{PointN, TwoDimensional};
 use crate::primitives::SimpleEdge;
-use crate::traits::{HasPosition, HasPosition2D};
-use std::marker::PhantomData;
+use crate::traits::{HasPosition, HasPosition2D, SpadeFloat};
+use crate::validation::{validate_point, NonFiniteCoordinateError};
+use alloc::{vec, vec::Vec};
+use core::marker::PhantomData;
 
 /// Type shorthand for a constrained Delaunay triangulation using
 /// the precise `FloatKernel`.
@@ -96,6 +98,8 @@ where
     all_points_on_line: bool,
     num_constraints: usize,
     __kernel: PhantomData<fn() -> K>,
+    #[cfg_attr(feature = "serde_serialize", serde(skip))]
+    transaction: Option<(TransactionStart, usize)>,
 }
 
 #[derive(Debug)]
@@ -127,6 +131,15 @@ where
     pub fn with_walk_locate() -> ConstrainedDelaunayTriangulation<V, K, DelaunayWalkLocate> {
         ConstrainedDelaunayTriangulation::new()
     }
+
+    /// Shorthand constructor for a triangulation that uses the
+    /// `DelaunayHierarchyLocate` strategy for insertion and point location
+    /// queries. This yields O(log(n)) locate time on average, independent
+    /// of the order of queries.
+    pub fn with_hierarchy_locate(
+    ) -> ConstrainedDelaunayTriangulation<V, K, DelaunayHierarchyLocate<V::Point, K>> {
+        ConstrainedDelaunayTriangulation::new()
+    }
 }
 
 impl<V, K, L> BasicDelaunaySubdivision<V> for ConstrainedDelaunayTriangulation<V, K, L>
@@ -214,9 +227,80 @@ where
             locate_structure: Default::default(),
             __kernel: Default::default(),
             num_constraints: 0,
+            transaction: None,
         }
     }
 
+    /// Removes all vertices from the triangulation.
+    ///
+    /// Already allocated memory is kept to speed up subsequent insertions.
+    ///
+    /// # Handle invalidation
+    /// This method will invalidate all vertex, edge and face handles.
+    pub fn clear(&mut self) {
+        self.s.clear();
+        self.locate_structure.clear();
+        self.all_points_on_line = true;
+        self.num_constraints = 0;
+    }
+
+    /// Renumbers all vertices, edges and faces along a Hilbert curve.
+    ///
+    /// The curve adapts to the vertex distribution, the resulting order does
+    /// not match the indices of `hilbert::HilbertCurve`.
+    /// Incrementally built triangulations scatter neighboring elements across
+    /// their storage. Compacting places them close to each other, which speeds
+    /// up subsequent queries and traversals. Returns the new handle of every
+    /// vertex, indexed by its old handle.
+    ///
+    /// Panics if a transaction is active.
+    ///
+    /// # Handle invalidation
+    /// This method will invalidate all vertex, edge and face handles.
+    pub fn compact(&mut self) -> Vec<FixedVertexHandle> {
+        BasicDelaunaySubdivision::compact(self)
+    }
+
+    /// Reserves memory for at least `additional` more vertices.
+    ///
+    /// Also reserves space for the edges and faces created by inserting these
+    /// vertices.
+    pub fn reserve(&mut self, additional: usize) {
+        self.s.reserve(additional);
+    }
+
+    /// Releases excess capacity of the vertex, edge and face arrays and of
+    /// the locate structure.
+    ///
+    /// Removing vertices does not free any memory, call this method after
+    /// removing many vertices to return it.
+    pub fn shrink_to_fit(&mut self) {
+        self.s.shrink_to_fit();
+        self.locate_structure.shrink_to_fit();
+    }
+
+    /// Returns the estimated heap memory used by this triangulation.
+    ///
+    /// Heap memory owned by the vertices is not included, use
+    /// `memory_usage_with` to account for it.
+    pub fn memory_usage(&self) -> TriangulationMemoryUsage {
+        self.memory_usage_with(|_| 0)
+    }
+
+    /// Returns the estimated heap memory used by this triangulation.
+    ///
+    /// `payload` returns the number of heap bytes owned by a vertex, e.g.
+    /// the capacity of a contained `Vec`.
+    pub fn memory_usage_with<F>(&self, mut payload: F) -> TriangulationMemoryUsage
+    where
+        F: FnMut(&V) -> usize,
+    {
+        let mut usage = self.s.memory_usage();
+        usage.locate_structure = self.locate_structure.memory_usage();
+        usage.payload = self.s.vertices().map(|vertex| payload(&*vertex)).sum();
+        usage
+    }
+
     /// Creates a dynamic vertex handle from a fixed vertex handle.
     ///
     /// May panic if the handle was invalidated by a previous vertex
@@ -295,6 +379,24 @@ where
         self.s.face(0)
     }
 
+    /// Collects all faces reachable from a seed face.
+    ///
+    /// Starting at `seed`, the region is expanded to a neighboring face
+    /// whenever `can_cross` returns `true` for the edge separating both faces.
+    /// The edge passed to `can_cross` is always adjacent to a face that has
+    /// already been visited. The result contains every face at most once,
+    /// starting with `seed`. Note that the infinite face is visited as well
+    /// if it is reachable.
+    ///
+    /// Use `classify_regions` to partition all faces along the constraint edges.
+    pub fn grow_region<F>(&self, seed: FixedFaceHandle, can_cross: F) -> Vec<FixedFaceHandle>
+    where
+        F: FnMut(EdgeHandle<V, CdtEdge>) -> bool,
+    {
+        let mut visited = vec![false; self.num_faces()];
+        regions::flood_fill(&self.s, seed, &mut visited, can_cross, |_| {})
+    }
+
     /// Returns `true` if the triangulation is degenerate
     ///
     /// A triangulation is degenerate if all vertices of the
@@ -401,6 +503,142 @@ where
         BasicDelaunaySubdivision::remove(self, vertex)
     }
 
+    /// Creates a triangulation with the same structure but different vertex data.
+    ///
+    /// All handles and constraint edges of this triangulation remain valid for
+    /// the returned triangulation. Refer to `DelaunayTriangulation::map_data`
+    /// for more information.
+    ///
+    /// # Panics
+    /// Panics if `f` returns a vertex at a different position.
+    pub fn map_data<U, F>(&self, mut f: F) -> ConstrainedDelaunayTriangulation<U, K, L>
+    where
+        U: HasPosition2D<Point = V::Point>,
+        F: FnMut(&V) -> U,
+    {
+        ConstrainedDelaunayTriangulation {
+            s: self.s.map_vertices(|v| {
+                let result = f(v);
+                assert!(
+                    result.position() == v.position(),
+                    "map_data must not change vertex positions"
+                );
+                result
+            }),
+            locate_structure: self.locate_structure.clone(),
+            all_points_on_line: self.all_points_on_line,
+            num_constraints: self.num_constraints,
+            __kernel: Default::default(),
+            transaction: None,
+        }
+    }
+
+    /// Starts a transaction.
+    ///
+    /// All vertex insertions and constraint additions until the next call to
+    /// `commit_transaction` or `rollback_transaction` are recorded. Refer to
+    /// `DelaunayTriangulation::begin_transaction` for more information.
+    ///
+    /// # Panics
+    /// Panics if a transaction is already active. Removing vertices during
+    /// a transaction will panic as well.
+    pub fn begin_transaction(&mut self) {
+        assert!(
+            self.transaction.is_none(),
+            "A transaction is already active"
+        );
+        let start = self.start_journal();
+        self.transaction = Some((start, self.num_constraints));
+    }
+
+    /// Ends the current transaction and keeps all of its changes.
+    ///
+    /// # Panics
+    /// Panics if no transaction is active.
+    pub fn commit_transaction(&mut self) {
+        assert!(
+            self.transaction.take().is_some(),
+            "No transaction is active"
+        );
+        self.s.commit_journal();
+    }
+
+    /// Ends the current transaction and reverts all of its changes.
+    ///
+    /// # Panics
+    /// Panics if no transaction is active.
+    pub fn rollback_transaction(&mut self) {
+        let (start, num_constraints) = self.transaction.take().expect("No transaction is active");
+        self.rollback_journal(start);
+        self.num_constraints = num_constraints;
+    }
+
+    /// Returns `true` if a transaction has been started and not yet been
+    /// committed or rolled back.
+    pub fn is_in_transaction(&self) -> bool {
+        self.transaction.is_some()
+    }
+
+    /// Removes all vertices for which `f` returns `false`.
+    ///
+    /// Constraint edges between two retained vertices are kept, all other
+    /// constraint edges are removed together with their vertices.
+    /// If a large part of the triangulation is removed, the remaining
+    /// vertices and constraints are triangulated again from scratch.
+    /// Otherwise, all faces adjacent to removed vertices are replaced at once.
+    ///
+    /// # Handle invalidation
+    /// This method will invalidate all vertex, edge and face handles
+    /// if any vertex is removed.
+    pub fn retain_vertices<F>(&mut self, mut f: F)
+    where
+        F: FnMut(VertexHandle<V, CdtEdge>) -> bool,
+    {
+        let retain: Vec<_> = self.s.vertices().map(&mut f).collect();
+        let num_retained = retain.iter().filter(|r| **r).count();
+        let num_removed = retain.len() - num_retained;
+        if num_removed == 0 {
+            return;
+        }
+        assert!(
+            self.transaction.is_none(),
+            "Vertices cannot be removed during a transaction"
+        );
+        if num_removed >= num_retained
+            || self.all_points_on_line()
+            || self.retained_points_on_line(&retain)
+        {
+            let constraints: Vec<_> = self
+                .s
+                .edges()
+                .filter(|e| self.is_constraint_edge(e.fix()))
+                .map(|e| (e.from().fix(), e.to().fix()))
+                .filter(|&(from, to)| retain[from] && retain[to])
+                .collect();
+            let old = ::core::mem::take(self);
+            let mut new_handles = Vec::with_capacity(retain.len());
+            for (vertex, retain) in old.s.into_vertices().into_iter().zip(retain) {
+                new_handles.push(if retain {
+                    Some(self.insert(vertex))
+                } else {
+                    None
+                });
+            }
+            for (from, to) in constraints {
+                self.add_constraint(new_handles[from].unwrap(), new_handles[to].unwrap());
+            }
+        } else {
+            let num_removed_constraints = self
+                .s
+                .edges()
+                .filter(|e| !retain[e.from().fix()] || !retain[e.to().fix()])
+                .filter(|e| self.is_constraint_edge(e.fix()))
+                .count();
+            self.num_constraints -= num_removed_constraints;
+            self.remove_vertices(&retain);
+        }
+    }
+
     /// Inserts a new vertex into the triangulation.
     ///
     /// This operation runs in O(log(n)) on average when using a tree
@@ -416,6 +654,15 @@ where
         self.insert_with_hint_option(vertex, None)
     }
 
+    /// Inserts a new vertex into the triangulation if its position is finite.
+    ///
+    /// Behaves like `insert`, but returns an error if any coordinate of the
+    /// vertex's position is NaN or infinite.
+    pub fn try_insert(&mut self, vertex: V) -> Result<FixedVertexHandle, NonFiniteCoordinateError> {
+        validate_point(&vertex.position())?;
+        Ok(self.insert(vertex))
+    }
+
     /// Returns the number of constraint edges.
     pub fn num_constraints(&self) -> usize {
         self.num_constraints
@@ -426,6 +673,60 @@ where
         self.s.edge_data(edge).is_constraint_edge()
     }
 
+    /// Compares the structure of two constrained triangulations.
+    ///
+    /// Vertices, edges and constraint edges are matched by their coordinates, vertex
+    /// data and handles are ignored. Entries of the other triangulation that are not
+    /// contained in this triangulation are reported as added.
+    /// This operation runs in O(n log(n)) time.
+    pub fn diff<V2, K2, L2>(
+        &self,
+        other: &ConstrainedDelaunayTriangulation<V2, K2, L2>,
+    ) -> TriangulationDiff<V::Point>
+    where
+        V2: HasPosition2D<Point = V::Point>,
+        K2: DelaunayKernel<<V::Point as PointN>::Scalar>,
+        L2: DelaunayLocateStructure<V::Point>,
+    {
+        diff::diff(self.snapshot(), other.snapshot())
+    }
+
+    /// Returns a hash of the triangulation's structure.
+    ///
+    /// The hash only depends on the positions of all vertices and on the
+    /// triangulation's edges and constraint edges. Vertex data and the order of insertion are ignored:
+    /// Triangulating the same point set yields the same hash, as long as the
+    /// triangulation is unique (no four points lie on a common circle).
+    /// The hash is stable across program runs and platforms, which makes it
+    /// suitable as cache key or for detecting nondeterminism.
+    /// Coordinates are hashed exactly, see `HashScalar`.
+    pub fn structural_hash(&self) -> u64
+    where
+        <V::Point as PointN>::Scalar: HashScalar,
+    {
+        self.snapshot().structural_hash()
+    }
+
+    fn snapshot(&self) -> diff::Snapshot<V::Point> {
+        let positions = |e: EdgeHandle<V, CdtEdge>| (e.from().position(), e.to().position());
+        diff::Snapshot::new(
+            self.vertices().map(|v| v.position()),
+            self.edges().map(positions),
+            self.edges()
+                .filter(|e| self.is_constraint_edge(e.fix()))
+                .map(positions),
+        )
+    }
+
+    /// Partitions all faces into regions that are separated by constraint edges.
+    ///
+    /// Two faces belong to the same region if they can be connected by a path
+    /// that does not cross any constraint edge. See `RegionClassification`
+    /// for more details.
+    pub fn classify_regions(&self) -> RegionClassification {
+        regions::classify_regions(&self.s, |edge| self.is_constraint_edge(edge))
+    }
+
     /// Checks if two vertices are connected by a constraint edge.
     pub fn exists_constraint(&self, from: FixedVertexHandle, to: FixedVertexHandle) -> bool {
         self.get_edge_from_neighbors(from, to)
@@ -686,6 +987,161 @@ where
     }
 }
 
+impl<V, K, L> ConstrainedDelaunayTriangulation<V, K, L>
+where
+    V: HasPosition2D,
+    V::Point: TwoDimensional,
+    <V::Point as PointN>::Scalar: SpadeFloat,
+    K: DelaunayKernel<<V::Point as PointN>::Scalar>,
+    L: DelaunayLocateStructure<V::Point>,
+{
+    /// Returns an iterator over all undirected edges and their lengths.
+    pub fn edge_lengths(&self) -> EdgeLengthIterator<'_, V, CdtEdge> {
+        EdgeLengthIterator::new(self.edges())
+    }
+
+    /// Returns an iterator over all triangles and their interior angles in radians.
+    pub fn triangle_angles(&self) -> TriangleAngleIterator<'_, V, CdtEdge> {
+        TriangleAngleIterator::new(self.triangles())
+    }
+
+    /// Returns the minimum, maximum and mean edge length.
+    ///
+    /// Returns `None` if the triangulation has no edges.
+    pub fn edge_length_statistics(&self) -> Option<Statistics<<V::Point as PointN>::Scalar>> {
+        Statistics::from_values(self.edge_lengths().map(|(_, length)| length))
+    }
+
+    /// Returns the minimum, maximum and mean interior angle of all triangles.
+    ///
+    /// Returns `None` if the triangulation has no triangles.
+    pub fn angle_statistics(&self) -> Option<Statistics<<V::Point as PointN>::Scalar>> {
+        Statistics::from_values(
+            self.triangle_angles()
+                .flat_map(|(_, angles)| angles.to_vec()),
+        )
+    }
+
+    /// Locates a point and calculates its coordinates relative to the located element.
+    ///
+    /// Refer to `DelaunayTriangulation::locate_with_coordinates` for more information.
+    pub fn locate_with_coordinates(&self, point: &V::Point) -> LocatedCoordinates<'_, V, CdtEdge> {
+        position_with_coordinates(self.locate(point), point)
+    }
+
+    /// Returns an iterator over all triangles and their slope, aspect and normal.
+    ///
+    /// The terrain surface is defined by the vertex heights returned by `height`.
+    pub fn face_slopes<F>(&self, height: F) -> FaceSlopeIterator<'_, V, F, CdtEdge>
+    where
+        F: Fn(&V) -> <V::Point as PointN>::Scalar,
+    {
+        FaceSlopeIterator::new(self.triangles(), height)
+    }
+
+    /// Checks if two points can see each other across a terrain surface.
+    ///
+    /// The terrain surface is defined by the vertex heights returned by `height`,
+    /// `from_elevation` and `to_elevation` are the absolute heights of both points.
+    /// The sightline is walked across the triangulation and compared against the
+    /// surface at every crossed edge and vertex. Touching the surface does not
+    /// obstruct the view. Parts of the sightline outside of the convex hull
+    /// are never obstructed.
+    pub fn is_visible<F>(
+        &self,
+        height: F,
+        from: &V::Point,
+        from_elevation: <V::Point as PointN>::Scalar,
+        to: &V::Point,
+        to_elevation: <V::Point as PointN>::Scalar,
+    ) -> bool
+    where
+        F: Fn(&V) -> <V::Point as PointN>::Scalar,
+    {
+        terrain::is_visible(self, &height, from, from_elevation, to, to_elevation)
+    }
+
+    /// Determines which triangles are visible from an observer.
+    ///
+    /// The terrain surface is defined by the vertex heights returned by `height`.
+    /// `observer_elevation` is the absolute height of the observer, to place an
+    /// observer above the ground, add the surface height at its position.
+    /// A triangle is considered visible if its centroid can be seen by the observer.
+    ///
+    /// Returns a vector indexed by fixed face handles. The entry of the infinite face
+    /// is always `false`.
+    pub fn viewshed<F>(
+        &self,
+        height: F,
+        observer: &V::Point,
+        observer_elevation: <V::Point as PointN>::Scalar,
+    ) -> Vec<bool>
+    where
+        F: Fn(&V) -> <V::Point as PointN>::Scalar,
+    {
+        terrain::viewshed(self, height, observer, observer_elevation)
+    }
+
+    /// Rasterizes the triangulation's surface into a grid of heights.
+    ///
+    /// Each triangle is scan converted, every cell whose center lies within the
+    /// triangle receives the height of the triangle's plane through the vertex
+    /// heights returned by `height`. This yields the same values as
+    /// `barycentric_interpolation` but is much faster than interpolating each
+    /// cell separately. Cells outside of the convex hull are `None`.
+    ///
+    /// The result is stored row by row, see `RasterGrid`.
+    pub fn rasterize_heights<F>(
+        &self,
+        grid: &RasterGrid<V::Point>,
+        height: F,
+    ) -> Vec<Option<<V::Point as PointN>::Scalar>>
+    where
+        F: Fn(&V) -> <V::Point as PointN>::Scalar,
+    {
+        terrain::rasterize(self.triangles(), grid, height)
+    }
+
+    /// Calculates the volume between this triangulation's surface and a horizontal plane.
+    ///
+    /// The surface is defined by linearly interpolating the vertex heights
+    /// returned by `height` within each triangle. Only the area covered by
+    /// the triangulation is considered.
+    pub fn volume_to_plane<F>(
+        &self,
+        height: F,
+        plane_height: <V::Point as PointN>::Scalar,
+    ) -> CutFillVolume<<V::Point as PointN>::Scalar>
+    where
+        F: Fn(&V) -> <V::Point as PointN>::Scalar,
+    {
+        terrain::volume_to_plane(self.triangles(), height, plane_height)
+    }
+
+    /// Calculates the volume between this triangulation's surface and another surface.
+    ///
+    /// Both surfaces are defined by linearly interpolating vertex heights within
+    /// each triangle. The other surface is given by its triangles, e.g. by calling
+    /// `triangles()` on another (constrained) Delaunay triangulation.
+    /// The volume is only calculated where both surfaces overlap. Cut volume refers
+    /// to areas where this surface lies above the other surface.
+    pub fn volume_to_surface<'b, F, W, EW, G, I>(
+        &self,
+        height: F,
+        other: I,
+        other_height: G,
+    ) -> CutFillVolume<<V::Point as PointN>::Scalar>
+    where
+        F: Fn(&V) -> <V::Point as PointN>::Scalar,
+        W: HasPosition2D<Point = V::Point> + 'b,
+        EW: Default + Copy + 'b,
+        G: Fn(&W) -> <V::Point as PointN>::Scalar,
+        I: IntoIterator<Item = FaceHandle<'b, W, EW>>,
+    {
+        terrain::volume_to_surface(self.triangles(), height, other, other_height)
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::delaunay_basic::BasicDelaunaySubdivision;
@@ -722,6 +1178,196 @@ mod test {
         cdt.cdt_sanity_check();
     }
 
+    #[test]
+    fn test_try_insert() {
+        let mut cdt = CDT::new();
+        assert!(cdt.try_insert(Point2::new(0.0, 0.0)).is_ok());
+        assert!(cdt.try_insert(Point2::new(f64::NAN, 0.0)).is_err());
+        assert_eq!(cdt.num_vertices(), 1);
+    }
+
+    #[test]
+    fn test_rollback_transaction() {
+        let seed = b"\x51\x0c\x9e\x27\xb4\x6d\x13\xf8\x3a\x85\xc2\x4f\x70\x1e\xd9\x66\
+\xab\x38\x02\x97\x5c\xe1\x4b\x2d\x86\x19\xf3\x60\x0a\xcd\x75\x3e";
+        let points = random_points_with_seed::<f64>(200, seed);
+        let mut cdt = CDT::new();
+        for point in &points[..100] {
+            cdt.insert(*point);
+        }
+        cdt.add_constraint(3, 40);
+        let reference = cdt.clone();
+        cdt.begin_transaction();
+        for point in &points[100..] {
+            cdt.insert(*point);
+        }
+        let edges: Vec<_> = cdt
+            .edges()
+            .step_by(7)
+            .map(|e| (e.from().fix(), e.to().fix()))
+            .collect();
+        for (from, to) in edges {
+            cdt.add_constraint(from, to);
+        }
+        cdt.add_constraint_edge(Point2::new(5.0, 5.0), Point2::new(6.0, -5.0));
+        cdt.rollback_transaction();
+        assert_eq!(cdt.s, reference.s);
+        assert_eq!(cdt.num_constraints(), 1);
+        cdt.cdt_sanity_check();
+        cdt.insert(Point2::new(5.0, 5.0));
+        cdt.cdt_sanity_check();
+    }
+
+    #[test]
+    fn test_compact() {
+        let seed = b"\x07\x9a\x3c\xe5\x41\x6b\xd2\x18\x8f\x20\xb3\x5e\xc9\x74\x0d\xa6\
+\x31\xfe\x52\x8b\x17\xc4\x69\xe0\x3d\x95\x0a\x4f\xb7\x26\xdb\x83";
+        let points = random_points_with_seed::<f64>(200, seed);
+        let mut cdt = CDT::new();
+        for point in &points {
+            cdt.insert(*point);
+        }
+        let constraints: Vec<_> = cdt
+            .edges()
+            .step_by(37)
+            .map(|e| (e.from().fix(), e.to().fix()))
+            .collect();
+        for &(from, to) in &constraints {
+            cdt.add_constraint(from, to);
+        }
+        let num_constraints = cdt.num_constraints();
+        let new_handles = cdt.compact();
+        assert_eq!(cdt.num_constraints(), num_constraints);
+        for &(from, to) in &constraints {
+            let edge = cdt
+                .get_edge_from_neighbors(new_handles[from], new_handles[to])
+                .map(|e| e.fix());
+            assert!(edge.map(|e| cdt.is_constraint_edge(e)).unwrap_or(false));
+        }
+        let constraint_edges = cdt.edges().filter(|e| cdt.is_constraint_edge(e.fix()));
+        assert_eq!(constraint_edges.count(), num_constraints);
+        cdt.cdt_sanity_check();
+        cdt.insert(Point2::new(0.3, 0.2));
+        cdt.cdt_sanity_check();
+    }
+
+    #[test]
+    fn test_clear() {
+        let mut cdt = CDT::new();
+        let v0 = cdt.insert(Point2::new(0.0, 0.0));
+        let v1 = cdt.insert(Point2::new(2.0, 2.0));
+        cdt.insert(Point2::new(1.0, 0.5));
+        cdt.add_constraint(v0, v1);
+        cdt.clear();
+        assert_eq!(cdt.num_vertices(), 0);
+        assert_eq!(cdt.num_constraints(), 0);
+        let v0 = cdt.insert(Point2::new(0.0, 0.0));
+        let v1 = cdt.insert(Point2::new(1.0, 0.0));
+        cdt.insert(Point2::new(0.0, 1.0));
+        assert!(cdt.add_constraint(v0, v1));
+        cdt.cdt_sanity_check();
+    }
+
+    #[test]
+    fn test_map_data() {
+        struct LabeledPoint {
+            point: Point2<f64>,
+            label: f64,
+        }
+
+        impl HasPosition for LabeledPoint {
+            type Point = Point2<f64>;
+            fn position(&self) -> Point2<f64> {
+                self.point
+            }
+        }
+
+        let mut cdt = CDT::new();
+        let v0 = cdt.insert(Point2::new(0.0, 0.0));
+        let v1 = cdt.insert(Point2::new(2.0, 2.0));
+        cdt.insert(Point2::new(1.0, 0.5));
+        cdt.insert(Point2::new(0.5, 1.0));
+        cdt.add_constraint(v0, v1);
+        let mapped = cdt.map_data(|p| LabeledPoint {
+            point: *p,
+            label: p.x * 2.0,
+        });
+        assert_eq!(mapped.num_vertices(), 4);
+        assert_eq!(mapped.num_constraints(), 1);
+        assert!(mapped.exists_constraint(v0, v1));
+        assert_eq!(mapped.vertex(v1).label, 4.0);
+    }
+
+    #[test]
+    fn test_retain_vertices() {
+        for &threshold in &[-0.8, 0.0, 0.8] {
+            let seed = b"\x8e\x1b\x40\x3f\x6a\x25\xd1\x09\xc7\x73\xb2\x5e\x14\xf0\x9d\x61\
+\x2a\xc8\x57\x93\x0b\xe6\x4d\x38\x7f\xa1\x16\xdc\x85\x42\x6b\xf9";
+            let points = random_points_with_seed::<f64>(200, seed);
+            let mut cdt = CDT::new();
+            for point in &points {
+                cdt.insert(*point);
+            }
+            let edges: Vec<_> = cdt
+                .edges()
+                .step_by(5)
+                .map(|e| (e.from().fix(), e.to().fix()))
+                .collect();
+            for (from, to) in edges {
+                cdt.add_constraint(from, to);
+            }
+            let expected_constraints = cdt
+                .edges()
+                .filter(|e| cdt.is_constraint_edge(e.fix()))
+                .filter(|e| e.from().x > threshold && e.to().x > threshold)
+                .count();
+            cdt.retain_vertices(|v| v.x > threshold);
+            let expected = points.iter().filter(|p| p.x > threshold).count();
+            assert_eq!(cdt.num_vertices(), expected);
+            assert!(cdt.vertices().all(|v| v.x > threshold));
+            assert_eq!(cdt.num_constraints(), expected_constraints);
+            cdt.cdt_sanity_check();
+            cdt.legality_check();
+        }
+    }
+
+    #[test]
+    fn test_retain_few_vertices() {
+        let seed = b"\x51\x0d\xe2\x7a\x93\x4c\x18\xbf\x66\xa0\x3e\xd5\x29\x87\xf4\x0b\
+\xc3\x5a\x71\x9e\x02\xed\x46\xb8\x1f\x8c\x37\x64\xda\x25\x90\x4f";
+        let points = random_points_with_seed::<f64>(200, seed);
+        let predicates: [fn(&Point2<f64>) -> bool; 2] = [
+            |p| !(0.09..0.25).contains(&(p.x * p.x + p.y * p.y)),
+            |p| p.x.abs() < 0.9 && p.y.abs() < 0.9,
+        ];
+        for predicate in &predicates {
+            let mut cdt = CDT::new();
+            for point in &points {
+                cdt.insert(*point);
+            }
+            let edges: Vec<_> = cdt
+                .edges()
+                .step_by(3)
+                .map(|e| (e.from().fix(), e.to().fix()))
+                .collect();
+            for (from, to) in edges {
+                cdt.add_constraint(from, to);
+            }
+            let expected_constraints = cdt
+                .edges()
+                .filter(|e| cdt.is_constraint_edge(e.fix()))
+                .filter(|e| predicate(&e.from()) && predicate(&e.to()))
+                .count();
+            cdt.retain_vertices(|v| predicate(&v));
+            let expected = points.iter().filter(|p| predicate(p)).count();
+            assert!(expected * 2 > points.len());
+            assert_eq!(cdt.num_vertices(), expected);
+            assert_eq!(cdt.num_constraints(), expected_constraints);
+            cdt.cdt_sanity_check();
+            cdt.legality_check();
+        }
+    }
+
     #[test]
     fn test_existing_edge_constraint() {
         let mut cdt = CDT::new();
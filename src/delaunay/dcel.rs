@@ -6,6 +6,10 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
+use crate::instrumentation::{record, Counter};
+use alloc::collections::BTreeMap;
+use alloc::{vec, vec::Vec};
+
 /// Handle to a vertex.
 ///
 /// This handle is "fixed", meaning it is intended to be used for
@@ -25,6 +29,194 @@ pub type FixedEdgeHandle = usize;
 /// face handles.
 pub type FixedFaceHandle = usize;
 
+/// The largest number of vertices, edges or faces a `DCEL` can store.
+///
+/// Handles are stored with 32 bits internally, one value marks a missing handle.
+const MAX_DCEL_ELEMENTS: usize = u32::MAX as usize;
+
+/// A handle as stored within the DCEL's arenas.
+///
+/// This halves the size of the entries compared to storing `usize` handles,
+/// which matters for triangulations with tens of millions of vertices.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(
+    feature = "serde_serialize",
+    derive(Serialize, Deserialize),
+    serde(transparent)
+)]
+struct Idx(u32);
+
+impl Idx {
+    #[inline]
+    fn get(self) -> usize {
+        self.0 as usize
+    }
+}
+
+impl From<usize> for Idx {
+    #[inline]
+    fn from(handle: usize) -> Idx {
+        assert!(
+            handle < MAX_DCEL_ELEMENTS,
+            "A DCEL cannot contain more than {} elements",
+            MAX_DCEL_ELEMENTS
+        );
+        Idx(handle as u32)
+    }
+}
+
+impl From<Idx> for usize {
+    #[inline]
+    fn from(handle: Idx) -> usize {
+        handle.get()
+    }
+}
+
+/// An optional `Idx`, using `u32::MAX` to mark a missing handle.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(
+    feature = "serde_serialize",
+    derive(Serialize, Deserialize),
+    serde(from = "Option<u32>", into = "Option<u32>")
+)]
+struct OptIdx(u32);
+
+impl OptIdx {
+    const NONE: OptIdx = OptIdx(u32::MAX);
+
+    #[inline]
+    fn get(self) -> Option<usize> {
+        if self == OptIdx::NONE {
+            None
+        } else {
+            Some(self.0 as usize)
+        }
+    }
+
+    #[inline]
+    fn is_none(self) -> bool {
+        self == OptIdx::NONE
+    }
+}
+
+impl From<Option<usize>> for OptIdx {
+    #[inline]
+    fn from(handle: Option<usize>) -> OptIdx {
+        handle.map_or(OptIdx::NONE, |handle| OptIdx(Idx::from(handle).0))
+    }
+}
+
+impl From<Idx> for OptIdx {
+    #[inline]
+    fn from(handle: Idx) -> OptIdx {
+        OptIdx(handle.0)
+    }
+}
+
+impl From<Option<u32>> for OptIdx {
+    fn from(handle: Option<u32>) -> OptIdx {
+        handle.map_or(OptIdx::NONE, OptIdx)
+    }
+}
+
+impl From<OptIdx> for Option<u32> {
+    fn from(handle: OptIdx) -> Option<u32> {
+        handle.get().map(|handle| handle as u32)
+    }
+}
+
+/// A contiguous arena of DCEL entries.
+///
+/// Can be indexed with both, public `usize` handles and stored `Idx` handles.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(
+    feature = "serde_serialize",
+    derive(Serialize, Deserialize),
+    serde(transparent)
+)]
+struct Arena<T>(Vec<T>);
+
+impl<T> Default for Arena<T> {
+    fn default() -> Self {
+        Arena(Vec::new())
+    }
+}
+
+impl<T> ::core::iter::FromIterator<T> for Arena<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        Arena(iter.into_iter().collect())
+    }
+}
+
+impl<T> IntoIterator for Arena<T> {
+    type Item = T;
+    type IntoIter = ::alloc::vec::IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl<T> ::core::ops::Deref for Arena<T> {
+    type Target = Vec<T>;
+
+    #[inline]
+    fn deref(&self) -> &Vec<T> {
+        &self.0
+    }
+}
+
+impl<T> ::core::ops::DerefMut for Arena<T> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Vec<T> {
+        &mut self.0
+    }
+}
+
+impl<T> ::core::ops::Index<usize> for Arena<T> {
+    type Output = T;
+
+    #[inline]
+    fn index(&self, handle: usize) -> &T {
+        &self.0[handle]
+    }
+}
+
+impl<T> ::core::ops::IndexMut<usize> for Arena<T> {
+    #[inline]
+    fn index_mut(&mut self, handle: usize) -> &mut T {
+        &mut self.0[handle]
+    }
+}
+
+impl<T> ::core::ops::Index<Idx> for Arena<T> {
+    type Output = T;
+
+    #[inline]
+    fn index(&self, handle: Idx) -> &T {
+        &self.0[handle.get()]
+    }
+}
+
+impl<T> ::core::ops::IndexMut<Idx> for Arena<T> {
+    #[inline]
+    fn index_mut(&mut self, handle: Idx) -> &mut T {
+        &mut self.0[handle.get()]
+    }
+}
+
+/// Moves each entry to its new index after updating it with `f`.
+fn permute<T, F>(entries: Arena<T>, new_index: &[usize], mut f: F) -> Arena<T>
+where
+    F: FnMut(T) -> T,
+{
+    let mut slots: Vec<Option<T>> = entries.iter().map(|_| None).collect();
+    for (entry, &index) in entries.into_iter().zip(new_index) {
+        slots[index] = Some(f(entry));
+    }
+    slots.into_iter().map(Option::unwrap).collect()
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct VertexRemovalResult<V> {
     pub updated_vertex: Option<FixedVertexHandle>,
@@ -34,21 +226,21 @@ pub struct VertexRemovalResult<V> {
 #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[cfg_attr(feature = "serde_serialize", derive(Serialize, Deserialize))]
 struct FaceEntry {
-    adjacent_edge: Option<FixedEdgeHandle>,
+    adjacent_edge: OptIdx,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[cfg_attr(feature = "serde_serialize", derive(Serialize, Deserialize))]
 struct VertexEntry<V> {
     data: V,
-    out_edge: Option<FixedEdgeHandle>,
+    out_edge: OptIdx,
 }
 
 impl<V> VertexEntry<V> {
     fn new(data: V) -> VertexEntry<V> {
         VertexEntry {
             data,
-            out_edge: None,
+            out_edge: OptIdx::NONE,
         }
     }
 }
@@ -56,20 +248,40 @@ impl<V> VertexEntry<V> {
 #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[cfg_attr(feature = "serde_serialize", derive(Serialize, Deserialize))]
 struct HalfEdgeEntry<T> {
-    next: FixedEdgeHandle,
-    prev: FixedEdgeHandle,
-    twin: FixedEdgeHandle,
-    origin: FixedVertexHandle,
-    face: FixedFaceHandle,
+    next: Idx,
+    prev: Idx,
+    twin: Idx,
+    origin: Idx,
+    face: Idx,
     data: T,
 }
 
+/// Reverts a single change of a `DCEL`.
+///
+/// While a journal is active, every modification of a `DCEL` pushes the
+/// entry that reverts it. Applying the entries in reverse order restores
+/// the state from the time the journal was started.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+enum JournalEntry<V, E> {
+    PopVertex,
+    SetVertexData(FixedVertexHandle, V),
+    SetOutEdge(FixedVertexHandle, OptIdx),
+    PopEdge,
+    PushEdge(HalfEdgeEntry<E>),
+    SetEdge(FixedEdgeHandle, HalfEdgeEntry<E>),
+    PopFace,
+    PushFace(FaceEntry),
+    SetFace(FixedFaceHandle, FaceEntry),
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[cfg_attr(feature = "serde_serialize", derive(Serialize, Deserialize))]
 pub struct DCEL<V, E = ()> {
-    vertices: Vec<VertexEntry<V>>,
-    faces: Vec<FaceEntry>,
-    edges: Vec<HalfEdgeEntry<E>>,
+    vertices: Arena<VertexEntry<V>>,
+    faces: Arena<FaceEntry>,
+    edges: Arena<HalfEdgeEntry<E>>,
+    #[cfg_attr(feature = "serde_serialize", serde(skip, default = "Option::default"))]
+    journal: Option<Vec<JournalEntry<V, E>>>,
 }
 
 impl<V> DCEL<V> {
@@ -84,11 +296,12 @@ where
 {
     pub fn new_with_edge() -> Self {
         DCEL {
-            vertices: Vec::new(),
-            edges: Vec::new(),
-            faces: vec![FaceEntry {
-                adjacent_edge: None,
-            }],
+            vertices: Arena::default(),
+            edges: Arena::default(),
+            faces: Arena(vec![FaceEntry {
+                adjacent_edge: OptIdx::NONE,
+            }]),
+            journal: None,
         }
     }
 
@@ -116,10 +329,6 @@ where
         &self.edges[handle].data
     }
 
-    pub fn edge_data_mut(&mut self, handle: FixedEdgeHandle) -> &mut E {
-        &mut self.edges[handle].data
-    }
-
     pub fn face(&self, handle: FixedFaceHandle) -> FaceHandle<V, E> {
         FaceHandle::new(self, handle)
     }
@@ -129,6 +338,7 @@ where
     }
 
     pub fn insert_vertex(&mut self, vertex: V) -> FixedVertexHandle {
+        self.record(JournalEntry::PopVertex);
         self.vertices.push(VertexEntry::new(vertex));
         self.vertices.len() - 1
     }
@@ -162,35 +372,36 @@ where
         let edge_index = self.edges.len();
         let twin_index = edge_index + 1;
         let edge = HalfEdgeEntry {
-            next: twin_index,
-            prev: twin_index,
-            twin: twin_index,
-            origin: v0,
-            face,
+            next: twin_index.into(),
+            prev: twin_index.into(),
+            twin: twin_index.into(),
+            origin: v0.into(),
+            face: face.into(),
             data: Default::default(),
         };
-        self.edges.push(edge);
+        self.push_edge(edge);
 
         let twin = HalfEdgeEntry {
-            next: edge_index,
-            prev: edge_index,
-            twin: edge_index,
-            origin: v1,
-            face,
+            next: edge_index.into(),
+            prev: edge_index.into(),
+            twin: edge_index.into(),
+            origin: v1.into(),
+            face: face.into(),
             data: Default::default(),
         };
-        self.edges.push(twin);
+        self.push_edge(twin);
 
-        self.vertices[v0].out_edge = Some(edge_index);
-        self.vertices[v1].out_edge = Some(twin_index);
+        self.set_out_edge(v0, Some(edge_index));
+        self.set_out_edge(v1, Some(twin_index));
 
-        self.faces[face].adjacent_edge = Some(edge_index);
+        self.face_mut(face).adjacent_edge = Some(edge_index).into();
 
         edge_index
     }
 
     pub fn update_vertex(&mut self, handle: FixedVertexHandle, data: V) {
-        self.vertices[handle].data = data;
+        let old = ::core::mem::replace(&mut self.vertices[handle].data, data);
+        self.record(JournalEntry::SetVertexData(handle, old));
     }
 
     pub fn edges(&self) -> EdgesIterator<V, E> {
@@ -208,12 +419,238 @@ where
     pub fn faces(&self) -> FacesIterator<V, E> {
         FacesIterator::new(&self)
     }
+
+    pub fn clear(&mut self) {
+        assert!(
+            self.journal.is_none(),
+            "A DCEL cannot be cleared while a journal is active"
+        );
+        self.vertices.clear();
+        self.edges.clear();
+        self.faces.truncate(1);
+        self.faces[0].adjacent_edge = OptIdx::NONE;
+    }
+
+    pub fn reserve(&mut self, num_vertices: usize) {
+        // A planar triangulation contains at most 3n - 6 edges and 2n - 4 faces
+        self.vertices.reserve(num_vertices);
+        self.edges.reserve(num_vertices * 6);
+        self.faces.reserve(num_vertices * 2);
+    }
+
+    pub fn shrink_to_fit(&mut self) {
+        self.vertices.shrink_to_fit();
+        self.edges.shrink_to_fit();
+        self.faces.shrink_to_fit();
+    }
+
+    /// Returns the heap memory of the vertex, edge and face arrays.
+    pub fn memory_usage(&self) -> super::TriangulationMemoryUsage {
+        super::TriangulationMemoryUsage {
+            vertices: self.vertices.capacity() * ::core::mem::size_of::<VertexEntry<V>>(),
+            edges: self.edges.capacity() * ::core::mem::size_of::<HalfEdgeEntry<E>>(),
+            faces: self.faces.capacity() * ::core::mem::size_of::<FaceEntry>(),
+            ..Default::default()
+        }
+    }
+
+    /// Starts recording all changes to allow reverting them with `rollback_journal`.
+    pub fn start_journal(&mut self) {
+        assert!(self.journal.is_none(), "A journal is already active");
+        self.journal = Some(Vec::new());
+    }
+
+    /// Stops recording and keeps all changes made since `start_journal`.
+    pub fn commit_journal(&mut self) {
+        assert!(self.journal.take().is_some(), "No journal is active");
+    }
+
+    /// Reverts all changes made since `start_journal` and stops recording.
+    pub fn rollback_journal(&mut self) {
+        let journal = self.journal.take().expect("No journal is active");
+        for entry in journal.into_iter().rev() {
+            match entry {
+                JournalEntry::PopVertex => {
+                    self.vertices.pop();
+                }
+                JournalEntry::SetVertexData(handle, data) => self.vertices[handle].data = data,
+                JournalEntry::SetOutEdge(handle, out_edge) => {
+                    self.vertices[handle].out_edge = out_edge
+                }
+                JournalEntry::PopEdge => {
+                    self.edges.pop();
+                }
+                JournalEntry::PushEdge(edge) => self.edges.push(edge),
+                JournalEntry::SetEdge(handle, edge) => self.edges[handle] = edge,
+                JournalEntry::PopFace => {
+                    self.faces.pop();
+                }
+                JournalEntry::PushFace(face) => self.faces.push(face),
+                JournalEntry::SetFace(handle, face) => self.faces[handle] = face,
+            }
+        }
+    }
+
+    fn record(&mut self, entry: JournalEntry<V, E>) {
+        if let Some(ref mut journal) = self.journal {
+            journal.push(entry);
+        }
+    }
+
+    fn push_edge(&mut self, edge: HalfEdgeEntry<E>) {
+        self.record(JournalEntry::PopEdge);
+        self.edges.push(edge);
+    }
+
+    fn push_face(&mut self, face: FaceEntry) {
+        self.record(JournalEntry::PopFace);
+        self.faces.push(face);
+    }
+
+    fn face_mut(&mut self, handle: FixedFaceHandle) -> &mut FaceEntry {
+        let old = self.faces[handle];
+        self.record(JournalEntry::SetFace(handle, old));
+        &mut self.faces[handle]
+    }
+
+    fn set_out_edge(&mut self, handle: FixedVertexHandle, out_edge: Option<FixedEdgeHandle>) {
+        let old = self.vertices[handle].out_edge;
+        self.record(JournalEntry::SetOutEdge(handle, old));
+        self.vertices[handle].out_edge = out_edge.into();
+    }
+
+    /// Renumbers all vertices, edges and faces.
+    ///
+    /// `vertex_order` lists the handles of all vertices in their new order. Edges
+    /// are ordered by the new handle of their origin, twins remain next to each
+    /// other. Faces are ordered by their first edge, the outer face remains face
+    /// `0`. Returns the new handle of each old vertex.
+    pub fn reorder(&mut self, vertex_order: &[FixedVertexHandle]) -> Vec<FixedVertexHandle> {
+        assert!(
+            self.journal.is_none(),
+            "A DCEL cannot be reordered while a journal is active"
+        );
+        assert_eq!(vertex_order.len(), self.num_vertices());
+        const UNSET: usize = usize::MAX;
+        let mut new_vertices = vec![UNSET; self.vertices.len()];
+        for (new, &old) in vertex_order.iter().enumerate() {
+            new_vertices[old] = new;
+        }
+
+        let mut edge_order = Vec::with_capacity(self.edges.len());
+        let mut new_edges = vec![UNSET; self.edges.len()];
+        for &vertex in vertex_order {
+            for edge in self.vertex(vertex).ccw_out_edges() {
+                let edge = edge.fix();
+                if new_edges[edge] == UNSET {
+                    let twin = self.edges[edge].twin.get();
+                    new_edges[edge] = edge_order.len();
+                    new_edges[twin] = edge_order.len() + 1;
+                    edge_order.push(edge);
+                    edge_order.push(twin);
+                }
+            }
+        }
+        assert_eq!(
+            edge_order.len(),
+            self.edges.len(),
+            "Found unreachable edges"
+        );
+
+        let mut new_faces = vec![UNSET; self.faces.len()];
+        new_faces[0] = 0;
+        let mut num_faces = 1;
+        for &edge in &edge_order {
+            let face = self.edges[edge].face.get();
+            if new_faces[face] == UNSET {
+                new_faces[face] = num_faces;
+                num_faces += 1;
+            }
+        }
+
+        let edge = |handle: Idx| Idx::from(new_edges[handle.get()]);
+        let optional_edge = |handle: OptIdx| OptIdx::from(handle.get().map(|e| new_edges[e]));
+        self.vertices = permute(
+            ::core::mem::take(&mut self.vertices),
+            &new_vertices,
+            |entry| VertexEntry {
+                data: entry.data,
+                out_edge: optional_edge(entry.out_edge),
+            },
+        );
+        self.edges = permute(::core::mem::take(&mut self.edges), &new_edges, |entry| {
+            HalfEdgeEntry {
+                next: edge(entry.next),
+                prev: edge(entry.prev),
+                twin: edge(entry.twin),
+                origin: new_vertices[entry.origin.get()].into(),
+                face: new_faces[entry.face.get()].into(),
+                data: entry.data,
+            }
+        });
+        self.faces = permute(::core::mem::take(&mut self.faces), &new_faces, |entry| {
+            FaceEntry {
+                adjacent_edge: optional_edge(entry.adjacent_edge),
+            }
+        });
+        new_vertices
+    }
+
+    pub fn into_vertices(self) -> Vec<V> {
+        self.vertices.into_iter().map(|entry| entry.data).collect()
+    }
+
+    pub fn map_vertices<U, F>(&self, mut f: F) -> DCEL<U, E>
+    where
+        E: Clone,
+        F: FnMut(&V) -> U,
+    {
+        DCEL {
+            vertices: self
+                .vertices
+                .iter()
+                .map(|entry| VertexEntry {
+                    data: f(&entry.data),
+                    out_edge: entry.out_edge,
+                })
+                .collect(),
+            faces: self.faces.clone(),
+            edges: self.edges.clone(),
+            journal: None,
+        }
+    }
 }
 
 impl<V, E> DCEL<V, E>
 where
     E: Default + Copy,
 {
+    pub fn edge_data_mut(&mut self, handle: FixedEdgeHandle) -> &mut E {
+        &mut self.edge_mut(handle).data
+    }
+
+    fn edge_mut(&mut self, handle: FixedEdgeHandle) -> &mut HalfEdgeEntry<E> {
+        let old = self.edges[handle];
+        self.record(JournalEntry::SetEdge(handle, old));
+        &mut self.edges[handle]
+    }
+
+    fn swap_remove_edge(&mut self, handle: FixedEdgeHandle) {
+        let old = self.edges[handle];
+        let last = *self.edges.last().unwrap();
+        self.record(JournalEntry::SetEdge(handle, old));
+        self.record(JournalEntry::PushEdge(last));
+        self.edges.swap_remove(handle);
+    }
+
+    fn swap_remove_face(&mut self, handle: FixedFaceHandle) {
+        let old = self.faces[handle];
+        let last = *self.faces.last().unwrap();
+        self.record(JournalEntry::SetFace(handle, old));
+        self.record(JournalEntry::PushFace(last));
+        self.faces.swap_remove(handle);
+    }
+
     pub fn connect_edge_to_isolated_vertex(
         &mut self,
         prev_handle: FixedEdgeHandle,
@@ -228,29 +665,29 @@ where
         let edge_index = self.edges.len();
         let twin_index = edge_index + 1;
         let edge = HalfEdgeEntry {
-            next: twin_index,
-            prev: prev_handle,
-            twin: twin_index,
+            next: twin_index.into(),
+            prev: prev_handle.into(),
+            twin: twin_index.into(),
             origin: self.edges[prev.twin].origin,
             face: prev.face,
             data: Default::default(),
         };
-        self.edges.push(edge);
+        self.push_edge(edge);
 
         let twin = HalfEdgeEntry {
             next: prev.next,
-            prev: edge_index,
-            twin: edge_index,
-            origin: vertex,
+            prev: edge_index.into(),
+            twin: edge_index.into(),
+            origin: vertex.into(),
             face: prev.face,
             data: Default::default(),
         };
-        self.edges.push(twin);
+        self.push_edge(twin);
 
-        self.edges[prev_handle].next = edge_index;
-        self.edges[prev.next].prev = twin_index;
+        self.edge_mut(prev_handle).next = edge_index.into();
+        self.edge_mut(prev.next.get()).prev = twin_index.into();
 
-        self.vertices[vertex].out_edge = Some(twin_index);
+        self.set_out_edge(vertex, Some(twin_index));
         edge_index
     }
 
@@ -259,7 +696,11 @@ where
         vertex_handle: FixedVertexHandle,
         remaining_face: Option<FixedFaceHandle>,
     ) -> VertexRemovalResult<V> {
-        while let Some(out_edge) = self.vertices[vertex_handle].out_edge {
+        assert!(
+            self.journal.is_none(),
+            "Vertices cannot be removed while a journal is active"
+        );
+        while let Some(out_edge) = self.vertices[vertex_handle].out_edge.get() {
             self.remove_edge(out_edge, remaining_face);
         }
         let data = self.vertices.swap_remove(vertex_handle).data;
@@ -273,7 +714,7 @@ where
                 .map(|e| e.fix())
                 .collect();
             for e in to_update {
-                self.edges[e].origin = vertex_handle;
+                self.edge_mut(e).origin = vertex_handle.into();
             }
             Some(self.vertices.len())
         };
@@ -284,6 +725,214 @@ where
         }
     }
 
+    /// Removes all vertices for which `retain` is `false` and fills the holes
+    /// left by their faces with the given triangles.
+    ///
+    /// The triangles must be ordered counterclockwise. Together with all faces
+    /// that are not adjacent to a removed vertex, they must form a
+    /// triangulation of the retained vertices. Existing edges are reused, the
+    /// entries of removed edges and faces are recycled for new ones.
+    /// Returns the new handle of each vertex, or `None` if it was removed.
+    pub fn replace_vertices(
+        &mut self,
+        retain: &[bool],
+        triangles: &[[FixedVertexHandle; 3]],
+    ) -> Vec<Option<FixedVertexHandle>> {
+        assert!(
+            self.journal.is_none(),
+            "Vertices cannot be removed while a journal is active"
+        );
+        let mut free_edges = Vec::new();
+        let mut free_faces = Vec::new();
+        let mut affected = Vec::new();
+        for vertex in (0..self.vertices.len()).filter(|v| !retain[*v]) {
+            for edge in self.vertex(vertex).ccw_out_edges() {
+                let to = edge.to().fix();
+                if retain[to] {
+                    affected.push(to);
+                }
+                // Edges between two removed vertices are visited twice
+                if retain[to] || vertex < to {
+                    free_edges.push((edge.fix(), edge.sym().fix()));
+                }
+                if edge.face().fix() != 0 {
+                    free_faces.push(edge.face().fix());
+                }
+            }
+        }
+        free_faces.sort_unstable();
+        free_faces.dedup();
+        affected.sort_unstable();
+        affected.dedup();
+
+        // Collect the edges of all triangles. Missing edges take over the
+        // entries of removed edges before new entries are pushed.
+        let mut new_pairs = BTreeMap::new();
+        let mut new_edges = Vec::new();
+        let mut num_pushed = 0;
+        let mut triangle_edges = Vec::with_capacity(triangles.len());
+        for triangle in triangles {
+            let mut edges = [0; 3];
+            for (k, edge) in edges.iter_mut().enumerate() {
+                let (from, to) = (triangle[k], triangle[(k + 1) % 3]);
+                if let Some(existing) = self.get_edge_from_neighbors(from, to) {
+                    *edge = existing.fix();
+                    continue;
+                }
+                let key = (from.min(to), from.max(to));
+                let (forward, backward) = match new_pairs.get(&key) {
+                    Some(pair) => *pair,
+                    None => {
+                        let pair = match free_edges.get(new_edges.len()) {
+                            Some(pair) => *pair,
+                            None => {
+                                let index = self.edges.len() + 2 * num_pushed;
+                                num_pushed += 1;
+                                (index, index + 1)
+                            }
+                        };
+                        new_pairs.insert(key, pair);
+                        new_edges.push((pair, key));
+                        pair
+                    }
+                };
+                *edge = if from == key.0 { forward } else { backward };
+            }
+            triangle_edges.push(edges);
+        }
+        let num_edges = self.edges.len() + 2 * num_pushed;
+        let mut in_triangle = vec![false; num_edges];
+        for edge in triangle_edges.iter().flatten() {
+            in_triangle[*edge] = true;
+        }
+        let mut is_new = vec![false; num_edges];
+        for &((forward, backward), _) in &new_edges {
+            is_new[forward] = true;
+            is_new[backward] = true;
+        }
+        // Edges that are not part of any triangle border the outer face
+        let is_outer = |dcel: &Self, edge: FixedEdgeHandle| {
+            !in_triangle[edge]
+                && (is_new[edge] || {
+                    let face = dcel.edges[edge].face.get();
+                    face == 0 || free_faces.binary_search(&face).is_ok()
+                })
+        };
+        let mut out_edges: Vec<_> = affected
+            .iter()
+            .map(|&vertex| {
+                let edges = self
+                    .vertex(vertex)
+                    .ccw_out_edges()
+                    .filter(|e| retain[e.to().fix()])
+                    .map(|e| {
+                        let twin = e.sym().fix();
+                        (e.fix(), is_outer(self, e.fix()), is_outer(self, twin))
+                    })
+                    .collect::<Vec<_>>();
+                (vertex, edges)
+            })
+            .collect();
+
+        // Write the new edges and triangles
+        for &((forward, backward), (from, to)) in &new_edges {
+            for (edge, twin, origin) in [(forward, backward, from), (backward, forward, to)] {
+                let entry = HalfEdgeEntry {
+                    next: twin.into(),
+                    prev: twin.into(),
+                    twin: twin.into(),
+                    origin: origin.into(),
+                    face: 0.into(),
+                    data: Default::default(),
+                };
+                if edge < self.edges.len() {
+                    *self.edge_mut(edge) = entry;
+                } else {
+                    self.push_edge(entry);
+                }
+            }
+            for (edge, twin, origin) in [(forward, backward, from), (backward, forward, to)] {
+                if let Ok(index) = out_edges.binary_search_by_key(&origin, |(v, _)| *v) {
+                    out_edges[index]
+                        .1
+                        .push((edge, !in_triangle[edge], !in_triangle[twin]));
+                }
+            }
+        }
+        for (index, edges) in triangle_edges.iter().enumerate() {
+            let face = match free_faces.get(index) {
+                Some(face) => *face,
+                None => {
+                    self.push_face(FaceEntry {
+                        adjacent_edge: OptIdx::NONE,
+                    });
+                    self.faces.len() - 1
+                }
+            };
+            self.face_mut(face).adjacent_edge = Some(edges[0]).into();
+            for k in 0..3 {
+                let edge = self.edge_mut(edges[k]);
+                edge.next = edges[(k + 1) % 3].into();
+                edge.prev = edges[(k + 2) % 3].into();
+                edge.face = face.into();
+            }
+        }
+
+        // Connect the edges of the outer face around each affected vertex
+        for (vertex, edges) in &out_edges {
+            self.set_out_edge(*vertex, Some(edges[0].0));
+            let outgoing = edges.iter().find(|(_, outer, _)| *outer);
+            let incoming = edges.iter().find(|(_, _, twin_outer)| *twin_outer);
+            if let (Some(&(outgoing, _, _)), Some(&(incoming, _, _))) = (outgoing, incoming) {
+                let incoming = self.edges[incoming].twin.get();
+                self.edge_mut(incoming).next = outgoing.into();
+                self.edge_mut(incoming).face = 0.into();
+                self.edge_mut(outgoing).prev = incoming.into();
+                self.edge_mut(outgoing).face = 0.into();
+                self.face_mut(0).adjacent_edge = Some(outgoing).into();
+            }
+        }
+
+        // Remove unused entries, starting with the largest index to move only valid entries
+        let mut unused_edges: Vec<_> = free_edges[new_edges.len().min(free_edges.len())..]
+            .iter()
+            .flat_map(|&(edge, twin)| [edge, twin])
+            .collect();
+        unused_edges.sort_unstable();
+        for edge in unused_edges.into_iter().rev() {
+            self.swap_out_edge(edge);
+        }
+        for face in free_faces
+            .iter()
+            .skip(triangles.len())
+            .rev()
+            .cloned()
+            .collect::<Vec<_>>()
+        {
+            self.remove_face(face);
+        }
+        let mut handles: Vec<_> = (0..self.vertices.len()).collect();
+        for vertex in (0..self.vertices.len()).rev().filter(|v| !retain[*v]) {
+            self.vertices.swap_remove(vertex);
+            handles.swap_remove(vertex);
+            if vertex < self.vertices.len() {
+                let to_update: Vec<_> = self
+                    .vertex(vertex)
+                    .ccw_out_edges()
+                    .map(|e| e.fix())
+                    .collect();
+                for e in to_update {
+                    self.edge_mut(e).origin = vertex.into();
+                }
+            }
+        }
+        let mut new_handles = vec![None; retain.len()];
+        for (new, old) in handles.into_iter().enumerate() {
+            new_handles[old] = Some(new);
+        }
+        new_handles
+    }
+
     pub fn connect_edge_to_edge(
         &mut self,
         prev_edge_handle: FixedEdgeHandle,
@@ -294,29 +943,29 @@ where
         let next_edge = self.edges[next_edge_handle];
         let prev_edge = self.edges[prev_edge_handle];
         let edge = HalfEdgeEntry {
-            next: next_edge_handle,
-            prev: prev_edge_handle,
-            twin: twin_index,
+            next: next_edge_handle.into(),
+            prev: prev_edge_handle.into(),
+            twin: twin_index.into(),
             origin: self.edges[prev_edge.twin].origin,
             face: next_edge.face,
             data: Default::default(),
         };
-        self.edges.push(edge);
+        self.push_edge(edge);
 
         let twin = HalfEdgeEntry {
             next: prev_edge.next,
             prev: next_edge.prev,
-            twin: edge_index,
+            twin: edge_index.into(),
             origin: next_edge.origin,
             face: next_edge.face,
             data: Default::default(),
         };
-        self.edges.push(twin);
+        self.push_edge(twin);
 
-        self.edges[next_edge_handle].prev = edge_index;
-        self.edges[prev_edge_handle].next = edge_index;
-        self.edges[next_edge.prev].next = twin_index;
-        self.edges[prev_edge.next].prev = twin_index;
+        self.edge_mut(next_edge_handle).prev = edge_index.into();
+        self.edge_mut(prev_edge_handle).next = edge_index.into();
+        self.edge_mut(next_edge.prev.get()).next = twin_index.into();
+        self.edge_mut(prev_edge.next.get()).prev = twin_index.into();
 
         edge_index
     }
@@ -339,40 +988,40 @@ where
         let (new_edge_next, new_twin_prev) = if is_isolated {
             (new_twin_index, new_edge_index)
         } else {
-            (edge.next, twin.prev)
+            (edge.next.get(), twin.prev.get())
         };
 
         let new_edge = HalfEdgeEntry {
-            next: new_edge_next,
-            prev: edge_handle,
-            twin: new_twin_index,
-            origin: split_vertex,
+            next: new_edge_next.into(),
+            prev: edge_handle.into(),
+            twin: new_twin_index.into(),
+            origin: split_vertex.into(),
             face: edge.face,
             data: Default::default(),
         };
 
         let new_twin = HalfEdgeEntry {
             next: edge.twin,
-            prev: new_twin_prev,
-            twin: new_edge_index,
+            prev: new_twin_prev.into(),
+            twin: new_edge_index.into(),
             origin: twin.origin,
             face: twin.face,
             data: Default::default(),
         };
 
         if !is_isolated {
-            self.edges[edge.next].prev = new_edge_index;
-            self.edges[twin.prev].next = new_twin_index;
+            self.edge_mut(edge.next.get()).prev = new_edge_index.into();
+            self.edge_mut(twin.prev.get()).next = new_twin_index.into();
         }
-        self.edges[edge.twin].prev = new_twin_index;
-        self.edges[edge_handle].next = new_edge_index;
+        self.edge_mut(edge.twin.get()).prev = new_twin_index.into();
+        self.edge_mut(edge_handle).next = new_edge_index.into();
 
-        self.edges[edge.twin].origin = split_vertex;
-        self.vertices[twin.origin].out_edge = Some(new_twin_index);
-        self.vertices[split_vertex].out_edge = Some(new_edge_index);
+        self.edge_mut(edge.twin.get()).origin = split_vertex.into();
+        self.set_out_edge(twin.origin.get(), Some(new_twin_index));
+        self.set_out_edge(split_vertex, Some(new_edge_index));
 
-        self.edges.push(new_edge);
-        self.edges.push(new_twin);
+        self.push_edge(new_edge);
+        self.push_edge(new_twin);
         new_edge_index
     }
 
@@ -385,12 +1034,12 @@ where
 
         let twin = self.edges[edge.twin];
 
-        self.edges[edge.prev].next = twin.next;
-        self.edges[twin.next].prev = edge.prev;
-        self.edges[edge.next].prev = twin.prev;
-        self.edges[twin.prev].next = edge.next;
+        self.edge_mut(edge.prev.get()).next = twin.next;
+        self.edge_mut(twin.next.get()).prev = edge.prev;
+        self.edge_mut(edge.next.get()).prev = twin.prev;
+        self.edge_mut(twin.prev.get()).next = edge.next;
 
-        let (to_remove, to_keep) = if remaining_face == Some(twin.face) {
+        let (to_remove, to_keep) = if remaining_face == Some(twin.face.get()) {
             (edge, twin)
         } else {
             (twin, edge)
@@ -398,75 +1047,75 @@ where
 
         if edge.prev == edge.twin && edge.next == edge.twin {
             // We remove an isolated edge
-            self.faces[to_keep.face].adjacent_edge = None;
+            self.face_mut(to_keep.face.get()).adjacent_edge = OptIdx::NONE;
         } else {
             let new_adjacent_edge = if edge.prev != edge.twin {
                 edge.prev
             } else {
                 edge.next
             };
-            self.faces[to_keep.face].adjacent_edge = Some(new_adjacent_edge);
-            self.edges[new_adjacent_edge].face = to_keep.face;
+            self.face_mut(to_keep.face.get()).adjacent_edge = new_adjacent_edge.into();
+            self.edge_mut(new_adjacent_edge.get()).face = to_keep.face;
         }
 
         if edge.prev == edge.twin {
-            self.vertices[edge.origin].out_edge = None;
+            self.set_out_edge(edge.origin.get(), None);
         } else {
-            self.vertices[edge.origin].out_edge = Some(twin.next);
+            self.set_out_edge(edge.origin.get(), Some(twin.next.get()));
         }
 
         if edge.next == edge.twin {
-            self.vertices[twin.origin].out_edge = None;
+            self.set_out_edge(twin.origin.get(), None);
         } else {
-            self.vertices[twin.origin].out_edge = Some(edge.next);
+            self.set_out_edge(twin.origin.get(), Some(edge.next.get()));
         }
 
         // We must remove the larger index first to prevent the other edge
         // from being updated
-        if edge_handle > edge.twin {
+        if edge_handle > edge.twin.get() {
             self.swap_out_edge(edge_handle);
-            self.swap_out_edge(edge.twin);
+            self.swap_out_edge(edge.twin.get());
         } else {
-            self.swap_out_edge(edge.twin);
+            self.swap_out_edge(edge.twin.get());
             self.swap_out_edge(edge_handle);
         }
         if edge.face != twin.face {
             let neighs: Vec<_> = self
-                .face(to_keep.face)
+                .face(to_keep.face.get())
                 .adjacent_edges()
                 .map(|e| e.fix())
                 .collect();
             for n in neighs {
-                self.edges[n].face = to_keep.face
+                self.edge_mut(n).face = to_keep.face
             }
-            self.remove_face(to_remove.face);
+            self.remove_face(to_remove.face.get());
         }
     }
 
     fn remove_face(&mut self, face: FixedFaceHandle) {
-        self.faces.swap_remove(face);
+        self.swap_remove_face(face);
         if self.faces.len() > face {
             let neighs: Vec<_> = self.face(face).adjacent_edges().map(|e| e.fix()).collect();
             for n in neighs {
-                self.edges[n].face = face;
+                self.edge_mut(n).face = face.into();
             }
         }
     }
 
     fn swap_out_edge(&mut self, edge_handle: FixedEdgeHandle) {
-        self.edges.swap_remove(edge_handle);
+        self.swap_remove_edge(edge_handle);
         if self.edges.len() > edge_handle {
             // Update edge index
             let old_handle = self.edges.len();
             let edge = self.edges[edge_handle];
-            self.edges[edge.next].prev = edge_handle;
-            self.edges[edge.prev].next = edge_handle;
-            self.edges[edge.twin].twin = edge_handle;
+            self.edge_mut(edge.next.get()).prev = edge_handle.into();
+            self.edge_mut(edge.prev.get()).next = edge_handle.into();
+            self.edge_mut(edge.twin.get()).twin = edge_handle.into();
 
-            if self.vertices[edge.origin].out_edge == Some(old_handle) {
-                self.vertices[edge.origin].out_edge = Some(edge_handle);
+            if self.vertices[edge.origin].out_edge.get() == Some(old_handle) {
+                self.set_out_edge(edge.origin.get(), Some(edge_handle));
             }
-            self.faces[edge.face].adjacent_edge = Some(edge_handle);
+            self.face_mut(edge.face.get()).adjacent_edge = Some(edge_handle).into();
         }
     }
 
@@ -479,69 +1128,70 @@ where
 
         let new_face = self.num_faces();
 
-        self.faces.push(FaceEntry {
-            adjacent_edge: Some(edge_index),
+        self.push_face(FaceEntry {
+            adjacent_edge: Some(edge_index).into(),
         });
 
         // Set the face to the left of the new edge
         let mut cur_edge = edge_index;
 
         loop {
-            self.edges[cur_edge].face = new_face;
-            cur_edge = self.edges[cur_edge].next;
+            self.edge_mut(cur_edge).face = new_face.into();
+            cur_edge = self.edges[cur_edge].next.get();
             if cur_edge == edge_index {
                 break;
             }
         }
         let twin = self.edges[edge_index].twin;
-        self.faces[self.edges[twin].face].adjacent_edge = Some(twin);
+        self.face_mut(self.edges[twin].face.get()).adjacent_edge = twin.into();
         edge_index
     }
 
     pub fn flip_cw(&mut self, e: FixedEdgeHandle) {
+        record(Counter::EdgeFlips, 1);
         let en = self.edges[e].next;
         let ep = self.edges[e].prev;
         let t = self.edges[e].twin;
         let tn = self.edges[t].next;
         let tp = self.edges[t].prev;
 
-        self.edges[en].next = e;
-        self.edges[en].prev = tp;
-        self.edges[e].next = tp;
-        self.edges[e].prev = en;
-        self.edges[tp].next = en;
-        self.edges[tp].prev = e;
+        self.edge_mut(en.get()).next = e.into();
+        self.edge_mut(en.get()).prev = tp;
+        self.edge_mut(e).next = tp;
+        self.edge_mut(e).prev = en;
+        self.edge_mut(tp.get()).next = en;
+        self.edge_mut(tp.get()).prev = e.into();
 
-        self.edges[tn].next = t;
-        self.edges[tn].prev = ep;
-        self.edges[t].next = ep;
-        self.edges[t].prev = tn;
-        self.edges[ep].next = tn;
-        self.edges[ep].prev = t;
+        self.edge_mut(tn.get()).next = t;
+        self.edge_mut(tn.get()).prev = ep;
+        self.edge_mut(t.get()).next = ep;
+        self.edge_mut(t.get()).prev = tn;
+        self.edge_mut(ep.get()).next = tn;
+        self.edge_mut(ep.get()).prev = t;
 
-        self.vertices[self.edges[e].origin].out_edge = Some(tn);
-        self.vertices[self.edges[t].origin].out_edge = Some(en);
+        self.set_out_edge(self.edges[e].origin.get(), Some(tn.get()));
+        self.set_out_edge(self.edges[t].origin.get(), Some(en.get()));
 
-        self.edges[e].origin = self.edges[ep].origin;
-        self.edges[t].origin = self.edges[tp].origin;
+        self.edge_mut(e).origin = self.edges[ep].origin;
+        self.edge_mut(t.get()).origin = self.edges[tp].origin;
 
-        self.faces[self.edges[e].face].adjacent_edge = Some(e);
-        self.faces[self.edges[t].face].adjacent_edge = Some(t);
+        self.face_mut(self.edges[e].face.get()).adjacent_edge = Some(e).into();
+        self.face_mut(self.edges[t].face.get()).adjacent_edge = t.into();
 
-        self.edges[tp].face = self.edges[e].face;
-        self.edges[ep].face = self.edges[t].face;
+        self.edge_mut(tp.get()).face = self.edges[e].face;
+        self.edge_mut(ep.get()).face = self.edges[t].face;
     }
 
     #[cfg(test)]
     pub fn sanity_check(&self) {
         for (index, face) in self.faces.iter().enumerate() {
-            if let Some(adj) = face.adjacent_edge {
-                assert_eq!(self.edges[adj].face, index);
+            if let Some(adj) = face.adjacent_edge.get() {
+                assert_eq!(self.edges[adj].face.get(), index);
             }
         }
         for (index, vertex) in self.vertices.iter().enumerate() {
-            if let Some(out_edge) = vertex.out_edge {
-                assert_eq!(self.edges[out_edge].origin, index);
+            if let Some(out_edge) = vertex.out_edge.get() {
+                assert_eq!(self.edges[out_edge].origin.get(), index);
             }
         }
         for handle in 0..self.num_edges() {
@@ -555,7 +1205,7 @@ where
 
 impl<V, E> DCEL<V, E>
 where
-    E: ::std::fmt::Debug,
+    E: ::core::fmt::Debug,
 {
     #[cfg(test)]
     fn print(&self) {
@@ -650,6 +1300,81 @@ where
     }
 }
 
+/// An iterator that iterates over the outgoing edges from a vertex in clockwise order.
+///
+/// Created by `VertexHandle::cw_out_edges` and `EdgeHandle::cw_iter`.
+pub type CWIterator<'a, V, E = ()> = ::core::iter::Rev<CCWIterator<'a, V, E>>;
+
+/// An iterator that iterates over the neighbors of a vertex.
+///
+/// The neighbors are the destinations of the vertex's outgoing edges, see
+/// `VertexHandle::ccw_neighbors` for the iteration order. Use `rev()` to
+/// iterate in clockwise order.
+pub struct NeighborIterator<'a, V, E = ()>
+where
+    V: 'a,
+    E: 'a,
+{
+    edges: CCWIterator<'a, V, E>,
+}
+
+impl<'a, V, E> Iterator for NeighborIterator<'a, V, E>
+where
+    V: 'a,
+    E: Default + 'a,
+{
+    type Item = VertexHandle<'a, V, E>;
+
+    fn next(&mut self) -> Option<VertexHandle<'a, V, E>> {
+        self.edges.next().map(|e| e.to())
+    }
+}
+
+impl<'a, V, E> DoubleEndedIterator for NeighborIterator<'a, V, E>
+where
+    V: 'a,
+    E: Default + 'a,
+{
+    fn next_back(&mut self) -> Option<VertexHandle<'a, V, E>> {
+        self.edges.next_back().map(|e| e.to())
+    }
+}
+
+/// An iterator that iterates over the faces adjacent to a vertex.
+///
+/// Each face is the face to the left of one of the vertex's outgoing edges,
+/// see `VertexHandle::ccw_adjacent_faces` for the iteration order. Use
+/// `rev()` to iterate in clockwise order.
+pub struct AdjacentFaceIterator<'a, V, E = ()>
+where
+    V: 'a,
+    E: 'a,
+{
+    edges: CCWIterator<'a, V, E>,
+}
+
+impl<'a, V, E> Iterator for AdjacentFaceIterator<'a, V, E>
+where
+    V: 'a,
+    E: Default + 'a,
+{
+    type Item = FaceHandle<'a, V, E>;
+
+    fn next(&mut self) -> Option<FaceHandle<'a, V, E>> {
+        self.edges.next().map(|e| e.face())
+    }
+}
+
+impl<'a, V, E> DoubleEndedIterator for AdjacentFaceIterator<'a, V, E>
+where
+    V: 'a,
+    E: Default + 'a,
+{
+    fn next_back(&mut self) -> Option<FaceHandle<'a, V, E>> {
+        self.edges.next_back().map(|e| e.face())
+    }
+}
+
 /// An iterator that iterates over the outgoing edges from a vertex.
 ///
 /// The edges will be iterated in counterclockwise order. Note that
@@ -685,6 +1410,14 @@ where
             cur_until: Some((edge.fix(), edge.cw().fix())),
         }
     }
+
+    fn until_edge(dcel: &'a DCEL<V, E>, edge: FixedEdgeHandle) -> Self {
+        let edge = dcel.edge(edge);
+        CCWIterator {
+            dcel,
+            cur_until: Some((edge.ccw().fix(), edge.fix())),
+        }
+    }
 }
 
 impl<'a, V, E> Iterator for CCWIterator<'a, V, E>
@@ -768,7 +1501,7 @@ where
     }
 }
 
-type FixedVerticesIterator = ::std::ops::Range<usize>;
+type FixedVerticesIterator = ::core::ops::Range<usize>;
 
 pub struct VerticesIterator<'a, V, E = ()>
 where
@@ -836,7 +1569,7 @@ where
         if let Some(edge) = self.dcel.edges.get(self.current) {
             let twin = edge.twin;
             self.current += 1;
-            if self.current - 1 < twin {
+            if self.current - 1 < twin.get() {
                 Some(EdgeHandle::new(self.dcel, self.current - 1))
             } else {
                 self.next()
@@ -883,11 +1616,11 @@ where
     handle: FixedFaceHandle,
 }
 
-impl<'a, V, E> ::std::fmt::Debug for VertexHandle<'a, V, E>
+impl<'a, V, E> ::core::fmt::Debug for VertexHandle<'a, V, E>
 where
     V: 'a,
 {
-    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
         write!(f, "VertexHandle({:?})", self.handle)
     }
 }
@@ -924,17 +1657,67 @@ where
     pub fn out_edge(&self) -> Option<EdgeHandle<'a, V, E>> {
         self.dcel.vertices[self.handle]
             .out_edge
+            .get()
             .map(|e| self.dcel.edge(e))
     }
 
     /// Returns all outgoing edges in counter clockwise order.
     ///
+    /// The iteration starts with the edge following `out_edge()` in
+    /// counterclockwise order and ends with `out_edge()` itself, every
+    /// outgoing edge is returned exactly once. Isolated vertices have
+    /// no outgoing edges.
+    ///
     /// Note that this assumes that you use a right handed coordinate system,
     /// otherwise the sense of orientation is inverted.
     pub fn ccw_out_edges(&self) -> CCWIterator<'a, V, E> {
         CCWIterator::new(self.dcel, self.handle)
     }
 
+    /// Returns all outgoing edges in clockwise order.
+    ///
+    /// The iteration starts with `out_edge()` and visits every outgoing
+    /// edge exactly once. This is the reverse order of `ccw_out_edges`.
+    pub fn cw_out_edges(&self) -> CWIterator<'a, V, E> {
+        self.ccw_out_edges().rev()
+    }
+
+    /// Returns all neighboring vertices in counter clockwise order.
+    ///
+    /// The neighbors are returned in the same order as their connecting
+    /// edges are returned by `ccw_out_edges`.
+    pub fn ccw_neighbors(&self) -> NeighborIterator<'a, V, E> {
+        NeighborIterator {
+            edges: self.ccw_out_edges(),
+        }
+    }
+
+    /// Returns all neighboring vertices in clockwise order.
+    ///
+    /// The first neighbor is the destination of `out_edge()`.
+    pub fn cw_neighbors(&self) -> ::core::iter::Rev<NeighborIterator<'a, V, E>> {
+        self.ccw_neighbors().rev()
+    }
+
+    /// Returns all adjacent faces in counter clockwise order.
+    ///
+    /// Each face is the face to the left of an outgoing edge, in the same
+    /// order as `ccw_out_edges`. The infinite face is returned as well if
+    /// the vertex lies on the convex hull. The infinite face may be returned
+    /// more than once if all vertices lie on a line.
+    pub fn ccw_adjacent_faces(&self) -> AdjacentFaceIterator<'a, V, E> {
+        AdjacentFaceIterator {
+            edges: self.ccw_out_edges(),
+        }
+    }
+
+    /// Returns all adjacent faces in clockwise order.
+    ///
+    /// The first face is the face to the left of `out_edge()`.
+    pub fn cw_adjacent_faces(&self) -> ::core::iter::Rev<AdjacentFaceIterator<'a, V, E>> {
+        self.ccw_adjacent_faces().rev()
+    }
+
     /// Creates a fixed vertex handle from this dynamic handle.
     ///
     /// # Notes
@@ -955,7 +1738,7 @@ where
     }
 }
 
-impl<'a, V, E> ::std::ops::Deref for VertexHandle<'a, V, E> {
+impl<'a, V, E> ::core::ops::Deref for VertexHandle<'a, V, E> {
     type Target = V;
 
     fn deref(&self) -> &V {
@@ -983,12 +1766,12 @@ where
     }
 }
 
-impl<'a, V, E> ::std::fmt::Debug for EdgeHandle<'a, V, E>
+impl<'a, V, E> ::core::fmt::Debug for EdgeHandle<'a, V, E>
 where
     V: 'a,
     E: Default,
 {
-    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
         write!(
             f,
             "EdgeHandle - id: {:?} ({:?} -> {:?})",
@@ -1022,7 +1805,7 @@ where
     /// Returns the edge's source vertex.
     pub fn from(&self) -> VertexHandle<'a, V, E> {
         let edge = &self.dcel.edges[self.handle];
-        VertexHandle::new(self.dcel, edge.origin)
+        VertexHandle::new(self.dcel, edge.origin.get())
     }
 
     /// Returns the oriented next edge.
@@ -1033,7 +1816,7 @@ where
     /// "Oriented" means counterclockwise for right handed
     /// coordinate systems.
     pub fn o_next(&self) -> EdgeHandle<'a, V, E> {
-        EdgeHandle::new(self.dcel, self.dcel.edges[self.handle].next)
+        EdgeHandle::new(self.dcel, self.dcel.edges[self.handle].next.get())
     }
 
     /// Returns the oriented previous edge.
@@ -1044,7 +1827,7 @@ where
     /// "Oriented" means counterclockwise for right handed
     /// coordinate systems.
     pub fn o_prev(&self) -> EdgeHandle<'a, V, E> {
-        EdgeHandle::new(self.dcel, self.dcel.edges[self.handle].prev)
+        EdgeHandle::new(self.dcel, self.dcel.edges[self.handle].prev.get())
     }
 
     /// Returns an iterator over all edges sharing the same face
@@ -1064,14 +1847,14 @@ where
 
     /// Returns the face located to the left of this edge.
     pub fn face(&self) -> FaceHandle<'a, V, E> {
-        self.dcel.face(self.dcel.edges[self.handle].face)
+        self.dcel.face(self.dcel.edges[self.handle].face.get())
     }
 
     /// Returns this edge's mirror edge.
     pub fn sym(&self) -> EdgeHandle<'a, V, E> {
         EdgeHandle {
             dcel: self.dcel,
-            handle: self.dcel.edges[self.handle].twin,
+            handle: self.dcel.edges[self.handle].twin.get(),
         }
     }
 
@@ -1083,7 +1866,7 @@ where
         let twin = self.sym().handle;
         EdgeHandle {
             dcel: self.dcel,
-            handle: self.dcel.edges[twin].next,
+            handle: self.dcel.edges[twin].next.get(),
         }
     }
 
@@ -1094,7 +1877,7 @@ where
     pub fn ccw(&self) -> EdgeHandle<'a, V, E> {
         EdgeHandle {
             dcel: self.dcel,
-            handle: self.dcel.edges[self.handle].prev,
+            handle: self.dcel.edges[self.handle].prev.get(),
         }
         .sym()
     }
@@ -1107,6 +1890,15 @@ where
     pub fn ccw_iter(&self) -> CCWIterator<'a, V, E> {
         CCWIterator::from_edge(self.dcel, self.handle)
     }
+
+    /// Returns an iterator over all edges with the same origin in clockwise
+    /// order.
+    ///
+    /// The iteration starts with this edge and ends with the edge following
+    /// it in counterclockwise order.
+    pub fn cw_iter(&self) -> CWIterator<'a, V, E> {
+        CCWIterator::until_edge(self.dcel, self.handle).rev()
+    }
 }
 
 impl<'a, V, E> Copy for FaceHandle<'a, V, E> where V: 'a {}
@@ -1129,11 +1921,11 @@ where
     }
 }
 
-impl<'a, V, E> ::std::fmt::Debug for FaceHandle<'a, V, E>
+impl<'a, V, E> ::core::fmt::Debug for FaceHandle<'a, V, E>
 where
     V: 'a,
 {
-    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
         write!(f, "FaceHandle({:?})", self.handle)
     }
 }
@@ -1166,6 +1958,7 @@ where
     pub fn as_triangle(&self) -> [VertexHandle<'a, V, E>; 3] {
         let adjacent = self.dcel.faces[self.handle]
             .adjacent_edge
+            .get()
             .expect("Face has no adjacent edge");
         let edge = self.dcel.edge(adjacent);
         let prev = edge.o_prev();
@@ -1182,6 +1975,7 @@ where
     pub fn adjacent_edge(&self) -> Option<EdgeHandle<'a, V, E>> {
         self.dcel.faces[self.handle]
             .adjacent_edge
+            .get()
             .map(|e| EdgeHandle::new(self.dcel, e))
     }
 
@@ -1191,7 +1985,7 @@ where
     /// This order will be counterclockwise for right handed coordinate
     /// system or clockwise for left handed systems.
     pub fn adjacent_edges(&self) -> ONextIterator<'a, V, E> {
-        if let Some(adj) = self.dcel.faces[self.handle].adjacent_edge {
+        if let Some(adj) = self.dcel.faces[self.handle].adjacent_edge.get() {
             ONextIterator::new(self.dcel, adj)
         } else {
             ONextIterator::new_empty(self.dcel)
@@ -1206,7 +2000,17 @@ where
 
 #[cfg(test)]
 mod test {
-    use super::{HalfEdgeEntry, DCEL};
+    use super::{FaceEntry, HalfEdgeEntry, Idx, OptIdx, VertexEntry, DCEL};
+    use std::mem::size_of;
+
+    #[test]
+    fn test_compact_entries() {
+        assert_eq!(size_of::<HalfEdgeEntry<()>>(), 20);
+        assert_eq!(size_of::<VertexEntry<()>>(), 4);
+        assert_eq!(size_of::<FaceEntry>(), 4);
+        assert_eq!(OptIdx::from(Some(7usize)).get(), Some(7));
+        assert_eq!(OptIdx::from(None::<usize>).get(), None);
+    }
 
     #[test]
     fn test_create_triangle() {
@@ -1223,39 +2027,39 @@ mod test {
         assert_eq!(
             dcel.edges[e01],
             HalfEdgeEntry {
-                next: e12,
-                prev: e20,
+                next: e12.into(),
+                prev: e20.into(),
                 twin: t01,
-                origin: 0,
-                face: 1,
+                origin: Idx(0),
+                face: Idx(1),
                 data: (),
             }
         );
         assert_eq!(
             dcel.edges[e12],
             HalfEdgeEntry {
-                next: e20,
-                prev: e01,
+                next: e20.into(),
+                prev: e01.into(),
                 twin: t12,
-                origin: 1,
-                face: 1,
+                origin: Idx(1),
+                face: Idx(1),
                 data: (),
             }
         );
         assert_eq!(
             dcel.edges[e20],
             HalfEdgeEntry {
-                next: e01,
-                prev: e12,
+                next: e01.into(),
+                prev: e12.into(),
                 twin: t20,
-                origin: 2,
-                face: 1,
+                origin: Idx(2),
+                face: Idx(1),
                 data: (),
             }
         );
-        assert_eq!(dcel.edges[t01].face, 0);
-        assert_eq!(dcel.edges[t12].face, 0);
-        assert_eq!(dcel.edges[t20].face, 0);
+        assert_eq!(dcel.edges[t01].face, Idx(0));
+        assert_eq!(dcel.edges[t12].face, Idx(0));
+        assert_eq!(dcel.edges[t20].face, Idx(0));
     }
 
     #[test]
@@ -1274,11 +2078,11 @@ mod test {
         assert_eq!(
             dcel.edges[e_flip],
             HalfEdgeEntry {
-                next: e23,
-                prev: e30,
+                next: e23.into(),
+                prev: e30.into(),
                 twin: dcel.edges[e_flip].twin,
-                origin: 0,
-                face: 2,
+                origin: Idx(0),
+                face: Idx(2),
                 data: (),
             }
         );
@@ -1287,22 +2091,22 @@ mod test {
         assert_eq!(
             dcel.edges[e_flip],
             HalfEdgeEntry {
-                next: e12,
-                prev: e23,
+                next: e12.into(),
+                prev: e23.into(),
                 twin,
-                origin: 3,
-                face: 2,
+                origin: Idx(3),
+                face: Idx(2),
                 data: (),
             }
         );
         assert_eq!(
             dcel.edges[twin],
             HalfEdgeEntry {
-                next: e30,
-                prev: e01,
-                twin: e_flip,
-                origin: 1,
-                face: 1,
+                next: e30.into(),
+                prev: e01.into(),
+                twin: e_flip.into(),
+                origin: Idx(1),
+                face: Idx(1),
                 data: (),
             }
         );
@@ -1339,53 +2143,54 @@ mod test {
         assert_eq!(
             dcel.edges[e20],
             HalfEdgeEntry {
-                next: e_split,
-                prev: e12,
-                twin: t20,
-                origin: v2,
-                face: 1,
+                next: e_split.into(),
+                prev: e12.into(),
+                twin: t20.into(),
+                origin: v2.into(),
+                face: Idx(1),
                 data: (),
             }
         );
         assert_eq!(
             dcel.edges[e_split],
             HalfEdgeEntry {
-                next: e01,
-                prev: e20,
-                twin: t_split,
-                origin: v3,
-                face: 1,
+                next: e01.into(),
+                prev: e20.into(),
+                twin: t_split.into(),
+                origin: v3.into(),
+                face: Idx(1),
                 data: (),
             }
         );
         assert_eq!(
             dcel.edges[t_split],
             HalfEdgeEntry {
-                next: t20,
-                prev: t01,
-                origin: v0,
-                twin: e_split,
-                face: 0,
+                next: t20.into(),
+                prev: t01.into(),
+                origin: v0.into(),
+                twin: e_split.into(),
+                face: Idx(0),
                 data: (),
             }
         );
         assert_eq!(
             dcel.edges[t20],
             HalfEdgeEntry {
-                next: t12,
-                prev: t_split,
-                origin: v3,
-                twin: e20,
-                face: 0,
+                next: t12.into(),
+                prev: t_split.into(),
+                origin: v3.into(),
+                twin: e20.into(),
+                face: Idx(0),
                 data: (),
             }
         );
-        assert_eq!(dcel.edges[t01].next, t_split);
-        assert_eq!(dcel.edges[e01].prev, e_split);
-        assert_eq!(dcel.edges[t12].prev, t20);
-        assert_eq!(dcel.edges[e12].next, e20);
+        assert_eq!(dcel.edges[t01].next, t_split.into());
+        assert_eq!(dcel.edges[e01].prev, e_split.into());
+        assert_eq!(dcel.edges[t12].prev, t20.into());
+        assert_eq!(dcel.edges[e12].next, e20.into());
         assert!(
-            dcel.vertices[v3].out_edge == Some(e_split) || dcel.vertices[v3].out_edge == Some(t20)
+            dcel.vertices[v3].out_edge.get() == Some(e_split)
+                || dcel.vertices[v3].out_edge.get() == Some(t20)
         );
         dcel.sanity_check();
     }
@@ -1419,7 +2224,7 @@ mod test {
 
         let e02 = dcel.edge(e02);
         assert_eq!(e02.cw().fix(), e01);
-        assert_eq!(e02.ccw().fix(), dcel.edges[e30].twin);
+        assert_eq!(e02.ccw().fix(), dcel.edges[e30].twin.get());
     }
 
     #[test]
@@ -1439,9 +2244,9 @@ mod test {
         let e02 = dcel.create_face(e40, e23);
         let e03 = dcel.create_face(e40, e34);
         let entry = dcel.edges[e02];
-        assert_eq!(entry.next, e23);
+        assert_eq!(entry.next, e23.into());
         assert_eq!(entry.prev, dcel.edges[e03].twin);
-        assert_eq!(entry.origin, v[0]);
+        assert_eq!(entry.origin, v[0].into());
     }
 
     #[test]
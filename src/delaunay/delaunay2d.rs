@@ -10,12 +10,14 @@ use crate::kernels::{DelaunayKernel, FloatKernel, TrivialKernel};
 use crate::point_traits::{PointN, PointNExtensions, ThreeDimensional, TwoDimensional};
 use crate::primitives::{SimpleEdge, SimpleTriangle};
 use crate::traits::{HasPosition, HasPosition2D, SpadeFloat, SpatialObject};
+use crate::validation::{validate_point, NonFiniteCoordinateError};
+use alloc::{vec, vec::Vec};
+use core::marker::PhantomData;
 use num::{one, zero, Float, One, Zero};
-use smallvec::{smallvec, SmallVec};
-use std::marker::PhantomData;
+use smallvec::SmallVec;
 
 use self::dcel::*;
-use self::delaunay_basic::{BasicDelaunaySubdivision, HasSubdivision};
+use self::delaunay_basic::{BasicDelaunaySubdivision, HasSubdivision, TransactionStart};
 use self::delaunay_locate::*;
 use crate::delaunay::*;
 
@@ -45,6 +47,85 @@ pub enum PositionInTriangulation<V: Copy, F: Copy, E: Copy> {
     NoTriangulationPresent,
 }
 
+/// Stores a point's position in a triangulation together with its coordinates
+/// relative to the located element.
+///
+/// Used as a return type of `DelaunayTriangulation::locate_with_coordinates(..)`.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum PositionWithCoordinates<V: Copy, F: Copy, E: Copy, S: Copy> {
+    /// The point is contained in a triangle. The barycentric coordinates refer
+    /// to the vertices in the order returned by `FaceHandle::as_triangle`.
+    InTriangle(F, [S; 3]),
+    /// The point is outside the convex hull. The given edge is an edge that
+    /// is close to the queried position. The weights of the edge's `from` and `to`
+    /// vertex describe the point on the edge that is closest to the query point.
+    OutsideConvexHull(E, [S; 2]),
+    /// A vertex with this position has already been inserted. Its handle is given.
+    OnPoint(V),
+    /// The point lies on an edge. The weights of the edge's `from` and `to` vertex
+    /// describe the point's position on the edge.
+    OnEdge(E, [S; 2]),
+    /// There is no valid triangulation yet, thus, less than two points where
+    /// inserted.
+    NoTriangulationPresent,
+}
+
+/// Result type of `locate_with_coordinates`.
+pub(crate) type LocatedCoordinates<'a, V, E> = PositionWithCoordinates<
+    VertexHandle<'a, V, E>,
+    FaceHandle<'a, V, E>,
+    EdgeHandle<'a, V, E>,
+    <<V as HasPosition>::Point as PointN>::Scalar,
+>;
+
+/// Calculates the coordinates of a point relative to its located position.
+pub(crate) fn position_with_coordinates<'a, V, E>(
+    position: PositionInTriangulation<
+        VertexHandle<'a, V, E>,
+        FaceHandle<'a, V, E>,
+        EdgeHandle<'a, V, E>,
+    >,
+    point: &V::Point,
+) -> LocatedCoordinates<'a, V, E>
+where
+    V: HasPosition2D,
+    V::Point: TwoDimensional,
+    <V::Point as PointN>::Scalar: SpadeFloat,
+    E: Default + Copy,
+{
+    let edge_weights = |edge: EdgeHandle<V, E>| {
+        let edge = SimpleEdge::new(edge.from().position(), edge.to().position());
+        let w1 = crate::misc::clamp(zero(), edge.project_point(point), one());
+        [<<V::Point as PointN>::Scalar>::one() - w1, w1]
+    };
+    match position {
+        PositionInTriangulation::InTriangle(face) => {
+            let vs = face.as_triangle();
+            let triangle =
+                SimpleTriangle::new(vs[0].position(), vs[1].position(), vs[2].position());
+            let coords = triangle.barycentric_interpolation(point);
+            PositionWithCoordinates::InTriangle(face, [coords.x, coords.y, coords.z])
+        }
+        PositionInTriangulation::OutsideConvexHull(edge) => {
+            PositionWithCoordinates::OutsideConvexHull(edge, edge_weights(edge))
+        }
+        PositionInTriangulation::OnPoint(vertex) => PositionWithCoordinates::OnPoint(vertex),
+        PositionInTriangulation::OnEdge(edge) => {
+            PositionWithCoordinates::OnEdge(edge, edge_weights(edge))
+        }
+        PositionInTriangulation::NoTriangulationPresent => {
+            PositionWithCoordinates::NoTriangulationPresent
+        }
+    }
+}
+
+/// Adds `values` multiplied by `weight` to `sum`.
+fn add_weighted<S: SpadeFloat, const N: usize>(sum: &mut [S; N], values: [S; N], weight: S) {
+    for (sum, value) in sum.iter_mut().zip(values) {
+        *sum += value * weight;
+    }
+}
+
 /// A two dimensional Delaunay triangulation.
 ///
 /// A Delaunay triangulation is a special triangulation of a set of points that fulfills some
@@ -132,6 +213,9 @@ pub enum PositionInTriangulation<V: Copy, F: Copy, E: Copy> {
 /// using `DelaunayWalkLocate` as locate strategy. As a consequence, subsequent
 /// queries - like insertion, interpolation or nearest neighbor queries - will
 /// run in O(1) if the query locations are close to each other.
+/// If queries are scattered across the triangulation, consider using
+/// `DelaunayHierarchyLocate` instead, which yields O(log(n)) locate time
+/// independent of the query order.
 #[derive(Debug)]
 #[cfg_attr(feature = "serde_serialize", derive(Serialize, Deserialize))]
 pub struct DelaunayTriangulation<V, K, L = DelaunayTreeLocate<<V as HasPosition>::Point>>
@@ -145,6 +229,8 @@ where
     s: DCEL<V>,
     all_points_on_line: bool,
     locate_structure: L,
+    #[cfg_attr(feature = "serde_serialize", serde(skip))]
+    transaction: Option<TransactionStart>,
 }
 
 impl<V, K, L> BasicDelaunaySubdivision<V> for DelaunayTriangulation<V, K, L>
@@ -205,6 +291,7 @@ where
             s: self.s.clone(),
             all_points_on_line: self.all_points_on_line,
             locate_structure: self.locate_structure.clone(),
+            transaction: self.transaction,
         }
     }
 }
@@ -240,6 +327,15 @@ where
     pub fn with_walk_locate() -> DelaunayTriangulation<V, K, DelaunayWalkLocate> {
         DelaunayTriangulation::new()
     }
+
+    /// Shorthand constructor for a Delaunay triangulation that uses the
+    /// `DelaunayHierarchyLocate` strategy for insertion and point location
+    /// queries. This yields O(log(n)) locate time on average, independent
+    /// of the order of queries.
+    pub fn with_hierarchy_locate(
+    ) -> DelaunayTriangulation<V, K, DelaunayHierarchyLocate<V::Point, K>> {
+        DelaunayTriangulation::new()
+    }
 }
 
 impl<V, K, L> DelaunayTriangulation<V, K, L>
@@ -276,9 +372,79 @@ where
             s: DCEL::new(),
             all_points_on_line: true,
             locate_structure: Default::default(),
+            transaction: None,
         }
     }
 
+    /// Removes all vertices from the triangulation.
+    ///
+    /// Already allocated memory is kept to speed up subsequent insertions.
+    ///
+    /// # Handle invalidation
+    /// This method will invalidate all vertex, edge and face handles.
+    pub fn clear(&mut self) {
+        self.s.clear();
+        self.locate_structure.clear();
+        self.all_points_on_line = true;
+    }
+
+    /// Renumbers all vertices, edges and faces along a Hilbert curve.
+    ///
+    /// The curve adapts to the vertex distribution, the resulting order does
+    /// not match the indices of `hilbert::HilbertCurve`.
+    /// Incrementally built triangulations scatter neighboring elements across
+    /// their storage. Compacting places them close to each other, which speeds
+    /// up subsequent queries and traversals. Returns the new handle of every
+    /// vertex, indexed by its old handle.
+    ///
+    /// Panics if a transaction is active.
+    ///
+    /// # Handle invalidation
+    /// This method will invalidate all vertex, edge and face handles.
+    pub fn compact(&mut self) -> Vec<FixedVertexHandle> {
+        BasicDelaunaySubdivision::compact(self)
+    }
+
+    /// Reserves memory for at least `additional` more vertices.
+    ///
+    /// Also reserves space for the edges and faces created by inserting these
+    /// vertices.
+    pub fn reserve(&mut self, additional: usize) {
+        self.s.reserve(additional);
+    }
+
+    /// Releases excess capacity of the vertex, edge and face arrays and of
+    /// the locate structure.
+    ///
+    /// Removing vertices does not free any memory, call this method after
+    /// removing many vertices to return it.
+    pub fn shrink_to_fit(&mut self) {
+        self.s.shrink_to_fit();
+        self.locate_structure.shrink_to_fit();
+    }
+
+    /// Returns the estimated heap memory used by this triangulation.
+    ///
+    /// Heap memory owned by the vertices is not included, use
+    /// `memory_usage_with` to account for it.
+    pub fn memory_usage(&self) -> TriangulationMemoryUsage {
+        self.memory_usage_with(|_| 0)
+    }
+
+    /// Returns the estimated heap memory used by this triangulation.
+    ///
+    /// `payload` returns the number of heap bytes owned by a vertex, e.g.
+    /// the capacity of a contained `Vec`.
+    pub fn memory_usage_with<F>(&self, mut payload: F) -> TriangulationMemoryUsage
+    where
+        F: FnMut(&V) -> usize,
+    {
+        let mut usage = self.s.memory_usage();
+        usage.locate_structure = self.locate_structure.memory_usage();
+        usage.payload = self.s.vertices().map(|vertex| payload(&*vertex)).sum();
+        usage
+    }
+
     /// Creates a dynamic vertex handle from a fixed vertex handle.
     ///
     /// May panic if the handle was invalidated by a previous vertex
@@ -357,6 +523,22 @@ where
         self.s.face(0)
     }
 
+    /// Collects all faces reachable from a seed face.
+    ///
+    /// Starting at `seed`, the region is expanded to a neighboring face
+    /// whenever `can_cross` returns `true` for the edge separating both faces.
+    /// The edge passed to `can_cross` is always adjacent to a face that has
+    /// already been visited. The result contains every face at most once,
+    /// starting with `seed`. Note that the infinite face is visited as well
+    /// if it is reachable.
+    pub fn grow_region<F>(&self, seed: FixedFaceHandle, can_cross: F) -> Vec<FixedFaceHandle>
+    where
+        F: FnMut(EdgeHandle<V>) -> bool,
+    {
+        let mut visited = vec![false; self.num_faces()];
+        regions::flood_fill(&self.s, seed, &mut visited, can_cross, |_| {})
+    }
+
     /// Returns `true` if the triangulation is degenerate
     ///
     /// A triangulation is degenerate if all vertices of the
@@ -386,6 +568,19 @@ where
             return None;
         }
         let start = self.get_default_hint(point);
+        let nearest = self.walk_to_nearest_neighbor(start, point);
+        self.locate_structure.new_query_result(nearest.fix());
+        Some(nearest)
+    }
+
+    /// Walks greedily from `start` to the vertex closest to `point`.
+    ///
+    /// The triangulation must not be empty.
+    pub(crate) fn walk_to_nearest_neighbor(
+        &self,
+        start: FixedVertexHandle,
+        point: &V::Point,
+    ) -> VertexHandle<'_, V> {
         let mut cur = self.vertex(start);
         let mut min_dist = cur.position().distance2(point);
         'outer: loop {
@@ -400,8 +595,74 @@ where
             }
             break;
         }
-        self.locate_structure.new_query_result(cur.fix());
-        Some(cur)
+        cur
+    }
+
+    /// Compares the structure of two triangulations.
+    ///
+    /// Vertices and edges are matched by their coordinates, vertex data and
+    /// handles are ignored. Entries of the other triangulation that are not
+    /// contained in this triangulation are reported as added.
+    /// This operation runs in O(n log(n)) time.
+    pub fn diff<V2, K2, L2>(
+        &self,
+        other: &DelaunayTriangulation<V2, K2, L2>,
+    ) -> TriangulationDiff<V::Point>
+    where
+        V2: HasPosition2D<Point = V::Point>,
+        K2: DelaunayKernel<<V::Point as PointN>::Scalar>,
+        L2: DelaunayLocateStructure<V::Point>,
+    {
+        diff::diff(self.snapshot(), other.snapshot())
+    }
+
+    /// Returns a hash of the triangulation's structure.
+    ///
+    /// The hash only depends on the positions of all vertices and on the
+    /// triangulation's edges. Vertex data and the order of insertion are ignored:
+    /// Triangulating the same point set yields the same hash, as long as the
+    /// triangulation is unique (no four points lie on a common circle).
+    /// The hash is stable across program runs and platforms, which makes it
+    /// suitable as cache key or for detecting nondeterminism.
+    /// Coordinates are hashed exactly, see `HashScalar`.
+    pub fn structural_hash(&self) -> u64
+    where
+        <V::Point as PointN>::Scalar: HashScalar,
+    {
+        self.snapshot().structural_hash()
+    }
+
+    fn snapshot(&self) -> diff::Snapshot<V::Point> {
+        diff::Snapshot::new(
+            self.vertices().map(|v| v.position()),
+            self.edges()
+                .map(|e| (e.from().position(), e.to().position())),
+            None,
+        )
+    }
+
+    /// Returns the nearest other vertex of every vertex.
+    ///
+    /// The result is indexed by fixed vertex handles. Since the nearest
+    /// neighbor of a vertex is always one of its neighbors in the Delaunay
+    /// triangulation, this runs in `O(n)` time. If several vertices are
+    /// equally close, an arbitrary one of them is returned. The entry is
+    /// `None` only if the triangulation contains a single vertex.
+    pub fn nearest_neighbor_graph(&self) -> Vec<Option<FixedVertexHandle>> {
+        self.vertices()
+            .map(|vertex| {
+                let position = vertex.position();
+                let mut nearest = None;
+                for neighbor in vertex.ccw_neighbors() {
+                    let distance = neighbor.position().distance2(&position);
+                    match nearest {
+                        Some((_, ref min_distance)) if *min_distance <= distance => {}
+                        _ => nearest = Some((neighbor.fix(), distance)),
+                    }
+                }
+                nearest.map(|(handle, _)| handle)
+            })
+            .collect()
     }
 
     /// Returns information about the location of a point in a triangulation.
@@ -449,6 +710,16 @@ where
         self.insert_with_hint_option(t, None)
     }
 
+    /// Inserts a new vertex into the triangulation if its position is finite.
+    ///
+    /// Behaves like `insert`, but returns an error if any coordinate of the vertex's
+    /// position is NaN or infinite. Inserting such a vertex with `insert` would leave
+    /// the triangulation in an inconsistent state.
+    pub fn try_insert(&mut self, t: V) -> Result<FixedVertexHandle, NonFiniteCoordinateError> {
+        validate_point(&t.position())?;
+        Ok(self.insert(t))
+    }
+
     /// Inserts a new vertex into the triangulation.
     ///
     /// A hint can be given to speed up the process. The hint should be a handle of a vertex
@@ -485,6 +756,132 @@ where
     pub fn remove(&mut self, vertex: FixedVertexHandle) -> V {
         BasicDelaunaySubdivision::remove(self, vertex)
     }
+
+    /// Creates a triangulation with the same structure but different vertex data.
+    ///
+    /// `f` is called once for every vertex. All handles of this triangulation
+    /// remain valid for the returned triangulation. Since the triangulation is not
+    /// recalculated, the new vertices must have the same positions as the old ones.
+    ///
+    /// # Panics
+    /// Panics if `f` returns a vertex at a different position.
+    pub fn map_data<U, F>(&self, mut f: F) -> DelaunayTriangulation<U, K, L>
+    where
+        U: HasPosition2D<Point = V::Point>,
+        F: FnMut(&V) -> U,
+    {
+        DelaunayTriangulation {
+            __kernel: Default::default(),
+            s: self.s.map_vertices(|v| {
+                let result = f(v);
+                assert!(
+                    result.position() == v.position(),
+                    "map_data must not change vertex positions"
+                );
+                result
+            }),
+            all_points_on_line: self.all_points_on_line,
+            locate_structure: self.locate_structure.clone(),
+            transaction: None,
+        }
+    }
+
+    /// Starts a transaction.
+    ///
+    /// All vertex insertions until the next call to `commit_transaction` or
+    /// `rollback_transaction` are recorded. Rolling back the transaction will
+    /// restore the triangulation's previous state, including any vertex data
+    /// that was overwritten by inserting a vertex at an existing position.
+    /// Changes made through `vertex_mut` are not recorded.
+    ///
+    /// # Panics
+    /// Panics if a transaction is already active. Removing vertices during
+    /// a transaction will panic as well.
+    ///
+    /// # Example
+    /// ```
+    /// use spade::delaunay::FloatDelaunayTriangulation;
+    /// let mut d = FloatDelaunayTriangulation::with_walk_locate();
+    /// d.insert([0.0, 0.0]);
+    /// d.begin_transaction();
+    /// d.insert([1.0, 0.0]);
+    /// d.insert([0.0, 1.0]);
+    /// d.rollback_transaction();
+    /// assert_eq!(d.num_vertices(), 1);
+    /// ```
+    pub fn begin_transaction(&mut self) {
+        assert!(
+            self.transaction.is_none(),
+            "A transaction is already active"
+        );
+        self.transaction = Some(self.start_journal());
+    }
+
+    /// Ends the current transaction and keeps all of its changes.
+    ///
+    /// # Panics
+    /// Panics if no transaction is active.
+    pub fn commit_transaction(&mut self) {
+        assert!(
+            self.transaction.take().is_some(),
+            "No transaction is active"
+        );
+        self.s.commit_journal();
+    }
+
+    /// Ends the current transaction and reverts all of its changes.
+    ///
+    /// # Panics
+    /// Panics if no transaction is active.
+    pub fn rollback_transaction(&mut self) {
+        let start = self.transaction.take().expect("No transaction is active");
+        self.rollback_journal(start);
+    }
+
+    /// Returns `true` if a transaction has been started and not yet been
+    /// committed or rolled back.
+    pub fn is_in_transaction(&self) -> bool {
+        self.transaction.is_some()
+    }
+
+    /// Removes all vertices for which `f` returns `false`.
+    ///
+    /// If a large part of the triangulation is removed, the remaining
+    /// vertices are triangulated again from scratch. Otherwise, all faces
+    /// adjacent to removed vertices are replaced at once instead of
+    /// repeatedly filling the holes left by removing the vertices one by one.
+    ///
+    /// # Handle invalidation
+    /// This method will invalidate all vertex, edge and face handles
+    /// if any vertex is removed.
+    pub fn retain_vertices<F>(&mut self, mut f: F)
+    where
+        F: FnMut(VertexHandle<V>) -> bool,
+    {
+        let retain: Vec<_> = self.s.vertices().map(&mut f).collect();
+        let num_retained = retain.iter().filter(|r| **r).count();
+        let num_removed = retain.len() - num_retained;
+        if num_removed == 0 {
+            return;
+        }
+        assert!(
+            self.transaction.is_none(),
+            "Vertices cannot be removed during a transaction"
+        );
+        if num_removed >= num_retained
+            || self.all_points_on_line()
+            || self.retained_points_on_line(&retain)
+        {
+            let old = ::core::mem::take(self);
+            for (vertex, retain) in old.s.into_vertices().into_iter().zip(retain) {
+                if retain {
+                    self.insert(vertex);
+                }
+            }
+        } else {
+            self.remove_vertices(&retain);
+        }
+    }
 }
 
 impl<V, K> DelaunayTriangulation<V, K, DelaunayTreeLocate<V::Point>>
@@ -525,6 +922,210 @@ where
     L: DelaunayLocateStructure<V::Point>,
     V::Point: TwoDimensional,
 {
+    /// Returns an iterator over all undirected edges and their lengths.
+    pub fn edge_lengths(&self) -> EdgeLengthIterator<'_, V> {
+        EdgeLengthIterator::new(self.edges())
+    }
+
+    /// Returns an iterator over all triangles and their interior angles in radians.
+    ///
+    /// The angles are ordered like the vertices returned by `FaceHandle::as_triangle`.
+    pub fn triangle_angles(&self) -> TriangleAngleIterator<'_, V> {
+        TriangleAngleIterator::new(self.triangles())
+    }
+
+    /// Returns the minimum, maximum and mean edge length.
+    ///
+    /// Returns `None` if the triangulation has no edges.
+    pub fn edge_length_statistics(&self) -> Option<Statistics<<V::Point as PointN>::Scalar>> {
+        Statistics::from_values(self.edge_lengths().map(|(_, length)| length))
+    }
+
+    /// Returns the minimum, maximum and mean interior angle of all triangles.
+    ///
+    /// Returns `None` if the triangulation has no triangles.
+    pub fn angle_statistics(&self) -> Option<Statistics<<V::Point as PointN>::Scalar>> {
+        Statistics::from_values(
+            self.triangle_angles()
+                .flat_map(|(_, angles)| angles.to_vec()),
+        )
+    }
+
+    /// Returns an iterator over all triangles and their slope, aspect and normal.
+    ///
+    /// The terrain surface is defined by the vertex heights returned by `height`.
+    pub fn face_slopes<F>(&self, height: F) -> FaceSlopeIterator<'_, V, F>
+    where
+        F: Fn(&V) -> <V::Point as PointN>::Scalar,
+    {
+        FaceSlopeIterator::new(self.triangles(), height)
+    }
+
+    /// Checks if two points can see each other across a terrain surface.
+    ///
+    /// The terrain surface is defined by the vertex heights returned by `height`,
+    /// `from_elevation` and `to_elevation` are the absolute heights of both points.
+    /// The sightline is walked across the triangulation and compared against the
+    /// surface at every crossed edge and vertex. Touching the surface does not
+    /// obstruct the view. Parts of the sightline outside of the convex hull
+    /// are never obstructed.
+    pub fn is_visible<F>(
+        &self,
+        height: F,
+        from: &V::Point,
+        from_elevation: <V::Point as PointN>::Scalar,
+        to: &V::Point,
+        to_elevation: <V::Point as PointN>::Scalar,
+    ) -> bool
+    where
+        F: Fn(&V) -> <V::Point as PointN>::Scalar,
+    {
+        terrain::is_visible(self, &height, from, from_elevation, to, to_elevation)
+    }
+
+    /// Determines which triangles are visible from an observer.
+    ///
+    /// The terrain surface is defined by the vertex heights returned by `height`.
+    /// `observer_elevation` is the absolute height of the observer, to place an
+    /// observer above the ground, add the surface height at its position.
+    /// A triangle is considered visible if its centroid can be seen by the observer.
+    ///
+    /// Returns a vector indexed by fixed face handles. The entry of the infinite face
+    /// is always `false`.
+    pub fn viewshed<F>(
+        &self,
+        height: F,
+        observer: &V::Point,
+        observer_elevation: <V::Point as PointN>::Scalar,
+    ) -> Vec<bool>
+    where
+        F: Fn(&V) -> <V::Point as PointN>::Scalar,
+    {
+        terrain::viewshed(self, height, observer, observer_elevation)
+    }
+
+    /// Rasterizes the triangulation's surface into a grid of heights.
+    ///
+    /// Each triangle is scan converted, every cell whose center lies within the
+    /// triangle receives the height of the triangle's plane through the vertex
+    /// heights returned by `height`. This yields the same values as
+    /// `barycentric_interpolation` but is much faster than interpolating each
+    /// cell separately. Cells outside of the convex hull are `None`.
+    ///
+    /// The result is stored row by row, see `RasterGrid`.
+    pub fn rasterize_heights<F>(
+        &self,
+        grid: &RasterGrid<V::Point>,
+        height: F,
+    ) -> Vec<Option<<V::Point as PointN>::Scalar>>
+    where
+        F: Fn(&V) -> <V::Point as PointN>::Scalar,
+    {
+        terrain::rasterize(self.triangles(), grid, height)
+    }
+
+    /// Calculates the volume between this triangulation's surface and a horizontal plane.
+    ///
+    /// The surface is defined by linearly interpolating the vertex heights
+    /// returned by `height` within each triangle. Only the area covered by
+    /// the triangulation is considered.
+    pub fn volume_to_plane<F>(
+        &self,
+        height: F,
+        plane_height: <V::Point as PointN>::Scalar,
+    ) -> CutFillVolume<<V::Point as PointN>::Scalar>
+    where
+        F: Fn(&V) -> <V::Point as PointN>::Scalar,
+    {
+        terrain::volume_to_plane(self.triangles(), height, plane_height)
+    }
+
+    /// Calculates the volume between this triangulation's surface and another surface.
+    ///
+    /// Both surfaces are defined by linearly interpolating vertex heights within
+    /// each triangle. The other surface is given by its triangles, e.g. by calling
+    /// `triangles()` on another (constrained) Delaunay triangulation.
+    /// The volume is only calculated where both surfaces overlap. Cut volume refers
+    /// to areas where this surface lies above the other surface.
+    ///
+    /// # Example
+    /// ```
+    /// # extern crate cgmath;
+    /// # extern crate spade;
+    /// use spade::delaunay::FloatDelaunayTriangulation;
+    /// use cgmath::Point3;
+    /// use spade::HasPosition;
+    ///
+    /// struct Sample(Point3<f64>);
+    ///
+    /// impl HasPosition for Sample {
+    ///     type Point = cgmath::Point2<f64>;
+    ///     fn position(&self) -> cgmath::Point2<f64> {
+    ///         cgmath::Point2::new(self.0.x, self.0.y)
+    ///     }
+    /// }
+    ///
+    /// # fn main() {
+    /// let mut ground = FloatDelaunayTriangulation::with_walk_locate();
+    /// let mut design = FloatDelaunayTriangulation::with_walk_locate();
+    /// for &(x, y) in &[(0.0, 0.0), (10.0, 0.0), (0.0, 10.0), (10.0, 10.0)] {
+    ///     ground.insert(Sample(Point3::new(x, y, 2.0 + x * 0.1)));
+    ///     design.insert(Sample(Point3::new(x, y, 2.0)));
+    /// }
+    /// let volume = ground.volume_to_surface(|v| v.0.z, design.triangles(), |v| v.0.z);
+    /// assert!((volume.cut - 50.0).abs() < 1e-10);
+    /// assert_eq!(volume.fill, 0.0);
+    /// # }
+    /// ```
+    pub fn volume_to_surface<'b, F, W, EW, G, I>(
+        &self,
+        height: F,
+        other: I,
+        other_height: G,
+    ) -> CutFillVolume<<V::Point as PointN>::Scalar>
+    where
+        F: Fn(&V) -> <V::Point as PointN>::Scalar,
+        W: HasPosition2D<Point = V::Point> + 'b,
+        EW: Default + Copy + 'b,
+        G: Fn(&W) -> <V::Point as PointN>::Scalar,
+        I: IntoIterator<Item = FaceHandle<'b, W, EW>>,
+    {
+        terrain::volume_to_surface(self.triangles(), height, other, other_height)
+    }
+
+    /// Locates a point and calculates its coordinates relative to the located element.
+    ///
+    /// Inside a triangle, the barycentric coordinates of the point are returned.
+    /// On an edge or outside of the convex hull, the weights of the edge's endpoints
+    /// are returned. The coordinates can be used for interpolation without
+    /// recalculating them.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # extern crate nalgebra;
+    /// # extern crate spade;
+    /// use nalgebra::Point2;
+    /// use spade::delaunay::{FloatDelaunayTriangulation, PositionWithCoordinates};
+    ///
+    /// # fn main() {
+    /// let mut delaunay = FloatDelaunayTriangulation::with_walk_locate();
+    /// delaunay.insert(Point2::new(0.0, 0.0));
+    /// delaunay.insert(Point2::new(1.0, 0.0));
+    /// delaunay.insert(Point2::new(0.0, 1.0));
+    /// match delaunay.locate_with_coordinates(&Point2::new(0.25, 0.25)) {
+    ///     PositionWithCoordinates::InTriangle(_, coords) => {
+    ///         let sum: f64 = coords.iter().sum();
+    ///         assert!((sum - 1.0).abs() < 1e-10);
+    ///     }
+    ///     _ => panic!("Point should lie inside a triangle"),
+    /// }
+    /// # }
+    /// ```
+    pub fn locate_with_coordinates(&self, point: &V::Point) -> LocatedCoordinates<'_, V, ()> {
+        position_with_coordinates(self.locate(point), point)
+    }
+
     /// Performs a barycentric interpolation.
     /// Returns `None` if the triangulation has no triangles yet.
     /// Points outside of the convex hull will be interpolated as well.
@@ -538,38 +1139,59 @@ where
     where
         F: Fn(&V) -> <V::Point as PointN>::Scalar,
     {
-        let vertices: SmallVec<[_; 3]> = match self.locate(point) {
-            PositionInTriangulation::NoTriangulationPresent => return None,
-            PositionInTriangulation::OnPoint(v) => smallvec![v],
-            PositionInTriangulation::OnEdge(e) => smallvec![e.from(), e.to()],
-            PositionInTriangulation::InTriangle(f) => {
-                let vs = f.as_triangle();
-                smallvec![vs[0], vs[1], vs[2]]
+        self.barycentric_interpolation_multi(point, |v| [f(v)])
+            .map(|[value]| value)
+    }
+
+    /// Performs a barycentric interpolation of several values at once.
+    ///
+    /// Works like `barycentric_interpolation` but interpolates each entry of the
+    /// arrays returned by `f` separately. This allows to interpolate multi-channel
+    /// data, like colors or velocities, with a single lookup.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # extern crate nalgebra;
+    /// # extern crate spade;
+    /// use nalgebra::Point2;
+    /// use spade::delaunay::FloatDelaunayTriangulation;
+    ///
+    /// # fn main() {
+    /// let mut delaunay = FloatDelaunayTriangulation::with_walk_locate();
+    /// delaunay.insert(Point2::new(0.0, 0.0));
+    /// delaunay.insert(Point2::new(2.0, 0.0));
+    /// delaunay.insert(Point2::new(0.0, 2.0));
+    /// // Interpolating the position itself must yield the query point
+    /// let result = delaunay.barycentric_interpolation_multi(&Point2::new(0.5, 1.0), |p| [p.x, p.y]);
+    /// let [x, y]: [f64; 2] = result.unwrap();
+    /// assert!((x - 0.5).abs() < 1e-10 && (y - 1.0).abs() < 1e-10);
+    /// # }
+    /// ```
+    pub fn barycentric_interpolation_multi<F, const N: usize>(
+        &self,
+        point: &V::Point,
+        f: F,
+    ) -> Option<[<V::Point as PointN>::Scalar; N]>
+    where
+        F: Fn(&V) -> [<V::Point as PointN>::Scalar; N],
+    {
+        let mut result = [zero(); N];
+        match self.locate_with_coordinates(point) {
+            PositionWithCoordinates::NoTriangulationPresent => return None,
+            PositionWithCoordinates::OnPoint(v) => return Some(f(&*v)),
+            PositionWithCoordinates::OnEdge(e, [w0, w1])
+            | PositionWithCoordinates::OutsideConvexHull(e, [w0, w1]) => {
+                add_weighted(&mut result, f(&*e.from()), w0);
+                add_weighted(&mut result, f(&*e.to()), w1);
+            }
+            PositionWithCoordinates::InTriangle(face, weights) => {
+                for (v, w) in face.as_triangle().iter().zip(weights.iter()) {
+                    add_weighted(&mut result, f(&**v), *w);
+                }
             }
-            PositionInTriangulation::OutsideConvexHull(e) => smallvec![e.from(), e.to()],
-        };
-        if vertices.len() == 1 {
-            Some(f(&*vertices[0]))
-        } else if vertices.len() == 2 {
-            let p0 = vertices[0].position();
-            let p1 = vertices[1].position();
-            let one = <<V::Point as PointN>::Scalar>::one();
-            let edge = SimpleEdge::new(p0, p1);
-            let w1 = ::clamp::clamp(zero(), edge.project_point(point), one);
-            let w0 = one - w1;
-            Some(w1 * f(&*vertices[1]) + w0 * f(&*vertices[0]))
-        } else {
-            let triangle = crate::primitives::SimpleTriangle::new(
-                vertices[0].position(),
-                vertices[1].position(),
-                vertices[2].position(),
-            );
-            let b_coords = triangle.barycentric_interpolation(point);
-            let w0 = f(&*vertices[0]);
-            let w1 = f(&*vertices[1]);
-            let w2 = f(&*vertices[2]);
-            Some(w0 * b_coords[0] + w1 * b_coords[1] + w2 * b_coords[2])
         }
+        Some(result)
     }
 
     /// Performs a natural neighbor interpolation for a given position.
@@ -620,19 +1242,70 @@ where
     ) -> Option<<V::Point as PointN>::Scalar>
     where
         F: Fn(&V) -> <V::Point as PointN>::Scalar,
+    {
+        self.nn_interpolation_multi(point, |v| [f(v)])
+            .map(|[value]| value)
+    }
+
+    /// Performs a natural neighbor interpolation of several values at once.
+    ///
+    /// Works like `nn_interpolation` but interpolates each entry of the arrays
+    /// returned by `f` separately. The natural neighbors and their weights are
+    /// only calculated once for all entries.
+    pub fn nn_interpolation_multi<F, const N: usize>(
+        &self,
+        point: &V::Point,
+        f: F,
+    ) -> Option<[<V::Point as PointN>::Scalar; N]>
+    where
+        F: Fn(&V) -> [<V::Point as PointN>::Scalar; N],
     {
         let nns = self.get_natural_neighbors(point);
-        let ws = self.get_weights(&nns, point);
+        self.interpolate_natural_neighbors(&nns, point, f)
+    }
 
-        let mut sum = None;
-        for (index, fixed_handle) in nns.iter().enumerate() {
-            let new = f(&*self.s.vertex(*fixed_handle)) * ws[index];
-            sum = match sum {
-                Some(val) => Some(val + new),
-                None => Some(new),
+    /// Performs a natural neighbor interpolation, returns `None` outside of the convex hull.
+    ///
+    /// Used by `interpolate_grid`, which would otherwise need a second
+    /// `locate` to exclude those points.
+    #[cfg(feature = "rayon")]
+    pub(crate) fn nn_interpolation_inside_hull<F>(
+        &self,
+        point: &V::Point,
+        f: F,
+    ) -> Option<<V::Point as PointN>::Scalar>
+    where
+        F: Fn(&V) -> <V::Point as PointN>::Scalar,
+    {
+        match self.locate_with_hint_option_fixed(point, None) {
+            PositionInTriangulation::OutsideConvexHull(_)
+            | PositionInTriangulation::NoTriangulationPresent => None,
+            location => {
+                let nns = self.natural_neighbors_at(point, location);
+                self.interpolate_natural_neighbors(&nns, point, |v| [f(v)])
+                    .map(|[value]| value)
             }
         }
-        sum
+    }
+
+    fn interpolate_natural_neighbors<F, const N: usize>(
+        &self,
+        nns: &SmallVec<[FixedVertexHandle; INTPL_SMALLVEC_CAPACITY]>,
+        point: &V::Point,
+        f: F,
+    ) -> Option<[<V::Point as PointN>::Scalar; N]>
+    where
+        F: Fn(&V) -> [<V::Point as PointN>::Scalar; N],
+    {
+        if nns.is_empty() {
+            return None;
+        }
+        let ws = self.get_weights(nns, point);
+        let mut result = [zero(); N];
+        for (fixed_handle, weight) in nns.iter().zip(ws) {
+            add_weighted(&mut result, f(&*self.s.vertex(*fixed_handle)), weight);
+        }
+        Some(result)
     }
 
     fn get_weights(
@@ -651,7 +1324,7 @@ where
             let p1 = (*self.s.vertex(nns[1])).position();
             let one = <<V::Point as PointN>::Scalar>::one();
             let edge = SimpleEdge::new(p0, p1);
-            let w1 = ::clamp::clamp(zero(), edge.project_point(point), one);
+            let w1 = crate::misc::clamp(zero(), edge.project_point(point), one);
             let w0 = one - w1;
             result.push(w0);
             result.push(w1);
@@ -713,7 +1386,17 @@ where
         &self,
         position: &V::Point,
     ) -> SmallVec<[FixedVertexHandle; INTPL_SMALLVEC_CAPACITY]> {
-        match self.locate_with_hint_option_fixed(position, None) {
+        let location = self.locate_with_hint_option_fixed(position, None);
+        self.natural_neighbors_at(position, location)
+    }
+
+    /// Returns the natural neighbors of a position that has already been located.
+    fn natural_neighbors_at(
+        &self,
+        position: &V::Point,
+        location: PositionInTriangulation<FixedVertexHandle, FixedFaceHandle, FixedEdgeHandle>,
+    ) -> SmallVec<[FixedVertexHandle; INTPL_SMALLVEC_CAPACITY]> {
+        match location {
             PositionInTriangulation::InTriangle(face) => {
                 let mut edges: SmallVec<_> = self
                     .face(face)
@@ -865,7 +1548,7 @@ where
     where
         F: Fn(&V) -> <V::Point as PointN>::Scalar,
     {
-        use cgmath::Point3;
+        use crate::point::Point3;
         let normal = self.estimate_normal::<_, Point3<_>>(v, f);
         // Calculate gradient from normal
         let mut gradient = V::Point::new();
@@ -1177,7 +1860,10 @@ where
 #[cfg(test)]
 mod test {
     use super::delaunay_basic::BasicDelaunaySubdivision;
-    use super::{FloatDelaunayTriangulation, IntDelaunayTriangulation};
+    use super::{
+        DelaunayTriangulation, FloatDelaunayTriangulation, IntDelaunayTriangulation,
+        PositionWithCoordinates,
+    };
     use crate::testutils::*;
     use crate::traits::{HasPosition, SpatialObject};
     use cgmath::Point2;
@@ -1200,6 +1886,389 @@ mod test {
         d.sanity_check();
     }
 
+    #[test]
+    fn test_try_insert() {
+        let mut d = FloatDelaunayTriangulation::with_walk_locate();
+        assert!(d.try_insert(Point2::new(0.0, 0.0)).is_ok());
+        assert!(d.try_insert(Point2::new(1.0, f64::NAN)).is_err());
+        assert!(d.try_insert(Point2::new(f64::INFINITY, 1.0)).is_err());
+        assert_eq!(d.num_vertices(), 1);
+        d.sanity_check();
+    }
+
+    #[test]
+    fn test_retain_vertices() {
+        for &threshold in &[-0.8, 0.0, 0.8] {
+            let points = random_points_with_seed::<f64>(500, SEED);
+            let mut d = FloatDelaunayTriangulation::with_tree_locate();
+            for p in &points {
+                d.insert(*p);
+            }
+            d.retain_vertices(|v| v.x > threshold);
+            let expected = points.iter().filter(|p| p.x > threshold).count();
+            assert_eq!(d.num_vertices(), expected);
+            assert!(d.vertices().all(|v| v.x > threshold));
+            for p in points.iter().filter(|p| p.x > threshold) {
+                assert!(d.locate_vertex(p).is_some());
+            }
+            d.sanity_check();
+            d.legality_check();
+        }
+    }
+
+    #[test]
+    fn test_retain_few_vertices() {
+        let points = random_points_with_seed::<f64>(500, SEED);
+        let predicates: [fn(usize, &Point2<f64>) -> bool; 3] = [
+            |index, _| index % 7 != 3,
+            |_, p| !(0.09..0.25).contains(&(p.x * p.x + p.y * p.y)),
+            |_, p| p.x.abs() < 0.9 && p.y.abs() < 0.9,
+        ];
+        for predicate in &predicates {
+            let mut d = FloatDelaunayTriangulation::with_tree_locate();
+            for p in &points {
+                d.insert(*p);
+            }
+            let retained: Vec<_> = points
+                .iter()
+                .enumerate()
+                .filter(|(index, p)| predicate(*index, p))
+                .map(|(_, p)| *p)
+                .collect();
+            assert!(retained.len() * 2 > points.len());
+            d.retain_vertices(|v| retained.contains(&v));
+            assert_eq!(d.num_vertices(), retained.len());
+            for p in &retained {
+                assert!(d.locate_vertex(p).is_some());
+            }
+            d.sanity_check();
+            d.legality_check();
+            let mut expected = FloatDelaunayTriangulation::with_tree_locate();
+            for p in &retained {
+                expected.insert(*p);
+            }
+            assert_eq!(d.num_edges(), expected.num_edges());
+            assert_eq!(d.num_triangles(), expected.num_triangles());
+        }
+    }
+
+    #[test]
+    fn test_retain_few_vertices_on_grid() {
+        let mut d = IntDelaunayTriangulation::with_tree_locate();
+        for x in 0..20 {
+            for y in 0..20 {
+                d.insert(Point2::new(x, y));
+            }
+        }
+        d.retain_vertices(|v| (v.x + 2 * v.y) % 5 != 0);
+        assert_eq!(d.num_vertices(), 320);
+        assert!(d.vertices().all(|v| (v.x + 2 * v.y) % 5 != 0));
+        d.sanity_check();
+        d.legality_check();
+    }
+
+    #[test]
+    fn test_map_data() {
+        let points = random_points_with_seed::<f64>(100, SEED);
+        let mut d = FloatDelaunayTriangulation::with_tree_locate();
+        for p in &points {
+            d.insert(*p);
+        }
+        let mapped = d.map_data(|p| PointWithHeight::new(p.x, p.y, p.x + p.y));
+        assert_eq!(mapped.num_vertices(), d.num_vertices());
+        assert_eq!(mapped.num_edges(), d.num_edges());
+        for v in d.vertices() {
+            let m = mapped.vertex(v.fix());
+            assert_eq!(m.point, *v);
+            assert_eq!(m.height, v.x + v.y);
+        }
+        for p in &points {
+            assert!(mapped.locate_vertex(p).is_some());
+        }
+        mapped.sanity_check();
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_map_data_moving_vertices() {
+        let mut d = FloatDelaunayTriangulation::with_walk_locate();
+        d.insert(Point2::new(0.0, 0.0));
+        d.map_data(|p| Point2::new(p.x + 1.0, p.y));
+    }
+
+    #[test]
+    fn test_clear_and_reserve() {
+        let points = random_points_with_seed::<f64>(200, SEED);
+        let mut d = FloatDelaunayTriangulation::with_tree_locate();
+        d.reserve(points.len());
+        for _ in 0..2 {
+            for p in &points {
+                d.insert(*p);
+            }
+            assert_eq!(d.num_vertices(), points.len());
+            d.sanity_check();
+            d.clear();
+            assert_eq!(d.num_vertices(), 0);
+            assert_eq!(d.num_faces(), 1);
+            assert!(d.locate_vertex(&points[0]).is_none());
+        }
+    }
+
+    #[test]
+    fn test_shrink_to_fit() {
+        let points = random_points_with_seed::<f64>(1000, SEED);
+        let mut d = FloatDelaunayTriangulation::with_tree_locate();
+        for p in &points {
+            d.insert(*p);
+        }
+        for p in &points[100..] {
+            d.locate_and_remove(p);
+        }
+        let before = d.memory_usage();
+        d.shrink_to_fit();
+        let after = d.memory_usage();
+        assert!(after.vertices * 5 < before.vertices);
+        assert!(after.edges * 5 < before.edges);
+        assert!(after.faces * 5 < before.faces);
+        assert!(after.locate_structure <= before.locate_structure);
+        d.sanity_check();
+        for p in &points[..100] {
+            assert!(d.locate_vertex(p).is_some());
+        }
+    }
+
+    #[test]
+    fn test_compact() {
+        let points = random_points_with_seed::<f64>(500, SEED);
+        let mut d = FloatDelaunayTriangulation::with_tree_locate();
+        for p in &points {
+            d.insert(*p);
+        }
+        let num_edges = d.num_edges();
+        let num_faces = d.num_faces();
+        let new_handles = d.compact();
+        d.sanity_check();
+        assert_eq!(d.num_edges(), num_edges);
+        assert_eq!(d.num_faces(), num_faces);
+        assert!(d.infinite_face().adjacent_edge().is_some());
+        for (old, p) in points.iter().enumerate() {
+            assert_eq!(*d.vertex(new_handles[old]), *p);
+            assert_eq!(d.locate_vertex(p).map(|v| v.fix()), Some(new_handles[old]));
+        }
+        // Consecutive vertices are close to each other
+        let path_length = |points: &[Point2<f64>]| -> f64 {
+            points
+                .windows(2)
+                .map(|pair| pair[0].distance2(&pair[1]).sqrt())
+                .sum()
+        };
+        let compacted: Vec<_> = d.vertices().map(|v| *v).collect();
+        assert!(path_length(&compacted) * 5.0 < path_length(&points));
+        d.insert(Point2::new(0.1, 0.2));
+        d.sanity_check();
+    }
+
+    #[test]
+    fn test_rollback_transaction() {
+        let points = random_points_with_seed::<f64>(300, SEED);
+        let mut d = FloatDelaunayTriangulation::with_tree_locate();
+        for p in &points[..100] {
+            d.insert(*p);
+        }
+        let reference = d.clone();
+        d.begin_transaction();
+        assert!(d.is_in_transaction());
+        for p in &points[100..] {
+            d.insert(*p);
+        }
+        d.insert(points[0]);
+        d.rollback_transaction();
+        assert!(!d.is_in_transaction());
+        assert_eq!(d.s, reference.s);
+        for p in &points[100..] {
+            assert!(d.locate_vertex(p).is_none());
+        }
+        d.sanity_check();
+        d.insert(points[150]);
+        d.sanity_check();
+    }
+
+    #[test]
+    fn test_rollback_initial_insertion() {
+        let mut d = FloatDelaunayTriangulation::with_walk_locate();
+        d.insert(Point2::new(0.0, 0.0));
+        d.insert(Point2::new(1.0, 0.0));
+        d.begin_transaction();
+        d.insert(Point2::new(2.0, 0.0));
+        d.insert(Point2::new(0.0, 1.0));
+        assert!(!d.is_degenerate());
+        d.rollback_transaction();
+        assert!(d.is_degenerate());
+        assert_eq!(d.num_vertices(), 2);
+        assert_eq!(d.num_edges(), 1);
+        d.sanity_check();
+    }
+
+    #[test]
+    fn test_commit_transaction() {
+        let mut d = FloatDelaunayTriangulation::with_walk_locate();
+        d.begin_transaction();
+        d.insert(Point2::new(0.0, 0.0));
+        d.insert(Point2::new(1.0, 0.0));
+        d.insert(Point2::new(0.0, 1.0));
+        d.commit_transaction();
+        assert_eq!(d.num_vertices(), 3);
+        d.remove(0);
+        d.sanity_check();
+    }
+
+    #[test]
+    fn test_vertex_circulators() {
+        let mut d = FloatDelaunayTriangulation::with_walk_locate();
+        let center = d.insert(Point2::new(0.0, 0.0));
+        for i in 0..6 {
+            let angle = ::std::f64::consts::PI / 3.0 * f64::from(i);
+            d.insert(Point2::new(angle.cos(), angle.sin()));
+        }
+        let vertex = d.vertex(center);
+        let out_edge = vertex.out_edge().unwrap();
+
+        let ccw: Vec<_> = vertex.ccw_neighbors().map(|v| v.fix()).collect();
+        assert_eq!(ccw.len(), 6);
+        assert_eq!(*ccw.last().unwrap(), out_edge.to().fix());
+        for i in 0..6 {
+            // Neighbors are inserted in counterclockwise order with handles 1..=6
+            assert_eq!(ccw[(i + 1) % 6], ccw[i] % 6 + 1);
+        }
+        let mut cw: Vec<_> = vertex.cw_neighbors().map(|v| v.fix()).collect();
+        assert_eq!(cw[0], out_edge.to().fix());
+        cw.reverse();
+        assert_eq!(cw, ccw);
+
+        let cw_edges: Vec<_> = vertex.cw_out_edges().collect();
+        assert_eq!(cw_edges[0], out_edge);
+        let start = cw_edges[2];
+        let from_start: Vec<_> = start.cw_iter().collect();
+        assert_eq!(from_start[0], start);
+        assert_eq!(from_start[1], start.cw());
+        assert_eq!(from_start.len(), 6);
+
+        let faces: Vec<_> = vertex.ccw_adjacent_faces().collect();
+        assert_eq!(faces.len(), 6);
+        assert!(faces.iter().all(|f| f.fix() != d.infinite_face().fix()));
+        let cw_faces: Vec<_> = vertex.cw_adjacent_faces().collect();
+        assert_eq!(cw_faces[0], out_edge.face());
+    }
+
+    #[test]
+    fn test_locate_with_coordinates() {
+        let mut d = FloatDelaunayTriangulation::with_walk_locate();
+        assert_eq!(
+            d.locate_with_coordinates(&Point2::new(0.0, 0.0)),
+            PositionWithCoordinates::NoTriangulationPresent
+        );
+        let v0 = d.insert(Point2::new(0.0, 0.0));
+        d.insert(Point2::new(2.0, 0.0));
+        d.insert(Point2::new(0.0, 2.0));
+        let query = Point2::new(0.5, 0.5);
+        match d.locate_with_coordinates(&query) {
+            PositionWithCoordinates::InTriangle(face, coords) => {
+                let vs = face.as_triangle();
+                let mut interpolated = Point2::new(0.0, 0.0);
+                for (v, w) in vs.iter().zip(coords.iter()) {
+                    interpolated.x += v.position().x * w;
+                    interpolated.y += v.position().y * w;
+                }
+                assert_relative_eq!(interpolated.x, query.x, epsilon = 1e-10);
+                assert_relative_eq!(interpolated.y, query.y, epsilon = 1e-10);
+            }
+            other => panic!("Unexpected position: {:?}", other),
+        }
+        match d.locate_with_coordinates(&Point2::new(1.5, 0.0)) {
+            PositionWithCoordinates::OnEdge(edge, [w0, w1]) => {
+                let expected = if edge.from().fix() == v0 { 0.25 } else { 0.75 };
+                assert_relative_eq!(w0, expected);
+                assert_relative_eq!(w0 + w1, 1.0);
+            }
+            other => panic!("Unexpected position: {:?}", other),
+        }
+        match d.locate_with_coordinates(&Point2::new(0.0, 0.0)) {
+            PositionWithCoordinates::OnPoint(v) => assert_eq!(v.fix(), v0),
+            other => panic!("Unexpected position: {:?}", other),
+        }
+        match d.locate_with_coordinates(&Point2::new(-1.0, -1.0)) {
+            PositionWithCoordinates::OutsideConvexHull(_, [w0, w1]) => {
+                assert!(w0 >= 0.0 && w1 >= 0.0);
+                assert_relative_eq!(w0 + w1, 1.0);
+            }
+            other => panic!("Unexpected position: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_grow_region() {
+        let mut d = FloatDelaunayTriangulation::with_walk_locate();
+        for x in 0..5 {
+            for y in 0..5 {
+                d.insert(Point2::new(f64::from(x), f64::from(y)));
+            }
+        }
+        let seed = d.triangles().next().unwrap().fix();
+        let infinite = d.infinite_face().fix();
+        let all = d.grow_region(seed, |edge| edge.sym().face().fix() != infinite);
+        assert_eq!(all.len(), d.num_triangles());
+        assert_eq!(all[0], seed);
+        let mut sorted = all.clone();
+        sorted.sort_unstable();
+        sorted.dedup();
+        assert_eq!(sorted.len(), all.len());
+
+        assert_eq!(d.grow_region(seed, |_| false), vec![seed]);
+        let with_infinite = d.grow_region(seed, |_| true);
+        assert_eq!(with_infinite.len(), d.num_faces());
+
+        // Only expand into triangles to the left of x = 2
+        let left = d.grow_region(seed, |edge| {
+            let face = edge.sym().face();
+            if face.fix() == infinite {
+                return false;
+            }
+            let [v0, v1, v2] = face.as_triangle();
+            v0.position().x + v1.position().x + v2.position().x < 6.0
+        });
+        assert!(!left.is_empty());
+        assert!(left.len() < d.num_triangles());
+    }
+
+    #[test]
+    fn test_nearest_neighbor_graph() {
+        let mut d = FloatDelaunayTriangulation::with_walk_locate();
+        assert!(d.nearest_neighbor_graph().is_empty());
+        d.insert(Point2::new(0.0, 0.0));
+        assert_eq!(d.nearest_neighbor_graph(), vec![None]);
+        d.insert(Point2::new(2.0, 0.0));
+        d.insert(Point2::new(5.0, 0.0));
+        // Degenerate triangulation
+        assert_eq!(d.nearest_neighbor_graph(), vec![Some(1), Some(0), Some(1)]);
+
+        let mut d = FloatDelaunayTriangulation::with_walk_locate();
+        let points = random_points_with_seed::<f64>(500, SEED);
+        for p in &points {
+            d.insert(*p);
+        }
+        let graph = d.nearest_neighbor_graph();
+        assert_eq!(graph.len(), d.num_vertices());
+        for (index, nearest) in graph.iter().enumerate() {
+            let position = d.vertex(index).position();
+            let expected = (0..d.num_vertices())
+                .filter(|other| *other != index)
+                .map(|other| d.vertex(other).position().distance2(&position))
+                .fold(f64::INFINITY, f64::min);
+            let nearest = d.vertex(nearest.unwrap()).position();
+            assert_eq!(nearest.distance2(&position), expected);
+        }
+    }
+
     #[test]
     fn test_insert_three_points() {
         let mut d = FloatDelaunayTriangulation::with_tree_locate();
@@ -1303,6 +2372,112 @@ mod test {
         d.sanity_check();
     }
 
+    #[test]
+    fn test_legalization_tests_circles_once() {
+        use crate::kernels::{DelaunayKernel, FloatKernel};
+        use crate::point_traits::TwoDimensional;
+        use crate::primitives::{EdgeSideInfo, SimpleEdge};
+        use core::cell::RefCell;
+
+        std::thread_local! {
+            static IN_CIRCLE_TESTS: RefCell<Vec<[f64; 8]>> = const { RefCell::new(Vec::new()) };
+        }
+
+        /// Records the circles and points of all in-circle tests.
+        #[derive(Clone)]
+        struct RecordingKernel;
+
+        impl DelaunayKernel<f64> for RecordingKernel {
+            fn contained_in_circumference<V: TwoDimensional<Scalar = f64>>(
+                v1: &V,
+                v2: &V,
+                v3: &V,
+                p: &V,
+            ) -> bool {
+                // The same circumcircle may be passed in any rotation
+                let mut circle = [v1, v2, v3].map(|v| [*v.nth(0), *v.nth(1)]);
+                circle.sort_by(|a, b| a.partial_cmp(b).unwrap());
+                let test = [circle[0], circle[1], circle[2], [*p.nth(0), *p.nth(1)]];
+                IN_CIRCLE_TESTS
+                    .with(|tests| tests.borrow_mut().push(test.concat().try_into().unwrap()));
+                FloatKernel::contained_in_circumference(v1, v2, v3, p)
+            }
+
+            fn side_query<V: TwoDimensional<Scalar = f64>>(
+                edge: &SimpleEdge<V>,
+                position: &V,
+            ) -> EdgeSideInfo<f64> {
+                FloatKernel::side_query(edge, position)
+            }
+        }
+
+        let mut rng = Hc128Rng::from_seed(*SEED);
+        let mut points = Vec::new();
+        // Points on a convex curve create ears with several visible hull edges
+        for i in 0..200 {
+            let ang = f64::from(i) * 0.015;
+            points.push(Point2::new(ang.sin(), -ang.cos()) * 100.);
+        }
+        for _ in 0..300 {
+            points.push(Point2::new(
+                rng.gen_range(-50.0..50.0),
+                rng.gen_range(-50.0..50.0),
+            ));
+        }
+        let mut d = DelaunayTriangulation::<_, RecordingKernel>::with_walk_locate();
+        let mut num_tests = 0;
+        for point in points {
+            d.insert(point);
+            let mut tests = IN_CIRCLE_TESTS.with(|tests| core::mem::take(&mut *tests.borrow_mut()));
+            num_tests += tests.len();
+            tests.sort_by(|a, b| a.partial_cmp(b).unwrap());
+            let len = tests.len();
+            tests.dedup();
+            assert_eq!(tests.len(), len);
+        }
+        assert!(num_tests > 500);
+        d.sanity_check();
+        d.legality_check();
+    }
+
+    #[test]
+    fn test_legalization_yields_delaunay_edges() {
+        use crate::kernels::{DelaunayKernel, FloatKernel};
+        let mut rng = Hc128Rng::from_seed(*SEED);
+        let mut d = FloatDelaunayTriangulation::with_walk_locate();
+        // Points on a convex curve create ears with several visible hull edges
+        for i in 0..200 {
+            let ang = f64::from(i) * 0.015;
+            d.insert(Point2::new(ang.sin(), -ang.cos()) * 100.);
+        }
+        for _ in 0..300 {
+            d.insert(Point2::new(
+                rng.gen_range(-50.0..50.0),
+                rng.gen_range(-50.0..50.0),
+            ));
+        }
+        for _ in 0..250 {
+            let handle = rng.gen_range(0..d.num_vertices());
+            d.remove(handle);
+        }
+        d.sanity_check();
+        let infinite_face = d.infinite_face().fix();
+        for edge in d.edges() {
+            for edge in &[edge, edge.sym()] {
+                if edge.face().fix() == infinite_face || edge.sym().face().fix() == infinite_face {
+                    continue;
+                }
+                let opposite = edge.sym().o_next().to().position();
+                assert!(!FloatKernel::contained_in_circumference(
+                    &edge.to().position(),
+                    &edge.from().position(),
+                    &edge.o_next().to().position(),
+                    &opposite
+                ));
+            }
+        }
+    }
+
     #[test]
     fn test_insert_same_point_small() {
         let mut d = FloatDelaunayTriangulation::with_tree_locate();
@@ -1533,6 +2708,34 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_multi_channel_interpolation() {
+        let mut d = FloatDelaunayTriangulation::with_walk_locate();
+        let f = |p: &PointWithHeight| [p.height, p.point.x * 2.0, p.point.y - 1.0];
+        let query = Point2::new(0.3, 0.4);
+        assert_eq!(d.nn_interpolation_multi(&query, f), None);
+        assert_eq!(d.barycentric_interpolation_multi(&query, f), None);
+        d.insert(PointWithHeight::new(0.0, 0.0, 1.0));
+        d.insert(PointWithHeight::new(1.0, 0.0, 2.0));
+        d.insert(PointWithHeight::new(0.0, 1.0, 3.0));
+        d.insert(PointWithHeight::new(1.0, 1.0, 4.0));
+        for query in &[query, Point2::new(0.5, 0.0), Point2::new(2.0, -1.0)] {
+            let nn = d.nn_interpolation_multi(query, f).unwrap();
+            let bary = d.barycentric_interpolation_multi(query, f).unwrap();
+            for channel in 0..3 {
+                let nn_single = d.nn_interpolation(query, |p| f(p)[channel]).unwrap();
+                let bary_single = d
+                    .barycentric_interpolation(query, |p| f(p)[channel])
+                    .unwrap();
+                assert_relative_eq!(nn[channel], nn_single);
+                assert_relative_eq!(bary[channel], bary_single);
+            }
+        }
+        let [_, x, y] = d.barycentric_interpolation_multi(&query, f).unwrap();
+        assert_relative_eq!(x, 0.6, epsilon = 1e-10);
+        assert_relative_eq!(y, -0.6, epsilon = 1e-10);
+    }
+
     #[test]
     fn test_insert_points_with_increasing_distance() {
         use cgmath::EuclideanSpace;
@@ -1,17 +1,40 @@
 use self::dcel::*;
 use self::delaunay_locate::VertexEntry;
 use crate::delaunay::*;
+use crate::hilbert::hilbert_sort;
 use crate::kernels::DelaunayKernel;
 use crate::point_traits::{PointN, PointNExtensions, TwoDimensional};
 use crate::primitives::SimpleEdge;
-use crate::traits::HasPosition2D;
+use crate::traits::{HasPosition, HasPosition2D};
+use alloc::collections::BTreeSet;
+use alloc::{vec, vec::Vec};
 use smallvec::SmallVec;
-use std::collections::HashSet;
 
 type FixedPosition = PositionInTriangulation<FixedVertexHandle, FixedFaceHandle, FixedEdgeHandle>;
 type DynamicPosition<'a, V, E> =
     PositionInTriangulation<VertexHandle<'a, V, E>, FaceHandle<'a, V, E>, EdgeHandle<'a, V, E>>;
 
+/// A retained vertex next to removed vertices, see `remove_vertices`.
+struct BoundaryVertex<P> {
+    position: P,
+    handle: FixedVertexHandle,
+}
+
+impl<P: PointN> HasPosition for BoundaryVertex<P> {
+    type Point = P;
+
+    fn position(&self) -> P {
+        self.position.clone()
+    }
+}
+
+/// The state of a triangulation at the beginning of a transaction.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct TransactionStart {
+    num_vertices: usize,
+    all_points_on_line: bool,
+}
+
 pub trait Subdivision<V>
 where
     V: HasPosition2D,
@@ -342,7 +365,24 @@ where
             self.brute_force_locate(point)
         } else {
             let start = hint.unwrap_or_else(|| self.get_default_hint(point));
-            self.locate_with_hint_fixed(point, start)
+            let result = self.locate_with_hint_fixed(point, start);
+            let close_vertex = match result {
+                PositionInTriangulation::InTriangle(face) => self
+                    .s()
+                    .face(face)
+                    .adjacent_edge()
+                    .map(|edge| edge.from().fix()),
+                PositionInTriangulation::OnPoint(vertex) => Some(vertex),
+                PositionInTriangulation::OnEdge(edge)
+                | PositionInTriangulation::OutsideConvexHull(edge) => {
+                    Some(self.s().edge(edge).from().fix())
+                }
+                PositionInTriangulation::NoTriangulationPresent => None,
+            };
+            if let Some(close_vertex) = close_vertex {
+                self.locate_structure().new_query_result(close_vertex);
+            }
+            result
         }
     }
 
@@ -362,7 +402,7 @@ where
         if query.is_on_right_side() {
             // Always make sure the point is on the left side of the query line
             query = query.reversed();
-            ::std::mem::swap(&mut line.from, &mut line.to);
+            ::core::mem::swap(&mut line.from, &mut line.to);
         }
         let dir = line.to.sub(&line.from);
         let mut vertices: Vec<_> = self
@@ -557,9 +597,13 @@ where
         V: HasPosition2D,
         V::Point: TwoDimensional,
     {
+        // Triangles whose circumcircle does not contain the new vertex are not
+        // changed by this insertion. All of their edges are legal, e.g. both
+        // edges of an ear that faces the new vertex need only one test.
+        let mut legal_faces = SmallVec::<[FixedFaceHandle; 16]>::new();
         while let Some(e) = edges.pop() {
             if !self.is_ch_edge(e) && !self.is_defined_legal(e) {
-                let (v0, v1, v2, e1, e2);
+                let (v0, v1, v2, e1, e2, face);
                 {
                     let edge = self.s_mut().edge(e);
                     v0 = (*edge.from()).position();
@@ -567,14 +611,25 @@ where
                     v2 = (*edge.cw().to()).position();
                     e1 = edge.sym().o_next().fix();
                     e2 = edge.sym().o_prev().fix();
+                    face = edge.sym().face().fix();
+                }
+                if legal_faces.contains(&face) {
+                    continue;
                 }
                 debug_assert!(Self::Kernel::is_ordered_ccw(&v2, &v1, &v0));
                 debug_assert!(Self::Kernel::is_ordered_ccw(position, &v0, &v1));
                 if Self::Kernel::contained_in_circumference(&v1, &v2, &v0, position) {
                     // The edge is illegal
                     self.s_mut().flip_cw(e);
-                    edges.push(e1);
-                    edges.push(e2);
+                    // An edge that is still queued will be checked after this flip anyway,
+                    // e.g. the second convex hull edge of an ear
+                    for e in [e1, e2] {
+                        if !edges.contains(&e) {
+                            edges.push(e);
+                        }
+                    }
+                } else {
+                    legal_faces.push(face);
                 }
             }
         }
@@ -749,6 +804,39 @@ where
         data
     }
 
+    fn start_journal(&mut self) -> TransactionStart {
+        self.s_mut().start_journal();
+        TransactionStart {
+            num_vertices: self.s().num_vertices(),
+            all_points_on_line: self.all_points_on_line(),
+        }
+    }
+
+    fn rollback_journal(&mut self, start: TransactionStart) {
+        // Vertices inserted during the transaction must be removed from the
+        // locate structure before the DCEL forgets their positions
+        for handle in start.num_vertices..self.s().num_vertices() {
+            let pos = (*self.s().vertex(handle)).position();
+            self.locate_structure_mut()
+                .remove_vertex_entry(&VertexEntry::new(pos, handle));
+        }
+        self.s_mut().rollback_journal();
+        self.set_all_points_on_line(start.all_points_on_line);
+    }
+
+    fn compact(&mut self) -> Vec<FixedVertexHandle> {
+        let mut order: Vec<_> = self.s().fixed_vertices().collect();
+        hilbert_sort(&mut order, |handle| (*self.s().vertex(*handle)).position());
+        let new_handles = self.s_mut().reorder(&order);
+        self.locate_structure_mut().clear();
+        for handle in self.s().fixed_vertices() {
+            let pos = (*self.s().vertex(handle)).position();
+            self.locate_structure_mut()
+                .insert_vertex_entry(VertexEntry::new(pos, handle));
+        }
+        new_handles
+    }
+
     fn repair_edge(&mut self, vertices: &[FixedVertexHandle]) {
         assert_eq!(vertices.len(), 2);
         assert!(self.all_points_on_line());
@@ -832,7 +920,7 @@ where
     }
 
     fn fill_hole(&mut self, loop_edges: Vec<FixedEdgeHandle>) {
-        let mut border_edges = HashSet::new();
+        let mut border_edges = BTreeSet::new();
 
         for e in &loop_edges {
             border_edges.insert(*e);
@@ -865,15 +953,191 @@ where
                 // Flip edge
                 self.s_mut().flip_cw(fixed_edge_handle);
 
-                for e in &[e1, e2, e3, e4] {
-                    if !border_edges.contains(e) {
-                        todo.push(*e);
+                for e in [e1, e2, e3, e4] {
+                    // Queued edges may be stored in either direction
+                    let sym = self.s().edge(e).sym().fix();
+                    if !border_edges.contains(&e) && !todo.contains(&e) && !todo.contains(&sym) {
+                        todo.push(e);
                     }
                 }
             }
         }
     }
 
+    /// Returns `true` if all vertices for which `retain` is `true` lie on a line.
+    fn retained_points_on_line(&self, retain: &[bool]) -> bool {
+        let mut positions = self
+            .s()
+            .vertices()
+            .filter(|v| retain[v.fix()])
+            .map(|v| (*v).position());
+        let first = match positions.next() {
+            Some(first) => first,
+            None => return true,
+        };
+        let mut positions = positions.skip_while(|p| *p == first);
+        let second = match positions.next() {
+            Some(second) => second,
+            None => return true,
+        };
+        let line = SimpleEdge::new(first.clone(), second);
+        positions.all(|p| Self::Kernel::side_query(&line, &p).is_on_line())
+    }
+
+    /// Removes all vertices for which `retain` is `false`.
+    ///
+    /// All faces adjacent to removed vertices are replaced at once: The
+    /// retained vertices around the holes are triangulated separately, using
+    /// the remaining edges between them as constraints. The faces of this
+    /// triangulation that lie within the holes are copied and legalized.
+    /// The retained vertices must not lie on a line.
+    fn remove_vertices(&mut self, retain: &[bool]) {
+        let mut removed = Vec::new();
+        let mut boundary = Vec::new();
+        let mut local_handles = vec![None; retain.len()];
+        let mut local = ConstrainedDelaunayTriangulation::<
+            BoundaryVertex<V::Point>,
+            Self::Kernel,
+            DelaunayWalkLocate,
+        >::new();
+        for vertex in self.s().vertices().filter(|v| !retain[v.fix()]) {
+            removed.push(VertexEntry::new((*vertex).position(), vertex.fix()));
+            for edge in vertex.ccw_out_edges() {
+                let to = edge.to();
+                if retain[to.fix()] && local_handles[to.fix()].is_none() {
+                    let handle = local.insert(BoundaryVertex {
+                        position: (*to).position(),
+                        handle: to.fix(),
+                    });
+                    local_handles[to.fix()] = Some(handle);
+                }
+                // The edge opposite of the removed vertex bounds a hole
+                let opposite = edge.o_next();
+                let (from, to) = (opposite.from().fix(), opposite.to().fix());
+                if edge.face() != self.infinite_face() && retain[from] && retain[to] {
+                    boundary.push((from, to));
+                }
+            }
+        }
+
+        let mut triangles = Vec::new();
+        if local.num_triangles() > 0 {
+            for &(from, to) in &boundary {
+                local.add_constraint(local_handles[from].unwrap(), local_handles[to].unwrap());
+            }
+            // The faces within the holes are separated from all other faces by
+            // the boundary edges. If no edge remains, all faces are new.
+            let mut todo: Vec<_> = boundary
+                .iter()
+                .map(|&(from, to)| {
+                    local
+                        .get_edge_from_neighbors(
+                            local_handles[from].unwrap(),
+                            local_handles[to].unwrap(),
+                        )
+                        .unwrap()
+                        .face()
+                        .fix()
+                })
+                .collect();
+            if boundary.is_empty() {
+                todo.extend(1..local.num_faces());
+            }
+            let mut visited = vec![false; local.num_faces()];
+            visited[0] = true;
+            while let Some(face) = todo.pop() {
+                if visited[face] {
+                    continue;
+                }
+                visited[face] = true;
+                let edge = local.face(face).adjacent_edge().unwrap();
+                triangles.push([
+                    edge.from().handle,
+                    edge.to().handle,
+                    edge.o_next().to().handle,
+                ]);
+                for edge in local.face(face).adjacent_edges() {
+                    if !local.is_constraint_edge(edge.fix()) {
+                        todo.push(edge.sym().face().fix());
+                    }
+                }
+            }
+        }
+
+        let new_handles = self.s_mut().replace_vertices(retain, &triangles);
+        for entry in &removed {
+            self.locate_structure_mut().remove_vertex_entry(entry);
+        }
+        for (old, new) in new_handles.iter().enumerate() {
+            if let Some(new) = *new {
+                if new != old {
+                    let point = (*self.s().vertex(new)).position();
+                    self.locate_structure_mut()
+                        .update_vertex_entry(VertexEntry::new(point, new));
+                }
+            }
+        }
+
+        let mut edges: Vec<_> = triangles
+            .iter()
+            .flat_map(|t| [(t[0], t[1]), (t[1], t[2]), (t[2], t[0])])
+            .map(|(from, to)| {
+                let (from, to) = (new_handles[from].unwrap(), new_handles[to].unwrap());
+                (from.min(to), from.max(to))
+            })
+            .collect();
+        edges.sort_unstable();
+        edges.dedup();
+        let todo = edges
+            .into_iter()
+            .map(|(from, to)| self.s().get_edge_from_neighbors(from, to).unwrap().fix())
+            .collect();
+        self.legalize_all(todo);
+    }
+
+    /// Flips edges until all given edges and all edges affected by a flip are legal.
+    fn legalize_all(&mut self, mut todo: Vec<FixedEdgeHandle>) {
+        while let Some(fixed_edge_handle) = todo.pop() {
+            if self.is_ch_edge(fixed_edge_handle) || self.is_defined_legal(fixed_edge_handle) {
+                continue;
+            }
+            let (v0, v1, vl, vr, e1, e2, e3, e4);
+            {
+                let edge = self.s().edge(fixed_edge_handle);
+                v0 = (*edge.from()).position();
+                v1 = (*edge.to()).position();
+                vl = (*edge.ccw().to()).position();
+                vr = (*edge.cw().to()).position();
+                e1 = edge.cw().fix();
+                e2 = edge.ccw().fix();
+                e3 = edge.sym().cw().fix();
+                e4 = edge.sym().ccw().fix();
+            }
+            // Cocircular quads are left alone, flipping them could cycle forever
+            if Self::Kernel::contained_in_circumference(&v1, &v0, &vl, &vr) {
+                self.s_mut().flip_cw(fixed_edge_handle);
+                todo.extend([e1, e2, e3, e4]);
+            }
+        }
+    }
+
+    #[cfg(test)]
+    fn legality_check(&self) {
+        for edge in self.s().edges() {
+            let fixed_edge_handle = edge.fix();
+            if self.is_ch_edge(fixed_edge_handle) || self.is_defined_legal(fixed_edge_handle) {
+                continue;
+            }
+            let v0 = (*edge.from()).position();
+            let v1 = (*edge.to()).position();
+            let vl = (*edge.ccw().to()).position();
+            let vr = (*edge.cw().to()).position();
+            assert!(!Self::Kernel::contained_in_circumference(
+                &v1, &v0, &vl, &vr
+            ));
+        }
+    }
+
     #[cfg(test)]
     fn sanity_check(&self) {
         self.s().sanity_check();
@@ -6,19 +6,22 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
-use crate::delaunay::FixedVertexHandle;
-use crate::point_traits::PointN;
+use crate::delaunay::{DelaunayTriangulation, FixedVertexHandle};
+use crate::kernels::{DelaunayKernel, FloatKernel};
+use crate::point_traits::{PointN, TwoDimensional};
 use crate::rtree::RTree;
 use crate::traits::HasPosition;
-use std::sync::atomic::{AtomicUsize, Ordering};
-use std::sync::Arc;
+use alloc::{vec, vec::Vec};
+#[cfg(feature = "std")]
+use core::cell::Cell;
+use core::sync::atomic::{AtomicUsize, Ordering};
 
 #[derive(Clone)]
 #[deprecated(since = "1.0.1", note = "Replaced by DelaunayWalkLocate")]
 #[allow(deprecated)]
 #[allow(missing_docs)]
 pub struct TriangulationWalkLocate<T: PointN> {
-    marker: ::std::marker::PhantomData<T>,
+    marker: ::core::marker::PhantomData<T>,
     locate: DelaunayWalkLocate,
 }
 
@@ -81,6 +84,16 @@ pub trait DelaunayLocateStructure<T: PointN>: Default + Clone {
     fn find_close_handle(&self, point: &T) -> FixedVertexHandle;
     /// Notifies the locate structure about the result of a query.
     fn new_query_result(&self, entry: FixedVertexHandle);
+    /// This method is called when all vertices have been removed.
+    fn clear(&mut self) {
+        *self = Default::default();
+    }
+    /// Returns the estimated heap memory used by this structure, in bytes.
+    fn memory_usage(&self) -> usize {
+        0
+    }
+    /// Releases excess capacity after many vertices have been removed.
+    fn shrink_to_fit(&mut self) {}
 }
 
 /// An entry of the Delaunay triangulation's internal r-tree.
@@ -118,24 +131,131 @@ impl<V: PointN> VertexEntry<V> {
 /// This strategy works especially well if subsequent queries like insertion, interpolation
 /// or locate queries, are performed close to each other, as the result of the last query
 /// operation will be used as hint for the next operation.
-#[derive(Clone, Debug)]
+///
+/// With the `std` feature, the result of the last query is kept per thread. Threads
+/// that query the same triangulation concurrently do not share their hints and thus
+/// do not disturb each other's walks. Each thread keeps the hints of the triangulations
+/// it queried most recently, alternating between a few triangulations keeps all of
+/// their hints.
+#[derive(Clone, Debug, Default)]
 #[cfg_attr(feature = "serde_serialize", derive(Serialize, Deserialize))]
 pub struct DelaunayWalkLocate {
     #[cfg_attr(feature = "serde_serialize", serde(skip))]
-    last: Arc<AtomicUsize>,
+    last_query: LastQueryResult,
+    #[cfg_attr(feature = "serde_serialize", serde(skip))]
+    last_inserted: FixedVertexHandle,
+}
+
+#[cfg(feature = "std")]
+static NEXT_WALK_LOCATE_ID: AtomicUsize = AtomicUsize::new(0);
+
+/// The number of locate structures whose last query result is kept per thread.
+#[cfg(feature = "std")]
+const NUM_LAST_QUERY_RESULTS: usize = 8;
+
+#[cfg(feature = "std")]
+std::thread_local! {
+    // The last query results of this thread together with the ids of the locate
+    // structures they belong to, most recently used first.
+    static LAST_QUERY_RESULTS: Cell<[(usize, FixedVertexHandle); NUM_LAST_QUERY_RESULTS]> =
+        const { Cell::new([(usize::MAX, 0); NUM_LAST_QUERY_RESULTS]) };
 }
 
-impl Default for DelaunayWalkLocate {
+/// The result of the last query of a walk locate structure.
+///
+/// With the `std` feature, this stores the structure's id in the thread local
+/// query results, otherwise the result itself. Clones do not share their
+/// results, as their triangulations may diverge.
+#[derive(Debug)]
+struct LastQueryResult {
+    #[cfg(feature = "std")]
+    id: usize,
+    #[cfg(not(feature = "std"))]
+    handle: AtomicUsize,
+}
+
+impl LastQueryResult {
+    #[cfg(feature = "std")]
+    fn get(&self) -> Option<FixedVertexHandle> {
+        LAST_QUERY_RESULTS.with(|results| {
+            results
+                .get()
+                .iter()
+                .find(|(id, _)| *id == self.id)
+                .map(|(_, handle)| *handle)
+        })
+    }
+
+    #[cfg(feature = "std")]
+    fn set(&self, handle: FixedVertexHandle) {
+        LAST_QUERY_RESULTS.with(|results| {
+            let mut entries = results.get();
+            // Move the entry to the front, evicting the least recently used one if it is new
+            let index = entries
+                .iter()
+                .position(|(id, _)| *id == self.id)
+                .unwrap_or(NUM_LAST_QUERY_RESULTS - 1);
+            entries[..=index].rotate_right(1);
+            entries[0] = (self.id, handle);
+            results.set(entries);
+        });
+    }
+
+    #[cfg(not(feature = "std"))]
+    fn get(&self) -> Option<FixedVertexHandle> {
+        match self.handle.load(Ordering::Relaxed) {
+            usize::MAX => None,
+            handle => Some(handle),
+        }
+    }
+
+    #[cfg(not(feature = "std"))]
+    fn set(&self, handle: FixedVertexHandle) {
+        self.handle.store(handle, Ordering::Relaxed);
+    }
+}
+
+impl Default for LastQueryResult {
+    #[cfg(feature = "std")]
     fn default() -> Self {
-        DelaunayWalkLocate {
-            last: Arc::new(AtomicUsize::new(0)),
+        LastQueryResult {
+            id: NEXT_WALK_LOCATE_ID.fetch_add(1, Ordering::Relaxed),
         }
     }
+
+    #[cfg(not(feature = "std"))]
+    fn default() -> Self {
+        LastQueryResult {
+            handle: AtomicUsize::new(usize::MAX),
+        }
+    }
+}
+
+impl Clone for LastQueryResult {
+    fn clone(&self) -> Self {
+        Default::default()
+    }
+}
+
+#[cfg(feature = "std")]
+impl Drop for LastQueryResult {
+    fn drop(&mut self) {
+        // Entries on other threads are evicted once they are least recently used
+        let _ = LAST_QUERY_RESULTS.try_with(|results| {
+            let mut entries = results.get();
+            if let Some(index) = entries.iter().position(|(id, _)| *id == self.id) {
+                entries[index..].rotate_left(1);
+                entries[NUM_LAST_QUERY_RESULTS - 1] = (usize::MAX, 0);
+                results.set(entries);
+            }
+        });
+    }
 }
 
 impl<T: PointN> DelaunayLocateStructure<T> for DelaunayWalkLocate {
     fn insert_vertex_entry(&mut self, entry: VertexEntry<T>) {
-        self.last.store(entry.handle, Ordering::Relaxed);
+        self.last_inserted = entry.handle;
+        self.last_query.set(entry.handle);
     }
 
     fn update_vertex_entry(&mut self, _: VertexEntry<T>) {}
@@ -143,11 +263,11 @@ impl<T: PointN> DelaunayLocateStructure<T> for DelaunayWalkLocate {
     fn remove_vertex_entry(&mut self, _: &VertexEntry<T>) {}
 
     fn find_close_handle(&self, _: &T) -> FixedVertexHandle {
-        self.last.load(Ordering::Relaxed)
+        self.last_query.get().unwrap_or(self.last_inserted)
     }
 
     fn new_query_result(&self, entry: FixedVertexHandle) {
-        self.last.store(entry, Ordering::Relaxed);
+        self.last_query.set(entry);
     }
 }
 
@@ -170,4 +290,355 @@ impl<T: PointN> DelaunayLocateStructure<T> for RTree<VertexEntry<T>> {
     }
 
     fn new_query_result(&self, _: FixedVertexHandle) {}
+
+    fn clear(&mut self) {
+        RTree::clear(self);
+    }
+
+    fn memory_usage(&self) -> usize {
+        RTree::memory_usage(self).total()
+    }
+
+    fn shrink_to_fit(&mut self) {
+        RTree::shrink_to_fit(self);
+    }
+}
+
+/// Each vertex of a hierarchy level is contained in the next level with a
+/// probability of `1 / HIERARCHY_RATIO`.
+const HIERARCHY_RATIO: u64 = 30;
+const MAX_HIERARCHY_LEVELS: usize = 5;
+
+/// Locate strategy that maintains a Delaunay hierarchy.
+///
+/// The hierarchy consists of a few coarser triangulations, each containing a random
+/// sample of the vertices of the level below. Points are located by walking
+/// to the nearest vertex of the coarsest level and descending level by level,
+/// using the result of each walk as start for the next one.
+/// This approach takes O(log(n)) time on average, independent of the
+/// order of queries. Compared to `DelaunayTreeLocate`, the hierarchy is usually
+/// smaller and faster to update, as only a small fraction of all vertices
+/// is stored.
+///
+/// The kernel `K` is used to triangulate the coarser levels and should match the
+/// kernel of the triangulation.
+#[derive(Clone)]
+#[cfg_attr(feature = "serde_serialize", derive(Serialize, Deserialize))]
+pub struct DelaunayHierarchyLocate<T, K = FloatKernel>
+where
+    T: TwoDimensional,
+    K: DelaunayKernel<T::Scalar>,
+{
+    // The entry handles of the first level refer to the triangulation's vertices,
+    // the handles of all other levels refer to the vertices of the level below.
+    levels: Vec<DelaunayTriangulation<VertexEntry<T>, K, DelaunayWalkLocate>>,
+    seed: u64,
+}
+
+impl<T, K> Default for DelaunayHierarchyLocate<T, K>
+where
+    T: TwoDimensional,
+    K: DelaunayKernel<T::Scalar>,
+{
+    fn default() -> Self {
+        DelaunayHierarchyLocate {
+            levels: Vec::new(),
+            seed: 0x2545_f491_4f6c_dd1d,
+        }
+    }
+}
+
+impl<T, K> DelaunayHierarchyLocate<T, K>
+where
+    T: TwoDimensional,
+    K: DelaunayKernel<T::Scalar>,
+{
+    /// Returns the number of levels, excluding the triangulation itself.
+    pub fn num_levels(&self) -> usize {
+        self.levels.len()
+    }
+
+    /// Returns the number of vertices contained in a level.
+    ///
+    /// Level `0` is the finest level above the triangulation itself.
+    pub fn num_level_vertices(&self, level: usize) -> usize {
+        self.levels[level].num_vertices()
+    }
+
+    fn random_level_count(&mut self) -> usize {
+        let mut count = 0;
+        while count < MAX_HIERARCHY_LEVELS {
+            // xorshift64
+            self.seed ^= self.seed << 13;
+            self.seed ^= self.seed >> 7;
+            self.seed ^= self.seed << 17;
+            if self.seed > u64::MAX / HIERARCHY_RATIO {
+                break;
+            }
+            count += 1;
+        }
+        count
+    }
+
+    /// Returns the vertex of each level that is closest to `point`.
+    fn descend(&self, point: &T) -> Vec<Option<FixedVertexHandle>> {
+        let mut result = vec![None; self.levels.len()];
+        let mut hint = 0;
+        for (index, level) in self.levels.iter().enumerate().rev() {
+            if level.num_vertices() == 0 {
+                continue;
+            }
+            let nearest = level.walk_to_nearest_neighbor(hint, point);
+            result[index] = Some(nearest.fix());
+            hint = nearest.handle;
+        }
+        result
+    }
+}
+
+impl<T, K> DelaunayLocateStructure<T> for DelaunayHierarchyLocate<T, K>
+where
+    T: TwoDimensional,
+    K: DelaunayKernel<T::Scalar>,
+{
+    fn insert_vertex_entry(&mut self, entry: VertexEntry<T>) {
+        let level_count = self.random_level_count();
+        if level_count == 0 {
+            return;
+        }
+        while self.levels.len() < level_count {
+            self.levels.push(DelaunayTriangulation::new());
+        }
+        let hints = self.descend(&entry.point);
+        let mut handle = entry.handle;
+        for (level, hint) in self.levels.iter_mut().zip(hints).take(level_count) {
+            let entry = VertexEntry::new(entry.point.clone(), handle);
+            handle = match hint {
+                Some(hint) => level.insert_with_hint(entry, hint),
+                None => level.insert(entry),
+            };
+        }
+    }
+
+    fn update_vertex_entry(&mut self, new_entry: VertexEntry<T>) {
+        if let Some(Some(handle)) = self.descend(&new_entry.point).first() {
+            let level = &mut self.levels[0];
+            if level.vertex(*handle).point == new_entry.point {
+                level.vertex_mut(*handle).handle = new_entry.handle;
+            }
+        }
+    }
+
+    fn remove_vertex_entry(&mut self, to_remove: &VertexEntry<T>) {
+        let handles = self.descend(&to_remove.point);
+        let num_levels = self.levels.len();
+        for (index, handle) in handles.into_iter().enumerate() {
+            let handle = match handle {
+                Some(handle) if self.levels[index].vertex(handle).point == to_remove.point => {
+                    handle
+                }
+                // Levels are nested, all coarser levels cannot contain the point either
+                _ => break,
+            };
+            let level = &mut self.levels[index];
+            level.remove(handle);
+            if handle < level.num_vertices() && index + 1 < num_levels {
+                // The last vertex of this level has been moved into the removed
+                // vertex's slot, its entry in the next level must be updated.
+                let moved = level.vertex(handle).point.clone();
+                let next = &mut self.levels[index + 1];
+                if let Some(moved) = next.locate_vertex(&moved).map(|v| v.fix()) {
+                    next.vertex_mut(moved).handle = handle;
+                }
+            }
+        }
+    }
+
+    fn find_close_handle(&self, point: &T) -> FixedVertexHandle {
+        match self.descend(point).first() {
+            Some(Some(handle)) => self.levels[0].vertex(*handle).handle,
+            _ => 0,
+        }
+    }
+
+    fn new_query_result(&self, _: FixedVertexHandle) {}
+
+    fn memory_usage(&self) -> usize {
+        let levels = self.levels.capacity()
+            * ::core::mem::size_of::<DelaunayTriangulation<VertexEntry<T>, K, DelaunayWalkLocate>>(
+            );
+        levels
+            + self
+                .levels
+                .iter()
+                .map(|level| level.memory_usage().total())
+                .sum::<usize>()
+    }
+
+    fn shrink_to_fit(&mut self) {
+        for level in &mut self.levels {
+            level.shrink_to_fit();
+        }
+        self.levels.shrink_to_fit();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{DelaunayHierarchyLocate, DelaunayLocateStructure};
+    use crate::delaunay::delaunay_basic::BasicDelaunaySubdivision;
+    use crate::delaunay::{
+        DelaunayTriangulation, FloatDelaunayTriangulation, PositionInTriangulation,
+    };
+    use crate::kernels::FloatKernel;
+    use crate::testutils::*;
+    use cgmath::Point2;
+
+    type HierarchyTriangulation =
+        DelaunayTriangulation<Point2<f64>, FloatKernel, DelaunayHierarchyLocate<Point2<f64>>>;
+
+    fn check_hierarchy(d: &HierarchyTriangulation) {
+        let hierarchy = d.locate_structure();
+        for (index, level) in hierarchy.levels.iter().enumerate() {
+            for vertex in level.vertices() {
+                let referenced = if index == 0 {
+                    *d.vertex(vertex.handle)
+                } else {
+                    hierarchy.levels[index - 1].vertex(vertex.handle).point
+                };
+                assert_eq!(referenced, vertex.point);
+            }
+        }
+    }
+
+    #[test]
+    fn test_hierarchy_locate() {
+        const SIZE: usize = 5000;
+        let seed = b"\x1c\x8e\x53\x07\xd4\x6b\x2a\x91\xf0\x3d\x76\xbe\x45\x0a\x9c\x28\
+                     \x61\xe7\x3f\xa2\x5d\x14\xc9\x80\x37\xfb\x6e\x02\xad\x58\x93\x4b";
+        let points = random_points_with_seed::<f64>(SIZE, seed);
+        let mut d = DelaunayTriangulation::with_hierarchy_locate();
+        for p in &points {
+            d.insert(*p);
+        }
+        d.sanity_check();
+        check_hierarchy(&d);
+        assert!(d.locate_structure().num_levels() >= 1);
+        assert!(d.locate_structure().num_level_vertices(0) < SIZE / 10);
+
+        for p in &points {
+            assert_eq!(d.locate_vertex(p).map(|v| *v), Some(*p));
+            if let PositionInTriangulation::OnPoint(v) = d.locate(p) {
+                assert_eq!(*v, *p);
+            } else {
+                panic!("Point should be located on a vertex");
+            }
+        }
+
+        for p in points.iter().step_by(2) {
+            assert_eq!(d.locate_and_remove(p), Some(*p));
+        }
+        d.sanity_check();
+        check_hierarchy(&d);
+        for p in points.iter().skip(1).step_by(2) {
+            assert_eq!(d.nearest_neighbor(p).map(|v| *v), Some(*p));
+        }
+    }
+
+    #[test]
+    fn test_hierarchy_locate_remove_all() {
+        let seed = b"\x4a\x17\xe2\x9b\x05\xc8\x73\x3e\xd1\x6f\xa4\x20\x8b\x5c\xf7\x12\
+                     \x39\xce\x84\x0d\x66\xb1\x2f\x95\x7a\xe3\x48\x0c\xbd\x51\x9e\x27";
+        let points = random_points_with_seed::<f64>(1000, seed);
+        let mut d: HierarchyTriangulation = DelaunayTriangulation::new();
+        for p in &points {
+            d.insert(*p);
+        }
+        for p in &points {
+            assert!(d.locate_and_remove(p).is_some());
+            check_hierarchy(&d);
+        }
+        assert_eq!(d.num_vertices(), 0);
+        assert!((0..d.locate_structure().num_levels())
+            .all(|level| d.locate_structure().num_level_vertices(level) == 0));
+    }
+
+    #[test]
+    fn test_walk_locate_thread_local_hints() {
+        let points = random_points_with_seed::<f64>(2000, &[5; 32]);
+        let mut d = FloatDelaunayTriangulation::with_walk_locate();
+        for p in &points {
+            d.insert(*p);
+        }
+        let last_inserted = points.len() - 1;
+        let queries = random_points_with_seed::<f64>(400, &[9; 32]);
+        let expected: Vec<_> = queries
+            .iter()
+            .map(|q| d.nearest_neighbor(q).unwrap().fix())
+            .collect();
+
+        let close = d.locate_structure().find_close_handle(&queries[0]);
+        assert_ne!(close, last_inserted);
+
+        let (d, points, queries) = (&d, &points, &queries);
+        ::std::thread::scope(|scope| {
+            // Other threads start their walks at the last inserted vertex
+            scope.spawn(move || {
+                assert_eq!(
+                    d.locate_structure().find_close_handle(&queries[0]),
+                    last_inserted
+                );
+            });
+            for chunk in queries.chunks(100).zip(expected.chunks(100)) {
+                scope.spawn(move || {
+                    for (query, expected) in chunk.0.iter().zip(chunk.1) {
+                        assert_eq!(d.nearest_neighbor(query).unwrap().fix(), *expected);
+                    }
+                    for p in &points[..500] {
+                        assert_eq!(d.locate_vertex(p).map(|v| *v), Some(*p));
+                    }
+                });
+            }
+        });
+        assert_eq!(d.locate_structure().find_close_handle(&queries[0]), close);
+
+        let cloned = d.clone();
+        assert_eq!(
+            cloned.locate_structure().find_close_handle(&queries[0]),
+            last_inserted
+        );
+    }
+
+    #[test]
+    fn test_walk_locate_hints_per_triangulation() {
+        let mut triangulations: Vec<_> = (0..4u8)
+            .map(|seed| {
+                let mut d = FloatDelaunayTriangulation::with_walk_locate();
+                for p in random_points_with_seed::<f64>(500, &[seed; 32]) {
+                    d.insert(p);
+                }
+                d
+            })
+            .collect();
+        let query = Point2::new(0.1, 0.2);
+        let closest: Vec<_> = triangulations
+            .iter()
+            .map(|d| {
+                d.nearest_neighbor(&query);
+                d.locate_structure().find_close_handle(&query)
+            })
+            .collect();
+        assert!(closest.iter().all(|handle| *handle != 499));
+        // Interleaved queries keep the hints of all triangulations
+        for (d, closest) in triangulations.iter().zip(&closest) {
+            assert_eq!(d.locate_structure().find_close_handle(&query), *closest);
+        }
+        triangulations.truncate(1);
+        assert_eq!(
+            triangulations[0]
+                .locate_structure()
+                .find_close_handle(&query),
+            closest[0]
+        );
+    }
 }
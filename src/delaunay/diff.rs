@@ -0,0 +1,429 @@
+// Copyright 2017 The Spade Developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use crate::point_traits::PointN;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::hash::Hasher;
+use num::{BigInt, BigRational};
+
+/// Structural differences between two triangulations.
+///
+/// Vertices, edges and constraint edges are matched by their coordinates.
+/// Edges are undirected, each edge is given by its two vertex positions
+/// in lexicographic order.
+///
+/// Created by `DelaunayTriangulation::diff` and `ConstrainedDelaunayTriangulation::diff`.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde_serialize", derive(Serialize, Deserialize))]
+pub struct TriangulationDiff<P> {
+    /// Vertex positions that are only contained in the first triangulation.
+    pub removed_vertices: Vec<P>,
+    /// Vertex positions that are only contained in the second triangulation.
+    pub added_vertices: Vec<P>,
+    /// Edges that are only contained in the first triangulation.
+    pub removed_edges: Vec<(P, P)>,
+    /// Edges that are only contained in the second triangulation.
+    pub added_edges: Vec<(P, P)>,
+    /// Constraint edges that are only contained in the first triangulation.
+    pub removed_constraints: Vec<(P, P)>,
+    /// Constraint edges that are only contained in the second triangulation.
+    pub added_constraints: Vec<(P, P)>,
+}
+
+impl<P> TriangulationDiff<P> {
+    /// Returns `true` if both triangulations are structurally identical.
+    pub fn is_empty(&self) -> bool {
+        self.removed_vertices.is_empty()
+            && self.added_vertices.is_empty()
+            && self.removed_edges.is_empty()
+            && self.added_edges.is_empty()
+            && self.removed_constraints.is_empty()
+            && self.added_constraints.is_empty()
+    }
+}
+
+/// Vertex positions, edges and constraint edges of a triangulation.
+pub(crate) struct Snapshot<P> {
+    pub vertices: Vec<P>,
+    pub edges: Vec<(P, P)>,
+    pub constraints: Vec<(P, P)>,
+}
+
+impl<P: PointN> Snapshot<P> {
+    pub fn new<V, E, C>(vertices: V, edges: E, constraints: C) -> Self
+    where
+        V: IntoIterator<Item = P>,
+        E: IntoIterator<Item = (P, P)>,
+        C: IntoIterator<Item = (P, P)>,
+    {
+        Snapshot {
+            vertices: vertices.into_iter().collect(),
+            edges: edges.into_iter().map(normalize_edge).collect(),
+            constraints: constraints.into_iter().map(normalize_edge).collect(),
+        }
+    }
+}
+
+impl<P: PointN> Snapshot<P>
+where
+    P::Scalar: HashScalar,
+{
+    /// Calculates a hash that does not depend on the order of vertices and edges.
+    pub fn structural_hash(mut self) -> u64 {
+        self.vertices.sort_by(compare_points);
+        self.edges.sort_by(compare_edges);
+        self.constraints.sort_by(compare_edges);
+        let mut hasher = Fnv1aHasher::default();
+        hasher.write_usize(self.vertices.len());
+        for vertex in &self.vertices {
+            hash_point(&mut hasher, vertex);
+        }
+        for edges in &[self.edges, self.constraints] {
+            hasher.write_usize(edges.len());
+            for (from, to) in edges {
+                hash_point(&mut hasher, from);
+                hash_point(&mut hasher, to);
+            }
+        }
+        hasher.finish()
+    }
+}
+
+fn hash_point<P>(hasher: &mut Fnv1aHasher, point: &P)
+where
+    P: PointN,
+    P::Scalar: HashScalar,
+{
+    for dim in 0..P::dimensions() {
+        point.nth(dim).hash_scalar(hasher);
+    }
+}
+
+/// Scalars whose exact value can be hashed.
+///
+/// Used by `structural_hash`. Equal values yield equal hashes, values that
+/// differ only slightly, e.g. two `i64`s that are rounded to the same `f64`,
+/// are hashed differently.
+pub trait HashScalar {
+    /// Feeds the exact value of this scalar into the given hasher.
+    fn hash_scalar<H: Hasher>(&self, state: &mut H);
+}
+
+impl HashScalar for f64 {
+    fn hash_scalar<H: Hasher>(&self, state: &mut H) {
+        // Treat 0.0 and -0.0 as equal
+        let value = if *self == 0.0 { 0.0 } else { *self };
+        state.write_u64(value.to_bits());
+    }
+}
+
+impl HashScalar for f32 {
+    fn hash_scalar<H: Hasher>(&self, state: &mut H) {
+        // Converting to f64 is exact
+        f64::from(*self).hash_scalar(state);
+    }
+}
+
+impl HashScalar for i32 {
+    fn hash_scalar<H: Hasher>(&self, state: &mut H) {
+        state.write_i64(i64::from(*self));
+    }
+}
+
+impl HashScalar for i64 {
+    fn hash_scalar<H: Hasher>(&self, state: &mut H) {
+        state.write_i64(*self);
+    }
+}
+
+impl HashScalar for BigInt {
+    fn hash_scalar<H: Hasher>(&self, state: &mut H) {
+        let bytes = self.to_signed_bytes_le();
+        state.write_usize(bytes.len());
+        state.write(&bytes);
+    }
+}
+
+impl HashScalar for BigRational {
+    fn hash_scalar<H: Hasher>(&self, state: &mut H) {
+        // Ratios are always stored in lowest terms with a positive denominator
+        self.numer().hash_scalar(state);
+        self.denom().hash_scalar(state);
+    }
+}
+
+#[cfg(feature = "fixed")]
+impl<Frac> HashScalar for fixed::FixedI16<Frac> {
+    fn hash_scalar<H: Hasher>(&self, state: &mut H) {
+        state.write_i64(i64::from(self.to_bits()));
+    }
+}
+
+#[cfg(feature = "fixed")]
+impl<Frac> HashScalar for fixed::FixedI32<Frac> {
+    fn hash_scalar<H: Hasher>(&self, state: &mut H) {
+        state.write_i64(i64::from(self.to_bits()));
+    }
+}
+
+#[cfg(feature = "fixed")]
+impl<Frac> HashScalar for fixed::FixedI64<Frac> {
+    fn hash_scalar<H: Hasher>(&self, state: &mut H) {
+        state.write_i64(self.to_bits());
+    }
+}
+
+/// The 64 bit FNV-1a hash function.
+///
+/// Unlike the standard library's default hasher, its output is guaranteed to be
+/// stable across program runs and platforms.
+struct Fnv1aHasher(u64);
+
+impl Default for Fnv1aHasher {
+    fn default() -> Self {
+        Fnv1aHasher(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Hasher for Fnv1aHasher {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= u64::from(*byte);
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+
+    fn write_u64(&mut self, value: u64) {
+        self.write(&value.to_le_bytes());
+    }
+
+    fn write_usize(&mut self, value: usize) {
+        self.write_u64(value as u64);
+    }
+}
+
+pub(crate) fn diff<P: PointN>(first: Snapshot<P>, second: Snapshot<P>) -> TriangulationDiff<P> {
+    let (removed_vertices, added_vertices) =
+        sorted_difference(first.vertices, second.vertices, compare_points);
+    let (removed_edges, added_edges) = sorted_difference(first.edges, second.edges, compare_edges);
+    let (removed_constraints, added_constraints) =
+        sorted_difference(first.constraints, second.constraints, compare_edges);
+    TriangulationDiff {
+        removed_vertices,
+        added_vertices,
+        removed_edges,
+        added_edges,
+        removed_constraints,
+        added_constraints,
+    }
+}
+
+/// Compares two points lexicographically by their coordinates.
+pub(crate) fn compare_points<P: PointN>(p1: &P, p2: &P) -> Ordering {
+    for dim in 0..P::dimensions() {
+        match p1.nth(dim).partial_cmp(p2.nth(dim)) {
+            Some(Ordering::Equal) | None => continue,
+            Some(ordering) => return ordering,
+        }
+    }
+    Ordering::Equal
+}
+
+fn compare_edges<P: PointN>(e1: &(P, P), e2: &(P, P)) -> Ordering {
+    compare_points(&e1.0, &e2.0).then_with(|| compare_points(&e1.1, &e2.1))
+}
+
+fn normalize_edge<P: PointN>((from, to): (P, P)) -> (P, P) {
+    if compare_points(&from, &to) == Ordering::Greater {
+        (to, from)
+    } else {
+        (from, to)
+    }
+}
+
+/// Returns all elements that are only contained in `first` or only in `second`.
+fn sorted_difference<T, F>(mut first: Vec<T>, mut second: Vec<T>, compare: F) -> (Vec<T>, Vec<T>)
+where
+    F: Fn(&T, &T) -> Ordering,
+{
+    first.sort_by(&compare);
+    second.sort_by(&compare);
+    let mut only_first = Vec::new();
+    let mut only_second = Vec::new();
+    let mut first = first.into_iter().peekable();
+    let mut second = second.into_iter().peekable();
+    loop {
+        let ordering = match (first.peek(), second.peek()) {
+            (Some(f), Some(s)) => compare(f, s),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => break,
+        };
+        match ordering {
+            Ordering::Less => only_first.extend(first.next()),
+            Ordering::Greater => only_second.extend(second.next()),
+            Ordering::Equal => {
+                first.next();
+                second.next();
+            }
+        }
+    }
+    (only_first, only_second)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Fnv1aHasher, HashScalar, Snapshot};
+    use crate::delaunay::{FloatCDT, FloatDelaunayTriangulation};
+    use crate::testutils::*;
+    use alloc::vec::Vec;
+    use cgmath::Point2;
+    use core::hash::Hasher;
+    use num::{BigInt, BigRational, ToPrimitive};
+
+    #[test]
+    fn test_diff_identical() {
+        let seed = b"\x5e\x21\x9a\xc4\x07\xbd\x63\x38\xf1\x4c\x82\x1b\xe9\x56\x0d\xa7\
+                     \x34\xcb\x70\x15\xde\x89\x42\x2f\x9b\x66\x03\xb8\x5d\xf4\x27\x90";
+        let points = random_points_with_seed::<f64>(200, seed);
+        let mut d1 = FloatDelaunayTriangulation::with_walk_locate();
+        let mut d2 = FloatDelaunayTriangulation::with_tree_locate();
+        for p in &points {
+            d1.insert(*p);
+        }
+        for p in points.iter().rev() {
+            d2.insert(*p);
+        }
+        assert!(d1.diff(&d2).is_empty());
+    }
+
+    #[test]
+    fn test_diff() {
+        let mut d1 = FloatDelaunayTriangulation::with_walk_locate();
+        d1.insert(Point2::new(0.0, 0.0));
+        d1.insert(Point2::new(2.0, 0.0));
+        d1.insert(Point2::new(0.0, 2.0));
+        let mut d2 = d1.clone();
+        d2.insert(Point2::new(2.0, 2.0));
+        let diff = d1.diff(&d2);
+        assert_eq!(diff.added_vertices, vec![Point2::new(2.0, 2.0)]);
+        assert!(diff.removed_vertices.is_empty());
+        assert!(diff.removed_edges.is_empty());
+        assert_eq!(diff.added_edges.len(), 2);
+        assert!(diff
+            .added_edges
+            .iter()
+            .all(|(_, to)| *to == Point2::new(2.0, 2.0)));
+
+        let reversed = d2.diff(&d1);
+        assert_eq!(reversed.removed_vertices, diff.added_vertices);
+        assert_eq!(reversed.removed_edges, diff.added_edges);
+    }
+
+    #[test]
+    fn test_structural_hash() {
+        let seed = b"\x0b\x93\x4e\xd7\x62\x1f\xa8\x35\xc0\x7d\x16\xe2\x59\xb4\x2a\x8f\
+                     \x73\x04\xcd\x61\x98\x3b\xf6\x45\x1e\xa3\x87\x5c\x20\xeb\x36\xd9";
+        let points = random_points_with_seed::<f64>(200, seed);
+        let mut d1 = FloatDelaunayTriangulation::with_walk_locate();
+        let mut d2 = FloatDelaunayTriangulation::with_walk_locate();
+        for p in &points {
+            d1.insert(*p);
+        }
+        for p in points.iter().rev() {
+            d2.insert(*p);
+        }
+        assert_eq!(d1.structural_hash(), d2.structural_hash());
+        d2.insert(Point2::new(0.25, 0.25));
+        assert_ne!(d1.structural_hash(), d2.structural_hash());
+
+        let mut empty = FloatDelaunayTriangulation::with_walk_locate();
+        let empty_hash = empty.structural_hash();
+        empty.insert(Point2::new(0.0, 0.0));
+        assert_ne!(empty.structural_hash(), empty_hash);
+        // The hash must be stable across runs and platforms
+        assert_eq!(
+            FloatDelaunayTriangulation::<Point2<f64>, _>::with_walk_locate().structural_hash(),
+            0x81d2_3fd7_003c_2305
+        );
+    }
+
+    #[test]
+    fn test_structural_hash_is_exact() {
+        let hash = |vertices: Vec<Point2<i64>>| {
+            Snapshot::new(vertices, Vec::new(), Vec::new()).structural_hash()
+        };
+        // Both coordinates are rounded to the same f64
+        let large = 1 << 60;
+        assert_eq!(large as f64, (large + 1) as f64);
+        assert_ne!(
+            hash(vec![Point2::new(large, 0)]),
+            hash(vec![Point2::new(large + 1, 0)])
+        );
+        assert_eq!(
+            hash(vec![Point2::new(large, 0)]),
+            hash(vec![Point2::new(large, 0)])
+        );
+
+        let rational_hash = |value: BigRational| {
+            let mut hasher = Fnv1aHasher::default();
+            value.hash_scalar(&mut hasher);
+            hasher.finish()
+        };
+        let third = BigRational::new(BigInt::from(1), BigInt::from(3));
+        let close = BigRational::new(
+            BigInt::from(333_333_333_333_333_333i64),
+            BigInt::from(10).pow(18),
+        );
+        assert_eq!(third.to_f64(), close.to_f64());
+        assert_ne!(rational_hash(third.clone()), rational_hash(close));
+        assert_eq!(
+            rational_hash(third),
+            rational_hash(BigRational::new(BigInt::from(-2), BigInt::from(-6)))
+        );
+        assert_ne!(
+            rational_hash(BigRational::from_integer(BigInt::from(1))),
+            rational_hash(BigRational::from_integer(BigInt::from(256)))
+        );
+    }
+
+    #[test]
+    fn test_cdt_structural_hash() {
+        let mut cdt1 = FloatCDT::with_walk_locate();
+        let v0 = cdt1.insert(Point2::new(0.0, 0.0));
+        let v1 = cdt1.insert(Point2::new(1.0, 0.0));
+        cdt1.insert(Point2::new(0.0, 1.0));
+        let mut cdt2 = cdt1.clone();
+        assert_eq!(cdt1.structural_hash(), cdt2.structural_hash());
+        cdt2.add_constraint(v0, v1);
+        assert_ne!(cdt1.structural_hash(), cdt2.structural_hash());
+    }
+
+    #[test]
+    fn test_cdt_diff() {
+        let mut cdt1 = FloatCDT::with_walk_locate();
+        let v0 = cdt1.insert(Point2::new(0.0, 0.0));
+        let v1 = cdt1.insert(Point2::new(1.0, 0.0));
+        cdt1.insert(Point2::new(0.0, 1.0));
+        let mut cdt2 = cdt1.clone();
+        cdt2.add_constraint(v1, v0);
+        let diff = cdt1.diff(&cdt2);
+        assert!(diff.added_vertices.is_empty());
+        assert!(diff.added_edges.is_empty());
+        assert_eq!(
+            diff.added_constraints,
+            vec![(Point2::new(0.0, 0.0), Point2::new(1.0, 0.0))]
+        );
+        assert!(!diff.is_empty());
+    }
+}
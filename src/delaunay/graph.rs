@@ -0,0 +1,161 @@
+// Copyright 2017 The Spade Developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use crate::delaunay::*;
+use crate::kernels::DelaunayKernel;
+use crate::point_traits::{PointN, TwoDimensional};
+use crate::traits::{HasPosition2D, SpadeFloat};
+use petgraph::graph::{NodeIndex, UnGraph};
+
+/// Edge weight of a graph created by `DelaunayTriangulation::to_petgraph`.
+///
+/// Requires the `petgraph` feature.
+#[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
+pub struct TriangulationGraphEdge<S> {
+    /// The triangulation edge this graph edge was created from.
+    pub edge: FixedEdgeHandle,
+    /// The euclidean length of the edge.
+    pub length: S,
+    /// `true` if the edge is a constraint edge. Always `false` for
+    /// unconstrained triangulations.
+    pub is_constraint: bool,
+}
+
+impl<V, K, L> DelaunayTriangulation<V, K, L>
+where
+    V: HasPosition2D,
+    V::Point: TwoDimensional,
+    <V::Point as PointN>::Scalar: SpadeFloat,
+    K: DelaunayKernel<<V::Point as PointN>::Scalar>,
+    L: DelaunayLocateStructure<V::Point>,
+{
+    /// Creates an undirected `petgraph` graph of all vertices and edges.
+    ///
+    /// The node index of each vertex equals its fixed vertex handle, the
+    /// node weight is the vertex handle itself. Every undirected edge of the
+    /// triangulation is added once.
+    ///
+    /// Requires the `petgraph` feature.
+    pub fn to_petgraph(
+        &self,
+    ) -> UnGraph<FixedVertexHandle, TriangulationGraphEdge<<V::Point as PointN>::Scalar>> {
+        create_graph(
+            self.num_vertices(),
+            self.num_edges(),
+            self.edge_lengths(),
+            |_| false,
+        )
+    }
+}
+
+impl<V, K, L> ConstrainedDelaunayTriangulation<V, K, L>
+where
+    V: HasPosition2D,
+    V::Point: TwoDimensional,
+    <V::Point as PointN>::Scalar: SpadeFloat,
+    K: DelaunayKernel<<V::Point as PointN>::Scalar>,
+    L: DelaunayLocateStructure<V::Point>,
+{
+    /// Creates an undirected `petgraph` graph of all vertices and edges.
+    ///
+    /// The node index of each vertex equals its fixed vertex handle, the
+    /// node weight is the vertex handle itself. Every undirected edge of the
+    /// triangulation is added once, constraint edges are flagged in their
+    /// edge weight.
+    ///
+    /// Requires the `petgraph` feature.
+    pub fn to_petgraph(
+        &self,
+    ) -> UnGraph<FixedVertexHandle, TriangulationGraphEdge<<V::Point as PointN>::Scalar>> {
+        create_graph(
+            self.num_vertices(),
+            self.num_edges(),
+            self.edge_lengths(),
+            |edge| self.is_constraint_edge(edge),
+        )
+    }
+}
+
+fn create_graph<'a, V, E, F>(
+    num_vertices: usize,
+    num_edges: usize,
+    edges: EdgeLengthIterator<'a, V, E>,
+    is_constraint: F,
+) -> UnGraph<FixedVertexHandle, TriangulationGraphEdge<<V::Point as PointN>::Scalar>>
+where
+    V: HasPosition2D + 'a,
+    V::Point: TwoDimensional,
+    <V::Point as PointN>::Scalar: SpadeFloat,
+    E: Default + Copy + 'a,
+    F: Fn(FixedEdgeHandle) -> bool,
+{
+    let mut graph = UnGraph::with_capacity(num_vertices, num_edges);
+    for vertex in 0..num_vertices {
+        graph.add_node(vertex);
+    }
+    for (edge, length) in edges {
+        let weight = TriangulationGraphEdge {
+            edge: edge.fix(),
+            length,
+            is_constraint: is_constraint(edge.fix()),
+        };
+        graph.add_edge(
+            NodeIndex::new(edge.from().fix()),
+            NodeIndex::new(edge.to().fix()),
+            weight,
+        );
+    }
+    graph
+}
+
+#[cfg(test)]
+mod test {
+    use crate::delaunay::{FloatCDT, FloatDelaunayTriangulation};
+    use cgmath::Point2;
+    use petgraph::algo::{connected_components, dijkstra};
+    use petgraph::graph::NodeIndex;
+
+    #[test]
+    fn test_to_petgraph() {
+        let mut d = FloatDelaunayTriangulation::with_walk_locate();
+        let v0 = d.insert(Point2::new(0.0, 0.0));
+        d.insert(Point2::new(3.0, 0.0));
+        d.insert(Point2::new(3.0, 4.0));
+        let v3 = d.insert(Point2::new(6.0, 4.0));
+        let graph = d.to_petgraph();
+        assert_eq!(graph.node_count(), d.num_vertices());
+        assert_eq!(graph.edge_count(), d.num_edges());
+        assert_eq!(connected_components(&graph), 1);
+        for edge in graph.edge_weights() {
+            assert!(!edge.is_constraint);
+            let edge = d.edge(edge.edge);
+            assert!(graph
+                .find_edge(
+                    NodeIndex::new(edge.from().fix()),
+                    NodeIndex::new(edge.to().fix())
+                )
+                .is_some());
+        }
+        let distances = dijkstra(&graph, NodeIndex::new(v0), None, |e| e.weight().length);
+        assert_relative_eq!(distances[&NodeIndex::new(v3)], 8.0);
+    }
+
+    #[test]
+    fn test_cdt_to_petgraph() {
+        let mut cdt = FloatCDT::with_walk_locate();
+        let v0 = cdt.insert(Point2::new(0.0, 0.0));
+        let v1 = cdt.insert(Point2::new(1.0, 0.0));
+        cdt.insert(Point2::new(0.0, 1.0));
+        cdt.add_constraint(v0, v1);
+        let graph = cdt.to_petgraph();
+        assert_eq!(graph.edge_count(), 3);
+        let constraints: Vec<_> = graph.edge_weights().filter(|e| e.is_constraint).collect();
+        assert_eq!(constraints.len(), 1);
+        assert_relative_eq!(constraints[0].length, 1.0);
+    }
+}
@@ -26,12 +26,12 @@ where
     EdgeOverlap(EdgeHandle<'a, V, E>),
 }
 
-impl<'a, V, E> ::std::fmt::Debug for Intersection<'a, V, E>
+impl<'a, V, E> ::core::fmt::Debug for Intersection<'a, V, E>
 where
     V: 'a,
     E: Default,
 {
-    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
         use self::Intersection::*;
         match self {
             EdgeIntersection(handle) => write!(f, "EdgeIntersection({:?})", handle),
@@ -125,8 +125,14 @@ where
                     } else {
                         Some(Intersection::EdgeOverlap(edge.sym()))
                     }
-                } else {
+                } else if T::to_simple_edge(edge)
+                    .side_query::<T::Kernel>(&line.to)
+                    .is_on_left_side_or_on_line()
+                {
                     Some(Intersection::EdgeIntersection(edge))
+                } else {
+                    // The line's target must lie on the left side of the edge
+                    Some(Intersection::EdgeIntersection(edge.sym()))
                 }
             }
             OutsideConvexHull(edge) => {
@@ -8,19 +8,42 @@
 
 //! A two dimensional Delaunay triangulation.
 
+mod apollonius;
 mod cdt;
 mod dcel;
 mod delaunay2d;
 mod delaunay_basic;
 mod delaunay_locate;
+mod diff;
+#[cfg(feature = "petgraph")]
+mod graph;
 mod line_intersection_iterator;
+mod regions;
+mod segment_voronoi;
+mod statistics;
+mod terrain;
 
+pub use self::apollonius::ApolloniusDiagram;
 pub use self::cdt::{CdtEdge, ConstrainedDelaunayTriangulation, FloatCDT};
 pub use self::dcel::{
-    CCWIterator, EdgeHandle, FaceHandle, FixedEdgeHandle, FixedFaceHandle, FixedVertexHandle,
-    ONextIterator, VertexHandle,
+    AdjacentFaceIterator, CCWIterator, CWIterator, EdgeHandle, FaceHandle, FixedEdgeHandle,
+    FixedFaceHandle, FixedVertexHandle, NeighborIterator, ONextIterator, VertexHandle,
 };
 pub use self::delaunay2d::*;
-pub use self::delaunay_locate::{DelaunayLocateStructure, DelaunayTreeLocate, DelaunayWalkLocate};
+pub use self::delaunay_locate::{
+    DelaunayHierarchyLocate, DelaunayLocateStructure, DelaunayTreeLocate, DelaunayWalkLocate,
+};
 #[allow(deprecated)]
 pub use self::delaunay_locate::{RTreeDelaunayLocate, TriangulationWalkLocate};
+pub use self::diff::{HashScalar, TriangulationDiff};
+#[cfg(feature = "petgraph")]
+pub use self::graph::TriangulationGraphEdge;
+pub use self::regions::RegionClassification;
+pub use self::segment_voronoi::{
+    BisectorCurve, SegmentVoronoiDiagram, SegmentVoronoiEdge, SegmentVoronoiError,
+    SegmentVoronoiVertex, VoronoiSite,
+};
+pub use self::statistics::{
+    EdgeLengthIterator, Statistics, TriangleAngleIterator, TriangulationMemoryUsage,
+};
+pub use self::terrain::{CutFillVolume, FaceSlope, FaceSlopeIterator, RasterGrid};
@@ -0,0 +1,220 @@
+// Copyright 2017 The Spade Developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use self::dcel::{EdgeHandle, DCEL};
+use crate::delaunay::*;
+use alloc::collections::VecDeque;
+use alloc::{vec, vec::Vec};
+
+/// Assigns every face of a constrained Delaunay triangulation to a region.
+///
+/// A region is a maximal set of faces that are connected without crossing a
+/// constraint edge. Region `0` always contains the infinite face.
+///
+/// Additionally, every region is assigned a _depth_: The minimal number of
+/// constraint edges that must be crossed to reach the region from the infinite
+/// face. If the constraint edges form the boundaries of non overlapping polygons
+/// (possibly with holes), faces with an odd depth lie inside a polygon.
+///
+/// Created by `ConstrainedDelaunayTriangulation::classify_regions`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct RegionClassification {
+    face_regions: Vec<usize>,
+    regions: Vec<Vec<FixedFaceHandle>>,
+    depths: Vec<usize>,
+}
+
+impl RegionClassification {
+    /// Returns the number of regions.
+    pub fn num_regions(&self) -> usize {
+        self.regions.len()
+    }
+
+    /// Returns the region id of a face.
+    pub fn region(&self, face: FixedFaceHandle) -> usize {
+        self.face_regions[face]
+    }
+
+    /// Returns the region ids of all faces, indexed by their fixed face handle.
+    pub fn face_regions(&self) -> &[usize] {
+        &self.face_regions
+    }
+
+    /// Returns all faces belonging to a region.
+    pub fn faces(&self, region: usize) -> &[FixedFaceHandle] {
+        &self.regions[region]
+    }
+
+    /// Returns the number of constraint edges that separate a region from
+    /// the infinite face.
+    pub fn depth(&self, region: usize) -> usize {
+        self.depths[region]
+    }
+
+    /// Returns `true` if a face lies at an odd depth.
+    ///
+    /// If the constraint edges describe polygon boundaries, these are
+    /// the faces in the polygons' interiors.
+    pub fn is_inside(&self, face: FixedFaceHandle) -> bool {
+        self.depths[self.region(face)] % 2 == 1
+    }
+}
+
+/// Collects all faces reachable from `seed` without crossing an edge for which
+/// `can_cross` returns `false`.
+///
+/// Faces are marked in `visited` and will not be visited twice. All edges that
+/// block the traversal are passed to `on_blocked`.
+pub(crate) fn flood_fill<'a, V, E, F, B>(
+    dcel: &'a DCEL<V, E>,
+    seed: FixedFaceHandle,
+    visited: &mut [bool],
+    mut can_cross: F,
+    mut on_blocked: B,
+) -> Vec<FixedFaceHandle>
+where
+    E: Default + Copy,
+    F: FnMut(EdgeHandle<'a, V, E>) -> bool,
+    B: FnMut(EdgeHandle<'a, V, E>),
+{
+    let mut result = Vec::new();
+    if visited[seed] {
+        return result;
+    }
+    visited[seed] = true;
+    let mut stack = vec![seed];
+    while let Some(face) = stack.pop() {
+        result.push(face);
+        for edge in dcel.face(face).adjacent_edges() {
+            let neighbor = edge.sym().face().fix();
+            if visited[neighbor] {
+                continue;
+            }
+            if can_cross(edge) {
+                visited[neighbor] = true;
+                stack.push(neighbor);
+            } else {
+                on_blocked(edge);
+            }
+        }
+    }
+    result
+}
+
+/// Partitions all faces into regions separated by constraint edges.
+pub(crate) fn classify_regions<V, E, F>(dcel: &DCEL<V, E>, is_constraint: F) -> RegionClassification
+where
+    E: Default + Copy,
+    F: Fn(FixedEdgeHandle) -> bool,
+{
+    let num_faces = dcel.num_faces();
+    let mut visited = vec![false; num_faces];
+    let mut face_regions = vec![0; num_faces];
+    let mut regions = Vec::new();
+    let mut depths = Vec::new();
+    // Visiting the seeds in breadth first order assigns each region its minimal depth
+    let mut seeds = VecDeque::new();
+    seeds.push_back((0, 0));
+    while let Some((seed, depth)) = seeds.pop_front() {
+        let faces = flood_fill(
+            dcel,
+            seed,
+            &mut visited,
+            |edge| !is_constraint(edge.fix()),
+            |edge| seeds.push_back((edge.sym().face().fix(), depth + 1)),
+        );
+        if faces.is_empty() {
+            continue;
+        }
+        for face in &faces {
+            face_regions[*face] = regions.len();
+        }
+        regions.push(faces);
+        depths.push(depth);
+    }
+    RegionClassification {
+        face_regions,
+        regions,
+        depths,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::delaunay::FloatCDT;
+    use cgmath::Point2;
+
+    fn add_square(cdt: &mut FloatCDT<Point2<f64>, crate::delaunay::DelaunayWalkLocate>, size: f64) {
+        let corners = [
+            Point2::new(-size, -size),
+            Point2::new(size, -size),
+            Point2::new(size, size),
+            Point2::new(-size, size),
+        ];
+        for i in 0..4 {
+            cdt.add_constraint_edge(corners[i], corners[(i + 1) % 4]);
+        }
+    }
+
+    #[test]
+    fn test_classify_regions() {
+        let mut cdt = FloatCDT::with_walk_locate();
+        add_square(&mut cdt, 3.0);
+        add_square(&mut cdt, 2.0);
+        add_square(&mut cdt, 1.0);
+        cdt.insert(Point2::new(0.0, 0.5));
+        cdt.insert(Point2::new(2.5, 0.0));
+        cdt.insert(Point2::new(10.0, 10.0));
+
+        let regions = cdt.classify_regions();
+        assert_eq!(regions.num_regions(), 4);
+        assert_eq!(regions.region(0), 0);
+        assert_eq!(regions.depth(0), 0);
+        let total: usize = (0..4).map(|r| regions.faces(r).len()).sum();
+        assert_eq!(total, cdt.num_faces());
+        for region in 0..4 {
+            assert_eq!(regions.depth(region), region);
+        }
+        let inner = cdt.locate(&Point2::new(0.1, 0.1));
+        if let crate::delaunay::PositionInTriangulation::InTriangle(face) = inner {
+            assert_eq!(regions.depth(regions.region(face.fix())), 3);
+            assert!(regions.is_inside(face.fix()));
+        } else {
+            panic!("Expected a triangle");
+        }
+    }
+
+    #[test]
+    fn test_grow_region_within_constraints() {
+        let mut cdt = FloatCDT::with_walk_locate();
+        add_square(&mut cdt, 2.0);
+        add_square(&mut cdt, 1.0);
+        let regions = cdt.classify_regions();
+        for region in 0..regions.num_regions() {
+            let seed = regions.faces(region)[0];
+            let mut grown = cdt.grow_region(seed, |edge| !cdt.is_constraint_edge(edge.fix()));
+            let mut expected = regions.faces(region).to_vec();
+            grown.sort_unstable();
+            expected.sort_unstable();
+            assert_eq!(grown, expected);
+        }
+    }
+
+    #[test]
+    fn test_classify_regions_without_constraints() {
+        let mut cdt = FloatCDT::with_walk_locate();
+        cdt.insert(Point2::new(0.0, 0.0));
+        cdt.insert(Point2::new(1.0, 0.0));
+        cdt.insert(Point2::new(0.0, 1.0));
+        cdt.insert(Point2::new(1.0, 1.0));
+        let regions = cdt.classify_regions();
+        assert_eq!(regions.num_regions(), 1);
+        assert_eq!(regions.faces(0).len(), cdt.num_faces());
+        assert!(!regions.is_inside(1));
+    }
+}
@@ -0,0 +1,1364 @@
+// Copyright 2017 The Spade Developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use crate::boundingrect::BoundingRect;
+use crate::delaunay::*;
+use crate::intersections::segment_intersections;
+use crate::kernels::FloatKernel;
+use crate::point_traits::{PointN, PointNExtensions, TwoDimensional};
+use crate::primitives::{SegmentIntersection, SimpleEdge, SimpleTriangle};
+use crate::rtree::RTree;
+use crate::traits::{HasPosition, SpadeFloat, SpatialObject};
+use crate::validation::validate_point;
+use alloc::collections::BTreeMap;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::fmt;
+use num::{one, zero, Float, FromPrimitive, NumCast, ToPrimitive};
+#[cfg(feature = "serde_serialize")]
+use serde::{Deserialize, Serialize};
+
+/// The maximum number of Newton iterations used to locate a Voronoi vertex.
+const MAX_NEWTON_STEPS: usize = 64;
+
+/// A site of a `SegmentVoronoiDiagram`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde_serialize", derive(Serialize, Deserialize))]
+pub enum VoronoiSite<P> {
+    /// A single point.
+    Point(P),
+    /// An open line segment, given by its two endpoints.
+    Segment(P, P),
+}
+
+impl<P> VoronoiSite<P>
+where
+    P: TwoDimensional,
+    P::Scalar: SpadeFloat,
+{
+    /// Returns the squared distance of a point to this site.
+    pub fn distance2(&self, point: &P) -> P::Scalar {
+        match self {
+            VoronoiSite::Point(p) => p.sub(point).length2(),
+            VoronoiSite::Segment(from, to) => {
+                SimpleEdge::new(from.clone(), to.clone()).distance2(point)
+            }
+        }
+    }
+}
+
+/// Error returned by `SegmentVoronoiDiagram::new` for invalid sites.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SegmentVoronoiError {
+    /// A site has a NaN or infinite coordinate.
+    NonFiniteCoordinate(usize),
+    /// Two sites intersect. Segments may only touch at common endpoints and
+    /// point sites must not lie in the interior of a segment.
+    Intersection(usize, usize),
+}
+
+impl fmt::Display for SegmentVoronoiError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SegmentVoronoiError::NonFiniteCoordinate(site) => {
+                write!(f, "site {} has a NaN or infinite coordinate", site)
+            }
+            SegmentVoronoiError::Intersection(first, second) => {
+                write!(f, "sites {} and {} intersect", first, second)
+            }
+        }
+    }
+}
+
+impl ::core::error::Error for SegmentVoronoiError {}
+
+/// The curve of points equidistant to two sites.
+///
+/// The bisector of two points or two segments consists of straight lines, the
+/// bisector of a point and a segment is a parabola. Points on a curve are
+/// addressed by a parameter `t`, see `point_at`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde_serialize", derive(Serialize, Deserialize))]
+pub enum BisectorCurve<P> {
+    /// The line `origin + direction * t`. `direction` has unit length.
+    Line {
+        /// A point on the line.
+        origin: P,
+        /// The line's unit direction.
+        direction: P,
+    },
+    /// A parabola with the given focus. Its directrix runs through `foot`
+    /// along the unit vector `direction`, `foot` is the focus' projection onto
+    /// the directrix.
+    ///
+    /// The parameter `t` is the position of a point's projection onto the
+    /// directrix, relative to `foot`. The apex lies at `t = 0`.
+    Parabola {
+        /// The parabola's focus.
+        focus: P,
+        /// The projection of the focus onto the directrix.
+        foot: P,
+        /// The directrix' unit direction.
+        direction: P,
+    },
+}
+
+impl<P> BisectorCurve<P>
+where
+    P: TwoDimensional,
+    P::Scalar: SpadeFloat,
+{
+    /// Returns the point of this curve at parameter `t`.
+    pub fn point_at(&self, t: P::Scalar) -> P {
+        match self {
+            BisectorCurve::Line { origin, direction } => origin.add(&direction.mul(t)),
+            BisectorCurve::Parabola {
+                focus,
+                foot,
+                direction,
+            } => {
+                let axis = focus.sub(foot);
+                let h2 = axis.length2();
+                let two = one::<P::Scalar>() + one();
+                foot.add(&direction.mul(t))
+                    .add(&axis.mul((t * t + h2) / (two * h2)))
+            }
+        }
+    }
+
+    /// Returns the parameter of the curve point closest to `point`.
+    ///
+    /// For parabolas, this is the parameter of the curve point with the same
+    /// projection onto the directrix.
+    pub fn parameter(&self, point: &P) -> P::Scalar {
+        match self {
+            BisectorCurve::Line { origin, direction } => point.sub(origin).dot(direction),
+            BisectorCurve::Parabola {
+                foot, direction, ..
+            } => point.sub(foot).dot(direction),
+        }
+    }
+
+    /// Returns the curve's tangent at parameter `t`. The tangent is not
+    /// normalized.
+    pub fn tangent_at(&self, t: P::Scalar) -> P {
+        match self {
+            BisectorCurve::Line { direction, .. } => direction.clone(),
+            BisectorCurve::Parabola {
+                focus,
+                foot,
+                direction,
+            } => {
+                let axis = focus.sub(foot);
+                direction.add(&axis.mul(t / axis.length2()))
+            }
+        }
+    }
+}
+
+/// An edge of a `SegmentVoronoiDiagram`.
+///
+/// The edge is the part of a bisector curve between the parameters
+/// `parameters()`. Unbounded edges have no parameter at their infinite ends.
+/// The cell of the first site lies on the left side of the edge, the cell of
+/// the second site on its right side.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde_serialize", derive(Serialize, Deserialize))]
+#[cfg_attr(
+    feature = "serde_serialize",
+    serde(bound(
+        serialize = "P: Serialize, P::Scalar: Serialize",
+        deserialize = "P: Deserialize<'de>, P::Scalar: Deserialize<'de>"
+    ))
+)]
+pub struct SegmentVoronoiEdge<P: PointN> {
+    sites: [usize; 2],
+    curve: BisectorCurve<P>,
+    start: Option<P::Scalar>,
+    end: Option<P::Scalar>,
+    vertices: [Option<usize>; 2],
+}
+
+impl<P> SegmentVoronoiEdge<P>
+where
+    P: TwoDimensional,
+    P::Scalar: SpadeFloat,
+{
+    /// Returns the indices of the sites on the left and right side of this edge.
+    pub fn sites(&self) -> [usize; 2] {
+        self.sites
+    }
+
+    /// Returns the bisector curve this edge lies on.
+    pub fn curve(&self) -> &BisectorCurve<P> {
+        &self.curve
+    }
+
+    /// Returns the curve parameters of the edge's start and end.
+    ///
+    /// `None` marks an end that extends to infinity.
+    pub fn parameters(&self) -> (Option<P::Scalar>, Option<P::Scalar>) {
+        (self.start, self.end)
+    }
+
+    /// Returns the edge's start point, or `None` if the edge starts at infinity.
+    pub fn from(&self) -> Option<P> {
+        self.start.map(|t| self.curve.point_at(t))
+    }
+
+    /// Returns the edge's end point, or `None` if the edge ends at infinity.
+    pub fn to(&self) -> Option<P> {
+        self.end.map(|t| self.curve.point_at(t))
+    }
+
+    /// Returns the indices of the Voronoi vertices at the edge's start and end.
+    pub fn vertices(&self) -> [Option<usize>; 2] {
+        self.vertices
+    }
+
+    /// Returns `true` if both ends of this edge are Voronoi vertices.
+    pub fn is_bounded(&self) -> bool {
+        self.start.is_some() && self.end.is_some()
+    }
+
+    /// Approximates this edge by a polyline, starting at `from()`.
+    ///
+    /// No point of the edge is further away from the polyline than
+    /// `tolerance`. Straight edges are returned as a single line segment.
+    /// Returns `None` if the edge is unbounded.
+    ///
+    /// # Panics
+    /// Panics if `tolerance` is not positive.
+    pub fn polygonize(&self, tolerance: P::Scalar) -> Option<Vec<P>> {
+        assert!(tolerance > zero(), "Tolerance must be positive");
+        let (start, end) = (self.start?, self.end?);
+        let steps = match &self.curve {
+            BisectorCurve::Line { .. } => 1,
+            BisectorCurve::Parabola { focus, foot, .. } => {
+                // The curvature of a parabola is at most 1 / h, a chord of
+                // parameter length l deviates from it by at most l² / (8h).
+                let h = focus.sub(foot).length2().sqrt();
+                let eight = <P::Scalar as NumCast>::from(8).unwrap();
+                let step = (eight * h * tolerance).sqrt();
+                ((end - start) / step).ceil().to_usize().unwrap_or(1).max(1)
+            }
+        };
+        let step = (end - start) / <P::Scalar as NumCast>::from(steps).unwrap();
+        let mut result: Vec<_> = (0..steps)
+            .map(|i| {
+                let t = start + step * <P::Scalar as NumCast>::from(i).unwrap();
+                self.curve.point_at(t)
+            })
+            .collect();
+        result.push(self.curve.point_at(end));
+        Some(result)
+    }
+}
+
+/// A vertex of a `SegmentVoronoiDiagram`.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde_serialize", derive(Serialize, Deserialize))]
+pub struct SegmentVoronoiVertex<P> {
+    position: P,
+    sites: Vec<usize>,
+}
+
+impl<P: PointN> SegmentVoronoiVertex<P> {
+    /// Returns the vertex's position.
+    pub fn position(&self) -> P {
+        self.position.clone()
+    }
+
+    /// Returns the indices of all sites closest to this vertex, in ascending order.
+    pub fn sites(&self) -> &[usize] {
+        &self.sites
+    }
+}
+
+#[derive(Clone, Debug)]
+struct SiteSample<P> {
+    position: P,
+    site: usize,
+}
+
+impl<P: PointN> HasPosition for SiteSample<P> {
+    type Point = P;
+
+    fn position(&self) -> P {
+        self.position.clone()
+    }
+}
+
+#[derive(Clone, Debug)]
+struct IndexedSite<P> {
+    index: usize,
+    site: VoronoiSite<P>,
+}
+
+impl<P> SpatialObject for IndexedSite<P>
+where
+    P: TwoDimensional,
+    P::Scalar: SpadeFloat,
+{
+    type Point = P;
+
+    fn mbr(&self) -> BoundingRect<P> {
+        match &self.site {
+            VoronoiSite::Point(p) => BoundingRect::from_point(p.clone()),
+            VoronoiSite::Segment(from, to) => BoundingRect::from_corners(from, to),
+        }
+    }
+
+    fn distance2(&self, point: &P) -> P::Scalar {
+        self.site.distance2(point)
+    }
+}
+
+#[derive(Clone, Debug)]
+struct VertexEntry<P> {
+    position: P,
+    index: usize,
+}
+
+impl<P: PointN> HasPosition for VertexEntry<P> {
+    type Point = P;
+
+    fn position(&self) -> P {
+        self.position.clone()
+    }
+}
+
+/// An implicit curve `value(x) = 0` whose gradient is used for Newton steps.
+enum Equation<P: PointN> {
+    /// `normal · x - offset = 0`
+    Linear { normal: P, offset: P::Scalar },
+    /// `|x - focus| - (normal · x - offset) = 0`
+    Parabola {
+        focus: P,
+        normal: P,
+        offset: P::Scalar,
+    },
+}
+
+impl<P> Equation<P>
+where
+    P: TwoDimensional,
+    P::Scalar: SpadeFloat,
+{
+    fn linear(normal: P, offset: P::Scalar) -> Option<Self> {
+        let length = normal.length2().sqrt();
+        if length <= P::Scalar::epsilon() {
+            return None;
+        }
+        Some(Equation::Linear {
+            normal: normal.div(length),
+            offset: offset / length,
+        })
+    }
+
+    fn evaluate(&self, x: &P) -> (P::Scalar, P) {
+        match self {
+            Equation::Linear { normal, offset } => (normal.dot(x) - *offset, normal.clone()),
+            Equation::Parabola {
+                focus,
+                normal,
+                offset,
+            } => {
+                let diff = x.sub(focus);
+                let length = diff.length2().sqrt();
+                let value = length - (normal.dot(x) - *offset);
+                let gradient = if length > zero() {
+                    diff.div(length).sub(normal)
+                } else {
+                    normal.mul(-one::<P::Scalar>())
+                };
+                (value, gradient)
+            }
+        }
+    }
+}
+
+/// A Voronoi diagram of points and line segments.
+///
+/// The cell of a site contains all points that are closer to this site than to
+/// any other site. Cells are separated by edges lying on the sites' bisector
+/// curves: straight lines between two points or two segments and parabolic
+/// arcs between a point and a segment. Edges are stored exactly, as a curve
+/// and a parameter range, and can be approximated by polylines with
+/// `SegmentVoronoiEdge::polygonize` and `cell_polygon`.
+///
+/// Segments are open, their endpoints are point sites of their own. Endpoints that
+/// are not contained in the given sites are appended to the site list. Repeated
+/// point sites and segments of zero length are ignored and have empty cells.
+///
+/// The diagram's topology is found from a Delaunay triangulation of samples
+/// placed along every segment, the positions of its vertices are then solved
+/// for exactly. The sample spacing must be small enough to resolve the
+/// smallest cell features of interest. Cells that extend to infinity have
+/// unbounded edges, see `SegmentVoronoiEdge::parameters`.
+///
+/// # Example
+///
+/// ```
+/// # extern crate nalgebra;
+/// # extern crate spade;
+/// use nalgebra::Point2;
+/// use spade::delaunay::{BisectorCurve, SegmentVoronoiDiagram, VoronoiSite};
+///
+/// # fn main() {
+/// let sites = vec![
+///     VoronoiSite::Segment(Point2::new(0.0, 0.0), Point2::new(4.0, 0.0)),
+///     VoronoiSite::Segment(Point2::new(0.0, 2.0), Point2::new(4.0, 2.0)),
+/// ];
+/// let diagram = SegmentVoronoiDiagram::new(sites, 0.1f64).unwrap();
+/// // Two segments and their four endpoints
+/// assert_eq!(diagram.sites().len(), 6);
+/// // The centerline between both segments
+/// let centerline = diagram.edges_between(0, 1).next().unwrap();
+/// assert!(matches!(centerline.curve(), BisectorCurve::Line { .. }));
+/// let from = centerline.from().unwrap();
+/// let to = centerline.to().unwrap();
+/// assert!((from.y - 1.0).abs() < 1e-10);
+/// assert!(((to.x - from.x).abs() - 4.0).abs() < 1e-10);
+/// # }
+/// ```
+pub struct SegmentVoronoiDiagram<P>
+where
+    P: TwoDimensional,
+    P::Scalar: SpadeFloat + FromPrimitive,
+{
+    sites: Vec<VoronoiSite<P>>,
+    vertices: Vec<SegmentVoronoiVertex<P>>,
+    edges: Vec<SegmentVoronoiEdge<P>>,
+    tree: RTree<IndexedSite<P>>,
+    scale: P::Scalar,
+    epsilon: P::Scalar,
+}
+
+impl<P> SegmentVoronoiDiagram<P>
+where
+    P: TwoDimensional,
+    P::Scalar: SpadeFloat + FromPrimitive,
+{
+    /// Creates the Voronoi diagram of a set of sites.
+    ///
+    /// Segments are sampled such that the distance between two adjacent samples
+    /// does not exceed `tolerance`.
+    ///
+    /// Returns an error if a site has a non finite coordinate, if two segments
+    /// intersect anywhere but at a common endpoint or if a point site lies in
+    /// the interior of a segment. If several sites are invalid, the error with
+    /// the smallest indices is returned.
+    ///
+    /// # Panics
+    /// Panics if `tolerance` is not positive.
+    pub fn new(
+        sites: Vec<VoronoiSite<P>>,
+        tolerance: P::Scalar,
+    ) -> Result<Self, SegmentVoronoiError> {
+        assert!(tolerance > zero(), "Tolerance must be positive");
+        check_sites(&sites)?;
+        let mut samples = FloatDelaunayTriangulation::with_tree_locate();
+        let sites = insert_samples(&mut samples, sites, tolerance);
+        let mut bounds: Option<BoundingRect<P>> = None;
+        let mut active = Vec::new();
+        for (index, site) in sites.iter().enumerate() {
+            let is_active = match site {
+                VoronoiSite::Point(p) => samples.locate_vertex(p).is_some_and(|v| v.site == index),
+                VoronoiSite::Segment(from, to) => from != to,
+            };
+            if is_active {
+                let entry = IndexedSite {
+                    index,
+                    site: site.clone(),
+                };
+                match &mut bounds {
+                    Some(bounds) => {
+                        bounds.add_point(entry.mbr().lower());
+                        bounds.add_point(entry.mbr().upper());
+                    }
+                    None => bounds = Some(entry.mbr()),
+                }
+                active.push(entry);
+            }
+        }
+        let scale = bounds.map_or(zero(), |b| b.lower().sub(&b.upper()).length2().sqrt());
+        let scale = if scale > zero() { scale } else { one() };
+        let mut diagram = SegmentVoronoiDiagram {
+            sites,
+            vertices: Vec::new(),
+            edges: Vec::new(),
+            tree: RTree::bulk_load(active),
+            scale,
+            epsilon: scale * P::Scalar::epsilon().sqrt(),
+        };
+        let pairs = diagram.calculate_vertices(&samples);
+        diagram.edges = diagram.calculate_edges(pairs);
+        Ok(diagram)
+    }
+
+    /// Returns all sites, including the appended segment endpoints.
+    pub fn sites(&self) -> &[VoronoiSite<P>] {
+        &self.sites
+    }
+
+    /// Returns all Voronoi vertices.
+    pub fn vertices(&self) -> &[SegmentVoronoiVertex<P>] {
+        &self.vertices
+    }
+
+    /// Returns all Voronoi edges.
+    pub fn edges(&self) -> &[SegmentVoronoiEdge<P>] {
+        &self.edges
+    }
+
+    /// Returns all Voronoi edges bounding the cell of a site.
+    pub fn cell_edges(&self, site: usize) -> impl Iterator<Item = &SegmentVoronoiEdge<P>> {
+        self.edges
+            .iter()
+            .filter(move |edge| edge.sites.contains(&site))
+    }
+
+    /// Returns all Voronoi edges separating the cells of two sites.
+    pub fn edges_between(
+        &self,
+        site0: usize,
+        site1: usize,
+    ) -> impl Iterator<Item = &SegmentVoronoiEdge<P>> {
+        self.edges
+            .iter()
+            .filter(move |edge| edge.sites == [site0, site1] || edge.sites == [site1, site0])
+    }
+
+    /// Approximates the boundary of a site's cell by a counterclockwise polygon.
+    ///
+    /// Every edge is polygonized with `SegmentVoronoiEdge::polygonize`, the
+    /// first point of the polygon is not repeated at its end. Returns `None`
+    /// if the cell is unbounded or empty.
+    ///
+    /// # Panics
+    /// Panics if `tolerance` is not positive.
+    pub fn cell_polygon(&self, site: usize, tolerance: P::Scalar) -> Option<Vec<P>> {
+        // Walk along every edge such that the cell lies on its left side
+        let mut pieces = Vec::new();
+        for edge in self.cell_edges(site) {
+            let mut points = edge.polygonize(tolerance)?;
+            let [mut from, mut to] = edge.vertices;
+            if edge.sites[0] != site {
+                points.reverse();
+                ::core::mem::swap(&mut from, &mut to);
+            }
+            pieces.push((from?, to?, points));
+        }
+        let (first, mut current, points) = pieces.pop()?;
+        let mut result = points;
+        result.pop();
+        while current != first {
+            let next = pieces.iter().position(|(from, _, _)| *from == current)?;
+            let (_, to, points) = pieces.swap_remove(next);
+            result.extend(points.into_iter().skip(1));
+            result.pop();
+            current = to;
+        }
+        if pieces.is_empty() {
+            Some(result)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the index of the site whose cell contains a point.
+    ///
+    /// Points on a cell boundary are assigned to point sites before segments
+    /// and to sites with smaller indices before larger ones. Returns `None`
+    /// if the diagram has no sites.
+    pub fn nearest_site(&self, point: &P) -> Option<usize> {
+        let mut iterator = self.tree.nearest_neighbor_iterator(point);
+        let first = iterator.next()?;
+        let distance2 = first.distance2(point);
+        let key = |site: &IndexedSite<P>| match site.site {
+            VoronoiSite::Point(_) => (0, site.index),
+            VoronoiSite::Segment(..) => (1, site.index),
+        };
+        let mut best = first;
+        for site in iterator {
+            if site.distance2(point) > distance2 {
+                break;
+            }
+            if key(site) < key(best) {
+                best = site;
+            }
+        }
+        Some(best.index)
+    }
+
+    /// Returns the distance of a point to its nearest site.
+    ///
+    /// Returns `None` if the diagram has no sites.
+    pub fn clearance(&self, point: &P) -> Option<P::Scalar> {
+        self.tree
+            .nearest_neighbor(point)
+            .map(|site| site.distance2(point).sqrt())
+    }
+
+    fn calculate_vertices(
+        &mut self,
+        samples: &FloatDelaunayTriangulation<SiteSample<P>, DelaunayTreeLocate<P>>,
+    ) -> BTreeMap<(usize, usize), Vec<usize>> {
+        let mut pairs: BTreeMap<_, Vec<usize>> = BTreeMap::new();
+        let mut entries = RTree::new();
+        let mut candidates = Vec::new();
+        for face in samples.triangles() {
+            let [v0, v1, v2] = face.as_triangle();
+            candidates.push(([v0.site, v1.site, v2.site], circumcenter(face)));
+        }
+        let infinite = samples.infinite_face().fix();
+        for edge in samples.edges() {
+            let (a, b) = (edge.from().site, edge.to().site);
+            if a == b {
+                continue;
+            }
+            pairs.entry((a.min(b), a.max(b))).or_default();
+            // A vertex's sites may be spread over both faces next to an edge
+            let faces = [edge.face(), edge.sym().face()];
+            for face in faces.iter().filter(|face| face.fix() != infinite) {
+                let guess = circumcenter(*face);
+                for other in faces.iter().filter(|face| face.fix() != infinite) {
+                    for v in other.as_triangle().iter() {
+                        candidates.push(([a, b, v.site], guess.clone()));
+                    }
+                }
+            }
+        }
+        for (triple, guess) in candidates {
+            let [a, b, c] = triple;
+            if a == b || a == c || b == c {
+                continue;
+            }
+            let equations = [
+                self.equation(a, b, &guess),
+                self.equation(a, c, &guess),
+                self.equation(b, c, &guess),
+            ];
+            // Bisectors meeting at a small angle converge slowly, try the
+            // best conditioned pair of equations first
+            let mut choices: Vec<_> = [(0, 1), (0, 2), (1, 2)]
+                .iter()
+                .filter_map(|&(i, j)| {
+                    let first = equations[i].as_ref()?;
+                    let second = equations[j].as_ref()?;
+                    let (_, gradient0) = first.evaluate(&guess);
+                    let (_, gradient1) = second.evaluate(&guess);
+                    let condition = cross(&gradient0, &gradient1).abs()
+                        / (gradient0.length2() * gradient1.length2()).sqrt();
+                    Some((condition, first, second))
+                })
+                .collect();
+            choices.sort_by(|l, r| r.0.partial_cmp(&l.0).unwrap_or(Ordering::Equal));
+            let found = choices.into_iter().find_map(|(_, first, second)| {
+                let position = self.solve(first, second, guess.clone())?;
+                let sites = self.vertex_sites(&position, triple)?;
+                Some((position, sites))
+            });
+            if let Some((position, sites)) = found {
+                self.insert_vertex(&mut entries, position, sites);
+            }
+        }
+        for (index, vertex) in self.vertices.iter().enumerate() {
+            for (i, &a) in vertex.sites.iter().enumerate() {
+                for &b in &vertex.sites[i + 1..] {
+                    pairs.entry((a, b)).or_default().push(index);
+                }
+            }
+        }
+        pairs
+    }
+
+    fn insert_vertex(
+        &mut self,
+        entries: &mut RTree<VertexEntry<P>>,
+        position: P,
+        sites: Vec<usize>,
+    ) {
+        let existing = entries
+            .nearest_neighbor(&position)
+            .filter(|entry| entry.position.sub(&position).length2() <= self.epsilon * self.epsilon)
+            .map(|entry| entry.index);
+        match existing {
+            Some(index) => {
+                let vertex = &mut self.vertices[index];
+                vertex.sites.extend(sites);
+                vertex.sites.sort_unstable();
+                vertex.sites.dedup();
+            }
+            None => {
+                entries.insert(VertexEntry {
+                    position: position.clone(),
+                    index: self.vertices.len(),
+                });
+                self.vertices.push(SegmentVoronoiVertex { position, sites });
+            }
+        }
+    }
+
+    fn calculate_edges(
+        &self,
+        pairs: BTreeMap<(usize, usize), Vec<usize>>,
+    ) -> Vec<SegmentVoronoiEdge<P>> {
+        let mut result = Vec::new();
+        for ((a, b), vertices) in pairs {
+            let curves = self.bisectors(a, b);
+            for curve in &curves {
+                // Vertices at the intersection of two bisector lines lie on both
+                let mut parameters: Vec<_> = vertices
+                    .iter()
+                    .map(|&vertex| (curve.parameter(&self.vertices[vertex].position), vertex))
+                    .filter(|&(t, vertex)| {
+                        let position = &self.vertices[vertex].position;
+                        curves.len() == 1
+                            || curve.point_at(t).sub(position).length2()
+                                <= self.epsilon * self.epsilon
+                    })
+                    .collect();
+                parameters.sort_by(|l, r| l.0.partial_cmp(&r.0).unwrap());
+                let mut bounds = Vec::with_capacity(parameters.len() + 1);
+                bounds.push(None);
+                bounds.extend(parameters.into_iter().map(Some));
+                bounds.push(None);
+                for window in bounds.windows(2) {
+                    if let Some(edge) = self.edge_on_interval(a, b, curve, window[0], window[1]) {
+                        result.push(edge);
+                    }
+                }
+            }
+        }
+        result
+    }
+
+    fn edge_on_interval(
+        &self,
+        a: usize,
+        b: usize,
+        curve: &BisectorCurve<P>,
+        start: Option<(P::Scalar, usize)>,
+        end: Option<(P::Scalar, usize)>,
+    ) -> Option<SegmentVoronoiEdge<P>> {
+        let two = one::<P::Scalar>() + one();
+        let t = match (start, end) {
+            (None, None) => zero(),
+            (None, Some((end, _))) => end - self.scale,
+            (Some((start, _)), None) => start + self.scale,
+            (Some((start, _)), Some((end, _))) => {
+                if end - start <= self.epsilon {
+                    return None;
+                }
+                (start + end) / two
+            }
+        };
+        let position = curve.point_at(t);
+        if !self.is_on_bisector(&position, a, b) {
+            return None;
+        }
+        let tangent = curve.tangent_at(t);
+        let tangent = tangent.div(tangent.length2().sqrt());
+        let side = |site: usize, midpoint: bool| {
+            let reference = match &self.sites[site] {
+                VoronoiSite::Point(p) => p.clone(),
+                VoronoiSite::Segment(from, to) if midpoint => from.add(to).div(two),
+                VoronoiSite::Segment(from, to) => {
+                    SimpleEdge::new(from.clone(), to.clone()).nearest_point(&position)
+                }
+            };
+            cross(&tangent, &reference.sub(&position))
+        };
+        let (mut side_a, mut side_b) = (side(a, false), side(b, false));
+        if (side_a - side_b).abs() <= self.epsilon {
+            // A segment and its endpoint whose bisector is perpendicular to the segment
+            side_a = side(a, true);
+            side_b = side(b, true);
+        }
+        let sites = if side_a > side_b { [a, b] } else { [b, a] };
+        Some(SegmentVoronoiEdge {
+            sites,
+            curve: curve.clone(),
+            start: start.map(|s| s.0),
+            end: end.map(|e| e.0),
+            vertices: [start.map(|s| s.1), end.map(|e| e.1)],
+        })
+    }
+
+    fn is_on_bisector(&self, position: &P, a: usize, b: usize) -> bool {
+        let distance_a = self.sites[a].distance2(position).sqrt();
+        let distance_b = self.sites[b].distance2(position).sqrt();
+        if (distance_a - distance_b).abs() > self.epsilon
+            || !self.is_in_slab(a, position)
+            || !self.is_in_slab(b, position)
+        {
+            return false;
+        }
+        self.tree
+            .nearest_neighbor_iterator(position)
+            .find(|site| site.index != a && site.index != b)
+            .is_none_or(|site| site.distance2(position).sqrt() >= distance_a - self.epsilon)
+    }
+
+    /// Returns `true` if a point's projection onto a segment lies on the
+    /// segment. Always returns `true` for point sites.
+    fn is_in_slab(&self, site: usize, position: &P) -> bool {
+        match &self.sites[site] {
+            VoronoiSite::Point(_) => true,
+            VoronoiSite::Segment(from, to) => {
+                let dir = to.sub(from);
+                let length = dir.length2().sqrt();
+                let s = position.sub(from).dot(&dir) / length;
+                -self.epsilon <= s && s <= length + self.epsilon
+            }
+        }
+    }
+
+    /// Returns all sites closest to a vertex candidate, or `None` if the
+    /// candidate is not equidistant to the given sites or if another site
+    /// lies closer.
+    fn vertex_sites(&self, position: &P, triple: [usize; 3]) -> Option<Vec<usize>> {
+        let distances = triple.map(|site| self.sites[site].distance2(position).sqrt());
+        let max = distances.iter().fold(distances[0], |l, r| l.max(*r));
+        let min = distances.iter().fold(distances[0], |l, r| l.min(*r));
+        if max - min > self.epsilon || triple.iter().any(|&s| !self.is_in_slab(s, position)) {
+            return None;
+        }
+        let mut sites = Vec::new();
+        for site in self.tree.nearest_neighbor_iterator(position) {
+            let distance = site.distance2(position).sqrt();
+            if distance > max + self.epsilon {
+                break;
+            }
+            if distance < min - self.epsilon {
+                return None;
+            }
+            if self.is_in_slab(site.index, position) {
+                sites.push(site.index);
+            }
+        }
+        sites.sort_unstable();
+        Some(sites)
+    }
+
+    /// Returns an implicit equation for the bisector of two sites that is
+    /// valid near `guess`.
+    fn equation(&self, a: usize, b: usize, guess: &P) -> Option<Equation<P>> {
+        let two = one::<P::Scalar>() + one();
+        match (&self.sites[a], &self.sites[b]) {
+            (VoronoiSite::Point(p), VoronoiSite::Point(q)) => {
+                let normal = q.sub(p);
+                let offset = normal.dot(&p.add(q).div(two));
+                Equation::linear(normal, offset)
+            }
+            (VoronoiSite::Point(p), VoronoiSite::Segment(from, to))
+            | (VoronoiSite::Segment(from, to), VoronoiSite::Point(p)) => {
+                let (dir, normal) = directions(from, to);
+                let offset = normal.dot(from);
+                let side = normal.dot(p) - offset;
+                if side.abs() <= self.epsilon {
+                    Equation::linear(dir.clone(), dir.dot(p))
+                } else {
+                    let sign = side.signum();
+                    Some(Equation::Parabola {
+                        focus: p.clone(),
+                        normal: normal.mul(sign),
+                        offset: offset * sign,
+                    })
+                }
+            }
+            (VoronoiSite::Segment(from0, to0), VoronoiSite::Segment(from1, to1)) => {
+                let (_, normal0) = directions(from0, to0);
+                let (_, normal1) = directions(from1, to1);
+                let (offset0, offset1) = (normal0.dot(from0), normal1.dot(from1));
+                let sign = |s: P::Scalar| {
+                    if s < zero() {
+                        -one::<P::Scalar>()
+                    } else {
+                        one()
+                    }
+                };
+                let sign0 = sign(normal0.dot(guess) - offset0);
+                let sign1 = sign(normal1.dot(guess) - offset1);
+                Equation::linear(
+                    normal0.mul(sign0).sub(&normal1.mul(sign1)),
+                    offset0 * sign0 - offset1 * sign1,
+                )
+            }
+        }
+    }
+
+    /// Solves for a common point of two bisectors with Newton's method.
+    fn solve(&self, first: &Equation<P>, second: &Equation<P>, guess: P) -> Option<P> {
+        let mut x = guess;
+        let limit = self.scale * P::Scalar::epsilon() * <P::Scalar as NumCast>::from(16).unwrap();
+        for _ in 0..MAX_NEWTON_STEPS {
+            let (value0, gradient0) = first.evaluate(&x);
+            let (value1, gradient1) = second.evaluate(&x);
+            let det = cross(&gradient0, &gradient1);
+            if det.abs() <= P::Scalar::epsilon() {
+                return None;
+            }
+            let step = xy::<P>(
+                (value0 * *gradient1.nth(1) - value1 * *gradient0.nth(1)) / det,
+                (value1 * *gradient0.nth(0) - value0 * *gradient1.nth(0)) / det,
+            );
+            x = x.sub(&step);
+            if validate_point(&x).is_err() {
+                return None;
+            }
+            if step.length2().sqrt() <= limit {
+                break;
+            }
+        }
+        Some(x)
+    }
+
+    /// Returns the curves of points equidistant to two sites.
+    fn bisectors(&self, a: usize, b: usize) -> Vec<BisectorCurve<P>> {
+        let two = one::<P::Scalar>() + one();
+        match (&self.sites[a], &self.sites[b]) {
+            (VoronoiSite::Point(p), VoronoiSite::Point(q)) => {
+                if p == q {
+                    return Vec::new();
+                }
+                let dir = perpendicular(&q.sub(p));
+                vec![BisectorCurve::Line {
+                    origin: p.add(q).div(two),
+                    direction: dir.div(dir.length2().sqrt()),
+                }]
+            }
+            (VoronoiSite::Point(p), VoronoiSite::Segment(from, to))
+            | (VoronoiSite::Segment(from, to), VoronoiSite::Point(p)) => {
+                let (dir, normal) = directions(from, to);
+                let side = normal.dot(&p.sub(from));
+                if side.abs() <= self.epsilon {
+                    vec![BisectorCurve::Line {
+                        origin: p.clone(),
+                        direction: normal,
+                    }]
+                } else {
+                    vec![BisectorCurve::Parabola {
+                        focus: p.clone(),
+                        foot: p.sub(&normal.mul(side)),
+                        direction: dir,
+                    }]
+                }
+            }
+            (VoronoiSite::Segment(from0, to0), VoronoiSite::Segment(from1, to1)) => {
+                let (dir0, normal0) = directions(from0, to0);
+                let (dir1, _) = directions(from1, to1);
+                let det = cross(&dir0, &dir1);
+                if det.abs() <= P::Scalar::epsilon().sqrt() {
+                    let distance = normal0.dot(&from1.sub(from0));
+                    if distance.abs() <= self.epsilon {
+                        return Vec::new();
+                    }
+                    return vec![BisectorCurve::Line {
+                        origin: from0.add(&normal0.mul(distance / two)),
+                        direction: dir0,
+                    }];
+                }
+                let s = cross(&from1.sub(from0), &dir1) / det;
+                let origin = from0.add(&dir0.mul(s));
+                [dir0.add(&dir1), dir0.sub(&dir1)]
+                    .iter()
+                    .map(|dir| BisectorCurve::Line {
+                        origin: origin.clone(),
+                        direction: dir.div(dir.length2().sqrt()),
+                    })
+                    .collect()
+            }
+        }
+    }
+}
+
+fn xy<P: TwoDimensional>(x: P::Scalar, y: P::Scalar) -> P {
+    let mut result = P::new();
+    *result.nth_mut(0) = x;
+    *result.nth_mut(1) = y;
+    result
+}
+
+fn cross<P: TwoDimensional>(a: &P, b: &P) -> P::Scalar {
+    a.nth(0).clone() * b.nth(1).clone() - a.nth(1).clone() * b.nth(0).clone()
+}
+
+fn perpendicular<P: TwoDimensional>(v: &P) -> P {
+    xy(-v.nth(1).clone(), v.nth(0).clone())
+}
+
+/// Returns a segment's unit direction and its left unit normal.
+fn directions<P>(from: &P, to: &P) -> (P, P)
+where
+    P: TwoDimensional,
+    P::Scalar: SpadeFloat,
+{
+    let dir = to.sub(from);
+    let dir = dir.div(dir.length2().sqrt());
+    let normal = perpendicular(&dir);
+    (dir, normal)
+}
+
+fn check_sites<P>(sites: &[VoronoiSite<P>]) -> Result<(), SegmentVoronoiError>
+where
+    P: TwoDimensional,
+    P::Scalar: SpadeFloat + FromPrimitive,
+{
+    for (index, site) in sites.iter().enumerate() {
+        let is_finite = match site {
+            VoronoiSite::Point(p) => validate_point(p).is_ok(),
+            VoronoiSite::Segment(from, to) => {
+                validate_point(from).is_ok() && validate_point(to).is_ok()
+            }
+        };
+        if !is_finite {
+            return Err(SegmentVoronoiError::NonFiniteCoordinate(index));
+        }
+    }
+    let (indices, segments): (Vec<_>, Vec<_>) = sites
+        .iter()
+        .enumerate()
+        .filter_map(|(index, site)| match site {
+            VoronoiSite::Segment(from, to) if from != to => {
+                Some((index, SimpleEdge::new(from.clone(), to.clone())))
+            }
+            _ => None,
+        })
+        .unzip();
+    let is_endpoint =
+        |segment: &SimpleEdge<P>, point: &P| *point == segment.from || *point == segment.to;
+    let mut first_error: Option<(usize, usize)> = None;
+    let mut report = |a: usize, b: usize| {
+        let pair = (a.min(b), a.max(b));
+        if first_error.is_none_or(|error| pair < error) {
+            first_error = Some(pair);
+        }
+    };
+    for (a, b, intersection) in segment_intersections::<FloatKernel, _>(&segments) {
+        let is_common_endpoint = match intersection {
+            SegmentIntersection::Touching(point) => {
+                is_endpoint(&segments[a], &point) && is_endpoint(&segments[b], &point)
+            }
+            _ => false,
+        };
+        if !is_common_endpoint {
+            report(indices[a], indices[b]);
+        }
+    }
+    for (index, site) in sites.iter().enumerate() {
+        if let VoronoiSite::Point(p) = site {
+            for (segment, &segment_index) in segments.iter().zip(&indices) {
+                let is_interior = !is_endpoint(segment, p)
+                    && segment.is_projection_on_edge(p)
+                    && segment.side_query::<FloatKernel>(p).is_on_line();
+                if is_interior {
+                    report(index, segment_index);
+                }
+            }
+        }
+    }
+    match first_error {
+        Some((a, b)) => Err(SegmentVoronoiError::Intersection(a, b)),
+        None => Ok(()),
+    }
+}
+
+/// Samples all sites and appends segment endpoints that are no point sites.
+fn insert_samples<P>(
+    samples: &mut FloatDelaunayTriangulation<SiteSample<P>, DelaunayTreeLocate<P>>,
+    mut sites: Vec<VoronoiSite<P>>,
+    tolerance: P::Scalar,
+) -> Vec<VoronoiSite<P>>
+where
+    P: TwoDimensional,
+    P::Scalar: SpadeFloat + FromPrimitive,
+{
+    let mut endpoints = Vec::new();
+    for (index, site) in sites.iter().enumerate() {
+        match site {
+            VoronoiSite::Point(p) => insert_sample(samples, p.clone(), index),
+            VoronoiSite::Segment(from, to) => {
+                endpoints.push(from.clone());
+                endpoints.push(to.clone());
+            }
+        }
+    }
+    for endpoint in endpoints {
+        if samples.locate_vertex(&endpoint).is_none() {
+            sites.push(VoronoiSite::Point(endpoint.clone()));
+            insert_sample(samples, endpoint, sites.len() - 1);
+        }
+    }
+    for (index, site) in sites.iter().enumerate() {
+        if let VoronoiSite::Segment(from, to) = site {
+            let length = from.sub(to).length2().sqrt();
+            let steps = (length / tolerance).ceil().to_usize().unwrap_or(1).max(2);
+            let step = one::<P::Scalar>() / <P::Scalar as NumCast>::from(steps).unwrap();
+            let dir = to.sub(from);
+            for i in 1..steps {
+                let t = step * <P::Scalar as NumCast>::from(i).unwrap();
+                insert_sample(samples, from.add(&dir.mul(t)), index);
+            }
+        }
+    }
+    sites
+}
+
+fn insert_sample<P>(
+    samples: &mut FloatDelaunayTriangulation<SiteSample<P>, DelaunayTreeLocate<P>>,
+    position: P,
+    site: usize,
+) where
+    P: TwoDimensional,
+    P::Scalar: SpadeFloat + FromPrimitive,
+{
+    // The first sample at a position determines its site
+    if samples.locate_vertex(&position).is_none() {
+        samples.insert(SiteSample { position, site });
+    }
+}
+
+fn circumcenter<P>(face: FaceHandle<SiteSample<P>>) -> P
+where
+    P: TwoDimensional,
+    P::Scalar: SpadeFloat,
+{
+    let [v0, v1, v2] = face.as_triangle();
+    SimpleTriangle::new(v0.position(), v1.position(), v2.position()).circumcenter()
+}
+
+#[cfg(test)]
+mod test {
+    use super::{BisectorCurve, SegmentVoronoiDiagram, SegmentVoronoiError, VoronoiSite};
+    use cgmath::Point2;
+
+    fn distance(site: &VoronoiSite<Point2<f64>>, p: &Point2<f64>) -> f64 {
+        site.distance2(p).sqrt()
+    }
+
+    #[test]
+    fn test_parallel_segments() {
+        let sites = vec![
+            VoronoiSite::Segment(Point2::new(0.0, 0.0), Point2::new(4.0, 0.0)),
+            VoronoiSite::Segment(Point2::new(0.0, 2.0), Point2::new(4.0, 2.0)),
+        ];
+        let diagram = SegmentVoronoiDiagram::new(sites, 0.1f64).unwrap();
+        assert_eq!(diagram.sites().len(), 6);
+        let centerline: Vec<_> = diagram.edges_between(0, 1).collect();
+        assert_eq!(centerline.len(), 1);
+        let (from, to) = (centerline[0].from().unwrap(), centerline[0].to().unwrap());
+        assert_relative_eq!(from.y, 1.0, epsilon = 1e-10);
+        assert_relative_eq!(to.y, 1.0, epsilon = 1e-10);
+        assert_relative_eq!(from.x.min(to.x), 0.0, epsilon = 1e-10);
+        assert_relative_eq!(from.x.max(to.x), 4.0, epsilon = 1e-10);
+        // The segment's cell lies on the edge's left side
+        let left = if from.x < to.x { 1 } else { 0 };
+        assert_eq!(centerline[0].sites()[0], left);
+        // Both corner vertices are shared by the segments and two endpoints
+        assert_eq!(diagram.vertices().len(), 2);
+        for vertex in diagram.vertices() {
+            assert_eq!(vertex.sites().len(), 4);
+        }
+        assert_eq!(diagram.nearest_site(&Point2::new(2.0, 0.3)), Some(0));
+        assert_eq!(diagram.nearest_site(&Point2::new(2.0, 1.7)), Some(1));
+        assert_eq!(diagram.nearest_site(&Point2::new(-1.0, -0.1)), Some(2));
+        assert_relative_eq!(diagram.clearance(&Point2::new(2.0, 0.5)).unwrap(), 0.5);
+        // All cells extend to infinity
+        for site in 0..diagram.sites().len() {
+            assert_eq!(diagram.cell_polygon(site, 0.1), None);
+        }
+    }
+
+    #[test]
+    fn test_point_and_segment() {
+        let sites: Vec<VoronoiSite<Point2<f64>>> = vec![
+            VoronoiSite::Point(Point2::new(0.0, 2.0)),
+            VoronoiSite::Segment(Point2::new(-3.0, 0.0), Point2::new(3.0, 0.0)),
+        ];
+        let diagram = SegmentVoronoiDiagram::new(sites.clone(), 0.5).unwrap();
+        let arcs: Vec<_> = diagram.edges_between(0, 1).collect();
+        assert_eq!(arcs.len(), 1);
+        let arc = arcs[0];
+        match *arc.curve() {
+            BisectorCurve::Parabola { focus, foot, .. } => {
+                assert_eq!(focus, Point2::new(0.0, 2.0));
+                assert_relative_eq!(
+                    distance(&VoronoiSite::Point(foot), &Point2::new(0.0, 0.0)),
+                    0.0,
+                    epsilon = 1e-12
+                );
+            }
+            _ => panic!("Expected a parabola"),
+        }
+        // The parabola's apex
+        let apex = arc.curve().point_at(0.0);
+        assert_relative_eq!(apex.x, 0.0, epsilon = 1e-12);
+        assert_relative_eq!(apex.y, 1.0, epsilon = 1e-12);
+        // Every point on the parabolic arc is equidistant to both sites
+        let points = arc.polygonize(1e-3).unwrap();
+        assert!(points.len() > 10);
+        for p in &points {
+            assert_relative_eq!(
+                distance(&sites[0], p),
+                distance(&sites[1], p),
+                epsilon = 1e-9
+            );
+        }
+        // The arc ends above the segment's endpoints
+        let (from, to) = (arc.from().unwrap(), arc.to().unwrap());
+        assert_relative_eq!(from.x.abs(), 3.0, epsilon = 1e-9);
+        assert_relative_eq!(to.x.abs(), 3.0, epsilon = 1e-9);
+        assert_relative_eq!(from.y, 13.0 / 4.0, epsilon = 1e-9);
+    }
+
+    #[test]
+    fn test_bounded_cell() {
+        let corners = [
+            Point2::new(-2.0, -2.0),
+            Point2::new(2.0, -2.0),
+            Point2::new(2.0, 2.0),
+            Point2::new(-2.0, 2.0),
+        ];
+        let mut sites = vec![VoronoiSite::Point(Point2::new(0.5, 0.0))];
+        for i in 0..4 {
+            sites.push(VoronoiSite::Segment(corners[i], corners[(i + 1) % 4]));
+        }
+        let diagram = SegmentVoronoiDiagram::new(sites.clone(), 0.2).unwrap();
+        assert_eq!(diagram.cell_edges(0).count(), 4);
+        let polygon = diagram.cell_polygon(0, 1e-3).unwrap();
+        assert!(polygon.len() > 20);
+        let mut area = 0.0;
+        for (i, p) in polygon.iter().enumerate() {
+            let q = polygon[(i + 1) % polygon.len()];
+            area += p.x * q.y - q.x * p.y;
+            let nearest = (1..5)
+                .map(|s| distance(&sites[s], p))
+                .fold(f64::INFINITY, f64::min);
+            assert_relative_eq!(distance(&sites[0], p), nearest, epsilon = 1e-9);
+        }
+        // Counterclockwise
+        assert!(area > 0.0);
+    }
+
+    #[test]
+    fn test_shared_endpoints() {
+        let sites = vec![
+            VoronoiSite::Point(Point2::new(1.0, 1.0)),
+            VoronoiSite::Segment(Point2::new(0.0, 0.0), Point2::new(1.0, 1.0)),
+            VoronoiSite::Segment(Point2::new(1.0, 1.0), Point2::new(2.0, 0.0)),
+        ];
+        let diagram = SegmentVoronoiDiagram::new(sites, 0.2).unwrap();
+        // Only (0, 0) and (2, 0) are added
+        assert_eq!(diagram.sites().len(), 5);
+        assert_eq!(diagram.nearest_site(&Point2::new(1.0, 1.0)), Some(0));
+        assert!(diagram.cell_edges(1).count() > 0);
+        let empty = SegmentVoronoiDiagram::<Point2<f64>>::new(Vec::new(), 1.0).unwrap();
+        assert_eq!(empty.nearest_site(&Point2::new(0.0, 0.0)), None);
+        assert!(empty.edges().is_empty());
+    }
+
+    #[test]
+    fn test_two_points() {
+        let sites = vec![
+            VoronoiSite::Point(Point2::new(0.0, 0.0)),
+            VoronoiSite::Point(Point2::new(2.0, 0.0)),
+            VoronoiSite::Point(Point2::new(0.0, 0.0)),
+        ];
+        let diagram = SegmentVoronoiDiagram::new(sites, 1.0).unwrap();
+        assert_eq!(diagram.edges().len(), 1);
+        let edge = &diagram.edges()[0];
+        assert_eq!(edge.parameters(), (None, None));
+        assert!(!edge.is_bounded());
+        assert_eq!(edge.polygonize(1.0), None);
+        assert_eq!(edge.curve().point_at(0.0), Point2::new(1.0, 0.0));
+        // The repeated point has an empty cell
+        assert_eq!(diagram.cell_edges(2).count(), 0);
+        assert_eq!(diagram.nearest_site(&Point2::new(0.0, 0.0)), Some(0));
+    }
+
+    #[test]
+    fn test_edges_are_equidistant() {
+        let sites = vec![
+            VoronoiSite::Segment(Point2::new(0.0, 0.0), Point2::new(3.0, 1.0)),
+            VoronoiSite::Segment(Point2::new(3.0, 1.0), Point2::new(1.0, 3.0)),
+            VoronoiSite::Segment(Point2::new(-1.0, 2.0), Point2::new(0.5, 4.0)),
+            VoronoiSite::Segment(Point2::new(4.0, -1.0), Point2::new(4.5, 3.0)),
+            VoronoiSite::Point(Point2::new(1.5, 1.5)),
+            VoronoiSite::Point(Point2::new(-0.5, 0.5)),
+        ];
+        let diagram = SegmentVoronoiDiagram::new(sites, 0.1f64).unwrap();
+        let sites = diagram.sites();
+        assert!(diagram.edges().len() > 10);
+        for edge in diagram.edges() {
+            let (start, end) = edge.parameters();
+            let start = start.unwrap_or_else(|| end.unwrap() - 10.0);
+            let end = end.unwrap_or(start + 10.0);
+            for i in 0..=10 {
+                let t = start + (end - start) * f64::from(i) / 10.0;
+                let p = edge.curve().point_at(t);
+                let [d0, d1] = edge.sites().map(|site| distance(&sites[site], &p));
+                assert_relative_eq!(d0, d1, epsilon = 1e-9);
+                for site in sites {
+                    assert!(distance(site, &p) >= d0 - 1e-9);
+                }
+            }
+        }
+        for vertex in diagram.vertices() {
+            let p = vertex.position();
+            let d = distance(&sites[vertex.sites()[0]], &p);
+            assert!(vertex.sites().len() >= 3);
+            for &site in vertex.sites() {
+                assert_relative_eq!(distance(&sites[site], &p), d, epsilon = 1e-9);
+            }
+        }
+    }
+
+    #[test]
+    fn test_crossing_segments() {
+        let sites = vec![
+            VoronoiSite::Segment(Point2::new(0.0, 0.0), Point2::new(2.0, 2.0)),
+            VoronoiSite::Segment(Point2::new(0.0, 2.0), Point2::new(2.0, 0.0)),
+        ];
+        assert_eq!(
+            SegmentVoronoiDiagram::new(sites, 0.1).err(),
+            Some(SegmentVoronoiError::Intersection(0, 1))
+        );
+    }
+
+    #[test]
+    fn test_segment_ending_on_other_segment() {
+        let sites = vec![
+            VoronoiSite::Point(Point2::new(5.0, 5.0)),
+            VoronoiSite::Segment(Point2::new(0.0, 0.0), Point2::new(2.0, 0.0)),
+            VoronoiSite::Segment(Point2::new(1.0, 0.0), Point2::new(1.0, 2.0)),
+        ];
+        assert_eq!(
+            SegmentVoronoiDiagram::new(sites, 0.1).err(),
+            Some(SegmentVoronoiError::Intersection(1, 2))
+        );
+    }
+
+    #[test]
+    fn test_invalid_points() {
+        let segment = VoronoiSite::Segment(Point2::new(0.0, 0.0), Point2::new(2.0, 0.0));
+        let sites = vec![segment, VoronoiSite::Point(Point2::new(1.0, 0.0))];
+        assert_eq!(
+            SegmentVoronoiDiagram::new(sites, 0.1).err(),
+            Some(SegmentVoronoiError::Intersection(0, 1))
+        );
+        let sites = vec![segment, VoronoiSite::Point(Point2::new(1.0, f64::NAN))];
+        assert_eq!(
+            SegmentVoronoiDiagram::new(sites, 0.1).err(),
+            Some(SegmentVoronoiError::NonFiniteCoordinate(1))
+        );
+    }
+}
@@ -0,0 +1,266 @@
+// Copyright 2017 The Spade Developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use self::dcel::{EdgeHandle, EdgesIterator, FaceHandle, FacesIterator};
+use crate::delaunay::*;
+use crate::point_traits::{PointN, PointNExtensions, TwoDimensional};
+use crate::traits::{HasPosition2D, SpadeFloat};
+use num::{zero, Float, NumCast};
+
+/// Aggregate statistics of a sequence of values.
+///
+/// Returned by `DelaunayTriangulation::edge_length_statistics` and
+/// `DelaunayTriangulation::angle_statistics`.
+#[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde_serialize", derive(Serialize, Deserialize))]
+pub struct Statistics<S> {
+    /// The number of values.
+    pub count: usize,
+    /// The smallest value.
+    pub min: S,
+    /// The largest value.
+    pub max: S,
+    /// The arithmetic mean of all values.
+    pub mean: S,
+    /// The population standard deviation of all values.
+    pub standard_deviation: S,
+}
+
+impl<S: SpadeFloat> Statistics<S> {
+    /// Calculates the statistics of a sequence of values.
+    ///
+    /// Returns `None` if the sequence is empty.
+    pub fn from_values<I>(values: I) -> Option<Statistics<S>>
+    where
+        I: IntoIterator<Item = S>,
+    {
+        let mut values = values.into_iter();
+        let first = values.next()?;
+        let mut result = Statistics {
+            count: 1,
+            min: first,
+            max: first,
+            mean: first,
+            standard_deviation: zero(),
+        };
+        // Welford's algorithm, `m2` is the sum of squared differences from the mean
+        let mut m2: S = zero();
+        for value in values {
+            result.count += 1;
+            result.min = result.min.min(value);
+            result.max = result.max.max(value);
+            let delta = value - result.mean;
+            let count: S = NumCast::from(result.count).unwrap();
+            result.mean += delta / count;
+            m2 += delta * (value - result.mean);
+        }
+        let count: S = NumCast::from(result.count).unwrap();
+        result.standard_deviation = (m2 / count).sqrt();
+        Some(result)
+    }
+}
+
+/// Estimated heap memory of a triangulation, in bytes.
+///
+/// Returned by `DelaunayTriangulation::memory_usage` and
+/// `DelaunayTriangulation::memory_usage_with`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct TriangulationMemoryUsage {
+    /// The vertex array, including the vertices' data.
+    pub vertices: usize,
+    /// The half edge array, including the edges' data.
+    pub edges: usize,
+    /// The face array.
+    pub faces: usize,
+    /// The locate structure, see `DelaunayLocateStructure::memory_usage`.
+    pub locate_structure: usize,
+    /// Heap memory owned by the vertices' data.
+    pub payload: usize,
+}
+
+impl TriangulationMemoryUsage {
+    /// Returns the sum of all components.
+    pub fn total(&self) -> usize {
+        self.vertices + self.edges + self.faces + self.locate_structure + self.payload
+    }
+}
+
+/// Iterates over all undirected edges of a triangulation and their lengths.
+///
+/// Created by `DelaunayTriangulation::edge_lengths`.
+pub struct EdgeLengthIterator<'a, V, E = ()>
+where
+    V: 'a,
+    E: 'a,
+{
+    edges: EdgesIterator<'a, V, E>,
+}
+
+impl<'a, V, E> EdgeLengthIterator<'a, V, E> {
+    pub(crate) fn new(edges: EdgesIterator<'a, V, E>) -> Self {
+        EdgeLengthIterator { edges }
+    }
+}
+
+impl<'a, V, E> Iterator for EdgeLengthIterator<'a, V, E>
+where
+    V: HasPosition2D + 'a,
+    V::Point: TwoDimensional,
+    <V::Point as PointN>::Scalar: SpadeFloat,
+    E: Default + Copy + 'a,
+{
+    type Item = (EdgeHandle<'a, V, E>, <V::Point as PointN>::Scalar);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.edges.next().map(|edge| {
+            let length = edge
+                .to()
+                .position()
+                .sub(&edge.from().position())
+                .length2()
+                .sqrt();
+            (edge, length)
+        })
+    }
+}
+
+/// Iterates over all triangles of a triangulation and their interior angles.
+///
+/// The angles are given in radians, in the same order as the vertices
+/// returned by `FaceHandle::as_triangle`.
+/// Created by `DelaunayTriangulation::triangle_angles`.
+pub struct TriangleAngleIterator<'a, V, E = ()>
+where
+    V: 'a,
+    E: 'a,
+{
+    faces: FacesIterator<'a, V, E>,
+}
+
+impl<'a, V, E> TriangleAngleIterator<'a, V, E> {
+    pub(crate) fn new(faces: FacesIterator<'a, V, E>) -> Self {
+        TriangleAngleIterator { faces }
+    }
+}
+
+impl<'a, V, E> Iterator for TriangleAngleIterator<'a, V, E>
+where
+    V: HasPosition2D + 'a,
+    V::Point: TwoDimensional,
+    <V::Point as PointN>::Scalar: SpadeFloat,
+    E: Default + Copy + 'a,
+{
+    type Item = (FaceHandle<'a, V, E>, [<V::Point as PointN>::Scalar; 3]);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.faces.next().map(|face| {
+            let [v0, v1, v2] = face.as_triangle();
+            let (p0, p1, p2) = (v0.position(), v1.position(), v2.position());
+            let angles = [
+                angle_at(&p0, &p1, &p2),
+                angle_at(&p1, &p2, &p0),
+                angle_at(&p2, &p0, &p1),
+            ];
+            (face, angles)
+        })
+    }
+}
+
+/// Returns the angle between the edges `apex -> p1` and `apex -> p2` in radians.
+fn angle_at<V>(apex: &V, p1: &V, p2: &V) -> V::Scalar
+where
+    V: TwoDimensional,
+    V::Scalar: SpadeFloat,
+{
+    let d1 = p1.sub(apex);
+    let d2 = p2.sub(apex);
+    let cross = *d1.nth(0) * *d2.nth(1) - *d1.nth(1) * *d2.nth(0);
+    Float::atan2(cross.abs(), d1.dot(&d2))
+}
+
+#[cfg(test)]
+mod test {
+    use super::{angle_at, Statistics};
+    use crate::delaunay::{FloatCDT, FloatDelaunayTriangulation};
+    use crate::testutils::*;
+    use cgmath::Point2;
+    use std::f64::consts::{FRAC_PI_2, FRAC_PI_4};
+
+    #[test]
+    fn test_statistics() {
+        assert_eq!(Statistics::<f64>::from_values(vec![]), None);
+        let stats = Statistics::from_values(vec![2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0]).unwrap();
+        assert_eq!(stats.count, 8);
+        assert_eq!(stats.min, 2.0);
+        assert_eq!(stats.max, 9.0);
+        assert_relative_eq!(stats.mean, 5.0);
+        assert_relative_eq!(stats.standard_deviation, 2.0);
+    }
+
+    #[test]
+    fn test_angle_at() {
+        let origin = Point2::new(0.0, 0.0);
+        let angle = angle_at(&origin, &Point2::new(2.0, 0.0), &Point2::new(0.0, 3.0));
+        assert_relative_eq!(angle, FRAC_PI_2);
+        let angle = angle_at(&origin, &Point2::new(1.0, 1.0), &Point2::new(1.0, 0.0));
+        assert_relative_eq!(angle, FRAC_PI_4);
+    }
+
+    #[test]
+    fn test_edge_lengths_and_angles() {
+        let mut d = FloatDelaunayTriangulation::with_walk_locate();
+        d.insert(Point2::new(0.0, 0.0));
+        d.insert(Point2::new(3.0, 0.0));
+        d.insert(Point2::new(0.0, 4.0));
+        let mut lengths: Vec<_> = d.edge_lengths().map(|(_, l)| l).collect();
+        lengths.sort_by(|l, r| l.partial_cmp(r).unwrap());
+        assert_eq!(lengths, vec![3.0, 4.0, 5.0]);
+        let stats = d.edge_length_statistics().unwrap();
+        assert_eq!(stats.count, 3);
+        assert_relative_eq!(stats.mean, 4.0);
+
+        let angles: Vec<_> = d.triangle_angles().collect();
+        assert_eq!(angles.len(), 1);
+        let sum: f64 = angles[0].1.iter().sum();
+        assert_relative_eq!(sum, ::std::f64::consts::PI);
+        let stats = d.angle_statistics().unwrap();
+        assert_eq!(stats.count, 3);
+        assert_relative_eq!(stats.max, FRAC_PI_2);
+    }
+
+    #[test]
+    fn test_memory_usage() {
+        let points = random_points_with_seed::<f64>(500, &[11; 32]);
+        let mut walk = FloatDelaunayTriangulation::with_walk_locate();
+        let mut tree = FloatDelaunayTriangulation::with_tree_locate();
+        for p in &points {
+            walk.insert(*p);
+            tree.insert(*p);
+        }
+        let walk_usage = walk.memory_usage();
+        let tree_usage = tree.memory_usage();
+        assert_eq!(walk_usage.locate_structure, 0);
+        assert!(tree_usage.locate_structure > 0);
+        assert_eq!(walk_usage.vertices, tree_usage.vertices);
+        assert!(walk_usage.vertices >= 500 * ::std::mem::size_of::<Point2<f64>>());
+        assert!(walk_usage.edges > walk_usage.faces);
+        assert_eq!(walk.memory_usage_with(|_| 2).payload, 1000);
+
+        walk.reserve(1000);
+        assert!(walk.memory_usage().total() > walk_usage.total());
+
+        let mut cdt = FloatCDT::with_walk_locate();
+        let v0 = cdt.insert(Point2::new(0.0, 0.0));
+        let v1 = cdt.insert(Point2::new(1.0, 0.0));
+        cdt.insert(Point2::new(0.0, 1.0));
+        cdt.add_constraint(v0, v1);
+        let usage = cdt.memory_usage();
+        assert!(usage.vertices > 0 && usage.edges > 0 && usage.faces > 0);
+        assert_eq!(usage.total(), usage.vertices + usage.edges + usage.faces);
+    }
+}
@@ -0,0 +1,920 @@
+// Copyright 2017 The Spade Developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use self::dcel::{FaceHandle, FacesIterator};
+use self::delaunay_basic::BasicDelaunaySubdivision;
+use self::line_intersection_iterator::{Intersection, LineIntersectionIterator};
+use crate::delaunay::*;
+use crate::point_traits::{PointN, PointNExtensions, TwoDimensional};
+use crate::rtree::RTree;
+use crate::traits::{HasPosition2D, SpadeFloat, SpatialObject};
+use crate::BoundingRect;
+use alloc::{vec, vec::Vec};
+use num::{one, zero, Float, NumCast, ToPrimitive};
+
+/// The volume between a triangulated surface and a reference surface.
+///
+/// Returned by `DelaunayTriangulation::volume_to_plane` and
+/// `DelaunayTriangulation::volume_to_surface`.
+#[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde_serialize", derive(Serialize, Deserialize))]
+pub struct CutFillVolume<S> {
+    /// The volume where the surface lies above the reference surface.
+    pub cut: S,
+    /// The volume where the surface lies below the reference surface.
+    pub fill: S,
+    /// The area of the footprint shared by both surfaces.
+    pub area: S,
+}
+
+impl<S: SpadeFloat> CutFillVolume<S> {
+    /// Returns the signed volume between both surfaces, equal to `cut - fill`.
+    ///
+    /// A positive value indicates that the surface lies, on average, above
+    /// the reference surface.
+    pub fn net(&self) -> S {
+        self.cut - self.fill
+    }
+
+    fn empty() -> Self {
+        CutFillVolume {
+            cut: zero(),
+            fill: zero(),
+            area: zero(),
+        }
+    }
+
+    fn add_triangle(&mut self, a: &[S; 3], b: &[S; 3], c: &[S; 3]) {
+        let (cut, fill, area) = triangle_cut_fill(a, b, c);
+        self.cut += cut;
+        self.fill += fill;
+        self.area += area;
+    }
+}
+
+/// Slope, aspect and normal of a triangle of a terrain surface.
+///
+/// Returned by `FaceSlopeIterator`.
+#[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde_serialize", derive(Serialize, Deserialize))]
+pub struct FaceSlope<S> {
+    /// The angle between the triangle and the horizontal plane in radians.
+    pub slope: S,
+    /// The compass direction of steepest descent in radians, measured clockwise
+    /// from the positive y axis ("north"). The value lies within `[0, 2 * PI)`.
+    /// `None` if the triangle is horizontal.
+    pub aspect: Option<S>,
+    /// The upward facing unit normal of the triangle.
+    pub normal: [S; 3],
+}
+
+impl<S: SpadeFloat> FaceSlope<S> {
+    fn from_plane(plane: &Plane<S>) -> Self {
+        let (gx, gy) = (plane.slope_x, plane.slope_y);
+        let steepness = (gx * gx + gy * gy).sqrt();
+        let length = (steepness * steepness + one()).sqrt();
+        let aspect = if steepness == zero() {
+            None
+        } else {
+            let two_pi: S = NumCast::from(2.0 * ::core::f64::consts::PI).unwrap();
+            let aspect = Float::atan2(-gx, -gy);
+            Some(if aspect < zero() {
+                aspect + two_pi
+            } else {
+                aspect
+            })
+        };
+        FaceSlope {
+            slope: steepness.atan(),
+            aspect,
+            normal: [-gx / length, -gy / length, one::<S>() / length],
+        }
+    }
+}
+
+/// Iterates over all triangles of a triangulation and their slopes.
+///
+/// Created by `DelaunayTriangulation::face_slopes`.
+pub struct FaceSlopeIterator<'a, V, F, E = ()>
+where
+    V: 'a,
+    E: 'a,
+{
+    faces: FacesIterator<'a, V, E>,
+    height: F,
+}
+
+impl<'a, V, F, E> FaceSlopeIterator<'a, V, F, E> {
+    pub(crate) fn new(faces: FacesIterator<'a, V, E>, height: F) -> Self {
+        FaceSlopeIterator { faces, height }
+    }
+}
+
+impl<'a, V, F, E> Iterator for FaceSlopeIterator<'a, V, F, E>
+where
+    V: HasPosition2D + 'a,
+    V::Point: TwoDimensional,
+    <V::Point as PointN>::Scalar: SpadeFloat,
+    E: Default + Copy + 'a,
+    F: Fn(&V) -> <V::Point as PointN>::Scalar,
+{
+    type Item = (
+        FaceHandle<'a, V, E>,
+        FaceSlope<<V::Point as PointN>::Scalar>,
+    );
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.faces.next().map(|face| {
+            let plane = Plane::from_triangle(&height_triangle(face, &self.height));
+            (face, FaceSlope::from_plane(&plane))
+        })
+    }
+}
+
+/// A regular grid of sample points within a rectangle.
+///
+/// The rectangle is divided into `columns` times `rows` equally sized cells,
+/// each cell is sampled at its center. Rasters over a grid store one value per
+/// cell, row by row, starting with the row of the smallest y coordinate.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde_serialize", derive(Serialize, Deserialize))]
+pub struct RasterGrid<V: PointN> {
+    bbox: BoundingRect<V>,
+    columns: usize,
+    rows: usize,
+}
+
+impl<V> RasterGrid<V>
+where
+    V: TwoDimensional,
+    V::Scalar: SpadeFloat,
+{
+    /// Creates a grid covering `bbox` with the given number of columns and rows.
+    pub fn new(bbox: BoundingRect<V>, columns: usize, rows: usize) -> RasterGrid<V> {
+        RasterGrid {
+            bbox,
+            columns,
+            rows,
+        }
+    }
+
+    /// Returns the rectangle covered by the grid.
+    pub fn bbox(&self) -> &BoundingRect<V> {
+        &self.bbox
+    }
+
+    /// Returns the number of cells per row.
+    pub fn columns(&self) -> usize {
+        self.columns
+    }
+
+    /// Returns the number of rows.
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    /// Returns the total number of cells.
+    pub fn len(&self) -> usize {
+        self.columns * self.rows
+    }
+
+    /// Returns `true` if the grid has no cells.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the width and height of a single cell.
+    pub fn cell_size(&self) -> V {
+        let extent = self.bbox.upper().sub(&self.bbox.lower());
+        let mut result = V::new();
+        *result.nth_mut(0) = *extent.nth(0) / to_scalar(self.columns);
+        *result.nth_mut(1) = *extent.nth(1) / to_scalar(self.rows);
+        result
+    }
+
+    /// Returns the center of the cell in the given column and row.
+    pub fn sample(&self, column: usize, row: usize) -> V {
+        let cell_size = self.cell_size();
+        let half: V::Scalar = one::<V::Scalar>() / (one::<V::Scalar>() + one());
+        let mut result = self.bbox.lower();
+        *result.nth_mut(0) += (to_scalar::<V::Scalar>(column) + half) * *cell_size.nth(0);
+        *result.nth_mut(1) += (to_scalar::<V::Scalar>(row) + half) * *cell_size.nth(1);
+        result
+    }
+}
+
+fn to_scalar<S: SpadeFloat>(value: usize) -> S {
+    <S as NumCast>::from(value).unwrap()
+}
+
+/// A triangle given by its vertices' x, y and height values.
+type HeightTriangle<S> = [[S; 3]; 3];
+
+fn height_triangle<V, E, F>(
+    face: FaceHandle<V, E>,
+    height: &F,
+) -> HeightTriangle<<V::Point as PointN>::Scalar>
+where
+    V: HasPosition2D,
+    V::Point: TwoDimensional,
+    <V::Point as PointN>::Scalar: SpadeFloat,
+    E: Default + Copy,
+    F: Fn(&V) -> <V::Point as PointN>::Scalar,
+{
+    let [v0, v1, v2] = face.as_triangle();
+    let to_array = |v: &V| {
+        let position = v.position();
+        [*position.nth(0), *position.nth(1), height(v)]
+    };
+    [to_array(&v0), to_array(&v1), to_array(&v2)]
+}
+
+pub(crate) fn volume_to_plane<'a, V, E, F>(
+    triangles: FacesIterator<'a, V, E>,
+    height: F,
+    plane_height: <V::Point as PointN>::Scalar,
+) -> CutFillVolume<<V::Point as PointN>::Scalar>
+where
+    V: HasPosition2D + 'a,
+    V::Point: TwoDimensional,
+    <V::Point as PointN>::Scalar: SpadeFloat,
+    E: Default + Copy + 'a,
+    F: Fn(&V) -> <V::Point as PointN>::Scalar,
+{
+    let mut result = CutFillVolume::empty();
+    for face in triangles {
+        let mut triangle = height_triangle(face, &height);
+        for vertex in &mut triangle {
+            vertex[2] -= plane_height;
+        }
+        let [a, b, c] = triangle;
+        result.add_triangle(&a, &b, &c);
+    }
+    result
+}
+
+/// Scan converts all triangles into a raster of linearly interpolated heights.
+///
+/// For each row of cells, the interval of x coordinates within a triangle is
+/// derived from the barycentric coordinates, which are linear in x along the
+/// row. Intervals are widened slightly to not miss cells whose center lies
+/// on an edge.
+pub(crate) fn rasterize<'a, V, E, F>(
+    triangles: FacesIterator<'a, V, E>,
+    grid: &RasterGrid<V::Point>,
+    height: F,
+) -> Vec<Option<<V::Point as PointN>::Scalar>>
+where
+    V: HasPosition2D + 'a,
+    V::Point: TwoDimensional,
+    <V::Point as PointN>::Scalar: SpadeFloat,
+    E: Default + Copy + 'a,
+    F: Fn(&V) -> <V::Point as PointN>::Scalar,
+{
+    let mut result = vec![None; grid.len()];
+    if grid.is_empty() {
+        return result;
+    }
+    let lower = grid.bbox().lower();
+    let cell_size = grid.cell_size();
+    let (cell_width, cell_height) = (*cell_size.nth(0), *cell_size.nth(1));
+    let half =
+        one::<<V::Point as PointN>::Scalar>() / (one::<<V::Point as PointN>::Scalar>() + one());
+    let tolerance = <<V::Point as PointN>::Scalar as Float>::epsilon() * to_scalar(1024);
+    // Returns the range of cell indices whose centers lie within [min, max]
+    let cell_range = |min: <V::Point as PointN>::Scalar,
+                      max: <V::Point as PointN>::Scalar,
+                      origin: <V::Point as PointN>::Scalar,
+                      size: <V::Point as PointN>::Scalar,
+                      count: usize| {
+        let first = Float::ceil((min - origin) / size - half);
+        let last = Float::floor((max - origin) / size - half);
+        let first = Float::max(first, zero());
+        let last = Float::min(last, to_scalar(count - 1));
+        if first <= last {
+            Some((
+                first.to_usize().unwrap_or(count),
+                last.to_usize().unwrap_or(0),
+            ))
+        } else {
+            None
+        }
+    };
+    for face in triangles {
+        let t = height_triangle(face, &height);
+        let area =
+            (t[1][0] - t[0][0]) * (t[2][1] - t[0][1]) - (t[2][0] - t[0][0]) * (t[1][1] - t[0][1]);
+        if area == zero() {
+            continue;
+        }
+        let min_y = Float::min(Float::min(t[0][1], t[1][1]), t[2][1]);
+        let max_y = Float::max(Float::max(t[0][1], t[1][1]), t[2][1]);
+        let rows = match cell_range(min_y, max_y, *lower.nth(1), cell_height, grid.rows()) {
+            Some(rows) => rows,
+            None => continue,
+        };
+        for row in rows.0..=rows.1 {
+            let y = *grid.sample(0, row).nth(1);
+            // The barycentric coordinate of vertex i along the row is a * x + b
+            let mut coefficients = [(zero(), zero()); 3];
+            let mut min_x = <<V::Point as PointN>::Scalar as Float>::neg_infinity();
+            let mut max_x = <<V::Point as PointN>::Scalar as Float>::infinity();
+            for (i, coefficient) in coefficients.iter_mut().enumerate() {
+                let [xj, yj, _] = t[(i + 1) % 3];
+                let [xk, yk, _] = t[(i + 2) % 3];
+                let a = (yj - yk) / area;
+                let b = (xj * (yk - y) - xk * (yj - y)) / area;
+                *coefficient = (a, b);
+                if a > zero() {
+                    min_x = Float::max(min_x, (-tolerance - b) / a);
+                } else if a < zero() {
+                    max_x = Float::min(max_x, (-tolerance - b) / a);
+                } else if b < -tolerance {
+                    max_x = min_x;
+                }
+            }
+            if min_x > max_x {
+                continue;
+            }
+            let columns = match cell_range(min_x, max_x, *lower.nth(0), cell_width, grid.columns())
+            {
+                Some(columns) => columns,
+                None => continue,
+            };
+            for column in columns.0..=columns.1 {
+                let x = *grid.sample(column, row).nth(0);
+                let mut value = zero();
+                for (vertex, (a, b)) in t.iter().zip(&coefficients) {
+                    value += (*a * x + *b) * vertex[2];
+                }
+                result[row * grid.columns() + column] = Some(value);
+            }
+        }
+    }
+    result
+}
+
+pub(crate) fn volume_to_surface<'a, 'b, V, E, F, W, EW, G, I>(
+    triangles: FacesIterator<'a, V, E>,
+    height: F,
+    other: I,
+    other_height: G,
+) -> CutFillVolume<<V::Point as PointN>::Scalar>
+where
+    V: HasPosition2D + 'a,
+    V::Point: TwoDimensional,
+    <V::Point as PointN>::Scalar: SpadeFloat,
+    E: Default + Copy + 'a,
+    F: Fn(&V) -> <V::Point as PointN>::Scalar,
+    W: HasPosition2D<Point = V::Point> + 'b,
+    EW: Default + Copy + 'b,
+    G: Fn(&W) -> <V::Point as PointN>::Scalar,
+    I: IntoIterator<Item = FaceHandle<'b, W, EW>>,
+{
+    let other: Vec<_> = other
+        .into_iter()
+        .map(|face| {
+            let triangle = height_triangle(face, &other_height);
+            SurfaceTriangle {
+                triangle,
+                plane: Plane::from_triangle(&triangle),
+            }
+        })
+        .collect();
+    let tree = RTree::bulk_load(other);
+    let mut result = CutFillVolume::empty();
+    for face in triangles {
+        let triangle = height_triangle(face, &height);
+        let plane = Plane::from_triangle(&triangle);
+        for candidate in tree.lookup_in_rectangle(&footprint(&triangle)) {
+            let clipped = clip_triangle(&triangle, &candidate.triangle);
+            // The clipped polygon is convex and can be triangulated as a fan
+            for i in 2..clipped.len() {
+                let mut fan = [clipped[0], clipped[i - 1], clipped[i]];
+                for vertex in &mut fan {
+                    vertex[2] = plane.height(vertex) - candidate.plane.height(vertex);
+                }
+                result.add_triangle(&fan[0], &fan[1], &fan[2]);
+            }
+        }
+    }
+    result
+}
+
+/// Checks if the straight sightline between two points passes above the surface.
+///
+/// The terrain surface is piecewise linear along the sightline, with breakpoints
+/// at all crossed edges and vertices. It suffices to compare the sightline's
+/// elevation at these breakpoints. Parts of the sightline outside of the
+/// triangulation's convex hull are never obstructed.
+pub(crate) fn is_visible<T, V, E, F>(
+    triangulation: &T,
+    height: &F,
+    from: &V::Point,
+    from_elevation: <V::Point as PointN>::Scalar,
+    to: &V::Point,
+    to_elevation: <V::Point as PointN>::Scalar,
+) -> bool
+where
+    T: BasicDelaunaySubdivision<V, EdgeType = E>,
+    V: HasPosition2D,
+    V::Point: TwoDimensional,
+    <V::Point as PointN>::Scalar: SpadeFloat,
+    E: Default + Copy,
+    F: Fn(&V) -> <V::Point as PointN>::Scalar,
+{
+    if triangulation.all_points_on_line() || from == to {
+        return true;
+    }
+    let (ax, ay) = (*from.nth(0), *from.nth(1));
+    let (dx, dy) = (*to.nth(0) - ax, *to.nth(1) - ay);
+    let length2 = dx * dx + dy * dy;
+    let is_blocking = |t: <V::Point as PointN>::Scalar, terrain| {
+        t >= zero() && t <= one() && terrain > from_elevation + (to_elevation - from_elevation) * t
+    };
+    let vertex_blocks = |vertex: VertexHandle<V, E>| {
+        let position = vertex.position();
+        let t = ((*position.nth(0) - ax) * dx + (*position.nth(1) - ay) * dy) / length2;
+        is_blocking(t, height(&vertex))
+    };
+    for intersection in LineIntersectionIterator::new(triangulation, from, to) {
+        let blocked = match intersection {
+            Intersection::VertexIntersection(vertex) => vertex_blocks(vertex),
+            Intersection::EdgeOverlap(edge) => {
+                vertex_blocks(edge.from()) || vertex_blocks(edge.to())
+            }
+            Intersection::EdgeIntersection(edge) => {
+                let (p, q) = (edge.from().position(), edge.to().position());
+                let (px, py) = (*p.nth(0), *p.nth(1));
+                let (ex, ey) = (*q.nth(0) - px, *q.nth(1) - py);
+                let denominator = dx * ey - dy * ex;
+                if denominator == zero() {
+                    false
+                } else {
+                    let (wx, wy) = (px - ax, py - ay);
+                    let t = (wx * ey - wy * ex) / denominator;
+                    let s = crate::misc::clamp(zero(), (wx * dy - wy * dx) / denominator, one());
+                    let (from_height, to_height) = (height(&edge.from()), height(&edge.to()));
+                    is_blocking(t, from_height + (to_height - from_height) * s)
+                }
+            }
+        };
+        if blocked {
+            return false;
+        }
+    }
+    true
+}
+
+/// Determines which faces can be seen from an observer.
+pub(crate) fn viewshed<T, V, E, F>(
+    triangulation: &T,
+    height: F,
+    observer: &V::Point,
+    observer_elevation: <V::Point as PointN>::Scalar,
+) -> Vec<bool>
+where
+    T: BasicDelaunaySubdivision<V, EdgeType = E>,
+    V: HasPosition2D,
+    V::Point: TwoDimensional,
+    <V::Point as PointN>::Scalar: SpadeFloat,
+    E: Default + Copy,
+    F: Fn(&V) -> <V::Point as PointN>::Scalar,
+{
+    let dcel = triangulation.s();
+    let mut result = vec![false; dcel.num_faces()];
+    if triangulation.all_points_on_line() {
+        return result;
+    }
+    let three: <V::Point as PointN>::Scalar = NumCast::from(3.0).unwrap();
+    for face in dcel.faces().skip(1) {
+        let [a, b, c] = height_triangle(face, &height);
+        let mut centroid = observer.clone();
+        for dim in 0..2 {
+            *centroid.nth_mut(dim) = (a[dim] + b[dim] + c[dim]) / three;
+        }
+        let elevation = (a[2] + b[2] + c[2]) / three;
+        result[face.fix()] = is_visible(
+            triangulation,
+            &height,
+            observer,
+            observer_elevation,
+            &centroid,
+            elevation,
+        );
+    }
+    result
+}
+
+/// Returns the two dimensional bounding rectangle of a triangle.
+fn footprint<S: SpadeFloat>(triangle: &HeightTriangle<S>) -> BoundingRect<[S; 2]> {
+    BoundingRect::from_points(triangle.iter().map(|p| [p[0], p[1]]))
+}
+
+/// A triangle of a reference surface, stored in an r-tree.
+#[derive(Clone)]
+struct SurfaceTriangle<S: SpadeFloat> {
+    triangle: HeightTriangle<S>,
+    plane: Plane<S>,
+}
+
+impl<S: SpadeFloat> SpatialObject for SurfaceTriangle<S> {
+    type Point = [S; 2];
+
+    fn mbr(&self) -> BoundingRect<[S; 2]> {
+        footprint(&self.triangle)
+    }
+
+    fn distance2(&self, point: &[S; 2]) -> S {
+        self.mbr().min_dist2(point)
+    }
+}
+
+/// The plane spanned by a non degenerate triangle.
+#[derive(Clone)]
+struct Plane<S> {
+    origin: [S; 3],
+    slope_x: S,
+    slope_y: S,
+}
+
+impl<S: SpadeFloat> Plane<S> {
+    fn from_triangle(triangle: &HeightTriangle<S>) -> Self {
+        let [p0, p1, p2] = triangle;
+        let (dx1, dy1, dh1) = (p1[0] - p0[0], p1[1] - p0[1], p1[2] - p0[2]);
+        let (dx2, dy2, dh2) = (p2[0] - p0[0], p2[1] - p0[1], p2[2] - p0[2]);
+        let det = dx1 * dy2 - dx2 * dy1;
+        Plane {
+            origin: *p0,
+            slope_x: (dh1 * dy2 - dh2 * dy1) / det,
+            slope_y: (dx1 * dh2 - dx2 * dh1) / det,
+        }
+    }
+
+    fn height(&self, point: &[S; 3]) -> S {
+        self.origin[2]
+            + self.slope_x * (point[0] - self.origin[0])
+            + self.slope_y * (point[1] - self.origin[1])
+    }
+}
+
+/// Clips a triangle against a counterclockwise oriented triangle.
+///
+/// Only the x and y coordinates are considered, heights of newly created points
+/// are undefined.
+fn clip_triangle<S: SpadeFloat>(
+    triangle: &HeightTriangle<S>,
+    clip: &HeightTriangle<S>,
+) -> Vec<[S; 3]> {
+    let mut polygon = triangle.to_vec();
+    for i in 0..3 {
+        if polygon.is_empty() {
+            break;
+        }
+        let from = clip[i];
+        let to = clip[(i + 1) % 3];
+        let side = |p: &[S; 3]| {
+            (to[0] - from[0]) * (p[1] - from[1]) - (to[1] - from[1]) * (p[0] - from[0])
+        };
+        let mut clipped = Vec::with_capacity(polygon.len() + 1);
+        for j in 0..polygon.len() {
+            let current = polygon[j];
+            let next = polygon[(j + 1) % polygon.len()];
+            let (side_current, side_next) = (side(&current), side(&next));
+            if side_current >= zero() {
+                clipped.push(current);
+            }
+            if (side_current < zero()) != (side_next < zero()) && side_current != side_next {
+                let t = side_current / (side_current - side_next);
+                clipped.push([
+                    current[0] + (next[0] - current[0]) * t,
+                    current[1] + (next[1] - current[1]) * t,
+                    zero(),
+                ]);
+            }
+        }
+        polygon = clipped;
+    }
+    if polygon.len() < 3 {
+        polygon.clear();
+    }
+    polygon
+}
+
+/// Integrates the positive and negative parts of a linear function over a triangle.
+///
+/// Each vertex is given as x, y and function value. Returns the integral of the
+/// positive part, the negated integral of the negative part and the triangle's area.
+fn triangle_cut_fill<S: SpadeFloat>(a: &[S; 3], b: &[S; 3], c: &[S; 3]) -> (S, S, S) {
+    let two: S = one::<S>() + one();
+    let three: S = NumCast::from(3.0).unwrap();
+    let area = Float::abs((b[0] - a[0]) * (c[1] - a[1]) - (c[0] - a[0]) * (b[1] - a[1])) / two;
+    let (ha, hb, hc) = (a[2], b[2], c[2]);
+    let total = area * (ha + hb + hc) / three;
+    let num_positive = [ha, hb, hc].iter().filter(|h| **h > zero()).count();
+    let num_negative = [ha, hb, hc].iter().filter(|h| **h < zero()).count();
+    if num_negative == 0 {
+        return (total, zero(), area);
+    }
+    if num_positive == 0 {
+        return (zero(), -total, area);
+    }
+    // Volume of the tetrahedron-like part cut off by the zero line at the vertex
+    // with value `apex`.
+    let corner = |apex: S, other1: S, other2: S| {
+        area * apex * apex * apex / (three * (apex - other1) * (apex - other2))
+    };
+    let is_single = |value: S, others: [S; 2]| {
+        if num_positive == 1 {
+            value > zero() && others.iter().all(|h| *h <= zero())
+        } else {
+            value < zero() && others.iter().all(|h| *h >= zero())
+        }
+    };
+    let (apex, other1, other2) = if is_single(ha, [hb, hc]) {
+        (ha, hb, hc)
+    } else if is_single(hb, [ha, hc]) {
+        (hb, ha, hc)
+    } else {
+        (hc, ha, hb)
+    };
+    let corner_volume = corner(apex, other1, other2);
+    if num_positive == 1 {
+        (corner_volume, corner_volume - total, area)
+    } else {
+        (total - corner_volume, -corner_volume, area)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{clip_triangle, triangle_cut_fill, RasterGrid};
+    use crate::delaunay::{
+        DelaunayWalkLocate, FloatDelaunayTriangulation, PositionInTriangulation,
+    };
+    use crate::traits::HasPosition;
+    use cgmath::Point2;
+
+    #[test]
+    fn test_triangle_cut_fill() {
+        let (cut, fill, area) =
+            triangle_cut_fill(&[0.0, 0.0, 3.0], &[1.0, 0.0, 3.0], &[0.0, 1.0, 3.0]);
+        assert_relative_eq!(cut, 1.5);
+        assert_relative_eq!(fill, 0.0);
+        assert_relative_eq!(area, 0.5);
+
+        let (cut, fill, _) =
+            triangle_cut_fill(&[0.0, 0.0, 1.0], &[1.0, 0.0, -1.0], &[0.0, 1.0, -1.0]);
+        // The positive part is a triangle with legs of length 0.5
+        assert_relative_eq!(cut, 0.125 * 1.0 / 3.0);
+        assert_relative_eq!(cut - fill, 0.5 * (1.0 - 1.0 - 1.0) / 3.0);
+
+        let (cut, fill, _) =
+            triangle_cut_fill(&[0.0, 0.0, -1.0], &[1.0, 0.0, 1.0], &[0.0, 1.0, 1.0]);
+        assert_relative_eq!(fill, 0.125 / 3.0);
+        assert_relative_eq!(cut - fill, 0.5 / 3.0);
+    }
+
+    #[test]
+    fn test_clip_triangle() {
+        let triangle = [[0.0, 0.0, 0.0], [2.0, 0.0, 0.0], [0.0, 2.0, 0.0]];
+        let clip = [[1.0, -1.0, 0.0], [3.0, 1.0, 0.0], [1.0, 3.0, 0.0]];
+        let clipped = clip_triangle(&triangle, &clip);
+        assert_eq!(clipped.len(), 3);
+        let disjoint = [[5.0, 5.0, 0.0], [6.0, 5.0, 0.0], [5.0, 6.0, 0.0]];
+        assert!(clip_triangle(&triangle, &disjoint).is_empty());
+        assert_eq!(clip_triangle(&triangle, &triangle).len(), 3);
+    }
+
+    struct PointWithHeight {
+        point: Point2<f64>,
+        height: f64,
+    }
+
+    impl HasPosition for PointWithHeight {
+        type Point = Point2<f64>;
+        fn position(&self) -> Point2<f64> {
+            self.point
+        }
+    }
+
+    fn grid<F>(
+        size: i32,
+        height: F,
+    ) -> FloatDelaunayTriangulation<PointWithHeight, DelaunayWalkLocate>
+    where
+        F: Fn(f64, f64) -> f64,
+    {
+        let mut d = FloatDelaunayTriangulation::with_walk_locate();
+        for x in 0..=size {
+            for y in 0..=size {
+                let (x, y) = (f64::from(x), f64::from(y));
+                d.insert(PointWithHeight {
+                    point: Point2::new(x, y),
+                    height: height(x, y),
+                });
+            }
+        }
+        d
+    }
+
+    #[test]
+    fn test_face_slopes() {
+        use std::f64::consts::{FRAC_PI_2, FRAC_PI_4, PI};
+        // Rises towards the east, descends towards the west
+        let d = grid(2, |x, _| x);
+        for (_, slope) in d.face_slopes(|v| v.height) {
+            assert_relative_eq!(slope.slope, FRAC_PI_4);
+            assert_relative_eq!(slope.aspect.unwrap(), 3.0 * FRAC_PI_2);
+            let expected = 0.5f64.sqrt();
+            assert_relative_eq!(slope.normal[0], -expected);
+            assert_relative_eq!(slope.normal[1], 0.0);
+            assert_relative_eq!(slope.normal[2], expected);
+        }
+        // Descends towards the north
+        let d = grid(2, |_, y| -2.0 * y);
+        for (_, slope) in d.face_slopes(|v| v.height) {
+            assert_relative_eq!(slope.slope, 2.0f64.atan());
+            assert_relative_eq!(slope.aspect.unwrap(), 0.0);
+        }
+        // Descends towards the south
+        let d = grid(2, |_, y| y);
+        let (_, slope) = d.face_slopes(|v| v.height).next().unwrap();
+        assert_relative_eq!(slope.aspect.unwrap(), PI);
+        let d = grid(2, |_, _| 1.0);
+        assert_eq!(d.face_slopes(|v| v.height).count(), d.num_triangles());
+        for (_, slope) in d.face_slopes(|v| v.height) {
+            assert_eq!(slope.slope, 0.0);
+            assert_eq!(slope.aspect, None);
+            assert_eq!(slope.normal, [0.0, 0.0, 1.0]);
+        }
+    }
+
+    fn ridge() -> FloatDelaunayTriangulation<PointWithHeight, DelaunayWalkLocate> {
+        let mut d = FloatDelaunayTriangulation::with_walk_locate();
+        for x in 0..=10 {
+            for y in 0..=4 {
+                let height = if x == 5 { 10.0 } else { 0.0 };
+                d.insert(PointWithHeight {
+                    point: Point2::new(f64::from(x), f64::from(y)),
+                    height,
+                });
+            }
+        }
+        d
+    }
+
+    #[test]
+    fn test_is_visible() {
+        let d = ridge();
+        let height = |v: &PointWithHeight| v.height;
+        let (left, right) = (Point2::new(1.0, 2.5), Point2::new(9.0, 1.5));
+        assert!(!d.is_visible(height, &left, 1.0, &right, 1.0));
+        assert!(!d.is_visible(height, &right, 1.0, &left, 1.0));
+        assert!(d.is_visible(height, &left, 10.0, &right, 10.5));
+        assert!(d.is_visible(height, &left, 1.0, &Point2::new(4.0, 0.5), 0.0));
+        // Sightlines through vertices and along edges
+        let (from, to) = (Point2::new(1.0, 2.0), Point2::new(9.0, 2.0));
+        assert!(!d.is_visible(height, &from, 5.0, &to, 5.0));
+        assert!(d.is_visible(height, &from, 11.0, &to, 11.0));
+        let (from, to) = (Point2::new(0.0, 0.0), Point2::new(10.0, 0.0));
+        assert!(!d.is_visible(height, &from, 5.0, &to, 5.0));
+        // Points outside of the convex hull
+        let (from, to) = (Point2::new(-5.0, 2.0), Point2::new(15.0, 2.0));
+        assert!(!d.is_visible(height, &from, 5.0, &to, 5.0));
+        assert!(d.is_visible(height, &from, 5.0, &Point2::new(-5.0, 10.0), 5.0));
+    }
+
+    #[test]
+    fn test_viewshed() {
+        let d = grid(4, |_, _| 0.0);
+        let visible = d.viewshed(|v| v.height, &Point2::new(2.0, 2.0), 1.0);
+        assert_eq!(visible.len(), d.num_faces());
+        assert!(!visible[d.infinite_face().fix()]);
+        assert!(d.triangles().all(|face| visible[face.fix()]));
+
+        let d = ridge();
+        let visible = d.viewshed(|v| v.height, &Point2::new(1.0, 2.0), 1.0);
+        for face in d.triangles() {
+            let max_x = face
+                .as_triangle()
+                .iter()
+                .map(|v| v.point.x)
+                .fold(0.0, f64::max);
+            let min_x = face
+                .as_triangle()
+                .iter()
+                .map(|v| v.point.x)
+                .fold(10.0, f64::min);
+            if max_x <= 4.0 {
+                assert!(visible[face.fix()]);
+            }
+            if min_x >= 6.0 {
+                assert!(!visible[face.fix()]);
+            }
+        }
+        // An observer far above the ridge sees the faces behind it
+        let visible = d.viewshed(|v| v.height, &Point2::new(1.0, 2.0), 100.0);
+        assert!(d
+            .triangles()
+            .filter(|face| face.as_triangle().iter().all(|v| v.point.x >= 6.0))
+            .all(|face| visible[face.fix()]));
+    }
+
+    #[test]
+    fn test_volume_to_plane() {
+        let d = grid(4, |x, _| x);
+        let volume = d.volume_to_plane(|v| v.height, 2.0);
+        assert_relative_eq!(volume.area, 16.0);
+        assert_relative_eq!(volume.cut, 8.0);
+        assert_relative_eq!(volume.fill, 8.0);
+        assert_relative_eq!(volume.net(), 0.0, epsilon = 1e-10);
+    }
+
+    #[test]
+    fn test_volume_to_surface() {
+        let ground = grid(4, |x, y| x + y);
+        let mut design = FloatDelaunayTriangulation::with_walk_locate();
+        for p in &[[1.5, 0.5], [6.0, 0.5], [1.5, 3.0], [6.0, 3.0]] {
+            design.insert(Point2::new(p[0], p[1]));
+        }
+        let volume = ground.volume_to_surface(|v| v.height, design.triangles(), |_| 2.0);
+        // The common footprint is the rectangle [1.5, 4] x [0.5, 3]
+        assert_relative_eq!(volume.area, 2.5 * 2.5, epsilon = 1e-10);
+        let expected_net = (2.75 + 1.75 - 2.0) * 2.5 * 2.5;
+        assert_relative_eq!(volume.net(), expected_net, epsilon = 1e-10);
+        let plane = ground.volume_to_surface(|v| v.height, ground.triangles(), |v| v.height);
+        assert_relative_eq!(plane.area, 16.0, epsilon = 1e-10);
+        assert_relative_eq!(plane.net(), 0.0, epsilon = 1e-10);
+    }
+
+    #[test]
+    fn test_raster_grid() {
+        let bbox =
+            crate::BoundingRect::from_corners(&Point2::new(1.0, -2.0), &Point2::new(5.0, 1.0));
+        let grid = RasterGrid::new(bbox, 4, 6);
+        assert_eq!(grid.len(), 24);
+        assert!(!grid.is_empty());
+        assert_eq!(grid.cell_size(), Point2::new(1.0, 0.5));
+        assert_eq!(grid.sample(0, 0), Point2::new(1.5, -1.75));
+        assert_eq!(grid.sample(3, 5), Point2::new(4.5, 0.75));
+        assert!(RasterGrid::new(bbox, 0, 3).is_empty());
+    }
+
+    #[test]
+    fn test_rasterize_heights() {
+        let mut delaunay = FloatDelaunayTriangulation::with_walk_locate();
+        for point in crate::testutils::random_points_with_seed::<f64>(200, &[9; 32]) {
+            delaunay.insert(point);
+        }
+        let height = |p: &Point2<f64>| p.x * p.x - 2.0 * p.y;
+        let bbox =
+            crate::BoundingRect::from_corners(&Point2::new(-1.2, -1.1), &Point2::new(1.3, 1.0));
+        let grid = RasterGrid::new(bbox, 53, 41);
+        let raster = delaunay.rasterize_heights(&grid, height);
+        assert_eq!(raster.len(), grid.len());
+        let mut inside = 0;
+        for row in 0..grid.rows() {
+            for column in 0..grid.columns() {
+                let sample = grid.sample(column, row);
+                let value = raster[row * grid.columns() + column];
+                if let PositionInTriangulation::OutsideConvexHull(_) = delaunay.locate(&sample) {
+                    assert_eq!(value, None);
+                } else {
+                    let expected = delaunay.barycentric_interpolation(&sample, height);
+                    assert_relative_eq!(value.unwrap(), expected.unwrap(), epsilon = 1e-10);
+                    inside += 1;
+                }
+            }
+        }
+        assert!(inside > 0 && inside < grid.len());
+    }
+
+    #[test]
+    fn test_rasterize_heights_on_edges() {
+        // Cell centers coincide with vertices and edges
+        let mut delaunay = FloatDelaunayTriangulation::with_walk_locate();
+        for x in 0..=10 {
+            for y in 0..=10 {
+                delaunay.insert(Point2::new(f64::from(x), f64::from(y)));
+            }
+        }
+        let bbox =
+            crate::BoundingRect::from_corners(&Point2::new(-0.5, -0.5), &Point2::new(10.5, 10.5));
+        let raster = delaunay.rasterize_heights(&RasterGrid::new(bbox, 22, 11), |p| p.x + p.y);
+        for (index, value) in raster.iter().enumerate() {
+            let (column, row) = (index % 22, index / 22);
+            let expected = (column as f64 + 0.5) * 0.5 - 0.5 + row as f64;
+            if column == 0 || column == 21 {
+                assert_eq!(*value, None);
+            } else {
+                assert_relative_eq!(value.unwrap(), expected, epsilon = 1e-12);
+            }
+        }
+    }
+}
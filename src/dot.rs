@@ -0,0 +1,303 @@
+// Copyright 2017 The Spade Developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! GraphViz DOT export of r-trees and triangulations.
+//!
+//! `RTree::to_dot` writes the directory structure of an r-tree as a directed
+//! graph. Each directory node is labeled with its depth, its number of
+//! children and its bounding rectangle, which makes unbalanced or badly
+//! overlapping nodes easy to spot. `RTree::to_dot_with_leaves` additionally
+//! writes one node per contained object.
+//!
+//! Triangulations write their vertices and edges as an undirected graph.
+//! Vertices are pinned to their position, use `neato -n` or `fdp` for
+//! rendering.
+//!
+//! # Example
+//!
+//! ```
+//! use spade::rtree::RTree;
+//! use spade::Point2;
+//!
+//! let mut tree = RTree::new();
+//! for i in 0..20 {
+//!     tree.insert(Point2::new(i as f64, (i * i % 7) as f64));
+//! }
+//! let dot = tree.to_dot();
+//! assert!(dot.starts_with("digraph rtree {"));
+//! // Write `dot` into a file and render it with `dot -Tsvg tree.dot -o tree.svg`.
+//! ```
+
+use crate::delaunay::{
+    ConstrainedDelaunayTriangulation, DelaunayLocateStructure, DelaunayTriangulation, EdgeHandle,
+    VertexHandle,
+};
+use crate::kernels::DelaunayKernel;
+use crate::point_traits::{PointN, TwoDimensional};
+use crate::rtree::{DirectoryNodeData, RTree, RTreeNode};
+use crate::traits::{HasPosition2D, SpatialObject};
+use alloc::{format, string::String, vec, vec::Vec};
+use core::fmt::{self, Write};
+
+/// Escapes a string for use within a quoted DOT identifier.
+fn escape(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '"' => result.push_str("\\\""),
+            '\\' => result.push_str("\\\\"),
+            '\n' => result.push_str("\\n"),
+            c => result.push(c),
+        }
+    }
+    result
+}
+
+fn write_point<P>(out: &mut String, point: &P)
+where
+    P: PointN,
+    P::Scalar: fmt::Display,
+{
+    out.push('(');
+    for i in 0..P::dimensions() {
+        if i > 0 {
+            out.push_str(", ");
+        }
+        write!(out, "{}", point.nth(i)).unwrap();
+    }
+    out.push(')');
+}
+
+fn write_rtree<T, F>(tree: &RTree<T>, mut leaf_label: Option<F>) -> String
+where
+    T: SpatialObject,
+    <T::Point as PointN>::Scalar: fmt::Display,
+    F: FnMut(&T) -> String,
+{
+    let mut out = String::from("digraph rtree {\n");
+    out.push_str("    node [shape=box, fontname=monospace];\n");
+    if tree.size() == 0 {
+        out.push_str("    n0 [label=\"empty\"];\n}\n");
+        return out;
+    }
+    let mut next_id = 1;
+    let mut stack: Vec<(&DirectoryNodeData<T>, usize)> = vec![(tree.root(), 0)];
+    while let Some((node, id)) = stack.pop() {
+        let leaves = node
+            .children()
+            .iter()
+            .filter(|child| matches!(child, RTreeNode::Leaf(_)))
+            .count();
+        let mbr = node.mbr();
+        let mut label = format!(
+            "depth {}\n{} children, {} leaves\n",
+            node.depth(),
+            node.children().len(),
+            leaves
+        );
+        write_point(&mut label, &mbr.lower());
+        label.push_str(" - ");
+        write_point(&mut label, &mbr.upper());
+        writeln!(out, "    n{} [label=\"{}\"];", id, escape(&label)).unwrap();
+        for child in node.children() {
+            match child {
+                RTreeNode::DirectoryNode(data) => {
+                    writeln!(out, "    n{} -> n{};", id, next_id).unwrap();
+                    stack.push((data, next_id));
+                    next_id += 1;
+                }
+                RTreeNode::Leaf(object) => {
+                    if let Some(leaf_label) = leaf_label.as_mut() {
+                        writeln!(
+                            out,
+                            "    n{} [shape=ellipse, label=\"{}\"];\n    n{} -> n{};",
+                            next_id,
+                            escape(&leaf_label(object)),
+                            id,
+                            next_id
+                        )
+                        .unwrap();
+                        next_id += 1;
+                    }
+                }
+            }
+        }
+    }
+    out.push_str("}\n");
+    out
+}
+
+impl<T> RTree<T>
+where
+    T: SpatialObject,
+    <T::Point as PointN>::Scalar: fmt::Display,
+{
+    /// Writes the tree's directory nodes as a GraphViz DOT graph.
+    ///
+    /// Each node is labeled with its depth, the number of its children and
+    /// leaves and its bounding rectangle. Leaves are not written, use
+    /// `to_dot_with_leaves` to include them.
+    pub fn to_dot(&self) -> String {
+        write_rtree(self, None::<fn(&T) -> String>)
+    }
+
+    /// Writes the tree as a GraphViz DOT graph, including one node per object.
+    ///
+    /// `label` returns the label of an object's node. This is only
+    /// recommended for small trees.
+    pub fn to_dot_with_leaves<F>(&self, label: F) -> String
+    where
+        F: FnMut(&T) -> String,
+    {
+        write_rtree(self, Some(label))
+    }
+}
+
+fn write_triangulation<'a, V, E, IV, IE, F>(
+    vertices: IV,
+    edges: IE,
+    mut edge_attributes: F,
+) -> String
+where
+    V: HasPosition2D + 'a,
+    V::Point: TwoDimensional,
+    <V::Point as PointN>::Scalar: fmt::Display,
+    E: Default + Copy + 'a,
+    IV: IntoIterator<Item = VertexHandle<'a, V, E>>,
+    IE: IntoIterator<Item = EdgeHandle<'a, V, E>>,
+    F: FnMut(&EdgeHandle<'a, V, E>) -> Option<&'static str>,
+{
+    let mut out = String::from("graph triangulation {\n");
+    out.push_str("    node [shape=point];\n");
+    for vertex in vertices {
+        let position = vertex.position();
+        writeln!(
+            out,
+            "    v{} [xlabel=\"{}\", pos=\"{},{}!\"];",
+            vertex.fix(),
+            vertex.fix(),
+            position.nth(0),
+            position.nth(1)
+        )
+        .unwrap();
+    }
+    for edge in edges {
+        write!(out, "    v{} -- v{}", edge.from().fix(), edge.to().fix()).unwrap();
+        if let Some(attributes) = edge_attributes(&edge) {
+            write!(out, " [{}]", attributes).unwrap();
+        }
+        out.push_str(";\n");
+    }
+    out.push_str("}\n");
+    out
+}
+
+impl<V, K, L> DelaunayTriangulation<V, K, L>
+where
+    V: HasPosition2D,
+    V::Point: TwoDimensional,
+    <V::Point as PointN>::Scalar: fmt::Display,
+    K: DelaunayKernel<<V::Point as PointN>::Scalar>,
+    L: DelaunayLocateStructure<V::Point>,
+{
+    /// Writes the triangulation's vertices and edges as a GraphViz DOT graph.
+    ///
+    /// Vertices are named after their fixed handle and pinned to their position.
+    pub fn to_dot(&self) -> String {
+        write_triangulation(self.vertices(), self.edges(), |_| None)
+    }
+}
+
+impl<V, K, L> ConstrainedDelaunayTriangulation<V, K, L>
+where
+    V: HasPosition2D,
+    V::Point: TwoDimensional,
+    <V::Point as PointN>::Scalar: fmt::Display,
+    K: DelaunayKernel<<V::Point as PointN>::Scalar>,
+    L: DelaunayLocateStructure<V::Point>,
+{
+    /// Writes the triangulation's vertices and edges as a GraphViz DOT graph.
+    ///
+    /// Vertices are named after their fixed handle and pinned to their position.
+    /// Constraint edges are drawn bold.
+    pub fn to_dot(&self) -> String {
+        write_triangulation(self.vertices(), self.edges(), |edge| {
+            if self.is_constraint_edge(edge.fix()) {
+                Some("style=bold")
+            } else {
+                None
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::delaunay::{FloatCDT, FloatDelaunayTriangulation};
+    use crate::rtree::RTree;
+    use crate::Point2;
+
+    #[test]
+    fn test_rtree_to_dot() {
+        let mut tree = RTree::new();
+        for i in 0..200 {
+            tree.insert(Point2::new(f64::from(i % 17), f64::from(i / 17)));
+        }
+        let dot = tree.to_dot();
+        assert!(dot.starts_with("digraph rtree {\n"));
+        assert!(dot.ends_with("}\n"));
+        let nodes = dot.matches("[label=").count();
+        let links = dot.matches(" -> ").count();
+        assert!(nodes > 1);
+        assert_eq!(links, nodes - 1);
+
+        let with_leaves = tree.to_dot_with_leaves(|p| format!("{:?}", p));
+        assert_eq!(with_leaves.matches("shape=ellipse").count(), 200);
+        assert_eq!(with_leaves.matches(" -> ").count(), nodes - 1 + 200);
+        assert!(with_leaves.contains("Point2 { x: 16.0, y: 0.0 }"));
+    }
+
+    #[test]
+    fn test_empty_rtree_to_dot() {
+        let tree: RTree<Point2<f64>> = RTree::new();
+        assert_eq!(
+            tree.to_dot(),
+            "digraph rtree {\n    node [shape=box, fontname=monospace];\n    n0 [label=\"empty\"];\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_label_escaping() {
+        let mut tree = RTree::new();
+        tree.insert(Point2::new(0.0, 0.0));
+        let dot = tree.to_dot_with_leaves(|_| "a \"quoted\" \\ label".to_string());
+        assert!(dot.contains("label=\"a \\\"quoted\\\" \\\\ label\""));
+    }
+
+    #[test]
+    fn test_triangulation_to_dot() {
+        let mut delaunay = FloatDelaunayTriangulation::with_walk_locate();
+        delaunay.insert(Point2::new(0.0, 0.0));
+        delaunay.insert(Point2::new(1.0, 0.0));
+        delaunay.insert(Point2::new(0.0, 1.5));
+        let dot = delaunay.to_dot();
+        assert!(dot.starts_with("graph triangulation {\n"));
+        assert!(dot.contains("v2 [xlabel=\"2\", pos=\"0,1.5!\"];"));
+        assert_eq!(dot.matches(" -- ").count(), 3);
+
+        let mut cdt = FloatCDT::with_walk_locate();
+        let v0 = cdt.insert(Point2::new(0.0, 0.0));
+        let v1 = cdt.insert(Point2::new(1.0, 0.0));
+        cdt.insert(Point2::new(0.0, 1.0));
+        cdt.insert(Point2::new(1.0, 1.0));
+        cdt.add_constraint(v0, v1);
+        let dot = cdt.to_dot();
+        assert_eq!(dot.matches(" -- ").count(), 5);
+        assert_eq!(dot.matches("[style=bold]").count(), 1);
+    }
+}
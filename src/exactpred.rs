@@ -325,7 +325,7 @@ fn incircleadapt(pa: [f64; 2], pb: [f64; 2], pc: [f64; 2], pd: [f64; 2], permane
         );
         finlength =
             fast_expansion_sum_zeroelim(&fin1[..finlength], &temp48[..temp48len], &mut fin2);
-        ::std::mem::swap(&mut fin1, &mut fin2)
+        ::core::mem::swap(&mut fin1, &mut fin2)
     }
 
     let mut aytbclen = 9;
@@ -355,7 +355,7 @@ fn incircleadapt(pa: [f64; 2], pb: [f64; 2], pc: [f64; 2], pd: [f64; 2], permane
         );
         finlength =
             fast_expansion_sum_zeroelim(&fin1[..finlength], &temp48[..temp48len], &mut fin2);
-        ::std::mem::swap(&mut fin1, &mut fin2)
+        ::core::mem::swap(&mut fin1, &mut fin2)
     }
 
     let mut bxtcalen = 9;
@@ -384,7 +384,7 @@ fn incircleadapt(pa: [f64; 2], pb: [f64; 2], pc: [f64; 2], pd: [f64; 2], permane
         );
         finlength =
             fast_expansion_sum_zeroelim(&fin1[..finlength], &temp48[..temp48len], &mut fin2);
-        ::std::mem::swap(&mut fin1, &mut fin2)
+        ::core::mem::swap(&mut fin1, &mut fin2)
     }
 
     let mut bytcalen = 9;
@@ -414,7 +414,7 @@ fn incircleadapt(pa: [f64; 2], pb: [f64; 2], pc: [f64; 2], pd: [f64; 2], permane
 
         finlength =
             fast_expansion_sum_zeroelim(&fin1[..finlength], &temp48[..temp48len], &mut fin2);
-        ::std::mem::swap(&mut fin1, &mut fin2)
+        ::core::mem::swap(&mut fin1, &mut fin2)
     }
 
     let mut cxtab = [0f64; 8];
@@ -443,7 +443,7 @@ fn incircleadapt(pa: [f64; 2], pb: [f64; 2], pc: [f64; 2], pd: [f64; 2], permane
         );
         finlength =
             fast_expansion_sum_zeroelim(&fin1[..finlength], &temp48[..temp48len], &mut fin2);
-        ::std::mem::swap(&mut fin1, &mut fin2);
+        ::core::mem::swap(&mut fin1, &mut fin2);
     }
 
     let mut cytab = [0f64; 8];
@@ -472,7 +472,7 @@ fn incircleadapt(pa: [f64; 2], pb: [f64; 2], pc: [f64; 2], pd: [f64; 2], permane
         );
         finlength =
             fast_expansion_sum_zeroelim(&fin1[..finlength], &temp48[..temp48len], &mut fin2);
-        ::std::mem::swap(&mut fin1, &mut fin2);
+        ::core::mem::swap(&mut fin1, &mut fin2);
     }
 
     if adxtail != 0.0 || adytail != 0.0 {
@@ -517,7 +517,7 @@ fn incircleadapt(pa: [f64; 2], pb: [f64; 2], pc: [f64; 2], pd: [f64; 2], permane
             );
             finlength =
                 fast_expansion_sum_zeroelim(&fin1[..finlength], &temp48[..temp48len], &mut fin2);
-            ::std::mem::swap(&mut fin1, &mut fin2);
+            ::core::mem::swap(&mut fin1, &mut fin2);
 
             if bdytail != 0.0 {
                 let temp8len = scale_expansion_zeroelim(&cc, adxtail, &mut temp8);
@@ -528,7 +528,7 @@ fn incircleadapt(pa: [f64; 2], pb: [f64; 2], pc: [f64; 2], pd: [f64; 2], permane
                     &temp16a[..temp16alen],
                     &mut fin2,
                 );
-                ::std::mem::swap(&mut fin1, &mut fin2);
+                ::core::mem::swap(&mut fin1, &mut fin2);
             }
             if cdytail != 0.0 {
                 let temp8len = scale_expansion_zeroelim(&bb, -adxtail, &mut temp8);
@@ -539,7 +539,7 @@ fn incircleadapt(pa: [f64; 2], pb: [f64; 2], pc: [f64; 2], pd: [f64; 2], permane
                     &temp16a[..temp16alen],
                     &mut fin2,
                 );
-                ::std::mem::swap(&mut fin1, &mut fin2);
+                ::core::mem::swap(&mut fin1, &mut fin2);
             }
 
             let temp32alen = scale_expansion_zeroelim(&axtbct[..axtbctlen], adxtail, &mut temp32a);
@@ -561,7 +561,7 @@ fn incircleadapt(pa: [f64; 2], pb: [f64; 2], pc: [f64; 2], pd: [f64; 2], permane
             );
             finlength =
                 fast_expansion_sum_zeroelim(&fin1[..finlength], &temp64[..temp64len], &mut fin2);
-            ::std::mem::swap(&mut fin1, &mut fin2);
+            ::core::mem::swap(&mut fin1, &mut fin2);
         }
 
         if adytail != 0.0 {
@@ -577,7 +577,7 @@ fn incircleadapt(pa: [f64; 2], pb: [f64; 2], pc: [f64; 2], pd: [f64; 2], permane
             );
             finlength =
                 fast_expansion_sum_zeroelim(&fin1[..finlength], &temp48[..temp48len], &mut fin2);
-            ::std::mem::swap(&mut fin1, &mut fin2);
+            ::core::mem::swap(&mut fin1, &mut fin2);
 
             let temp32alen = scale_expansion_zeroelim(&aytbct[..aytbctlen], adytail, &mut temp32a);
             let mut aytbctt = [0f64; 8];
@@ -598,7 +598,7 @@ fn incircleadapt(pa: [f64; 2], pb: [f64; 2], pc: [f64; 2], pd: [f64; 2], permane
             );
             finlength =
                 fast_expansion_sum_zeroelim(&fin1[..finlength], &temp64[..temp64len], &mut fin2);
-            ::std::mem::swap(&mut fin1, &mut fin2);
+            ::core::mem::swap(&mut fin1, &mut fin2);
         }
     }
 
@@ -646,7 +646,7 @@ fn incircleadapt(pa: [f64; 2], pb: [f64; 2], pc: [f64; 2], pd: [f64; 2], permane
             );
             finlength =
                 fast_expansion_sum_zeroelim(&fin1[..finlength], &temp48[..temp48len], &mut fin2);
-            ::std::mem::swap(&mut fin1, &mut fin2);
+            ::core::mem::swap(&mut fin1, &mut fin2);
 
             if cdytail != 0.0 {
                 let temp8len = scale_expansion_zeroelim(&aa, bdxtail, &mut temp8);
@@ -657,7 +657,7 @@ fn incircleadapt(pa: [f64; 2], pb: [f64; 2], pc: [f64; 2], pd: [f64; 2], permane
                     &temp16a[..temp16alen],
                     &mut fin2,
                 );
-                ::std::mem::swap(&mut fin1, &mut fin2);
+                ::core::mem::swap(&mut fin1, &mut fin2);
             }
             if adytail != 0.0 {
                 let temp8len = scale_expansion_zeroelim(&cc, -bdxtail, &mut temp8);
@@ -668,7 +668,7 @@ fn incircleadapt(pa: [f64; 2], pb: [f64; 2], pc: [f64; 2], pd: [f64; 2], permane
                     &temp16a[..temp16alen],
                     &mut fin2,
                 );
-                ::std::mem::swap(&mut fin1, &mut fin2);
+                ::core::mem::swap(&mut fin1, &mut fin2);
             }
 
             let temp32alen = scale_expansion_zeroelim(&bxtcat[..bxtcatlen], bdxtail, &mut temp32a);
@@ -690,7 +690,7 @@ fn incircleadapt(pa: [f64; 2], pb: [f64; 2], pc: [f64; 2], pd: [f64; 2], permane
             );
             finlength =
                 fast_expansion_sum_zeroelim(&fin1[..finlength], &temp64[..temp64len], &mut fin2);
-            ::std::mem::swap(&mut fin1, &mut fin2);
+            ::core::mem::swap(&mut fin1, &mut fin2);
         }
         if bdytail != 0.0 {
             let temp16alen = scale_expansion_zeroelim(&bytca[..bytcalen], bdytail, &mut temp16a);
@@ -705,7 +705,7 @@ fn incircleadapt(pa: [f64; 2], pb: [f64; 2], pc: [f64; 2], pd: [f64; 2], permane
             );
             finlength =
                 fast_expansion_sum_zeroelim(&fin1[..finlength], &temp48[..temp48len], &mut fin2);
-            ::std::mem::swap(&mut fin1, &mut fin2);
+            ::core::mem::swap(&mut fin1, &mut fin2);
 
             let temp32alen = scale_expansion_zeroelim(&bytcat[..bytcatlen], bdytail, &mut temp32a);
             let mut bytcatt = [0f64; 8];
@@ -726,7 +726,7 @@ fn incircleadapt(pa: [f64; 2], pb: [f64; 2], pc: [f64; 2], pd: [f64; 2], permane
             );
             finlength =
                 fast_expansion_sum_zeroelim(&fin1[..finlength], &temp64[..temp64len], &mut fin2);
-            ::std::mem::swap(&mut fin1, &mut fin2);
+            ::core::mem::swap(&mut fin1, &mut fin2);
         }
     }
 
@@ -774,7 +774,7 @@ fn incircleadapt(pa: [f64; 2], pb: [f64; 2], pc: [f64; 2], pd: [f64; 2], permane
             );
             finlength =
                 fast_expansion_sum_zeroelim(&fin1[..finlength], &temp48[..temp48len], &mut fin2);
-            ::std::mem::swap(&mut fin1, &mut fin2);
+            ::core::mem::swap(&mut fin1, &mut fin2);
 
             if adytail != 0.0 {
                 let temp8len = scale_expansion_zeroelim(&bb, cdxtail, &mut temp8);
@@ -785,7 +785,7 @@ fn incircleadapt(pa: [f64; 2], pb: [f64; 2], pc: [f64; 2], pd: [f64; 2], permane
                     &temp16a[..temp16alen],
                     &mut fin2,
                 );
-                ::std::mem::swap(&mut fin1, &mut fin2);
+                ::core::mem::swap(&mut fin1, &mut fin2);
             }
             if bdytail != 0.0 {
                 let temp8len = scale_expansion_zeroelim(&aa, -cdxtail, &mut temp8);
@@ -796,7 +796,7 @@ fn incircleadapt(pa: [f64; 2], pb: [f64; 2], pc: [f64; 2], pd: [f64; 2], permane
                     &temp16a[..temp16alen],
                     &mut fin2,
                 );
-                ::std::mem::swap(&mut fin1, &mut fin2);
+                ::core::mem::swap(&mut fin1, &mut fin2);
             }
 
             let temp32alen = scale_expansion_zeroelim(&cxtabt[..cxtabtlen], cdxtail, &mut temp32a);
@@ -818,7 +818,7 @@ fn incircleadapt(pa: [f64; 2], pb: [f64; 2], pc: [f64; 2], pd: [f64; 2], permane
             );
             finlength =
                 fast_expansion_sum_zeroelim(&fin1[..finlength], &temp64[..temp64len], &mut fin2);
-            ::std::mem::swap(&mut fin1, &mut fin2);
+            ::core::mem::swap(&mut fin1, &mut fin2);
         }
         if cdytail != 0.0 {
             let temp16alen = scale_expansion_zeroelim(&cytab[..cytablen], cdytail, &mut temp16a);
@@ -833,7 +833,7 @@ fn incircleadapt(pa: [f64; 2], pb: [f64; 2], pc: [f64; 2], pd: [f64; 2], permane
             );
             finlength =
                 fast_expansion_sum_zeroelim(&fin1[..finlength], &temp48[..temp48len], &mut fin2);
-            ::std::mem::swap(&mut fin1, &mut fin2);
+            ::core::mem::swap(&mut fin1, &mut fin2);
 
             let temp32alen = scale_expansion_zeroelim(&cytabt[..cytabtlen], cdytail, &mut temp32a);
             let mut cytabtt = [0f64; 8];
@@ -854,7 +854,7 @@ fn incircleadapt(pa: [f64; 2], pb: [f64; 2], pc: [f64; 2], pd: [f64; 2], permane
             );
             finlength =
                 fast_expansion_sum_zeroelim(&fin1[..finlength], &temp64[..temp64len], &mut fin2);
-            ::std::mem::swap(&mut fin1, &mut fin2);
+            ::core::mem::swap(&mut fin1, &mut fin2);
         }
     }
     fin1[finlength - 1]
@@ -1165,5 +1165,4 @@ mod test {
         // the result predicates.c gives
         assert!(incircle(&pa, &pb, &pc, &pd) == 7.226864249343135e-30);
     }
-
 }
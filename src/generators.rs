@@ -0,0 +1,435 @@
+// Copyright 2017 The Spade Developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Input generators for property based testing and fuzzing.
+//!
+//! With the `arbitrary` feature, points, edges and triangles implement
+//! `arbitrary::Arbitrary`. `SimplePolygon` implements it as well and always
+//! yields a valid, counterclockwise ring. `CollinearPoints` and
+//! `CocircularPoints` generate degenerate configurations that are known to
+//! stress geometric predicates.
+//!
+//! With the `proptest` feature, the `strategies` module offers the equivalent
+//! `proptest` strategies, e.g. `point2`, `polygon_ring` or `cocircular_points`.
+//!
+//! Degenerate point sets use integer coordinates. They are exactly collinear
+//! or cocircular for every scalar type.
+//!
+//! # Example
+//!
+//! ```
+//! # extern crate spade;
+//! use spade::delaunay::FloatDelaunayTriangulation;
+//! use spade::generators::lattice_circle_points;
+//! use spade::Point2;
+//!
+//! # fn main() {
+//! let points = lattice_circle_points(&Point2::new(10.0, -5.0));
+//! let mut delaunay = FloatDelaunayTriangulation::with_walk_locate();
+//! for point in &points {
+//!     delaunay.insert(*point);
+//! }
+//! assert_eq!(delaunay.num_vertices(), points.len());
+//! # }
+//! ```
+
+use crate::point_traits::{PointNExtensions, TwoDimensional};
+use crate::primitives::SimplePolygon;
+use crate::traits::{SpadeFloat, SpadeNum};
+use alloc::vec::Vec;
+use num::{Float, NumCast};
+
+#[cfg(feature = "arbitrary")]
+use arbitrary::{Arbitrary, Unstructured};
+
+/// The radius of the circle used by `lattice_circle_points`.
+///
+/// 65 is the smallest radius whose circle passes through 36 integer points.
+pub const LATTICE_CIRCLE_RADIUS: i32 = 65;
+
+/// The largest number of vertices of a generated polygon ring.
+pub const MAX_RING_VERTICES: usize = 32;
+
+fn from_i32<S: NumCast>(value: i32) -> S {
+    NumCast::from(value).expect("Integer coordinate does not fit into the scalar type")
+}
+
+fn point_from_i32<V>(x: i32, y: i32) -> V
+where
+    V: TwoDimensional,
+    V::Scalar: NumCast,
+{
+    let mut result = V::new();
+    *result.nth_mut(0) = from_i32(x);
+    *result.nth_mut(1) = from_i32(y);
+    result
+}
+
+/// Creates a star shaped polygon around a center.
+///
+/// The vertices are spread evenly by angle, vertex `i` has the distance
+/// `radii[i]` to the center. The polygon is ordered counterclockwise and is
+/// simple as long as all radii are positive.
+///
+/// # Panics
+/// Panics if less than three radii are given.
+pub fn star_polygon<V>(center: &V, radii: &[V::Scalar]) -> SimplePolygon<V>
+where
+    V: TwoDimensional,
+    V::Scalar: SpadeFloat,
+{
+    let n: V::Scalar = NumCast::from(radii.len()).unwrap();
+    let full_turn: V::Scalar = NumCast::from(2.0 * ::core::f64::consts::PI).unwrap();
+    let vertices = radii
+        .iter()
+        .enumerate()
+        .map(|(i, radius)| {
+            let angle = full_turn * NumCast::from(i).unwrap() / n;
+            let mut offset = V::new();
+            *offset.nth_mut(0) = angle.cos() * *radius;
+            *offset.nth_mut(1) = angle.sin() * *radius;
+            center.add(&offset)
+        })
+        .collect();
+    SimplePolygon::new(vertices)
+}
+
+/// Returns the points `origin + direction * step` for each step.
+///
+/// With integer coordinates, the result is exactly collinear.
+pub fn collinear_points<V>(origin: &V, direction: &V, steps: &[i32]) -> Vec<V>
+where
+    V: TwoDimensional,
+    V::Scalar: SpadeNum + NumCast,
+{
+    steps
+        .iter()
+        .map(|step| origin.add(&direction.mul(from_i32(*step))))
+        .collect()
+}
+
+/// Returns all 36 integer points on a circle of radius `LATTICE_CIRCLE_RADIUS`.
+///
+/// The points are ordered counterclockwise, starting at the positive x axis.
+/// With an integer center, any four of them are exactly cocircular.
+pub fn lattice_circle_points<V>(center: &V) -> Vec<V>
+where
+    V: TwoDimensional,
+    V::Scalar: SpadeNum + NumCast,
+{
+    lattice_circle_offsets()
+        .into_iter()
+        .map(|(x, y)| center.add(&point_from_i32(x, y)))
+        .collect()
+}
+
+fn lattice_circle_offsets() -> Vec<(i32, i32)> {
+    let r = LATTICE_CIRCLE_RADIUS;
+    let mut first_quadrant = Vec::new();
+    for x in (1..=r).rev() {
+        let y2 = r * r - x * x;
+        let y = (y2 as f64).sqrt().round() as i32;
+        if y * y == y2 {
+            first_quadrant.push((x, y));
+        }
+    }
+    // Rotate the quadrant by 0, 90, 180 and 270 degrees.
+    let mut result = Vec::with_capacity(first_quadrant.len() * 4);
+    for quadrant in 0..4 {
+        for &(x, y) in &first_quadrant {
+            result.push(match quadrant {
+                0 => (x, y),
+                1 => (-y, x),
+                2 => (-x, -y),
+                _ => (y, -x),
+            });
+        }
+    }
+    result
+}
+
+/// A set of exactly collinear points with integer coordinates.
+///
+/// Points may repeat.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CollinearPoints<V>(pub Vec<V>);
+
+/// A set of at least four exactly cocircular points with integer coordinates.
+///
+/// The points are a subset of `lattice_circle_points`, ordered counterclockwise.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CocircularPoints<V>(pub Vec<V>);
+
+#[cfg(feature = "arbitrary")]
+impl<'a, V> Arbitrary<'a> for SimplePolygon<V>
+where
+    V: TwoDimensional,
+    V::Scalar: SpadeFloat,
+{
+    fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+        let count = u.int_in_range(3..=MAX_RING_VERTICES)?;
+        let radii = (0..count)
+            .map(|_| {
+                let step: u8 = u.int_in_range(1..=64)?;
+                Ok(<V::Scalar as NumCast>::from(step as f64 / 64.0).unwrap())
+            })
+            .collect::<arbitrary::Result<Vec<_>>>()?;
+        Ok(star_polygon(&V::new(), &radii))
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+impl<'a, V> Arbitrary<'a> for CollinearPoints<V>
+where
+    V: TwoDimensional,
+    V::Scalar: SpadeNum + NumCast,
+{
+    fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+        let origin =
+            point_from_i32::<V>(u.int_in_range(-1000..=1000)?, u.int_in_range(-1000..=1000)?);
+        let mut direction = (u.int_in_range(-10..=10)?, u.int_in_range(-10..=10)?);
+        if direction == (0, 0) {
+            direction = (1, 0);
+        }
+        let direction = point_from_i32::<V>(direction.0, direction.1);
+        let count = u.int_in_range(2..=16)?;
+        let steps = (0..count)
+            .map(|_| u.int_in_range(-100..=100))
+            .collect::<arbitrary::Result<Vec<_>>>()?;
+        Ok(CollinearPoints(collinear_points(
+            &origin, &direction, &steps,
+        )))
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+impl<'a, V> Arbitrary<'a> for CocircularPoints<V>
+where
+    V: TwoDimensional,
+    V::Scalar: SpadeNum + NumCast,
+{
+    fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+        let center =
+            point_from_i32::<V>(u.int_in_range(-1000..=1000)?, u.int_in_range(-1000..=1000)?);
+        let candidates = lattice_circle_points(&center);
+        let mut points = Vec::new();
+        for point in &candidates {
+            if u.arbitrary()? {
+                points.push(point.clone());
+            }
+        }
+        if points.len() < 4 {
+            points = candidates.into_iter().step_by(9).collect();
+        }
+        Ok(CocircularPoints(points))
+    }
+}
+
+/// `proptest` strategies for primitives, polygon rings and degenerate point sets.
+///
+/// Requires the `proptest` feature.
+#[cfg(feature = "proptest")]
+pub mod strategies {
+    use crate::point::Point2;
+    use crate::primitives::{SimpleEdge, SimplePolygon, SimpleTriangle};
+    use alloc::vec::Vec;
+    use core::ops::{Range, RangeInclusive};
+    use proptest::prelude::*;
+
+    /// A strategy for points with both coordinates in `range`.
+    pub fn point2(range: Range<f64>) -> impl Strategy<Value = Point2<f64>> {
+        (range.clone(), range).prop_map(|(x, y)| Point2::new(x, y))
+    }
+
+    /// A strategy for edges whose end points lie in `range`.
+    ///
+    /// Both end points may coincide.
+    pub fn edge(range: Range<f64>) -> impl Strategy<Value = SimpleEdge<Point2<f64>>> {
+        (point2(range.clone()), point2(range)).prop_map(|(from, to)| SimpleEdge::new(from, to))
+    }
+
+    /// A strategy for non degenerate triangles whose vertices lie in `range`.
+    pub fn triangle(range: Range<f64>) -> impl Strategy<Value = SimpleTriangle<Point2<f64>>> {
+        (point2(range.clone()), point2(range.clone()), point2(range))
+            .prop_map(|(v0, v1, v2)| SimpleTriangle::new(v0, v1, v2))
+            .prop_filter("degenerate triangle", |triangle| {
+                triangle.double_area() != 0.0
+            })
+    }
+
+    /// A strategy for valid, counterclockwise polygon rings.
+    ///
+    /// The rings are star shaped around the origin and fit into the unit circle.
+    /// `vertices` is clamped to `3..=MAX_RING_VERTICES`.
+    pub fn polygon_ring(
+        vertices: RangeInclusive<usize>,
+    ) -> impl Strategy<Value = SimplePolygon<Point2<f64>>> {
+        let min = (*vertices.start()).clamp(3, super::MAX_RING_VERTICES);
+        let max = (*vertices.end()).clamp(min, super::MAX_RING_VERTICES);
+        proptest::collection::vec(1.0f64 / 64.0..=1.0, min..=max)
+            .prop_map(|radii| super::star_polygon(&Point2::new(0.0, 0.0), &radii))
+    }
+
+    /// A strategy for exactly collinear points with integer coordinates.
+    ///
+    /// `count` gives the number of points, which may repeat.
+    pub fn collinear_points(
+        count: RangeInclusive<usize>,
+    ) -> impl Strategy<Value = Vec<Point2<f64>>> {
+        (
+            (-1000i32..=1000, -1000i32..=1000),
+            (-10i32..=10, -10i32..=10).prop_filter("zero direction", |d| *d != (0, 0)),
+            proptest::collection::vec(-100i32..=100, count),
+        )
+            .prop_map(|(origin, direction, steps)| {
+                super::collinear_points(
+                    &super::point_from_i32(origin.0, origin.1),
+                    &super::point_from_i32(direction.0, direction.1),
+                    &steps,
+                )
+            })
+    }
+
+    /// A strategy for exactly cocircular points with integer coordinates.
+    ///
+    /// The points are a subset of `super::lattice_circle_points` around a random
+    /// center. `count` is clamped to `4..=36`.
+    pub fn cocircular_points(
+        count: RangeInclusive<usize>,
+    ) -> impl Strategy<Value = Vec<Point2<f64>>> {
+        let min = (*count.start()).clamp(4, 36);
+        let max = (*count.end()).clamp(min, 36);
+        (
+            (-1000i32..=1000, -1000i32..=1000),
+            proptest::sample::subsequence(super::lattice_circle_offsets(), min..=max),
+        )
+            .prop_map(|(center, offsets)| {
+                offsets
+                    .into_iter()
+                    .map(|(x, y)| Point2::new(f64::from(center.0 + x), f64::from(center.1 + y)))
+                    .collect()
+            })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{collinear_points, lattice_circle_points, star_polygon, LATTICE_CIRCLE_RADIUS};
+    use crate::delaunay::FloatDelaunayTriangulation;
+    use crate::point::Point2;
+    use crate::polygon::validate_polygon;
+
+    #[test]
+    fn test_lattice_circle_points() {
+        let points = lattice_circle_points(&Point2::new(3i64, -2));
+        assert_eq!(points.len(), 36);
+        let r2 = i64::from(LATTICE_CIRCLE_RADIUS * LATTICE_CIRCLE_RADIUS);
+        for point in &points {
+            let (x, y) = (point.x - 3, point.y + 2);
+            assert_eq!(x * x + y * y, r2);
+        }
+        let float_points = lattice_circle_points(&Point2::new(0.0, 0.0));
+        let mut delaunay = FloatDelaunayTriangulation::with_walk_locate();
+        for point in &float_points {
+            delaunay.insert(*point);
+        }
+        assert_eq!(delaunay.num_vertices(), 36);
+        assert_eq!(delaunay.num_triangles(), 34);
+    }
+
+    #[test]
+    fn test_star_polygon_is_valid() {
+        let radii = [1.0, 0.2, 0.9, 0.2, 0.5, 1.0, 0.05];
+        let polygon = star_polygon(&Point2::new(1.0, 2.0), &radii);
+        assert_eq!(validate_polygon(polygon.vertices()), Ok(()));
+    }
+
+    #[test]
+    fn test_collinear_points() {
+        let points = collinear_points(&Point2::new(1, 1), &Point2::new(2, -1), &[0, 3, -2]);
+        assert_eq!(
+            points,
+            vec![Point2::new(1, 1), Point2::new(7, -2), Point2::new(-3, 3)]
+        );
+    }
+
+    #[cfg(feature = "arbitrary")]
+    #[test]
+    fn test_arbitrary_polygons_are_valid() {
+        use crate::primitives::SimplePolygon;
+        use arbitrary::{Arbitrary, Unstructured};
+        use rand::{Rng, SeedableRng};
+
+        let mut rng = rand::rngs::StdRng::seed_from_u64(7);
+        for _ in 0..100 {
+            let bytes: Vec<u8> = (0..256).map(|_| rng.gen()).collect();
+            let mut u = Unstructured::new(&bytes);
+            let polygon = SimplePolygon::<Point2<f64>>::arbitrary(&mut u).unwrap();
+            assert_eq!(validate_polygon(polygon.vertices()), Ok(()));
+        }
+    }
+
+    #[cfg(feature = "arbitrary")]
+    #[test]
+    fn test_arbitrary_degenerate_points() {
+        use super::{CocircularPoints, CollinearPoints};
+        use crate::kernels::{DelaunayKernel, TrivialKernel};
+        use arbitrary::{Arbitrary, Unstructured};
+
+        let bytes: Vec<u8> = (0..=255).collect();
+        let mut u = Unstructured::new(&bytes);
+        let CollinearPoints(points) = CollinearPoints::<Point2<i64>>::arbitrary(&mut u).unwrap();
+        for window in points.windows(3) {
+            let edge = crate::primitives::SimpleEdge::new(window[0], window[1]);
+            if window[0] != window[1] {
+                assert!(TrivialKernel::side_query(&edge, &window[2]).is_on_line());
+            }
+        }
+        let CocircularPoints(points) = CocircularPoints::<Point2<i64>>::arbitrary(&mut u).unwrap();
+        assert!(points.len() >= 4);
+        for window in points.windows(4) {
+            assert!(!TrivialKernel::contained_in_circumference(
+                &window[0], &window[1], &window[2], &window[3]
+            ));
+        }
+    }
+
+    #[cfg(feature = "proptest")]
+    mod strategies {
+        use crate::delaunay::FloatDelaunayTriangulation;
+        use crate::generators::strategies::{
+            cocircular_points, collinear_points, polygon_ring, triangle,
+        };
+        use crate::polygon::validate_polygon;
+        use proptest::prelude::*;
+
+        proptest! {
+            #[test]
+            fn polygon_rings_are_valid(polygon in polygon_ring(3..=20)) {
+                prop_assert_eq!(validate_polygon(polygon.vertices()), Ok(()));
+            }
+
+            #[test]
+            fn triangles_are_not_degenerate(triangle in triangle(-10.0..10.0)) {
+                prop_assert!(triangle.double_area() != 0.0);
+            }
+
+            #[test]
+            fn degenerate_points_triangulate(
+                collinear in collinear_points(2..=10),
+                cocircular in cocircular_points(4..=12),
+            ) {
+                let mut delaunay = FloatDelaunayTriangulation::with_walk_locate();
+                for point in collinear.iter().chain(cocircular.iter()) {
+                    delaunay.insert(*point);
+                }
+                prop_assert!(delaunay.num_vertices() >= cocircular.len());
+            }
+        }
+    }
+}
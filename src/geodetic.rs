@@ -0,0 +1,193 @@
+// Copyright 2017 The Spade Developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use crate::rtree::RTree;
+use crate::traits::{HasPosition, SpadeFloat};
+use alloc::vec::Vec;
+use num::{one, NumCast};
+
+/// The earth's mean radius in meters, used by `GeoPoint` distances.
+pub const EARTH_RADIUS: f64 = 6_371_008.8;
+
+/// A position on the earth's surface, given by latitude and longitude in degrees.
+///
+/// Geodetic points can be inserted into r-trees. Internally, each point is
+/// represented by its position on the unit sphere. The straight line distance
+/// between two such positions grows monotonically with their great-circle
+/// distance, nearest neighbor queries thus return the neighbor with the smallest
+/// great-circle distance. Since bounding rectangles are calculated on the sphere
+/// instead of in the latitude / longitude plane, queries work across the
+/// antimeridian and near the poles.
+///
+/// # Example
+///
+/// ```
+/// use spade::GeoPoint;
+/// use spade::rtree::RTree;
+///
+/// let mut tree = RTree::new();
+/// tree.insert(GeoPoint::new(0.0, -179.9));
+/// tree.insert(GeoPoint::new(0.0, 170.0));
+/// // The nearest neighbor lies on the other side of the antimeridian
+/// let nearest = tree.nearest_geodetic_neighbor(&GeoPoint::new(0.0, 179.95)).unwrap();
+/// assert_eq!(nearest.longitude(), -179.9);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde_serialize", derive(Serialize, Deserialize))]
+pub struct GeoPoint<S> {
+    latitude: S,
+    longitude: S,
+    position: [S; 3],
+}
+
+impl<S: SpadeFloat> GeoPoint<S> {
+    /// Creates a new point from latitude and longitude in degrees.
+    ///
+    /// # Panics
+    /// Panics if the latitude lies outside of `[-90, 90]` or if a coordinate
+    /// is not finite.
+    pub fn new(latitude: S, longitude: S) -> Self {
+        let max_latitude: S = NumCast::from(90.0).unwrap();
+        assert!(
+            latitude.abs() <= max_latitude && longitude.is_finite(),
+            "Invalid geodetic coordinates"
+        );
+        let (sin_lat, cos_lat) = latitude.to_radians().sin_cos();
+        let (sin_lon, cos_lon) = longitude.to_radians().sin_cos();
+        GeoPoint {
+            latitude,
+            longitude,
+            position: [cos_lat * cos_lon, cos_lat * sin_lon, sin_lat],
+        }
+    }
+
+    /// Returns the latitude in degrees.
+    pub fn latitude(&self) -> S {
+        self.latitude
+    }
+
+    /// Returns the longitude in degrees.
+    pub fn longitude(&self) -> S {
+        self.longitude
+    }
+
+    /// Returns the great-circle distance to another point in meters.
+    ///
+    /// Uses the haversine formula and assumes a spherical earth with radius
+    /// `EARTH_RADIUS`.
+    pub fn haversine_distance(&self, other: &GeoPoint<S>) -> S {
+        let two = one::<S>() + one();
+        let (lat1, lat2) = (self.latitude.to_radians(), other.latitude.to_radians());
+        let half_dlat = (lat2 - lat1) / two;
+        let half_dlon = (other.longitude - self.longitude).to_radians() / two;
+        let h = half_dlat.sin().powi(2) + lat1.cos() * lat2.cos() * half_dlon.sin().powi(2);
+        let radius: S = NumCast::from(EARTH_RADIUS).unwrap();
+        two * radius * h.sqrt().min(one()).asin()
+    }
+}
+
+impl<S: SpadeFloat> HasPosition for GeoPoint<S> {
+    type Point = [S; 3];
+
+    fn position(&self) -> [S; 3] {
+        self.position
+    }
+}
+
+/// Returns the squared straight line distance of two points on the unit sphere
+/// whose great-circle distance is `distance` meters.
+fn chord_length2<S: SpadeFloat>(distance: S) -> S {
+    let two = one::<S>() + one();
+    let radius: S = NumCast::from(EARTH_RADIUS).unwrap();
+    let pi: S = NumCast::from(::core::f64::consts::PI).unwrap();
+    let angle = distance / radius;
+    if angle >= pi {
+        return two * two;
+    }
+    let chord = two * (angle / two).sin();
+    chord * chord
+}
+
+impl<S: SpadeFloat> RTree<GeoPoint<S>> {
+    /// Returns the point with the smallest great-circle distance to a query point.
+    ///
+    /// Returns `None` if the tree is empty.
+    pub fn nearest_geodetic_neighbor(&self, query_point: &GeoPoint<S>) -> Option<&GeoPoint<S>> {
+        self.nearest_neighbor(&query_point.position)
+    }
+
+    /// Returns the `n` points with the smallest great-circle distance to a query point.
+    ///
+    /// The result is sorted by distance.
+    pub fn nearest_n_geodetic_neighbors(
+        &self,
+        query_point: &GeoPoint<S>,
+        n: usize,
+    ) -> Vec<&GeoPoint<S>> {
+        self.nearest_n_neighbors(&query_point.position, n)
+    }
+
+    /// Returns all points whose great-circle distance to a center is at most
+    /// `distance` meters.
+    pub fn lookup_within_distance(&self, center: &GeoPoint<S>, distance: S) -> Vec<&GeoPoint<S>> {
+        self.lookup_in_circle(&center.position, &chord_length2(distance))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::GeoPoint;
+    use crate::rtree::RTree;
+
+    #[test]
+    fn test_haversine_distance() {
+        let london = GeoPoint::new(51.5074, -0.1278);
+        let paris = GeoPoint::new(48.8566, 2.3522);
+        assert_relative_eq!(london.haversine_distance(&paris), 343_556.53, epsilon = 0.1);
+        assert_eq!(london.haversine_distance(&london), 0.0);
+        let west = GeoPoint::new(0.0, -179.9);
+        let east = GeoPoint::new(0.0, 179.9);
+        assert_relative_eq!(west.haversine_distance(&east), 22_239.016, epsilon = 0.01);
+        let north = GeoPoint::new(90.0, 0.0);
+        let south = GeoPoint::new(-90.0, 0.0);
+        assert_relative_eq!(
+            north.haversine_distance(&south),
+            super::EARTH_RADIUS * ::std::f64::consts::PI,
+            epsilon = 1e-6
+        );
+    }
+
+    #[test]
+    fn test_geodetic_queries() {
+        let points = vec![
+            GeoPoint::new(0.0, -179.9),
+            GeoPoint::new(0.0, 170.0),
+            GeoPoint::new(89.9, 180.0),
+            GeoPoint::new(89.0, 0.0),
+            GeoPoint::new(51.5074, -0.1278),
+        ];
+        let mut tree = RTree::new();
+        for point in &points {
+            tree.insert(*point);
+        }
+        let query = GeoPoint::new(0.0, 179.95);
+        assert_eq!(tree.nearest_geodetic_neighbor(&query), Some(&points[0]));
+        // Close to the pole, points with opposite longitudes are neighbors
+        let query = GeoPoint::new(89.9, 0.0);
+        assert_eq!(tree.nearest_geodetic_neighbor(&query), Some(&points[2]));
+        let nearest = tree.nearest_n_geodetic_neighbors(&query, 2);
+        assert_eq!(nearest, vec![&points[2], &points[3]]);
+
+        let found = tree.lookup_within_distance(&GeoPoint::new(0.0, 180.0), 20_000.0);
+        assert_eq!(found, vec![&points[0]]);
+        let mut found = tree.lookup_within_distance(&GeoPoint::new(89.95, 90.0), 150_000.0);
+        found.sort_by(|a, b| a.latitude().partial_cmp(&b.latitude()).unwrap());
+        assert_eq!(found, vec![&points[3], &points[2]]);
+        assert_eq!(tree.lookup_within_distance(&query, 1e9).len(), 5);
+    }
+}
@@ -0,0 +1,414 @@
+// Copyright 2017 The Spade Developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Writing geometries and triangulations as GeoJSON.
+//!
+//! A `FeatureCollection` gathers points, line strings and polygons, each with
+//! its own properties. Triangulations can write their triangles, Voronoi cells
+//! and contour lines directly into a collection. The result can be inspected
+//! with tools like geojson.io or QGIS.
+//! Requires the `geojson` feature.
+//!
+//! # Example
+//!
+//! ```
+//! # extern crate serde_json;
+//! # extern crate spade;
+//! use serde_json::json;
+//! use spade::delaunay::FloatDelaunayTriangulation;
+//! use spade::geojson::FeatureCollection;
+//! use spade::hull::convex_hull;
+//! use spade::kernels::FloatKernel;
+//! use spade::Point2;
+//!
+//! # fn main() {
+//! let points = vec![Point2::new(0.0, 0.0), Point2::new(2.0, 0.0), Point2::new(1.0, 2.0)];
+//! let mut delaunay = FloatDelaunayTriangulation::with_walk_locate();
+//! for point in &points {
+//!     delaunay.insert(*point);
+//! }
+//! let mut collection = delaunay.triangles_to_geojson(|face| json!({ "face": face.fix() }));
+//! collection.add_polygon(&convex_hull::<FloatKernel, _>(&points), json!({ "name": "hull" }));
+//! assert_eq!(collection.features().len(), 2);
+//! assert!(collection.to_string().contains("\"FeatureCollection\""));
+//! # }
+//! ```
+
+use crate::delaunay::{
+    CdtEdge, ConstrainedDelaunayTriangulation, DelaunayLocateStructure, DelaunayTriangulation,
+    FaceHandle, VertexHandle,
+};
+use crate::kernels::DelaunayKernel;
+use crate::point_traits::{PointN, PointNExtensions, TwoDimensional};
+use crate::primitives::SimpleTriangle;
+use crate::traits::{HasPosition2D, SpadeFloat};
+use core::fmt;
+use num::ToPrimitive;
+use serde_json::{json, Value};
+
+/// A GeoJSON feature collection.
+///
+/// Coordinates are converted into `f64`. The properties of a feature are given
+/// as `serde_json::Value`, they should be an object or `Value::Null`.
+/// Use `to_string` to write the collection as GeoJSON text.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FeatureCollection {
+    features: Vec<Value>,
+}
+
+fn coordinate<V>(point: &V) -> Value
+where
+    V: TwoDimensional,
+    V::Scalar: ToPrimitive,
+{
+    json!([point.nth(0).to_f64(), point.nth(1).to_f64()])
+}
+
+fn coordinates<V>(points: &[V]) -> Value
+where
+    V: TwoDimensional,
+    V::Scalar: ToPrimitive,
+{
+    Value::Array(points.iter().map(coordinate).collect())
+}
+
+impl FeatureCollection {
+    /// Creates an empty feature collection.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Returns all features that have been added.
+    pub fn features(&self) -> &[Value] {
+        &self.features
+    }
+
+    /// Adds a feature with an arbitrary GeoJSON geometry object.
+    pub fn add_feature(&mut self, geometry: Value, properties: Value) {
+        self.features.push(json!({
+            "type": "Feature",
+            "geometry": geometry,
+            "properties": properties,
+        }));
+    }
+
+    /// Adds a `Point` feature.
+    pub fn add_point<V>(&mut self, point: &V, properties: Value)
+    where
+        V: TwoDimensional,
+        V::Scalar: ToPrimitive,
+    {
+        let geometry = json!({ "type": "Point", "coordinates": coordinate(point) });
+        self.add_feature(geometry, properties);
+    }
+
+    /// Adds a `LineString` feature.
+    pub fn add_line_string<V>(&mut self, points: &[V], properties: Value)
+    where
+        V: TwoDimensional,
+        V::Scalar: ToPrimitive,
+    {
+        let geometry = json!({ "type": "LineString", "coordinates": coordinates(points) });
+        self.add_feature(geometry, properties);
+    }
+
+    /// Adds a `MultiLineString` feature.
+    pub fn add_multi_line_string<V>(&mut self, lines: &[Vec<V>], properties: Value)
+    where
+        V: TwoDimensional,
+        V::Scalar: ToPrimitive,
+    {
+        let lines: Vec<_> = lines.iter().map(|line| coordinates(line)).collect();
+        let geometry = json!({ "type": "MultiLineString", "coordinates": lines });
+        self.add_feature(geometry, properties);
+    }
+
+    /// Adds a `Polygon` feature without holes.
+    ///
+    /// The ring must not repeat its first vertex, as returned by
+    /// `convex_hull` or `SimplePolygon::vertices`. GeoJSON expects
+    /// counterclockwise exterior rings.
+    pub fn add_polygon<V>(&mut self, ring: &[V], properties: Value)
+    where
+        V: TwoDimensional,
+        V::Scalar: ToPrimitive,
+    {
+        let mut ring = ring.to_vec();
+        ring.extend(ring.first().cloned());
+        let geometry = json!({ "type": "Polygon", "coordinates": [coordinates(&ring)] });
+        self.add_feature(geometry, properties);
+    }
+
+    /// Returns the collection as a GeoJSON object.
+    pub fn to_value(&self) -> Value {
+        json!({ "type": "FeatureCollection", "features": self.features })
+    }
+}
+
+impl fmt::Display for FeatureCollection {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.to_value())
+    }
+}
+
+impl From<FeatureCollection> for Value {
+    fn from(collection: FeatureCollection) -> Value {
+        collection.to_value()
+    }
+}
+
+fn triangles_to_geojson<'a, V, E, F, I>(faces: I, mut properties: F) -> FeatureCollection
+where
+    I: IntoIterator<Item = FaceHandle<'a, V, E>>,
+    V: HasPosition2D + 'a,
+    V::Point: TwoDimensional,
+    <V::Point as PointN>::Scalar: ToPrimitive,
+    E: Default + Copy + 'a,
+    F: FnMut(FaceHandle<'a, V, E>) -> Value,
+{
+    let mut result = FeatureCollection::new();
+    for face in faces {
+        let [v0, v1, v2] = face.as_triangle();
+        let ring = [v0.position(), v1.position(), v2.position()];
+        result.add_polygon(&ring, properties(face));
+    }
+    result
+}
+
+/// Returns the segment along which a triangle crosses a contour level.
+fn contour_segment<P>(corners: &[(P, P::Scalar); 3], level: P::Scalar) -> Option<Vec<P>>
+where
+    P: TwoDimensional,
+    P::Scalar: SpadeFloat,
+{
+    let mut segment = Vec::with_capacity(2);
+    for i in 0..3 {
+        let (from, from_height) = &corners[i];
+        let (to, to_height) = &corners[(i + 1) % 3];
+        if (*from_height >= level) != (*to_height >= level) {
+            let t = (level - *from_height) / (*to_height - *from_height);
+            segment.push(from.add(&to.sub(from).mul(t)));
+        }
+    }
+    if segment.len() == 2 {
+        Some(segment)
+    } else {
+        None
+    }
+}
+
+impl<V, K, L> DelaunayTriangulation<V, K, L>
+where
+    V: HasPosition2D,
+    V::Point: TwoDimensional,
+    <V::Point as PointN>::Scalar: ToPrimitive,
+    K: DelaunayKernel<<V::Point as PointN>::Scalar>,
+    L: DelaunayLocateStructure<V::Point>,
+{
+    /// Writes every triangle as a `Polygon` feature.
+    ///
+    /// The properties of each feature are calculated from its face, e.g. from
+    /// the data of the face's vertices.
+    /// Requires the `geojson` feature.
+    pub fn triangles_to_geojson<F>(&self, properties: F) -> FeatureCollection
+    where
+        F: FnMut(FaceHandle<V>) -> Value,
+    {
+        triangles_to_geojson(self.triangles(), properties)
+    }
+
+    /// Writes the Voronoi cell of every vertex as a `Polygon` feature.
+    ///
+    /// Cells of vertices on the convex hull are unbounded and are skipped.
+    /// Requires the `geojson` feature.
+    pub fn voronoi_cells_to_geojson<F>(&self, mut properties: F) -> FeatureCollection
+    where
+        <V::Point as PointN>::Scalar: SpadeFloat,
+        F: FnMut(VertexHandle<V>) -> Value,
+    {
+        let infinite_face = self.infinite_face().fix();
+        let mut result = FeatureCollection::new();
+        for vertex in self.vertices() {
+            let faces: Vec<_> = vertex.ccw_adjacent_faces().collect();
+            if faces.is_empty() || faces.iter().any(|face| face.fix() == infinite_face) {
+                continue;
+            }
+            let cell: Vec<_> = faces
+                .iter()
+                .map(|face| {
+                    let [v0, v1, v2] = face.as_triangle();
+                    SimpleTriangle::new(v0.position(), v1.position(), v2.position()).circumcenter()
+                })
+                .collect();
+            result.add_polygon(&cell, properties(vertex));
+        }
+        result
+    }
+
+    /// Writes contour lines of a surface as `MultiLineString` features.
+    ///
+    /// The surface is defined by linearly interpolating the vertex heights
+    /// returned by `height` within each triangle. One feature is written per
+    /// level, its `level` property contains the contour's height. Each line
+    /// consists of the segment within a single triangle, segments are not
+    /// joined.
+    /// Requires the `geojson` feature.
+    pub fn contours_to_geojson<F>(
+        &self,
+        levels: &[<V::Point as PointN>::Scalar],
+        height: F,
+    ) -> FeatureCollection
+    where
+        <V::Point as PointN>::Scalar: SpadeFloat,
+        F: Fn(&V) -> <V::Point as PointN>::Scalar,
+    {
+        let triangles: Vec<_> = self
+            .triangles()
+            .map(|face| {
+                let [v0, v1, v2] = face.as_triangle();
+                [
+                    (v0.position(), height(&*v0)),
+                    (v1.position(), height(&*v1)),
+                    (v2.position(), height(&*v2)),
+                ]
+            })
+            .collect();
+        let mut result = FeatureCollection::new();
+        for level in levels {
+            let lines: Vec<_> = triangles
+                .iter()
+                .filter_map(|corners| contour_segment(corners, *level))
+                .collect();
+            result.add_multi_line_string(&lines, json!({ "level": level.to_f64() }));
+        }
+        result
+    }
+}
+
+impl<V, K, L> ConstrainedDelaunayTriangulation<V, K, L>
+where
+    V: HasPosition2D,
+    V::Point: TwoDimensional,
+    <V::Point as PointN>::Scalar: ToPrimitive,
+    K: DelaunayKernel<<V::Point as PointN>::Scalar>,
+    L: DelaunayLocateStructure<V::Point>,
+{
+    /// Writes every triangle as a `Polygon` feature.
+    ///
+    /// The properties of each feature are calculated from its face, e.g. from
+    /// the data of the face's vertices. Use `classify_regions` within
+    /// `properties` to mark triangles inside of constrained regions.
+    /// Requires the `geojson` feature.
+    pub fn triangles_to_geojson<F>(&self, properties: F) -> FeatureCollection
+    where
+        F: FnMut(FaceHandle<V, CdtEdge>) -> Value,
+    {
+        triangles_to_geojson(self.triangles(), properties)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::FeatureCollection;
+    use crate::delaunay::{FloatCDT, FloatDelaunayTriangulation};
+    use crate::point::Point2;
+    use crate::traits::HasPosition;
+    use serde_json::{json, Value};
+
+    #[test]
+    fn test_feature_collection() {
+        let mut collection = FeatureCollection::new();
+        collection.add_point(&[1.5f32, 2.0], Value::Null);
+        collection.add_line_string(&[[0, 0], [1, 1]], json!({ "id": 3 }));
+        collection.add_polygon(&[[0.0, 0.0], [1.0, 0.0], [0.0, 1.0]], json!({}));
+        let value: Value = serde_json::from_str(&collection.to_string()).unwrap();
+        assert_eq!(value, Value::from(collection));
+        assert_eq!(value["type"], "FeatureCollection");
+        let features = value["features"].as_array().unwrap();
+        assert_eq!(features.len(), 3);
+        assert_eq!(
+            features[0]["geometry"],
+            json!({ "type": "Point", "coordinates": [1.5, 2.0] })
+        );
+        assert_eq!(features[0]["properties"], Value::Null);
+        assert_eq!(features[1]["properties"]["id"], 3);
+        assert_eq!(
+            features[2]["geometry"]["coordinates"],
+            json!([[[0.0, 0.0], [1.0, 0.0], [0.0, 1.0], [0.0, 0.0]]])
+        );
+    }
+
+    #[derive(Clone, Copy, Debug)]
+    struct Sample {
+        position: Point2<f64>,
+        height: f64,
+    }
+
+    impl HasPosition for Sample {
+        type Point = Point2<f64>;
+
+        fn position(&self) -> Point2<f64> {
+            self.position
+        }
+    }
+
+    #[test]
+    fn test_triangulation_geojson() {
+        let mut delaunay = FloatDelaunayTriangulation::with_walk_locate();
+        for x in 0..3 {
+            for y in 0..3 {
+                delaunay.insert(Sample {
+                    position: Point2::new(x as f64, y as f64),
+                    height: x as f64,
+                });
+            }
+        }
+        let triangles = delaunay.triangles_to_geojson(|face| {
+            let [v0, v1, v2] = face.as_triangle();
+            json!({ "height": (v0.height + v1.height + v2.height) / 3.0 })
+        });
+        assert_eq!(triangles.features().len(), delaunay.num_triangles());
+        assert!(triangles
+            .features()
+            .iter()
+            .all(|f| f["properties"]["height"].is_f64()));
+
+        // Only the center vertex is not on the convex hull
+        let cells = delaunay.voronoi_cells_to_geojson(|v| json!({ "vertex": v.fix() }));
+        assert_eq!(cells.features().len(), 1);
+        let ring = cells.features()[0]["geometry"]["coordinates"][0]
+            .as_array()
+            .unwrap();
+        for corner in ring {
+            let (x, y) = (corner[0].as_f64().unwrap(), corner[1].as_f64().unwrap());
+            assert!((x - 1.0).abs() <= 0.5 + 1e-10 && (y - 1.0).abs() <= 0.5 + 1e-10);
+        }
+
+        let contours = delaunay.contours_to_geojson(&[0.5, 1.5, 5.0], |v| v.height);
+        let features = contours.features();
+        assert_eq!(features.len(), 3);
+        assert_eq!(features[0]["properties"]["level"], 0.5);
+        let lines = features[0]["geometry"]["coordinates"].as_array().unwrap();
+        assert!(!lines.is_empty());
+        for line in lines {
+            assert_eq!(line[0][0], 0.5);
+            assert_eq!(line[1][0], 0.5);
+        }
+        assert_eq!(features[2]["geometry"]["coordinates"], json!([]));
+    }
+
+    #[test]
+    fn test_cdt_geojson() {
+        let mut cdt = FloatCDT::with_walk_locate();
+        cdt.add_constraint_edge(Point2::new(0.0, 0.0), Point2::new(1.0, 1.0));
+        cdt.insert(Point2::new(1.0, 0.0));
+        cdt.insert(Point2::new(0.0, 1.0));
+        let collection = cdt.triangles_to_geojson(|_| Value::Null);
+        assert_eq!(collection.features().len(), 2);
+    }
+}
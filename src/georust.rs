@@ -0,0 +1,397 @@
+// Copyright 2017 The Spade Developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use crate::boundingrect::BoundingRect;
+use crate::delaunay::{ConstrainedDelaunayTriangulation, DelaunayLocateStructure};
+use crate::kernels::{DelaunayKernel, FloatKernel};
+use crate::point_traits::{PointN, PointNExtensions, TwoDimensional};
+use crate::polygon::winding_number;
+use crate::primitives::{SimpleEdge, SimplePolygon, SimpleTriangle};
+use crate::traits::{HasPosition2D, SpadeFloat, SpadeNum, SpatialObject};
+use alloc::vec::Vec;
+use geo_types::{Coord, CoordNum, Geometry, Line, LineString, Polygon, Rect, Triangle};
+use num::{zero, Float, FromPrimitive};
+
+impl<S: SpadeNum + CoordNum> From<Line<S>> for SimpleEdge<Coord<S>> {
+    fn from(line: Line<S>) -> Self {
+        SimpleEdge::new(line.start, line.end)
+    }
+}
+
+impl<S: SpadeNum + CoordNum> From<Triangle<S>> for SimpleTriangle<Coord<S>> {
+    fn from(triangle: Triangle<S>) -> Self {
+        let [v0, v1, v2] = triangle.to_array();
+        SimpleTriangle::new(v0, v1, v2)
+    }
+}
+
+impl<S: SpadeNum + CoordNum> From<Rect<S>> for BoundingRect<Coord<S>> {
+    fn from(rect: Rect<S>) -> Self {
+        BoundingRect::from_corners(&rect.min(), &rect.max())
+    }
+}
+
+impl<'a, S: SpadeNum + CoordNum> From<&'a LineString<S>> for SimplePolygon<Coord<S>> {
+    /// Interprets a line string as a polygon ring.
+    ///
+    /// The closing coordinate of a closed line string is not repeated.
+    ///
+    /// # Panics
+    /// Panics if the ring has less than three vertices.
+    fn from(ring: &'a LineString<S>) -> Self {
+        let mut vertices = ring.0.clone();
+        if ring.is_closed() {
+            vertices.pop();
+        }
+        SimplePolygon::new(vertices)
+    }
+}
+
+impl<V, K, L, S> ConstrainedDelaunayTriangulation<V, K, L>
+where
+    V: HasPosition2D + From<Coord<S>>,
+    V::Point: TwoDimensional + PointN<Scalar = S>,
+    S: SpadeNum + CoordNum,
+    K: DelaunayKernel<S>,
+    L: DelaunayLocateStructure<V::Point>,
+{
+    /// Inserts all coordinates of a line string and adds its segments as
+    /// constraint edges.
+    ///
+    /// Returns `true` if at least one constraint edge was added.
+    /// Requires the `geo-types` feature.
+    ///
+    /// # Panics
+    /// Panics if a segment intersects an existing constraint edge.
+    pub fn add_line_string_constraints(&mut self, line_string: &LineString<S>) -> bool {
+        let handles: Vec<_> = line_string
+            .coords()
+            .map(|coord| self.insert(V::from(*coord)))
+            .collect();
+        let mut result = false;
+        for pair in handles.windows(2) {
+            if pair[0] != pair[1] {
+                result |= self.add_constraint(pair[0], pair[1]);
+            }
+        }
+        result
+    }
+
+    /// Adds the exterior and all interior rings of a polygon as constraint
+    /// edges.
+    ///
+    /// Afterwards, `classify_regions` marks the polygon's interior as inside
+    /// while its holes are marked as outside.
+    /// Returns `true` if at least one constraint edge was added.
+    /// Requires the `geo-types` feature.
+    ///
+    /// # Panics
+    /// Panics if a ring intersects an existing constraint edge.
+    pub fn add_polygon_constraints(&mut self, polygon: &Polygon<S>) -> bool {
+        let mut result = self.add_line_string_constraints(polygon.exterior());
+        for interior in polygon.interiors() {
+            result |= self.add_line_string_constraints(interior);
+        }
+        result
+    }
+}
+
+/// A `geo-types` geometry that can be inserted into an r-tree.
+///
+/// The `geo` crate re-exports these geometries, a `geo::Geometry` can be
+/// wrapped as well.
+/// Supports all kinds of geometries, including multi geometries and geometry
+/// collections. The geometry is decomposed into points, edges and polygons
+/// once on construction, `distance2` is zero for points within a polygon or a
+/// rectangle and the squared distance to the closest point or edge otherwise.
+/// Requires the `geo-types` feature.
+///
+/// # Example
+///
+/// ```
+/// # extern crate geo_types;
+/// # extern crate spade;
+/// use geo_types::{coord, Geometry, Line, Rect};
+/// use spade::GeometryObject;
+/// use spade::rtree::RTree;
+///
+/// # fn main() {
+/// let mut tree = RTree::new();
+/// let rect = Rect::new(coord! { x: 0.0, y: 0.0 }, coord! { x: 2.0, y: 2.0 });
+/// let line = Line::new(coord! { x: 4.0, y: 0.0 }, coord! { x: 4.0, y: 4.0 });
+/// tree.insert(GeometryObject::new(Geometry::Rect(rect)).unwrap());
+/// tree.insert(GeometryObject::new(Geometry::Line(line)).unwrap());
+/// let nearest = tree.nearest_neighbor(&coord! { x: 3.5, y: 3.0 }).unwrap();
+/// assert_eq!(nearest.geometry(), &Geometry::Line(line));
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct GeometryObject<S: SpadeNum + CoordNum> {
+    geometry: Geometry<S>,
+    mbr: BoundingRect<Coord<S>>,
+    points: Vec<Coord<S>>,
+    edges: Vec<SimpleEdge<Coord<S>>>,
+    polygons: Vec<Vec<Vec<Coord<S>>>>,
+}
+
+impl<S> GeometryObject<S>
+where
+    S: SpadeFloat + FromPrimitive + CoordNum,
+{
+    /// Wraps a geometry.
+    ///
+    /// Returns `None` if the geometry contains no coordinates.
+    pub fn new(geometry: Geometry<S>) -> Option<Self> {
+        let mut result = GeometryObject {
+            geometry: geometry.clone(),
+            mbr: BoundingRect::from_point(Coord::new()),
+            points: Vec::new(),
+            edges: Vec::new(),
+            polygons: Vec::new(),
+        };
+        result.add(&geometry);
+        let coords = result
+            .points
+            .iter()
+            .chain(result.edges.iter().flat_map(|edge| [&edge.from, &edge.to]))
+            .cloned();
+        let mut coords = coords.peekable();
+        coords.peek()?;
+        result.mbr = BoundingRect::from_points(coords);
+        Some(result)
+    }
+
+    /// Returns the wrapped geometry.
+    pub fn geometry(&self) -> &Geometry<S> {
+        &self.geometry
+    }
+
+    /// Unwraps the geometry.
+    pub fn into_inner(self) -> Geometry<S> {
+        self.geometry
+    }
+
+    fn add(&mut self, geometry: &Geometry<S>) {
+        match geometry {
+            Geometry::Point(point) => self.points.push(point.0),
+            Geometry::Line(line) => self.edges.push(SimpleEdge::from(*line)),
+            Geometry::LineString(line_string) => self.add_line_string(line_string),
+            Geometry::Polygon(polygon) => self.add_polygon(polygon),
+            Geometry::MultiPoint(points) => self.points.extend(points.iter().map(|p| p.0)),
+            Geometry::MultiLineString(line_strings) => {
+                for line_string in line_strings {
+                    self.add_line_string(line_string);
+                }
+            }
+            Geometry::MultiPolygon(polygons) => {
+                for polygon in polygons {
+                    self.add_polygon(polygon);
+                }
+            }
+            Geometry::GeometryCollection(collection) => {
+                for geometry in collection {
+                    self.add(geometry);
+                }
+            }
+            Geometry::Rect(rect) => self.add_polygon(&rect.to_polygon()),
+            Geometry::Triangle(triangle) => self.add_polygon(&triangle.to_polygon()),
+        }
+    }
+
+    fn add_line_string(&mut self, line_string: &LineString<S>) {
+        match line_string.0.len() {
+            0 => {}
+            1 => self.points.push(line_string.0[0]),
+            _ => self.edges.extend(line_string.lines().map(SimpleEdge::from)),
+        }
+    }
+
+    fn add_polygon(&mut self, polygon: &Polygon<S>) {
+        let rings = ::core::iter::once(polygon.exterior()).chain(polygon.interiors());
+        let mut vertices = Vec::new();
+        for ring in rings {
+            self.add_line_string(ring);
+            let mut ring = ring.0.clone();
+            if ring.len() > 1 && ring.first() == ring.last() {
+                ring.pop();
+            }
+            vertices.push(ring);
+        }
+        self.polygons.push(vertices);
+    }
+
+    fn area_contains(&self, point: &Coord<S>) -> bool {
+        self.polygons.iter().any(|rings| {
+            let inside = |ring: &Vec<Coord<S>>| {
+                ring.len() >= 3 && winding_number::<FloatKernel, _>(ring, point) != 0
+            };
+            inside(&rings[0]) && !rings[1..].iter().any(inside)
+        })
+    }
+}
+
+impl<S> SpatialObject for GeometryObject<S>
+where
+    S: SpadeFloat + FromPrimitive + CoordNum,
+{
+    type Point = Coord<S>;
+
+    fn mbr(&self) -> BoundingRect<Coord<S>> {
+        self.mbr
+    }
+
+    fn distance2(&self, point: &Coord<S>) -> S {
+        if self.area_contains(point) {
+            return zero();
+        }
+        let point_distances = self.points.iter().map(|p| p.distance2(point));
+        let edge_distances = self.edges.iter().map(|edge| edge.distance2(point));
+        point_distances
+            .chain(edge_distances)
+            .fold(S::infinity(), Float::min)
+    }
+
+    fn contains(&self, point: &Coord<S>) -> bool {
+        self.distance2(point) <= zero()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::boundingrect::BoundingRect;
+    use crate::delaunay::{DelaunayWalkLocate, FloatCDT};
+    use crate::kernels::FloatKernel;
+    use crate::point_traits::{PointN, PointNExtensions};
+    use crate::primitives::{SimpleEdge, SimplePolygon, SimpleTriangle};
+    use crate::rtree::RTree;
+    use geo_types::{coord, line_string, point, polygon, Coord, Line, Point, Rect, Triangle};
+
+    #[test]
+    fn test_geo_points() {
+        let mut p = coord! { x: 1.0, y: 2.0 };
+        *p.nth_mut(0) = 3.0;
+        assert_eq!(p, coord! { x: 3.0, y: 2.0 });
+        assert_eq!(p.length2(), 13.0);
+        let points = vec![point!(x: 0.0, y: 0.0), point!(x: 2.0, y: 1.0)];
+        let tree = RTree::bulk_load(points);
+        assert_eq!(
+            tree.nearest_neighbor(&Point::new(1.5, 1.0)),
+            Some(&point!(x: 2.0, y: 1.0))
+        );
+    }
+
+    #[test]
+    fn test_primitive_conversions() {
+        let edge = SimpleEdge::from(Line::new(
+            coord! { x: 0.0, y: 0.0 },
+            coord! { x: 3.0, y: 4.0 },
+        ));
+        assert_eq!(edge.length2(), 25.0);
+        let triangle = SimpleTriangle::from(Triangle::new(
+            coord! { x: 0.0, y: 0.0 },
+            coord! { x: 2.0, y: 0.0 },
+            coord! { x: 0.0, y: 2.0 },
+        ));
+        assert_eq!(triangle.double_area(), 4.0);
+        let rect = BoundingRect::from(Rect::new(
+            coord! { x: 2.0, y: 0.0 },
+            coord! { x: 0.0, y: 1.0 },
+        ));
+        assert_eq!(rect.lower(), coord! { x: 0.0, y: 0.0 });
+        assert_eq!(rect.upper(), coord! { x: 2.0, y: 1.0 });
+
+        let ring =
+            line_string![(x: 0.0, y: 0.0), (x: 2.0, y: 0.0), (x: 2.0, y: 2.0), (x: 0.0, y: 0.0)];
+        let polygon = SimplePolygon::from(&ring);
+        assert_eq!(polygon.vertices().len(), 3);
+        assert_eq!(polygon.area(), 2.0);
+        assert!(polygon.contains_point::<FloatKernel>(&coord! { x: 1.5, y: 0.5 }));
+    }
+
+    #[test]
+    fn test_polygon_constraints() {
+        let polygon = polygon!(
+            exterior: [
+                (x: 0.0, y: 0.0),
+                (x: 4.0, y: 0.0),
+                (x: 4.0, y: 4.0),
+                (x: 0.0, y: 4.0),
+            ],
+            interiors: [
+                [
+                    (x: 1.0, y: 1.0),
+                    (x: 1.0, y: 2.0),
+                    (x: 2.0, y: 2.0),
+                    (x: 2.0, y: 1.0),
+                ],
+            ],
+        );
+        let mut cdt = FloatCDT::<Coord<f64>, DelaunayWalkLocate>::new();
+        assert!(cdt.add_polygon_constraints(&polygon));
+        assert_eq!(cdt.num_vertices(), 8);
+        assert_eq!(cdt.num_constraints(), 8);
+        let regions = cdt.classify_regions();
+        let inside_area: f64 = cdt
+            .triangles()
+            .filter(|face| regions.is_inside(face.fix()))
+            .map(|face| {
+                let [v0, v1, v2] = face.as_triangle();
+                SimpleTriangle::new(*v0, *v1, *v2).double_area() / 2.0
+            })
+            .sum();
+        assert_eq!(inside_area, 15.0);
+        assert!(!cdt.add_line_string_constraints(&line_string![(x: 0.0, y: 0.0), (x: 4.0, y: 0.0)]));
+    }
+
+    #[test]
+    fn test_geometry_objects() {
+        use super::GeometryObject;
+        use crate::traits::SpatialObject;
+        use geo_types::{Geometry, GeometryCollection, MultiPoint};
+
+        let with_hole = GeometryObject::new(Geometry::Polygon(polygon!(
+            exterior: [(x: 0.0, y: 0.0), (x: 4.0, y: 0.0), (x: 4.0, y: 4.0), (x: 0.0, y: 4.0)],
+            interiors: [[(x: 1.0, y: 1.0), (x: 3.0, y: 1.0), (x: 3.0, y: 3.0), (x: 1.0, y: 3.0)]],
+        )))
+        .unwrap();
+        assert_eq!(with_hole.distance2(&coord! { x: 0.5, y: 0.5 }), 0.0);
+        assert_eq!(with_hole.distance2(&coord! { x: 2.0, y: 1.5 }), 0.25);
+        assert_eq!(with_hole.distance2(&coord! { x: 6.0, y: 4.0 }), 4.0);
+        assert!(with_hole.contains(&coord! { x: 1.0, y: 2.0 }));
+        assert!(!with_hole.contains(&coord! { x: 2.0, y: 2.0 }));
+        assert_eq!(with_hole.mbr().upper(), coord! { x: 4.0, y: 4.0 });
+
+        let collection = Geometry::GeometryCollection(GeometryCollection::from(vec![
+            Geometry::MultiPoint(MultiPoint::from(vec![(10.0, 0.0), (12.0, 0.0)])),
+            Geometry::LineString(line_string![(x: 0.0, y: 10.0), (x: 0.0, y: 12.0)]),
+        ]));
+        let collection = GeometryObject::new(collection).unwrap();
+        assert_eq!(collection.distance2(&coord! { x: 11.0, y: 1.0 }), 2.0);
+        assert_eq!(collection.distance2(&coord! { x: 1.0, y: 11.0 }), 1.0);
+        assert_eq!(collection.mbr().lower(), coord! { x: 0.0, y: 0.0 });
+        assert!(
+            GeometryObject::<f64>::new(Geometry::GeometryCollection(Default::default())).is_none()
+        );
+
+        let mut tree = RTree::new();
+        tree.insert(with_hole);
+        tree.insert(collection.clone());
+        let nearest = tree.nearest_neighbor(&coord! { x: 9.0, y: 0.0 }).unwrap();
+        assert_eq!(nearest.geometry(), collection.geometry());
+        assert_eq!(
+            tree.lookup_in_circle(&coord! { x: 2.0, y: 2.0 }, &0.5)
+                .len(),
+            0
+        );
+        assert_eq!(
+            tree.lookup_in_circle(&coord! { x: 2.0, y: 2.0 }, &1.5)
+                .len(),
+            1
+        );
+    }
+}
@@ -0,0 +1,333 @@
+// Copyright 2017 The Spade Developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Distance calculation between convex shapes.
+//!
+//! Uses the Gilbert-Johnson-Keerthi (GJK) algorithm. Any convex shape can
+//! take part by implementing `ConvexShape`, no special case is needed for
+//! each pair of shapes.
+
+use crate::boundingrect::BoundingRect;
+use crate::point_traits::{PointNExtensions, TwoDimensional};
+use crate::primitives::{
+    ConvexPolygon, OrientedRect, SimpleCapsule, SimpleCircle, SimpleEdge, SimpleTriangle,
+};
+use crate::traits::SpadeFloat;
+use alloc::{vec, vec::Vec};
+use num::{one, zero, Float, NumCast};
+
+const MAX_ITERATIONS: usize = 64;
+
+/// A convex shape, described by its support function.
+pub trait ConvexShape {
+    /// The shape's point type.
+    type Point: TwoDimensional;
+
+    /// Returns a point of the shape that lies farthest in a given direction.
+    ///
+    /// The direction does not need to be normalized. If several points are
+    /// farthest away, any of them can be returned.
+    fn support(&self, direction: &Self::Point) -> Self::Point;
+}
+
+/// Calculates the squared distance between two convex shapes.
+///
+/// Returns zero if the shapes overlap or touch.
+///
+/// # Example
+///
+/// ```
+/// # extern crate nalgebra;
+/// # extern crate spade;
+/// use nalgebra::Point2;
+/// use spade::gjk::distance2;
+/// use spade::primitives::{SimpleCircle, SimpleEdge};
+///
+/// # fn main() {
+/// let circle = SimpleCircle::new(Point2::new(0.0f64, 0.0), 1.0);
+/// let edge = SimpleEdge::new(Point2::new(-5.0, 3.0), Point2::new(5.0, 3.0));
+/// assert!((distance2(&circle, &edge) - 4.0).abs() < 1e-10);
+/// # }
+/// ```
+pub fn distance2<A, B, V>(first: &A, second: &B) -> V::Scalar
+where
+    A: ConvexShape<Point = V>,
+    B: ConvexShape<Point = V>,
+    V: TwoDimensional,
+    V::Scalar: SpadeFloat,
+{
+    let tolerance: V::Scalar = NumCast::from(1e-12).unwrap();
+    // Support function of the Minkowski difference first - second
+    let support = |direction: &V| {
+        first
+            .support(direction)
+            .sub(&second.support(&direction.mul(-one::<V::Scalar>())))
+    };
+    let mut initial = V::new();
+    *initial.nth_mut(0) = one();
+    let mut closest = support(&initial);
+    let mut simplex = vec![closest.clone()];
+    for _ in 0..MAX_ITERATIONS {
+        let length2 = closest.length2();
+        if length2 == zero() {
+            return zero();
+        }
+        let new_point = support(&closest.mul(-one::<V::Scalar>()));
+        // Stop if the new point does not get closer to the origin
+        if length2 - closest.dot(&new_point) <= tolerance * length2 {
+            break;
+        }
+        if simplex.contains(&new_point) {
+            break;
+        }
+        simplex.push(new_point);
+        match reduce_simplex(&simplex) {
+            Some((point, reduced)) => {
+                closest = point;
+                simplex = reduced;
+            }
+            // The origin lies within the simplex
+            None => return zero(),
+        }
+    }
+    closest.length2()
+}
+
+/// Returns the simplex' point closest to the origin and the smallest sub
+/// simplex that contains this point.
+///
+/// Returns `None` if the simplex is a triangle that contains the origin.
+fn reduce_simplex<V>(simplex: &[V]) -> Option<(V, Vec<V>)>
+where
+    V: TwoDimensional,
+    V::Scalar: SpadeFloat,
+{
+    match simplex.len() {
+        1 => Some((simplex[0].clone(), simplex.to_vec())),
+        2 => {
+            let (a, b) = (&simplex[0], &simplex[1]);
+            let dir = b.sub(a);
+            let length2 = dir.length2();
+            if length2 == zero() {
+                return Some((a.clone(), vec![a.clone()]));
+            }
+            let t = -a.dot(&dir) / length2;
+            if t <= zero() {
+                Some((a.clone(), vec![a.clone()]))
+            } else if t >= one() {
+                Some((b.clone(), vec![b.clone()]))
+            } else {
+                Some((a.add(&dir.mul(t)), simplex.to_vec()))
+            }
+        }
+        _ => {
+            let sides: Vec<_> = (0..3)
+                .map(|i| {
+                    let (from, to) = (&simplex[i], &simplex[(i + 1) % 3]);
+                    let edge = to.sub(from);
+                    *edge.nth(0) * *from.nth(1) - *edge.nth(1) * *from.nth(0)
+                })
+                .collect();
+            let (a, b, c) = (&simplex[0], &simplex[1], &simplex[2]);
+            let (ab, ac) = (b.sub(a), c.sub(a));
+            let area = *ab.nth(0) * *ac.nth(1) - *ab.nth(1) * *ac.nth(0);
+            let on_left = sides.iter().all(|side| *side <= zero());
+            let on_right = sides.iter().all(|side| *side >= zero());
+            if area != zero() && (on_left || on_right) {
+                return None;
+            }
+            (0..3)
+                .filter_map(|i| reduce_simplex(&[simplex[i].clone(), simplex[(i + 1) % 3].clone()]))
+                .min_by(|(p0, _), (p1, _)| p0.length2().partial_cmp(&p1.length2()).unwrap())
+        }
+    }
+}
+
+fn farthest_point<'a, V, I>(points: I, direction: &V) -> V
+where
+    V: TwoDimensional + 'a,
+    V::Scalar: SpadeFloat,
+    I: IntoIterator<Item = &'a V>,
+{
+    let mut iter = points.into_iter();
+    let mut result = iter.next().unwrap();
+    let mut max = result.dot(direction);
+    for point in iter {
+        let value = point.dot(direction);
+        if value > max {
+            result = point;
+            max = value;
+        }
+    }
+    result.clone()
+}
+
+impl<V> ConvexShape for SimpleEdge<V>
+where
+    V: TwoDimensional,
+    V::Scalar: SpadeFloat,
+{
+    type Point = V;
+
+    fn support(&self, direction: &V) -> V {
+        farthest_point(&[self.from.clone(), self.to.clone()], direction)
+    }
+}
+
+impl<V> ConvexShape for SimpleTriangle<V>
+where
+    V: TwoDimensional,
+    V::Scalar: SpadeFloat,
+{
+    type Point = V;
+
+    fn support(&self, direction: &V) -> V {
+        farthest_point(self.vertices().iter().cloned(), direction)
+    }
+}
+
+impl<V> ConvexShape for SimpleCircle<V>
+where
+    V: TwoDimensional,
+    V::Scalar: SpadeFloat,
+{
+    type Point = V;
+
+    fn support(&self, direction: &V) -> V {
+        let length = direction.length2().sqrt();
+        if length == zero() {
+            return self.center.clone();
+        }
+        self.center.add(&direction.mul(self.radius / length))
+    }
+}
+
+impl<V> ConvexShape for SimpleCapsule<V>
+where
+    V: TwoDimensional,
+    V::Scalar: SpadeFloat,
+{
+    type Point = V;
+
+    fn support(&self, direction: &V) -> V {
+        SimpleCircle::new(self.segment.support(direction), self.radius).support(direction)
+    }
+}
+
+impl<V> ConvexShape for BoundingRect<V>
+where
+    V: TwoDimensional,
+    V::Scalar: SpadeFloat,
+{
+    type Point = V;
+
+    fn support(&self, direction: &V) -> V {
+        let (lower, upper) = (self.lower(), self.upper());
+        let mut result = V::new();
+        for dim in 0..2 {
+            *result.nth_mut(dim) = if *direction.nth(dim) >= zero() {
+                *upper.nth(dim)
+            } else {
+                *lower.nth(dim)
+            };
+        }
+        result
+    }
+}
+
+impl<V> ConvexShape for ConvexPolygon<V>
+where
+    V: TwoDimensional,
+    V::Scalar: SpadeFloat,
+{
+    type Point = V;
+
+    fn support(&self, direction: &V) -> V {
+        self.extreme_point(direction).clone()
+    }
+}
+
+impl<V> ConvexShape for OrientedRect<V>
+where
+    V: TwoDimensional,
+    V::Scalar: SpadeFloat,
+{
+    type Point = V;
+
+    fn support(&self, direction: &V) -> V {
+        farthest_point(&self.corners(), direction)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::distance2;
+    use crate::boundingrect::BoundingRect;
+    use crate::primitives::{
+        OrientedRect, SimpleCapsule, SimpleCircle, SimpleEdge, SimpleTriangle,
+    };
+    use crate::testutils::*;
+    use crate::traits::SpatialObject;
+    use cgmath::Point2;
+
+    #[test]
+    fn test_simple_distances() {
+        let c1 = SimpleCircle::new(Point2::new(0.0f64, 0.0), 1.0);
+        let c2 = SimpleCircle::new(Point2::new(4.0, 3.0), 2.0);
+        assert_relative_eq!(distance2(&c1, &c2), 4.0, epsilon = 1e-8);
+        assert_relative_eq!(distance2(&c2, &c1), 4.0, epsilon = 1e-8);
+        let c3 = SimpleCircle::new(Point2::new(1.0, 1.0), 1.0);
+        assert_eq!(distance2(&c1, &c3), 0.0);
+
+        let r1 = BoundingRect::from_corners(&Point2::new(0.0, 0.0), &Point2::new(1.0, 1.0));
+        let r2 = BoundingRect::from_corners(&Point2::new(3.0, 2.0), &Point2::new(4.0, 4.0));
+        assert_relative_eq!(distance2(&r1, &r2), 5.0, epsilon = 1e-10);
+        assert_eq!(distance2(&r1, &r1), 0.0);
+
+        let triangle = SimpleTriangle::new(
+            Point2::new(0.0, 0.0),
+            Point2::new(4.0, 0.0),
+            Point2::new(0.0, 4.0),
+        );
+        let inside = SimpleCircle::new(Point2::new(1.0, 1.0), 0.1);
+        assert_eq!(distance2(&triangle, &inside), 0.0);
+        let outside = SimpleCircle::new(Point2::new(4.0, 4.0), 1.0);
+        let expected = (8.0f64.sqrt() - 1.0).powi(2);
+        assert_relative_eq!(distance2(&triangle, &outside), expected, epsilon = 1e-8);
+
+        let capsule = SimpleCapsule::new(Point2::new(-5.0, 6.0), Point2::new(5.0, 6.0), 1.0);
+        assert_relative_eq!(distance2(&capsule, &triangle), 1.0, epsilon = 1e-8);
+
+        let rotated = OrientedRect::new(
+            Point2::new(6.0, 0.0),
+            Point2::new(1.0, 1.0),
+            ::std::f64::consts::FRAC_PI_4,
+        );
+        let expected = (6.0 - 2.0f64.sqrt() - 4.0).powi(2);
+        assert_relative_eq!(distance2(&rotated, &triangle), expected, epsilon = 1e-8);
+    }
+
+    #[test]
+    fn test_edge_distances() {
+        let seed = b"\x61\xd4\x0a\x9e\x37\xc2\x85\x1b\xf0\x4e\xa9\x26\x73\xbd\x58\x0c\
+                     \xe1\x3f\x96\x42\x7a\xcd\x15\x68\xb3\x2e\x89\xf4\x5d\x07\xa0\x3b";
+        let points = random_points_with_seed::<f64>(400, seed);
+        for chunk in points.chunks(4) {
+            let e1 = SimpleEdge::new(chunk[0], chunk[1]);
+            let e2 = SimpleEdge::new(chunk[2], chunk[3]);
+            assert_relative_eq!(
+                distance2(&e1, &e2),
+                e1.distance2_to_edge(&e2),
+                epsilon = 1e-8
+            );
+            let circle = SimpleCircle::new(chunk[2], 0.5);
+            let expected = circle.distance2(&e1.nearest_point(&chunk[2]));
+            assert_relative_eq!(distance2(&e1, &circle), expected, epsilon = 1e-8);
+        }
+    }
+}
@@ -0,0 +1,532 @@
+// Copyright 2017 The Spade Developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! A uniform spatial hash grid.
+//!
+//! `HashGrid` divides space into square cells of a fixed size. Each object is
+//! stored in the cell containing its position, cells are mapped into a hash
+//! table and only occupied cells take up memory. Inserting, removing and
+//! moving an object takes constant time, which makes the grid a good
+//! broad-phase for many moving objects of similar size, e.g. agents of a
+//! simulation.
+//!
+//! Queries are fastest if their radius is in the order of the cell size. If
+//! objects are distributed very unevenly or queries differ a lot in size, an
+//! `RTree` or a `QuadTree` is usually the better choice.
+//!
+//! # Example
+//!
+//! ```
+//! use spade::hashgrid::HashGrid;
+//! use spade::Point2;
+//!
+//! let mut grid = HashGrid::new(1.0);
+//! let agent = grid.insert(Point2::new(0.5, 0.5));
+//! grid.insert(Point2::new(3.0, 0.5));
+//! assert_eq!(grid.lookup_in_circle(&Point2::new(1.0, 0.5), &1.0).len(), 1);
+//!
+//! // Move the first agent next to the second
+//! grid.update(agent, |position| *position = Point2::new(2.5, 0.5));
+//! assert_eq!(grid.lookup_in_circle(&Point2::new(2.8, 0.5), &1.0).len(), 2);
+//! assert_eq!(grid.pairs_within(&1.0), vec![(agent, grid.handles().nth(1).unwrap())]);
+//! ```
+
+use crate::boundingrect::BoundingRect;
+use crate::instrumentation::{record, Counter};
+use crate::point_traits::{PointN, PointNExtensions};
+use crate::traits::{HasPosition, SpadeFloat};
+use alloc::{vec, vec::Vec};
+use num::{zero, Float, ToPrimitive};
+use smallvec::SmallVec;
+
+type Cell = SmallVec<[i64; 3]>;
+
+const INITIAL_BUCKETS: usize = 64;
+
+/// Refers to an object stored in a `HashGrid`.
+///
+/// A handle stays valid until its object is removed. Handles of removed
+/// objects are reused by later insertions.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct HashGridHandle(usize);
+
+impl HashGridHandle {
+    /// Returns the handle's index. Indices are smaller than the number of slots of the grid.
+    pub fn index(&self) -> usize {
+        self.0
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Entry<T> {
+    object: T,
+    bucket: usize,
+}
+
+/// A uniform grid of cells, hashed into a table of buckets.
+///
+/// See the [module documentation](index.html) for more information.
+#[derive(Clone, Debug)]
+pub struct HashGrid<T>
+where
+    T: HasPosition,
+    <T::Point as PointN>::Scalar: SpadeFloat,
+{
+    cell_size: <T::Point as PointN>::Scalar,
+    entries: Vec<Option<Entry<T>>>,
+    free: Vec<usize>,
+    buckets: Vec<Vec<usize>>,
+    size: usize,
+}
+
+fn hash_cell(cell: &Cell) -> u64 {
+    cell.iter()
+        .fold(0xcbf2_9ce4_8422_2325, |hash: u64, coordinate| {
+            (hash.rotate_left(5) ^ *coordinate as u64).wrapping_mul(0x517c_c1b7_2722_0a95)
+        })
+}
+
+impl<T> HashGrid<T>
+where
+    T: HasPosition,
+    <T::Point as PointN>::Scalar: SpadeFloat,
+{
+    /// Creates an empty grid with the given cell size.
+    ///
+    /// # Panics
+    /// Panics if `cell_size` is not positive.
+    pub fn new(cell_size: <T::Point as PointN>::Scalar) -> Self {
+        assert!(cell_size > zero(), "cell_size must be positive");
+        HashGrid {
+            cell_size,
+            entries: Vec::new(),
+            free: Vec::new(),
+            buckets: vec![Vec::new(); INITIAL_BUCKETS],
+            size: 0,
+        }
+    }
+
+    /// Returns the edge length of a cell.
+    pub fn cell_size(&self) -> <T::Point as PointN>::Scalar {
+        self.cell_size
+    }
+
+    /// Returns the number of objects contained in this grid.
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Returns `true` if the grid contains no objects.
+    pub fn is_empty(&self) -> bool {
+        self.size == 0
+    }
+
+    /// Returns the object referred to by a handle.
+    pub fn get(&self, handle: HashGridHandle) -> Option<&T> {
+        self.entries
+            .get(handle.0)
+            .and_then(Option::as_ref)
+            .map(|entry| &entry.object)
+    }
+
+    /// Returns the handles of all contained objects in ascending order.
+    pub fn handles(&self) -> impl Iterator<Item = HashGridHandle> + '_ {
+        self.entries
+            .iter()
+            .enumerate()
+            .filter(|(_, entry)| entry.is_some())
+            .map(|(index, _)| HashGridHandle(index))
+    }
+
+    /// Returns an iterator over all contained objects.
+    pub fn iter(&self) -> impl Iterator<Item = &T> + '_ {
+        self.entries
+            .iter()
+            .filter_map(|entry| entry.as_ref().map(|entry| &entry.object))
+    }
+
+    /// Returns the cell containing a position.
+    ///
+    /// Cell coordinates saturate for positions far away from the origin.
+    fn cell(&self, position: &T::Point) -> Cell {
+        (0..T::Point::dimensions())
+            .map(|i| {
+                let coordinate = (*position.nth(i) / self.cell_size).floor();
+                coordinate.to_i64().unwrap_or(if coordinate > zero() {
+                    i64::MAX
+                } else {
+                    i64::MIN
+                })
+            })
+            .collect()
+    }
+
+    fn bucket(&self, cell: &Cell) -> usize {
+        hash_cell(cell) as usize & (self.buckets.len() - 1)
+    }
+
+    /// Inserts an object and returns its handle.
+    pub fn insert(&mut self, t: T) -> HashGridHandle {
+        if self.size >= self.buckets.len() * 2 {
+            self.rehash(self.buckets.len() * 2);
+        }
+        let bucket = self.bucket(&self.cell(&t.position()));
+        let entry = Some(Entry { object: t, bucket });
+        let index = match self.free.pop() {
+            Some(index) => {
+                self.entries[index] = entry;
+                index
+            }
+            None => {
+                self.entries.push(entry);
+                self.entries.len() - 1
+            }
+        };
+        self.buckets[bucket].push(index);
+        self.size += 1;
+        HashGridHandle(index)
+    }
+
+    /// Removes an object and returns it.
+    ///
+    /// Returns `None` if the handle does not refer to an object.
+    pub fn remove(&mut self, handle: HashGridHandle) -> Option<T> {
+        let entry = self.entries.get_mut(handle.0)?.take()?;
+        self.unlink(handle.0, entry.bucket);
+        self.free.push(handle.0);
+        self.size -= 1;
+        Some(entry.object)
+    }
+
+    /// Modifies an object, e.g. to move it, and moves it into its new cell.
+    ///
+    /// Returns `false` if the handle does not refer to an object.
+    pub fn update<F>(&mut self, handle: HashGridHandle, f: F) -> bool
+    where
+        F: FnOnce(&mut T),
+    {
+        let (old_bucket, position) = match self.entries.get_mut(handle.0) {
+            Some(Some(entry)) => {
+                f(&mut entry.object);
+                (entry.bucket, entry.object.position())
+            }
+            _ => return false,
+        };
+        let new_bucket = self.bucket(&self.cell(&position));
+        if new_bucket != old_bucket {
+            self.unlink(handle.0, old_bucket);
+            self.buckets[new_bucket].push(handle.0);
+            if let Some(entry) = self.entries[handle.0].as_mut() {
+                entry.bucket = new_bucket;
+            }
+        }
+        true
+    }
+
+    /// Removes all objects.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.free.clear();
+        self.buckets = vec![Vec::new(); INITIAL_BUCKETS];
+        self.size = 0;
+    }
+
+    fn unlink(&mut self, index: usize, bucket: usize) {
+        let bucket = &mut self.buckets[bucket];
+        if let Some(position) = bucket.iter().position(|other| *other == index) {
+            bucket.swap_remove(position);
+        }
+    }
+
+    fn rehash(&mut self, bucket_count: usize) {
+        self.buckets = vec![Vec::new(); bucket_count];
+        for index in 0..self.entries.len() {
+            if let Some(entry) = &self.entries[index] {
+                let bucket = self.bucket(&self.cell(&entry.object.position()));
+                self.buckets[bucket].push(index);
+                if let Some(entry) = self.entries[index].as_mut() {
+                    entry.bucket = bucket;
+                }
+            }
+        }
+    }
+
+    /// Returns the number of cells between two cells, `None` on overflow.
+    fn cell_count(&self, lower: &Cell, upper: &Cell) -> Option<usize> {
+        lower
+            .iter()
+            .zip(upper.iter())
+            .try_fold(1usize, |count, (l, u)| {
+                count.checked_mul(usize::try_from(u.checked_sub(*l)?.checked_add(1)?).ok()?)
+            })
+    }
+
+    /// Returns `true` if a query of `rect` only scans the buckets of its cells
+    /// instead of all buckets.
+    fn is_local(&self, rect: &BoundingRect<T::Point>) -> bool {
+        let lower = self.cell(&rect.lower());
+        let upper = self.cell(&rect.upper());
+        self.cell_count(&lower, &upper)
+            .is_some_and(|count| count < self.buckets.len())
+    }
+
+    /// Calls `visitor` for all objects whose cell intersects a rectangle.
+    ///
+    /// Objects of other cells that share a bucket with these cells may be
+    /// visited as well, `visitor` needs to check the object's position.
+    fn visit_candidates<'a, F>(&'a self, query_rect: &BoundingRect<T::Point>, mut visitor: F)
+    where
+        F: FnMut(usize, &'a T),
+    {
+        let lower = self.cell(&query_rect.lower());
+        let upper = self.cell(&query_rect.upper());
+        let mut buckets: Vec<usize> = match self.cell_count(&lower, &upper) {
+            Some(count) if count < self.buckets.len() => {
+                let mut buckets = Vec::with_capacity(count);
+                let mut cell = lower.clone();
+                'cells: loop {
+                    buckets.push(self.bucket(&cell));
+                    for i in 0..cell.len() {
+                        if cell[i] < upper[i] {
+                            cell[i] += 1;
+                            continue 'cells;
+                        }
+                        cell[i] = lower[i];
+                    }
+                    break;
+                }
+                buckets
+            }
+            _ => (0..self.buckets.len()).collect(),
+        };
+        buckets.sort_unstable();
+        buckets.dedup();
+        for bucket in buckets {
+            let bucket = &self.buckets[bucket];
+            record(Counter::DistanceComputations, bucket.len());
+            for index in bucket {
+                if let Some(entry) = &self.entries[*index] {
+                    visitor(*index, &entry.object);
+                }
+            }
+        }
+    }
+
+    fn circle_rect(
+        origin: &T::Point,
+        radius2: <T::Point as PointN>::Scalar,
+    ) -> BoundingRect<T::Point> {
+        let radius = radius2.sqrt();
+        let offset = T::Point::from_value(radius);
+        BoundingRect::from_corners(&origin.sub(&offset), &origin.add(&offset))
+    }
+
+    /// Returns all objects contained in a rectangle, including its boundary.
+    pub fn lookup_in_rectangle(&self, query_rect: &BoundingRect<T::Point>) -> Vec<&T> {
+        let mut result = Vec::new();
+        self.visit_candidates(query_rect, |_, object| {
+            if query_rect.contains_point(&object.position()) {
+                result.push(object);
+            }
+        });
+        result
+    }
+
+    /// Returns all objects contained in a circle.
+    ///
+    /// Note that `radius2` is the circle's squared radius, not the actual radius.
+    pub fn lookup_in_circle(
+        &self,
+        circle_origin: &T::Point,
+        radius2: &<T::Point as PointN>::Scalar,
+    ) -> Vec<&T> {
+        self.handles_in_circle(circle_origin, radius2)
+            .into_iter()
+            .filter_map(|handle| self.get(handle))
+            .collect()
+    }
+
+    /// Returns the handles of all objects contained in a circle.
+    ///
+    /// Note that `radius2` is the circle's squared radius, not the actual radius.
+    pub fn handles_in_circle(
+        &self,
+        circle_origin: &T::Point,
+        radius2: &<T::Point as PointN>::Scalar,
+    ) -> Vec<HashGridHandle> {
+        let mut result = Vec::new();
+        let rect = Self::circle_rect(circle_origin, *radius2);
+        self.visit_candidates(&rect, |index, object| {
+            if object.position().sub(circle_origin).length2() < *radius2 {
+                result.push(HashGridHandle(index));
+            }
+        });
+        result
+    }
+
+    /// Returns the nearest neighbor.
+    ///
+    /// Searches the cells around the query point with a growing radius.
+    /// Returns `None` if the grid is empty.
+    pub fn nearest_neighbor(&self, query_point: &T::Point) -> Option<&T> {
+        if self.size == 0 {
+            return None;
+        }
+        let mut radius = self.cell_size;
+        loop {
+            let offset = T::Point::from_value(radius);
+            let rect =
+                BoundingRect::from_corners(&query_point.sub(&offset), &query_point.add(&offset));
+            let mut best: Option<(&T, <T::Point as PointN>::Scalar)> = None;
+            self.visit_candidates(&rect, |_, object| {
+                let distance2 = object.position().sub(query_point).length2();
+                if best.is_none_or(|(_, best)| distance2 < best) {
+                    best = Some((object, distance2));
+                }
+            });
+            if let Some((object, distance2)) = best {
+                // All objects outside of the rectangle are farther away than `radius`
+                if distance2 <= radius * radius || !self.is_local(&rect) {
+                    return Some(object);
+                }
+            }
+            radius = radius + radius;
+        }
+    }
+
+    /// Returns all pairs of objects closer than a given distance, e.g. for collision detection.
+    ///
+    /// Each pair is returned once, the smaller handle comes first. Note that
+    /// `radius2` is the squared distance.
+    pub fn pairs_within(
+        &self,
+        radius2: &<T::Point as PointN>::Scalar,
+    ) -> Vec<(HashGridHandle, HashGridHandle)> {
+        let mut result = Vec::new();
+        for (index, entry) in self.entries.iter().enumerate() {
+            if let Some(entry) = entry {
+                let position = entry.object.position();
+                let rect = Self::circle_rect(&position, *radius2);
+                let start = result.len();
+                self.visit_candidates(&rect, |other, object| {
+                    if other > index && object.position().sub(&position).length2() < *radius2 {
+                        result.push((HashGridHandle(index), HashGridHandle(other)));
+                    }
+                });
+                result[start..].sort_unstable();
+            }
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::HashGrid;
+    use crate::boundingrect::BoundingRect;
+    use crate::rtree::RTree;
+    use crate::testutils::*;
+    use crate::{Point2, Point3};
+    use cgmath::Point2 as CgPoint2;
+
+    const SEED: &[u8; 32] = b"\x3c\x91\x07\xd5\x6a\xe2\x48\x1f\xb0\x7d\x25\xc9\x83\x5e\x14\xfa\
+        \x62\x0b\xad\x37\xf8\x49\x9e\x15\xc4\x70\x2b\xe6\x8d\x53\x01\xbf";
+
+    fn sorted(mut points: Vec<&CgPoint2<f64>>) -> Vec<&CgPoint2<f64>> {
+        points.sort_by(|l, r| (l.x, l.y).partial_cmp(&(r.x, r.y)).unwrap());
+        points
+    }
+
+    #[test]
+    fn test_hash_grid_queries_match_rtree() {
+        let points = random_points_with_seed::<f64>(1000, SEED);
+        let mut grid = HashGrid::new(0.1);
+        for point in &points {
+            grid.insert(*point);
+        }
+        let rtree = RTree::bulk_load(points.clone());
+        assert_eq!(grid.size(), 1000);
+        for point in points.iter().take(100) {
+            let query = CgPoint2::new(point.y * 3.0, -point.x);
+            assert_eq!(
+                grid.nearest_neighbor(&query),
+                rtree.nearest_neighbor(&query)
+            );
+            assert_eq!(
+                sorted(grid.lookup_in_circle(&query, &0.02)),
+                sorted(rtree.lookup_in_circle(&query, &0.02))
+            );
+            let rect = BoundingRect::from_corners(&query, &CgPoint2::new(0.0, 0.0));
+            assert_eq!(
+                sorted(grid.lookup_in_rectangle(&rect)),
+                sorted(rtree.lookup_in_rectangle(&rect))
+            );
+        }
+    }
+
+    #[test]
+    fn test_hash_grid_update_and_remove() {
+        let mut grid = HashGrid::new(2.0);
+        let handles: Vec<_> = (0..100)
+            .map(|i| grid.insert(Point3::new(f64::from(i), 0.0, 0.0)))
+            .collect();
+        for handle in &handles {
+            assert!(grid.update(*handle, |p| p.y = 1000.0 - p.x));
+        }
+        assert!(grid
+            .lookup_in_circle(&Point3::new(0.0, 0.0, 0.0), &4.0)
+            .is_empty());
+        assert_eq!(
+            grid.nearest_neighbor(&Point3::new(0.0, 0.0, 0.0)),
+            Some(&Point3::new(99.0, 901.0, 0.0))
+        );
+        assert_eq!(
+            grid.remove(handles[99]),
+            Some(Point3::new(99.0, 901.0, 0.0))
+        );
+        assert_eq!(grid.remove(handles[99]), None);
+        assert!(!grid.update(handles[99], |_| ()));
+        assert_eq!(grid.size(), 99);
+        assert_eq!(
+            grid.nearest_neighbor(&Point3::new(0.0, 0.0, 0.0)),
+            Some(&Point3::new(98.0, 902.0, 0.0))
+        );
+        assert_eq!(grid.insert(Point3::new(0.0, 0.0, 0.0)), handles[99]);
+        assert_eq!(grid.iter().count(), 100);
+    }
+
+    #[test]
+    fn test_pairs_within() {
+        let points = random_points_with_seed::<f64>(300, SEED);
+        let mut grid = HashGrid::new(0.05);
+        for point in &points {
+            grid.insert(*point);
+        }
+        let mut expected = Vec::new();
+        for (i, p) in points.iter().enumerate() {
+            for (j, q) in points.iter().enumerate().skip(i + 1) {
+                if (p.x - q.x).powi(2) + (p.y - q.y).powi(2) < 0.0025 {
+                    expected.push((i, j));
+                }
+            }
+        }
+        let pairs: Vec<_> = grid
+            .pairs_within(&0.0025)
+            .into_iter()
+            .map(|(a, b)| (a.index(), b.index()))
+            .collect();
+        assert!(!expected.is_empty());
+        assert_eq!(pairs, expected);
+
+        let mut grid = HashGrid::new(1.0);
+        grid.insert(Point2::new(0.0, 0.0));
+        assert!(grid.pairs_within(&100.0).is_empty());
+        grid.insert(Point2::new(1e30, -1e30));
+        assert_eq!(grid.pairs_within(&f64::INFINITY).len(), 1);
+    }
+}
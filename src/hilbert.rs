@@ -0,0 +1,479 @@
+// Copyright 2017 The Spade Developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Hilbert curve indices.
+//!
+//! A Hilbert curve visits all cells of a `2^order × 2^order` grid (or of a
+//! cube in 3D) such that consecutive cells are neighbors. Sorting objects by
+//! the Hilbert index of their cell keeps nearby objects close together, which
+//! makes the index a good key for spatial sorting, partitioning or sharding.
+//!
+//! `hilbert_index_2d` and `hilbert_index_3d` map integer grid coordinates to
+//! indices, `hilbert_coordinates_2d` and `hilbert_coordinates_3d` are their
+//! inverses. `HilbertCurve` maps floating point positions within a bounding
+//! rectangle to indices. The indices only depend on the inputs, not on the
+//! platform or the crate version, so they can be used as persistent keys.
+//!
+//! The order in which `compact` renumbers triangulation vertices is unrelated
+//! to these indices. It stems from an adaptive sort that splits at medians
+//! instead of fixed grid cells, so it generally differs from sorting by
+//! `HilbertCurve::index`.
+//!
+//! # Example
+//!
+//! ```
+//! use spade::hilbert::{hilbert_index_2d, HilbertCurve};
+//! use spade::{BoundingRect, Point2};
+//!
+//! // The curve of order 1 visits the four cells of a 2×2 grid
+//! assert_eq!(hilbert_index_2d(0, 0, 1), 0);
+//! assert_eq!(hilbert_index_2d(0, 1, 1), 1);
+//! assert_eq!(hilbert_index_2d(1, 1, 1), 2);
+//! assert_eq!(hilbert_index_2d(1, 0, 1), 3);
+//!
+//! let bounds = BoundingRect::from_corners(&Point2::new(0.0, 0.0), &Point2::new(100.0, 100.0));
+//! let curve = HilbertCurve::new(bounds, 16);
+//! let mut points = vec![
+//!     Point2::new(90.0, 10.0),
+//!     Point2::new(10.0, 10.0),
+//!     Point2::new(90.0, 90.0),
+//!     Point2::new(10.0, 90.0),
+//! ];
+//! points.sort_by_key(|p| curve.index(p));
+//! assert_eq!(points[0], Point2::new(10.0, 10.0));
+//! assert_eq!(points[3], Point2::new(90.0, 10.0));
+//! ```
+
+use crate::boundingrect::BoundingRect;
+use crate::point_traits::{PointN, PointNExtensions, TwoDimensional};
+use crate::traits::SpadeFloat;
+use core::cmp::Ordering;
+use num::{cast, zero, Float, ToPrimitive};
+
+#[cfg(feature = "serde_serialize")]
+use serde::{Deserialize, Serialize};
+
+/// The largest supported order of two dimensional curves.
+pub const MAX_ORDER_2D: u32 = 32;
+
+/// The largest supported order of three dimensional curves.
+pub const MAX_ORDER_3D: u32 = 21;
+
+/// Returns the Hilbert index of a cell of a `2^order × 2^order` grid.
+///
+/// The curve starts at `(0, 0)` and ends at `(2^order - 1, 0)`.
+///
+/// Panics if `order` is zero or larger than `MAX_ORDER_2D` or if a coordinate
+/// is not smaller than `2^order`.
+pub fn hilbert_index_2d(x: u32, y: u32, order: u32) -> u64 {
+    check_order(order, MAX_ORDER_2D);
+    let mut coordinates = [x, y];
+    check_coordinates(&coordinates, order);
+    axes_to_transpose(&mut coordinates, order);
+    interleave(&coordinates, order)
+}
+
+/// Returns the grid cell of a two dimensional Hilbert index.
+///
+/// This is the inverse of `hilbert_index_2d`.
+///
+/// Panics if `order` is zero or larger than `MAX_ORDER_2D` or if `index` is
+/// not smaller than `4^order`.
+pub fn hilbert_coordinates_2d(index: u64, order: u32) -> (u32, u32) {
+    check_order(order, MAX_ORDER_2D);
+    check_index(index, order, 2);
+    let mut coordinates = [0; 2];
+    deinterleave(index, &mut coordinates, order);
+    transpose_to_axes(&mut coordinates, order);
+    (coordinates[0], coordinates[1])
+}
+
+/// Returns the Hilbert index of a cell of a `2^order × 2^order × 2^order` grid.
+///
+/// Panics if `order` is zero or larger than `MAX_ORDER_3D` or if a coordinate
+/// is not smaller than `2^order`.
+pub fn hilbert_index_3d(x: u32, y: u32, z: u32, order: u32) -> u64 {
+    check_order(order, MAX_ORDER_3D);
+    let mut coordinates = [x, y, z];
+    check_coordinates(&coordinates, order);
+    axes_to_transpose(&mut coordinates, order);
+    interleave(&coordinates, order)
+}
+
+/// Returns the grid cell of a three dimensional Hilbert index.
+///
+/// This is the inverse of `hilbert_index_3d`.
+///
+/// Panics if `order` is zero or larger than `MAX_ORDER_3D` or if `index` is
+/// not smaller than `8^order`.
+pub fn hilbert_coordinates_3d(index: u64, order: u32) -> (u32, u32, u32) {
+    check_order(order, MAX_ORDER_3D);
+    check_index(index, order, 3);
+    let mut coordinates = [0; 3];
+    deinterleave(index, &mut coordinates, order);
+    transpose_to_axes(&mut coordinates, order);
+    (coordinates[0], coordinates[1], coordinates[2])
+}
+
+fn check_order(order: u32, max_order: u32) {
+    assert!(
+        order > 0 && order <= max_order,
+        "Hilbert curve order must be between 1 and {}",
+        max_order
+    );
+}
+
+fn check_coordinates(coordinates: &[u32], order: u32) {
+    assert!(
+        order == 32 || coordinates.iter().all(|c| *c >> order == 0),
+        "Grid coordinates must be smaller than 2^order"
+    );
+}
+
+fn check_index(index: u64, order: u32, dimensions: u32) {
+    let bits = order * dimensions;
+    assert!(
+        bits == 64 || index >> bits == 0,
+        "Hilbert index must be smaller than 2^(order * dimensions)"
+    );
+}
+
+// The transformations between grid coordinates and the "transposed" index
+// follow J. Skilling, "Programming the Hilbert curve", AIP Conf. Proc. 707 (2004).
+
+fn axes_to_transpose(x: &mut [u32], order: u32) {
+    let n = x.len();
+    let m = 1u32 << (order - 1);
+    // Inverse undo
+    let mut q = m;
+    while q > 1 {
+        let p = q - 1;
+        for i in 0..n {
+            if x[i] & q != 0 {
+                x[0] ^= p;
+            } else {
+                let t = (x[0] ^ x[i]) & p;
+                x[0] ^= t;
+                x[i] ^= t;
+            }
+        }
+        q >>= 1;
+    }
+    // Gray encode
+    for i in 1..n {
+        x[i] ^= x[i - 1];
+    }
+    let mut t = 0;
+    let mut q = m;
+    while q > 1 {
+        if x[n - 1] & q != 0 {
+            t ^= q - 1;
+        }
+        q >>= 1;
+    }
+    for value in x.iter_mut() {
+        *value ^= t;
+    }
+}
+
+fn transpose_to_axes(x: &mut [u32], order: u32) {
+    let n = x.len();
+    // Gray decode
+    let t = x[n - 1] >> 1;
+    for i in (1..n).rev() {
+        x[i] ^= x[i - 1];
+    }
+    x[0] ^= t;
+    // Undo excess work
+    let mut q = 2u64;
+    while q != 1u64 << order {
+        let p = (q - 1) as u32;
+        for i in (0..n).rev() {
+            if u64::from(x[i]) & q != 0 {
+                x[0] ^= p;
+            } else {
+                let t = (x[0] ^ x[i]) & p;
+                x[0] ^= t;
+                x[i] ^= t;
+            }
+        }
+        q <<= 1;
+    }
+}
+
+/// Interleaves the bits of a transposed index, most significant bits first.
+fn interleave(x: &[u32], order: u32) -> u64 {
+    let mut index = 0u64;
+    for bit in (0..order).rev() {
+        for value in x {
+            index = (index << 1) | u64::from((value >> bit) & 1);
+        }
+    }
+    index
+}
+
+fn deinterleave(index: u64, x: &mut [u32], order: u32) {
+    let n = x.len() as u32;
+    for bit in 0..order {
+        for (i, value) in x.iter_mut().enumerate() {
+            let position = bit * n + (n - 1 - i as u32);
+            *value |= (((index >> position) & 1) as u32) << bit;
+        }
+    }
+}
+
+/// Maps positions within a bounding rectangle to Hilbert indices.
+///
+/// The rectangle is divided into a grid of `2^order` cells per axis, a
+/// position's index is the Hilbert index of the cell containing it. Positions
+/// outside of the rectangle are clamped to its boundary. Supports two and
+/// three dimensional points.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde_serialize", derive(Serialize, Deserialize))]
+pub struct HilbertCurve<P: PointN> {
+    bounds: BoundingRect<P>,
+    order: u32,
+}
+
+impl<P> HilbertCurve<P>
+where
+    P: PointN,
+    P::Scalar: SpadeFloat,
+{
+    /// Creates a curve of a given order through a bounding rectangle.
+    ///
+    /// Panics if the points are neither two nor three dimensional, or if
+    /// `order` is zero or larger than `MAX_ORDER_2D` or `MAX_ORDER_3D`,
+    /// respectively.
+    pub fn new(bounds: BoundingRect<P>, order: u32) -> Self {
+        match P::dimensions() {
+            2 => check_order(order, MAX_ORDER_2D),
+            3 => check_order(order, MAX_ORDER_3D),
+            _ => panic!("Hilbert curves support only two and three dimensional points"),
+        }
+        HilbertCurve { bounds, order }
+    }
+
+    /// Returns the bounding rectangle covered by this curve.
+    pub fn bounds(&self) -> &BoundingRect<P> {
+        &self.bounds
+    }
+
+    /// Returns this curve's order.
+    pub fn order(&self) -> u32 {
+        self.order
+    }
+
+    /// Returns the grid cell containing a position.
+    fn cell(&self, position: &P, axis: usize) -> u32 {
+        let lower = *self.bounds.lower().nth(axis);
+        let extent = *self.bounds.upper().nth(axis) - lower;
+        let max_cell = (1u64 << self.order) - 1;
+        if extent <= zero() {
+            return 0;
+        }
+        let cells: P::Scalar = cast(1u64 << self.order).unwrap();
+        let cell = ((*position.nth(axis) - lower) / extent * cells).floor();
+        if cell.is_nan() || cell <= zero() {
+            0
+        } else {
+            cell.to_u64().unwrap_or(max_cell).min(max_cell) as u32
+        }
+    }
+
+    /// Returns the Hilbert index of a position.
+    pub fn index(&self, position: &P) -> u64 {
+        if P::dimensions() == 2 {
+            hilbert_index_2d(self.cell(position, 0), self.cell(position, 1), self.order)
+        } else {
+            hilbert_index_3d(
+                self.cell(position, 0),
+                self.cell(position, 1),
+                self.cell(position, 2),
+                self.order,
+            )
+        }
+    }
+
+    /// Returns the center of the cell with a given Hilbert index.
+    ///
+    /// Panics if `index` is too large for this curve's order.
+    pub fn cell_center(&self, index: u64) -> P {
+        let mut cells = [0u32; 3];
+        if P::dimensions() == 2 {
+            let (x, y) = hilbert_coordinates_2d(index, self.order);
+            cells[0] = x;
+            cells[1] = y;
+        } else {
+            let (x, y, z) = hilbert_coordinates_3d(index, self.order);
+            cells = [x, y, z];
+        }
+        let lower = self.bounds.lower();
+        let upper = self.bounds.upper();
+        let count: P::Scalar = cast(1u64 << self.order).unwrap();
+        let half: P::Scalar = cast(0.5).unwrap();
+        let mut result = P::new();
+        for (axis, cell) in cells.iter().enumerate().take(P::dimensions()) {
+            let cell: P::Scalar = cast(*cell).unwrap();
+            let lower = *lower.nth(axis);
+            *result.nth_mut(axis) = lower + (*upper.nth(axis) - lower) * (cell + half) / count;
+        }
+        result
+    }
+}
+
+/// Sorts items along a Hilbert curve through their positions.
+///
+/// The items are recursively split at the median of alternating axes, the
+/// resulting quadrants are visited in the order of the Hilbert curve. This only
+/// requires comparisons and thus works for all scalar types.
+///
+/// The quadrants adapt to the items instead of forming a regular grid. The
+/// resulting order is therefore unrelated to the indices of `HilbertCurve`
+/// and must not be mixed with them.
+pub(crate) fn hilbert_sort<T, P, F>(items: &mut [T], position: F)
+where
+    P: TwoDimensional,
+    F: Fn(&T) -> P,
+{
+    recursive_sort(items, &position, 0, false, false);
+}
+
+fn recursive_sort<T, P, F>(items: &mut [T], position: &F, x: usize, up_x: bool, up_y: bool)
+where
+    P: TwoDimensional,
+    F: Fn(&T) -> P,
+{
+    if items.len() <= 1 {
+        return;
+    }
+    let y = 1 - x;
+    let m2 = split(items, position, x, up_x);
+    let (lower, upper) = items.split_at_mut(m2);
+    let m1 = split(lower, position, y, up_y);
+    let m3 = split(upper, position, y, !up_y);
+    let (q0, q1) = lower.split_at_mut(m1);
+    let (q2, q3) = upper.split_at_mut(m3);
+    recursive_sort(q0, position, y, up_y, up_x);
+    recursive_sort(q1, position, x, up_x, up_y);
+    recursive_sort(q2, position, x, up_x, up_y);
+    recursive_sort(q3, position, y, !up_y, !up_x);
+}
+
+/// Partitions the items at their median along an axis and returns the median's index.
+fn split<T, P, F>(items: &mut [T], position: &F, axis: usize, up: bool) -> usize
+where
+    P: TwoDimensional,
+    F: Fn(&T) -> P,
+{
+    let middle = items.len() / 2;
+    if middle > 0 {
+        ::pdqselect::select_by(items, middle, |l, r| {
+            let order = position(l)
+                .nth(axis)
+                .partial_cmp(position(r).nth(axis))
+                .unwrap_or(Ordering::Equal);
+            if up {
+                order.reverse()
+            } else {
+                order
+            }
+        });
+    }
+    middle
+}
+
+#[cfg(test)]
+mod test {
+    use super::{
+        hilbert_coordinates_2d, hilbert_coordinates_3d, hilbert_index_2d, hilbert_index_3d,
+        hilbert_sort, HilbertCurve,
+    };
+    use crate::{BoundingRect, Point2};
+    use cgmath::Point3;
+
+    #[test]
+    fn test_hilbert_sort_grid() {
+        let mut points = Vec::new();
+        for x in 0..8i32 {
+            for y in 0..8 {
+                points.push(Point2::new(x, y));
+            }
+        }
+        points.reverse();
+        hilbert_sort(&mut points, |p| *p);
+        // Consecutive points of a Hilbert curve through a grid are neighbors
+        for pair in points.windows(2) {
+            let step = (pair[0].x - pair[1].x).abs() + (pair[0].y - pair[1].y).abs();
+            assert_eq!(step, 1);
+        }
+    }
+
+    #[test]
+    fn test_hilbert_index_2d() {
+        let order = 4;
+        let mut previous = hilbert_coordinates_2d(0, order);
+        assert_eq!(previous, (0, 0));
+        for index in 1..256 {
+            let (x, y) = hilbert_coordinates_2d(index, order);
+            assert_eq!(hilbert_index_2d(x, y, order), index);
+            assert_eq!(x.abs_diff(previous.0) + y.abs_diff(previous.1), 1);
+            previous = (x, y);
+        }
+        assert_eq!(previous, (15, 0));
+        let max = u32::MAX;
+        assert_eq!(
+            hilbert_coordinates_2d(hilbert_index_2d(max, 7, 32), 32),
+            (max, 7)
+        );
+    }
+
+    #[test]
+    fn test_hilbert_index_3d() {
+        let order = 3;
+        let mut previous = hilbert_coordinates_3d(0, order);
+        assert_eq!(previous, (0, 0, 0));
+        for index in 1..512 {
+            let (x, y, z) = hilbert_coordinates_3d(index, order);
+            assert_eq!(hilbert_index_3d(x, y, z, order), index);
+            let step = x.abs_diff(previous.0) + y.abs_diff(previous.1) + z.abs_diff(previous.2);
+            assert_eq!(step, 1);
+            previous = (x, y, z);
+        }
+        let max = (1 << 21) - 1;
+        let index = hilbert_index_3d(max, 3, max, 21);
+        assert_eq!(hilbert_coordinates_3d(index, 21), (max, 3, max));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_coordinates_out_of_range() {
+        hilbert_index_2d(16, 0, 4);
+    }
+
+    #[test]
+    fn test_hilbert_curve() {
+        let bounds = BoundingRect::from_corners(&Point2::new(-1.0, 2.0), &Point2::new(3.0, 4.0));
+        let curve = HilbertCurve::new(bounds, 2);
+        for index in 0..16 {
+            let center = curve.cell_center(index);
+            assert_eq!(curve.index(&center), index);
+        }
+        assert_eq!(curve.index(&Point2::new(-1.0, 2.0)), 0);
+        // Points outside of the bounds are clamped
+        assert_eq!(curve.index(&Point2::new(-5.0, 0.0)), 0);
+        assert_eq!(curve.index(&Point2::new(10.0, 0.0)), 15);
+        assert_eq!(curve.index(&Point2::new(f64::NAN, 0.0)), 0);
+
+        let bounds =
+            BoundingRect::from_corners(&Point3::new(0.0f32, 0.0, 0.0), &Point3::new(1.0, 1.0, 1.0));
+        let curve = HilbertCurve::new(bounds, 10);
+        let center = curve.cell_center(12345);
+        assert_eq!(curve.index(&center), 12345);
+    }
+}
@@ -0,0 +1,513 @@
+// Copyright 2017 The Spade Developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Convex hull computation for point sets.
+//!
+//! These functions work on plain point slices and do not require
+//! building a triangulation.
+
+use crate::kernels::DelaunayKernel;
+use crate::point_traits::{PointNExtensions, TwoDimensional};
+use crate::primitives::SimpleEdge;
+use crate::traits::SpadeFloat;
+use alloc::vec::Vec;
+use num::{zero, Float, NumCast};
+
+/// Calculates the convex hull of a set of points.
+///
+/// Uses Andrew's monotone chain algorithm with `O(n log n)` runtime. The
+/// kernel `K` determines how orientation tests are performed, see
+/// `spade::kernels`.
+///
+/// The hull's vertices are returned in counterclockwise order, starting with
+/// the lexicographically smallest point. Duplicate and collinear points are
+/// not contained in the result. If all points are collinear, only the two
+/// outermost points are returned.
+///
+/// # Example
+///
+/// ```
+/// # extern crate nalgebra;
+/// # extern crate spade;
+/// use nalgebra::Point2;
+/// use spade::hull::convex_hull;
+/// use spade::kernels::FloatKernel;
+///
+/// # fn main() {
+/// let points = vec![
+///     Point2::new(0.0, 0.0),
+///     Point2::new(1.0, 0.0),
+///     Point2::new(0.5, 0.5),
+///     Point2::new(1.0, 1.0),
+///     Point2::new(0.0, 1.0),
+/// ];
+/// let hull = convex_hull::<FloatKernel, _>(&points);
+/// assert_eq!(hull.len(), 4);
+/// assert_eq!(hull[0], Point2::new(0.0, 0.0));
+/// assert_eq!(hull[1], Point2::new(1.0, 0.0));
+/// # }
+/// ```
+pub fn convex_hull<K, V>(points: &[V]) -> Vec<V>
+where
+    K: DelaunayKernel<V::Scalar>,
+    V: TwoDimensional,
+{
+    let mut sorted = points.to_vec();
+    sorted.sort_by(|p1, p2| p1.lex_compare(p2));
+    sorted.dedup();
+    if sorted.len() < 3 {
+        return sorted;
+    }
+    let mut lower = half_hull::<K, _, _>(sorted.iter());
+    let mut upper = half_hull::<K, _, _>(sorted.iter().rev());
+    // The last point of each chain is the first point of the other chain
+    lower.pop();
+    upper.pop();
+    lower.extend(upper);
+    lower
+}
+
+fn half_hull<'a, K, V, I>(points: I) -> Vec<V>
+where
+    K: DelaunayKernel<V::Scalar>,
+    V: TwoDimensional + 'a,
+    I: Iterator<Item = &'a V>,
+{
+    let mut result: Vec<V> = Vec::new();
+    for point in points {
+        while result.len() >= 2 {
+            let edge = SimpleEdge::new(
+                result[result.len() - 2].clone(),
+                result[result.len() - 1].clone(),
+            );
+            if K::side_query(&edge, point).is_on_left_side() {
+                break;
+            }
+            result.pop();
+        }
+        result.push(point.clone());
+    }
+    result
+}
+
+/// Returns the area of a convex polygon, e.g. a hull calculated by `convex_hull`.
+///
+/// The area is positive if the vertices are ordered counterclockwise.
+pub fn hull_area<V>(hull: &[V]) -> V::Scalar
+where
+    V: TwoDimensional,
+    V::Scalar: SpadeFloat,
+{
+    let mut double_area: V::Scalar = zero();
+    for (index, p0) in hull.iter().enumerate() {
+        let p1 = &hull[(index + 1) % hull.len()];
+        double_area += *p0.nth(0) * *p1.nth(1) - *p1.nth(0) * *p0.nth(1);
+    }
+    let two: V::Scalar = NumCast::from(2.0).unwrap();
+    double_area / two
+}
+
+/// Returns the perimeter of a convex polygon, e.g. a hull calculated by `convex_hull`.
+pub fn hull_perimeter<V>(hull: &[V]) -> V::Scalar
+where
+    V: TwoDimensional,
+    V::Scalar: SpadeFloat,
+{
+    if hull.len() < 2 {
+        return zero();
+    }
+    let mut result: V::Scalar = zero();
+    for (index, p0) in hull.iter().enumerate() {
+        let p1 = &hull[(index + 1) % hull.len()];
+        result += p0.sub(p1).length2().sqrt();
+    }
+    result
+}
+
+/// Returns the two points of a convex hull with the largest distance.
+///
+/// `hull` must be a convex polygon in counterclockwise order without collinear
+/// vertices, as returned by `convex_hull`. Uses the rotating calipers method
+/// with linear runtime. Returns `None` if the hull is empty.
+pub fn hull_diameter<V>(hull: &[V]) -> Option<(V, V)>
+where
+    V: TwoDimensional,
+    V::Scalar: SpadeFloat,
+{
+    match hull.len() {
+        0 => return None,
+        1 => return Some((hull[0].clone(), hull[0].clone())),
+        2 => return Some((hull[0].clone(), hull[1].clone())),
+        _ => {}
+    }
+    let mut result = (hull[0].clone(), hull[1].clone());
+    let mut max_distance = hull[0].sub(&hull[1]).length2();
+    for calipers in Calipers::new(hull) {
+        // Candidates are all antipodal pairs of the current edge
+        let next_far = (calipers.far + 1) % hull.len();
+        for from in &[calipers.edge, (calipers.edge + 1) % hull.len()] {
+            for to in &[calipers.far, next_far] {
+                let distance = hull[*from].sub(&hull[*to]).length2();
+                if distance > max_distance {
+                    max_distance = distance;
+                    result = (hull[*from].clone(), hull[*to].clone());
+                }
+            }
+        }
+    }
+    Some(result)
+}
+
+/// Returns the minimal width of a convex hull.
+///
+/// The width is the smallest distance between two parallel lines enclosing the
+/// hull. `hull` must be a convex polygon in counterclockwise order without
+/// collinear vertices, as returned by `convex_hull`.
+/// Returns `None` if the hull is empty.
+pub fn hull_width<V>(hull: &[V]) -> Option<V::Scalar>
+where
+    V: TwoDimensional,
+    V::Scalar: SpadeFloat,
+{
+    if hull.is_empty() {
+        return None;
+    }
+    Calipers::new(hull)
+        .map(|calipers| calipers.height)
+        .fold(None, |min, height| match min {
+            Some(min) if min <= height => Some(min),
+            _ => Some(height),
+        })
+        .or_else(|| Some(zero()))
+}
+
+/// Returns the oriented rectangle with the smallest area enclosing a convex hull.
+///
+/// The rectangle's corners are returned in counterclockwise order. `hull` must
+/// be a convex polygon in counterclockwise order without collinear vertices, as
+/// returned by `convex_hull`. Returns `None` if the hull is empty.
+pub fn minimum_area_bounding_box<V>(hull: &[V]) -> Option<[V; 4]>
+where
+    V: TwoDimensional,
+    V::Scalar: SpadeFloat,
+{
+    minimum_bounding_box(hull, |width, height| width * height)
+}
+
+/// Returns the oriented rectangle with the smallest perimeter enclosing a convex hull.
+///
+/// Refer to `minimum_area_bounding_box` for more information.
+pub fn minimum_perimeter_bounding_box<V>(hull: &[V]) -> Option<[V; 4]>
+where
+    V: TwoDimensional,
+    V::Scalar: SpadeFloat,
+{
+    minimum_bounding_box(hull, |width, height| width + height)
+}
+
+fn minimum_bounding_box<V, F>(hull: &[V], cost: F) -> Option<[V; 4]>
+where
+    V: TwoDimensional,
+    V::Scalar: SpadeFloat,
+    F: Fn(V::Scalar, V::Scalar) -> V::Scalar,
+{
+    match hull.len() {
+        0 => return None,
+        1 => {
+            return Some([
+                hull[0].clone(),
+                hull[0].clone(),
+                hull[0].clone(),
+                hull[0].clone(),
+            ])
+        }
+        2 => {
+            return Some([
+                hull[0].clone(),
+                hull[1].clone(),
+                hull[1].clone(),
+                hull[0].clone(),
+            ])
+        }
+        _ => {}
+    }
+    let mut result = None;
+    let mut min_cost = zero();
+    for calipers in Calipers::new(hull) {
+        let current = cost(
+            calipers.max_projection - calipers.min_projection,
+            calipers.height,
+        );
+        if result.is_none() || current < min_cost {
+            min_cost = current;
+            result = Some(calipers);
+        }
+    }
+    result.map(|calipers| calipers.corners(hull))
+}
+
+/// The state of the rotating calipers when aligned with a hull edge.
+struct CalipersState<S> {
+    /// The index of the edge's start vertex.
+    edge: usize,
+    /// The index of the vertex farthest away from the edge.
+    far: usize,
+    /// The distance of the farthest vertex from the edge's line.
+    height: S,
+    /// Minimal and maximal projection of the hull onto the edge's direction,
+    /// relative to the edge's start vertex.
+    min_projection: S,
+    max_projection: S,
+}
+
+impl<S: SpadeFloat> CalipersState<S> {
+    fn corners<V: TwoDimensional<Scalar = S>>(&self, hull: &[V]) -> [V; 4] {
+        let origin = &hull[self.edge];
+        let (dir, normal) = edge_frame(hull, self.edge);
+        let lower = dir.mul(self.min_projection);
+        let upper = dir.mul(self.max_projection);
+        let offset = normal.mul(self.height);
+        [
+            origin.add(&lower),
+            origin.add(&upper),
+            origin.add(&upper).add(&offset),
+            origin.add(&lower).add(&offset),
+        ]
+    }
+}
+
+/// Returns the unit direction of a hull edge and its left facing normal.
+fn edge_frame<V>(hull: &[V], edge: usize) -> (V, V)
+where
+    V: TwoDimensional,
+    V::Scalar: SpadeFloat,
+{
+    let dir = hull[(edge + 1) % hull.len()].sub(&hull[edge]);
+    let dir = dir.div(dir.length2().sqrt());
+    let mut normal = V::new();
+    *normal.nth_mut(0) = -*dir.nth(1);
+    *normal.nth_mut(1) = *dir.nth(0);
+    (dir, normal)
+}
+
+/// Rotates calipers around a convex hull, yielding one state per hull edge.
+struct Calipers<'a, V: TwoDimensional> {
+    hull: &'a [V],
+    edge: usize,
+    far: usize,
+    max_index: usize,
+    min_index: usize,
+}
+
+impl<'a, V> Calipers<'a, V>
+where
+    V: TwoDimensional,
+    V::Scalar: SpadeFloat,
+{
+    fn new(hull: &'a [V]) -> Self {
+        Calipers {
+            hull,
+            edge: 0,
+            far: 1,
+            max_index: 1,
+            min_index: 1,
+        }
+    }
+
+    /// Advances `index` while `value` increases, returns the final index and value.
+    fn advance<F>(&self, mut index: usize, value: F) -> (usize, V::Scalar)
+    where
+        F: Fn(&V) -> V::Scalar,
+    {
+        let n = self.hull.len();
+        let mut current = value(&self.hull[index]);
+        for _ in 0..n {
+            let next = value(&self.hull[(index + 1) % n]);
+            if next <= current {
+                break;
+            }
+            current = next;
+            index = (index + 1) % n;
+        }
+        (index, current)
+    }
+}
+
+impl<'a, V> Iterator for Calipers<'a, V>
+where
+    V: TwoDimensional,
+    V::Scalar: SpadeFloat,
+{
+    type Item = CalipersState<V::Scalar>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.edge >= self.hull.len() || self.hull.len() < 3 {
+            return None;
+        }
+        let origin = self.hull[self.edge].clone();
+        let (dir, normal) = edge_frame(self.hull, self.edge);
+        // The vertices of maximal projection, maximal height and minimal projection
+        // follow the edge in this order
+        let (max_index, max_projection) =
+            self.advance(self.max_index, |p| p.sub(&origin).dot(&dir));
+        let far_start = if self.edge == 0 { max_index } else { self.far };
+        let (far, height) = self.advance(far_start, |p| p.sub(&origin).dot(&normal));
+        let min_start = if self.edge == 0 { far } else { self.min_index };
+        let (min_index, min_projection) = self.advance(min_start, |p| -p.sub(&origin).dot(&dir));
+        let state = CalipersState {
+            edge: self.edge,
+            far,
+            height,
+            min_projection: -min_projection,
+            max_projection,
+        };
+        self.max_index = max_index;
+        self.far = far;
+        self.min_index = min_index;
+        self.edge += 1;
+        Some(state)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{
+        convex_hull, hull_area, hull_diameter, hull_perimeter, hull_width,
+        minimum_area_bounding_box, minimum_perimeter_bounding_box,
+    };
+    use crate::delaunay::FloatDelaunayTriangulation;
+    use crate::kernels::{FloatKernel, TrivialKernel};
+    use crate::testutils::*;
+    use crate::traits::HasPosition;
+    use cgmath::{InnerSpace, Point2, Vector2};
+
+    #[test]
+    fn test_convex_hull_degenerate() {
+        let empty: Vec<Point2<f64>> = Vec::new();
+        assert!(convex_hull::<FloatKernel, _>(&empty).is_empty());
+        let single = vec![Point2::new(1.0, 2.0); 3];
+        assert_eq!(convex_hull::<FloatKernel, _>(&single), vec![single[0]]);
+        let collinear = vec![
+            Point2::new(1.0, 1.0),
+            Point2::new(0.0, 0.0),
+            Point2::new(2.0, 2.0),
+            Point2::new(0.5, 0.5),
+        ];
+        assert_eq!(
+            convex_hull::<FloatKernel, _>(&collinear),
+            vec![Point2::new(0.0, 0.0), Point2::new(2.0, 2.0)]
+        );
+        assert_eq!(hull_perimeter(&[Point2::new(1.0, 2.0)]), 0.0);
+    }
+
+    #[test]
+    fn test_convex_hull_square() {
+        let mut points = vec![[0i64, 0], [4, 0], [4, 4], [0, 4], [2, 0], [2, 2], [1, 3]];
+        points.extend(points.clone());
+        let hull = convex_hull::<TrivialKernel, _>(&points);
+        assert_eq!(hull, vec![[0, 0], [4, 0], [4, 4], [0, 4]]);
+
+        let hull: Vec<_> = hull
+            .iter()
+            .map(|p| Point2::new(p[0] as f64, p[1] as f64))
+            .collect();
+        assert_relative_eq!(hull_area(&hull), 16.0);
+        assert_relative_eq!(hull_perimeter(&hull), 16.0);
+        let clockwise: Vec<_> = hull.iter().rev().cloned().collect();
+        assert_relative_eq!(hull_area(&clockwise), -16.0);
+    }
+
+    #[test]
+    fn test_convex_hull_matches_triangulation() {
+        let seed = b"\x6a\x13\xe8\x27\xc5\x90\x3d\xbf\x02\x74\xa9\x58\x1c\xe3\x46\x8b\
+                     \xd7\x3a\x61\x0f\x95\xcc\x28\x7e\xb4\x11\x5b\xf0\x83\x4d\x9e\x36";
+        let points = random_points_with_seed::<f64>(500, seed);
+        let hull = convex_hull::<FloatKernel, _>(&points);
+        let mut d = FloatDelaunayTriangulation::with_walk_locate();
+        for p in &points {
+            d.insert(*p);
+        }
+        let hull_edges = d.infinite_face().adjacent_edges().count();
+        assert_eq!(hull.len(), hull_edges);
+        let area: f64 = d
+            .triangles()
+            .map(|t| {
+                let [v0, v1, v2] = t.as_triangle();
+                let (a, b, c) = (v0.position(), v1.position(), v2.position());
+                ((b.x - a.x) * (c.y - a.y) - (c.x - a.x) * (b.y - a.y)).abs() / 2.0
+            })
+            .sum();
+        assert_relative_eq!(hull_area(&hull), area, epsilon = 1e-10);
+    }
+
+    fn rotated_rectangle() -> Vec<Point2<f64>> {
+        let (sin, cos) = 0.5f64.sin_cos();
+        [(0.0, 0.0), (4.0, 0.0), (4.0, 1.0), (0.0, 1.0), (2.0, 0.5)]
+            .iter()
+            .map(|&(x, y)| Point2::new(x * cos - y * sin + 1.0, x * sin + y * cos - 2.0))
+            .collect()
+    }
+
+    #[test]
+    fn test_rotating_calipers_rectangle() {
+        let hull = convex_hull::<FloatKernel, _>(&rotated_rectangle());
+        assert_eq!(hull.len(), 4);
+        let (p0, p1) = hull_diameter(&hull).unwrap();
+        assert_relative_eq!((p0 - p1).magnitude2(), 17.0, epsilon = 1e-10);
+        assert_relative_eq!(hull_width(&hull).unwrap(), 1.0, epsilon = 1e-10);
+        for bbox in &[
+            minimum_area_bounding_box(&hull).unwrap(),
+            minimum_perimeter_bounding_box(&hull).unwrap(),
+        ] {
+            assert_relative_eq!(hull_area(bbox), 4.0, epsilon = 1e-10);
+            for corner in bbox {
+                assert!(hull.iter().any(|p| (p - corner).magnitude2() < 1e-10));
+            }
+        }
+    }
+
+    #[test]
+    fn test_rotating_calipers_random() {
+        let seed = b"\x19\xc2\x7d\x40\xe5\x3b\x86\x0a\xf1\x5e\x97\x24\xbd\x68\x03\xca\
+                     \x52\x8f\x1e\xa4\x3d\x76\xe9\x0b\xc8\x61\x2a\xf5\x97\x4c\xb3\x18";
+        let points = random_points_with_seed::<f64>(300, seed);
+        let hull = convex_hull::<FloatKernel, _>(&points);
+        let n = hull.len();
+        // Brute force calculation of all values
+        let mut diameter: f64 = 0.0;
+        for p0 in &hull {
+            for p1 in &hull {
+                diameter = diameter.max((p0 - p1).magnitude2());
+            }
+        }
+        let (mut width, mut area, mut perimeter) = (f64::MAX, f64::MAX, f64::MAX);
+        for i in 0..n {
+            let dir = (hull[(i + 1) % n] - hull[i]).normalize();
+            let normal = Vector2::new(-dir.y, dir.x);
+            let projections: Vec<_> = hull.iter().map(|p| (p - hull[i]).dot(dir)).collect();
+            let heights: Vec<_> = hull.iter().map(|p| (p - hull[i]).dot(normal)).collect();
+            let length = projections.iter().cloned().fold(f64::MIN, f64::max)
+                - projections.iter().cloned().fold(f64::MAX, f64::min);
+            let height = heights.iter().cloned().fold(f64::MIN, f64::max);
+            width = width.min(height);
+            area = area.min(length * height);
+            perimeter = perimeter.min(2.0 * (length + height));
+        }
+        let (p0, p1) = hull_diameter(&hull).unwrap();
+        assert_relative_eq!((p0 - p1).magnitude2(), diameter);
+        assert_relative_eq!(hull_width(&hull).unwrap(), width, epsilon = 1e-10);
+        let bbox = minimum_area_bounding_box(&hull).unwrap();
+        assert_relative_eq!(hull_area(&bbox), area, epsilon = 1e-10);
+        let bbox = minimum_perimeter_bounding_box(&hull).unwrap();
+        assert_relative_eq!(hull_perimeter(&bbox), perimeter, epsilon = 1e-10);
+
+        assert_eq!(hull_diameter::<Point2<f64>>(&[]), None);
+        assert_eq!(hull_width(&hull[..1]), Some(0.0));
+        assert_eq!(hull_width(&hull[..2]), Some(0.0));
+    }
+}
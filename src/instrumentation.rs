@@ -0,0 +1,191 @@
+// Copyright 2017 The Spade Developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Counters for the work done by queries and triangulations.
+//!
+//! The counters help to compare pruning strategies or to find inputs that
+//! cause an unusual amount of work. With the `std` feature, each thread has
+//! its own counters, which only count the work done on that thread. Work
+//! done on other threads, e.g. by the `rayon` batch queries, is not included.
+//! Without `std`, the counters are global, shared by all threads and updated
+//! with relaxed atomic operations. Without the `instrumentation` feature,
+//! nothing is counted and the recording compiles to nothing.
+
+#[cfg(all(feature = "instrumentation", feature = "std"))]
+use core::cell::Cell;
+#[cfg(all(feature = "instrumentation", not(feature = "std")))]
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+/// A snapshot of all counters.
+#[cfg(feature = "instrumentation")]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Counters {
+    /// R-tree directory nodes whose children were inspected by a query.
+    pub node_visits: usize,
+    /// Distances between a query point and an object or a bounding rectangle.
+    pub distance_computations: usize,
+    /// Orientation and in-circle tests of Delaunay kernels.
+    pub predicate_evaluations: usize,
+    /// Edge flips performed by triangulations.
+    pub edge_flips: usize,
+}
+
+#[cfg(feature = "instrumentation")]
+impl Counters {
+    /// Returns the componentwise difference to an earlier snapshot.
+    pub fn since(&self, earlier: &Counters) -> Counters {
+        Counters {
+            node_visits: self.node_visits.wrapping_sub(earlier.node_visits),
+            distance_computations: self
+                .distance_computations
+                .wrapping_sub(earlier.distance_computations),
+            predicate_evaluations: self
+                .predicate_evaluations
+                .wrapping_sub(earlier.predicate_evaluations),
+            edge_flips: self.edge_flips.wrapping_sub(earlier.edge_flips),
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+pub(crate) enum Counter {
+    NodeVisits,
+    DistanceComputations,
+    PredicateEvaluations,
+    EdgeFlips,
+}
+
+#[cfg(all(feature = "instrumentation", feature = "std"))]
+std::thread_local! {
+    static COUNTERS: [Cell<usize>; 4] = const { [const { Cell::new(0) }; 4] };
+}
+
+#[cfg(all(feature = "instrumentation", not(feature = "std")))]
+static COUNTERS: [AtomicUsize; 4] = [const { AtomicUsize::new(0) }; 4];
+
+/// Adds `count` to a counter.
+#[inline(always)]
+pub(crate) fn record(counter: Counter, count: usize) {
+    #[cfg(all(feature = "instrumentation", feature = "std"))]
+    COUNTERS.with(|counters| {
+        let cell = &counters[counter as usize];
+        cell.set(cell.get().wrapping_add(count));
+    });
+    #[cfg(all(feature = "instrumentation", not(feature = "std")))]
+    COUNTERS[counter as usize].fetch_add(count, Ordering::Relaxed);
+    #[cfg(not(feature = "instrumentation"))]
+    let _ = (counter, count);
+}
+
+#[cfg(all(feature = "instrumentation", feature = "std"))]
+fn get(counter: Counter) -> usize {
+    COUNTERS.with(|counters| counters[counter as usize].get())
+}
+
+#[cfg(all(feature = "instrumentation", not(feature = "std")))]
+fn get(counter: Counter) -> usize {
+    COUNTERS[counter as usize].load(Ordering::Relaxed)
+}
+
+/// Returns the current values of all counters.
+///
+/// With the `std` feature, these are the counters of the current thread.
+#[cfg(feature = "instrumentation")]
+pub fn counters() -> Counters {
+    Counters {
+        node_visits: get(Counter::NodeVisits),
+        distance_computations: get(Counter::DistanceComputations),
+        predicate_evaluations: get(Counter::PredicateEvaluations),
+        edge_flips: get(Counter::EdgeFlips),
+    }
+}
+
+/// Sets all counters to zero.
+///
+/// With the `std` feature, only the counters of the current thread are reset.
+#[cfg(feature = "instrumentation")]
+pub fn reset() {
+    #[cfg(feature = "std")]
+    COUNTERS.with(|counters| counters.iter().for_each(|counter| counter.set(0)));
+    #[cfg(not(feature = "std"))]
+    for counter in &COUNTERS {
+        counter.store(0, Ordering::Relaxed);
+    }
+}
+
+/// Runs `f` and returns its result together with the work it caused.
+///
+/// With the `std` feature, this counts the work `f` does on the current
+/// thread. Work that `f` moves to other threads is missing. Without `std`,
+/// work done by other threads at the same time is included as well.
+///
+/// # Example
+///
+/// ```
+/// use spade::instrumentation;
+/// use spade::rtree::RTree;
+/// use spade::Point2;
+///
+/// let tree = RTree::bulk_load((0..1000).map(|i| Point2::new(i as f64, 0.0)).collect());
+/// let (_, counters) = instrumentation::measure(|| tree.nearest_neighbor(&Point2::new(500.2, 1.0)));
+/// assert!(counters.node_visits > 0);
+/// assert!(counters.distance_computations > 0);
+/// ```
+#[cfg(feature = "instrumentation")]
+pub fn measure<R, F>(f: F) -> (R, Counters)
+where
+    F: FnOnce() -> R,
+{
+    let before = counters();
+    let result = f();
+    (result, counters().since(&before))
+}
+
+#[cfg(all(test, feature = "instrumentation"))]
+mod test {
+    use super::measure;
+    use crate::delaunay::FloatDelaunayTriangulation;
+    use crate::rtree::RTree;
+    use crate::testutils::*;
+
+    #[test]
+    fn test_counters() {
+        let points = random_points_with_seed::<f64>(500, &[1; 32]);
+        let (_, construction) = measure(|| {
+            let mut delaunay = FloatDelaunayTriangulation::with_walk_locate();
+            for point in &points {
+                delaunay.insert(*point);
+            }
+        });
+        assert!(construction.predicate_evaluations >= 500);
+        assert!(construction.edge_flips > 0);
+
+        let tree = RTree::bulk_load(points.clone());
+        let (nearest, query) = measure(|| tree.nearest_neighbor(&points[7]).cloned());
+        assert_eq!(nearest, Some(points[7]));
+        assert!(query.node_visits >= tree.depth());
+        assert!(query.distance_computations >= query.node_visits);
+        let (_, approx) = measure(|| tree.nearest_neighbor_approx(&points[7], 1.0));
+        assert!(approx.node_visits > 0);
+        let (found, lookup) = measure(|| tree.lookup(&points[3]).cloned());
+        assert_eq!(found, Some(points[3]));
+        assert!(lookup.node_visits > 0);
+    }
+
+    #[test]
+    fn test_counters_are_thread_local() {
+        let points = random_points_with_seed::<f64>(100, &[2; 32]);
+        let tree = RTree::bulk_load(points.clone());
+        let (_, counters) = measure(|| {
+            std::thread::scope(|scope| {
+                scope.spawn(|| tree.nearest_neighbor(&points[0]).cloned());
+            })
+        });
+        assert_eq!(counters, Default::default());
+    }
+}
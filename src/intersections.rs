@@ -0,0 +1,673 @@
+// Copyright 2017 The Spade Developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Intersections among sets of segments.
+//!
+//! `segment_intersections` finds all intersecting pairs of a set of segments
+//! with the Bentley–Ottmann plane sweep. It runs in expected `O((n + k) log n)` time
+//! for `n` segments and `k` intersections, compared to the `O(n²)` time of
+//! testing all pairs. A typical use is to split ("node") unclean input data
+//! at its intersections before inserting it as constraints into a
+//! `ConstrainedDelaunayTriangulation`, which does not allow intersecting
+//! constraint edges.
+//!
+//! All decisions whether a point lies on, above or below a segment are made
+//! by the kernel. Intersection points are computed in floating point
+//! arithmetic. Use `FloatKernel` to handle touching, collinear and
+//! overlapping segments robustly.
+
+use crate::kernels::DelaunayKernel;
+use crate::point_traits::{PointNExtensions, TwoDimensional};
+use crate::primitives::{SegmentIntersection, SimpleEdge};
+use crate::traits::SpadeFloat;
+use alloc::collections::{BTreeSet, BinaryHeap};
+use alloc::{vec, vec::Vec};
+use core::cmp::Ordering;
+
+enum EventKind {
+    Start(usize),
+    End(usize),
+    Crossing(usize, usize),
+}
+
+/// An event of the sweep, ordered such that `BinaryHeap` yields the
+/// lexicographically smallest point first.
+struct Event<V> {
+    point: V,
+    kind: EventKind,
+}
+
+impl<V: TwoDimensional> PartialEq for Event<V> {
+    fn eq(&self, other: &Self) -> bool {
+        self.point == other.point
+    }
+}
+
+impl<V: TwoDimensional> Eq for Event<V> {}
+
+impl<V: TwoDimensional> PartialOrd for Event<V> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<V: TwoDimensional> Ord for Event<V> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.point.lex_compare(&self.point)
+    }
+}
+
+fn is_vertical<V: TwoDimensional>(segment: &SimpleEdge<V>) -> bool {
+    segment.from.nth(0) == segment.to.nth(0)
+}
+
+/// Returns whether `point` lies above (`Greater`), on (`Equal`) or below
+/// (`Less`) a segment whose `from` point is its lexicographically smaller
+/// end point.
+fn locate<K, V>(segment: &SimpleEdge<V>, point: &V) -> Ordering
+where
+    K: DelaunayKernel<V::Scalar>,
+    V: TwoDimensional,
+{
+    if is_vertical(segment) {
+        if point.nth(1) > segment.to.nth(1) {
+            Ordering::Greater
+        } else if point.nth(1) < segment.from.nth(1) {
+            Ordering::Less
+        } else {
+            Ordering::Equal
+        }
+    } else {
+        let side = K::side_query(segment, point);
+        if side.is_on_left_side() {
+            Ordering::Greater
+        } else if side.is_on_right_side() {
+            Ordering::Less
+        } else {
+            Ordering::Equal
+        }
+    }
+}
+
+/// Compares two segments passing through `point` by their order right
+/// after `point`. Vertical segments come last.
+fn compare_after<K, V>(point: &V, a: &SimpleEdge<V>, b: &SimpleEdge<V>) -> Ordering
+where
+    K: DelaunayKernel<V::Scalar>,
+    V: TwoDimensional,
+{
+    match (is_vertical(a), is_vertical(b)) {
+        (true, true) => Ordering::Equal,
+        (true, false) => Ordering::Greater,
+        (false, true) => Ordering::Less,
+        (false, false) => {
+            let side = K::side_query(&SimpleEdge::new(point.clone(), b.to.clone()), &a.to);
+            if side.is_on_left_side() {
+                Ordering::Greater
+            } else if side.is_on_right_side() {
+                Ordering::Less
+            } else {
+                Ordering::Equal
+            }
+        }
+    }
+}
+
+const NONE: u32 = u32::MAX;
+
+#[derive(Clone, Copy)]
+struct StatusNode {
+    left: u32,
+    right: u32,
+    parent: u32,
+    size: u32,
+    priority: u32,
+}
+
+impl StatusNode {
+    fn new(priority: u32) -> Self {
+        StatusNode {
+            left: NONE,
+            right: NONE,
+            parent: NONE,
+            size: 1,
+            priority,
+        }
+    }
+}
+
+/// The segments crossing the sweep line, ordered from bottom to top.
+///
+/// The order of two segments depends on the position of the sweep line,
+/// hence the status is a treap with implicit keys: Positions are derived
+/// from subtree sizes and every segment owns the node with its index. All
+/// operations take expected `O(log n)` time, plus the number of segments
+/// inserted or removed.
+struct Status {
+    nodes: Vec<StatusNode>,
+    root: u32,
+}
+
+impl Status {
+    fn new(num_segments: usize) -> Self {
+        let nodes = (0..num_segments as u32)
+            .map(|index| {
+                // Priorities must not correlate with the order of the segments
+                let mut hash = index.wrapping_add(0x9e37_79b9);
+                hash = (hash ^ (hash >> 16)).wrapping_mul(0x85eb_ca6b);
+                hash = (hash ^ (hash >> 13)).wrapping_mul(0xc2b2_ae35);
+                StatusNode::new(hash ^ (hash >> 16))
+            })
+            .collect();
+        Status { nodes, root: NONE }
+    }
+
+    fn size(&self, node: u32) -> usize {
+        if node == NONE {
+            0
+        } else {
+            self.nodes[node as usize].size as usize
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.size(self.root)
+    }
+
+    /// Updates the size of a node and the parent links of its children.
+    fn update(&mut self, node: u32) {
+        let StatusNode { left, right, .. } = self.nodes[node as usize];
+        self.nodes[node as usize].size = (1 + self.size(left) + self.size(right)) as u32;
+        for child in [left, right] {
+            if child != NONE {
+                self.nodes[child as usize].parent = node;
+            }
+        }
+    }
+
+    fn set_root(&mut self, root: u32) {
+        self.root = root;
+        if root != NONE {
+            self.nodes[root as usize].parent = NONE;
+        }
+    }
+
+    /// Returns the segment at the given position.
+    fn get(&self, mut position: usize) -> usize {
+        let mut node = self.root;
+        loop {
+            let StatusNode { left, right, .. } = self.nodes[node as usize];
+            let left_size = self.size(left);
+            match position.cmp(&left_size) {
+                Ordering::Less => node = left,
+                Ordering::Equal => return node as usize,
+                Ordering::Greater => {
+                    position -= left_size + 1;
+                    node = right;
+                }
+            }
+        }
+    }
+
+    /// Returns the position of the first segment for which `predicate` is
+    /// `false`, assuming that it is `true` for all segments before.
+    fn partition_point(&self, mut predicate: impl FnMut(&usize) -> bool) -> usize {
+        let mut position = 0;
+        let mut node = self.root;
+        while node != NONE {
+            let StatusNode { left, right, .. } = self.nodes[node as usize];
+            if predicate(&(node as usize)) {
+                position += self.size(left) + 1;
+                node = right;
+            } else {
+                node = left;
+            }
+        }
+        position
+    }
+
+    /// Returns the position of a segment, if it crosses the sweep line.
+    fn position(&self, segment: usize) -> Option<usize> {
+        let mut node = segment as u32;
+        if self.nodes[segment].parent == NONE && self.root != node {
+            return None;
+        }
+        let mut position = self.size(self.nodes[segment].left);
+        loop {
+            let parent = self.nodes[node as usize].parent;
+            if parent == NONE {
+                return Some(position);
+            }
+            if self.nodes[parent as usize].right == node {
+                position += self.size(self.nodes[parent as usize].left) + 1;
+            }
+            node = parent;
+        }
+    }
+
+    /// Splits a subtree into its first `count` nodes and all others.
+    fn split(&mut self, node: u32, count: usize) -> (u32, u32) {
+        if node == NONE {
+            return (NONE, NONE);
+        }
+        let StatusNode { left, right, .. } = self.nodes[node as usize];
+        let left_size = self.size(left);
+        if count <= left_size {
+            let (first, second) = self.split(left, count);
+            self.nodes[node as usize].left = second;
+            self.update(node);
+            (first, node)
+        } else {
+            let (first, second) = self.split(right, count - left_size - 1);
+            self.nodes[node as usize].right = first;
+            self.update(node);
+            (node, second)
+        }
+    }
+
+    /// Concatenates two subtrees.
+    fn merge(&mut self, first: u32, second: u32) -> u32 {
+        if first == NONE {
+            return second;
+        }
+        if second == NONE {
+            return first;
+        }
+        if self.nodes[first as usize].priority > self.nodes[second as usize].priority {
+            let right = self.nodes[first as usize].right;
+            self.nodes[first as usize].right = self.merge(right, second);
+            self.update(first);
+            first
+        } else {
+            let left = self.nodes[second as usize].left;
+            self.nodes[second as usize].left = self.merge(first, left);
+            self.update(second);
+            second
+        }
+    }
+
+    /// Removes all nodes of a subtree and appends their segments in order.
+    fn take_subtree(&mut self, node: u32, segments: &mut Vec<usize>) {
+        if node == NONE {
+            return;
+        }
+        let StatusNode {
+            left,
+            right,
+            priority,
+            ..
+        } = self.nodes[node as usize];
+        self.take_subtree(left, segments);
+        segments.push(node as usize);
+        self.take_subtree(right, segments);
+        self.nodes[node as usize] = StatusNode::new(priority);
+    }
+
+    /// Removes and returns the segments at positions `lower..upper`.
+    fn drain(&mut self, lower: usize, upper: usize) -> Vec<usize> {
+        let (rest, second) = self.split(self.root, upper);
+        let (first, drained) = self.split(rest, lower);
+        let mut segments = Vec::with_capacity(upper - lower);
+        self.take_subtree(drained, &mut segments);
+        let root = self.merge(first, second);
+        self.set_root(root);
+        segments
+    }
+
+    /// Inserts segments in the given order before `position`.
+    fn insert(&mut self, position: usize, segments: Vec<usize>) {
+        let mut inserted = NONE;
+        for segment in segments {
+            inserted = self.merge(inserted, segment as u32);
+        }
+        let (first, second) = self.split(self.root, position);
+        let root = self.merge(first, inserted);
+        let root = self.merge(root, second);
+        self.set_root(root);
+    }
+}
+
+struct Sweep<'a, V: TwoDimensional> {
+    segments: &'a [SimpleEdge<V>],
+    events: BinaryHeap<Event<V>>,
+    status: Status,
+    pairs: BTreeSet<(usize, usize)>,
+}
+
+impl<'a, V> Sweep<'a, V>
+where
+    V: TwoDimensional,
+    V::Scalar: SpadeFloat,
+{
+    /// Schedules the intersection of two neighboring segments if it lies
+    /// right of the sweep line.
+    fn check<K: DelaunayKernel<V::Scalar>>(&mut self, a: usize, b: usize, point: &V) {
+        let pair = (a.min(b), a.max(b));
+        if self.pairs.contains(&pair) {
+            // Two segments need to be swapped at most once
+            return;
+        }
+        let first = match self.segments[a].classify_intersection::<K>(&self.segments[b]) {
+            SegmentIntersection::Disjoint => return,
+            SegmentIntersection::Crossing(p) | SegmentIntersection::Touching(p) => p,
+            SegmentIntersection::CollinearOverlap(overlap) => {
+                if overlap.from.lex_compare(&overlap.to) == Ordering::Greater {
+                    overlap.to
+                } else {
+                    overlap.from
+                }
+            }
+        };
+        if first.lex_compare(point) == Ordering::Greater {
+            self.events.push(Event {
+                point: first,
+                kind: EventKind::Crossing(pair.0, pair.1),
+            });
+        }
+    }
+
+    fn handle_event_point<K: DelaunayKernel<V::Scalar>>(&mut self, event: Event<V>) {
+        let point = event.point;
+        let mut starting = Vec::new();
+        let mut hinted = Vec::new();
+        let mut kinds = vec![event.kind];
+        while self.events.peek().is_some_and(|next| next.point == point) {
+            if let Some(next) = self.events.pop() {
+                kinds.push(next.kind);
+            }
+        }
+        for kind in kinds {
+            match kind {
+                EventKind::Start(s) => starting.push(s),
+                EventKind::End(s) => hinted.push(s),
+                EventKind::Crossing(a, b) => hinted.extend([a, b]),
+            }
+        }
+
+        // Segments containing the event point are adjacent in the status
+        let segments = self.segments;
+        let on_point = |s: &usize| locate::<K, V>(&segments[*s], &point);
+        let mut lower = self
+            .status
+            .partition_point(|s| on_point(s) == Ordering::Greater);
+        let mut upper = self
+            .status
+            .partition_point(|s| on_point(s) != Ordering::Less);
+        // Computed intersection points may miss the segments by a rounding
+        // error. The status positions of the intersecting segments are used
+        // instead.
+        for s in hinted {
+            if let Some(index) = self.status.position(s) {
+                if lower == upper {
+                    lower = index;
+                    upper = index + 1;
+                } else {
+                    lower = lower.min(index);
+                    upper = upper.max(index + 1);
+                }
+            }
+        }
+        while lower > 0 && on_point(&self.status.get(lower - 1)) == Ordering::Equal {
+            lower -= 1;
+        }
+        while upper < self.status.len() && on_point(&self.status.get(upper)) == Ordering::Equal {
+            upper += 1;
+        }
+        let through = self.status.drain(lower, upper);
+
+        let mut involved: Vec<usize> = through.iter().chain(starting.iter()).cloned().collect();
+        involved.sort_unstable();
+        involved.dedup();
+        for (position, a) in involved.iter().enumerate() {
+            for b in &involved[position + 1..] {
+                self.pairs.insert((*a, *b));
+            }
+        }
+
+        let position = lower;
+        let mut inserted: Vec<usize> = involved
+            .into_iter()
+            .filter(|s| segments[*s].to.lex_compare(&point) == Ordering::Greater)
+            .collect();
+        inserted.sort_by(|a, b| {
+            compare_after::<K, V>(&point, &segments[*a], &segments[*b]).then(a.cmp(b))
+        });
+        let count = inserted.len();
+        self.status.insert(position, inserted);
+
+        self.check_gap::<K>(position, &point);
+        if count > 0 {
+            self.check_gap::<K>(position + count, &point);
+        }
+    }
+
+    /// Checks the segments on both sides of the gap before `status[gap]`.
+    ///
+    /// Collinear overlapping segments share a position in the status, only
+    /// one of them would be a direct neighbor. Hence, all segments collinear
+    /// to the direct neighbors are checked as well.
+    fn check_gap<K: DelaunayKernel<V::Scalar>>(&mut self, gap: usize, point: &V) {
+        if gap == 0 || gap >= self.status.len() {
+            return;
+        }
+        let segments = self.segments;
+        let mut lower = gap - 1;
+        while lower > 0
+            && is_collinear::<K, V>(
+                &segments[self.status.get(lower - 1)],
+                &segments[self.status.get(gap - 1)],
+            )
+        {
+            lower -= 1;
+        }
+        let mut upper = gap + 1;
+        while upper < self.status.len()
+            && is_collinear::<K, V>(
+                &segments[self.status.get(upper)],
+                &segments[self.status.get(gap)],
+            )
+        {
+            upper += 1;
+        }
+        for a in lower..gap {
+            for b in gap..upper {
+                self.check::<K>(self.status.get(a), self.status.get(b), point);
+            }
+        }
+    }
+}
+
+fn is_collinear<K, V>(a: &SimpleEdge<V>, b: &SimpleEdge<V>) -> bool
+where
+    K: DelaunayKernel<V::Scalar>,
+    V: TwoDimensional,
+{
+    K::side_query(a, &b.from).is_on_line() && K::side_query(a, &b.to).is_on_line()
+}
+
+/// Finds all pairs of intersecting segments.
+///
+/// Returns the indices of both segments, the smaller one first, and how they
+/// intersect. Segments that touch each other or share an end point are
+/// reported as well. The pairs are sorted by their indices.
+///
+/// All coordinates must be finite.
+///
+/// # Example
+///
+/// ```
+/// use spade::intersections::segment_intersections;
+/// use spade::kernels::FloatKernel;
+/// use spade::primitives::{SegmentIntersection, SimpleEdge};
+/// use spade::Point2;
+///
+/// let segments = vec![
+///     SimpleEdge::new(Point2::new(0.0, 0.0), Point2::new(2.0, 2.0)),
+///     SimpleEdge::new(Point2::new(0.0, 2.0), Point2::new(2.0, 0.0)),
+///     SimpleEdge::new(Point2::new(3.0, 0.0), Point2::new(3.0, 2.0)),
+/// ];
+/// let intersections = segment_intersections::<FloatKernel, _>(&segments);
+/// assert_eq!(
+///     intersections,
+///     vec![(0, 1, SegmentIntersection::Crossing(Point2::new(1.0, 1.0)))]
+/// );
+/// ```
+pub fn segment_intersections<K, V>(
+    segments: &[SimpleEdge<V>],
+) -> Vec<(usize, usize, SegmentIntersection<V>)>
+where
+    K: DelaunayKernel<V::Scalar>,
+    V: TwoDimensional,
+    V::Scalar: SpadeFloat,
+{
+    let normalized: Vec<_> = segments
+        .iter()
+        .map(|s| {
+            if s.from.lex_compare(&s.to) == Ordering::Greater {
+                SimpleEdge::new(s.to.clone(), s.from.clone())
+            } else {
+                s.clone()
+            }
+        })
+        .collect();
+    let mut events = BinaryHeap::with_capacity(2 * normalized.len());
+    for (index, segment) in normalized.iter().enumerate() {
+        events.push(Event {
+            point: segment.from.clone(),
+            kind: EventKind::Start(index),
+        });
+        events.push(Event {
+            point: segment.to.clone(),
+            kind: EventKind::End(index),
+        });
+    }
+    let mut sweep = Sweep {
+        segments: &normalized,
+        events,
+        status: Status::new(normalized.len()),
+        pairs: BTreeSet::new(),
+    };
+    while let Some(event) = sweep.events.pop() {
+        sweep.handle_event_point::<K>(event);
+    }
+    sweep
+        .pairs
+        .into_iter()
+        .filter_map(
+            |(a, b)| match segments[a].classify_intersection::<K>(&segments[b]) {
+                SegmentIntersection::Disjoint => None,
+                intersection => Some((a, b, intersection)),
+            },
+        )
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::segment_intersections;
+    use crate::kernels::FloatKernel;
+    use crate::primitives::{SegmentIntersection, SimpleEdge};
+    use crate::Point2;
+    use rand::{Rng, SeedableRng};
+    use rand_hc::Hc128Rng;
+
+    fn brute_force(
+        segments: &[SimpleEdge<Point2<f64>>],
+    ) -> Vec<(usize, usize, SegmentIntersection<Point2<f64>>)> {
+        let mut result = Vec::new();
+        for i in 0..segments.len() {
+            for j in i + 1..segments.len() {
+                let intersection = segments[i].classify_intersection::<FloatKernel>(&segments[j]);
+                if intersection != SegmentIntersection::Disjoint {
+                    result.push((i, j, intersection));
+                }
+            }
+        }
+        result
+    }
+
+    #[test]
+    fn test_random_segments() {
+        let mut rng = Hc128Rng::from_seed([9; 32]);
+        let segments: Vec<_> = (0..300)
+            .map(|_| {
+                let from = Point2::new(rng.gen_range(-10.0..10.0), rng.gen_range(-10.0..10.0));
+                let to = Point2::new(
+                    from.x + rng.gen_range(-3.0..3.0),
+                    from.y + rng.gen_range(-3.0..3.0),
+                );
+                SimpleEdge::new(from, to)
+            })
+            .collect();
+        let expected = brute_force(&segments);
+        assert!(expected.len() > 100);
+        assert_eq!(segment_intersections::<FloatKernel, _>(&segments), expected);
+    }
+
+    #[test]
+    fn test_degenerate_segments() {
+        let mut rng = Hc128Rng::from_seed([10; 32]);
+        // Segments on a small integer grid share end points, touch, overlap,
+        // are vertical or have zero length.
+        let segments: Vec<_> = (0..200)
+            .map(|_| {
+                let mut point = || {
+                    Point2::new(
+                        f64::from(rng.gen_range(0..6)),
+                        f64::from(rng.gen_range(0..6)),
+                    )
+                };
+                SimpleEdge::new(point(), point())
+            })
+            .collect();
+        assert_eq!(
+            segment_intersections::<FloatKernel, _>(&segments),
+            brute_force(&segments)
+        );
+    }
+
+    #[test]
+    fn test_many_parallel_segments() {
+        // A deep status: many stacked segments crossed by a few steep ones
+        let mut segments: Vec<_> = (0..1000)
+            .map(|i| {
+                let y = f64::from(i) * 0.01;
+                SimpleEdge::new(Point2::new(f64::from(i % 7), y), Point2::new(20.0, y))
+            })
+            .collect();
+        for i in 0..8 {
+            let x = 2.5 * f64::from(i) + 0.3;
+            segments.push(SimpleEdge::new(
+                Point2::new(x, -1.0),
+                Point2::new(x + 1.0, 11.0),
+            ));
+        }
+        let expected = brute_force(&segments);
+        assert!(expected.len() > 5000);
+        assert_eq!(segment_intersections::<FloatKernel, _>(&segments), expected);
+    }
+
+    #[test]
+    fn test_star() {
+        // Many segments through a single point
+        let segments: Vec<_> = (0..16)
+            .map(|i| {
+                let angle = f64::from(i) * core::f64::consts::PI / 16.0;
+                let direction = Point2::new(angle.cos(), angle.sin());
+                SimpleEdge::new(
+                    Point2::new(-direction.x, -direction.y),
+                    Point2::new(direction.x, direction.y),
+                )
+            })
+            .collect();
+        let intersections = segment_intersections::<FloatKernel, _>(&segments);
+        assert_eq!(intersections.len(), 16 * 15 / 2);
+        assert_eq!(intersections, brute_force(&segments));
+        assert!(segment_intersections::<FloatKernel, Point2<f64>>(&[]).is_empty());
+    }
+}
@@ -0,0 +1,280 @@
+// Copyright 2017 The Spade Developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! One dimensional intervals and a static interval tree.
+//!
+//! `IntervalTree` answers stabbing queries (which intervals contain a value?)
+//! and overlap queries (which intervals overlap a range?) for a fixed set of
+//! closed intervals, each with an associated value. Intervals can use any of
+//! spade's scalar types, e.g. time stamps as `i64` or scanline coordinates as
+//! `f64`.
+//!
+//! # Example
+//!
+//! ```
+//! use spade::interval::{Interval, IntervalTree};
+//!
+//! let tree = IntervalTree::bulk_load(vec![
+//!     (Interval::new(0, 10), "morning"),
+//!     (Interval::new(8, 14), "noon"),
+//!     (Interval::new(13, 20), "afternoon"),
+//! ]);
+//! let at_nine: Vec<_> = tree.lookup_containing(&9).into_iter().map(|(_, v)| *v).collect();
+//! assert_eq!(at_nine, vec!["morning", "noon"]);
+//! assert_eq!(tree.lookup_overlapping(&Interval::new(14, 30)).len(), 2);
+//! ```
+
+use crate::traits::SpadeNum;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+
+#[cfg(feature = "serde_serialize")]
+use serde::{Deserialize, Serialize};
+
+/// A closed interval `[start, end]` of scalar values.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde_serialize", derive(Serialize, Deserialize))]
+pub struct Interval<S> {
+    start: S,
+    end: S,
+}
+
+impl<S: SpadeNum> Interval<S> {
+    /// Creates an interval between two values.
+    ///
+    /// The values may be given in any order.
+    pub fn new(a: S, b: S) -> Self {
+        if b < a {
+            Interval { start: b, end: a }
+        } else {
+            Interval { start: a, end: b }
+        }
+    }
+
+    /// Returns the interval's smaller bound.
+    pub fn start(&self) -> &S {
+        &self.start
+    }
+
+    /// Returns the interval's larger bound.
+    pub fn end(&self) -> &S {
+        &self.end
+    }
+
+    /// Returns the interval's length.
+    pub fn length(&self) -> S {
+        self.end.clone() - self.start.clone()
+    }
+
+    /// Returns `true` if a value lies within the interval, including its bounds.
+    pub fn contains(&self, value: &S) -> bool {
+        self.start <= *value && *value <= self.end
+    }
+
+    /// Returns `true` if both intervals share at least one value.
+    pub fn overlaps(&self, other: &Interval<S>) -> bool {
+        self.start <= other.end && other.start <= self.end
+    }
+}
+
+/// A static interval tree.
+///
+/// The tree stores its intervals sorted by their start as an implicit,
+/// balanced binary tree. Each node knows the largest end of its subtree,
+/// which allows to skip subtrees that end before a query begins. Building
+/// takes `O(n log n)` time, a query takes `O(log n + k)` time for `k` results.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde_serialize", derive(Serialize, Deserialize))]
+pub struct IntervalTree<S, T> {
+    entries: Vec<(Interval<S>, T)>,
+    // The largest end of the subtree rooted at the same index
+    max_ends: Vec<S>,
+}
+
+impl<S: SpadeNum, T> Default for IntervalTree<S, T> {
+    fn default() -> Self {
+        IntervalTree {
+            entries: Vec::new(),
+            max_ends: Vec::new(),
+        }
+    }
+}
+
+impl<S: SpadeNum, T> IntervalTree<S, T> {
+    /// Creates an empty interval tree.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Creates an interval tree containing the given intervals and values.
+    pub fn bulk_load(mut entries: Vec<(Interval<S>, T)>) -> Self {
+        entries.sort_by(|(l, _), (r, _)| l.start.partial_cmp(&r.start).unwrap_or(Ordering::Equal));
+        let mut max_ends: Vec<S> = entries.iter().map(|(i, _)| i.end.clone()).collect();
+        augment(&entries, &mut max_ends, 0, entries.len());
+        IntervalTree { entries, max_ends }
+    }
+
+    /// Creates an interval tree containing a copy of the given intervals and values.
+    pub fn bulk_load_from_slice(entries: &[(Interval<S>, T)]) -> Self
+    where
+        T: Clone,
+    {
+        Self::bulk_load(entries.to_vec())
+    }
+
+    /// Returns the number of intervals contained in this tree.
+    pub fn size(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns all intervals and their values, sorted by the intervals' start.
+    pub fn entries(&self) -> &[(Interval<S>, T)] {
+        &self.entries
+    }
+
+    /// Returns an iterator over all intervals and their values, sorted by the intervals' start.
+    pub fn iter(&self) -> impl Iterator<Item = (&Interval<S>, &T)> + '_ {
+        self.entries
+            .iter()
+            .map(|(interval, value)| (interval, value))
+    }
+
+    /// Returns all intervals containing a value (stabbing query).
+    ///
+    /// The results are sorted by the intervals' start.
+    pub fn lookup_containing(&self, value: &S) -> Vec<(&Interval<S>, &T)> {
+        self.lookup_overlapping(&Interval::new(value.clone(), value.clone()))
+    }
+
+    /// Returns all intervals sharing at least one value with a query interval.
+    ///
+    /// The results are sorted by the intervals' start.
+    pub fn lookup_overlapping(&self, query: &Interval<S>) -> Vec<(&Interval<S>, &T)> {
+        let mut result = Vec::new();
+        self.overlapping(query, 0, self.entries.len(), &mut result);
+        result
+    }
+
+    fn overlapping<'a>(
+        &'a self,
+        query: &Interval<S>,
+        lower: usize,
+        upper: usize,
+        result: &mut Vec<(&'a Interval<S>, &'a T)>,
+    ) {
+        if lower >= upper {
+            return;
+        }
+        let middle = lower + (upper - lower) / 2;
+        if self.max_ends[middle] < query.start {
+            // All intervals of this subtree end before the query starts
+            return;
+        }
+        self.overlapping(query, lower, middle, result);
+        let (interval, value) = &self.entries[middle];
+        if interval.start > query.end {
+            // The remaining intervals start after the query ends
+            return;
+        }
+        if interval.end >= query.start {
+            result.push((interval, value));
+        }
+        self.overlapping(query, middle + 1, upper, result);
+    }
+}
+
+/// Stores the largest end of each subtree of the implicit tree in `max_ends`.
+fn augment<S: SpadeNum, T>(
+    entries: &[(Interval<S>, T)],
+    max_ends: &mut [S],
+    lower: usize,
+    upper: usize,
+) -> Option<S> {
+    if lower >= upper {
+        return None;
+    }
+    let middle = lower + (upper - lower) / 2;
+    let mut max_end = entries[middle].0.end.clone();
+    for child in [
+        augment(entries, max_ends, lower, middle),
+        augment(entries, max_ends, middle + 1, upper),
+    ]
+    .into_iter()
+    .flatten()
+    {
+        if child > max_end {
+            max_end = child;
+        }
+    }
+    max_ends[middle] = max_end.clone();
+    Some(max_end)
+}
+
+impl<S: SpadeNum, T> FromIterator<(Interval<S>, T)> for IntervalTree<S, T> {
+    fn from_iter<I: IntoIterator<Item = (Interval<S>, T)>>(iter: I) -> Self {
+        IntervalTree::bulk_load(iter.into_iter().collect())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Interval, IntervalTree};
+    use rand::{Rng, SeedableRng};
+    use rand_hc::Hc128Rng;
+
+    #[test]
+    fn test_interval() {
+        let interval = Interval::new(3.0, -1.0);
+        assert_eq!(*interval.start(), -1.0);
+        assert_eq!(interval.length(), 4.0);
+        assert!(interval.contains(&3.0));
+        assert!(!interval.contains(&3.5));
+        assert!(interval.overlaps(&Interval::new(3.0, 7.0)));
+        assert!(!interval.overlaps(&Interval::new(-3.0, -1.5)));
+    }
+
+    #[test]
+    fn test_interval_tree_queries() {
+        let mut rng = Hc128Rng::from_seed([7; 32]);
+        let intervals: Vec<_> = (0..500)
+            .map(|index| {
+                let start = rng.gen_range(-1000i64..1000);
+                let length = rng.gen_range(0..100);
+                (Interval::new(start, start + length), index)
+            })
+            .collect();
+        let tree: IntervalTree<_, _> = intervals.iter().cloned().collect();
+        assert_eq!(tree.size(), 500);
+        for _ in 0..200 {
+            let a = rng.gen_range(-1100..1100);
+            let query = Interval::new(a, a + rng.gen_range(0..50));
+            let mut expected: Vec<_> = intervals
+                .iter()
+                .filter(|(interval, _)| interval.overlaps(&query))
+                .map(|(_, index)| *index)
+                .collect();
+            let mut found: Vec<_> = tree
+                .lookup_overlapping(&query)
+                .into_iter()
+                .map(|(_, index)| *index)
+                .collect();
+            expected.sort_unstable();
+            found.sort_unstable();
+            assert_eq!(found, expected);
+
+            let stabbed = tree.lookup_containing(&a);
+            assert!(stabbed.windows(2).all(|w| w[0].0.start() <= w[1].0.start()));
+            assert_eq!(
+                stabbed.len(),
+                intervals.iter().filter(|(i, _)| i.contains(&a)).count()
+            );
+        }
+        let empty: IntervalTree<f64, ()> = IntervalTree::new();
+        assert!(empty.lookup_containing(&0.0).is_empty());
+    }
+}
@@ -0,0 +1,434 @@
+// Copyright 2017 The Spade Developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! A static kd-tree for point data.
+//!
+//! `KdTree` stores nothing but its points: they are reordered into an
+//! implicit, balanced tree when the tree is built. This makes it smaller and
+//! usually faster to query than an `RTree` of the same points.
+//!
+//! Which one to use? Use a `KdTree` if the point set is known up front and
+//! never changes. Use an `RTree` if points are inserted or removed after
+//! construction or if the objects are not points, e.g. edges or rectangles.
+//!
+//! # Example
+//!
+//! ```
+//! use spade::kdtree::KdTree;
+//! use spade::Point2;
+//!
+//! let points: Vec<_> = (0..100)
+//!     .map(|i| Point2::new(f64::from(i % 10), f64::from(i / 10)))
+//!     .collect();
+//! let tree = KdTree::bulk_load_from_slice(&points);
+//! assert_eq!(tree.nearest_neighbor(&Point2::new(2.2, 3.9)), Some(&Point2::new(2.0, 4.0)));
+//! assert_eq!(tree.nearest_n_neighbors(&Point2::new(0.0, 0.0), 3).len(), 3);
+//! assert_eq!(tree.lookup_in_circle(&Point2::new(5.0, 5.0), &1.5).len(), 5);
+//! ```
+
+use crate::boundingrect::BoundingRect;
+use crate::instrumentation::{record, Counter};
+use crate::point_traits::{PointN, PointNExtensions};
+use alloc::collections::BinaryHeap;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use num::zero;
+
+#[cfg(feature = "serde_serialize")]
+use serde::{Deserialize, Serialize};
+
+/// A static kd-tree of points.
+///
+/// The tree is built once from a set of points and cannot be modified
+/// afterwards. See the [module documentation](index.html) for a comparison
+/// with `RTree`.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde_serialize", derive(Serialize, Deserialize))]
+pub struct KdTree<P: PointN> {
+    // The points of an implicit tree. The root of a subslice is stored at
+    // its middle and splits the subslice along the axis `depth % dimensions`.
+    points: Vec<P>,
+}
+
+impl<P: PointN> Default for KdTree<P> {
+    fn default() -> Self {
+        KdTree { points: Vec::new() }
+    }
+}
+
+/// A point of a n-nearest neighbor query, ordered by its distance.
+struct Candidate<'a, P: PointN> {
+    point: &'a P,
+    distance2: P::Scalar,
+}
+
+impl<'a, P: PointN> PartialEq for Candidate<'a, P> {
+    fn eq(&self, other: &Self) -> bool {
+        self.distance2 == other.distance2
+    }
+}
+
+impl<'a, P: PointN> Eq for Candidate<'a, P> {}
+
+impl<'a, P: PointN> PartialOrd for Candidate<'a, P> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<'a, P: PointN> Ord for Candidate<'a, P> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.distance2
+            .partial_cmp(&other.distance2)
+            .unwrap_or(Ordering::Equal)
+    }
+}
+
+fn build<P: PointN>(points: &mut [P], depth: usize) {
+    if points.len() <= 1 {
+        return;
+    }
+    let axis = depth % P::dimensions();
+    let middle = points.len() / 2;
+    ::pdqselect::select_by(points, middle, |l, r| {
+        l.nth(axis)
+            .partial_cmp(r.nth(axis))
+            .unwrap_or(Ordering::Equal)
+    });
+    let (lower, upper) = points.split_at_mut(middle);
+    build(lower, depth + 1);
+    build(&mut upper[1..], depth + 1);
+}
+
+fn distance2<P: PointN>(point: &P, query_point: &P) -> P::Scalar {
+    record(Counter::DistanceComputations, 1);
+    point.sub(query_point).length2()
+}
+
+/// Splits a subtree into its root, the subtree containing the query point
+/// and the other subtree. Also returns the query point's distance to the
+/// root's splitting plane.
+fn split<'a, P: PointN>(points: &'a [P], depth: usize, query_point: &P) -> Split<'a, P> {
+    let axis = depth % P::dimensions();
+    let middle = points.len() / 2;
+    let root = &points[middle];
+    let difference = query_point.nth(axis).clone() - root.nth(axis).clone();
+    let (lower, upper) = (&points[..middle], &points[middle + 1..]);
+    let (near, far) = if difference < zero() {
+        (lower, upper)
+    } else {
+        (upper, lower)
+    };
+    Split {
+        root,
+        near,
+        far,
+        plane_distance2: difference.clone() * difference,
+    }
+}
+
+struct Split<'a, P: PointN> {
+    root: &'a P,
+    near: &'a [P],
+    far: &'a [P],
+    plane_distance2: P::Scalar,
+}
+
+fn nearest_neighbor<'a, P: PointN>(
+    points: &'a [P],
+    depth: usize,
+    query_point: &P,
+    best: &mut Option<Candidate<'a, P>>,
+) {
+    if points.is_empty() {
+        return;
+    }
+    let split = split(points, depth, query_point);
+    let distance2 = distance2(split.root, query_point);
+    if best.as_ref().is_none_or(|best| distance2 < best.distance2) {
+        *best = Some(Candidate {
+            point: split.root,
+            distance2,
+        });
+    }
+    nearest_neighbor(split.near, depth + 1, query_point, best);
+    if best
+        .as_ref()
+        .is_none_or(|best| split.plane_distance2 < best.distance2)
+    {
+        nearest_neighbor(split.far, depth + 1, query_point, best);
+    }
+}
+
+fn nearest_n_neighbors<'a, P: PointN>(
+    points: &'a [P],
+    depth: usize,
+    query_point: &P,
+    n: usize,
+    heap: &mut BinaryHeap<Candidate<'a, P>>,
+) {
+    if points.is_empty() {
+        return;
+    }
+    let split = split(points, depth, query_point);
+    let distance2 = distance2(split.root, query_point);
+    if heap.len() < n {
+        heap.push(Candidate {
+            point: split.root,
+            distance2,
+        });
+    } else if heap.peek().is_some_and(|worst| distance2 < worst.distance2) {
+        heap.pop();
+        heap.push(Candidate {
+            point: split.root,
+            distance2,
+        });
+    }
+    nearest_n_neighbors(split.near, depth + 1, query_point, n, heap);
+    if heap.len() < n
+        || heap
+            .peek()
+            .is_some_and(|worst| split.plane_distance2 < worst.distance2)
+    {
+        nearest_n_neighbors(split.far, depth + 1, query_point, n, heap);
+    }
+}
+
+fn lookup_in_rectangle<'a, P: PointN>(
+    points: &'a [P],
+    depth: usize,
+    query_rect: &BoundingRect<P>,
+    result: &mut Vec<&'a P>,
+) {
+    if points.is_empty() {
+        return;
+    }
+    let axis = depth % P::dimensions();
+    let middle = points.len() / 2;
+    let root = &points[middle];
+    if query_rect.contains_point(root) {
+        result.push(root);
+    }
+    let split = root.nth(axis);
+    if query_rect.lower().nth(axis) <= split {
+        lookup_in_rectangle(&points[..middle], depth + 1, query_rect, result);
+    }
+    if query_rect.upper().nth(axis) >= split {
+        lookup_in_rectangle(&points[middle + 1..], depth + 1, query_rect, result);
+    }
+}
+
+fn lookup_in_circle<'a, P: PointN>(
+    points: &'a [P],
+    depth: usize,
+    origin: &P,
+    radius2: &P::Scalar,
+    result: &mut Vec<&'a P>,
+) {
+    if points.is_empty() {
+        return;
+    }
+    let split = split(points, depth, origin);
+    if distance2(split.root, origin) < *radius2 {
+        result.push(split.root);
+    }
+    lookup_in_circle(split.near, depth + 1, origin, radius2, result);
+    if split.plane_distance2 < *radius2 {
+        lookup_in_circle(split.far, depth + 1, origin, radius2, result);
+    }
+}
+
+impl<P: PointN> KdTree<P> {
+    /// Creates an empty kd-tree.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Creates a kd-tree containing the given points.
+    ///
+    /// Takes `O(n log n)` time. The points are reordered but not copied.
+    pub fn bulk_load(mut points: Vec<P>) -> Self {
+        build(&mut points, 0);
+        KdTree { points }
+    }
+
+    /// Creates a kd-tree containing a copy of the given points.
+    pub fn bulk_load_from_slice(points: &[P]) -> Self {
+        Self::bulk_load(points.to_vec())
+    }
+
+    /// Returns the number of points contained in this tree.
+    pub fn size(&self) -> usize {
+        self.points.len()
+    }
+
+    /// Returns all points in the tree's internal order.
+    pub fn points(&self) -> &[P] {
+        &self.points
+    }
+
+    /// Returns an iterator over all contained points.
+    pub fn iter(&self) -> ::core::slice::Iter<'_, P> {
+        self.points.iter()
+    }
+
+    /// Returns the points, leaving the tree empty.
+    pub fn into_points(self) -> Vec<P> {
+        self.points
+    }
+
+    /// Returns the nearest neighbor.
+    ///
+    /// Returns `None` if the tree is empty.
+    pub fn nearest_neighbor(&self, query_point: &P) -> Option<&P> {
+        let mut best = None;
+        nearest_neighbor(&self.points, 0, query_point, &mut best);
+        best.map(|best| best.point)
+    }
+
+    /// Returns the nearest n neighbors, sorted by their distance.
+    pub fn nearest_n_neighbors(&self, query_point: &P, n: usize) -> Vec<&P> {
+        if n == 0 {
+            return Vec::new();
+        }
+        let mut heap = BinaryHeap::with_capacity(n + 1);
+        nearest_n_neighbors(&self.points, 0, query_point, n, &mut heap);
+        heap.into_sorted_vec()
+            .into_iter()
+            .map(|candidate| candidate.point)
+            .collect()
+    }
+
+    /// Returns all points contained in a rectangle, including its boundary.
+    pub fn lookup_in_rectangle(&self, query_rect: &BoundingRect<P>) -> Vec<&P> {
+        let mut result = Vec::new();
+        lookup_in_rectangle(&self.points, 0, query_rect, &mut result);
+        result
+    }
+
+    /// Returns all points contained in a circle.
+    ///
+    /// Note that `radius2` is the circle's squared radius, not the actual radius.
+    pub fn lookup_in_circle(&self, circle_origin: &P, radius2: &P::Scalar) -> Vec<&P> {
+        let mut result = Vec::new();
+        lookup_in_circle(&self.points, 0, circle_origin, radius2, &mut result);
+        result
+    }
+
+    /// Returns a point at the given position, if the tree contains one.
+    pub fn lookup(&self, query_point: &P) -> Option<&P> {
+        self.nearest_neighbor(query_point)
+            .filter(|point| *point == query_point)
+    }
+
+    /// Returns `true` if the tree contains a point at the given position.
+    pub fn contains(&self, query_point: &P) -> bool {
+        self.lookup(query_point).is_some()
+    }
+}
+
+impl<P: PointN> FromIterator<P> for KdTree<P> {
+    fn from_iter<I: IntoIterator<Item = P>>(iter: I) -> Self {
+        KdTree::bulk_load(iter.into_iter().collect())
+    }
+}
+
+impl<'a, P: PointN> IntoIterator for &'a KdTree<P> {
+    type Item = &'a P;
+    type IntoIter = ::core::slice::Iter<'a, P>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.points.iter()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::KdTree;
+    use crate::boundingrect::BoundingRect;
+    use crate::point_traits::PointNExtensions;
+    use crate::rtree::RTree;
+    use crate::testutils::*;
+    use crate::Point2;
+    use cgmath::Point2 as CgPoint2;
+
+    const SEED: &[u8; 32] = b"\x1b\x6c\x92\x0e\x44\xd7\x31\x58\xa9\x03\xfe\x6d\x27\x80\xc4\x15\
+        \x5f\xe2\x0a\x93\x76\x1c\xbd\x48\x02\x39\xa7\xd1\x6e\xf4\x85\x2c";
+    const SEED2: &[u8; 32] = b"\x74\x0d\xc8\x59\x3e\xa1\x17\xf6\x82\x4b\xe9\x20\x9c\x65\x0f\xd3\
+        \xb8\x11\x46\xea\x7d\x93\x28\xc5\x5a\x0e\xf1\x37\x86\x6b\xd4\x09";
+
+    fn sorted(mut points: Vec<&CgPoint2<f64>>) -> Vec<&CgPoint2<f64>> {
+        points.sort_by(|l, r| (l.x, l.y).partial_cmp(&(r.x, r.y)).unwrap());
+        points
+    }
+
+    #[test]
+    fn test_empty_kdtree() {
+        let tree: KdTree<Point2<f64>> = KdTree::new();
+        assert_eq!(tree.size(), 0);
+        assert_eq!(tree.nearest_neighbor(&Point2::new(0.0, 0.0)), None);
+        assert!(tree
+            .nearest_n_neighbors(&Point2::new(0.0, 0.0), 3)
+            .is_empty());
+        assert!(tree
+            .lookup_in_circle(&Point2::new(0.0, 0.0), &1.0)
+            .is_empty());
+    }
+
+    #[test]
+    fn test_kdtree_queries_match_rtree() {
+        let points = random_points_with_seed::<f64>(1000, SEED);
+        let tree = KdTree::bulk_load_from_slice(&points);
+        let rtree = RTree::bulk_load(points.clone());
+        assert_eq!(tree.size(), 1000);
+        for point in &points {
+            assert!(tree.contains(point));
+        }
+        for query in random_points_with_seed::<f64>(100, SEED2) {
+            assert_eq!(
+                tree.nearest_neighbor(&query),
+                rtree.nearest_neighbor(&query)
+            );
+            let expected = rtree.nearest_n_neighbors(&query, 7);
+            let nearest = tree.nearest_n_neighbors(&query, 7);
+            assert_eq!(nearest, expected);
+
+            assert_eq!(
+                sorted(tree.lookup_in_circle(&query, &0.01)),
+                sorted(rtree.lookup_in_circle(&query, &0.01))
+            );
+
+            let rect = BoundingRect::from_corners(&query, &query.add(&CgPoint2::new(0.1, 0.05)));
+            assert_eq!(
+                sorted(tree.lookup_in_rectangle(&rect)),
+                sorted(rtree.lookup_in_rectangle(&rect))
+            );
+        }
+    }
+
+    #[test]
+    fn test_kdtree_with_duplicates_and_integers() {
+        let mut points = Vec::new();
+        for x in 0..10i64 {
+            for y in 0..10 {
+                points.push([x, y, x * y]);
+                points.push([x, y, x * y]);
+            }
+        }
+        let tree: KdTree<_> = points.into_iter().collect();
+        assert_eq!(tree.nearest_neighbor(&[3, 4, 12]), Some(&[3, 4, 12]));
+        assert_eq!(
+            tree.nearest_n_neighbors(&[3, 4, 12], 2),
+            vec![&[3, 4, 12]; 2]
+        );
+        assert!(!tree.contains(&[3, 4, 13]));
+        let rect = BoundingRect::from_corners(&[0, 0, 0], &[1, 1, 1]);
+        assert_eq!(tree.lookup_in_rectangle(&rect).len(), 8);
+        assert_eq!(tree.iter().count(), 200);
+    }
+}
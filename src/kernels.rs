@@ -13,9 +13,16 @@
 
 use crate::bigvec::{AdaptiveInt, BigVec2};
 use crate::exactpred::{incircle, orient2d};
+use crate::instrumentation::{record, Counter};
+#[cfg(feature = "rational_kernel")]
+use crate::point::Point2;
 use crate::point_traits::TwoDimensional;
 use crate::primitives::{EdgeSideInfo, SimpleEdge};
 use crate::traits::{SpadeFloat, SpadeNum};
+#[cfg(any(feature = "fixed", feature = "rational_kernel"))]
+use num::{one, zero};
+#[cfg(feature = "rational_kernel")]
+use num::{BigInt, BigRational, One, Signed, Zero};
 use num::{FromPrimitive, ToPrimitive};
 
 /// Determines how a delaunay triangulation performs its basic geometry computations.
@@ -28,7 +35,7 @@ use num::{FromPrimitive, ToPrimitive};
 /// Since each application has different needs, a `DelaunayKernel` will define how these geometric
 /// queries are calculated for a triangulation. It is recommended to use one of the predefined
 /// kernels that fits your needs.
-pub trait DelaunayKernel<D: SpadeNum>: ::std::marker::Sized + Clone {
+pub trait DelaunayKernel<D: SpadeNum>: ::core::marker::Sized + Clone {
     /// Returns true if pd is contained in the circumference of the triangle spanned by pa, pb, pc.
     ///
     /// pa, pb, pc have to be ordered clockwise, otherwise the result is inverted.
@@ -38,6 +45,7 @@ pub trait DelaunayKernel<D: SpadeNum>: ::std::marker::Sized + Clone {
         pc: &V,
         pd: &V,
     ) -> bool {
+        record(Counter::PredicateEvaluations, 1);
         let pa = [pa.nth(0), pa.nth(1)];
         let pb = [pb.nth(0), pb.nth(1)];
         let pc = [pc.nth(0), pc.nth(1)];
@@ -66,6 +74,7 @@ pub trait DelaunayKernel<D: SpadeNum>: ::std::marker::Sized + Clone {
         edge: &SimpleEdge<Ve>,
         position: &Ve,
     ) -> EdgeSideInfo<D> {
+        record(Counter::PredicateEvaluations, 1);
         let (a, b) = (&edge.from, &edge.to);
         let q = position;
         let signed_side = (b.nth(0).clone() - a.nth(0).clone())
@@ -192,6 +201,7 @@ where
         v3: &V,
         p: &V,
     ) -> bool {
+        record(Counter::PredicateEvaluations, 1);
         let v1 = to_f64_arr(v1);
         let v2 = to_f64_arr(v2);
         let v3 = to_f64_arr(v3);
@@ -204,6 +214,7 @@ where
         edge: &SimpleEdge<V>,
         position: &V,
     ) -> EdgeSideInfo<S> {
+        record(Counter::PredicateEvaluations, 1);
         let edge_from = to_f64_arr(&edge.from);
         let edge_to = to_f64_arr(&edge.to);
         let position = to_f64_arr(position);
@@ -212,6 +223,241 @@ where
     }
 }
 
+/// Exact delaunay kernel for fixed-point coordinates.
+///
+/// Works with the signed fixed-point types of the `fixed` crate with up to 64 bits,
+/// e.g. `I32F32`. The coordinates' raw bits are used as integers, their common scaling
+/// factor does not affect the result of orientation and in-circle tests. The calculations
+/// are performed like in `AdaptiveIntKernel` and are thus exact and overflow free,
+/// independent of the number of fractional bits.
+///
+/// Requires the `fixed` feature.
+#[cfg(feature = "fixed")]
+#[derive(Clone)]
+pub enum FixedPointKernel {}
+
+#[cfg(feature = "fixed")]
+fn to_raw_bigvec<V>(v: &V) -> BigVec2<AdaptiveInt>
+where
+    V: TwoDimensional,
+    V::Scalar: fixed::traits::FixedSigned,
+    <V::Scalar as fixed::traits::Fixed>::Bits: Into<i64>,
+{
+    use fixed::traits::Fixed;
+    BigVec2::new(
+        AdaptiveInt::from_i64(v.nth(0).to_bits().into()),
+        AdaptiveInt::from_i64(v.nth(1).to_bits().into()),
+    )
+}
+
+#[cfg(feature = "fixed")]
+impl<D> DelaunayKernel<D> for FixedPointKernel
+where
+    D: SpadeNum + fixed::traits::FixedSigned,
+    D::Bits: Into<i64>,
+{
+    fn contained_in_circumference<V: TwoDimensional<Scalar = D>>(
+        pa: &V,
+        pb: &V,
+        pc: &V,
+        pd: &V,
+    ) -> bool {
+        TrivialKernel::contained_in_circumference(
+            &to_raw_bigvec(pa),
+            &to_raw_bigvec(pb),
+            &to_raw_bigvec(pc),
+            &to_raw_bigvec(pd),
+        )
+    }
+
+    fn side_query<V: TwoDimensional<Scalar = D>>(
+        edge: &SimpleEdge<V>,
+        position: &V,
+    ) -> EdgeSideInfo<D> {
+        let edge = SimpleEdge::new(to_raw_bigvec(&edge.from), to_raw_bigvec(&edge.to));
+        let det = TrivialKernel::side_query(&edge, &to_raw_bigvec(position));
+        let sign = match det.signed_side.sign() {
+            1 => one(),
+            -1 => -one::<D>(),
+            _ => zero(),
+        };
+        EdgeSideInfo::from_determinant(sign)
+    }
+}
+
+/// Scalars that can be converted into a `BigRational` without loss of precision.
+///
+/// Requires the `rational_kernel` feature.
+#[cfg(feature = "rational_kernel")]
+pub trait ToBigRational {
+    /// Converts this value into a `BigRational`.
+    ///
+    /// # Panics
+    /// Panics if the value is not finite.
+    fn to_big_rational(&self) -> BigRational;
+}
+
+#[cfg(feature = "rational_kernel")]
+impl ToBigRational for f32 {
+    fn to_big_rational(&self) -> BigRational {
+        BigRational::from_float(*self).expect("Coordinates must be finite")
+    }
+}
+
+#[cfg(feature = "rational_kernel")]
+impl ToBigRational for f64 {
+    fn to_big_rational(&self) -> BigRational {
+        BigRational::from_float(*self).expect("Coordinates must be finite")
+    }
+}
+
+#[cfg(feature = "rational_kernel")]
+impl ToBigRational for i32 {
+    fn to_big_rational(&self) -> BigRational {
+        BigRational::from_integer(BigInt::from(*self))
+    }
+}
+
+#[cfg(feature = "rational_kernel")]
+impl ToBigRational for i64 {
+    fn to_big_rational(&self) -> BigRational {
+        BigRational::from_integer(BigInt::from(*self))
+    }
+}
+
+#[cfg(feature = "rational_kernel")]
+impl ToBigRational for BigInt {
+    fn to_big_rational(&self) -> BigRational {
+        BigRational::from_integer(self.clone())
+    }
+}
+
+#[cfg(feature = "rational_kernel")]
+impl ToBigRational for BigRational {
+    fn to_big_rational(&self) -> BigRational {
+        self.clone()
+    }
+}
+
+/// Exact delaunay kernel backed by arbitrary precision rationals.
+///
+/// All coordinates are converted into `BigRational`s before any calculation, every
+/// predicate is thus evaluated exactly, independent of the coordinate type. This kernel
+/// is by far the slowest kernel, use it as ground truth to verify the results of the
+/// other kernels.
+///
+/// Besides predicates, this kernel offers exact constructions like `circumcenter` and
+/// `edge_intersection`. Their results are given as `BigRational` coordinates.
+///
+/// Requires the `rational_kernel` feature.
+#[cfg(feature = "rational_kernel")]
+#[derive(Clone)]
+pub enum RationalKernel {}
+
+#[cfg(feature = "rational_kernel")]
+fn to_rational_bigvec<V>(v: &V) -> BigVec2<BigRational>
+where
+    V: TwoDimensional,
+    V::Scalar: ToBigRational,
+{
+    BigVec2::new(v.nth(0).to_big_rational(), v.nth(1).to_big_rational())
+}
+
+#[cfg(feature = "rational_kernel")]
+fn cross(a: &BigVec2<BigRational>, b: &BigVec2<BigRational>) -> BigRational {
+    &a.x * &b.y - &a.y * &b.x
+}
+
+#[cfg(feature = "rational_kernel")]
+impl RationalKernel {
+    /// Returns the exact circumcenter of a triangle.
+    ///
+    /// Returns `None` if the triangle's vertices are collinear.
+    pub fn circumcenter<V>(v0: &V, v1: &V, v2: &V) -> Option<Point2<BigRational>>
+    where
+        V: TwoDimensional,
+        V::Scalar: ToBigRational,
+    {
+        let a = to_rational_bigvec(v0);
+        let b = to_rational_bigvec(v1) - a.clone();
+        let c = to_rational_bigvec(v2) - a.clone();
+        let d = cross(&b, &c) * BigRational::from_integer(BigInt::from(2));
+        if d.is_zero() {
+            return None;
+        }
+        let len_b = &b.x * &b.x + &b.y * &b.y;
+        let len_c = &c.x * &c.x + &c.y * &c.y;
+        let x = (&len_b * &c.y - &len_c * &b.y) / &d;
+        let y = (&len_c * &b.x - &len_b * &c.x) / &d;
+        Some(Point2::new(x + a.x, y + a.y))
+    }
+
+    /// Returns the exact intersection point of two edges.
+    ///
+    /// Returns `None` if the edges do not intersect or if they are parallel.
+    pub fn edge_intersection<V>(
+        edge0: &SimpleEdge<V>,
+        edge1: &SimpleEdge<V>,
+    ) -> Option<Point2<BigRational>>
+    where
+        V: TwoDimensional,
+        V::Scalar: ToBigRational,
+    {
+        let p = to_rational_bigvec(&edge0.from);
+        let r = to_rational_bigvec(&edge0.to) - p.clone();
+        let q = to_rational_bigvec(&edge1.from);
+        let s = to_rational_bigvec(&edge1.to) - q.clone();
+        let denominator = cross(&r, &s);
+        if denominator.is_zero() {
+            return None;
+        }
+        let qp = q - p.clone();
+        let t = cross(&qp, &s) / &denominator;
+        let u = cross(&qp, &r) / &denominator;
+        let unit = BigRational::zero()..=BigRational::one();
+        if !unit.contains(&t) || !unit.contains(&u) {
+            return None;
+        }
+        Some(Point2::new(p.x + &r.x * &t, p.y + &r.y * &t))
+    }
+}
+
+#[cfg(feature = "rational_kernel")]
+impl<D> DelaunayKernel<D> for RationalKernel
+where
+    D: SpadeNum + ToBigRational,
+{
+    fn contained_in_circumference<V: TwoDimensional<Scalar = D>>(
+        pa: &V,
+        pb: &V,
+        pc: &V,
+        pd: &V,
+    ) -> bool {
+        TrivialKernel::contained_in_circumference(
+            &to_rational_bigvec(pa),
+            &to_rational_bigvec(pb),
+            &to_rational_bigvec(pc),
+            &to_rational_bigvec(pd),
+        )
+    }
+
+    fn side_query<V: TwoDimensional<Scalar = D>>(
+        edge: &SimpleEdge<V>,
+        position: &V,
+    ) -> EdgeSideInfo<D> {
+        let edge = SimpleEdge::new(to_rational_bigvec(&edge.from), to_rational_bigvec(&edge.to));
+        let det = TrivialKernel::side_query(&edge, &to_rational_bigvec(position));
+        let sign = if det.signed_side.is_positive() {
+            one()
+        } else if det.signed_side.is_negative() {
+            -one::<D>()
+        } else {
+            zero()
+        };
+        EdgeSideInfo::from_determinant(sign)
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::{DelaunayKernel, FloatKernel, TrivialKernel};
@@ -259,4 +505,99 @@ mod test {
             &na::Point2::new(0f32, 1f32),
         );
     }
+
+    #[cfg(feature = "fixed")]
+    #[test]
+    fn test_fixed_point_kernel() {
+        use super::FixedPointKernel;
+        use crate::delaunay::DelaunayTriangulation;
+        use crate::point::Point2;
+        use fixed::types::I32F32;
+
+        let p = |x: f64, y: f64| Point2::new(I32F32::from_num(x), I32F32::from_num(y));
+        // Raw products of these coordinates overflow 64 bit integers
+        let (a, b, c) = (p(-1e9, -1e9), p(1e9, -1e9), p(1e9, 1e9));
+        assert!(FixedPointKernel::is_ordered_ccw(&a, &b, &c));
+        assert!(!FixedPointKernel::is_ordered_ccw(&c, &b, &a));
+        let on_line = p(0.25, 0.25);
+        assert!(FixedPointKernel::side_query(&super::SimpleEdge::new(a, c), &on_line).is_on_line());
+        // Co-circular points are not contained in each other's circumference
+        let d = p(-1e9, 1e9);
+        assert!(!FixedPointKernel::contained_in_circumference(
+            &c, &b, &a, &d
+        ));
+        let inside = p(-0.5e9, 0.75e9);
+        assert!(FixedPointKernel::contained_in_circumference(
+            &c, &b, &a, &inside
+        ));
+
+        let mut delaunay = DelaunayTriangulation::<_, FixedPointKernel, _>::with_walk_locate();
+        for i in 0..10 {
+            for j in 0..10 {
+                delaunay.insert(p(i as f64 * 0.125, j as f64 * 0.125));
+            }
+        }
+        assert_eq!(delaunay.num_triangles(), 162);
+    }
+
+    #[cfg(feature = "rational_kernel")]
+    #[test]
+    fn test_rational_kernel() {
+        use super::{RationalKernel, SimpleEdge};
+        use crate::delaunay::DelaunayTriangulation;
+        use crate::point::Point2;
+        use num::{BigInt, BigRational};
+
+        let rational = |n: i64, d: i64| BigRational::new(BigInt::from(n), BigInt::from(d));
+        // 0.1 + 0.2 != 0.3 in floating point, the kernel sees the exact float values
+        let (a, b) = (Point2::new(0.0, 0.0), Point2::new(0.1, 0.3));
+        let c = Point2::new(0.1 + 0.2, 0.9);
+        assert_eq!(
+            RationalKernel::side_query(&SimpleEdge::new(a, b), &c).is_on_line(),
+            FloatKernel::side_query(&SimpleEdge::new(a, b), &c).is_on_line()
+        );
+        let (v0, v1, v2) = (Point2::new(0, 0), Point2::new(3, 0), Point2::new(0, 1));
+        assert!(RationalKernel::is_ordered_ccw(&v0, &v1, &v2));
+        assert_eq!(
+            RationalKernel::circumcenter(&v0, &v1, &v2),
+            Some(Point2::new(rational(3, 2), rational(1, 2)))
+        );
+        assert!(RationalKernel::contained_in_circumference(
+            &v2,
+            &v1,
+            &v0,
+            &Point2::new(1, 0)
+        ));
+        assert!(!RationalKernel::contained_in_circumference(
+            &v2,
+            &v1,
+            &v0,
+            &Point2::new(3, 1)
+        ));
+        assert_eq!(
+            RationalKernel::circumcenter(&v0, &v1, &Point2::new(6, 0)),
+            None
+        );
+
+        let e0 = SimpleEdge::new(Point2::new(0, 0), Point2::new(3, 1));
+        let e1 = SimpleEdge::new(Point2::new(0, 1), Point2::new(1, 0));
+        assert_eq!(
+            RationalKernel::edge_intersection(&e0, &e1),
+            Some(Point2::new(rational(3, 4), rational(1, 4)))
+        );
+        let e2 = SimpleEdge::new(Point2::new(2, 0), Point2::new(3, -1));
+        assert_eq!(RationalKernel::edge_intersection(&e1, &e2), None);
+
+        let mut exact = DelaunayTriangulation::<_, RationalKernel, _>::with_walk_locate();
+        let mut fast = DelaunayTriangulation::<_, FloatKernel, _>::with_walk_locate();
+        for i in 0..8 {
+            for j in 0..8 {
+                let point = Point2::new(i as f64 * 0.1, j as f64 * 0.3);
+                exact.insert(point);
+                fast.insert(point);
+            }
+        }
+        assert_eq!(exact.num_triangles(), fast.num_triangles());
+        assert_eq!(exact.num_edges(), fast.num_edges());
+    }
 }
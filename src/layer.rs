@@ -0,0 +1,638 @@
+// Copyright 2017 The Spade Developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Loading point and line layers from GIS files.
+//!
+//! A `Layer` reads the geometries of an ESRI shapefile (`shapefile` feature)
+//! or of a GeoPackage table (`geopackage` feature). Its points can be bulk
+//! loaded into an r-tree, a constrained Delaunay triangulation inserts its
+//! points as vertices and its lines and polygon rings as constraint edges.
+//! Attributes and coordinate reference systems are not read, Z and M values
+//! are ignored.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use spade::delaunay::FloatCDT;
+//! use spade::layer::Layer;
+//! use spade::Point2;
+//!
+//! # fn main() -> Result<(), spade::layer::LayerError> {
+//! let mut layer = Layer::from_shapefile("rivers.shp")?;
+//! // Only keep the part of the layer that lies within the area of interest
+//! layer.retain_coordinates(|&[x, y]| x >= 0.0 && y >= 0.0);
+//! let mut cdt = FloatCDT::<Point2<f64>, _>::with_walk_locate();
+//! cdt.add_layer(&layer);
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::delaunay::{ConstrainedDelaunayTriangulation, DelaunayLocateStructure};
+use crate::kernels::DelaunayKernel;
+use crate::point_traits::{PointN, TwoDimensional};
+use crate::rtree::RTree;
+use crate::traits::{HasPosition2D, SpatialObject};
+use std::convert::TryInto;
+use std::fmt;
+use std::io;
+#[cfg(feature = "shapefile")]
+use std::io::Read;
+use std::path::Path;
+
+/// Error returned when a layer cannot be read.
+#[derive(Debug)]
+pub enum LayerError {
+    /// The file could not be read.
+    Io(io::Error),
+    /// The file is corrupt or uses an unsupported geometry type.
+    InvalidData(String),
+    /// The GeoPackage database could not be queried.
+    #[cfg(feature = "geopackage")]
+    Sqlite(rusqlite::Error),
+}
+
+impl fmt::Display for LayerError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LayerError::Io(error) => write!(f, "could not read layer: {}", error),
+            LayerError::InvalidData(reason) => write!(f, "invalid layer data: {}", reason),
+            #[cfg(feature = "geopackage")]
+            LayerError::Sqlite(error) => write!(f, "could not query geopackage: {}", error),
+        }
+    }
+}
+
+impl ::std::error::Error for LayerError {}
+
+impl From<io::Error> for LayerError {
+    fn from(error: io::Error) -> Self {
+        LayerError::Io(error)
+    }
+}
+
+#[cfg(feature = "geopackage")]
+impl From<rusqlite::Error> for LayerError {
+    fn from(error: rusqlite::Error) -> Self {
+        LayerError::Sqlite(error)
+    }
+}
+
+/// The maximum nesting depth of well-known binary geometry collections.
+#[cfg(feature = "geopackage")]
+const MAX_WKB_DEPTH: usize = 32;
+
+fn invalid<T>(reason: &str) -> Result<T, LayerError> {
+    Err(LayerError::InvalidData(reason.to_string()))
+}
+
+/// The points and lines of a GIS layer.
+///
+/// Multi geometries are split into their parts. Polygon rings are stored as
+/// closed lines whose last coordinate repeats the first one.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Layer {
+    points: Vec<[f64; 2]>,
+    lines: Vec<Vec<[f64; 2]>>,
+}
+
+impl Layer {
+    /// Creates an empty layer.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Returns all point geometries.
+    pub fn points(&self) -> &[[f64; 2]] {
+        &self.points
+    }
+
+    /// Returns all line strings and polygon rings.
+    pub fn lines(&self) -> &[Vec<[f64; 2]>] {
+        &self.lines
+    }
+
+    /// Adds a point.
+    pub fn add_point(&mut self, point: [f64; 2]) {
+        self.points.push(point);
+    }
+
+    /// Adds a line. Lines with less than two coordinates are ignored.
+    pub fn add_line(&mut self, line: Vec<[f64; 2]>) {
+        if line.len() >= 2 {
+            self.lines.push(line);
+        }
+    }
+
+    /// Removes all coordinates for which `filter` returns `false`.
+    ///
+    /// Lines are split where they leave the retained area, only segments
+    /// whose end points are both retained are kept.
+    pub fn retain_coordinates<F>(&mut self, mut filter: F)
+    where
+        F: FnMut(&[f64; 2]) -> bool,
+    {
+        self.points.retain(|point| filter(point));
+        let lines = ::std::mem::take(&mut self.lines);
+        for line in lines {
+            let mut part = Vec::new();
+            for coordinate in line {
+                if filter(&coordinate) {
+                    part.push(coordinate);
+                } else {
+                    self.add_line(::std::mem::take(&mut part));
+                }
+            }
+            self.add_line(part);
+        }
+    }
+
+    /// Converts the layer's points and loads them into an r-tree.
+    pub fn to_rtree<T>(&self) -> RTree<T>
+    where
+        T: SpatialObject + Clone + From<[f64; 2]>,
+        T::Point: TwoDimensional,
+    {
+        RTree::bulk_load(self.points.iter().map(|point| T::from(*point)).collect())
+    }
+
+    /// Reads all geometries of a shapefile.
+    ///
+    /// Only the geometry file (`.shp`) is read.
+    /// Requires the `shapefile` feature.
+    #[cfg(feature = "shapefile")]
+    pub fn from_shapefile<P: AsRef<Path>>(path: P) -> Result<Self, LayerError> {
+        Self::read_shapefile(::std::fs::File::open(path)?)
+    }
+
+    /// Reads all geometries of a shapefile's geometry file from a reader.
+    ///
+    /// Requires the `shapefile` feature.
+    #[cfg(feature = "shapefile")]
+    pub fn read_shapefile<R: Read>(mut reader: R) -> Result<Self, LayerError> {
+        let mut data = Vec::new();
+        reader.read_to_end(&mut data)?;
+        let mut result = Layer::new();
+        let header = Bytes::new(&data);
+        if data.len() < 100 || header.u32_be(0)? != 9994 {
+            return invalid("not a shapefile");
+        }
+        let mut offset = 100;
+        while offset + 8 <= data.len() {
+            let length = header.u32_be(offset + 4)? as usize * 2;
+            let end = (offset + 8)
+                .checked_add(length)
+                .filter(|&end| end <= data.len())
+                .ok_or_else(|| LayerError::InvalidData("truncated record".to_string()))?;
+            result.add_shape(Bytes::new(&data[offset + 8..end]))?;
+            offset = end;
+        }
+        Ok(result)
+    }
+
+    #[cfg(feature = "shapefile")]
+    fn add_shape(&mut self, shape: Bytes) -> Result<(), LayerError> {
+        let points = |count_offset: usize, parts: usize| -> Result<Vec<[f64; 2]>, LayerError> {
+            let count = shape.u32_le(count_offset)? as usize;
+            let start = count_offset + 4 + parts * 4;
+            (0..count)
+                .map(|index| shape.point_le(start + index * 16))
+                .collect()
+        };
+        match shape.u32_le(0)? {
+            // Null shape
+            0 => (),
+            // Point, PointZ, PointM
+            1 | 11 | 21 => self.add_point(shape.point_le(4)?),
+            // MultiPoint, MultiPointZ, MultiPointM
+            8 | 18 | 28 => self.points.extend(points(36, 0)?),
+            // PolyLine and Polygon with Z and M variants
+            3 | 5 | 13 | 15 | 23 | 25 => {
+                let num_parts = shape.u32_le(36)? as usize;
+                let points = points(40, num_parts)?;
+                for part in 0..num_parts {
+                    let start = shape.u32_le(44 + part * 4)? as usize;
+                    let end = if part + 1 < num_parts {
+                        shape.u32_le(48 + part * 4)? as usize
+                    } else {
+                        points.len()
+                    };
+                    match points.get(start..end) {
+                        Some(line) => self.add_line(line.to_vec()),
+                        None => return invalid("invalid part index"),
+                    }
+                }
+            }
+            shape_type => return invalid(&format!("unsupported shape type {}", shape_type)),
+        }
+        Ok(())
+    }
+
+    /// Reads all geometries of a GeoPackage table.
+    ///
+    /// The table's geometry column is looked up in `gpkg_geometry_columns`.
+    /// Geometry collections are split into their parts.
+    /// Requires the `geopackage` feature.
+    #[cfg(feature = "geopackage")]
+    pub fn from_geopackage<P: AsRef<Path>>(path: P, table: &str) -> Result<Self, LayerError> {
+        let flags = rusqlite::OpenFlags::SQLITE_OPEN_READ_ONLY;
+        let connection = rusqlite::Connection::open_with_flags(path, flags)?;
+        let column: String = connection.query_row(
+            "SELECT column_name FROM gpkg_geometry_columns WHERE table_name = ?1",
+            [table],
+            |row| row.get(0),
+        )?;
+        let query = format!(
+            "SELECT \"{}\" FROM \"{}\"",
+            column.replace('"', "\"\""),
+            table.replace('"', "\"\"")
+        );
+        let mut statement = connection.prepare(&query)?;
+        let mut rows = statement.query([])?;
+        let mut result = Layer::new();
+        while let Some(row) = rows.next()? {
+            if let Some(blob) = row.get::<_, Option<Vec<u8>>>(0)? {
+                result.add_geopackage_blob(&blob)?;
+            }
+        }
+        Ok(result)
+    }
+
+    #[cfg(feature = "geopackage")]
+    fn add_geopackage_blob(&mut self, blob: &[u8]) -> Result<(), LayerError> {
+        if blob.len() < 8 || &blob[0..2] != b"GP" {
+            return invalid("missing geopackage geometry header");
+        }
+        let flags = blob[3];
+        let envelope_length = match (flags >> 1) & 0x07 {
+            0 => 0,
+            1 => 32,
+            2 | 3 => 48,
+            4 => 64,
+            _ => return invalid("invalid envelope type"),
+        };
+        let wkb = blob
+            .get(8 + envelope_length..)
+            .ok_or_else(|| LayerError::InvalidData("truncated geometry".to_string()))?;
+        self.add_wkb(Bytes::new(wkb), 0)?;
+        Ok(())
+    }
+
+    /// Adds a well-known binary geometry and returns its length in bytes.
+    ///
+    /// `depth` is the number of geometry collections containing the geometry.
+    #[cfg(feature = "geopackage")]
+    fn add_wkb(&mut self, wkb: Bytes, depth: usize) -> Result<usize, LayerError> {
+        let little_endian = match wkb.data.first() {
+            Some(0) => false,
+            Some(1) => true,
+            _ => return invalid("invalid byte order"),
+        };
+        let u32_at = |offset| {
+            if little_endian {
+                wkb.u32_le(offset)
+            } else {
+                wkb.u32_be(offset)
+            }
+        };
+        let mut geometry_type = u32_at(1)?;
+        let mut offset = 5;
+        // Extended WKB flags for Z, M and an embedded SRID
+        let mut dimensions = 2;
+        dimensions += (geometry_type >> 31) as usize + ((geometry_type >> 30) & 1) as usize;
+        if geometry_type & 0x2000_0000 != 0 {
+            offset += 4;
+        }
+        geometry_type &= 0x0fff_ffff;
+        // ISO WKB encodes Z and M by adding multiples of 1000
+        dimensions += match geometry_type / 1000 {
+            1 | 2 => 1,
+            3 => 2,
+            _ => 0,
+        };
+        let stride = dimensions * 8;
+        let point_at = |offset| -> Result<[f64; 2], LayerError> {
+            let x = u64_bits(&wkb, offset, little_endian)?;
+            let y = u64_bits(&wkb, offset + 8, little_endian)?;
+            Ok([f64::from_bits(x), f64::from_bits(y)])
+        };
+        let read_line = |offset: &mut usize| -> Result<Vec<[f64; 2]>, LayerError> {
+            let count = u32_at(*offset)? as usize;
+            let line = (0..count)
+                .map(|index| point_at(*offset + 4 + index * stride))
+                .collect();
+            *offset += 4 + count * stride;
+            line
+        };
+        match geometry_type % 1000 {
+            1 => {
+                let point = point_at(offset)?;
+                // Empty points are encoded as NaN coordinates
+                if !point[0].is_nan() {
+                    self.add_point(point);
+                }
+                offset += stride;
+            }
+            2 => {
+                let line = read_line(&mut offset)?;
+                self.add_line(line);
+            }
+            3 => {
+                let num_rings = u32_at(offset)?;
+                offset += 4;
+                for _ in 0..num_rings {
+                    let ring = read_line(&mut offset)?;
+                    self.add_line(ring);
+                }
+            }
+            4..=7 => {
+                if depth == MAX_WKB_DEPTH {
+                    return invalid("geometry collections are nested too deeply");
+                }
+                let num_geometries = u32_at(offset)?;
+                offset += 4;
+                for _ in 0..num_geometries {
+                    let rest = wkb
+                        .data
+                        .get(offset..)
+                        .ok_or_else(|| LayerError::InvalidData("truncated geometry".to_string()))?;
+                    offset += self.add_wkb(Bytes::new(rest), depth + 1)?;
+                }
+            }
+            geometry_type => {
+                return invalid(&format!("unsupported geometry type {}", geometry_type));
+            }
+        }
+        Ok(offset)
+    }
+}
+
+/// Bounds checked access to binary data.
+#[derive(Clone, Copy)]
+struct Bytes<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Bytes<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Bytes { data }
+    }
+
+    fn get<const N: usize>(&self, offset: usize) -> Result<[u8; N], LayerError> {
+        self.data
+            .get(offset..offset + N)
+            .map(|bytes| bytes.try_into().unwrap())
+            .ok_or_else(|| LayerError::InvalidData("unexpected end of data".to_string()))
+    }
+
+    fn u32_be(&self, offset: usize) -> Result<u32, LayerError> {
+        self.get(offset).map(u32::from_be_bytes)
+    }
+
+    fn u32_le(&self, offset: usize) -> Result<u32, LayerError> {
+        self.get(offset).map(u32::from_le_bytes)
+    }
+
+    #[cfg_attr(not(feature = "shapefile"), allow(dead_code))]
+    fn point_le(&self, offset: usize) -> Result<[f64; 2], LayerError> {
+        Ok([
+            f64::from_le_bytes(self.get(offset)?),
+            f64::from_le_bytes(self.get(offset + 8)?),
+        ])
+    }
+}
+
+#[cfg(feature = "geopackage")]
+fn u64_bits(bytes: &Bytes, offset: usize, little_endian: bool) -> Result<u64, LayerError> {
+    let raw = bytes.get(offset)?;
+    Ok(if little_endian {
+        u64::from_le_bytes(raw)
+    } else {
+        u64::from_be_bytes(raw)
+    })
+}
+
+impl<V, K, L> ConstrainedDelaunayTriangulation<V, K, L>
+where
+    V: HasPosition2D + From<[f64; 2]>,
+    V::Point: TwoDimensional,
+    K: DelaunayKernel<<V::Point as PointN>::Scalar>,
+    L: DelaunayLocateStructure<V::Point>,
+{
+    /// Inserts a layer's points as vertices and its lines as constraint edges.
+    ///
+    /// Real world data often contains crossing lines. Segments that would
+    /// intersect an existing constraint edge are skipped, their end points
+    /// are inserted nonetheless. Returns the number of skipped segments.
+    pub fn add_layer(&mut self, layer: &Layer) -> usize {
+        for point in layer.points() {
+            self.insert(V::from(*point));
+        }
+        let mut skipped = 0;
+        for line in layer.lines() {
+            let handles: Vec<_> = line.iter().map(|p| self.insert(V::from(*p))).collect();
+            for pair in handles.windows(2) {
+                if pair[0] == pair[1] {
+                    continue;
+                }
+                if self.can_add_constraint(pair[0], pair[1]) {
+                    self.add_constraint(pair[0], pair[1]);
+                } else {
+                    skipped += 1;
+                }
+            }
+        }
+        skipped
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Layer;
+    use crate::delaunay::FloatCDT;
+    use crate::point::Point2;
+
+    #[test]
+    fn test_retain_coordinates() {
+        let mut layer = Layer::new();
+        layer.add_point([0.0, 0.0]);
+        layer.add_point([5.0, 0.0]);
+        layer.add_line(vec![
+            [0.0, 0.0],
+            [1.0, 0.0],
+            [9.0, 0.0],
+            [2.0, 0.0],
+            [3.0, 0.0],
+        ]);
+        layer.add_line(vec![[0.0, 1.0], [8.0, 1.0], [1.0, 1.0]]);
+        layer.retain_coordinates(|&[x, _]| x < 4.0);
+        assert_eq!(layer.points(), &[[0.0, 0.0]]);
+        assert_eq!(
+            layer.lines(),
+            &[vec![[0.0, 0.0], [1.0, 0.0]], vec![[2.0, 0.0], [3.0, 0.0]]]
+        );
+        let tree = layer.to_rtree::<Point2<f64>>();
+        assert_eq!(tree.size(), 1);
+    }
+
+    #[test]
+    fn test_add_layer() {
+        let mut layer = Layer::new();
+        layer.add_point([1.0, 3.0]);
+        layer.add_line(vec![[0.0, 0.0], [2.0, 2.0]]);
+        layer.add_line(vec![[0.0, 2.0], [2.0, 0.0], [2.0, 0.0], [3.0, 0.0]]);
+        let mut cdt = FloatCDT::<Point2<f64>, _>::with_walk_locate();
+        assert_eq!(cdt.add_layer(&layer), 1);
+        assert_eq!(cdt.num_vertices(), 6);
+        assert_eq!(cdt.num_constraints(), 2);
+    }
+
+    #[cfg(feature = "shapefile")]
+    mod shapefile {
+        use super::super::Layer;
+
+        fn record(content: Vec<u8>) -> Vec<u8> {
+            let mut result = Vec::new();
+            result.extend_from_slice(&1u32.to_be_bytes());
+            result.extend_from_slice(&(content.len() as u32 / 2).to_be_bytes());
+            result.extend(content);
+            result
+        }
+
+        fn shape(shape_type: u32, parts: &[u32], points: &[[f64; 2]]) -> Vec<u8> {
+            let mut result = shape_type.to_le_bytes().to_vec();
+            result.extend_from_slice(&[0; 32]);
+            if !parts.is_empty() {
+                result.extend_from_slice(&(parts.len() as u32).to_le_bytes());
+            }
+            result.extend_from_slice(&(points.len() as u32).to_le_bytes());
+            for part in parts {
+                result.extend_from_slice(&part.to_le_bytes());
+            }
+            for [x, y] in points {
+                result.extend_from_slice(&x.to_le_bytes());
+                result.extend_from_slice(&y.to_le_bytes());
+            }
+            result
+        }
+
+        #[test]
+        fn test_read_shapefile() {
+            let mut file = vec![0; 100];
+            file[0..4].copy_from_slice(&9994u32.to_be_bytes());
+            let mut point = 1u32.to_le_bytes().to_vec();
+            point.extend_from_slice(&1.5f64.to_le_bytes());
+            point.extend_from_slice(&(-2.0f64).to_le_bytes());
+            file.extend(record(point));
+            file.extend(record(0u32.to_le_bytes().to_vec()));
+            file.extend(record(shape(8, &[], &[[0.0, 0.0], [1.0, 1.0]])));
+            let points = [[0.0, 0.0], [1.0, 0.0], [2.0, 2.0], [3.0, 3.0], [4.0, 4.0]];
+            file.extend(record(shape(3, &[0, 3], &points)));
+            let layer = Layer::read_shapefile(&file[..]).unwrap();
+            assert_eq!(layer.points(), &[[1.5, -2.0], [0.0, 0.0], [1.0, 1.0]]);
+            assert_eq!(
+                layer.lines(),
+                &[points[0..3].to_vec(), points[3..].to_vec()]
+            );
+
+            assert!(Layer::read_shapefile(&file[0..50]).is_err());
+            assert!(Layer::read_shapefile(&file[..file.len() - 1]).is_err());
+            let mut huge = file[..100].to_vec();
+            huge.extend_from_slice(&1u32.to_be_bytes());
+            huge.extend_from_slice(&u32::MAX.to_be_bytes());
+            assert!(Layer::read_shapefile(&huge[..]).is_err());
+            assert!(Layer::from_shapefile("does/not/exist.shp").is_err());
+        }
+    }
+
+    #[cfg(feature = "geopackage")]
+    mod geopackage {
+        use super::super::Layer;
+
+        fn blob(wkb: &[u8]) -> Vec<u8> {
+            // Header with a little endian xy envelope
+            let mut result = vec![b'G', b'P', 0, 0b0000_0011, 0, 0, 0, 0];
+            result.extend_from_slice(&[0; 32]);
+            result.extend_from_slice(wkb);
+            result
+        }
+
+        fn line_wkb(geometry_type: u32, points: &[[f64; 2]]) -> Vec<u8> {
+            let mut result = vec![1];
+            result.extend_from_slice(&geometry_type.to_le_bytes());
+            result.extend_from_slice(&(points.len() as u32).to_le_bytes());
+            for [x, y] in points {
+                result.extend_from_slice(&x.to_le_bytes());
+                result.extend_from_slice(&y.to_le_bytes());
+            }
+            result
+        }
+
+        #[test]
+        fn test_read_geopackage() {
+            let path = ::std::env::temp_dir().join(format!("spade-{}.gpkg", ::std::process::id()));
+            let _ = ::std::fs::remove_file(&path);
+            let connection = rusqlite::Connection::open(&path).unwrap();
+            connection
+                .execute_batch(
+                    "CREATE TABLE gpkg_geometry_columns (table_name TEXT, column_name TEXT);
+                     INSERT INTO gpkg_geometry_columns VALUES ('roads', 'geom');
+                     CREATE TABLE roads (id INTEGER PRIMARY KEY, geom BLOB);",
+                )
+                .unwrap();
+            let insert = |wkb: Vec<u8>| {
+                connection
+                    .execute("INSERT INTO roads (geom) VALUES (?1)", [blob(&wkb)])
+                    .unwrap();
+            };
+            // A big endian point
+            let mut point = vec![0, 0, 0, 0, 1];
+            point.extend_from_slice(&2.0f64.to_be_bytes());
+            point.extend_from_slice(&3.0f64.to_be_bytes());
+            insert(point);
+            insert(line_wkb(2, &[[0.0, 0.0], [1.0, 1.0]]));
+            // A multi line string with a single part
+            let mut multi = vec![1];
+            multi.extend_from_slice(&5u32.to_le_bytes());
+            multi.extend_from_slice(&1u32.to_le_bytes());
+            multi.extend(line_wkb(2, &[[4.0, 4.0], [5.0, 4.0]]));
+            insert(multi);
+            connection
+                .execute("INSERT INTO roads (geom) VALUES (NULL)", [])
+                .unwrap();
+            drop(connection);
+
+            let layer = Layer::from_geopackage(&path, "roads").unwrap();
+            assert_eq!(layer.points(), &[[2.0, 3.0]]);
+            assert_eq!(
+                layer.lines(),
+                &[vec![[0.0, 0.0], [1.0, 1.0]], vec![[4.0, 4.0], [5.0, 4.0]]]
+            );
+            assert!(Layer::from_geopackage(&path, "rivers").is_err());
+            ::std::fs::remove_file(&path).unwrap();
+        }
+
+        #[test]
+        fn test_nested_collections() {
+            let nested = |depth: usize| {
+                let mut wkb = Vec::new();
+                for _ in 0..depth {
+                    wkb.push(1);
+                    wkb.extend_from_slice(&7u32.to_le_bytes());
+                    wkb.extend_from_slice(&1u32.to_le_bytes());
+                }
+                wkb.extend(line_wkb(2, &[[0.0, 0.0], [1.0, 1.0]]));
+                blob(&wkb)
+            };
+            let mut layer = Layer::new();
+            layer.add_geopackage_blob(&nested(32)).unwrap();
+            assert_eq!(layer.lines().len(), 1);
+            assert!(layer.add_geopackage_blob(&nested(33)).is_err());
+            assert!(layer.add_geopackage_blob(&nested(100_000)).is_err());
+        }
+    }
+}
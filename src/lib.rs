@@ -11,6 +11,11 @@
 //!
 //! # Features
 //! * An n-dimensional r*-tree: `spade::rtree::RTree`
+//! * A static kd-tree for point sets that never change: `spade::kdtree::KdTree`
+//! * Region quadtrees and octrees with level of detail traversal: `spade::quadtree::QuadTree`
+//! * A uniform spatial hash grid for many moving objects: `spade::hashgrid::HashGrid`
+//! * Interval trees for stabbing and overlap queries on 1D ranges: `spade::interval::IntervalTree`
+//! * Stabbing queries on horizontal and vertical segments: `spade::segmenttree::SegmentTree`
 //! * A 2D Delaunay triangulation: `spade::delaunay::DelaunayTriangulation`
 //!   * Supports integral and floating point coordinates as input
 //!   * Uses exact predicates to avoid floating point rounding issues, see `spade::kernels::FloatKernel`
@@ -18,23 +23,61 @@
 //!   * Can be backed up by an r*-tree to improve performance when inserting randomly distributed points
 //!   * Supports vertex removal
 //! * A 2D constrained Delaunay triangulation: `spade::delaunay::ConstrainedDelaunayTriangulation`
+//! * Convex hulls of point sets: `spade::hull`
+//! * Distances between convex shapes: `spade::gjk`
+//! * Polygon algorithms: `spade::polygon`
+//! * Finding all intersections among a set of segments: `spade::intersections`
+//! * Density based clustering with DBSCAN and OPTICS: `spade::cluster`
+//! * Hilbert curve indices for spatial sort keys: `spade::hilbert`
+//! * Geodetic points with great-circle nearest neighbor queries: `spade::GeoPoint`
+//! * Reading and writing well-known text (WKT): `spade::wkt`
+//! * Rendering triangulations and r-trees into SVG images: `spade::svg`
+//! * Exporting r-tree structures and triangulations as GraphViz DOT graphs: `spade::dot`
 //! * Supports serde. Activate the `serde_serialize` feature to enable (de)serialization of r*-trees,
 //! (constrained) Delaunay triangulations and primitives.
+//! * Supports conversion of triangulations into `petgraph` graphs. Activate the `petgraph` feature to enable.
+//! * Supports `mint` points. Activate the `mint` feature to enable.
+//! * Supports `glam` vectors. Activate the `glam` feature to enable.
+//! * Supports `geo-types` coordinates and geometries. Activate the `geo-types` feature to enable.
+//! * Supports unit-tagged `euclid` points. Activate the `euclid` feature to enable.
+//! * Supports fixed-point coordinates with an exact kernel. Activate the `fixed` feature to enable.
+//! * An exact kernel based on arbitrary precision rationals. Activate the `rational_kernel` feature to enable.
+//! * GeoJSON export of triangulations, Voronoi cells and contour lines. Activate the `geojson` feature to enable.
+//! * Loading point and line layers from shapefiles and GeoPackages. Activate the `shapefile` or `geopackage` feature to enable.
+//! * Zero-copy `rkyv` archives of r-trees and triangulations. Activate the `rkyv` feature to enable.
+//! * Adapters between spade's and `rstar`'s r-tree object traits. Activate the `rstar` feature to enable.
+//! * `arbitrary` and `proptest` generators for primitives, polygons and degenerate point sets. Activate the `arbitrary` or `proptest` feature to enable.
+//! * Parallel batch queries on r-trees and parallel grid interpolation using `rayon`. Activate the `rayon` feature to enable.
+//! * Counters for node visits, distance computations, predicates and edge flips: `spade::instrumentation`. Activate the `instrumentation` feature to enable.
+//! * Supports `no_std` environments with an allocator. Disable the default features and activate the `libm` feature to enable.
 //!
 //! # Supported point types
-//! Spade works well with points from the `nalgebra` and `cgmath` packages. Also, fixed size arrays of size 2, 3 and 4 are
-//! supported. Also own vector types can be defined.
+//! Spade comes with its own minimal point types, `spade::Point2` and `spade::Point3`. It also works well with points
+//! from the `nalgebra` and `cgmath` packages. Also, fixed size arrays of any size and tuples `(S, S)` and `(S, S, S)`
+//! are supported. Also own vector types can be defined. With the `mint` feature, `mint::Point2` and `mint::Point3`
+//! can be used as well, which avoids depending on a particular math library. The `glam` feature adds support for
+//! `glam`'s `Vec2`, `DVec2`, `Vec3` and `DVec3`. The `euclid` feature supports `euclid::Point2D` and `euclid::Point3D`,
+//! their unit parameter is kept in all query results.
 //! Please note that, due to the way cargo resolves dependencies, there might be issues when using spade combined with cgmath
 //! or nalgebra: every time spade updates these libraries, the using code must be update too, even if spade would still work
-//!  with the older version. To avoid this, consider switching to fixed size arrays as points until
+//!  with the older version. To avoid this, consider switching to spade's own point types or fixed size arrays until
 //! [public / private dependencies make their way into cargo](https://github.com/rust-lang/rust/issues/44663).
+//!
+//! Support for `cgmath` and `nalgebra` is provided by the `cgmath` and `nalgebra` features, both are enabled by
+//! default. Disable the default features and activate the `std` feature to depend on neither of these crates.
 
+#![cfg_attr(not(feature = "std"), no_std)]
 #![warn(missing_docs)]
 #![warn(clippy::all)]
 #![allow(clippy::float_cmp)]
 
+#[cfg(not(any(feature = "std", feature = "libm")))]
+compile_error!("spade requires either the `std` or the `libm` feature for floating point math");
+
+extern crate alloc;
+#[cfg(feature = "cgmath")]
 extern crate cgmath;
-extern crate clamp;
+#[cfg(feature = "nalgebra")]
 extern crate nalgebra;
 extern crate num;
 extern crate pdqselect;
@@ -44,7 +87,43 @@ extern crate smallvec;
 #[macro_use]
 extern crate serde;
 
-#[cfg(all(feature = "serde_serialize", test))]
+#[cfg(feature = "petgraph")]
+extern crate petgraph;
+
+#[cfg(feature = "mint")]
+extern crate mint;
+
+#[cfg(feature = "glam")]
+extern crate glam;
+
+#[cfg(feature = "geo-types")]
+extern crate geo_types;
+
+#[cfg(feature = "euclid")]
+extern crate euclid;
+
+#[cfg(feature = "fixed")]
+extern crate fixed;
+
+#[cfg(feature = "geopackage")]
+extern crate rusqlite;
+
+#[cfg(feature = "rkyv")]
+extern crate rkyv;
+
+#[cfg(feature = "arbitrary")]
+extern crate arbitrary;
+
+#[cfg(feature = "proptest")]
+extern crate proptest;
+
+#[cfg(feature = "rstar")]
+extern crate rstar;
+
+#[cfg(feature = "rayon")]
+extern crate rayon;
+
+#[cfg(any(feature = "geojson", all(feature = "serde_serialize", test)))]
 extern crate serde_json;
 
 #[cfg(test)]
@@ -59,15 +138,61 @@ mod testutils;
 mod bigvec;
 mod boundingrect;
 mod exactpred;
+mod geodetic;
+#[cfg(feature = "geo-types")]
+mod georust;
+#[cfg(not(feature = "instrumentation"))]
+mod instrumentation;
+mod metric;
 mod misc;
+#[cfg(feature = "rayon")]
+mod parallel;
+mod point;
 mod point_traits;
+#[cfg(feature = "rstar")]
+mod rstar_compat;
 mod traits;
+mod transform;
+mod validation;
 
+#[cfg(feature = "rkyv")]
+pub mod archive;
+pub mod cluster;
 pub mod delaunay;
+pub mod dot;
+#[cfg(any(feature = "arbitrary", feature = "proptest"))]
+pub mod generators;
+#[cfg(feature = "geojson")]
+pub mod geojson;
+pub mod gjk;
+pub mod hashgrid;
+pub mod hilbert;
+pub mod hull;
+#[cfg(feature = "instrumentation")]
+pub mod instrumentation;
+pub mod intersections;
+pub mod interval;
+pub mod kdtree;
 pub mod kernels;
+#[cfg(any(feature = "shapefile", feature = "geopackage"))]
+pub mod layer;
+pub mod polygon;
 pub mod primitives;
+pub mod quadtree;
 pub mod rtree;
+pub mod segmenttree;
+pub mod svg;
+pub mod wkt;
 
 pub use crate::boundingrect::*;
+pub use crate::geodetic::{GeoPoint, EARTH_RADIUS};
+#[cfg(feature = "geo-types")]
+pub use crate::georust::GeometryObject;
+pub use crate::metric::AnisotropicMetric;
+pub use crate::point::{Point2, Point3};
 pub use crate::point_traits::{PointN, ThreeDimensional, TwoDimensional};
+#[cfg(feature = "rstar")]
+pub use crate::rstar_compat::{RstarObject, SpadeObject};
 pub use crate::traits::*;
+pub use crate::transform::AffineTransform2;
+pub use crate::validation::NonFiniteCoordinateError;
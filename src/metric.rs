@@ -0,0 +1,238 @@
+// Copyright 2017 The Spade Developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use crate::point_traits::{PointNExtensions, TwoDimensional};
+use crate::traits::SpadeFloat;
+use num::{one, zero};
+
+/// An anisotropic distance metric in the plane.
+///
+/// The metric is given by a symmetric, positive definite 2×2 matrix `M`. The
+/// squared distance between two points `p` and `q` is defined as
+/// `(p - q)ᵀ M (p - q)`. The identity matrix yields the euclidean distance.
+///
+/// Anisotropic metrics are useful if data is more strongly correlated along
+/// one direction than along another one, e.g. along a coastline or a river.
+///
+/// # Triangulations
+/// Every anisotropic metric can be reduced to the euclidean metric by a linear
+/// transformation, see `transform`. A Delaunay triangulation of the transformed
+/// points is the Delaunay triangulation in respect to the metric. Inserting
+/// transformed positions thus makes natural neighbor interpolation and nearest
+/// neighbor queries of a triangulation use the anisotropic metric.
+///
+/// # Example
+///
+/// ```
+/// # extern crate nalgebra;
+/// # extern crate spade;
+/// use nalgebra::Point2;
+/// use spade::AnisotropicMetric;
+///
+/// # fn main() {
+/// // Distances along the x axis count only a quarter
+/// let metric = AnisotropicMetric::from_direction(&Point2::new(1.0, 0.0), 4.0);
+/// let origin = Point2::new(0.0, 0.0);
+/// assert_eq!(metric.distance2(&origin, &Point2::new(4.0, 0.0)), 1.0);
+/// assert_eq!(metric.distance2(&origin, &Point2::new(0.0, 1.0)), 1.0);
+/// # }
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde_serialize", derive(Serialize, Deserialize))]
+pub struct AnisotropicMetric<S> {
+    m11: S,
+    m12: S,
+    m22: S,
+}
+
+impl<S: SpadeFloat> Default for AnisotropicMetric<S> {
+    fn default() -> Self {
+        AnisotropicMetric::identity()
+    }
+}
+
+impl<S: SpadeFloat> AnisotropicMetric<S> {
+    /// Creates the euclidean metric.
+    pub fn identity() -> Self {
+        AnisotropicMetric {
+            m11: one(),
+            m12: zero(),
+            m22: one(),
+        }
+    }
+
+    /// Creates a metric from the entries of a symmetric 2×2 matrix.
+    ///
+    /// The matrix is `[[m11, m12], [m12, m22]]`.
+    /// Returns `None` if the matrix is not positive definite.
+    pub fn new(m11: S, m12: S, m22: S) -> Option<Self> {
+        if m11 > zero() && m11 * m22 - m12 * m12 > zero() {
+            Some(AnisotropicMetric { m11, m12, m22 })
+        } else {
+            None
+        }
+    }
+
+    /// Creates a metric that shrinks distances along a direction.
+    ///
+    /// Distances along `direction` are divided by `stretch`, distances
+    /// perpendicular to it remain unchanged. A stretch greater than one
+    /// thus extends the neighborhood of a point along `direction`.
+    ///
+    /// # Panics
+    /// Panics if `direction` has zero length or `stretch` is not positive.
+    pub fn from_direction<V>(direction: &V, stretch: S) -> Self
+    where
+        V: TwoDimensional<Scalar = S>,
+    {
+        let length = direction.length2().sqrt();
+        assert!(length > zero(), "Direction must not have zero length");
+        assert!(stretch > zero(), "Stretch must be positive");
+        let (dx, dy) = (*direction.nth(0) / length, *direction.nth(1) / length);
+        let along = S::one() / (stretch * stretch);
+        // M = along * d dᵀ + n nᵀ with n being perpendicular to d
+        AnisotropicMetric {
+            m11: along * dx * dx + dy * dy,
+            m12: (along - S::one()) * dx * dy,
+            m22: along * dy * dy + dx * dx,
+        }
+    }
+
+    /// Returns the matrix entries `(m11, m12, m22)`.
+    pub fn matrix(&self) -> (S, S, S) {
+        (self.m11, self.m12, self.m22)
+    }
+
+    /// Returns the squared distance between two points.
+    pub fn distance2<V>(&self, p1: &V, p2: &V) -> S
+    where
+        V: TwoDimensional<Scalar = S>,
+    {
+        let diff = p1.sub(p2);
+        let (dx, dy) = (*diff.nth(0), *diff.nth(1));
+        self.m11 * dx * dx + (self.m12 + self.m12) * dx * dy + self.m22 * dy * dy
+    }
+
+    /// Maps a point into a space in which the euclidean distance equals this metric.
+    ///
+    /// For any two points, `metric.distance2(p, q)` equals the squared euclidean
+    /// distance of `metric.transform(p)` and `metric.transform(q)`.
+    pub fn transform<V>(&self, point: &V) -> V
+    where
+        V: TwoDimensional<Scalar = S>,
+    {
+        // Uses the Cholesky decomposition M = L Lᵀ and maps p to Lᵀ p
+        let l11 = self.m11.sqrt();
+        let l21 = self.m12 / l11;
+        let l22 = (self.m22 - l21 * l21).sqrt();
+        let (x, y) = (*point.nth(0), *point.nth(1));
+        let mut result = V::new();
+        *result.nth_mut(0) = l11 * x + l21 * y;
+        *result.nth_mut(1) = l22 * y;
+        result
+    }
+
+    /// Returns the smallest eigenvalue of the metric's matrix.
+    ///
+    /// Multiplying a squared euclidean distance with this value yields a lower
+    /// bound for the squared distance in this metric.
+    pub(crate) fn min_eigenvalue(&self) -> S {
+        let two = S::one() + S::one();
+        let mean = (self.m11 + self.m22) / two;
+        let half_diff = (self.m11 - self.m22) / two;
+        let radius = (half_diff * half_diff + self.m12 * self.m12).sqrt();
+        let eigenvalue = mean - radius;
+        if eigenvalue > zero() {
+            eigenvalue
+        } else {
+            zero()
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::AnisotropicMetric;
+    use crate::delaunay::FloatDelaunayTriangulation;
+    use crate::point_traits::PointNExtensions;
+    use crate::rtree::RTree;
+    use crate::testutils::*;
+    use cgmath::Point2;
+
+    const SEED: &[u8; 32] = b"\x92\x1e\x5a\xc7\x38\x04\xfd\x6b\x2e\xa1\x57\xd0\x8c\x43\xb9\x16\
+                              \x7f\xe2\x0a\x95\x61\xcc\x24\x8b\xd3\x3e\x70\x09\xaf\x52\xe8\x1d";
+    const SEED2: &[u8; 32] = b"\x4d\xb0\x13\x8e\xf6\x29\x65\xca\x01\x7b\xde\x34\x98\x5f\xa2\x47\
+                               \xc1\x0e\x83\x6a\xf9\x25\xbc\x50\x17\xe4\x3c\x99\x66\xd8\x0f\xab";
+
+    #[test]
+    fn test_metric_construction() {
+        assert!(AnisotropicMetric::new(1.0, 2.0, 1.0).is_none());
+        assert!(AnisotropicMetric::new(-1.0, 0.0, -1.0).is_none());
+        let metric = AnisotropicMetric::new(2.0, 0.5, 1.0).unwrap();
+        assert_eq!(metric.matrix(), (2.0, 0.5, 1.0));
+        assert_eq!(
+            AnisotropicMetric::<f64>::default(),
+            AnisotropicMetric::identity()
+        );
+
+        let diagonal = AnisotropicMetric::from_direction(&Point2::new(1.0, 1.0), 2.0);
+        let origin = Point2::new(0.0, 0.0);
+        assert_relative_eq!(diagonal.distance2(&origin, &Point2::new(2.0, 2.0)), 2.0);
+        assert_relative_eq!(diagonal.distance2(&origin, &Point2::new(-1.0, 1.0)), 2.0);
+        assert_relative_eq!(diagonal.min_eigenvalue(), 0.25);
+    }
+
+    #[test]
+    fn test_transform() {
+        let metric = AnisotropicMetric::new(3.0, -1.0, 0.5).unwrap();
+        let points = random_points_with_seed::<f64>(50, SEED);
+        for pair in points.windows(2) {
+            let (p, q) = (pair[0], pair[1]);
+            let transformed = metric.transform(&p).sub(&metric.transform(&q)).length2();
+            assert_relative_eq!(metric.distance2(&p, &q), transformed, epsilon = 1e-10);
+            assert!(metric.distance2(&p, &q) >= metric.min_eigenvalue() * p.sub(&q).length2());
+        }
+    }
+
+    #[test]
+    fn test_nearest_neighbor_with_metric() {
+        let metric = AnisotropicMetric::from_direction(&Point2::new(1.0, 0.5), 5.0);
+        let points = random_points_with_seed::<f64>(300, SEED);
+        let tree = RTree::bulk_load(points.clone());
+        let mut transformed = FloatDelaunayTriangulation::with_walk_locate();
+        for p in &points {
+            transformed.insert(metric.transform(p));
+        }
+        for query in random_points_with_seed::<f64>(50, SEED2) {
+            let expected = points
+                .iter()
+                .min_by(|a, b| {
+                    metric
+                        .distance2(*a, &query)
+                        .partial_cmp(&metric.distance2(*b, &query))
+                        .unwrap()
+                })
+                .unwrap();
+            assert_eq!(
+                tree.nearest_neighbor_with_metric(&query, &metric),
+                Some(expected)
+            );
+            let nn = transformed
+                .nearest_neighbor(&metric.transform(&query))
+                .unwrap();
+            assert_eq!(*nn, metric.transform(expected));
+
+            let ordered: Vec<_> = tree
+                .nearest_neighbor_iterator_with_metric(&query, &metric)
+                .map(|p| metric.distance2(p, &query))
+                .collect();
+            assert_eq!(ordered.len(), points.len());
+            assert!(ordered.windows(2).all(|w| w[0] <= w[1]));
+        }
+    }
+}
@@ -27,3 +27,25 @@ pub fn max_inline<S: SpadeNum>(a: S, b: S) -> S {
         b
     }
 }
+
+/// Restricts a value to the interval `[lower, upper]`.
+#[inline]
+pub fn clamp<S: PartialOrd>(lower: S, value: S, upper: S) -> S {
+    if value < lower {
+        lower
+    } else if value > upper {
+        upper
+    } else {
+        value
+    }
+}
+
+/// Returns `false` if a value is NaN or infinite.
+///
+/// `x - x` is zero for every finite value and NaN for NaN and +-inf. This
+/// allows to check for finiteness without requiring float specific methods.
+#[inline]
+pub fn is_finite<S: SpadeNum>(value: &S) -> bool {
+    let difference = value.clone() - value.clone();
+    difference == ::num::zero()
+}
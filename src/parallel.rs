@@ -0,0 +1,164 @@
+// Copyright 2017 The Spade Developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use crate::delaunay::{DelaunayLocateStructure, DelaunayTriangulation, RasterGrid};
+use crate::kernels::DelaunayKernel;
+use crate::point_traits::{PointN, TwoDimensional};
+use crate::rtree::{QueryScratch, RTree};
+use crate::traits::{HasPosition2D, SpadeFloat, SpatialObject};
+use alloc::{vec, vec::Vec};
+use rayon::prelude::*;
+
+impl<T> RTree<T>
+where
+    T: SpatialObject + Sync,
+    T::Point: Sync,
+{
+    /// Returns the nearest neighbor of each query point.
+    ///
+    /// The queries are split across rayon's thread pool. Each job reuses a
+    /// single `QueryScratch` for all of its queries. The results are returned
+    /// in the order of `query_points`, an entry is `None` if the tree is empty.
+    /// Requires the `rayon` feature.
+    ///
+    /// # Example
+    /// ```
+    /// use spade::rtree::RTree;
+    /// use spade::Point2;
+    ///
+    /// let tree = RTree::bulk_load(vec![Point2::new(0.0, 0.0), Point2::new(10.0, 0.0)]);
+    /// let queries = [Point2::new(1.0, 1.0), Point2::new(8.0, -2.0)];
+    /// let nearest = tree.par_nearest_neighbors(&queries);
+    /// assert_eq!(nearest, vec![Some(&Point2::new(0.0, 0.0)), Some(&Point2::new(10.0, 0.0))]);
+    /// ```
+    pub fn par_nearest_neighbors(&self, query_points: &[T::Point]) -> Vec<Option<&T>> {
+        query_points
+            .par_iter()
+            .map_init(QueryScratch::new, |scratch, query_point| {
+                self.nearest_neighbor_with_scratch(query_point, scratch)
+            })
+            .collect()
+    }
+}
+
+impl<V, K, L> DelaunayTriangulation<V, K, L>
+where
+    V: HasPosition2D,
+    V::Point: TwoDimensional + Sync,
+    <V::Point as PointN>::Scalar: SpadeFloat + Send,
+    K: DelaunayKernel<<V::Point as PointN>::Scalar>,
+    L: DelaunayLocateStructure<V::Point>,
+    Self: Sync,
+{
+    /// Samples a natural neighbor interpolation at every cell of a grid.
+    ///
+    /// Returns one interpolated value per cell of `grid`, in the grid's row
+    /// order. Like `rasterize_heights`, cells outside of the convex hull are
+    /// `None`. Rows are interpolated in parallel on rayon's thread pool. Each
+    /// thread walks along its row, locating the next cell starts at the last
+    /// location of the same thread. Requires the `rayon` feature.
+    ///
+    /// # Example
+    /// ```
+    /// use spade::delaunay::{FloatDelaunayTriangulation, RasterGrid};
+    /// use spade::{BoundingRect, Point2};
+    ///
+    /// let mut delaunay = FloatDelaunayTriangulation::with_walk_locate();
+    /// delaunay.insert(Point2::new(0.0, 0.0));
+    /// delaunay.insert(Point2::new(4.0, 0.0));
+    /// delaunay.insert(Point2::new(0.0, 4.0));
+    /// delaunay.insert(Point2::new(4.0, 4.0));
+    /// let bbox = BoundingRect::from_corners(&Point2::new(0.0, 0.0), &Point2::new(4.0, 4.0));
+    /// let grid = RasterGrid::new(bbox, 4, 2);
+    /// let raster: Vec<Option<f64>> = delaunay.interpolate_grid(&grid, |v| v.x);
+    /// assert_eq!(raster.len(), 8);
+    /// assert!((raster[5].unwrap() - 1.5).abs() < 1e-9);
+    /// ```
+    pub fn interpolate_grid<F>(
+        &self,
+        grid: &RasterGrid<V::Point>,
+        f: F,
+    ) -> Vec<Option<<V::Point as PointN>::Scalar>>
+    where
+        F: Fn(&V) -> <V::Point as PointN>::Scalar + Sync,
+    {
+        let mut result = vec![None; grid.len()];
+        if grid.is_empty() {
+            return result;
+        }
+        result
+            .par_chunks_mut(grid.columns())
+            .enumerate()
+            .for_each(|(row, values)| {
+                for (column, value) in values.iter_mut().enumerate() {
+                    let sample = grid.sample(column, row);
+                    *value = self.nn_interpolation_inside_hull(&sample, &f);
+                }
+            });
+        result
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::delaunay::{FloatDelaunayTriangulation, PositionInTriangulation, RasterGrid};
+    use crate::rtree::RTree;
+    use crate::testutils::*;
+    use crate::BoundingRect;
+    use cgmath::Point2;
+
+    #[test]
+    fn test_par_nearest_neighbors() {
+        let points = random_points_with_seed::<f64>(1000, &[3; 32]);
+        let queries = random_points_with_seed::<f64>(500, &[7; 32]);
+        let tree = RTree::bulk_load(points);
+        let results = tree.par_nearest_neighbors(&queries);
+        assert_eq!(results.len(), queries.len());
+        for (query, result) in queries.iter().zip(results) {
+            assert_eq!(result, tree.nearest_neighbor(query));
+        }
+
+        let empty: RTree<Point2<f64>> = RTree::new();
+        assert_eq!(empty.par_nearest_neighbors(&queries[..3]), vec![None; 3]);
+    }
+
+    #[test]
+    fn test_interpolate_grid() {
+        let mut delaunay = FloatDelaunayTriangulation::with_walk_locate();
+        for point in random_points_with_seed::<f64>(300, &[5; 32]) {
+            delaunay.insert(point);
+        }
+        let height = |p: &Point2<f64>| p.x * p.x + p.y;
+        let bbox = BoundingRect::from_corners(&Point2::new(-1.5, -1.0), &Point2::new(1.0, 1.5));
+        let grid = RasterGrid::new(bbox, 37, 23);
+        let raster = delaunay.interpolate_grid(&grid, height);
+        assert_eq!(raster.len(), 37 * 23);
+        let mut inside = 0;
+        for row in 0..23 {
+            for column in 0..37 {
+                let sample = grid.sample(column, row);
+                let value = raster[row * 37 + column];
+                if let PositionInTriangulation::OutsideConvexHull(_) = delaunay.locate(&sample) {
+                    assert_eq!(value, None);
+                } else {
+                    assert_eq!(value, delaunay.nn_interpolation(&sample, height));
+                    inside += 1;
+                }
+            }
+        }
+        assert!(inside > 0 && inside < grid.len());
+
+        let empty = FloatDelaunayTriangulation::<Point2<f64>, _>::with_walk_locate();
+        assert_eq!(
+            empty.interpolate_grid(&grid, height),
+            vec![None; grid.len()]
+        );
+        let no_cells = RasterGrid::new(bbox, 0, 5);
+        assert_eq!(delaunay.interpolate_grid(&no_cells, height), vec![]);
+    }
+}
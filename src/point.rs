@@ -0,0 +1,201 @@
+// Copyright 2017 The Spade Developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use crate::point_traits::{PointN, ThreeDimensional, TwoDimensional};
+use crate::traits::SpadeNum;
+
+/// A minimal two dimensional point.
+///
+/// Spade's own point type, useful if no other math library is used.
+/// Offers no arithmetic beyond what `PointN` provides.
+///
+/// # Example
+///
+/// ```
+/// use spade::Point2;
+/// use spade::rtree::RTree;
+///
+/// let tree = RTree::bulk_load(vec![Point2::new(0.0, 0.0), Point2::new(2.0, 1.0)]);
+/// assert_eq!(tree.nearest_neighbor(&Point2::new(1.5, 1.5)), Some(&Point2::new(2.0, 1.0)));
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde_serialize", derive(Serialize, Deserialize))]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct Point2<S> {
+    /// The x coordinate.
+    pub x: S,
+    /// The y coordinate.
+    pub y: S,
+}
+
+impl<S> Point2<S> {
+    /// Creates a new point.
+    pub fn new(x: S, y: S) -> Self {
+        Point2 { x, y }
+    }
+}
+
+/// A minimal three dimensional point.
+///
+/// Spade's own point type, useful if no other math library is used.
+/// Offers no arithmetic beyond what `PointN` and `ThreeDimensional` provide.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde_serialize", derive(Serialize, Deserialize))]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct Point3<S> {
+    /// The x coordinate.
+    pub x: S,
+    /// The y coordinate.
+    pub y: S,
+    /// The z coordinate.
+    pub z: S,
+}
+
+impl<S> Point3<S> {
+    /// Creates a new point.
+    pub fn new(x: S, y: S, z: S) -> Self {
+        Point3 { x, y, z }
+    }
+}
+
+impl<S: SpadeNum + Copy> PointN for Point2<S> {
+    type Scalar = S;
+
+    fn dimensions() -> usize {
+        2
+    }
+
+    fn nth(&self, index: usize) -> &S {
+        match index {
+            0 => &self.x,
+            1 => &self.y,
+            _ => panic!("Index out of bounds: {}", index),
+        }
+    }
+    fn nth_mut(&mut self, index: usize) -> &mut S {
+        match index {
+            0 => &mut self.x,
+            1 => &mut self.y,
+            _ => panic!("Index out of bounds: {}", index),
+        }
+    }
+
+    fn from_value(value: Self::Scalar) -> Self {
+        Point2::new(value, value)
+    }
+}
+
+impl<S: SpadeNum + Copy> TwoDimensional for Point2<S> {}
+
+impl<S: SpadeNum + Copy> PointN for Point3<S> {
+    type Scalar = S;
+
+    fn dimensions() -> usize {
+        3
+    }
+
+    fn nth(&self, index: usize) -> &S {
+        match index {
+            0 => &self.x,
+            1 => &self.y,
+            2 => &self.z,
+            _ => panic!("Index out of bounds: {}", index),
+        }
+    }
+    fn nth_mut(&mut self, index: usize) -> &mut S {
+        match index {
+            0 => &mut self.x,
+            1 => &mut self.y,
+            2 => &mut self.z,
+            _ => panic!("Index out of bounds: {}", index),
+        }
+    }
+
+    fn from_value(value: Self::Scalar) -> Self {
+        Point3::new(value, value, value)
+    }
+}
+
+impl<S: SpadeNum + Copy> ThreeDimensional for Point3<S> {}
+
+impl<S> From<[S; 2]> for Point2<S> {
+    fn from([x, y]: [S; 2]) -> Self {
+        Point2::new(x, y)
+    }
+}
+
+impl<S> From<(S, S)> for Point2<S> {
+    fn from((x, y): (S, S)) -> Self {
+        Point2::new(x, y)
+    }
+}
+
+impl<S> From<Point2<S>> for [S; 2] {
+    fn from(point: Point2<S>) -> Self {
+        [point.x, point.y]
+    }
+}
+
+impl<S> From<[S; 3]> for Point3<S> {
+    fn from([x, y, z]: [S; 3]) -> Self {
+        Point3::new(x, y, z)
+    }
+}
+
+impl<S> From<(S, S, S)> for Point3<S> {
+    fn from((x, y, z): (S, S, S)) -> Self {
+        Point3::new(x, y, z)
+    }
+}
+
+impl<S> From<Point3<S>> for [S; 3] {
+    fn from(point: Point3<S>) -> Self {
+        [point.x, point.y, point.z]
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Point2, Point3};
+    use crate::delaunay::FloatDelaunayTriangulation;
+    use crate::point_traits::{PointN, PointNExtensions, ThreeDimensional};
+
+    #[test]
+    fn test_native_points() {
+        let mut p = Point2::new(1.0, 2.0);
+        *p.nth_mut(0) = 3.0;
+        assert_eq!(p, Point2::from([3.0, 2.0]));
+        assert_eq!(p.length2(), 13.0);
+        assert_eq!(<[f64; 2]>::from(p), [3.0, 2.0]);
+        assert_eq!(Point2::from_value(0), Point2::default());
+
+        let x = Point3::new(1, 0, 0);
+        assert_eq!(x.cross(&Point3::from((0, 1, 0))), Point3::new(0, 0, 1));
+        assert_eq!(*x.nth(2), 0);
+        assert_eq!(Point3::<i64>::dimensions(), 3);
+    }
+
+    #[test]
+    fn test_native_delaunay() {
+        let mut delaunay = FloatDelaunayTriangulation::with_walk_locate();
+        for (x, y) in [(0.0, 0.0), (1.0, 0.0), (0.0, 1.0), (1.0, 1.0), (0.5, 0.4)] {
+            delaunay.insert(Point2::new(x, y));
+        }
+        assert_eq!(delaunay.num_triangles(), 4);
+        let nearest = delaunay.nearest_neighbor(&Point2::new(0.6, 0.5)).unwrap();
+        assert_eq!(*nearest, Point2::new(0.5, 0.4));
+    }
+}
@@ -6,20 +6,23 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
+#[cfg(feature = "cgmath")]
 use cgmath as cg;
+#[cfg(feature = "nalgebra")]
 use nalgebra as na;
 
 use crate::misc::{max_inline, min_inline};
 use crate::traits::SpadeNum;
+use core::fmt::Debug;
 use num::zero;
-use std::fmt::Debug;
 
 /// Abstraction over a point with a fixed number of dimensions.
 ///
 /// Spade will work with any point type implementing this trait, at the
 /// moment points of the `cgmath` and `nalgebra` crates are supported.
-/// Also, the trait is implemented for fixed arrays of length 2, 3 and 4, allowing
-/// to use spade's datastructures with fixed size arrays as point coordinates.
+/// Also, the trait is implemented for fixed size arrays of any length and for
+/// tuples `(S, S)` and `(S, S, S)`, allowing to use spade's datastructures with
+/// plain arrays or tuples as point coordinates.
 /// That means that the trait's methods are also implemented for
 /// these array types, thus be careful when importing `PointN`.
 ///
@@ -133,16 +136,16 @@ pub trait PointNExtensions: PointN {
         self.dot(&self)
     }
 
-    fn lex_compare(&self, other: &Self) -> std::cmp::Ordering {
+    fn lex_compare(&self, other: &Self) -> core::cmp::Ordering {
         for i in 0..Self::dimensions() {
             let left = self.nth(i);
             let right = other.nth(i);
             let compare = left.partial_cmp(right).unwrap();
-            if compare != std::cmp::Ordering::Equal {
+            if compare != core::cmp::Ordering::Equal {
                 return compare;
             }
         }
-        std::cmp::Ordering::Equal
+        core::cmp::Ordering::Equal
     }
 }
 
@@ -154,9 +157,14 @@ impl<T> PointNExtensions for T where T: PointN {}
 /// this trait makes sure that only such points can be passed.
 pub trait TwoDimensional: PointN {}
 
+#[cfg(feature = "cgmath")]
 impl<S: SpadeNum + cg::BaseNum> TwoDimensional for cg::Point2<S> {}
+#[cfg(feature = "nalgebra")]
 impl<S: SpadeNum + na::Scalar> TwoDimensional for na::Point2<S> {}
 impl<S: SpadeNum + Copy> TwoDimensional for [S; 2] {}
+impl<S: SpadeNum + Copy> TwoDimensional for (S, S) {}
+#[cfg(feature = "mint")]
+impl<S: SpadeNum + Copy> TwoDimensional for mint::Point2<S> {}
 
 /// A three dimensional Point.
 ///
@@ -176,16 +184,24 @@ pub trait ThreeDimensional: PointN {
     }
 }
 
+#[cfg(feature = "cgmath")]
 impl<S: SpadeNum + cg::BaseNum> ThreeDimensional for cg::Point3<S> {}
 
+#[cfg(feature = "nalgebra")]
 impl<S: SpadeNum + na::Scalar> ThreeDimensional for na::Point3<S> {}
 
 impl<S: SpadeNum + Copy> ThreeDimensional for [S; 3] {}
 
-impl<S: SpadeNum + Copy> PointN for [S; 2] {
+impl<S: SpadeNum + Copy> ThreeDimensional for (S, S, S) {}
+
+#[cfg(feature = "mint")]
+impl<S: SpadeNum + Copy> ThreeDimensional for mint::Point3<S> {}
+
+impl<S: SpadeNum + Copy, const N: usize> PointN for [S; N] {
     type Scalar = S;
+
     fn dimensions() -> usize {
-        2
+        N
     }
 
     fn nth(&self, index: usize) -> &S {
@@ -196,47 +212,67 @@ impl<S: SpadeNum + Copy> PointN for [S; 2] {
     }
 
     fn from_value(value: Self::Scalar) -> Self {
-        [value; 2]
+        [value; N]
     }
 }
 
-impl<S: SpadeNum + Copy> PointN for [S; 3] {
+impl<S: SpadeNum + Copy> PointN for (S, S) {
     type Scalar = S;
+
     fn dimensions() -> usize {
-        3
+        2
     }
 
     fn nth(&self, index: usize) -> &S {
-        &self[index]
+        match index {
+            0 => &self.0,
+            1 => &self.1,
+            _ => panic!("Index out of bounds: {}", index),
+        }
     }
     fn nth_mut(&mut self, index: usize) -> &mut S {
-        &mut self[index]
+        match index {
+            0 => &mut self.0,
+            1 => &mut self.1,
+            _ => panic!("Index out of bounds: {}", index),
+        }
     }
 
     fn from_value(value: Self::Scalar) -> Self {
-        [value; 3]
+        (value, value)
     }
 }
 
-impl<S: SpadeNum + Copy> PointN for [S; 4] {
+impl<S: SpadeNum + Copy> PointN for (S, S, S) {
     type Scalar = S;
 
     fn dimensions() -> usize {
-        4
+        3
     }
 
     fn nth(&self, index: usize) -> &S {
-        &self[index]
+        match index {
+            0 => &self.0,
+            1 => &self.1,
+            2 => &self.2,
+            _ => panic!("Index out of bounds: {}", index),
+        }
     }
     fn nth_mut(&mut self, index: usize) -> &mut S {
-        &mut self[index]
+        match index {
+            0 => &mut self.0,
+            1 => &mut self.1,
+            2 => &mut self.2,
+            _ => panic!("Index out of bounds: {}", index),
+        }
     }
 
     fn from_value(value: Self::Scalar) -> Self {
-        [value; 4]
+        (value, value, value)
     }
 }
 
+#[cfg(feature = "cgmath")]
 impl<S: SpadeNum + cg::BaseNum> PointN for cg::Point2<S> {
     type Scalar = S;
 
@@ -256,6 +292,7 @@ impl<S: SpadeNum + cg::BaseNum> PointN for cg::Point2<S> {
     }
 }
 
+#[cfg(feature = "cgmath")]
 impl<S: SpadeNum + cg::BaseNum> PointN for cg::Point3<S> {
     type Scalar = S;
 
@@ -275,6 +312,7 @@ impl<S: SpadeNum + cg::BaseNum> PointN for cg::Point3<S> {
     }
 }
 
+#[cfg(feature = "nalgebra")]
 impl<S: SpadeNum + na::Scalar> PointN for na::Point2<S> {
     type Scalar = S;
 
@@ -294,6 +332,7 @@ impl<S: SpadeNum + na::Scalar> PointN for na::Point2<S> {
     }
 }
 
+#[cfg(feature = "nalgebra")]
 impl<S: SpadeNum + na::Scalar> PointN for na::Point3<S> {
     type Scalar = S;
 
@@ -313,6 +352,7 @@ impl<S: SpadeNum + na::Scalar> PointN for na::Point3<S> {
     }
 }
 
+#[cfg(feature = "nalgebra")]
 impl<S: SpadeNum + na::Scalar + na::Scalar> PointN for na::Point4<S> {
     type Scalar = S;
 
@@ -331,3 +371,389 @@ impl<S: SpadeNum + na::Scalar + na::Scalar> PointN for na::Point4<S> {
         na::Point4::new(value.clone(), value.clone(), value.clone(), value)
     }
 }
+
+#[cfg(feature = "mint")]
+impl<S: SpadeNum + Copy> PointN for mint::Point2<S> {
+    type Scalar = S;
+
+    fn dimensions() -> usize {
+        2
+    }
+
+    fn nth(&self, index: usize) -> &S {
+        &AsRef::<[S; 2]>::as_ref(self)[index]
+    }
+    fn nth_mut(&mut self, index: usize) -> &mut S {
+        &mut AsMut::<[S; 2]>::as_mut(self)[index]
+    }
+
+    fn from_value(value: Self::Scalar) -> Self {
+        mint::Point2::from([value; 2])
+    }
+}
+
+#[cfg(feature = "mint")]
+impl<S: SpadeNum + Copy> PointN for mint::Point3<S> {
+    type Scalar = S;
+
+    fn dimensions() -> usize {
+        3
+    }
+
+    fn nth(&self, index: usize) -> &S {
+        &AsRef::<[S; 3]>::as_ref(self)[index]
+    }
+    fn nth_mut(&mut self, index: usize) -> &mut S {
+        &mut AsMut::<[S; 3]>::as_mut(self)[index]
+    }
+
+    fn from_value(value: Self::Scalar) -> Self {
+        mint::Point3::from([value; 3])
+    }
+}
+
+#[cfg(feature = "glam")]
+macro_rules! impl_glam_point {
+    ($point:ty, $scalar:ty, $dimensions:expr, $dimension_trait:ident) => {
+        impl PointN for $point {
+            type Scalar = $scalar;
+
+            fn dimensions() -> usize {
+                $dimensions
+            }
+
+            fn nth(&self, index: usize) -> &$scalar {
+                &self[index]
+            }
+            fn nth_mut(&mut self, index: usize) -> &mut $scalar {
+                &mut self[index]
+            }
+
+            fn from_value(value: Self::Scalar) -> Self {
+                <$point>::splat(value)
+            }
+        }
+
+        impl $dimension_trait for $point {}
+    };
+}
+
+#[cfg(feature = "glam")]
+impl_glam_point!(glam::Vec2, f32, 2, TwoDimensional);
+#[cfg(feature = "glam")]
+impl_glam_point!(glam::DVec2, f64, 2, TwoDimensional);
+#[cfg(feature = "glam")]
+impl_glam_point!(glam::Vec3, f32, 3, ThreeDimensional);
+#[cfg(feature = "glam")]
+impl_glam_point!(glam::DVec3, f64, 3, ThreeDimensional);
+
+#[cfg(feature = "geo-types")]
+impl<S: SpadeNum + geo_types::CoordNum> PointN for geo_types::Coord<S> {
+    type Scalar = S;
+
+    fn dimensions() -> usize {
+        2
+    }
+
+    fn nth(&self, index: usize) -> &S {
+        match index {
+            0 => &self.x,
+            1 => &self.y,
+            _ => panic!("Index out of bounds: {}", index),
+        }
+    }
+    fn nth_mut(&mut self, index: usize) -> &mut S {
+        match index {
+            0 => &mut self.x,
+            1 => &mut self.y,
+            _ => panic!("Index out of bounds: {}", index),
+        }
+    }
+
+    fn from_value(value: Self::Scalar) -> Self {
+        geo_types::Coord { x: value, y: value }
+    }
+}
+
+#[cfg(feature = "geo-types")]
+impl<S: SpadeNum + geo_types::CoordNum> TwoDimensional for geo_types::Coord<S> {}
+
+#[cfg(feature = "geo-types")]
+impl<S: SpadeNum + geo_types::CoordNum> PointN for geo_types::Point<S> {
+    type Scalar = S;
+
+    fn dimensions() -> usize {
+        2
+    }
+
+    fn nth(&self, index: usize) -> &S {
+        self.0.nth(index)
+    }
+    fn nth_mut(&mut self, index: usize) -> &mut S {
+        self.0.nth_mut(index)
+    }
+
+    fn from_value(value: Self::Scalar) -> Self {
+        geo_types::Point(geo_types::Coord::from_value(value))
+    }
+}
+
+#[cfg(feature = "geo-types")]
+impl<S: SpadeNum + geo_types::CoordNum> TwoDimensional for geo_types::Point<S> {}
+
+#[cfg(feature = "euclid")]
+impl<S: SpadeNum + Copy, U> PointN for euclid::Point2D<S, U> {
+    type Scalar = S;
+
+    fn dimensions() -> usize {
+        2
+    }
+
+    fn nth(&self, index: usize) -> &S {
+        match index {
+            0 => &self.x,
+            1 => &self.y,
+            _ => panic!("Index out of bounds: {}", index),
+        }
+    }
+    fn nth_mut(&mut self, index: usize) -> &mut S {
+        match index {
+            0 => &mut self.x,
+            1 => &mut self.y,
+            _ => panic!("Index out of bounds: {}", index),
+        }
+    }
+
+    fn from_value(value: Self::Scalar) -> Self {
+        euclid::Point2D::splat(value)
+    }
+}
+
+#[cfg(feature = "euclid")]
+impl<S: SpadeNum + Copy, U> TwoDimensional for euclid::Point2D<S, U> {}
+
+#[cfg(feature = "euclid")]
+impl<S: SpadeNum + Copy, U> PointN for euclid::Point3D<S, U> {
+    type Scalar = S;
+
+    fn dimensions() -> usize {
+        3
+    }
+
+    fn nth(&self, index: usize) -> &S {
+        match index {
+            0 => &self.x,
+            1 => &self.y,
+            2 => &self.z,
+            _ => panic!("Index out of bounds: {}", index),
+        }
+    }
+    fn nth_mut(&mut self, index: usize) -> &mut S {
+        match index {
+            0 => &mut self.x,
+            1 => &mut self.y,
+            2 => &mut self.z,
+            _ => panic!("Index out of bounds: {}", index),
+        }
+    }
+
+    fn from_value(value: Self::Scalar) -> Self {
+        euclid::Point3D::splat(value)
+    }
+}
+
+#[cfg(feature = "euclid")]
+impl<S: SpadeNum + Copy, U> ThreeDimensional for euclid::Point3D<S, U> {}
+
+#[cfg(test)]
+mod test {
+    use super::{PointN, PointNExtensions, ThreeDimensional};
+    use crate::delaunay::FloatDelaunayTriangulation;
+    use crate::rtree::RTree;
+
+    #[test]
+    fn test_array_points() {
+        let mut p = [1.0, 2.0, 3.0, 4.0, 5.0, 6.0];
+        assert_eq!(<[f64; 6]>::dimensions(), 6);
+        assert_eq!(*p.nth(5), 6.0);
+        *p.nth_mut(0) = 0.0;
+        assert_eq!(p.length2(), 90.0);
+        assert_eq!(<[i32; 5]>::from_value(2), [2; 5]);
+        assert_eq!([1, 2, 3, 4, 5].max_point(&[5, 4, 3, 2, 1]), [5, 4, 3, 4, 5]);
+    }
+
+    #[test]
+    fn test_tuple_points() {
+        let mut p = (1.0, 2.0);
+        assert_eq!(<(f64, f64)>::dimensions(), 2);
+        *p.nth_mut(1) = 4.0;
+        assert_eq!(p.length2(), 17.0);
+        assert_eq!(<(i32, i32, i32)>::from_value(3), (3, 3, 3));
+        assert_eq!((1, 0, 0).cross(&(0, 1, 0)), (0, 0, 1));
+
+        let mut delaunay = FloatDelaunayTriangulation::with_walk_locate();
+        for point in [(0.0, 0.0), (1.0, 0.0), (0.0, 1.0), (1.0, 1.0)] {
+            delaunay.insert(point);
+        }
+        assert_eq!(delaunay.num_triangles(), 2);
+        let tree = RTree::bulk_load(vec![(0.0, 0.0), (3.0, 1.0)]);
+        assert_eq!(tree.nearest_neighbor(&(2.0, 2.0)), Some(&(3.0, 1.0)));
+    }
+
+    #[test]
+    fn test_high_dimensional_rtree() {
+        let mut tree = RTree::new();
+        for i in 0..100 {
+            let mut point = [0.0; 8];
+            point[i % 8] = i as f64;
+            tree.insert(point);
+        }
+        let mut query = [0.0; 8];
+        query[3] = 42.0;
+        assert_eq!(tree.nearest_neighbor(&query).unwrap()[3], 43.0);
+        assert_eq!(tree.size(), 100);
+    }
+}
+
+#[cfg(all(test, feature = "mint"))]
+mod mint_test {
+    use super::{PointN, PointNExtensions, ThreeDimensional};
+    use crate::delaunay::FloatDelaunayTriangulation;
+    use crate::rtree::RTree;
+    use mint::{Point2, Point3};
+
+    #[test]
+    fn test_mint_points() {
+        let mut p = Point2 { x: 1.0, y: 2.0 };
+        assert_eq!(*p.nth(1), 2.0);
+        *p.nth_mut(0) = 3.0;
+        assert_eq!(p, Point2 { x: 3.0, y: 2.0 });
+        assert_eq!(Point2::from_value(1), Point2 { x: 1, y: 1 });
+        assert_eq!(p.length2(), 13.0);
+
+        let x = Point3 { x: 1, y: 0, z: 0 };
+        let y = Point3 { x: 0, y: 1, z: 0 };
+        assert_eq!(x.cross(&y), Point3 { x: 0, y: 0, z: 1 });
+        assert_eq!(Point3::<i32>::dimensions(), 3);
+    }
+
+    #[test]
+    fn test_mint_datastructures() {
+        let points = vec![
+            Point2 { x: 0.0, y: 0.0 },
+            Point2 { x: 1.0, y: 0.0 },
+            Point2 { x: 0.0, y: 1.0 },
+            Point2 { x: 1.0, y: 1.0 },
+        ];
+        let tree = RTree::bulk_load(points.clone());
+        assert_eq!(
+            tree.nearest_neighbor(&Point2 { x: 0.9, y: 0.8 }),
+            Some(&Point2 { x: 1.0, y: 1.0 })
+        );
+        let mut delaunay = FloatDelaunayTriangulation::with_tree_locate();
+        for point in points {
+            delaunay.insert(point);
+        }
+        assert_eq!(delaunay.num_triangles(), 2);
+    }
+}
+
+#[cfg(all(test, feature = "glam"))]
+mod glam_test {
+    use super::{PointN, PointNExtensions, ThreeDimensional};
+    use crate::delaunay::FloatDelaunayTriangulation;
+    use crate::kernels::FloatKernel;
+    use crate::primitives::SimpleTriangle;
+    use crate::rtree::RTree;
+    use glam::{DVec2, DVec3, Vec2, Vec3};
+
+    #[test]
+    fn test_glam_points() {
+        let mut p = DVec2::new(1.0, 2.0);
+        assert_eq!(*p.nth(1), 2.0);
+        *p.nth_mut(0) = 3.0;
+        assert_eq!(p, DVec2::new(3.0, 2.0));
+        assert_eq!(PointNExtensions::length2(&p), 13.0);
+        assert_eq!(<Vec2 as PointN>::from_value(1.0), Vec2::ONE);
+
+        assert_eq!(Vec3::X.cross(Vec3::Y), Vec3::Z);
+        assert_eq!(ThreeDimensional::cross(&DVec3::X, &DVec3::Y), DVec3::Z);
+        assert_eq!(<Vec3 as PointN>::dimensions(), 3);
+    }
+
+    #[test]
+    fn test_glam_datastructures() {
+        let points = vec![
+            DVec2::new(0.0, 0.0),
+            DVec2::new(1.0, 0.0),
+            DVec2::new(0.0, 1.0),
+            DVec2::new(1.0, 1.0),
+        ];
+        let tree = RTree::bulk_load(points.clone());
+        assert_eq!(
+            tree.nearest_neighbor(&DVec2::new(0.9, 0.8)),
+            Some(&DVec2::new(1.0, 1.0))
+        );
+        let mut delaunay = FloatDelaunayTriangulation::with_tree_locate();
+        for point in points {
+            delaunay.insert(point);
+        }
+        assert_eq!(delaunay.num_triangles(), 2);
+
+        let triangle = SimpleTriangle::new(
+            Vec2::new(0.0, 0.0),
+            Vec2::new(2.0, 0.0),
+            Vec2::new(0.0, 2.0),
+        );
+        assert_eq!(triangle.double_area(), 4.0);
+        assert!(triangle.contains_point::<FloatKernel>(&Vec2::new(0.5, 0.5)));
+    }
+}
+
+#[cfg(all(test, feature = "euclid"))]
+mod euclid_test {
+    use super::{PointN, PointNExtensions, ThreeDimensional};
+    use crate::delaunay::FloatDelaunayTriangulation;
+    use crate::rtree::RTree;
+    use euclid::{Point2D, Point3D};
+
+    #[derive(Debug)]
+    struct WorldSpace;
+
+    type WorldPoint = Point2D<f64, WorldSpace>;
+
+    #[test]
+    fn test_euclid_points() {
+        let mut p = WorldPoint::new(1.0, 2.0);
+        assert_eq!(*p.nth(1), 2.0);
+        *p.nth_mut(0) = 3.0;
+        assert_eq!(p, WorldPoint::new(3.0, 2.0));
+        assert_eq!(p.length2(), 13.0);
+        assert_eq!(WorldPoint::from_value(1.0), WorldPoint::splat(1.0));
+
+        let x = Point3D::<i32, WorldSpace>::new(1, 0, 0);
+        let y = Point3D::new(0, 1, 0);
+        assert_eq!(x.cross(&y), Point3D::new(0, 0, 1));
+        assert_eq!(Point3D::<i32, WorldSpace>::dimensions(), 3);
+    }
+
+    #[test]
+    fn test_euclid_datastructures() {
+        let points: Vec<WorldPoint> = vec![
+            Point2D::new(0.0, 0.0),
+            Point2D::new(1.0, 0.0),
+            Point2D::new(0.0, 1.0),
+            Point2D::new(1.0, 1.0),
+        ];
+        let tree = RTree::bulk_load(points.clone());
+        let nearest: Option<&WorldPoint> = tree.nearest_neighbor(&Point2D::new(0.9, 0.8));
+        assert_eq!(nearest, Some(&Point2D::new(1.0, 1.0)));
+        let mut delaunay = FloatDelaunayTriangulation::with_tree_locate();
+        for point in points {
+            delaunay.insert(point);
+        }
+        assert_eq!(delaunay.num_triangles(), 2);
+        let vertex: &WorldPoint = &delaunay.nearest_neighbor(&Point2D::new(0.1, 0.2)).unwrap();
+        assert_eq!(*vertex, Point2D::new(0.0, 0.0));
+    }
+}
@@ -0,0 +1,2120 @@
+// Copyright 2017 The Spade Developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Algorithms for polygons.
+//!
+//! Polygons are given as slices of their vertices, the last vertex is
+//! implicitly connected to the first one. See `spade::primitives::SimplePolygon`
+//! for a polygon type. Polylines are given as slices of their vertices as well,
+//! without the closing edge.
+
+use crate::boundingrect::BoundingRect;
+use crate::delaunay::{
+    ConstrainedDelaunayTriangulation, DelaunayWalkLocate, FixedEdgeHandle, FixedVertexHandle,
+};
+use crate::intersections::segment_intersections;
+use crate::kernels::{DelaunayKernel, FloatKernel};
+use crate::point_traits::{PointN, PointNExtensions, TwoDimensional};
+use crate::primitives::{SegmentIntersection, SimpleEdge, SimplePolygon};
+use crate::traits::{SpadeFloat, SpatialObject};
+use crate::validation::validate_point;
+use alloc::{vec, vec::Vec};
+use core::cmp::Ordering;
+use core::fmt;
+use num::{one, zero, Float, FromPrimitive, NumCast, ToPrimitive};
+
+/// Returns the signed area of a polygon.
+///
+/// The area is positive if the vertices are ordered counterclockwise and
+/// negative if they are ordered clockwise.
+pub fn signed_area<V>(polygon: &[V]) -> V::Scalar
+where
+    V: TwoDimensional,
+    V::Scalar: SpadeFloat,
+{
+    let two: V::Scalar = NumCast::from(2.0).unwrap();
+    double_signed_area(polygon) / two
+}
+
+/// Returns the area of a polygon, independent of its orientation.
+pub fn area<V>(polygon: &[V]) -> V::Scalar
+where
+    V: TwoDimensional,
+    V::Scalar: SpadeFloat,
+{
+    signed_area(polygon).abs()
+}
+
+/// Returns `true` if a polygon's vertices are ordered counterclockwise.
+///
+/// Returns `false` for clockwise ordered polygons and for polygons with
+/// an area of zero.
+pub fn is_ordered_ccw<V: TwoDimensional>(polygon: &[V]) -> bool {
+    double_signed_area(polygon) > zero()
+}
+
+/// Returns a polygon's centroid, its center of mass.
+///
+/// Returns `None` if the polygon's area is zero.
+pub fn centroid<V>(polygon: &[V]) -> Option<V>
+where
+    V: TwoDimensional,
+    V::Scalar: SpadeFloat,
+{
+    let n = polygon.len();
+    if n < 3 {
+        return None;
+    }
+    // Relative coordinates reduce the rounding error for polygons far away from the origin
+    let origin = &polygon[0];
+    let mut double_area: V::Scalar = zero();
+    let mut sum = V::new();
+    for i in 1..n - 1 {
+        let (p, q) = (polygon[i].sub(origin), polygon[i + 1].sub(origin));
+        let weight = cross(&p, &q);
+        double_area += weight;
+        sum = sum.add(&p.add(&q).mul(weight));
+    }
+    if double_area == zero() {
+        return None;
+    }
+    let three: V::Scalar = NumCast::from(3.0).unwrap();
+    Some(origin.add(&sum.div(three * double_area)))
+}
+
+/// Returns the winding number of a polygon's boundary around a point.
+///
+/// The winding number counts how often the boundary winds counterclockwise
+/// around the point, it is `0` for points outside of a simple polygon.
+/// Points on the boundary may be considered to lie on either side.
+/// The kernel `K` determines how orientation tests are performed,
+/// see `spade::kernels`.
+pub fn winding_number<K, V>(polygon: &[V], point: &V) -> i32
+where
+    K: DelaunayKernel<V::Scalar>,
+    V: TwoDimensional,
+{
+    let n = polygon.len();
+    let y = point.nth(1);
+    let mut result = 0;
+    for i in 0..n {
+        let edge = SimpleEdge::new(polygon[i].clone(), polygon[(i + 1) % n].clone());
+        if edge.from.nth(1) <= y {
+            if edge.to.nth(1) > y && K::side_query(&edge, point).is_on_left_side() {
+                result += 1;
+            }
+        } else if edge.to.nth(1) <= y && K::side_query(&edge, point).is_on_right_side() {
+            result -= 1;
+        }
+    }
+    result
+}
+
+/// Returns `true` if a polygon is convex.
+///
+/// The polygon can be ordered clockwise or counterclockwise. Collinear
+/// consecutive vertices are allowed. Polygons with an area of zero are not
+/// considered to be convex.
+pub fn is_convex<V: TwoDimensional>(polygon: &[V]) -> bool {
+    let n = polygon.len();
+    if n < 3 {
+        return false;
+    }
+    let double_area = double_signed_area(polygon);
+    if double_area == zero() {
+        return false;
+    }
+    let has_orientation = |value: V::Scalar| {
+        if double_area > zero() {
+            value >= zero()
+        } else {
+            value <= zero()
+        }
+    };
+    let first = &polygon[0];
+    // All turns must point into the same direction. Additionally, the boundary
+    // must wind around the polygon only once.
+    (0..n).all(|i| {
+        let (v0, v1, v2) = (&polygon[i], &polygon[(i + 1) % n], &polygon[(i + 2) % n]);
+        has_orientation(cross(&v1.sub(v0), &v2.sub(v1)))
+    }) && (1..n - 1)
+        .all(|i| has_orientation(cross(&polygon[i].sub(first), &polygon[i + 1].sub(first))))
+}
+
+/// Calculates the Minkowski sum of two convex polygons.
+///
+/// The result contains all sums `p + q` of a point `p` of the first and a
+/// point `q` of the second polygon. The polygons can be ordered clockwise or
+/// counterclockwise. The result is ordered counterclockwise, starting with its
+/// lowest vertex. Degenerate polygons, e.g. single points or edges, are supported.
+///
+/// Runs in `O(n + m)`.
+///
+/// # Example
+///
+/// ```
+/// # extern crate nalgebra;
+/// # extern crate spade;
+/// use nalgebra::Point2;
+/// use spade::polygon::minkowski_sum;
+///
+/// # fn main() {
+/// let square = vec![
+///     Point2::new(0, 0),
+///     Point2::new(1, 0),
+///     Point2::new(1, 1),
+///     Point2::new(0, 1),
+/// ];
+/// let triangle = vec![Point2::new(0, 0), Point2::new(2, 0), Point2::new(0, 2)];
+/// let sum = minkowski_sum(&square, &triangle);
+/// assert_eq!(
+///     sum,
+///     vec![
+///         Point2::new(0, 0),
+///         Point2::new(3, 0),
+///         Point2::new(3, 1),
+///         Point2::new(1, 3),
+///         Point2::new(0, 3),
+///     ]
+/// );
+/// # }
+/// ```
+pub fn minkowski_sum<V>(first: &[V], second: &[V]) -> Vec<V>
+where
+    V: TwoDimensional,
+{
+    if first.is_empty() || second.is_empty() {
+        return Vec::new();
+    }
+    let first = ccw_from_lowest(first);
+    let second = ccw_from_lowest(second);
+    let (n, m) = (first.len(), second.len());
+    let (mut i, mut j) = (0, 0);
+    let mut result = Vec::with_capacity(n + m);
+    while i < n || j < m {
+        result.push(first[i % n].add(&second[j % m]));
+        if i == n {
+            j += 1;
+        } else if j == m {
+            i += 1;
+        } else {
+            let first_edge = first[(i + 1) % n].sub(&first[i]);
+            let second_edge = second[(j + 1) % m].sub(&second[j]);
+            match cross(&first_edge, &second_edge).partial_cmp(&zero()) {
+                Some(Ordering::Greater) => i += 1,
+                Some(Ordering::Less) => j += 1,
+                _ => {
+                    i += 1;
+                    j += 1;
+                }
+            }
+        }
+    }
+    result.dedup();
+    result
+}
+
+/// Enlarges a convex polygon by a radius.
+///
+/// Approximates the Minkowski sum of the polygon and a circle. Each of the
+/// polygon's corners is rounded by a circular arc, which is approximated with
+/// segments spanning at most `2π / segments_per_circle` radians. The result
+/// encloses the exact buffer, making it suitable for conservative obstacle
+/// expansion. The result is ordered counterclockwise.
+///
+/// # Panics
+/// Panics if `segments_per_circle` is smaller than three.
+pub fn buffer_convex<V>(polygon: &[V], radius: V::Scalar, segments_per_circle: usize) -> Vec<V>
+where
+    V: TwoDimensional,
+    V::Scalar: SpadeFloat,
+{
+    assert!(
+        segments_per_circle >= 3,
+        "At least three segments per circle are required"
+    );
+    let polygon = ccw_from_lowest(polygon);
+    let two: V::Scalar = NumCast::from(2.0).unwrap();
+    let pi: V::Scalar = NumCast::from(::core::f64::consts::PI).unwrap();
+    let max_step = two * pi / NumCast::from(segments_per_circle).unwrap();
+    let n = polygon.len();
+    let outward_normal_angle = |from: &V, to: &V| {
+        let dir = to.sub(from);
+        // The outward normal of a counterclockwise polygon points to the right
+        (-*dir.nth(0)).atan2(*dir.nth(1))
+    };
+    let at_angle = |center: &V, angle: V::Scalar, distance: V::Scalar| {
+        let (sin, cos) = angle.sin_cos();
+        let mut result = center.clone();
+        *result.nth_mut(0) += distance * cos;
+        *result.nth_mut(1) += distance * sin;
+        result
+    };
+    let mut result = Vec::new();
+    for i in 0..n {
+        let vertex = &polygon[i];
+        let (prev, next) = (&polygon[(i + n - 1) % n], &polygon[(i + 1) % n]);
+        let (start, mut sweep) = if n == 1 {
+            (zero(), two * pi)
+        } else if n == 2 {
+            let start = outward_normal_angle(prev, vertex);
+            (start, pi)
+        } else {
+            let start = outward_normal_angle(prev, vertex);
+            (start, outward_normal_angle(vertex, next) - start)
+        };
+        while sweep < zero() {
+            sweep += two * pi;
+        }
+        let steps = (sweep / max_step).ceil();
+        // A single point has no tangent points, its buffer is a regular polygon
+        if n > 1 {
+            result.push(at_angle(vertex, start, radius));
+        }
+        if steps > zero() {
+            let step = sweep / steps;
+            // Corners of the circumscribed polygon lie between the tangent points
+            let distance = radius / (step / two).cos();
+            let mut index: V::Scalar = zero();
+            while index < steps {
+                let angle = start + (index + one::<V::Scalar>() / two) * step;
+                result.push(at_angle(vertex, angle, distance));
+                index += one();
+            }
+            if n > 1 {
+                result.push(at_angle(vertex, start + sweep, radius));
+            }
+        }
+    }
+    result.dedup();
+    result
+}
+
+/// Clips a polygon against a convex window.
+///
+/// Uses the Sutherland-Hodgman algorithm. Returns the part of `subject` that
+/// lies within `window`, keeping the subject's orientation. The window can be
+/// ordered clockwise or counterclockwise. The result is empty if the polygons
+/// do not overlap. If the subject is not convex and its clipped part consists
+/// of several components, these are connected by edges along the window's
+/// boundary.
+///
+/// # Example
+///
+/// ```
+/// # extern crate nalgebra;
+/// # extern crate spade;
+/// use nalgebra::Point2;
+/// use spade::polygon::clip_to_convex;
+///
+/// # fn main() {
+/// let window = vec![Point2::new(0.0, 0.0), Point2::new(2.0, 0.0), Point2::new(0.0, 2.0)];
+/// let subject = vec![
+///     Point2::new(1.0, -1.0),
+///     Point2::new(3.0, -1.0),
+///     Point2::new(3.0, 3.0),
+///     Point2::new(1.0, 3.0),
+/// ];
+/// let clipped = clip_to_convex(&subject, &window);
+/// assert_eq!(
+///     clipped,
+///     vec![Point2::new(1.0, 1.0), Point2::new(1.0, 0.0), Point2::new(2.0, 0.0)]
+/// );
+/// # }
+/// ```
+pub fn clip_to_convex<V>(subject: &[V], window: &[V]) -> Vec<V>
+where
+    V: TwoDimensional,
+    V::Scalar: SpadeFloat,
+{
+    let window = ccw_from_lowest(window);
+    let mut result = subject.to_vec();
+    for (index, from) in window.iter().enumerate() {
+        if result.is_empty() {
+            break;
+        }
+        let to = &window[(index + 1) % window.len()];
+        let dir = to.sub(from);
+        if dir.length2() == zero() {
+            continue;
+        }
+        let input = ::core::mem::replace(&mut result, Vec::with_capacity(subject.len() + 4));
+        for (i, p) in input.iter().enumerate() {
+            let q = &input[(i + 1) % input.len()];
+            let side_p = cross(&dir, &p.sub(from));
+            let side_q = cross(&dir, &q.sub(from));
+            if side_p >= zero() {
+                result.push(p.clone());
+            }
+            if (side_p > zero() && side_q < zero()) || (side_p < zero() && side_q > zero()) {
+                let s = side_p / (side_p - side_q);
+                result.push(p.add(&q.sub(p).mul(s)));
+            }
+        }
+        result.dedup();
+        while result.len() > 1 && result.first() == result.last() {
+            result.pop();
+        }
+    }
+    result
+}
+
+/// Clips a polygon against a bounding rectangle.
+///
+/// See `clip_to_convex` for more details.
+pub fn clip_to_rect<V>(subject: &[V], rect: &BoundingRect<V>) -> Vec<V>
+where
+    V: TwoDimensional,
+    V::Scalar: SpadeFloat,
+{
+    let (lower, upper) = (rect.lower(), rect.upper());
+    let mut lower_right = lower.clone();
+    *lower_right.nth_mut(0) = *upper.nth(0);
+    let mut upper_left = upper.clone();
+    *upper_left.nth_mut(0) = *lower.nth(0);
+    clip_to_convex(subject, &[lower, lower_right, upper, upper_left])
+}
+
+/// Describes why a polygon is invalid.
+///
+/// Returned by `validate_polygon`. Vertices are referred to by their index,
+/// edge `i` connects vertex `i` and vertex `i + 1`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum PolygonError {
+    /// The polygon has less than three vertices.
+    TooFewVertices,
+    /// A vertex has a NaN or infinite coordinate.
+    NonFiniteCoordinate(usize),
+    /// Two vertices share the same position.
+    DuplicateVertex(usize, usize),
+    /// Two edges intersect. Adjacent edges intersect if they overlap.
+    SelfIntersection(usize, usize),
+    /// The polygon is valid but its vertices are ordered clockwise.
+    ClockwiseOrientation,
+}
+
+impl fmt::Display for PolygonError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PolygonError::TooFewVertices => write!(f, "polygon has less than three vertices"),
+            PolygonError::NonFiniteCoordinate(vertex) => {
+                write!(f, "vertex {} has a NaN or infinite coordinate", vertex)
+            }
+            PolygonError::DuplicateVertex(first, second) => {
+                write!(f, "vertices {} and {} are equal", first, second)
+            }
+            PolygonError::SelfIntersection(first, second) => {
+                write!(f, "edges {} and {} intersect", first, second)
+            }
+            PolygonError::ClockwiseOrientation => write!(f, "polygon is ordered clockwise"),
+        }
+    }
+}
+
+impl ::core::error::Error for PolygonError {}
+
+/// Checks if a polygon is simple and ordered counterclockwise.
+///
+/// The checks are performed in the order of `PolygonError`'s variants and
+/// the first failing check is reported. If several vertices or edges fail the
+/// same check, the error with the smallest indices is returned. The
+/// orientation is checked last, callers that accept either orientation
+/// can ignore `PolygonError::ClockwiseOrientation`.
+///
+/// The ring must not be closed explicitly, repeating the first vertex at the
+/// end is reported as a duplicate vertex. Edge intersections are found by
+/// sweeping over the edges sorted by their x coordinates, which runs in
+/// `O(n log n + k)` for `k` pairs of edges with overlapping x ranges.
+///
+/// # Example
+///
+/// ```
+/// # extern crate nalgebra;
+/// # extern crate spade;
+/// use nalgebra::Point2;
+/// use spade::polygon::{validate_polygon, PolygonError};
+///
+/// # fn main() {
+/// let bowtie = vec![
+///     Point2::new(0.0, 0.0),
+///     Point2::new(1.0, 1.0),
+///     Point2::new(1.0, 0.0),
+///     Point2::new(0.0, 1.0),
+/// ];
+/// assert_eq!(validate_polygon(&bowtie), Err(PolygonError::SelfIntersection(0, 2)));
+/// # }
+/// ```
+pub fn validate_polygon<V>(polygon: &[V]) -> Result<(), PolygonError>
+where
+    V: TwoDimensional,
+    V::Scalar: SpadeFloat + FromPrimitive,
+{
+    let n = polygon.len();
+    if n < 3 {
+        return Err(PolygonError::TooFewVertices);
+    }
+    if let Some(vertex) = polygon.iter().position(|p| validate_point(p).is_err()) {
+        return Err(PolygonError::NonFiniteCoordinate(vertex));
+    }
+    let mut sorted: Vec<_> = (0..n).collect();
+    sorted.sort_by(|i, j| polygon[*i].lex_compare(&polygon[*j]).then(i.cmp(j)));
+    let duplicate = sorted
+        .windows(2)
+        .filter(|pair| polygon[pair[0]] == polygon[pair[1]])
+        .map(|pair| (pair[0].min(pair[1]), pair[0].max(pair[1])))
+        .min();
+    if let Some((first, second)) = duplicate {
+        return Err(PolygonError::DuplicateVertex(first, second));
+    }
+    let edges: Vec<_> = (0..n)
+        .map(|i| SimpleEdge::new(polygon[i].clone(), polygon[(i + 1) % n].clone()))
+        .collect();
+    let intersection = x_overlapping_pairs(&edges)
+        .into_iter()
+        .filter(|(i, j)| {
+            let adjacent = i + 1 == *j || (*i == 0 && *j == n - 1);
+            match edges[*i].classify_intersection::<FloatKernel>(&edges[*j]) {
+                SegmentIntersection::Disjoint => false,
+                SegmentIntersection::CollinearOverlap(_) => true,
+                _ => !adjacent,
+            }
+        })
+        .min();
+    if let Some((first, second)) = intersection {
+        return Err(PolygonError::SelfIntersection(first, second));
+    }
+    if double_signed_area(polygon) < zero() {
+        return Err(PolygonError::ClockwiseOrientation);
+    }
+    Ok(())
+}
+
+/// A polygon that is prepared for fast containment queries.
+///
+/// The plane is divided into horizontal slabs at the y coordinates of the
+/// polygon's vertices. Within a slab, no edges start or end and the crossing
+/// edges are sorted from left to right. A containment query locates the slab
+/// and counts the edges left of the point, both with a binary search.
+///
+/// Construction runs in `O(n log n + k)`, queries run in `O(log n)`. `k` is
+/// the total number of edges stored in all slabs. It is usually far less than
+/// its worst case of `O(n²)`.
+///
+/// # Example
+///
+/// ```
+/// # extern crate nalgebra;
+/// # extern crate spade;
+/// use nalgebra::Point2;
+/// use spade::kernels::FloatKernel;
+/// use spade::polygon::PreparedPolygon;
+///
+/// # fn main() {
+/// let triangle = vec![Point2::new(0.0, 0.0), Point2::new(4.0, 0.0), Point2::new(0.0, 4.0)];
+/// let prepared = PreparedPolygon::new(&triangle);
+/// assert!(prepared.contains_point::<FloatKernel>(&Point2::new(1.0, 1.0)));
+/// assert!(prepared.contains_point::<FloatKernel>(&Point2::new(2.0, 2.0)));
+/// assert!(!prepared.contains_point::<FloatKernel>(&Point2::new(3.0, 3.0)));
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct PreparedPolygon<V: PointN> {
+    ys: Vec<V::Scalar>,
+    /// All edges crossing the slab between `ys[i]` and `ys[i + 1]`, pointing upwards.
+    slabs: Vec<Vec<SimpleEdge<V>>>,
+    /// All edges with an end point at `ys[i]`.
+    touching: Vec<Vec<SimpleEdge<V>>>,
+}
+
+impl<V> PreparedPolygon<V>
+where
+    V: TwoDimensional,
+    V::Scalar: SpadeFloat,
+{
+    /// Prepares a simple polygon.
+    ///
+    /// The polygon can be ordered clockwise or counterclockwise.
+    pub fn new(polygon: &[V]) -> Self {
+        let n = polygon.len();
+        let mut ys: Vec<_> = polygon.iter().map(|p| *p.nth(1)).collect();
+        ys.sort_by(|y0, y1| y0.partial_cmp(y1).unwrap_or(Ordering::Equal));
+        ys.dedup();
+        let slab_index = |y: &V::Scalar| {
+            ys.binary_search_by(|probe| probe.partial_cmp(y).unwrap_or(Ordering::Equal))
+                .unwrap()
+        };
+        let mut starting = vec![Vec::new(); ys.len()];
+        let mut touching = vec![Vec::new(); ys.len()];
+        for i in 0..n {
+            let (from, to) = (&polygon[i], &polygon[(i + 1) % n]);
+            let edge = if from.nth(1) <= to.nth(1) {
+                SimpleEdge::new(from.clone(), to.clone())
+            } else {
+                SimpleEdge::new(to.clone(), from.clone())
+            };
+            let (low, high) = (slab_index(edge.from.nth(1)), slab_index(edge.to.nth(1)));
+            touching[low].push(edge.clone());
+            if high != low {
+                touching[high].push(edge.clone());
+                starting[low].push((edge, high));
+            }
+        }
+        let two: V::Scalar = NumCast::from(2.0).unwrap();
+        let mut slabs = Vec::with_capacity(ys.len().saturating_sub(1));
+        let mut active: Vec<(SimpleEdge<V>, usize)> = Vec::new();
+        for (index, starting) in starting
+            .into_iter()
+            .enumerate()
+            .take(ys.len().saturating_sub(1))
+        {
+            active.retain(|(_, end)| *end > index);
+            active.extend(starting);
+            let mid = (ys[index] + ys[index + 1]) / two;
+            let x_at_mid = |edge: &SimpleEdge<V>| {
+                let (x0, y0) = (*edge.from.nth(0), *edge.from.nth(1));
+                let (x1, y1) = (*edge.to.nth(0), *edge.to.nth(1));
+                x0 + (mid - y0) * (x1 - x0) / (y1 - y0)
+            };
+            let mut slab: Vec<_> = active.iter().map(|(edge, _)| edge.clone()).collect();
+            slab.sort_by(|e0, e1| {
+                x_at_mid(e0)
+                    .partial_cmp(&x_at_mid(e1))
+                    .unwrap_or(Ordering::Equal)
+            });
+            slabs.push(slab);
+        }
+        PreparedPolygon {
+            ys,
+            slabs,
+            touching,
+        }
+    }
+
+    /// Checks if a point is contained in the polygon or lies on its boundary.
+    ///
+    /// The kernel `K` determines how orientation tests are performed,
+    /// see `spade::kernels`.
+    pub fn contains_point<K>(&self, point: &V) -> bool
+    where
+        K: DelaunayKernel<V::Scalar>,
+    {
+        let y = point.nth(1);
+        let index = self.ys.partition_point(|probe| probe <= y);
+        if index > 0
+            && self.ys[index - 1] == *y
+            && self.touching[index - 1]
+                .iter()
+                .any(|edge| K::point_on_edge(edge, point))
+        {
+            return true;
+        }
+        if index == 0 || index == self.ys.len() {
+            return false;
+        }
+        let slab = &self.slabs[index - 1];
+        let left_edges = slab.partition_point(|edge| K::side_query(edge, point).is_on_right_side());
+        if left_edges < slab.len() && K::point_on_edge(&slab[left_edges], point) {
+            return true;
+        }
+        left_edges % 2 == 1
+    }
+}
+
+/// A boolean operation on two polygons.
+///
+/// Used by `boolean_op`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde_serialize", derive(Serialize, Deserialize))]
+pub enum BooleanOp {
+    /// All points contained in at least one polygon.
+    Union,
+    /// All points contained in both polygons.
+    Intersection,
+    /// All points contained in the first but not in the second polygon.
+    Difference,
+}
+
+/// Calculates the union, intersection or difference of two simple polygons.
+///
+/// Both polygons can be non convex and ordered clockwise or counterclockwise.
+/// Their boundaries are split at their intersections and inserted into a
+/// constrained Delaunay triangulation, each triangle is then classified as
+/// part of the result or not.
+///
+/// Returns the boundaries of the result. Outer boundaries are ordered
+/// counterclockwise, boundaries of holes are ordered clockwise. Vertices lying
+/// on a straight line between their neighbors are removed.
+///
+/// Intersection points are rounded to floating point coordinates. Edges
+/// adjacent to a rounded point are split again if they cross other edges.
+/// Returns `None` if the boundaries still cross after several rounds, which
+/// can only happen for near degenerate input.
+///
+/// Runs in `O(k * (n + m))`, where `n` and `m` are the polygons' numbers of
+/// vertices and `k` is the number of vertices including all intersections:
+/// each triangle of the overlay is classified by testing its center against
+/// both polygons.
+///
+/// # Panics
+/// Panics if any polygon has less than three vertices.
+///
+/// # Example
+///
+/// ```
+/// # extern crate nalgebra;
+/// # extern crate spade;
+/// use nalgebra::Point2;
+/// use spade::polygon::{boolean_op, BooleanOp};
+///
+/// # fn main() {
+/// let square = |x: f64, y: f64| {
+///     vec![
+///         Point2::new(x, y),
+///         Point2::new(x + 2.0, y),
+///         Point2::new(x + 2.0, y + 2.0),
+///         Point2::new(x, y + 2.0),
+///     ]
+/// };
+/// let union = boolean_op(&square(0.0, 0.0), &square(1.0, 1.0), BooleanOp::Union).unwrap();
+/// assert_eq!(union.len(), 1);
+/// assert_eq!(union[0].len(), 8);
+/// let intersection =
+///     boolean_op(&square(0.0, 0.0), &square(1.0, 1.0), BooleanOp::Intersection).unwrap();
+/// assert_eq!(intersection[0].len(), 4);
+/// let disjoint = boolean_op(&square(0.0, 0.0), &square(5.0, 0.0), BooleanOp::Intersection);
+/// assert_eq!(disjoint, Some(Vec::new()));
+/// # }
+/// ```
+pub fn boolean_op<V>(first: &[V], second: &[V], op: BooleanOp) -> Option<Vec<Vec<V>>>
+where
+    V: TwoDimensional,
+    V::Scalar: SpadeFloat + FromPrimitive,
+{
+    let first_polygon = SimplePolygon::new(first.to_vec());
+    let second_polygon = SimplePolygon::new(second.to_vec());
+    let edges: Vec<_> = first_polygon
+        .edges()
+        .chain(second_polygon.edges())
+        .collect();
+    overlay(&edges, |point| {
+        let in_first = first_polygon.contains_point::<FloatKernel>(point);
+        let in_second = second_polygon.contains_point::<FloatKernel>(point);
+        match op {
+            BooleanOp::Union => in_first || in_second,
+            BooleanOp::Intersection => in_first && in_second,
+            BooleanOp::Difference => in_first && !in_second,
+        }
+    })
+}
+
+/// Describes how the offset edges at a polygon's corner are connected.
+///
+/// Used by `offset_polygon` and `offset_polyline`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde_serialize", derive(Serialize, Deserialize))]
+pub enum OffsetJoin<S> {
+    /// Connects the offset edges with a circular arc.
+    ///
+    /// The arc is approximated by a polyline, the value determines how many
+    /// segments a full circle would consist of.
+    Round(usize),
+    /// Extends the offset edges until they meet.
+    ///
+    /// If the corner would lie farther away from the original vertex than
+    /// the given multiple of the offset distance, the corner is cut off instead.
+    Miter(S),
+}
+
+/// Offsets a polygon's boundary by a given distance.
+///
+/// A positive distance grows the polygon, a negative distance shrinks it.
+/// The polygon can be non convex and ordered clockwise or counterclockwise.
+/// Convex corners of a grown polygon, respectively concave corners of a
+/// shrunk polygon, are connected according to `join`.
+///
+/// Returns the boundaries of the result, ordered like the result of
+/// `boolean_op`. Shrinking can split the polygon into several parts or make
+/// it vanish completely. Polygons with an area of zero yield an empty result.
+///
+/// Returns `None` if the raw offset curve's self intersections cannot be
+/// resolved, see `boolean_op`.
+///
+/// Runs in `O(k * n)`, `n` being the number of vertices of the raw offset curve
+/// and `k` the number of vertices including its self intersections.
+///
+/// # Panics
+/// Panics if a round join with less than three segments per circle is used.
+///
+/// # Example
+///
+/// ```
+/// # extern crate nalgebra;
+/// # extern crate spade;
+/// use nalgebra::Point2;
+/// use spade::polygon::{area, offset_polygon, OffsetJoin};
+///
+/// # fn main() {
+/// let square = vec![
+///     Point2::new(0.0, 0.0),
+///     Point2::new(4.0, 0.0),
+///     Point2::new(4.0, 4.0),
+///     Point2::new(0.0, 4.0),
+/// ];
+/// let grown = offset_polygon(&square, 1.0, OffsetJoin::Miter(2.0)).unwrap();
+/// assert_eq!(area(&grown[0]), 36.0);
+/// let shrunk = offset_polygon(&square, -1.0, OffsetJoin::Round(32)).unwrap();
+/// assert_eq!(area(&shrunk[0]), 4.0);
+/// # }
+/// ```
+pub fn offset_polygon<V>(
+    polygon: &[V],
+    distance: V::Scalar,
+    join: OffsetJoin<V::Scalar>,
+) -> Option<Vec<Vec<V>>>
+where
+    V: TwoDimensional,
+    V::Scalar: SpadeFloat + FromPrimitive,
+{
+    let mut ring = polygon.to_vec();
+    ring.dedup();
+    while ring.len() > 1 && ring.first() == ring.last() {
+        ring.pop();
+    }
+    let double_area = double_signed_area(&ring);
+    if double_area == zero() {
+        return Some(Vec::new());
+    }
+    if double_area < zero() {
+        ring.reverse();
+    }
+    if distance == zero() {
+        return Some(vec![ring]);
+    }
+    let raw = raw_offset(&ring, distance, join);
+    overlay(&ring_edges(&raw), |point| {
+        winding_number::<FloatKernel, _>(&raw, point) > 0
+    })
+}
+
+/// Calculates the area within a given distance of a polyline.
+///
+/// Corners are connected according to `join`. Round joins also create round
+/// caps at the polyline's ends, miter joins create flat caps.
+///
+/// Returns the boundaries of the result, ordered like the result of
+/// `boolean_op`. The result is empty if `distance` is not positive or if the
+/// polyline has less than two distinct vertices.
+///
+/// Returns `None` if the raw offset curve's self intersections cannot be
+/// resolved, see `boolean_op`.
+///
+/// Runs in `O(k * n)`, `n` being the number of vertices of the raw offset curve
+/// and `k` the number of vertices including its self intersections.
+///
+/// # Panics
+/// Panics if a round join with less than three segments per circle is used.
+pub fn offset_polyline<V>(
+    polyline: &[V],
+    distance: V::Scalar,
+    join: OffsetJoin<V::Scalar>,
+) -> Option<Vec<Vec<V>>>
+where
+    V: TwoDimensional,
+    V::Scalar: SpadeFloat + FromPrimitive,
+{
+    let mut points = polyline.to_vec();
+    points.dedup();
+    if points.len() < 2 || distance <= zero() {
+        return Some(Vec::new());
+    }
+    // Walking along the polyline and back yields a ring whose offset encloses the polyline
+    let mut ring = points.clone();
+    ring.extend(points[1..points.len() - 1].iter().rev().cloned());
+    let raw = raw_offset(&ring, distance, join);
+    overlay(&ring_edges(&raw), |point| {
+        winding_number::<FloatKernel, _>(&raw, point) > 0
+    })
+}
+
+/// Offsets each edge of a ring to its right side and connects the offset edges.
+///
+/// The result can intersect itself. For counterclockwise rings, the points
+/// with positive winding number form the offset polygon.
+fn raw_offset<V>(ring: &[V], distance: V::Scalar, join: OffsetJoin<V::Scalar>) -> Vec<V>
+where
+    V: TwoDimensional,
+    V::Scalar: SpadeFloat,
+{
+    let n = ring.len();
+    let normal = |from: &V, to: &V| {
+        let dir = to.sub(from);
+        let length = dir.length2().sqrt();
+        let mut result = V::new();
+        *result.nth_mut(0) = *dir.nth(1) / length;
+        *result.nth_mut(1) = -*dir.nth(0) / length;
+        result
+    };
+    let mut result = Vec::new();
+    for i in 0..n {
+        let (prev, current, next) = (&ring[(i + n - 1) % n], &ring[i], &ring[(i + 1) % n]);
+        let (n1, n2) = (normal(prev, current), normal(current, next));
+        let p1 = current.add(&n1.mul(distance));
+        let p2 = current.add(&n2.mul(distance));
+        let turn = cross(&n1, &n2);
+        let dot = n1.dot(&n2);
+        if turn == zero() && dot > zero() {
+            result.push(p1);
+            continue;
+        }
+        if turn * distance < zero() {
+            // The offset edges overlap, passing through the vertex keeps the
+            // overlapping part's winding number positive
+            result.push(p1);
+            result.push(current.clone());
+            result.push(p2);
+            continue;
+        }
+        match join {
+            OffsetJoin::Round(segments_per_circle) => {
+                assert!(
+                    segments_per_circle >= 3,
+                    "At least three segments per circle are required"
+                );
+                let sweep = turn.abs().atan2(dot) * distance.signum();
+                let two_pi: V::Scalar = NumCast::from(2.0 * ::core::f64::consts::PI).unwrap();
+                let segments: V::Scalar = NumCast::from(segments_per_circle).unwrap();
+                let steps = (sweep.abs() * segments / two_pi).ceil().max(one());
+                let offset = n1.mul(distance);
+                result.push(p1);
+                for step in 1..steps.to_usize().unwrap() {
+                    let (sin, cos) = (sweep * NumCast::from(step).unwrap() / steps).sin_cos();
+                    let mut rotated = V::new();
+                    *rotated.nth_mut(0) = *offset.nth(0) * cos - *offset.nth(1) * sin;
+                    *rotated.nth_mut(1) = *offset.nth(0) * sin + *offset.nth(1) * cos;
+                    result.push(current.add(&rotated));
+                }
+                result.push(p2);
+            }
+            OffsetJoin::Miter(limit) => {
+                let one: V::Scalar = one();
+                let two = one + one;
+                let cos_half = ((one + dot) / two).sqrt();
+                if cos_half > zero() && cos_half * limit >= one {
+                    result.push(current.add(&n1.add(&n2).mul(distance / (one + dot))));
+                } else {
+                    result.push(p1);
+                    result.push(p2);
+                }
+            }
+        }
+    }
+    result.dedup();
+    result
+}
+
+fn ring_edges<V: TwoDimensional>(ring: &[V]) -> Vec<SimpleEdge<V>> {
+    let n = ring.len();
+    (0..n)
+        .map(|i| SimpleEdge::new(ring[i].clone(), ring[(i + 1) % n].clone()))
+        .filter(|edge| edge.from != edge.to)
+        .collect()
+}
+
+/// How often `node_edges` splits edges at their crossings before it gives up.
+const MAX_NODING_ROUNDS: usize = 8;
+
+/// Splits edges at their crossings until no two edges cross anymore.
+///
+/// Crossing points are rounded, the edges adjacent to a rounded point may
+/// cross other edges that the original edge did not cross. Such crossings
+/// are split in the next round. Returns `None` if crossings remain after
+/// `MAX_NODING_ROUNDS` rounds.
+fn node_edges<V>(edges: &[SimpleEdge<V>]) -> Option<Vec<SimpleEdge<V>>>
+where
+    V: TwoDimensional,
+    V::Scalar: SpadeFloat + FromPrimitive,
+{
+    let mut edges = edges.to_vec();
+    for _ in 0..MAX_NODING_ROUNDS {
+        let mut splits: Vec<Vec<V>> = vec![Vec::new(); edges.len()];
+        let mut has_crossings = false;
+        for (i, j, intersection) in segment_intersections::<FloatKernel, _>(&edges) {
+            // Touching and overlapping edges meet at existing vertices, the
+            // triangulation splits its constraint edges at these on its own
+            if let SegmentIntersection::Crossing(p) = intersection {
+                splits[i].push(p.clone());
+                splits[j].push(p);
+                has_crossings = true;
+            }
+        }
+        if !has_crossings {
+            return Some(edges);
+        }
+        let mut noded = Vec::with_capacity(edges.len());
+        for (edge, mut points) in edges.iter().zip(splits) {
+            points.push(edge.from.clone());
+            points.push(edge.to.clone());
+            points.sort_by(|p0, p1| {
+                edge.project_point(p0)
+                    .partial_cmp(&edge.project_point(p1))
+                    .unwrap_or(Ordering::Equal)
+            });
+            points.dedup();
+            noded.extend(
+                points
+                    .windows(2)
+                    .map(|pair| SimpleEdge::new(pair[0].clone(), pair[1].clone())),
+            );
+        }
+        edges = noded;
+    }
+    None
+}
+
+/// Calculates the boundaries of an area that is bounded by a set of edges.
+///
+/// The edges are split at their intersections and inserted into a constrained
+/// Delaunay triangulation. A triangle belongs to the area if `is_selected`
+/// returns `true` for its center. Returns `None` if the intersections cannot
+/// be resolved, see `node_edges`.
+fn overlay<V, F>(edges: &[SimpleEdge<V>], is_selected: F) -> Option<Vec<Vec<V>>>
+where
+    V: TwoDimensional,
+    V::Scalar: SpadeFloat + FromPrimitive,
+    F: Fn(&V) -> bool,
+{
+    let edges = node_edges(edges)?;
+    let mut cdt = ConstrainedDelaunayTriangulation::<V, FloatKernel, DelaunayWalkLocate>::new();
+    let handles: Vec<_> = edges
+        .iter()
+        .map(|edge| (cdt.insert(edge.from.clone()), cdt.insert(edge.to.clone())))
+        .collect();
+    for (from, to) in handles {
+        if from == to {
+            continue;
+        }
+        if !cdt.can_add_constraint(from, to) {
+            return None;
+        }
+        cdt.add_constraint(from, to);
+    }
+
+    let three: V::Scalar = NumCast::from(3.0).unwrap();
+    let mut selected = vec![false; cdt.num_faces()];
+    for face in cdt.triangles() {
+        let [v0, v1, v2] = face.as_triangle();
+        let center = v0.add(&*v1).add(&*v2).div(three);
+        selected[face.fix()] = is_selected(&center);
+    }
+
+    let is_boundary = |edge: FixedEdgeHandle| {
+        let edge = cdt.edge(edge);
+        selected[edge.face().fix()] && !selected[edge.sym().face().fix()]
+    };
+    let mut visited = vec![false; cdt.num_edges() * 2];
+    let mut result = Vec::new();
+    for edge in cdt.edges() {
+        for start in [edge.fix(), edge.sym().fix()] {
+            if visited[start] || !is_boundary(start) {
+                continue;
+            }
+            let mut ring = Vec::new();
+            let mut current = start;
+            loop {
+                visited[current] = true;
+                let edge = cdt.edge(current);
+                ring.push((*edge.from()).clone());
+                // Rotate around the edge's end point until the next boundary edge is found
+                let mut next = edge.o_next();
+                while !is_boundary(next.fix()) {
+                    next = next.sym().o_next();
+                }
+                current = next.fix();
+                if current == start {
+                    break;
+                }
+            }
+            let ring = remove_collinear(ring);
+            if ring.len() >= 3 {
+                result.push(ring);
+            }
+        }
+    }
+    Some(result)
+}
+
+/// Decomposes a simple polygon into convex pieces.
+///
+/// Uses the Hertel-Mehlhorn algorithm: The polygon is triangulated with a
+/// constrained Delaunay triangulation, afterwards all diagonals whose removal
+/// keeps the adjacent pieces convex are removed. The number of pieces is at
+/// most four times the optimal number.
+///
+/// The polygon can be ordered clockwise or counterclockwise, the pieces are
+/// ordered counterclockwise.
+///
+/// # Example
+///
+/// ```
+/// # extern crate nalgebra;
+/// # extern crate spade;
+/// use nalgebra::Point2;
+/// use spade::polygon::{convex_decomposition, is_convex};
+///
+/// # fn main() {
+/// let l_shape = vec![
+///     Point2::new(0.0, 0.0),
+///     Point2::new(2.0, 0.0),
+///     Point2::new(2.0, 1.0),
+///     Point2::new(1.0, 1.0),
+///     Point2::new(1.0, 2.0),
+///     Point2::new(0.0, 2.0),
+/// ];
+/// let pieces = convex_decomposition(&l_shape);
+/// assert_eq!(pieces.len(), 2);
+/// assert!(pieces.iter().all(|piece| is_convex(piece)));
+/// # }
+/// ```
+pub fn convex_decomposition<V>(polygon: &[V]) -> Vec<Vec<V>>
+where
+    V: TwoDimensional,
+    V::Scalar: SpadeFloat + FromPrimitive,
+{
+    merge_triangles(polygon, |piece| is_convex(piece))
+}
+
+/// Decomposes a simple polygon into y-monotone pieces.
+///
+/// A polygon is y-monotone if every horizontal line intersects it in at most
+/// one segment. Monotone pieces can be triangulated or scanned in linear time.
+/// The polygon is triangulated with a constrained Delaunay triangulation,
+/// afterwards adjacent triangles are merged as long as the pieces stay monotone.
+///
+/// The polygon can be ordered clockwise or counterclockwise, the pieces are
+/// ordered counterclockwise.
+pub fn monotone_decomposition<V>(polygon: &[V]) -> Vec<Vec<V>>
+where
+    V: TwoDimensional,
+    V::Scalar: SpadeFloat + FromPrimitive,
+{
+    merge_triangles(polygon, |piece| is_y_monotone(piece))
+}
+
+/// Triangulates a polygon and greedily merges adjacent pieces as long as
+/// `accept` returns `true` for the merged piece.
+fn merge_triangles<V, F>(polygon: &[V], accept: F) -> Vec<Vec<V>>
+where
+    V: TwoDimensional,
+    V::Scalar: SpadeFloat + FromPrimitive,
+    F: Fn(&[V]) -> bool,
+{
+    let mut cdt = ConstrainedDelaunayTriangulation::<V, FloatKernel, DelaunayWalkLocate>::new();
+    let handles: Vec<_> = polygon.iter().map(|p| cdt.insert(p.clone())).collect();
+    for (index, from) in handles.iter().enumerate() {
+        let to = handles[(index + 1) % handles.len()];
+        if cdt.can_add_constraint(*from, to) {
+            cdt.add_constraint(*from, to);
+        }
+    }
+    let regions = cdt.classify_regions();
+    let mut piece_of_face = vec![None; cdt.num_faces()];
+    let mut pieces = Vec::new();
+    for face in cdt.triangles() {
+        if regions.is_inside(face.fix()) {
+            piece_of_face[face.fix()] = Some(pieces.len());
+            pieces.push(Some(
+                face.as_triangle()
+                    .iter()
+                    .map(|v| v.fix())
+                    .collect::<Vec<_>>(),
+            ));
+        }
+    }
+    // Pieces that were merged into another piece point to it
+    let mut merged_into: Vec<_> = (0..pieces.len()).collect();
+    let find = |merged_into: &[usize], mut piece: usize| {
+        while merged_into[piece] != piece {
+            piece = merged_into[piece];
+        }
+        piece
+    };
+    let positions = |piece: &[FixedVertexHandle]| -> Vec<V> {
+        piece.iter().map(|v| (*cdt.vertex(*v)).clone()).collect()
+    };
+    for edge in cdt.edges() {
+        if cdt.is_constraint_edge(edge.fix()) {
+            continue;
+        }
+        let (left, right) = match (
+            piece_of_face[edge.face().fix()],
+            piece_of_face[edge.sym().face().fix()],
+        ) {
+            (Some(left), Some(right)) => (find(&merged_into, left), find(&merged_into, right)),
+            _ => continue,
+        };
+        if left == right {
+            continue;
+        }
+        let (from, to) = (edge.from().fix(), edge.to().fix());
+        let merged = {
+            let (left_piece, right_piece) = (
+                pieces[left].as_ref().unwrap(),
+                pieces[right].as_ref().unwrap(),
+            );
+            // The left piece contains the edge from -> to, the right piece to -> from
+            let mut merged = rotated_to(left_piece, to);
+            let right_piece = rotated_to(right_piece, from);
+            merged.extend(&right_piece[1..right_piece.len() - 1]);
+            merged
+        };
+        if accept(&positions(&merged)) {
+            pieces[left] = Some(merged);
+            pieces[right] = None;
+            merged_into[right] = left;
+        }
+    }
+    pieces
+        .iter()
+        .flatten()
+        .map(|piece| positions(piece))
+        .collect()
+}
+
+/// Rotates a cyclic sequence to start with a given element.
+fn rotated_to<T: PartialEq + Clone>(sequence: &[T], first: T) -> Vec<T> {
+    let index = sequence.iter().position(|e| *e == first).unwrap();
+    let mut result = sequence.to_vec();
+    result.rotate_left(index);
+    result
+}
+
+/// Returns `true` if the boundary of a simple polygon consists of one
+/// ascending and one descending chain.
+fn is_y_monotone<V: TwoDimensional>(polygon: &[V]) -> bool {
+    let n = polygon.len();
+    let ascends = |i: usize| {
+        let (p, q) = (&polygon[i], &polygon[(i + 1) % n]);
+        // Ties are broken by the x coordinate
+        (q.nth(1), q.nth(0)) > (p.nth(1), p.nth(0))
+    };
+    (0..n)
+        .filter(|i| ascends(*i) != ascends((i + 1) % n))
+        .count()
+        <= 2
+}
+
+/// Simplifies a polyline with the Douglas-Peucker algorithm.
+///
+/// Returns a subset of the polyline's vertices, including its first and last
+/// vertex. Every removed vertex lies within a distance of `tolerance` to the
+/// simplified polyline. Works in any dimension.
+///
+/// Runs in `O(n log n)` on average and `O(n²)` in the worst case. A simplified
+/// trace is well suited for insertion into an r-tree, e.g. as a set of
+/// `SimpleEdge`s.
+///
+/// # Example
+///
+/// ```
+/// # extern crate nalgebra;
+/// # extern crate spade;
+/// use nalgebra::Point2;
+/// use spade::polygon::simplify_polyline;
+///
+/// # fn main() {
+/// let trace = vec![
+///     Point2::new(0.0, 0.0),
+///     Point2::new(1.0, 0.1),
+///     Point2::new(2.0, -0.1),
+///     Point2::new(3.0, 5.0),
+///     Point2::new(4.0, 6.0),
+/// ];
+/// let simplified = simplify_polyline(&trace, 0.5);
+/// assert_eq!(
+///     simplified,
+///     vec![
+///         Point2::new(0.0, 0.0),
+///         Point2::new(2.0, -0.1),
+///         Point2::new(3.0, 5.0),
+///         Point2::new(4.0, 6.0),
+///     ]
+/// );
+/// assert_eq!(simplify_polyline(&trace, 1.0).len(), 3);
+/// # }
+/// ```
+pub fn simplify_polyline<V>(polyline: &[V], tolerance: V::Scalar) -> Vec<V>
+where
+    V: PointN,
+    V::Scalar: SpadeFloat,
+{
+    let n = polyline.len();
+    if n <= 2 {
+        return polyline.to_vec();
+    }
+    let mut keep = vec![false; n];
+    keep[0] = true;
+    keep[n - 1] = true;
+    douglas_peucker(polyline, 0, n - 1, tolerance * tolerance, &mut keep);
+    kept_vertices(polyline, &keep)
+}
+
+/// Simplifies a closed ring without introducing self intersections.
+///
+/// Behaves like `simplify_polyline`, the last vertex is implicitly connected
+/// to the first one. The result is guaranteed to be simple if the input is:
+/// Removed vertices are inserted again until no two edges of the
+/// simplified ring intersect. The first vertex is always kept, the result
+/// contains at least three vertices if the input does.
+///
+/// Each intersection check runs in `O(k²)`, `k` being the number of
+/// remaining vertices.
+pub fn simplify_ring<V>(ring: &[V], tolerance: V::Scalar) -> Vec<V>
+where
+    V: TwoDimensional,
+    V::Scalar: SpadeFloat + FromPrimitive,
+{
+    let n = ring.len();
+    if n <= 3 {
+        return ring.to_vec();
+    }
+    let tolerance2 = tolerance * tolerance;
+    let farthest = (1..n)
+        .max_by(|i, j| {
+            let (d0, d1) = (ring[0].distance2(&ring[*i]), ring[0].distance2(&ring[*j]));
+            d0.partial_cmp(&d1).unwrap_or(Ordering::Equal)
+        })
+        .unwrap();
+    let mut keep = vec![false; n];
+    keep[0] = true;
+    keep[farthest] = true;
+    // Indices are taken modulo n, `n` refers to the first vertex
+    for (from, to) in [(0, farthest), (farthest, n)] {
+        // Each half keeps at least one vertex, this prevents the ring from collapsing
+        if let Some((index, _)) = farthest_from_segment(ring, from, to) {
+            keep[index] = true;
+            douglas_peucker(ring, from, index, tolerance2, &mut keep);
+            douglas_peucker(ring, index, to, tolerance2, &mut keep);
+        }
+    }
+    loop {
+        let kept: Vec<_> = (0..n).filter(|i| keep[*i]).chain(Some(n)).collect();
+        let k = kept.len() - 1;
+        let segment =
+            |i: usize| SimpleEdge::new(ring[kept[i] % n].clone(), ring[kept[i + 1] % n].clone());
+        let mut changed = false;
+        for i in 0..k {
+            let has_conflict = (0..k).any(|j| {
+                if i == j {
+                    return false;
+                }
+                let adjacent = (i + 1) % k == j || (j + 1) % k == i;
+                match segment(i).classify_intersection::<FloatKernel>(&segment(j)) {
+                    SegmentIntersection::Disjoint => false,
+                    SegmentIntersection::CollinearOverlap(_) => true,
+                    _ => !adjacent,
+                }
+            });
+            if has_conflict {
+                if let Some((index, _)) = farthest_from_segment(ring, kept[i], kept[i + 1]) {
+                    keep[index] = true;
+                    changed = true;
+                }
+            }
+        }
+        if !changed {
+            return kept_vertices(ring, &keep);
+        }
+    }
+}
+
+fn douglas_peucker<V>(
+    points: &[V],
+    from: usize,
+    to: usize,
+    tolerance2: V::Scalar,
+    keep: &mut [bool],
+) where
+    V: PointN,
+    V::Scalar: SpadeFloat,
+{
+    let mut stack = vec![(from, to)];
+    while let Some((from, to)) = stack.pop() {
+        if let Some((index, distance2)) = farthest_from_segment(points, from, to) {
+            if distance2 > tolerance2 {
+                keep[index] = true;
+                stack.push((from, index));
+                stack.push((index, to));
+            }
+        }
+    }
+}
+
+/// Returns the index and squared distance of the vertex between `from` and
+/// `to` that lies farthest away from the segment connecting both.
+///
+/// Indices are taken modulo the number of points. Returns `None` if no vertex
+/// lies between `from` and `to`.
+fn farthest_from_segment<V>(points: &[V], from: usize, to: usize) -> Option<(usize, V::Scalar)>
+where
+    V: PointN,
+    V::Scalar: SpadeFloat,
+{
+    let n = points.len();
+    let segment = SimpleEdge::new(points[from % n].clone(), points[to % n].clone());
+    let mut result: Option<(usize, V::Scalar)> = None;
+    for index in from + 1..to {
+        let distance2 = segment.distance2(&points[index % n]);
+        if result.map(|(_, max)| distance2 > max).unwrap_or(true) {
+            result = Some((index % n, distance2));
+        }
+    }
+    result
+}
+
+fn kept_vertices<V: Clone>(points: &[V], keep: &[bool]) -> Vec<V> {
+    points
+        .iter()
+        .zip(keep)
+        .filter(|(_, keep)| **keep)
+        .map(|(point, _)| point.clone())
+        .collect()
+}
+
+/// Returns all pairs of edges whose x ranges overlap.
+///
+/// Only these edges can intersect. The edges are sorted by their minimal x
+/// coordinate and swept from left to right.
+fn x_overlapping_pairs<V>(edges: &[SimpleEdge<V>]) -> Vec<(usize, usize)>
+where
+    V: TwoDimensional,
+    V::Scalar: SpadeFloat,
+{
+    let x_range = |edge: &SimpleEdge<V>| {
+        let (x0, x1) = (*edge.from.nth(0), *edge.to.nth(0));
+        (x0.min(x1), x0.max(x1))
+    };
+    let mut order: Vec<_> = (0..edges.len()).collect();
+    order.sort_by(|i, j| {
+        x_range(&edges[*i])
+            .0
+            .partial_cmp(&x_range(&edges[*j]).0)
+            .unwrap_or(Ordering::Equal)
+    });
+    let mut result = Vec::new();
+    for (position, i) in order.iter().enumerate() {
+        let max_x = x_range(&edges[*i]).1;
+        for j in &order[position + 1..] {
+            if x_range(&edges[*j]).0 > max_x {
+                break;
+            }
+            result.push((*i.min(j), *i.max(j)));
+        }
+    }
+    result
+}
+
+/// Removes all vertices that lie on a straight line between their neighbors.
+fn remove_collinear<V: TwoDimensional>(mut polygon: Vec<V>) -> Vec<V> {
+    let mut index = 0;
+    let mut unchanged = 0;
+    while polygon.len() >= 3 && unchanged < polygon.len() {
+        let n = polygon.len();
+        let prev = &polygon[(index + n - 1) % n];
+        let next = &polygon[(index + 1) % n];
+        let current = &polygon[index % n];
+        if cross(&current.sub(prev), &next.sub(current)) == zero() {
+            polygon.remove(index % n);
+            unchanged = 0;
+        } else {
+            index = (index + 1) % n;
+            unchanged += 1;
+        }
+    }
+    polygon
+}
+
+/// Returns the vertices in counterclockwise order, starting with the lowest vertex.
+fn ccw_from_lowest<V: TwoDimensional>(polygon: &[V]) -> Vec<V> {
+    let mut result = polygon.to_vec();
+    if double_signed_area(&result) < zero() {
+        result.reverse();
+    }
+    let lowest = (0..result.len())
+        .min_by(|i, j| {
+            let (p, q) = (&result[*i], &result[*j]);
+            p.nth(1)
+                .partial_cmp(q.nth(1))
+                .unwrap_or(Ordering::Equal)
+                .then_with(|| p.nth(0).partial_cmp(q.nth(0)).unwrap_or(Ordering::Equal))
+        })
+        .unwrap_or(0);
+    result.rotate_left(lowest);
+    result
+}
+
+fn double_signed_area<V: TwoDimensional>(polygon: &[V]) -> V::Scalar {
+    let n = polygon.len();
+    (0..n).fold(zero(), |sum: V::Scalar, i| {
+        sum + cross(&polygon[i], &polygon[(i + 1) % n])
+    })
+}
+
+fn cross<V: TwoDimensional>(v0: &V, v1: &V) -> V::Scalar {
+    v0.nth(0).clone() * v1.nth(1).clone() - v0.nth(1).clone() * v1.nth(0).clone()
+}
+
+#[cfg(test)]
+mod test {
+    use super::{
+        area, boolean_op, buffer_convex, centroid, clip_to_convex, clip_to_rect,
+        convex_decomposition, is_convex, is_ordered_ccw, minkowski_sum, monotone_decomposition,
+        offset_polygon, offset_polyline, signed_area, simplify_polyline, simplify_ring,
+        validate_polygon, winding_number, BooleanOp, OffsetJoin, PolygonError, PreparedPolygon,
+    };
+    use crate::boundingrect::BoundingRect;
+    use crate::kernels::FloatKernel;
+    use crate::primitives::{SimpleEdge, SimplePolygon};
+    use crate::traits::SpatialObject;
+    use cgmath::Point2;
+
+    #[test]
+    fn test_minkowski_sum() {
+        let square = vec![
+            Point2::new(1.0f64, 1.0),
+            Point2::new(1.0, -1.0),
+            Point2::new(-1.0, -1.0),
+            Point2::new(-1.0, 1.0),
+        ];
+        let sum = minkowski_sum(&square, &square);
+        assert_eq!(
+            sum,
+            vec![
+                Point2::new(-2.0, -2.0),
+                Point2::new(2.0, -2.0),
+                Point2::new(2.0, 2.0),
+                Point2::new(-2.0, 2.0),
+            ]
+        );
+        let point = vec![Point2::new(5.0, 5.0)];
+        let moved = minkowski_sum(&square, &point);
+        assert_eq!(moved.len(), 4);
+        assert_eq!(moved[0], Point2::new(4.0, 4.0));
+        let edge = vec![Point2::new(0.0, 0.0), Point2::new(2.0, 2.0)];
+        let swept = minkowski_sum(&square, &edge);
+        assert_eq!(swept.len(), 6);
+        assert_relative_eq!(SimplePolygon::new(swept).area(), 12.0);
+        assert!(minkowski_sum(&square, &[]).is_empty());
+    }
+
+    #[test]
+    fn test_buffer_convex() {
+        let triangle = vec![
+            Point2::new(0.0f64, 0.0),
+            Point2::new(4.0, 0.0),
+            Point2::new(0.0, 3.0),
+        ];
+        let radius = 0.5;
+        let buffer = SimplePolygon::new(buffer_convex(&triangle, radius, 64));
+        assert!(buffer.is_ordered_ccw());
+        let pi = ::std::f64::consts::PI;
+        let exact_area = 6.0 + 12.0 * radius + pi * radius * radius;
+        assert!(buffer.area() >= exact_area);
+        assert!(buffer.area() < exact_area * 1.01);
+        // Points at a distance slightly smaller than the radius are contained
+        for i in 0..100 {
+            let angle = i as f64 * 2.0 * pi / 100.0;
+            for vertex in &triangle {
+                let p = Point2::new(
+                    vertex.x + 0.499 * angle.cos(),
+                    vertex.y + 0.499 * angle.sin(),
+                );
+                assert!(buffer.contains_point::<FloatKernel>(&p));
+            }
+        }
+        assert!(!buffer.contains_point::<FloatKernel>(&Point2::new(2.0, -0.6)));
+
+        let point = buffer_convex(&[Point2::new(1.0f64, 1.0)], 1.0, 4);
+        assert_eq!(point.len(), 4);
+        assert_relative_eq!(SimplePolygon::new(point).area(), 4.0);
+    }
+
+    #[test]
+    fn test_clip() {
+        let window = vec![
+            Point2::new(0.0f64, 0.0),
+            Point2::new(0.0, 4.0),
+            Point2::new(4.0, 4.0),
+            Point2::new(4.0, 0.0),
+        ];
+        let inside = vec![
+            Point2::new(1.0, 1.0),
+            Point2::new(2.0, 1.0),
+            Point2::new(1.0, 2.0),
+        ];
+        assert_eq!(clip_to_convex(&inside, &window), inside);
+        let outside = vec![
+            Point2::new(5.0, 5.0),
+            Point2::new(6.0, 5.0),
+            Point2::new(5.0, 6.0),
+        ];
+        assert!(clip_to_convex(&outside, &window).is_empty());
+        let covering = vec![
+            Point2::new(-1.0, -1.0),
+            Point2::new(6.0, -1.0),
+            Point2::new(-1.0, 6.0),
+        ];
+        let clipped = SimplePolygon::new(clip_to_convex(&covering, &window));
+        assert_relative_eq!(clipped.area(), 16.0 - 0.5 * 3.0 * 3.0);
+        assert!(clipped.is_ordered_ccw());
+
+        // Concave subject
+        let u_shape = vec![
+            Point2::new(-1.0, -1.0),
+            Point2::new(5.0, -1.0),
+            Point2::new(5.0, 6.0),
+            Point2::new(3.0, 6.0),
+            Point2::new(3.0, 2.0),
+            Point2::new(1.0, 2.0),
+            Point2::new(1.0, 6.0),
+            Point2::new(-1.0, 6.0),
+        ];
+        let rect = BoundingRect::from_corners(&Point2::new(0.0, 0.0), &Point2::new(4.0, 4.0));
+        let clipped = SimplePolygon::new(clip_to_rect(&u_shape, &rect));
+        assert_relative_eq!(clipped.area(), 16.0 - 4.0);
+    }
+
+    fn square(x: f64, y: f64, size: f64) -> Vec<Point2<f64>> {
+        vec![
+            Point2::new(x, y),
+            Point2::new(x + size, y),
+            Point2::new(x + size, y + size),
+            Point2::new(x, y + size),
+        ]
+    }
+
+    fn signed_areas(rings: &[Vec<Point2<f64>>]) -> Vec<f64> {
+        rings
+            .iter()
+            .map(|ring| SimplePolygon::new(ring.clone()).double_signed_area() * 0.5)
+            .collect()
+    }
+
+    #[test]
+    fn test_boolean_op() {
+        let a = square(0.0, 0.0, 2.0);
+        let b = square(1.0, 1.0, 2.0);
+        assert_eq!(
+            signed_areas(&boolean_op(&a, &b, BooleanOp::Union).unwrap()),
+            vec![7.0]
+        );
+        assert_eq!(
+            signed_areas(&boolean_op(&a, &b, BooleanOp::Intersection).unwrap()),
+            vec![1.0]
+        );
+        assert_eq!(
+            signed_areas(&boolean_op(&a, &b, BooleanOp::Difference).unwrap()),
+            vec![3.0]
+        );
+
+        // Clockwise input and shared boundary segments
+        let mut reversed = a.clone();
+        reversed.reverse();
+        assert_eq!(
+            signed_areas(&boolean_op(&reversed, &a, BooleanOp::Union).unwrap()),
+            vec![4.0]
+        );
+        assert!(boolean_op(&reversed, &a, BooleanOp::Difference)
+            .unwrap()
+            .is_empty());
+        let neighbor = square(2.0, 0.0, 2.0);
+        let union = boolean_op(&a, &neighbor, BooleanOp::Union).unwrap();
+        assert_eq!(union.len(), 1);
+        assert_eq!(union[0].len(), 4);
+
+        // Disjoint polygons and holes
+        let far = square(5.0, 5.0, 1.0);
+        assert_eq!(boolean_op(&a, &far, BooleanOp::Union).unwrap().len(), 2);
+        assert!(boolean_op(&a, &far, BooleanOp::Intersection)
+            .unwrap()
+            .is_empty());
+        let inner = square(0.5, 0.5, 1.0);
+        let mut areas = signed_areas(&boolean_op(&a, &inner, BooleanOp::Difference).unwrap());
+        areas.sort_by(|a0, a1| a0.partial_cmp(a1).unwrap());
+        assert_eq!(areas, vec![-1.0, 4.0]);
+
+        // Non convex polygon
+        let u_shape = vec![
+            Point2::new(0.0, 0.0),
+            Point2::new(3.0, 0.0),
+            Point2::new(3.0, 3.0),
+            Point2::new(2.0, 3.0),
+            Point2::new(2.0, 1.0),
+            Point2::new(1.0, 1.0),
+            Point2::new(1.0, 3.0),
+            Point2::new(0.0, 3.0),
+        ];
+        let bar = vec![
+            Point2::new(-1.0, 2.0),
+            Point2::new(4.0, 2.0),
+            Point2::new(4.0, 2.5),
+            Point2::new(-1.0, 2.5),
+        ];
+        let intersection = boolean_op(&u_shape, &bar, BooleanOp::Intersection).unwrap();
+        assert_eq!(signed_areas(&intersection).len(), 2);
+        let total: f64 = signed_areas(&intersection).iter().sum();
+        assert_relative_eq!(total, 1.0);
+        let union = boolean_op(&u_shape, &bar, BooleanOp::Union).unwrap();
+        let mut areas = signed_areas(&union);
+        areas.sort_by(|a0, a1| a0.partial_cmp(a1).unwrap());
+        assert_eq!(areas, vec![-1.0, 9.5]);
+    }
+
+    #[test]
+    fn test_boolean_op_rounded_crossings() {
+        // Star polygons whose boundaries cross at many inexact points
+        let star = |offset: f64, rotation: f64| -> Vec<Point2<f64>> {
+            (0..40)
+                .map(|i| {
+                    let angle = rotation + i as f64 * core::f64::consts::PI / 20.0;
+                    let radius = if i % 2 == 0 { 3.0 } else { 1.3 };
+                    Point2::new(offset + radius * angle.cos(), radius * angle.sin())
+                })
+                .collect()
+        };
+        let a = star(0.0, 0.1);
+        let b = star(0.7, 0.3);
+        let total_area = |rings: &[Vec<Point2<f64>>]| -> f64 { signed_areas(rings).iter().sum() };
+        let union = total_area(&boolean_op(&a, &b, BooleanOp::Union).unwrap());
+        let intersection = total_area(&boolean_op(&a, &b, BooleanOp::Intersection).unwrap());
+        let a_area = total_area(core::slice::from_ref(&a));
+        let b_area = total_area(core::slice::from_ref(&b));
+        assert_relative_eq!(union + intersection, a_area + b_area, epsilon = 1e-9);
+        let difference = total_area(&boolean_op(&a, &b, BooleanOp::Difference).unwrap());
+        assert_relative_eq!(difference + intersection, a_area, epsilon = 1e-9);
+    }
+
+    #[test]
+    fn test_area_and_orientation() {
+        let triangle = vec![
+            Point2::new(1.0f64, 1.0),
+            Point2::new(5.0, 1.0),
+            Point2::new(1.0, 4.0),
+        ];
+        assert_eq!(signed_area(&triangle), 6.0);
+        assert!(is_ordered_ccw(&triangle));
+        let center = centroid(&triangle).unwrap();
+        assert_relative_eq!(center.x, 7.0 / 3.0);
+        assert_relative_eq!(center.y, 2.0);
+
+        let mut reversed = triangle.clone();
+        reversed.reverse();
+        assert_eq!(signed_area(&reversed), -6.0);
+        assert_eq!(area(&reversed), 6.0);
+        assert!(!is_ordered_ccw(&reversed));
+        assert_eq!(centroid(&reversed), centroid(&triangle));
+
+        let l_shape = vec![
+            Point2::new(0.0, 0.0),
+            Point2::new(2.0, 0.0),
+            Point2::new(2.0, 1.0),
+            Point2::new(1.0, 1.0),
+            Point2::new(1.0, 2.0),
+            Point2::new(0.0, 2.0),
+        ];
+        let center = centroid(&l_shape).unwrap();
+        assert_relative_eq!(center.x, 5.0 / 6.0);
+        assert_relative_eq!(center.y, 5.0 / 6.0);
+        let line = vec![
+            Point2::new(0.0, 0.0),
+            Point2::new(1.0, 1.0),
+            Point2::new(2.0, 2.0),
+        ];
+        assert_eq!(centroid(&line), None);
+        assert!(!is_ordered_ccw(&line));
+
+        let p = Point2::new(0.5, 0.5);
+        assert_eq!(winding_number::<FloatKernel, _>(&l_shape, &p), 1);
+        assert_eq!(
+            winding_number::<FloatKernel, _>(&reversed, &Point2::new(2.0, 2.0)),
+            -1
+        );
+        assert_eq!(
+            winding_number::<FloatKernel, _>(&l_shape, &Point2::new(1.5, 1.5)),
+            0
+        );
+    }
+
+    #[test]
+    fn test_is_convex() {
+        let square = vec![
+            Point2::new(0, 0),
+            Point2::new(1, 0),
+            Point2::new(2, 0),
+            Point2::new(2, 2),
+            Point2::new(0, 2),
+        ];
+        assert!(is_convex(&square));
+        let mut reversed = square.clone();
+        reversed.reverse();
+        assert!(is_convex(&reversed));
+        let arrow = vec![
+            Point2::new(0, 0),
+            Point2::new(2, 1),
+            Point2::new(0, 2),
+            Point2::new(1, 1),
+        ];
+        assert!(!is_convex(&arrow));
+        // A pentagram turns left at each vertex but winds twice
+        let pentagram = vec![
+            Point2::new(0, 10),
+            Point2::new(-6, -8),
+            Point2::new(10, 3),
+            Point2::new(-10, 3),
+            Point2::new(6, -8),
+        ];
+        assert!(!is_convex(&pentagram));
+        assert!(!is_convex(&[
+            Point2::new(0, 0),
+            Point2::new(1, 1),
+            Point2::new(2, 2)
+        ]));
+    }
+
+    #[test]
+    fn test_simplify_polyline() {
+        let points: Vec<_> = (0..100)
+            .map(|i| {
+                let x = f64::from(i) * 0.1;
+                Point2::new(x, x.sin() + if i % 2 == 0 { 0.01 } else { -0.01 })
+            })
+            .collect();
+        let simplified = simplify_polyline(&points, 0.05);
+        assert!(simplified.len() < 20);
+        assert_eq!(simplified.first(), points.first());
+        assert_eq!(simplified.last(), points.last());
+        for point in &points {
+            let distance2 = simplified
+                .windows(2)
+                .map(|pair| SimpleEdge::new(pair[0], pair[1]).distance2(point))
+                .fold(f64::INFINITY, f64::min);
+            assert!(distance2 <= 0.05 * 0.05);
+        }
+        assert_eq!(simplify_polyline(&points, 0.0).len(), points.len());
+        assert_eq!(simplify_polyline(&points[..2], 1.0), &points[..2]);
+    }
+
+    #[test]
+    fn test_simplify_ring() {
+        let dip = Point2::new(5.0, -0.4);
+        let ring = vec![
+            Point2::new(0.0, 0.0),
+            dip,
+            Point2::new(10.0, 0.0),
+            Point2::new(10.0, 3.0),
+            Point2::new(7.5, 3.0),
+            Point2::new(7.0, -0.1),
+            Point2::new(6.5, 3.0),
+            Point2::new(0.0, 3.0),
+        ];
+        // Removing the dip would cut the notch's tip off
+        let simplified = simplify_ring(&ring, 0.45);
+        assert_eq!(simplified, ring);
+        let simplified = simplify_ring(&ring, 0.35);
+        assert_eq!(simplified, ring);
+
+        let mut closed = ring.clone();
+        closed.push(ring[0]);
+        assert!(!simplify_polyline(&closed, 0.45).contains(&dip));
+
+        let small = vec![
+            Point2::new(0.0, 0.0),
+            Point2::new(1.0, 0.0),
+            Point2::new(1.0, 1.0),
+            Point2::new(0.0, 1.0),
+        ];
+        assert_eq!(simplify_ring(&small, 10.0), small);
+    }
+
+    fn total_area(rings: &[Vec<Point2<f64>>]) -> f64 {
+        rings.iter().map(|ring| signed_area(ring)).sum()
+    }
+
+    #[test]
+    fn test_offset_polygon() {
+        let square = vec![
+            Point2::new(0.0, 0.0),
+            Point2::new(0.0, 4.0),
+            Point2::new(4.0, 4.0),
+            Point2::new(4.0, 0.0),
+        ];
+        let grown = offset_polygon(&square, 1.0, OffsetJoin::Round(256)).unwrap();
+        assert_eq!(grown.len(), 1);
+        assert_relative_eq!(
+            total_area(&grown),
+            32.0 + ::std::f64::consts::PI,
+            epsilon = 1e-3
+        );
+        assert_eq!(
+            total_area(&offset_polygon(&square, 1.0, OffsetJoin::Miter(2.0)).unwrap()),
+            36.0
+        );
+        // The miter limit is exceeded at right angles
+        assert_eq!(
+            total_area(&offset_polygon(&square, 1.0, OffsetJoin::Miter(1.2)).unwrap()),
+            34.0
+        );
+        assert_eq!(
+            total_area(&offset_polygon(&square, -1.0, OffsetJoin::Miter(2.0)).unwrap()),
+            4.0
+        );
+        assert!(offset_polygon(&square, -2.5, OffsetJoin::Round(16))
+            .unwrap()
+            .is_empty());
+
+        let l_shape = vec![
+            Point2::new(0.0, 0.0),
+            Point2::new(2.0, 0.0),
+            Point2::new(2.0, 1.0),
+            Point2::new(1.0, 1.0),
+            Point2::new(1.0, 2.0),
+            Point2::new(0.0, 2.0),
+        ];
+        let grown = offset_polygon(&l_shape, 1.0, OffsetJoin::Miter(2.0)).unwrap();
+        assert_eq!(grown.len(), 1);
+        assert_eq!(total_area(&grown), 15.0);
+        let shrunk = offset_polygon(&l_shape, -0.25, OffsetJoin::Round(1024)).unwrap();
+        // The concave corner is rounded, the convex corners stay sharp
+        let expected = 1.5 * 1.5 - 1.0 + 0.25 * 0.25 * (1.0 - ::std::f64::consts::PI / 4.0);
+        assert_relative_eq!(total_area(&shrunk), expected, epsilon = 1e-4);
+
+        // Shrinking splits a dumbbell into two parts
+        let dumbbell = vec![
+            Point2::new(0.0, 0.0),
+            Point2::new(4.0, 0.0),
+            Point2::new(4.0, 1.5),
+            Point2::new(6.0, 1.5),
+            Point2::new(6.0, 0.0),
+            Point2::new(10.0, 0.0),
+            Point2::new(10.0, 4.0),
+            Point2::new(6.0, 4.0),
+            Point2::new(6.0, 2.5),
+            Point2::new(4.0, 2.5),
+            Point2::new(4.0, 4.0),
+            Point2::new(0.0, 4.0),
+        ];
+        let parts = offset_polygon(&dumbbell, -1.0, OffsetJoin::Round(64)).unwrap();
+        assert_eq!(parts.len(), 2);
+        // Both parts bulge slightly into the neck
+        assert_relative_eq!(
+            signed_area(&parts[0]),
+            signed_area(&parts[1]),
+            epsilon = 1e-10
+        );
+        assert!(signed_area(&parts[0]) > 4.0 && signed_area(&parts[0]) < 4.1);
+        // Growing fills a hole
+        let grown = offset_polygon(&dumbbell, 1.0, OffsetJoin::Miter(2.0)).unwrap();
+        assert_eq!(grown.len(), 1);
+        assert_eq!(total_area(&grown), 12.0 * 6.0);
+    }
+
+    #[test]
+    fn test_offset_polyline() {
+        let line = vec![Point2::new(0.0, 0.0), Point2::new(4.0, 0.0)];
+        let rect = offset_polyline(&line, 1.0, OffsetJoin::Miter(2.0)).unwrap();
+        assert_eq!(total_area(&rect), 8.0);
+        let capsule = offset_polyline(&line, 1.0, OffsetJoin::Round(256)).unwrap();
+        assert_relative_eq!(
+            total_area(&capsule),
+            8.0 + ::std::f64::consts::PI,
+            epsilon = 1e-3
+        );
+
+        let corner = vec![
+            Point2::new(0.0, 0.0),
+            Point2::new(4.0, 0.0),
+            Point2::new(4.0, 4.0),
+        ];
+        let buffer = offset_polyline(&corner, 1.0, OffsetJoin::Miter(2.0)).unwrap();
+        assert_eq!(buffer.len(), 1);
+        assert_eq!(total_area(&buffer), 16.0);
+        // A closed polyline encloses a hole. The flat caps at its start and end
+        // point leave out the outer corner.
+        let ring = vec![
+            Point2::new(0.0, 0.0),
+            Point2::new(4.0, 0.0),
+            Point2::new(4.0, 4.0),
+            Point2::new(0.0, 4.0),
+            Point2::new(0.0, 0.0),
+        ];
+        let buffer = offset_polyline(&ring, 1.0, OffsetJoin::Miter(2.0)).unwrap();
+        assert_eq!(buffer.len(), 2);
+        assert_eq!(total_area(&buffer), 36.0 - 4.0 - 1.0);
+        assert!(offset_polyline(&line, 0.0, OffsetJoin::Round(16))
+            .unwrap()
+            .is_empty());
+        assert!(offset_polyline(&line[..1], 1.0, OffsetJoin::Round(16))
+            .unwrap()
+            .is_empty());
+    }
+
+    fn comb() -> Vec<Point2<f64>> {
+        vec![
+            Point2::new(0.0, 0.0),
+            Point2::new(7.0, 0.0),
+            Point2::new(7.0, 3.0),
+            Point2::new(6.0, 3.0),
+            Point2::new(5.5, 1.0),
+            Point2::new(5.0, 3.0),
+            Point2::new(4.0, 3.0),
+            Point2::new(3.5, 1.0),
+            Point2::new(3.0, 3.0),
+            Point2::new(2.0, 3.0),
+            Point2::new(1.5, 1.0),
+            Point2::new(1.0, 3.0),
+            Point2::new(0.0, 3.0),
+        ]
+    }
+
+    #[test]
+    fn test_convex_decomposition() {
+        let mut polygon = comb();
+        polygon.reverse();
+        let pieces = convex_decomposition(&polygon);
+        // Each of the three notches requires at least one diagonal
+        assert!(pieces.len() >= 4 && pieces.len() <= 16);
+        for piece in &pieces {
+            assert!(is_convex(piece));
+            assert!(is_ordered_ccw(piece));
+        }
+        assert_relative_eq!(total_area(&pieces), area(&polygon), epsilon = 1e-10);
+
+        let square = square(0.0, 0.0, 1.0);
+        assert_eq!(convex_decomposition(&square).len(), 1);
+        assert_eq!(total_area(&convex_decomposition(&square)), 1.0);
+    }
+
+    #[test]
+    fn test_monotone_decomposition() {
+        let polygon = comb();
+        let pieces = monotone_decomposition(&polygon);
+        // The comb's teeth end in separate local maxima
+        assert!(pieces.len() >= 4);
+        for piece in &pieces {
+            assert!(super::is_y_monotone(piece));
+            assert!(is_ordered_ccw(piece));
+        }
+        assert_relative_eq!(total_area(&pieces), area(&polygon), epsilon = 1e-10);
+
+        // A polygon rotated by 90 degrees is already monotone
+        let rotated: Vec<_> = polygon.iter().map(|p| Point2::new(p.y, p.x)).collect();
+        assert_eq!(monotone_decomposition(&rotated).len(), 1);
+        assert!(!super::is_y_monotone(&polygon));
+    }
+
+    #[test]
+    fn test_prepared_polygon() {
+        use crate::testutils::*;
+        let seed = b"\x52\x0f\xc8\x3d\x91\x6e\xa4\x17\xdb\x20\x85\x4c\xf3\x69\xb0\x0e\
+                     \x7d\x34\xe2\x58\x1a\xcf\x96\x03\x6b\xa1\x45\xfe\x28\x8d\x50\xc7";
+        // A random star shaped polygon
+        let mut angles: Vec<_> = random_points_with_seed::<f64>(100, seed)
+            .into_iter()
+            .map(|p| (p.x * ::std::f64::consts::PI, 0.5 + p.y.abs() * 2.0))
+            .collect();
+        angles.sort_by(|a0, a1| a0.0.partial_cmp(&a1.0).unwrap());
+        let star: Vec<_> = angles
+            .iter()
+            .map(|(angle, radius)| Point2::new(angle.cos() * radius, angle.sin() * radius))
+            .collect();
+        let reference = SimplePolygon::new(star.clone());
+        let prepared = PreparedPolygon::new(&star);
+        for point in random_points_with_seed::<f64>(2000, &[3; 32]) {
+            let point = Point2::new(point.x * 3.0, point.y * 3.0);
+            assert_eq!(
+                prepared.contains_point::<FloatKernel>(&point),
+                reference.contains_point::<FloatKernel>(&point)
+            );
+        }
+        for vertex in &star {
+            assert!(prepared.contains_point::<FloatKernel>(vertex));
+        }
+
+        // Horizontal edges and vertices sharing their y coordinate
+        let polygon = comb();
+        let prepared = PreparedPolygon::new(&polygon);
+        let reference = SimplePolygon::new(polygon.clone());
+        for x in 0..=32 {
+            for y in -4..=16 {
+                let point = Point2::new(f64::from(x) * 0.25 - 0.5, f64::from(y) * 0.25);
+                assert_eq!(
+                    prepared.contains_point::<FloatKernel>(&point),
+                    reference.contains_point::<FloatKernel>(&point)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_validate_polygon() {
+        let mut polygon = comb();
+        assert_eq!(validate_polygon(&polygon), Ok(()));
+        polygon.reverse();
+        assert_eq!(
+            validate_polygon(&polygon),
+            Err(PolygonError::ClockwiseOrientation)
+        );
+        assert_eq!(
+            validate_polygon(&polygon[..2]),
+            Err(PolygonError::TooFewVertices)
+        );
+
+        let mut polygon = comb();
+        polygon[4] = Point2::new(5.5, f64::NAN);
+        assert_eq!(
+            validate_polygon(&polygon),
+            Err(PolygonError::NonFiniteCoordinate(4))
+        );
+
+        let mut polygon = comb();
+        polygon.push(polygon[0]);
+        assert_eq!(
+            validate_polygon(&polygon),
+            Err(PolygonError::DuplicateVertex(0, 13))
+        );
+
+        // A tooth crossing its neighbor
+        let mut polygon = comb();
+        polygon[4] = Point2::new(3.2, 1.0);
+        assert_eq!(
+            validate_polygon(&polygon),
+            Err(PolygonError::SelfIntersection(3, 6))
+        );
+        // A vertex touching another edge
+        let mut polygon = comb();
+        polygon[4] = Point2::new(3.75, 2.0);
+        assert_eq!(
+            validate_polygon(&polygon),
+            Err(PolygonError::SelfIntersection(3, 6))
+        );
+        // A spike folding back onto its edge
+        let spike = vec![
+            Point2::new(0.0, 0.0),
+            Point2::new(2.0, 0.0),
+            Point2::new(1.0, 0.0),
+            Point2::new(1.0, 1.0),
+        ];
+        assert_eq!(
+            validate_polygon(&spike),
+            Err(PolygonError::SelfIntersection(0, 1))
+        );
+        assert_eq!(
+            format!("{}", PolygonError::SelfIntersection(0, 1)),
+            "edges 0 and 1 intersect"
+        );
+    }
+}
@@ -13,11 +13,15 @@
 //! implementing `SpatialObject`.
 
 use crate::boundingrect::BoundingRect;
-use crate::kernels::{DelaunayKernel, TrivialKernel};
-use crate::point_traits::{PointN, PointNExtensions, TwoDimensional};
+use crate::kernels::{DelaunayKernel, FloatKernel, TrivialKernel};
+use crate::point::Point3;
+use crate::point_traits::{PointN, PointNExtensions, ThreeDimensional, TwoDimensional};
+use crate::polygon::clip_to_convex;
 use crate::traits::{SpadeFloat, SpadeNum, SpatialObject};
-use cgmath::{One, Point3, Zero};
-use num::{one, zero, Float, Signed};
+use crate::transform::AffineTransform2;
+use alloc::{vec, vec::Vec};
+use core::cmp::Ordering;
+use num::{one, zero, Float, FromPrimitive, NumCast, One, Signed, Zero};
 
 #[cfg(feature = "serde_serialize")]
 use serde::{Deserialize, Serialize};
@@ -25,6 +29,7 @@ use serde::{Deserialize, Serialize};
 /// An edge defined by it's two end points.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[cfg_attr(feature = "serde_serialize", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct SimpleEdge<V: PointN> {
     /// The edge's origin.
     pub from: V,
@@ -154,10 +159,9 @@ where
 
     /// Checks if this and another edge intersect.
     ///
-    /// The edges must not be collinear. Also, `true` is returned if the edges
-    /// just touch each other.
-    /// # Panics
-    /// Panics if both lines are collinear.
+    /// Also, `true` is returned if the edges just touch each other. Collinear
+    /// edges intersect if they share at least one point.
+    /// See `classify_intersection` for a more detailed result.
     pub fn intersects_edge_non_collinear<K>(&self, other: &SimpleEdge<V>) -> bool
     where
         K: DelaunayKernel<V::Scalar>,
@@ -167,15 +171,62 @@ where
         let self_from = other.side_query::<K>(&self.from);
         let self_to = other.side_query::<K>(&self.to);
 
-        assert!(
-            ![&other_from, &other_to, &self_from, &self_to]
-                .iter()
-                .all(|q| q.is_on_line()),
-            "intersects_edge_non_collinear: Given edge is collinear."
-        );
+        if [&other_from, &other_to, &self_from, &self_to]
+            .iter()
+            .all(|q| q.is_on_line())
+        {
+            return self.collinear_overlap(other).is_some();
+        }
 
         other_from != other_to && self_from != self_to
     }
+
+    /// Returns the lexicographically smallest and greatest point that two
+    /// collinear edges have in common.
+    fn collinear_overlap(&self, other: &SimpleEdge<V>) -> Option<(V, V)> {
+        let sorted = |edge: &SimpleEdge<V>| {
+            if edge.from.lex_compare(&edge.to) == Ordering::Greater {
+                (edge.to.clone(), edge.from.clone())
+            } else {
+                (edge.from.clone(), edge.to.clone())
+            }
+        };
+        let (self_min, self_max) = sorted(self);
+        let (other_min, other_max) = sorted(other);
+        let min = if self_min.lex_compare(&other_min) == Ordering::Less {
+            other_min
+        } else {
+            self_min
+        };
+        let max = if self_max.lex_compare(&other_max) == Ordering::Greater {
+            other_max
+        } else {
+            self_max
+        };
+        if min.lex_compare(&max) == Ordering::Greater {
+            None
+        } else {
+            Some((min, max))
+        }
+    }
+}
+
+/// Describes how two edges intersect.
+///
+/// Returned by `SimpleEdge::classify_intersection`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde_serialize", derive(Serialize, Deserialize))]
+pub enum SegmentIntersection<V: PointN> {
+    /// The edges have no point in common.
+    Disjoint,
+    /// The edges cross each other at a point in their interior.
+    Crossing(V),
+    /// The edges have exactly one point in common, which is an end point
+    /// of at least one edge.
+    Touching(V),
+    /// The edges are collinear and share a segment.
+    /// The segment is oriented like the first edge.
+    CollinearOverlap(SimpleEdge<V>),
 }
 
 impl<V> SimpleEdge<V>
@@ -188,7 +239,7 @@ where
         let (p1, p2) = (&self.from, &self.to);
         let dir = p2.sub(p1);
         let s = self.project_point(query_point);
-        if V::Scalar::zero() < s && s < one() {
+        if zero::<V::Scalar>() < s && s < one() {
             p1.add(&dir.mul(s))
         } else if s <= V::Scalar::zero() {
             p1.clone()
@@ -218,6 +269,207 @@ where
         let dir = p2.sub(p1);
         query_point.sub(p1).dot(&dir) / dir.length2()
     }
+
+    /// Returns the minimum squared distance between this and another edge.
+    ///
+    /// Returns zero if both edges intersect.
+    pub fn distance2_to_edge(&self, other: &SimpleEdge<V>) -> V::Scalar {
+        let clamp = |s: V::Scalar| crate::misc::clamp(zero(), s, one());
+        let self_dir = self.to.sub(&self.from);
+        let other_dir = other.to.sub(&other.from);
+        let diff = self.from.sub(&other.from);
+        let a = self_dir.length2();
+        let e = other_dir.length2();
+        let f = other_dir.dot(&diff);
+        // s and t are the relative positions of the nearest points on both edges
+        let (s, t) = if a == zero() && e == zero() {
+            (zero(), zero())
+        } else if a == zero() {
+            (zero(), clamp(f / e))
+        } else {
+            let c = self_dir.dot(&diff);
+            if e == zero() {
+                (clamp(-c / a), zero())
+            } else {
+                let b = self_dir.dot(&other_dir);
+                let denominator = a * e - b * b;
+                // Parallel edges: any s is fine, pick the start point
+                let s = if denominator != zero() {
+                    clamp((b * f - c * e) / denominator)
+                } else {
+                    zero()
+                };
+                let t = (b * s + f) / e;
+                if t < zero() {
+                    (clamp(-c / a), zero())
+                } else if t > one() {
+                    (clamp((b - c) / a), one())
+                } else {
+                    (s, t)
+                }
+            }
+        };
+        let p1 = self.from.add(&self_dir.mul(s));
+        let p2 = other.from.add(&other_dir.mul(t));
+        p1.sub(&p2).length2()
+    }
+}
+
+impl<V> SimpleEdge<V>
+where
+    V: TwoDimensional,
+    V::Scalar: SpadeFloat,
+{
+    /// Applies an affine transformation to this edge.
+    pub fn transform(&self, transform: &AffineTransform2<V::Scalar>) -> SimpleEdge<V> {
+        SimpleEdge::new(
+            transform.transform_point(&self.from),
+            transform.transform_point(&self.to),
+        )
+    }
+
+    /// Calculates where this and another edge intersect.
+    ///
+    /// Returns the relative positions of the intersection on both edges,
+    /// a value of 0. refers to an edge's `from` and 1. to its `to` point.
+    /// The kernel decides whether the edges intersect at all.
+    /// Returns `None` if the edges do not intersect or if they are collinear.
+    pub fn intersection_parameters<K>(
+        &self,
+        other: &SimpleEdge<V>,
+    ) -> Option<(V::Scalar, V::Scalar)>
+    where
+        K: DelaunayKernel<V::Scalar>,
+    {
+        let other_from = self.side_query::<K>(&other.from);
+        let other_to = self.side_query::<K>(&other.to);
+        let self_from = other.side_query::<K>(&self.from);
+        let self_to = other.side_query::<K>(&self.to);
+        if [&other_from, &other_to, &self_from, &self_to]
+            .iter()
+            .all(|q| q.is_on_line())
+            || other_from == other_to
+            || self_from == self_to
+        {
+            return None;
+        }
+        let self_dir = self.to.sub(&self.from);
+        let other_dir = other.to.sub(&other.from);
+        let denominator = cross(&self_dir, &other_dir);
+        if denominator == zero() {
+            return None;
+        }
+        let diff = other.from.sub(&self.from);
+        let clamp = |s: V::Scalar| crate::misc::clamp(zero(), s, one());
+        Some((
+            clamp(cross(&diff, &other_dir) / denominator),
+            clamp(cross(&diff, &self_dir) / denominator),
+        ))
+    }
+
+    /// Classifies how this and another edge intersect.
+    ///
+    /// Unlike `intersection_point`, this also handles edges that touch or
+    /// are collinear. The kernel decides whether the edges intersect, end
+    /// points are returned exactly, crossing points are calculated.
+    ///
+    /// # Example:
+    ///
+    /// ```
+    /// # extern crate nalgebra;
+    /// # extern crate spade;
+    ///
+    /// use nalgebra::Point2;
+    /// use spade::kernels::FloatKernel;
+    /// use spade::primitives::{SegmentIntersection, SimpleEdge};
+    ///
+    /// # fn main() {
+    /// let e1 = SimpleEdge::new(Point2::new(0f64, 0.), Point2::new(2., 0.));
+    /// let e2 = SimpleEdge::new(Point2::new(1f64, 0.), Point2::new(3., 0.));
+    /// assert_eq!(
+    ///     e1.classify_intersection::<FloatKernel>(&e2),
+    ///     SegmentIntersection::CollinearOverlap(SimpleEdge::new(
+    ///         Point2::new(1., 0.),
+    ///         Point2::new(2., 0.)
+    ///     ))
+    /// );
+    /// # }
+    /// ```
+    pub fn classify_intersection<K>(&self, other: &SimpleEdge<V>) -> SegmentIntersection<V>
+    where
+        K: DelaunayKernel<V::Scalar>,
+    {
+        let other_from = self.side_query::<K>(&other.from);
+        let other_to = self.side_query::<K>(&other.to);
+        let self_from = other.side_query::<K>(&self.from);
+        let self_to = other.side_query::<K>(&self.to);
+        if [&other_from, &other_to, &self_from, &self_to]
+            .iter()
+            .all(|q| q.is_on_line())
+        {
+            return match self.collinear_overlap(other) {
+                None => SegmentIntersection::Disjoint,
+                Some((min, max)) => {
+                    if min == max {
+                        SegmentIntersection::Touching(min)
+                    } else if self.from.lex_compare(&self.to) == Ordering::Greater {
+                        SegmentIntersection::CollinearOverlap(SimpleEdge::new(max, min))
+                    } else {
+                        SegmentIntersection::CollinearOverlap(SimpleEdge::new(min, max))
+                    }
+                }
+            };
+        }
+        if other_from == other_to || self_from == self_to {
+            return SegmentIntersection::Disjoint;
+        }
+        if other_from.is_on_line() {
+            SegmentIntersection::Touching(other.from.clone())
+        } else if other_to.is_on_line() {
+            SegmentIntersection::Touching(other.to.clone())
+        } else if self_from.is_on_line() {
+            SegmentIntersection::Touching(self.from.clone())
+        } else if self_to.is_on_line() {
+            SegmentIntersection::Touching(self.to.clone())
+        } else {
+            let self_dir = self.to.sub(&self.from);
+            let diff = other.from.sub(&self.from);
+            let other_dir = other.to.sub(&other.from);
+            let s = cross(&diff, &other_dir) / cross(&self_dir, &other_dir);
+            let s = crate::misc::clamp(zero(), s, one());
+            SegmentIntersection::Crossing(self.from.add(&self_dir.mul(s)))
+        }
+    }
+
+    /// Calculates the point at which this and another edge intersect.
+    ///
+    /// Returns `None` if the edges do not intersect or if they are collinear.
+    ///
+    /// # Example:
+    ///
+    /// ```
+    /// # extern crate nalgebra;
+    /// # extern crate spade;
+    ///
+    /// use nalgebra::Point2;
+    /// use spade::kernels::FloatKernel;
+    /// use spade::primitives::SimpleEdge;
+    ///
+    /// # fn main() {
+    /// let e1 = SimpleEdge::new(Point2::new(0f64, 0.), Point2::new(2., 2.));
+    /// let e2 = SimpleEdge::new(Point2::new(0f64, 2.), Point2::new(2., 0.));
+    /// let e3 = SimpleEdge::new(Point2::new(3f64, 0.), Point2::new(3., 2.));
+    /// assert_eq!(e1.intersection_point::<FloatKernel>(&e2), Some(Point2::new(1., 1.)));
+    /// assert_eq!(e1.intersection_point::<FloatKernel>(&e3), None);
+    /// # }
+    /// ```
+    pub fn intersection_point<K>(&self, other: &SimpleEdge<V>) -> Option<V>
+    where
+        K: DelaunayKernel<V::Scalar>,
+    {
+        self.intersection_parameters::<K>(other)
+            .map(|(s, _)| self.from.add(&self.to.sub(&self.from).mul(s)))
+    }
 }
 
 impl<V: PointN> SpatialObject for SimpleEdge<V>
@@ -239,6 +491,7 @@ where
 /// A triangle, defined by it's three points.
 #[derive(Clone, Copy, Debug, Eq, PartialOrd, Ord)]
 #[cfg_attr(feature = "serde_serialize", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct SimpleTriangle<V: PointN> {
     v0: V,
     v1: V,
@@ -270,6 +523,100 @@ where
         let c = self.v2.sub(&self.v0);
         (b.nth(0).clone() * c.nth(1).clone() - b.nth(1).clone() * c.nth(0).clone()).abs()
     }
+
+    /// Checks if a point lies within this triangle or on its boundary.
+    ///
+    /// The kernel `K` determines how the required orientation tests are
+    /// performed, see `spade::kernels`.
+    pub fn contains_point<K>(&self, point: &V) -> bool
+    where
+        K: DelaunayKernel<V::Scalar>,
+    {
+        self.locate_point::<K>(point) != PositionInTriangle::Outside
+    }
+
+    /// Determines where a point lies relative to this triangle.
+    ///
+    /// The kernel `K` determines how the required orientation tests are
+    /// performed, see `spade::kernels`. The triangle may be ordered clockwise
+    /// or counterclockwise. A degenerate triangle contains only the points on
+    /// its edges.
+    ///
+    /// # Example:
+    ///
+    /// ```
+    /// # extern crate nalgebra;
+    /// # extern crate spade;
+    ///
+    /// use nalgebra::Point2;
+    /// use spade::kernels::FloatKernel;
+    /// use spade::primitives::{PositionInTriangle, SimpleTriangle};
+    ///
+    /// # fn main() {
+    /// let triangle = SimpleTriangle::new(
+    ///     Point2::new(0f64, 0.),
+    ///     Point2::new(1., 0.),
+    ///     Point2::new(0., 1.),
+    /// );
+    /// let locate = |x, y| triangle.locate_point::<FloatKernel>(&Point2::new(x, y));
+    /// assert_eq!(locate(0.2, 0.2), PositionInTriangle::InTriangle);
+    /// assert_eq!(locate(0.5, 0.5), PositionInTriangle::OnEdge(1));
+    /// assert_eq!(locate(1.0, 0.0), PositionInTriangle::OnVertex(1));
+    /// assert_eq!(locate(1.0, 1.0), PositionInTriangle::Outside);
+    /// # }
+    /// ```
+    pub fn locate_point<K>(&self, point: &V) -> PositionInTriangle
+    where
+        K: DelaunayKernel<V::Scalar>,
+    {
+        let vertices = self.vertices();
+        if let Some(index) = vertices.iter().position(|v| *v == point) {
+            return PositionInTriangle::OnVertex(index);
+        }
+        let edges = [
+            SimpleEdge::new(self.v0.clone(), self.v1.clone()),
+            SimpleEdge::new(self.v1.clone(), self.v2.clone()),
+            SimpleEdge::new(self.v2.clone(), self.v0.clone()),
+        ];
+        if edges[0].side_query::<K>(&self.v2).is_on_line() {
+            // Degenerate triangle
+            return edges
+                .iter()
+                .position(|edge| edge.from != edge.to && K::point_on_edge(edge, point))
+                .map(PositionInTriangle::OnEdge)
+                .unwrap_or(PositionInTriangle::Outside);
+        }
+        let ordered_ccw = K::is_ordered_ccw(&self.v0, &self.v1, &self.v2);
+        let mut on_edge = None;
+        for (index, edge) in edges.iter().enumerate() {
+            let side = edge.side_query::<K>(point);
+            if side.is_on_line() {
+                on_edge = Some(index);
+            } else if side.is_on_right_side() == ordered_ccw {
+                return PositionInTriangle::Outside;
+            }
+        }
+        on_edge
+            .map(PositionInTriangle::OnEdge)
+            .unwrap_or(PositionInTriangle::InTriangle)
+    }
+}
+
+/// Describes where a point lies relative to a triangle.
+///
+/// Used as return type of `SimpleTriangle::locate_point`. Edge `i` connects
+/// vertex `i` and vertex `(i + 1) % 3`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde_serialize", derive(Serialize, Deserialize))]
+pub enum PositionInTriangle {
+    /// The point lies strictly inside the triangle.
+    InTriangle,
+    /// The point lies on an edge, but not on a vertex. The edge's index is given.
+    OnEdge(usize),
+    /// The point is equal to a vertex. The vertex's index is given.
+    OnVertex(usize),
+    /// The point lies outside of the triangle.
+    Outside,
 }
 
 impl<V> PartialEq for SimpleTriangle<V>
@@ -289,11 +636,11 @@ where
     }
 }
 
-impl<V> std::hash::Hash for SimpleTriangle<V>
+impl<V> core::hash::Hash for SimpleTriangle<V>
 where
-    V: PointN + std::hash::Hash,
+    V: PointN + core::hash::Hash,
 {
-    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
         // Needs to be adjusted as PartialEq is overwritten
         let mut to_sort = [&self.v0, &self.v1, &self.v2];
         to_sort.sort_by(|l, r| l.lex_compare(r));
@@ -325,6 +672,37 @@ where
         }
         p2
     }
+
+    /// Returns the triangle's centroid, the mean of its vertices.
+    pub fn centroid(&self) -> V {
+        let three: V::Scalar = NumCast::from(3.0).unwrap();
+        self.v0.add(&self.v1).add(&self.v2).div(three)
+    }
+
+    /// Returns the triangle's incenter, the center of its inscribed circle.
+    pub fn incenter(&self) -> V {
+        let a = self.v1.distance2(&self.v2).sqrt();
+        let b = self.v2.distance2(&self.v0).sqrt();
+        let c = self.v0.distance2(&self.v1).sqrt();
+        self.v0
+            .mul(a)
+            .add(&self.v1.mul(b))
+            .add(&self.v2.mul(c))
+            .div(a + b + c)
+    }
+
+    /// Returns the triangle's smallest interior angle in radians.
+    pub fn min_angle(&self) -> V::Scalar {
+        let angle = |at: &V, p1: &V, p2: &V| {
+            let (d1, d2) = (p1.sub(at), p2.sub(at));
+            let cos = d1.dot(&d2) / (d1.length2() * d2.length2()).sqrt();
+            crate::misc::clamp(-V::Scalar::one(), cos, one()).acos()
+        };
+        let a0 = angle(&self.v0, &self.v1, &self.v2);
+        let a1 = angle(&self.v1, &self.v2, &self.v0);
+        let a2 = angle(&self.v2, &self.v0, &self.v1);
+        a0.min(a1).min(a2)
+    }
 }
 
 impl<V> SimpleTriangle<V>
@@ -332,10 +710,19 @@ where
     V: TwoDimensional,
     V::Scalar: SpadeFloat,
 {
+    /// Applies an affine transformation to this triangle.
+    pub fn transform(&self, transform: &AffineTransform2<V::Scalar>) -> SimpleTriangle<V> {
+        SimpleTriangle::new(
+            transform.transform_point(&self.v0),
+            transform.transform_point(&self.v1),
+            transform.transform_point(&self.v2),
+        )
+    }
+
     /// Returns the position of the triangle's circumcenter.
     #[allow(clippy::many_single_char_names)]
     pub fn circumcenter(&self) -> V {
-        let one: V::Scalar = One::one();
+        let one: V::Scalar = one();
         let two = one + one;
         let b = self.v1.sub(&self.v0);
         let c = self.v2.sub(&self.v0);
@@ -351,6 +738,19 @@ where
         result.add(&self.v0)
     }
 
+    /// Returns the radius of the triangle's circumcircle.
+    pub fn circumradius(&self) -> V::Scalar {
+        self.circumcenter().distance2(&self.v0).sqrt()
+    }
+
+    /// Returns the radius of the triangle's inscribed circle.
+    pub fn inradius(&self) -> V::Scalar {
+        let perimeter = self.v0.distance2(&self.v1).sqrt()
+            + self.v1.distance2(&self.v2).sqrt()
+            + self.v2.distance2(&self.v0).sqrt();
+        self.double_area() / perimeter
+    }
+
     /// Returns the barycentric coordinates of a point.
     pub fn barycentric_interpolation(&self, coord: &V) -> Point3<V::Scalar> {
         let (v1, v2, v3) = (self.v0.clone(), self.v1.clone(), self.v2.clone());
@@ -363,6 +763,47 @@ where
         let lambda3 = one::<V::Scalar>() - lambda1 - lambda2;
         Point3::new(lambda1, lambda2, lambda3)
     }
+
+    /// Checks if this and another triangle overlap.
+    ///
+    /// Triangles that only touch each other are considered to overlap.
+    /// The kernel `K` determines how the required orientation tests are
+    /// performed, see `spade::kernels`.
+    pub fn intersects_triangle<K>(&self, other: &SimpleTriangle<V>) -> bool
+    where
+        K: DelaunayKernel<V::Scalar>,
+    {
+        let edges = |t: &SimpleTriangle<V>| {
+            [
+                SimpleEdge::new(t.v0.clone(), t.v1.clone()),
+                SimpleEdge::new(t.v1.clone(), t.v2.clone()),
+                SimpleEdge::new(t.v2.clone(), t.v0.clone()),
+            ]
+        };
+        let other_edges = edges(other);
+        edges(self).iter().any(|edge| {
+            other_edges
+                .iter()
+                .any(|o| edge.intersects_edge_non_collinear::<K>(o))
+        }) || self.contains_point::<K>(&other.v0)
+            || other.contains_point::<K>(&self.v0)
+    }
+
+    /// Calculates the area in which this and another triangle overlap.
+    ///
+    /// Returns the vertices of the convex intersection polygon in counterclockwise
+    /// order. The result is empty if the triangles do not overlap and contains
+    /// less than three vertices if they only touch.
+    pub fn triangle_intersection(&self, other: &SimpleTriangle<V>) -> Vec<V> {
+        let ccw = |t: &SimpleTriangle<V>| {
+            if cross(&t.v1.sub(&t.v0), &t.v2.sub(&t.v0)) < zero() {
+                vec![t.v0.clone(), t.v2.clone(), t.v1.clone()]
+            } else {
+                vec![t.v0.clone(), t.v1.clone(), t.v2.clone()]
+            }
+        };
+        clip_to_convex(&ccw(self), &ccw(other))
+    }
 }
 
 impl<V> SpatialObject for SimpleTriangle<V>
@@ -394,6 +835,116 @@ where
     }
 }
 
+/// A triangle in three dimensional space.
+///
+/// Unlike `SimpleTriangle`, this triangle can be inserted into a three
+/// dimensional r-tree, e.g. to index a terrain mesh.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde_serialize", derive(Serialize, Deserialize))]
+pub struct SimpleTriangle3<V: PointN> {
+    v0: V,
+    v1: V,
+    v2: V,
+}
+
+impl<V> SimpleTriangle3<V>
+where
+    V: ThreeDimensional,
+{
+    /// Creates a new triangle.
+    pub fn new(v0: V, v1: V, v2: V) -> SimpleTriangle3<V> {
+        SimpleTriangle3 { v0, v1, v2 }
+    }
+
+    /// Returns the triangle's vertices.
+    pub fn vertices(&self) -> [&V; 3] {
+        [&self.v0, &self.v1, &self.v2]
+    }
+
+    /// Returns the triangle's normal.
+    ///
+    /// The normal is not normalized, its length equals the triangle's doubled area.
+    pub fn normal(&self) -> V {
+        self.v1.sub(&self.v0).cross(&self.v2.sub(&self.v0))
+    }
+}
+
+impl<V> SimpleTriangle3<V>
+where
+    V: ThreeDimensional,
+    V::Scalar: SpadeFloat,
+{
+    /// Returns the triangle's area.
+    pub fn area(&self) -> V::Scalar {
+        let two: V::Scalar = NumCast::from(2.0).unwrap();
+        self.normal().length2().sqrt() / two
+    }
+
+    /// Returns the point of this triangle that is nearest to a given point.
+    pub fn nearest_point(&self, point: &V) -> V {
+        let (a, b, c) = (&self.v0, &self.v1, &self.v2);
+        let ab = b.sub(a);
+        let ac = c.sub(a);
+        let ap = point.sub(a);
+        let d1 = ab.dot(&ap);
+        let d2 = ac.dot(&ap);
+        if d1 <= zero() && d2 <= zero() {
+            return a.clone();
+        }
+        let bp = point.sub(b);
+        let d3 = ab.dot(&bp);
+        let d4 = ac.dot(&bp);
+        if d3 >= zero() && d4 <= d3 {
+            return b.clone();
+        }
+        let vc = d1 * d4 - d3 * d2;
+        if vc <= zero() && d1 >= zero() && d3 <= zero() {
+            return a.add(&ab.mul(d1 / (d1 - d3)));
+        }
+        let cp = point.sub(c);
+        let d5 = ab.dot(&cp);
+        let d6 = ac.dot(&cp);
+        if d6 >= zero() && d5 <= d6 {
+            return c.clone();
+        }
+        let vb = d5 * d2 - d1 * d6;
+        if vb <= zero() && d2 >= zero() && d6 <= zero() {
+            return a.add(&ac.mul(d2 / (d2 - d6)));
+        }
+        let va = d3 * d6 - d5 * d4;
+        if va <= zero() && d4 >= d3 && d5 >= d6 {
+            let w = (d4 - d3) / ((d4 - d3) + (d5 - d6));
+            return b.add(&c.sub(b).mul(w));
+        }
+        let denominator = va + vb + vc;
+        if denominator == zero() {
+            // Degenerate triangle
+            return SimpleTriangle::new(a.clone(), b.clone(), c.clone())
+                .nearest_point_on_edge(point);
+        }
+        a.add(&ab.mul(vb / denominator))
+            .add(&ac.mul(vc / denominator))
+    }
+}
+
+impl<V> SpatialObject for SimpleTriangle3<V>
+where
+    V: ThreeDimensional,
+    V::Scalar: SpadeFloat,
+{
+    type Point = V;
+
+    fn mbr(&self) -> BoundingRect<V> {
+        let mut result = BoundingRect::from_corners(&self.v0, &self.v1);
+        result.add_point(self.v2.clone());
+        result
+    }
+
+    fn distance2(&self, point: &V) -> V::Scalar {
+        self.nearest_point(point).distance2(point)
+    }
+}
+
 /// An n-dimensional circle, defined by its origin and radius.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[cfg_attr(feature = "serde_serialize", derive(Serialize, Deserialize))]
@@ -449,54 +1000,1003 @@ where
     }
 }
 
-#[cfg(test)]
-mod test {
-    use super::{SimpleCircle, SimpleEdge, SimpleTriangle};
-    use crate::kernels::{FloatKernel, TrivialKernel};
-    use crate::traits::SpatialObject;
-    use cgmath::{Point2, Point3};
+/// A capsule, the set of all points within a given distance to an edge.
+///
+/// Capsules describe buffered lines, e.g. roads, or circles swept along an edge.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde_serialize", derive(Serialize, Deserialize))]
+pub struct SimpleCapsule<V: PointN> {
+    /// The capsule's center line.
+    pub segment: SimpleEdge<V>,
+    /// The capsule's radius.
+    pub radius: V::Scalar,
+}
 
-    #[test]
-    fn test_edge_distance() {
-        let e = SimpleEdge::new(Point2::new(0f32, 0.), Point2::new(1., 1.));
-        assert_relative_eq!(e.distance2(&Point2::new(1.0, 0.0)), 0.5);
+impl<V> SimpleCapsule<V>
+where
+    V: PointN,
+    V::Scalar: SpadeFloat,
+{
+    /// Creates a new capsule around the edge from `from` to `to`.
+    pub fn new(from: V, to: V, radius: V::Scalar) -> SimpleCapsule<V> {
+        SimpleCapsule {
+            segment: SimpleEdge::new(from, to),
+            radius,
+        }
+    }
+}
 
-        assert_relative_eq!(e.distance2(&Point2::new(0.0, 1.)), 0.5);
-        assert_relative_eq!(e.distance2(&Point2::new(-1.0, -1.0)), 2.0);
-        assert_relative_eq!(e.distance2(&Point2::new(2.0, 2.0)), 2.0);
+impl<V> SpatialObject for SimpleCapsule<V>
+where
+    V: PointN,
+    V::Scalar: SpadeFloat,
+{
+    type Point = V;
+
+    fn mbr(&self) -> BoundingRect<V> {
+        self.segment.mbr().expanded_by(self.radius)
     }
 
-    #[test]
-    fn test_edge_side() {
-        let e = SimpleEdge::new(Point2::new(0f32, 0.), Point2::new(1., 1.));
-        assert!(e
-            .side_query::<TrivialKernel>(&Point2::new(1.0, 0.0))
-            .is_on_right_side());
-        assert!(e
-            .side_query::<TrivialKernel>(&Point2::new(0.0, 1.0))
-            .is_on_left_side());
-        assert!(e
-            .side_query::<TrivialKernel>(&Point2::new(0.5, 0.5))
-            .is_on_line());
+    fn distance2(&self, point: &V) -> V::Scalar {
+        let dist = (self.segment.distance2(point).sqrt() - self.radius).max(zero());
+        dist * dist
     }
 
-    #[test]
-    fn test_intersects_middle() {
-        let e1 = SimpleEdge::new(Point2::new(0f32, 0f32), Point2::new(5f32, 5f32));
-        let e2 = SimpleEdge::new(Point2::new(-1.5, 1.), Point2::new(1.0, -1.5));
-        let e3 = SimpleEdge::new(Point2::new(0.5, 4.), Point2::new(0.5, -4.));
-        assert!(!e1.intersects_edge_non_collinear::<TrivialKernel>(&e2));
-        assert!(!e2.intersects_edge_non_collinear::<TrivialKernel>(&e1));
-        assert!(e1.intersects_edge_non_collinear::<TrivialKernel>(&e3));
-        assert!(e3.intersects_edge_non_collinear::<TrivialKernel>(&e1));
-        assert!(e2.intersects_edge_non_collinear::<TrivialKernel>(&e3));
-        assert!(e3.intersects_edge_non_collinear::<TrivialKernel>(&e2));
+    fn contains(&self, point: &V) -> bool {
+        self.segment.distance2(point) <= self.radius * self.radius
     }
+}
 
-    #[test]
-    fn test_intersects_end_points() {
-        // Check for intersection of one endpoint touching another edge
-        let e1 = SimpleEdge::new(Point2::new(0.33f64, 0.33f64), Point2::new(1.0, 0.0));
+/// A rectangle that is rotated around its center.
+///
+/// Unlike `BoundingRect`, an oriented rectangle can tightly enclose long
+/// objects that are not aligned to the coordinate axes.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde_serialize", derive(Serialize, Deserialize))]
+pub struct OrientedRect<V: PointN> {
+    /// The rectangle's center.
+    pub center: V,
+    /// Half the rectangle's width and height, measured along its rotated axes.
+    pub half_extents: V,
+    /// The counterclockwise rotation of the rectangle in radians.
+    pub rotation: V::Scalar,
+}
+
+impl<V> OrientedRect<V>
+where
+    V: TwoDimensional,
+    V::Scalar: SpadeFloat,
+{
+    /// Creates a new oriented rectangle.
+    pub fn new(center: V, half_extents: V, rotation: V::Scalar) -> OrientedRect<V> {
+        OrientedRect {
+            center,
+            half_extents,
+            rotation,
+        }
+    }
+
+    /// Returns the rectangle's rotated x and y axis as unit vectors.
+    pub fn axes(&self) -> (V, V) {
+        let (sin, cos) = self.rotation.sin_cos();
+        let mut x_axis = V::new();
+        *x_axis.nth_mut(0) = cos;
+        *x_axis.nth_mut(1) = sin;
+        let mut y_axis = V::new();
+        *y_axis.nth_mut(0) = -sin;
+        *y_axis.nth_mut(1) = cos;
+        (x_axis, y_axis)
+    }
+
+    /// Returns the rectangle's corners in counterclockwise order.
+    pub fn corners(&self) -> [V; 4] {
+        let (x_axis, y_axis) = self.axes();
+        let x = x_axis.mul(*self.half_extents.nth(0));
+        let y = y_axis.mul(*self.half_extents.nth(1));
+        [
+            self.center.sub(&x).sub(&y),
+            self.center.add(&x).sub(&y),
+            self.center.add(&x).add(&y),
+            self.center.sub(&x).add(&y),
+        ]
+    }
+
+    /// Returns the rectangle's area.
+    pub fn area(&self) -> V::Scalar {
+        let four: V::Scalar = NumCast::from(4.0).unwrap();
+        four * *self.half_extents.nth(0) * *self.half_extents.nth(1)
+    }
+
+    /// Transforms a point into the rectangle's local coordinate system.
+    ///
+    /// The rectangle's center is mapped to the origin and its axes are
+    /// mapped to the coordinate axes.
+    pub fn to_local(&self, point: &V) -> V {
+        let (x_axis, y_axis) = self.axes();
+        let diff = point.sub(&self.center);
+        let mut result = V::new();
+        *result.nth_mut(0) = diff.dot(&x_axis);
+        *result.nth_mut(1) = diff.dot(&y_axis);
+        result
+    }
+}
+
+impl<V> SpatialObject for OrientedRect<V>
+where
+    V: TwoDimensional,
+    V::Scalar: SpadeFloat,
+{
+    type Point = V;
+
+    fn mbr(&self) -> BoundingRect<V> {
+        let (sin, cos) = self.rotation.sin_cos();
+        let (hx, hy) = (*self.half_extents.nth(0), *self.half_extents.nth(1));
+        let mut extent = V::new();
+        *extent.nth_mut(0) = cos.abs() * hx + sin.abs() * hy;
+        *extent.nth_mut(1) = sin.abs() * hx + cos.abs() * hy;
+        BoundingRect::from_corners(&self.center.sub(&extent), &self.center.add(&extent))
+    }
+
+    fn distance2(&self, point: &V) -> V::Scalar {
+        let local = self.to_local(point);
+        let mut result = zero();
+        for dim in 0..2 {
+            let outside = local.nth(dim).abs() - *self.half_extents.nth(dim);
+            if outside > zero() {
+                result += outside * outside;
+            }
+        }
+        result
+    }
+
+    fn contains(&self, point: &V) -> bool {
+        let local = self.to_local(point);
+        local.nth(0).abs() <= *self.half_extents.nth(0)
+            && local.nth(1).abs() <= *self.half_extents.nth(1)
+    }
+}
+
+/// A circular arc.
+///
+/// The arc starts at `start_angle` and sweeps around its center by `sweep`
+/// radians. A positive sweep runs counterclockwise, a negative sweep runs
+/// clockwise. Angles are measured counterclockwise from the positive x axis.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde_serialize", derive(Serialize, Deserialize))]
+pub struct SimpleArc<V: PointN> {
+    /// The center of the arc's circle.
+    pub center: V,
+    /// The radius of the arc's circle.
+    pub radius: V::Scalar,
+    /// The angle at which the arc starts, in radians.
+    pub start_angle: V::Scalar,
+    /// The arc's angular extent in radians.
+    pub sweep: V::Scalar,
+}
+
+impl<V> SimpleArc<V>
+where
+    V: TwoDimensional,
+    V::Scalar: SpadeFloat,
+{
+    /// Creates a new arc.
+    pub fn new(center: V, radius: V::Scalar, start_angle: V::Scalar, sweep: V::Scalar) -> Self {
+        SimpleArc {
+            center,
+            radius,
+            start_angle,
+            sweep,
+        }
+    }
+
+    /// Returns the point of the arc's circle at a given angle.
+    pub fn point_at_angle(&self, angle: V::Scalar) -> V {
+        let (sin, cos) = angle.sin_cos();
+        let mut result = V::new();
+        *result.nth_mut(0) = *self.center.nth(0) + self.radius * cos;
+        *result.nth_mut(1) = *self.center.nth(1) + self.radius * sin;
+        result
+    }
+
+    /// Returns the arc's start point.
+    pub fn start_point(&self) -> V {
+        self.point_at_angle(self.start_angle)
+    }
+
+    /// Returns the arc's end point.
+    pub fn end_point(&self) -> V {
+        self.point_at_angle(self.start_angle + self.sweep)
+    }
+
+    /// Returns the arc's length.
+    pub fn length(&self) -> V::Scalar {
+        self.radius * self.sweep.abs()
+    }
+
+    /// Returns `true` if the arc covers a given angle.
+    pub fn contains_angle(&self, angle: V::Scalar) -> bool {
+        let two_pi: V::Scalar = NumCast::from(2.0 * ::core::f64::consts::PI).unwrap();
+        if self.sweep.abs() >= two_pi {
+            return true;
+        }
+        let offset = if self.sweep >= zero() {
+            angle - self.start_angle
+        } else {
+            self.start_angle - angle
+        };
+        let mut offset = offset % two_pi;
+        if offset < zero() {
+            offset += two_pi;
+        }
+        offset <= self.sweep.abs()
+    }
+
+    /// Returns the nearest point on this arc.
+    pub fn nearest_point(&self, point: &V) -> V {
+        let diff = point.sub(&self.center);
+        if diff.length2() > zero() {
+            let angle = diff.nth(1).atan2(*diff.nth(0));
+            if self.contains_angle(angle) {
+                return self.point_at_angle(angle);
+            }
+        }
+        let (start, end) = (self.start_point(), self.end_point());
+        if start.distance2(point) <= end.distance2(point) {
+            start
+        } else {
+            end
+        }
+    }
+
+    /// Calculates all points in which this arc and an edge intersect.
+    ///
+    /// Returns up to two points, ordered by their distance to `edge.from`.
+    pub fn intersect_edge(&self, edge: &SimpleEdge<V>) -> Vec<V> {
+        let dir = edge.to.sub(&edge.from);
+        let to_from = edge.from.sub(&self.center);
+        let a = dir.length2();
+        if a == zero() {
+            return Vec::new();
+        }
+        let half_b = dir.dot(&to_from);
+        let c = to_from.length2() - self.radius * self.radius;
+        let discriminant = half_b * half_b - a * c;
+        if discriminant < zero() {
+            return Vec::new();
+        }
+        let root = discriminant.sqrt();
+        let mut parameters = vec![(-half_b - root) / a];
+        if root > zero() {
+            parameters.push((-half_b + root) / a);
+        }
+        parameters
+            .into_iter()
+            .filter(|t| *t >= zero() && *t <= one())
+            .map(|t| edge.from.add(&dir.mul(t)))
+            .filter(|p| {
+                let diff = p.sub(&self.center);
+                self.contains_angle(diff.nth(1).atan2(*diff.nth(0)))
+            })
+            .collect()
+    }
+}
+
+impl<V> SpatialObject for SimpleArc<V>
+where
+    V: TwoDimensional,
+    V::Scalar: SpadeFloat,
+{
+    type Point = V;
+
+    fn mbr(&self) -> BoundingRect<V> {
+        let mut result = BoundingRect::from_corners(&self.start_point(), &self.end_point());
+        let half_pi: V::Scalar = NumCast::from(::core::f64::consts::FRAC_PI_2).unwrap();
+        for quadrant in 0..4 {
+            let angle = half_pi * NumCast::from(quadrant).unwrap();
+            if self.contains_angle(angle) {
+                result.add_point(self.point_at_angle(angle));
+            }
+        }
+        result
+    }
+
+    fn distance2(&self, point: &V) -> V::Scalar {
+        self.nearest_point(point).distance2(point)
+    }
+}
+
+/// A simple polygon, defined by its vertices.
+///
+/// The polygon may be non convex but its boundary must not intersect itself.
+/// The vertices can be ordered clockwise or counterclockwise, the last
+/// vertex is implicitly connected to the first one.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde_serialize", derive(Serialize, Deserialize))]
+pub struct SimplePolygon<V: PointN> {
+    vertices: Vec<V>,
+}
+
+impl<V> SimplePolygon<V>
+where
+    V: TwoDimensional,
+{
+    /// Creates a new polygon from its vertices.
+    ///
+    /// # Panics
+    /// Panics if less than three vertices are given.
+    pub fn new(vertices: Vec<V>) -> SimplePolygon<V> {
+        assert!(
+            vertices.len() >= 3,
+            "A polygon requires at least three vertices"
+        );
+        SimplePolygon { vertices }
+    }
+
+    /// Returns the polygon's vertices.
+    pub fn vertices(&self) -> &[V] {
+        &self.vertices
+    }
+
+    /// Returns an iterator over the polygon's boundary edges.
+    pub fn edges(&self) -> impl Iterator<Item = SimpleEdge<V>> + '_ {
+        let n = self.vertices.len();
+        (0..n).map(move |i| {
+            SimpleEdge::new(self.vertices[i].clone(), self.vertices[(i + 1) % n].clone())
+        })
+    }
+
+    /// Returns the polygon's doubled signed area.
+    ///
+    /// The area is positive if the vertices are ordered counterclockwise.
+    pub fn double_signed_area(&self) -> V::Scalar {
+        self.edges().fold(zero(), |sum: V::Scalar, edge| {
+            sum + edge.from.nth(0).clone() * edge.to.nth(1).clone()
+                - edge.to.nth(0).clone() * edge.from.nth(1).clone()
+        })
+    }
+
+    /// Returns `true` if the vertices are ordered counterclockwise.
+    pub fn is_ordered_ccw(&self) -> bool {
+        self.double_signed_area() > zero()
+    }
+
+    /// Checks if a point is contained in this polygon or lies on its boundary.
+    ///
+    /// Uses the winding number of the polygon's boundary around the point. The kernel
+    /// `K` determines how the required orientation tests are performed, see
+    /// `spade::kernels`.
+    pub fn contains_point<K: DelaunayKernel<V::Scalar>>(&self, point: &V) -> bool {
+        if self.edges().any(|edge| K::point_on_edge(&edge, point)) {
+            return true;
+        }
+        crate::polygon::winding_number::<K, _>(&self.vertices, point) != 0
+    }
+
+    /// Returns `true` if this polygon is convex.
+    ///
+    /// See `spade::polygon::is_convex` for more details.
+    pub fn is_convex(&self) -> bool {
+        crate::polygon::is_convex(&self.vertices)
+    }
+}
+
+impl<V> SimplePolygon<V>
+where
+    V: TwoDimensional,
+    V::Scalar: SpadeFloat,
+{
+    /// Applies an affine transformation to this polygon.
+    pub fn transform(&self, transform: &AffineTransform2<V::Scalar>) -> SimplePolygon<V> {
+        SimplePolygon::new(
+            self.vertices
+                .iter()
+                .map(|v| transform.transform_point(v))
+                .collect(),
+        )
+    }
+
+    /// Returns the polygon's area.
+    pub fn area(&self) -> V::Scalar {
+        let two = V::Scalar::one() + V::Scalar::one();
+        self.double_signed_area().abs() / two
+    }
+
+    /// Returns the polygon's centroid.
+    ///
+    /// Returns `None` if the polygon's area is zero.
+    pub fn centroid(&self) -> Option<V> {
+        crate::polygon::centroid(&self.vertices)
+    }
+}
+
+impl<V> SpatialObject for SimplePolygon<V>
+where
+    V: TwoDimensional,
+    V::Scalar: SpadeFloat + FromPrimitive,
+{
+    type Point = V;
+
+    fn mbr(&self) -> BoundingRect<V> {
+        BoundingRect::from_points(self.vertices.iter().cloned())
+    }
+
+    fn distance2(&self, point: &V) -> V::Scalar {
+        if self.contains_point::<FloatKernel>(point) {
+            return zero();
+        }
+        self.edges()
+            .map(|edge| edge.distance2(point))
+            .fold(V::Scalar::infinity(), Float::min)
+    }
+
+    fn contains(&self, point: &V) -> bool {
+        self.contains_point::<FloatKernel>(point)
+    }
+}
+
+/// A convex polygon, defined by its vertices in counterclockwise order.
+///
+/// Unlike `SimplePolygon`, containment and extreme point queries run in
+/// `O(log n)`. This pays off if many queries are performed against the
+/// same polygon.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde_serialize", derive(Serialize, Deserialize))]
+pub struct ConvexPolygon<V: PointN> {
+    vertices: Vec<V>,
+}
+
+impl<V> ConvexPolygon<V>
+where
+    V: TwoDimensional,
+{
+    /// Creates a new convex polygon from its vertices.
+    ///
+    /// The vertices can be ordered clockwise or counterclockwise, they will
+    /// be stored in counterclockwise order.
+    ///
+    /// # Panics
+    /// Panics if less than three vertices are given or if the polygon is not
+    /// strictly convex, e.g. if three consecutive vertices are collinear.
+    pub fn new(mut vertices: Vec<V>) -> ConvexPolygon<V> {
+        assert!(
+            vertices.len() >= 3,
+            "A polygon requires at least three vertices"
+        );
+        let n = vertices.len();
+        let turn = |vertices: &[V], i: usize| {
+            let (v0, v1, v2) = (&vertices[i], &vertices[(i + 1) % n], &vertices[(i + 2) % n]);
+            cross(&v1.sub(v0), &v2.sub(v1))
+        };
+        if turn(&vertices, 0) < zero() {
+            vertices.reverse();
+        }
+        let first = &vertices[0];
+        let is_convex = (0..n).all(|i| turn(&vertices, i) > zero())
+            && (1..n - 1)
+                .all(|i| cross(&vertices[i].sub(first), &vertices[i + 1].sub(first)) > zero());
+        assert!(is_convex, "The polygon must be strictly convex");
+        ConvexPolygon { vertices }
+    }
+
+    /// Creates the convex hull of a set of points.
+    ///
+    /// Returns `None` if all points are collinear or if less than three
+    /// distinct points are given. The kernel `K` determines how orientation
+    /// tests are performed, see `spade::kernels`.
+    pub fn from_points<K>(points: &[V]) -> Option<ConvexPolygon<V>>
+    where
+        K: DelaunayKernel<V::Scalar>,
+    {
+        let hull = crate::hull::convex_hull::<K, _>(points);
+        if hull.len() < 3 {
+            None
+        } else {
+            Some(ConvexPolygon { vertices: hull })
+        }
+    }
+
+    /// Returns the polygon's vertices in counterclockwise order.
+    pub fn vertices(&self) -> &[V] {
+        &self.vertices
+    }
+
+    /// Returns an iterator over the polygon's boundary edges.
+    pub fn edges(&self) -> impl Iterator<Item = SimpleEdge<V>> + '_ {
+        let n = self.vertices.len();
+        (0..n).map(move |i| {
+            SimpleEdge::new(self.vertices[i].clone(), self.vertices[(i + 1) % n].clone())
+        })
+    }
+
+    /// Checks if a point is contained in this polygon or lies on its boundary.
+    ///
+    /// Runs in `O(log n)`. The kernel `K` determines how orientation tests
+    /// are performed, see `spade::kernels`.
+    pub fn contains_point<K>(&self, point: &V) -> bool
+    where
+        K: DelaunayKernel<V::Scalar>,
+    {
+        let vertices = &self.vertices;
+        let n = vertices.len();
+        let side = |from: usize, to: usize| {
+            K::side_query(
+                &SimpleEdge::new(vertices[from].clone(), vertices[to].clone()),
+                point,
+            )
+        };
+        // Check if the point lies within the wedge spanned at the first vertex
+        if side(0, 1).is_on_right_side() || side(0, n - 1).is_on_left_side() {
+            return false;
+        }
+        let (mut low, mut high) = (1, n - 1);
+        while high - low > 1 {
+            let mid = (low + high) / 2;
+            if side(0, mid).is_on_right_side() {
+                high = mid;
+            } else {
+                low = mid;
+            }
+        }
+        !side(low, low + 1).is_on_right_side()
+    }
+
+    /// Returns the vertex that lies farthest in a given direction.
+    ///
+    /// If two vertices are farthest away, any of them is returned.
+    /// Runs in `O(log n)`.
+    pub fn extreme_point(&self, direction: &V) -> &V {
+        &self.vertices[self.extreme_index(direction)]
+    }
+
+    fn extreme_index(&self, direction: &V) -> usize {
+        let vertices = &self.vertices;
+        let n = vertices.len();
+        let value = |i: usize| vertices[i % n].dot(direction);
+        let rises = |i: usize| value(i + 1) > value(i);
+        let is_maximum = |i: usize| !rises(i) && value(i + n - 1) <= value(i);
+        if is_maximum(0) {
+            return 0;
+        }
+        // Binary search on the cyclic sequence of values, which has a single maximum
+        let (mut low, mut high) = (0, n);
+        let mut low_rises = rises(0);
+        while high - low > 1 {
+            let mid = (low + high) / 2;
+            if is_maximum(mid) {
+                return mid;
+            }
+            let mid_rises = rises(mid);
+            let select_low = if low_rises {
+                !mid_rises || value(low) > value(mid)
+            } else {
+                !mid_rises && value(low) < value(mid)
+            };
+            if select_low {
+                high = mid;
+            } else {
+                low = mid;
+                low_rises = mid_rises;
+            }
+        }
+        if value(low) >= value(high) {
+            low % n
+        } else {
+            high % n
+        }
+    }
+}
+
+impl<V> ConvexPolygon<V>
+where
+    V: TwoDimensional,
+    V::Scalar: SpadeFloat,
+{
+    /// Returns the polygon's area.
+    pub fn area(&self) -> V::Scalar {
+        crate::polygon::area(&self.vertices)
+    }
+
+    /// Returns the polygon's centroid.
+    pub fn centroid(&self) -> V {
+        crate::polygon::centroid(&self.vertices).unwrap()
+    }
+}
+
+impl<V> SpatialObject for ConvexPolygon<V>
+where
+    V: TwoDimensional,
+    V::Scalar: SpadeFloat + FromPrimitive,
+{
+    type Point = V;
+
+    fn mbr(&self) -> BoundingRect<V> {
+        BoundingRect::from_points(self.vertices.iter().cloned())
+    }
+
+    fn distance2(&self, point: &V) -> V::Scalar {
+        if self.contains_point::<FloatKernel>(point) {
+            return zero();
+        }
+        self.edges()
+            .map(|edge| edge.distance2(point))
+            .fold(V::Scalar::infinity(), Float::min)
+    }
+
+    fn contains(&self, point: &V) -> bool {
+        self.contains_point::<FloatKernel>(point)
+    }
+}
+
+/// An infinite line, defined by two distinct points lying on it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde_serialize", derive(Serialize, Deserialize))]
+pub struct SimpleLine<V: PointN> {
+    /// A point on the line.
+    pub from: V,
+    /// Another point on the line, defining the line's direction.
+    pub to: V,
+}
+
+impl<V> SimpleLine<V>
+where
+    V: PointN,
+{
+    /// Creates a line going through two points.
+    pub fn new(from: V, to: V) -> SimpleLine<V> {
+        SimpleLine { from, to }
+    }
+
+    /// Creates a line going through a point along a direction.
+    pub fn with_direction(point: V, direction: &V) -> SimpleLine<V> {
+        let to = point.add(direction);
+        SimpleLine { from: point, to }
+    }
+
+    /// Returns the line's direction, pointing from `from` to `to`.
+    pub fn direction(&self) -> V {
+        self.to.sub(&self.from)
+    }
+}
+
+impl<V> SimpleLine<V>
+where
+    V: TwoDimensional,
+{
+    /// Determines on which side of this line a given point lies.
+    ///
+    /// The left side is the left side when looking along the line's direction.
+    pub fn side_query<K: DelaunayKernel<V::Scalar>>(&self, q: &V) -> EdgeSideInfo<V::Scalar> {
+        SimpleEdge::new(self.from.clone(), self.to.clone()).side_query::<K>(q)
+    }
+}
+
+impl<V> SimpleLine<V>
+where
+    V: PointN,
+    V::Scalar: SpadeFloat,
+{
+    /// Projects a point onto this line and returns its relative position.
+    ///
+    /// `from` has the position 0., `to` has the position 1.
+    pub fn project_point(&self, point: &V) -> V::Scalar {
+        let dir = self.direction();
+        point.sub(&self.from).dot(&dir) / dir.length2()
+    }
+
+    /// Returns the point on this line that is nearest to a given point.
+    pub fn nearest_point(&self, point: &V) -> V {
+        self.from
+            .add(&self.direction().mul(self.project_point(point)))
+    }
+
+    /// Returns the squared distance of a point to this line.
+    pub fn distance2(&self, point: &V) -> V::Scalar {
+        self.nearest_point(point).distance2(point)
+    }
+}
+
+impl<V> SimpleLine<V>
+where
+    V: TwoDimensional,
+    V::Scalar: SpadeFloat,
+{
+    /// Applies an affine transformation to this line.
+    pub fn transform(&self, transform: &AffineTransform2<V::Scalar>) -> SimpleLine<V> {
+        SimpleLine::new(
+            transform.transform_point(&self.from),
+            transform.transform_point(&self.to),
+        )
+    }
+
+    /// Calculates the point in which this and another line intersect.
+    ///
+    /// Returns `None` if the lines are parallel.
+    pub fn intersection(&self, other: &SimpleLine<V>) -> Option<V> {
+        let self_dir = self.direction();
+        let other_dir = other.direction();
+        let denominator = cross(&self_dir, &other_dir);
+        if denominator == zero() {
+            return None;
+        }
+        let s = cross(&other.from.sub(&self.from), &other_dir) / denominator;
+        Some(self.from.add(&self_dir.mul(s)))
+    }
+}
+
+/// A ray, starting at an origin and extending infinitely along a direction.
+///
+/// Intersection tests return the ray parameter `t` of the first intersection,
+/// the intersection point itself can be obtained by `point_at(t)`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde_serialize", derive(Serialize, Deserialize))]
+pub struct SimpleRay<V: PointN> {
+    /// The ray's origin.
+    pub origin: V,
+    /// The ray's direction. Does not need to be normalized.
+    pub direction: V,
+}
+
+impl<V> SimpleRay<V>
+where
+    V: PointN,
+    V::Scalar: SpadeFloat,
+{
+    /// Creates a new ray.
+    pub fn new(origin: V, direction: V) -> SimpleRay<V> {
+        SimpleRay { origin, direction }
+    }
+
+    /// Returns the point `origin + t * direction`.
+    pub fn point_at(&self, t: V::Scalar) -> V {
+        self.origin.add(&self.direction.mul(t))
+    }
+
+    /// Returns the squared distance of a point to this ray.
+    pub fn distance2(&self, point: &V) -> V::Scalar {
+        let t = point.sub(&self.origin).dot(&self.direction) / self.direction.length2();
+        self.point_at(t.max(zero())).sub(point).length2()
+    }
+
+    /// Returns the ray parameter at which the ray enters a bounding rectangle.
+    ///
+    /// Returns `Some(0)` if the ray's origin lies within the rectangle and `None`
+    /// if the ray misses it.
+    pub fn intersect_rect(&self, rect: &BoundingRect<V>) -> Option<V::Scalar> {
+        let (lower, upper) = (rect.lower(), rect.upper());
+        let mut t_min: V::Scalar = zero();
+        let mut t_max = V::Scalar::infinity();
+        for dim in 0..V::dimensions() {
+            let (origin, direction) = (*self.origin.nth(dim), *self.direction.nth(dim));
+            let (low, high) = (*lower.nth(dim), *upper.nth(dim));
+            if direction == zero() {
+                if origin < low || origin > high {
+                    return None;
+                }
+                continue;
+            }
+            let t1 = (low - origin) / direction;
+            let t2 = (high - origin) / direction;
+            t_min = t_min.max(t1.min(t2));
+            t_max = t_max.min(t1.max(t2));
+            if t_min > t_max {
+                return None;
+            }
+        }
+        Some(t_min)
+    }
+
+    /// Returns the ray parameter at which the ray enters a circle.
+    ///
+    /// Returns `Some(0)` if the ray's origin lies within the circle and `None`
+    /// if the ray misses it.
+    pub fn intersect_circle(&self, circle: &SimpleCircle<V>) -> Option<V::Scalar> {
+        let to_origin = self.origin.sub(&circle.center);
+        let c = to_origin.length2() - circle.radius * circle.radius;
+        if c <= zero() {
+            return Some(zero());
+        }
+        let a = self.direction.length2();
+        let half_b = self.direction.dot(&to_origin);
+        let discriminant = half_b * half_b - a * c;
+        if discriminant < zero() {
+            return None;
+        }
+        let t = (-half_b - discriminant.sqrt()) / a;
+        if t >= zero() {
+            Some(t)
+        } else {
+            None
+        }
+    }
+}
+
+impl<V> SimpleRay<V>
+where
+    V: TwoDimensional,
+    V::Scalar: SpadeFloat,
+{
+    /// Applies an affine transformation to this ray.
+    pub fn transform(&self, transform: &AffineTransform2<V::Scalar>) -> SimpleRay<V> {
+        SimpleRay::new(
+            transform.transform_point(&self.origin),
+            transform.transform_vector(&self.direction),
+        )
+    }
+
+    /// Returns the ray parameter of the first intersection with an edge.
+    ///
+    /// Returns `None` if the ray misses the edge. If the ray and the edge are
+    /// collinear, the parameter of the first common point is returned.
+    pub fn intersect_edge(&self, edge: &SimpleEdge<V>) -> Option<V::Scalar> {
+        let edge_dir = edge.to.sub(&edge.from);
+        let to_edge = edge.from.sub(&self.origin);
+        let denominator = cross(&self.direction, &edge_dir);
+        if denominator == zero() {
+            if cross(&to_edge, &self.direction) != zero() {
+                // Parallel, but not collinear
+                return None;
+            }
+            let length2 = self.direction.length2();
+            let t_from = to_edge.dot(&self.direction) / length2;
+            let t_to = edge.to.sub(&self.origin).dot(&self.direction) / length2;
+            if t_from < zero() && t_to < zero() {
+                return None;
+            }
+            return Some(t_from.min(t_to).max(zero()));
+        }
+        let t = cross(&to_edge, &edge_dir) / denominator;
+        let s = cross(&to_edge, &self.direction) / denominator;
+        if t >= zero() && s >= zero() && s <= one() {
+            Some(t)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the ray parameter at which the ray enters a triangle.
+    ///
+    /// Returns `Some(0)` if the ray's origin lies within the triangle and `None`
+    /// if the ray misses it.
+    pub fn intersect_triangle(&self, triangle: &SimpleTriangle<V>) -> Option<V::Scalar> {
+        if triangle.distance2(&self.origin) <= zero() {
+            return Some(zero());
+        }
+        let [v0, v1, v2] = triangle.vertices();
+        [(v0, v1), (v1, v2), (v2, v0)]
+            .iter()
+            .filter_map(|(from, to)| {
+                self.intersect_edge(&SimpleEdge::new((*from).clone(), (*to).clone()))
+            })
+            .fold(None, |min: Option<V::Scalar>, t| match min {
+                Some(min) if min <= t => Some(min),
+                _ => Some(t),
+            })
+    }
+}
+
+/// Wraps a spatial object and caches its minimum bounding rectangle.
+///
+/// R-trees call `mbr` repeatedly during insertion, queries and rebalancing.
+/// For objects with an expensive `mbr`, e.g. polygons with many vertices,
+/// the wrapper computes it only once when it is created. Distances and
+/// containment are still delegated to the wrapped object.
+///
+/// The wrapped object cannot be mutated, as this would invalidate the
+/// cached rectangle.
+///
+/// # Example
+/// ```
+/// use spade::primitives::{CachedEnvelope, SimplePolygon};
+/// use spade::rtree::RTree;
+/// use spade::Point2;
+///
+/// let mut tree = RTree::new();
+/// let polygon = SimplePolygon::new(vec![
+///     Point2::new(0.0, 0.0),
+///     Point2::new(2.0, 0.0),
+///     Point2::new(1.0, 2.0),
+/// ]);
+/// tree.insert(CachedEnvelope::new(polygon));
+/// let found = tree.lookup_in_circle(&Point2::new(1.0, -1.0), &1.5);
+/// assert_eq!(found[0].object().vertices().len(), 3);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct CachedEnvelope<T: SpatialObject> {
+    object: T,
+    mbr: BoundingRect<T::Point>,
+}
+
+impl<T: SpatialObject> CachedEnvelope<T> {
+    /// Wraps an object and computes its minimum bounding rectangle.
+    pub fn new(object: T) -> CachedEnvelope<T> {
+        let mbr = object.mbr();
+        CachedEnvelope { object, mbr }
+    }
+
+    /// Returns the wrapped object.
+    pub fn object(&self) -> &T {
+        &self.object
+    }
+
+    /// Unwraps the object.
+    pub fn into_inner(self) -> T {
+        self.object
+    }
+}
+
+impl<T: SpatialObject> SpatialObject for CachedEnvelope<T> {
+    type Point = T::Point;
+
+    fn mbr(&self) -> BoundingRect<T::Point> {
+        self.mbr.clone()
+    }
+
+    fn distance2(&self, point: &T::Point) -> <T::Point as PointN>::Scalar {
+        self.object.distance2(point)
+    }
+
+    fn contains(&self, point: &T::Point) -> bool {
+        self.object.contains(point)
+    }
+}
+
+fn cross<V: TwoDimensional>(v0: &V, v1: &V) -> V::Scalar {
+    v0.nth(0).clone() * v1.nth(1).clone() - v0.nth(1).clone() * v1.nth(0).clone()
+}
+
+#[cfg(test)]
+mod test {
+    use super::{
+        CachedEnvelope, ConvexPolygon, OrientedRect, SimpleArc, SimpleCapsule, SimpleCircle,
+        SimpleEdge, SimpleLine, SimplePolygon, SimpleRay, SimpleTriangle, SimpleTriangle3,
+    };
+    use crate::boundingrect::BoundingRect;
+    use crate::kernels::{FloatKernel, TrivialKernel};
+    use crate::traits::SpatialObject;
+    use crate::transform::AffineTransform2;
+    use cgmath::{Point2, Point3};
+
+    #[test]
+    fn test_edge_distance() {
+        let e = SimpleEdge::new(Point2::new(0f32, 0.), Point2::new(1., 1.));
+        assert_relative_eq!(e.distance2(&Point2::new(1.0, 0.0)), 0.5);
+
+        assert_relative_eq!(e.distance2(&Point2::new(0.0, 1.)), 0.5);
+        assert_relative_eq!(e.distance2(&Point2::new(-1.0, -1.0)), 2.0);
+        assert_relative_eq!(e.distance2(&Point2::new(2.0, 2.0)), 2.0);
+    }
+
+    #[test]
+    fn test_edge_side() {
+        let e = SimpleEdge::new(Point2::new(0f32, 0.), Point2::new(1., 1.));
+        assert!(e
+            .side_query::<TrivialKernel>(&Point2::new(1.0, 0.0))
+            .is_on_right_side());
+        assert!(e
+            .side_query::<TrivialKernel>(&Point2::new(0.0, 1.0))
+            .is_on_left_side());
+        assert!(e
+            .side_query::<TrivialKernel>(&Point2::new(0.5, 0.5))
+            .is_on_line());
+    }
+
+    #[test]
+    fn test_intersects_middle() {
+        let e1 = SimpleEdge::new(Point2::new(0f32, 0f32), Point2::new(5f32, 5f32));
+        let e2 = SimpleEdge::new(Point2::new(-1.5, 1.), Point2::new(1.0, -1.5));
+        let e3 = SimpleEdge::new(Point2::new(0.5, 4.), Point2::new(0.5, -4.));
+        assert!(!e1.intersects_edge_non_collinear::<TrivialKernel>(&e2));
+        assert!(!e2.intersects_edge_non_collinear::<TrivialKernel>(&e1));
+        assert!(e1.intersects_edge_non_collinear::<TrivialKernel>(&e3));
+        assert!(e3.intersects_edge_non_collinear::<TrivialKernel>(&e1));
+        assert!(e2.intersects_edge_non_collinear::<TrivialKernel>(&e3));
+        assert!(e3.intersects_edge_non_collinear::<TrivialKernel>(&e2));
+    }
+
+    #[test]
+    fn test_intersects_end_points() {
+        // Check for intersection of one endpoint touching another edge
+        let e1 = SimpleEdge::new(Point2::new(0.33f64, 0.33f64), Point2::new(1.0, 0.0));
         let e2 = SimpleEdge::new(Point2::new(0.33, -1.0), Point2::new(0.33, 1.0));
         assert!(e1.intersects_edge_non_collinear::<FloatKernel>(&e2));
         assert!(e2.intersects_edge_non_collinear::<FloatKernel>(&e1));
@@ -510,11 +2010,64 @@ mod test {
     }
 
     #[test]
-    #[should_panic]
-    fn test_collinear_fail() {
+    fn test_intersects_collinear() {
         let e1 = SimpleEdge::new(Point2::new(1.0, 2.0), Point2::new(3.0, 3.0));
         let e2 = SimpleEdge::new(Point2::new(-1.0, 1.0), Point2::new(-3.0, 0.0));
-        e1.intersects_edge_non_collinear::<FloatKernel>(&e2);
+        assert!(!e1.intersects_edge_non_collinear::<FloatKernel>(&e2));
+        let e3 = SimpleEdge::new(Point2::new(5.0, 4.0), Point2::new(-1.0, 1.0));
+        assert!(e1.intersects_edge_non_collinear::<FloatKernel>(&e3));
+        assert!(e2.intersects_edge_non_collinear::<FloatKernel>(&e3));
+    }
+
+    #[test]
+    fn test_classify_intersection() {
+        use super::SegmentIntersection::*;
+        let e1 = SimpleEdge::new(Point2::new(0.0f64, 0.0), Point2::new(4.0, 2.0));
+        let crossing = SimpleEdge::new(Point2::new(1.0, 3.0), Point2::new(3.0, -1.0));
+        match e1.classify_intersection::<FloatKernel>(&crossing) {
+            Crossing(p) => {
+                assert_relative_eq!(p.x, 2.0);
+                assert_relative_eq!(p.y, 1.0);
+            }
+            other => panic!("Unexpected intersection: {:?}", other),
+        }
+        let touching = SimpleEdge::new(Point2::new(2.0, 1.0), Point2::new(2.0, 5.0));
+        assert_eq!(
+            e1.classify_intersection::<FloatKernel>(&touching),
+            Touching(Point2::new(2.0, 1.0))
+        );
+        assert_eq!(
+            touching.classify_intersection::<FloatKernel>(&e1),
+            Touching(Point2::new(2.0, 1.0))
+        );
+        let disjoint = SimpleEdge::new(Point2::new(5.0, 3.0), Point2::new(6.0, -3.0));
+        assert_eq!(e1.classify_intersection::<FloatKernel>(&disjoint), Disjoint);
+        let parallel = SimpleEdge::new(Point2::new(0.0, 1.0), Point2::new(4.0, 3.0));
+        assert_eq!(e1.classify_intersection::<FloatKernel>(&parallel), Disjoint);
+
+        let reversed = SimpleEdge::new(Point2::new(4.0, 2.0), Point2::new(0.0, 0.0));
+        let overlapping = SimpleEdge::new(Point2::new(6.0, 3.0), Point2::new(2.0, 1.0));
+        assert_eq!(
+            e1.classify_intersection::<FloatKernel>(&overlapping),
+            CollinearOverlap(SimpleEdge::new(
+                Point2::new(2.0, 1.0),
+                Point2::new(4.0, 2.0)
+            ))
+        );
+        assert_eq!(
+            reversed.classify_intersection::<FloatKernel>(&overlapping),
+            CollinearOverlap(SimpleEdge::new(
+                Point2::new(4.0, 2.0),
+                Point2::new(2.0, 1.0)
+            ))
+        );
+        let adjacent = SimpleEdge::new(Point2::new(4.0, 2.0), Point2::new(8.0, 4.0));
+        assert_eq!(
+            e1.classify_intersection::<FloatKernel>(&adjacent),
+            Touching(Point2::new(4.0, 2.0))
+        );
+        let separate = SimpleEdge::new(Point2::new(6.0, 3.0), Point2::new(8.0, 4.0));
+        assert_eq!(e1.classify_intersection::<FloatKernel>(&separate), Disjoint);
     }
 
     #[test]
@@ -582,4 +2135,643 @@ mod test {
         assert!(!c.contains(&p2));
         assert!(!c.contains(&p3));
     }
+
+    #[test]
+    fn test_polygon() {
+        // An L shaped polygon
+        let vertices = vec![
+            Point2::new(0.0, 0.0),
+            Point2::new(2.0, 0.0),
+            Point2::new(2.0, 1.0),
+            Point2::new(1.0, 1.0),
+            Point2::new(1.0, 2.0),
+            Point2::new(0.0, 2.0),
+        ];
+        let polygon = SimplePolygon::new(vertices.clone());
+        assert!(polygon.is_ordered_ccw());
+        assert_relative_eq!(polygon.area(), 3.0);
+        assert_eq!(polygon.edges().count(), 6);
+        let mbr = polygon.mbr();
+        assert_eq!(mbr.lower(), Point2::new(0.0, 0.0));
+        assert_eq!(mbr.upper(), Point2::new(2.0, 2.0));
+
+        let reversed = SimplePolygon::new(vertices.into_iter().rev().collect());
+        assert!(!reversed.is_ordered_ccw());
+        assert_relative_eq!(reversed.area(), 3.0);
+        for polygon in &[&polygon, &reversed] {
+            assert!(polygon.contains(&Point2::new(0.5, 1.5)));
+            assert!(polygon.contains(&Point2::new(1.5, 0.5)));
+            assert!(!polygon.contains(&Point2::new(1.5, 1.5)));
+            // Points on the boundary and on vertices
+            assert!(polygon.contains(&Point2::new(1.0, 1.5)));
+            assert!(polygon.contains(&Point2::new(1.0, 1.0)));
+            assert!(polygon.contains(&Point2::new(0.0, 2.0)));
+            // Points on the extension of a horizontal edge
+            assert!(!polygon.contains(&Point2::new(3.0, 1.0)));
+            assert!(!polygon.contains(&Point2::new(-1.0, 2.0)));
+            assert_eq!(polygon.distance2(&Point2::new(0.5, 0.5)), 0.0);
+            assert_relative_eq!(polygon.distance2(&Point2::new(1.5, 1.5)), 0.25);
+            assert_relative_eq!(polygon.distance2(&Point2::new(3.0, 3.0)), 5.0);
+        }
+
+        let integral = SimplePolygon::new(vec![[0i64, 0], [4, 0], [0, 4]]);
+        assert_eq!(integral.double_signed_area(), 16);
+        assert!(integral.contains_point::<TrivialKernel>(&[1, 1]));
+        assert!(integral.contains_point::<TrivialKernel>(&[2, 2]));
+        assert!(!integral.contains_point::<TrivialKernel>(&[3, 3]));
+    }
+
+    #[test]
+    fn test_ray() {
+        let ray = SimpleRay::new(Point2::new(0.0, 0.0), Point2::new(2.0, 1.0));
+        assert_eq!(ray.point_at(2.0), Point2::new(4.0, 2.0));
+        assert_relative_eq!(ray.distance2(&Point2::new(-1.0, 0.0)), 1.0);
+        assert_relative_eq!(ray.distance2(&Point2::new(0.0, 5.0)), 20.0);
+
+        let edge = SimpleEdge::new(Point2::new(4.0, -1.0), Point2::new(4.0, 5.0));
+        assert_relative_eq!(ray.intersect_edge(&edge).unwrap(), 2.0);
+        let behind = SimpleEdge::new(Point2::new(-4.0, -1.0), Point2::new(-4.0, 5.0));
+        assert_eq!(ray.intersect_edge(&behind), None);
+        let short = SimpleEdge::new(Point2::new(4.0, 3.0), Point2::new(4.0, 5.0));
+        assert_eq!(ray.intersect_edge(&short), None);
+        let parallel = SimpleEdge::new(Point2::new(0.0, 1.0), Point2::new(2.0, 2.0));
+        assert_eq!(ray.intersect_edge(&parallel), None);
+        let collinear = SimpleEdge::new(Point2::new(8.0, 4.0), Point2::new(6.0, 3.0));
+        assert_relative_eq!(ray.intersect_edge(&collinear).unwrap(), 3.0);
+
+        let triangle = SimpleTriangle::new(
+            Point2::new(4.0, 0.0),
+            Point2::new(6.0, 0.0),
+            Point2::new(4.0, 4.0),
+        );
+        assert_relative_eq!(ray.intersect_triangle(&triangle).unwrap(), 2.0);
+        let inside = SimpleRay::new(Point2::new(4.5, 0.5), Point2::new(1.0, 0.0));
+        assert_eq!(inside.intersect_triangle(&triangle), Some(0.0));
+        let miss = SimpleRay::new(Point2::new(0.0, 0.0), Point2::new(-1.0, 0.0));
+        assert_eq!(miss.intersect_triangle(&triangle), None);
+
+        let circle = SimpleCircle::new(Point2::new(10.0, 5.0), 5.0);
+        assert_relative_eq!(
+            ray.intersect_circle(&circle).unwrap(),
+            5.0 - 5.0 / 5f64.sqrt()
+        );
+        assert_eq!(miss.intersect_circle(&circle), None);
+        let circle = SimpleCircle::new(Point2::new(0.0, 0.0), 1.0);
+        assert_eq!(ray.intersect_circle(&circle), Some(0.0));
+
+        let rect = BoundingRect::from_corners(&Point2::new(2.0, -1.0), &Point2::new(3.0, 3.0));
+        assert_relative_eq!(ray.intersect_rect(&rect).unwrap(), 1.0);
+        assert_eq!(miss.intersect_rect(&rect), None);
+        let vertical = SimpleRay::new(Point2::new(2.5, -5.0), Point2::new(0.0, 1.0));
+        assert_relative_eq!(vertical.intersect_rect(&rect).unwrap(), 4.0);
+        let outside = SimpleRay::new(Point2::new(5.0, -5.0), Point2::new(0.0, 1.0));
+        assert_eq!(outside.intersect_rect(&rect), None);
+        let ray3 = SimpleRay::new(Point3::new(0.0, 0.0, 0.0), Point3::new(1.0, 1.0, 1.0));
+        let cube =
+            BoundingRect::from_corners(&Point3::new(1.0, 1.0, 1.0), &Point3::new(2.0, 2.0, 2.0));
+        assert_relative_eq!(ray3.intersect_rect(&cube).unwrap(), 1.0);
+    }
+
+    #[test]
+    fn test_edge_intersection_point() {
+        let e1 = SimpleEdge::new(Point2::new(0.0f64, 0.0), Point2::new(4.0, 2.0));
+        let e2 = SimpleEdge::new(Point2::new(1.0, 3.0), Point2::new(3.0, -1.0));
+        let (s, t) = e1.intersection_parameters::<FloatKernel>(&e2).unwrap();
+        assert_relative_eq!(s, 0.5);
+        assert_relative_eq!(t, 0.5);
+        let p = e1.intersection_point::<FloatKernel>(&e2).unwrap();
+        assert_relative_eq!(p.x, 2.0);
+        assert_relative_eq!(p.y, 1.0);
+        let p = e2.intersection_point::<FloatKernel>(&e1).unwrap();
+        assert_relative_eq!(p.x, 2.0);
+        assert_relative_eq!(p.y, 1.0);
+
+        // Touching edges
+        let e3 = SimpleEdge::new(Point2::new(4.0, 2.0), Point2::new(5.0, 0.0));
+        assert_eq!(
+            e1.intersection_parameters::<FloatKernel>(&e3),
+            Some((1.0, 0.0))
+        );
+        // Disjoint, parallel and collinear edges
+        let e4 = SimpleEdge::new(Point2::new(5.0, 3.0), Point2::new(6.0, -3.0));
+        assert_eq!(e1.intersection_point::<FloatKernel>(&e4), None);
+        let e5 = SimpleEdge::new(Point2::new(0.0, 1.0), Point2::new(4.0, 3.0));
+        assert_eq!(e1.intersection_point::<FloatKernel>(&e5), None);
+        let e6 = SimpleEdge::new(Point2::new(2.0, 1.0), Point2::new(6.0, 3.0));
+        assert_eq!(e1.intersection_point::<FloatKernel>(&e6), None);
+    }
+
+    #[test]
+    fn test_edge_to_edge_distance() {
+        let e1 = SimpleEdge::new(Point2::new(0.0f64, 0.0), Point2::new(4.0, 0.0));
+        let crossing = SimpleEdge::new(Point2::new(1.0, -1.0), Point2::new(2.0, 1.0));
+        assert_eq!(e1.distance2_to_edge(&crossing), 0.0);
+        let above = SimpleEdge::new(Point2::new(1.0, 2.0), Point2::new(3.0, 5.0));
+        assert_relative_eq!(e1.distance2_to_edge(&above), 4.0);
+        assert_relative_eq!(above.distance2_to_edge(&e1), 4.0);
+        let parallel = SimpleEdge::new(Point2::new(-2.0, 1.0), Point2::new(6.0, 1.0));
+        assert_relative_eq!(e1.distance2_to_edge(&parallel), 1.0);
+        let collinear = SimpleEdge::new(Point2::new(7.0, 0.0), Point2::new(5.0, 0.0));
+        assert_relative_eq!(e1.distance2_to_edge(&collinear), 1.0);
+        let diagonal = SimpleEdge::new(Point2::new(5.0, 1.0), Point2::new(7.0, 3.0));
+        assert_relative_eq!(e1.distance2_to_edge(&diagonal), 2.0);
+        let point = SimpleEdge::new(Point2::new(2.0, -3.0), Point2::new(2.0, -3.0));
+        assert_relative_eq!(e1.distance2_to_edge(&point), 9.0);
+        assert_relative_eq!(point.distance2_to_edge(&e1), 9.0);
+        assert_relative_eq!(point.distance2_to_edge(&point), 0.0);
+
+        // Skew edges in three dimensions
+        let e2 = SimpleEdge::new(Point3::new(0.0f64, 0.0, 0.0), Point3::new(2.0, 0.0, 0.0));
+        let e3 = SimpleEdge::new(Point3::new(1.0, -1.0, 3.0), Point3::new(1.0, 1.0, 3.0));
+        assert_relative_eq!(e2.distance2_to_edge(&e3), 9.0);
+    }
+
+    #[test]
+    fn test_triangle_centers() {
+        let triangle = SimpleTriangle::new(
+            Point2::new(0.0f64, 0.0),
+            Point2::new(4.0, 0.0),
+            Point2::new(0.0, 3.0),
+        );
+        let centroid = triangle.centroid();
+        assert_relative_eq!(centroid.x, 4.0 / 3.0);
+        assert_relative_eq!(centroid.y, 1.0);
+        let incenter = triangle.incenter();
+        assert_relative_eq!(incenter.x, 1.0);
+        assert_relative_eq!(incenter.y, 1.0);
+        assert_relative_eq!(triangle.inradius(), 1.0);
+        assert_relative_eq!(triangle.circumradius(), 2.5);
+        assert_relative_eq!(triangle.min_angle(), (3.0f64 / 5.0).asin());
+
+        let equilateral = SimpleTriangle::new(
+            Point2::new(0.0f64, 0.0),
+            Point2::new(2.0, 0.0),
+            Point2::new(1.0, 3.0f64.sqrt()),
+        );
+        let incenter = equilateral.incenter();
+        let circumcenter = equilateral.circumcenter();
+        assert_relative_eq!(incenter.x, circumcenter.x);
+        assert_relative_eq!(incenter.y, circumcenter.y);
+        assert_relative_eq!(equilateral.circumradius(), 2.0 * equilateral.inradius());
+        assert_relative_eq!(equilateral.min_angle(), ::std::f64::consts::PI / 3.0);
+    }
+
+    #[test]
+    fn test_triangle_locate_point() {
+        use super::PositionInTriangle::*;
+        let ccw = SimpleTriangle::new(
+            Point2::new(0.0f64, 0.0),
+            Point2::new(3.0, 0.0),
+            Point2::new(0.0, 3.0),
+        );
+        let cw = SimpleTriangle::new(
+            Point2::new(0.0f64, 0.0),
+            Point2::new(0.0, 3.0),
+            Point2::new(3.0, 0.0),
+        );
+        for triangle in &[ccw, cw] {
+            let locate = |x, y| triangle.locate_point::<FloatKernel>(&Point2::new(x, y));
+            assert_eq!(locate(1.0, 1.0), InTriangle);
+            assert_eq!(
+                locate(0.0, 3.0),
+                OnVertex(if triangle == &ccw { 2 } else { 1 })
+            );
+            assert_eq!(locate(4.0, 0.0), Outside);
+            assert_eq!(locate(-1.0, 1.0), Outside);
+            assert!(triangle.contains_point::<FloatKernel>(&Point2::new(0.1, 2.9)));
+            assert!(!triangle.contains_point::<FloatKernel>(&Point2::new(1.6, 1.6)));
+        }
+        assert_eq!(
+            ccw.locate_point::<FloatKernel>(&Point2::new(1.5, 0.0)),
+            OnEdge(0)
+        );
+        assert_eq!(
+            ccw.locate_point::<FloatKernel>(&Point2::new(1.5, 1.5)),
+            OnEdge(1)
+        );
+        assert_eq!(
+            cw.locate_point::<FloatKernel>(&Point2::new(1.5, 1.5)),
+            OnEdge(1)
+        );
+        assert_eq!(
+            cw.locate_point::<FloatKernel>(&Point2::new(0.0, 1.0)),
+            OnEdge(0)
+        );
+        // A point very close to an edge must not be reported on the edge
+        let near = Point2::new(0.1f64 + 0.2, 0.0 + 1e-17);
+        assert_eq!(ccw.locate_point::<FloatKernel>(&near), InTriangle);
+
+        let degenerate = SimpleTriangle::new(
+            Point2::new(0.0f64, 0.0),
+            Point2::new(1.0, 1.0),
+            Point2::new(2.0, 2.0),
+        );
+        assert_eq!(
+            degenerate.locate_point::<FloatKernel>(&Point2::new(0.5, 0.5)),
+            OnEdge(0)
+        );
+        assert_eq!(
+            degenerate.locate_point::<FloatKernel>(&Point2::new(1.0, 0.0)),
+            Outside
+        );
+        assert_eq!(
+            degenerate.locate_point::<FloatKernel>(&Point2::new(3.0, 3.0)),
+            Outside
+        );
+    }
+
+    #[test]
+    fn test_triangle_intersection() {
+        let t1 = SimpleTriangle::new(
+            Point2::new(0.0f64, 0.0),
+            Point2::new(4.0, 0.0),
+            Point2::new(0.0, 4.0),
+        );
+        // Clockwise ordered
+        let t2 = SimpleTriangle::new(
+            Point2::new(1.0, 1.0),
+            Point2::new(1.0, 5.0),
+            Point2::new(5.0, 1.0),
+        );
+        assert!(t1.intersects_triangle::<FloatKernel>(&t2));
+        let overlap = t1.triangle_intersection(&t2);
+        assert_eq!(overlap.len(), 3);
+        let area = SimplePolygon::new(overlap.clone()).area();
+        assert_relative_eq!(area, 2.0);
+        assert!(SimplePolygon::new(overlap).is_ordered_ccw());
+
+        // Contained triangle
+        let inner = SimpleTriangle::new(
+            Point2::new(0.5, 0.5),
+            Point2::new(1.0, 0.5),
+            Point2::new(0.5, 1.0),
+        );
+        assert!(t1.intersects_triangle::<FloatKernel>(&inner));
+        assert!(inner.intersects_triangle::<FloatKernel>(&t1));
+        assert_relative_eq!(
+            SimplePolygon::new(t1.triangle_intersection(&inner)).area(),
+            0.125
+        );
+
+        // Touching and disjoint triangles
+        let touching = SimpleTriangle::new(
+            Point2::new(4.0, 0.0),
+            Point2::new(6.0, 0.0),
+            Point2::new(6.0, 2.0),
+        );
+        assert!(t1.intersects_triangle::<FloatKernel>(&touching));
+        assert!(t1.triangle_intersection(&touching).len() < 3);
+        let disjoint = SimpleTriangle::new(
+            Point2::new(3.0, 3.0),
+            Point2::new(6.0, 3.0),
+            Point2::new(3.0, 6.0),
+        );
+        assert!(!t1.intersects_triangle::<FloatKernel>(&disjoint));
+        assert!(t1.triangle_intersection(&disjoint).is_empty());
+
+        // Star of david
+        let up = SimpleTriangle::new(
+            Point2::new(0.0, 0.0),
+            Point2::new(6.0, 0.0),
+            Point2::new(3.0, 6.0),
+        );
+        let down = SimpleTriangle::new(
+            Point2::new(0.0, 4.0),
+            Point2::new(3.0, -2.0),
+            Point2::new(6.0, 4.0),
+        );
+        assert_eq!(up.triangle_intersection(&down).len(), 6);
+    }
+
+    #[test]
+    fn test_oriented_rect() {
+        let quarter = ::std::f64::consts::FRAC_PI_4;
+        let rect = OrientedRect::new(Point2::new(1.0f64, 1.0), Point2::new(2.0, 0.5), quarter);
+        assert_relative_eq!(rect.area(), 4.0);
+        let corners = rect.corners();
+        assert!(SimplePolygon::new(corners.to_vec()).is_ordered_ccw());
+        assert_relative_eq!(SimplePolygon::new(corners.to_vec()).area(), 4.0);
+        for corner in &corners {
+            assert!(rect.distance2(corner) < 1e-20);
+        }
+
+        let mbr = rect.mbr();
+        let extent = 2.5 / 2.0f64.sqrt();
+        assert_relative_eq!(mbr.lower().x, 1.0 - extent);
+        assert_relative_eq!(mbr.upper().y, 1.0 + extent);
+
+        assert!(rect.contains(&Point2::new(2.0, 2.0)));
+        assert!(rect.contains(&Point2::new(1.0, 1.0)));
+        // Contained in the mbr, but not in the rectangle
+        let outside = Point2::new(1.0 - extent + 0.1, 1.0 + extent - 0.1);
+        assert!(mbr.contains_point(&outside));
+        assert!(!rect.contains(&outside));
+        assert_relative_eq!(rect.distance2(&Point2::new(1.0, 1.0)), 0.0);
+        // One unit beyond the long side
+        let beyond = Point2::new(1.0 - 1.5 * quarter.sin(), 1.0 + 1.5 * quarter.cos());
+        assert_relative_eq!(rect.distance2(&beyond), 1.0);
+
+        let aligned = OrientedRect::new(Point2::new(0.0f64, 0.0), Point2::new(1.0, 2.0), 0.0);
+        assert_relative_eq!(aligned.distance2(&Point2::new(4.0, 6.0)), 25.0);
+        let local = aligned.to_local(&Point2::new(3.0, -1.0));
+        assert_relative_eq!(local.x, 3.0);
+        assert_relative_eq!(local.y, -1.0);
+    }
+
+    #[test]
+    fn test_arc() {
+        use std::f64::consts::{FRAC_PI_2, PI};
+        // Upper half of the unit circle
+        let arc = SimpleArc::new(Point2::new(0.0f64, 0.0), 1.0, 0.0, PI);
+        assert_relative_eq!(arc.length(), PI);
+        assert!(arc.contains_angle(FRAC_PI_2));
+        assert!(arc.contains_angle(-3.0 * PI / 2.0));
+        assert!(!arc.contains_angle(-FRAC_PI_2));
+        let end = arc.end_point();
+        assert_relative_eq!(end.x, -1.0);
+        assert_relative_eq!(end.y, 0.0, epsilon = 1e-15);
+
+        let nearest = arc.nearest_point(&Point2::new(0.0, 3.0));
+        assert_relative_eq!(nearest.x, 0.0, epsilon = 1e-15);
+        assert_relative_eq!(nearest.y, 1.0);
+        assert_relative_eq!(arc.distance2(&Point2::new(0.0, 3.0)), 4.0);
+        assert_relative_eq!(arc.distance2(&Point2::new(0.0, 0.0)), 1.0);
+        // Below the arc, the end points are closest
+        assert_relative_eq!(arc.distance2(&Point2::new(0.5, -1.0)), 1.25);
+
+        let mbr = arc.mbr();
+        assert_relative_eq!(mbr.lower().x, -1.0);
+        assert_relative_eq!(mbr.lower().y, 0.0, epsilon = 1e-15);
+        assert_relative_eq!(mbr.upper().x, 1.0);
+        assert_relative_eq!(mbr.upper().y, 1.0);
+
+        // Clockwise arc from the top to the right
+        let cw = SimpleArc::new(Point2::new(1.0f64, 1.0), 2.0, FRAC_PI_2, -FRAC_PI_2);
+        assert!(cw.contains_angle(PI / 4.0));
+        assert!(!cw.contains_angle(PI));
+        let mbr = cw.mbr();
+        assert_relative_eq!(mbr.lower().x, 1.0, epsilon = 1e-15);
+        assert_relative_eq!(mbr.upper().x, 3.0);
+
+        let edge = SimpleEdge::new(Point2::new(-2.0, 0.5), Point2::new(2.0, 0.5));
+        let hits = arc.intersect_edge(&edge);
+        assert_eq!(hits.len(), 2);
+        assert_relative_eq!(hits[0].x, -(0.75f64.sqrt()));
+        assert_relative_eq!(hits[1].x, 0.75f64.sqrt());
+        let below = SimpleEdge::new(Point2::new(-2.0, -0.5), Point2::new(2.0, -0.5));
+        assert!(arc.intersect_edge(&below).is_empty());
+        let short = SimpleEdge::new(Point2::new(0.0, 0.5), Point2::new(2.0, 0.5));
+        assert_eq!(arc.intersect_edge(&short).len(), 1);
+        let tangent = SimpleEdge::new(Point2::new(-2.0, 1.0), Point2::new(2.0, 1.0));
+        assert_eq!(arc.intersect_edge(&tangent).len(), 1);
+    }
+
+    #[test]
+    fn test_triangle3() {
+        let triangle = SimpleTriangle3::new(
+            Point3::new(0.0f64, 0.0, 0.0),
+            Point3::new(2.0, 0.0, 0.0),
+            Point3::new(0.0, 2.0, 0.0),
+        );
+        assert_eq!(triangle.normal(), Point3::new(0.0, 0.0, 4.0));
+        assert_relative_eq!(triangle.area(), 2.0);
+        let mbr = triangle.mbr();
+        assert_eq!(mbr.lower(), Point3::new(0.0, 0.0, 0.0));
+        assert_eq!(mbr.upper(), Point3::new(2.0, 2.0, 0.0));
+
+        // Above the interior
+        assert_relative_eq!(triangle.distance2(&Point3::new(0.5, 0.5, 3.0)), 9.0);
+        // Closest to a vertex
+        assert_relative_eq!(triangle.distance2(&Point3::new(-1.0, -1.0, 1.0)), 3.0);
+        assert_relative_eq!(triangle.distance2(&Point3::new(3.0, 0.0, 0.0)), 1.0);
+        assert_relative_eq!(triangle.distance2(&Point3::new(0.0, 4.0, 0.0)), 4.0);
+        // Closest to an edge
+        assert_relative_eq!(triangle.distance2(&Point3::new(1.0, -2.0, 0.0)), 4.0);
+        assert_relative_eq!(triangle.distance2(&Point3::new(-1.0, 1.0, 0.0)), 1.0);
+        assert_relative_eq!(triangle.distance2(&Point3::new(2.0, 2.0, 1.0)), 3.0);
+        let nearest = triangle.nearest_point(&Point3::new(2.0, 2.0, 1.0));
+        assert_relative_eq!(nearest.x, 1.0);
+        assert_relative_eq!(nearest.y, 1.0);
+        assert_relative_eq!(nearest.z, 0.0);
+
+        let degenerate = SimpleTriangle3::new(
+            Point3::new(0.0f64, 0.0, 0.0),
+            Point3::new(1.0, 1.0, 1.0),
+            Point3::new(2.0, 2.0, 2.0),
+        );
+        assert_relative_eq!(degenerate.distance2(&Point3::new(3.0, 3.0, 3.0)), 3.0);
+
+        let mut tree = crate::rtree::RTree::new();
+        tree.insert(triangle);
+        tree.insert(SimpleTriangle3::new(
+            Point3::new(0.0, 0.0, 5.0),
+            Point3::new(2.0, 0.0, 5.0),
+            Point3::new(0.0, 2.0, 5.0),
+        ));
+        let nn = tree.nearest_neighbor(&Point3::new(0.5, 0.5, 1.0)).unwrap();
+        assert_eq!(*nn, triangle);
+    }
+
+    #[test]
+    fn test_capsule() {
+        let capsule = SimpleCapsule::new(Point2::new(0.0f64, 0.0), Point2::new(4.0, 0.0), 1.0);
+        let mbr = capsule.mbr();
+        assert_eq!(mbr.lower(), Point2::new(-1.0, -1.0));
+        assert_eq!(mbr.upper(), Point2::new(5.0, 1.0));
+        assert!(capsule.contains(&Point2::new(2.0, 1.0)));
+        assert!(capsule.contains(&Point2::new(4.5, 0.5)));
+        assert!(!capsule.contains(&Point2::new(4.8, 0.8)));
+        assert_eq!(capsule.distance2(&Point2::new(2.0, 0.5)), 0.0);
+        assert_relative_eq!(capsule.distance2(&Point2::new(2.0, 3.0)), 4.0);
+        assert_relative_eq!(capsule.distance2(&Point2::new(-3.0, 0.0)), 4.0);
+        assert_relative_eq!(capsule.distance2(&Point2::new(7.0, 4.0)), 16.0);
+
+        let capsule3 = SimpleCapsule::new(
+            Point3::new(0.0f64, 0.0, 0.0),
+            Point3::new(0.0, 0.0, 2.0),
+            0.5,
+        );
+        assert_relative_eq!(capsule3.distance2(&Point3::new(1.5, 0.0, 1.0)), 1.0);
+        assert!(capsule3.contains(&Point3::new(0.0, 0.0, 2.5)));
+    }
+
+    #[test]
+    fn test_cached_envelope() {
+        use core::cell::Cell;
+
+        #[derive(Clone, Debug, PartialEq)]
+        struct Counted<'a> {
+            circle: SimpleCircle<Point2<f64>>,
+            mbr_calls: &'a Cell<usize>,
+        }
+
+        impl SpatialObject for Counted<'_> {
+            type Point = Point2<f64>;
+
+            fn mbr(&self) -> BoundingRect<Point2<f64>> {
+                self.mbr_calls.set(self.mbr_calls.get() + 1);
+                self.circle.mbr()
+            }
+
+            fn distance2(&self, point: &Point2<f64>) -> f64 {
+                self.circle.distance2(point)
+            }
+        }
+
+        let mbr_calls = Cell::new(0);
+        let mut tree = crate::rtree::RTree::new();
+        for i in 0..100 {
+            let circle = SimpleCircle::new(Point2::new(f64::from(i % 10), f64::from(i / 10)), 0.2);
+            let cached = CachedEnvelope::new(Counted {
+                circle,
+                mbr_calls: &mbr_calls,
+            });
+            assert_eq!(cached.mbr(), circle.mbr());
+            tree.insert(cached);
+        }
+        assert_eq!(mbr_calls.get(), 100);
+        let nearest = tree.nearest_neighbor(&Point2::new(3.1, 4.0)).unwrap();
+        assert_eq!(nearest.object().circle.center, Point2::new(3.0, 4.0));
+        assert_relative_eq!(nearest.distance2(&Point2::new(3.0, 4.5)), 0.09);
+        assert!(nearest.contains(&Point2::new(3.1, 4.1)));
+        assert_eq!(tree.lookup_in_circle(&Point2::new(5.0, 5.0), &1.0).len(), 5);
+        assert_eq!(mbr_calls.get(), 100);
+        let nearest = nearest.clone();
+        assert!(tree.remove(&nearest));
+        assert_eq!(nearest.into_inner().circle.radius, 0.2);
+    }
+
+    #[test]
+    fn test_line() {
+        let line = SimpleLine::new(Point2::new(0.0f64, 1.0), Point2::new(2.0, 2.0));
+        let diagonal = SimpleLine::with_direction(Point2::new(1.0, 1.0), &Point2::new(1.0, 1.0));
+        assert_eq!(diagonal.to, Point2::new(2.0, 2.0));
+        assert_eq!(line.direction(), Point2::new(2.0, 1.0));
+        assert!(line
+            .side_query::<FloatKernel>(&Point2::new(10.0, 20.0))
+            .is_on_left_side());
+        assert!(line
+            .side_query::<FloatKernel>(&Point2::new(-10.0, -20.0))
+            .is_on_right_side());
+        assert!(line
+            .side_query::<FloatKernel>(&Point2::new(-4.0, -1.0))
+            .is_on_line());
+
+        // Projections are not restricted to the segment between from and to
+        assert_relative_eq!(line.project_point(&Point2::new(6.0, 4.0)), 3.0);
+        assert_relative_eq!(line.project_point(&Point2::new(-2.0, 0.0)), -1.0);
+        assert_relative_eq!(line.distance2(&Point2::new(-4.0, -1.0)), 0.0);
+        assert_relative_eq!(diagonal.distance2(&Point2::new(-5.0, -3.0)), 2.0);
+        let nearest = diagonal.nearest_point(&Point2::new(-5.0, -3.0));
+        assert_relative_eq!(nearest.x, -4.0);
+        assert_relative_eq!(nearest.y, -4.0);
+
+        let p = line.intersection(&diagonal).unwrap();
+        assert_relative_eq!(p.x, 2.0);
+        assert_relative_eq!(p.y, 2.0);
+        let parallel = SimpleLine::new(Point2::new(0.0, 0.0), Point2::new(-4.0, -2.0));
+        assert_eq!(line.intersection(&parallel), None);
+
+        let line3 = SimpleLine::new(Point3::new(0.0f64, 0.0, 0.0), Point3::new(0.0, 0.0, 1.0));
+        assert_relative_eq!(line3.distance2(&Point3::new(3.0, 4.0, -7.0)), 25.0);
+    }
+
+    #[test]
+    fn test_transform_primitives() {
+        let transform = AffineTransform2::rotation(::std::f64::consts::FRAC_PI_2)
+            .then(&AffineTransform2::translation(1.0, 0.0));
+        let edge = SimpleEdge::new(Point2::new(0.0f64, 0.0), Point2::new(2.0, 0.0));
+        let transformed = edge.transform(&transform);
+        assert_relative_eq!(transformed.to.x, 1.0, epsilon = 1e-15);
+        assert_relative_eq!(transformed.to.y, 2.0);
+        assert_relative_eq!(transformed.length2(), 4.0);
+
+        let triangle = SimpleTriangle::new(
+            Point2::new(0.0f64, 0.0),
+            Point2::new(1.0, 0.0),
+            Point2::new(0.0, 1.0),
+        );
+        let scaled = triangle.transform(&AffineTransform2::scaling(2.0, 3.0));
+        assert_eq!(scaled.vertices()[2], &Point2::new(0.0, 3.0));
+        assert_eq!(scaled.double_area(), 6.0);
+
+        let polygon = SimplePolygon::new(vec![
+            Point2::new(0.0f64, 0.0),
+            Point2::new(1.0, 0.0),
+            Point2::new(1.0, 1.0),
+        ]);
+        let mirrored = polygon.transform(&AffineTransform2::scaling(-1.0, 1.0));
+        assert!(!mirrored.is_ordered_ccw());
+
+        let ray = SimpleRay::new(Point2::new(1.0f64, 1.0), Point2::new(1.0, 0.0));
+        let moved = ray.transform(&AffineTransform2::translation(1.0, 2.0));
+        assert_eq!(moved.origin, Point2::new(2.0, 3.0));
+        assert_eq!(moved.direction, Point2::new(1.0, 0.0));
+        let line = SimpleLine::new(Point2::new(1.0f64, 1.0), Point2::new(2.0, 1.0));
+        let moved = line.transform(&AffineTransform2::translation(1.0, 2.0));
+        assert_eq!(moved.from, Point2::new(2.0, 3.0));
+    }
+
+    #[test]
+    fn test_convex_polygon() {
+        use crate::testutils::*;
+        let seed = b"\x3a\x91\xc4\x07\x5e\xd2\x68\xbf\x13\xa6\x4d\xf9\x20\x8c\x75\xe1\
+                     \x9b\x46\x02\xdd\x37\xf0\x6a\xc5\x18\x83\x5f\xb4\x29\xee\x70\x0d";
+        let points = random_points_with_seed::<f64>(200, seed);
+        let polygon = ConvexPolygon::from_points::<FloatKernel>(&points).unwrap();
+        let reference = SimplePolygon::new(polygon.vertices().to_vec());
+        assert!(reference.is_ordered_ccw());
+        for point in random_points_with_seed::<f64>(500, &[7; 32]) {
+            let point = Point2::new(point.x * 1.2, point.y * 1.2);
+            assert_eq!(
+                polygon.contains_point::<FloatKernel>(&point),
+                reference.contains_point::<FloatKernel>(&point)
+            );
+            let extreme = polygon.extreme_point(&point);
+            let max = polygon
+                .vertices()
+                .iter()
+                .map(|v| v.x * point.x + v.y * point.y)
+                .fold(f64::NEG_INFINITY, f64::max);
+            assert_eq!(extreme.x * point.x + extreme.y * point.y, max);
+        }
+        for vertex in polygon.vertices() {
+            assert!(polygon.contains_point::<FloatKernel>(vertex));
+        }
+
+        let square = ConvexPolygon::new(vec![
+            Point2::new(0.0f64, 0.0),
+            Point2::new(0.0, 1.0),
+            Point2::new(1.0, 1.0),
+            Point2::new(1.0, 0.0),
+        ]);
+        assert_eq!(square.vertices()[1], Point2::new(1.0, 1.0));
+        assert!(square.contains(&Point2::new(0.5, 0.0)));
+        assert!(!square.contains(&Point2::new(1.5, 0.0)));
+        assert!(!square.contains(&Point2::new(-0.5, 0.0)));
+        assert_eq!(square.distance2(&Point2::new(2.0, 2.0)), 2.0);
+        assert_eq!(square.distance2(&Point2::new(0.5, 0.5)), 0.0);
+        assert_eq!(square.area(), 1.0);
+        assert_eq!(square.centroid(), Point2::new(0.5, 0.5));
+        assert_eq!(
+            square.extreme_point(&Point2::new(1.0, 0.1)),
+            &Point2::new(1.0, 1.0)
+        );
+        assert!(ConvexPolygon::from_points::<FloatKernel>(&[
+            Point2::new(0.0, 0.0),
+            Point2::new(1.0, 1.0)
+        ])
+        .is_none());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_non_convex_polygon() {
+        ConvexPolygon::new(vec![
+            Point2::new(0.0f64, 0.0),
+            Point2::new(2.0, 0.0),
+            Point2::new(1.0, 0.5),
+            Point2::new(1.0, 2.0),
+        ]);
+    }
 }
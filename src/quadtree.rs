@@ -0,0 +1,778 @@
+// Copyright 2017 The Spade Developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Region quadtrees and octrees.
+//!
+//! A `QuadTree` recursively splits its region into equally sized cells: four
+//! quadrants for two dimensional points, eight octants for three dimensional
+//! points. A cell is split as soon as it contains more than a fixed number of
+//! objects and merged again once its objects fit into a single cell. The
+//! region grows automatically if an object is inserted outside of it.
+//!
+//! Quadtrees suit sparse and frequently changing point sets: insertions and
+//! removals only touch the cells along a single path and never require
+//! rebalancing. Cells of the same depth always have the same size, which makes
+//! them a natural fit for level of detail processing, see `QuadTree::traverse`.
+//!
+//! # Example
+//!
+//! ```
+//! use spade::quadtree::QuadTree;
+//! use spade::{BoundingRect, Point2};
+//!
+//! let region = BoundingRect::from_corners(&Point2::new(0.0, 0.0), &Point2::new(100.0, 100.0));
+//! let mut tree = QuadTree::new(region);
+//! for i in 0..100 {
+//!     tree.insert(Point2::new(f64::from(i), f64::from(i % 10)));
+//! }
+//! assert_eq!(tree.nearest_neighbor(&Point2::new(41.9, 1.8)), Some(&Point2::new(42.0, 2.0)));
+//!
+//! // Visit the cells down to a depth of three. Deeper cells are summarized by
+//! // their ancestor.
+//! let mut coarse_cells = 0;
+//! tree.traverse(|cell| {
+//!     if cell.depth() == 3 || cell.is_leaf() {
+//!         coarse_cells += 1;
+//!         false
+//!     } else {
+//!         true
+//!     }
+//! });
+//! assert!(coarse_cells > 1);
+//! ```
+
+use crate::boundingrect::BoundingRect;
+use crate::instrumentation::{record, Counter};
+use crate::point_traits::{PointN, PointNExtensions};
+use crate::traits::HasPosition;
+use crate::validation::{validate_point, NonFiniteCoordinateError};
+use alloc::collections::BinaryHeap;
+use alloc::{vec, vec::Vec};
+use core::cmp::Ordering;
+use num::{one, zero};
+
+/// Parameters of a `QuadTree`.
+///
+/// # Example
+/// ```
+/// use spade::quadtree::{QuadTree, QuadTreeOptions};
+/// use spade::{BoundingRect, Point2};
+///
+/// let options = QuadTreeOptions::new().leaf_capacity(16).max_depth(10);
+/// let region = BoundingRect::from_corners(&Point2::new(-1.0, -1.0), &Point2::new(1.0, 1.0));
+/// let mut tree = QuadTree::new_with_options(region, options);
+/// tree.insert(Point2::new(0.5, 0.5));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct QuadTreeOptions {
+    leaf_capacity: usize,
+    max_depth: usize,
+}
+
+impl Default for QuadTreeOptions {
+    fn default() -> Self {
+        QuadTreeOptions {
+            leaf_capacity: 8,
+            max_depth: 24,
+        }
+    }
+}
+
+impl QuadTreeOptions {
+    /// Creates the default options.
+    ///
+    /// Cells are split once they contain more than 8 objects, the maximum depth is 24.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Sets the maximum number of objects stored in a cell before it is split.
+    ///
+    /// # Panics
+    /// Panics if `leaf_capacity` is zero.
+    pub fn leaf_capacity(mut self, leaf_capacity: usize) -> Self {
+        assert!(leaf_capacity > 0, "leaf_capacity must be positive");
+        self.leaf_capacity = leaf_capacity;
+        self
+    }
+
+    /// Sets the maximum depth of a cell.
+    ///
+    /// Cells at this depth are never split, even if they contain more than
+    /// `leaf_capacity` objects. This limits the depth for duplicate points.
+    pub fn max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+}
+
+/// An octree, a `QuadTree` of three dimensional objects.
+pub type Octree<T> = QuadTree<T>;
+
+#[derive(Clone, Debug)]
+enum Node<T> {
+    Leaf(Vec<T>),
+    Branch { children: Vec<Node<T>>, size: usize },
+}
+
+/// Returns the index of the child cell containing a position.
+///
+/// Bit `i` of the index is set if the position lies in the upper half of axis `i`.
+fn child_index<P: PointN>(center: &P, position: &P) -> usize {
+    let mut index = 0;
+    for i in 0..P::dimensions() {
+        if position.nth(i) >= center.nth(i) {
+            index |= 1 << i;
+        }
+    }
+    index
+}
+
+fn child_bounds<P: PointN>(bounds: &BoundingRect<P>, index: usize) -> BoundingRect<P> {
+    let center = bounds.center();
+    let mut lower = bounds.lower();
+    let mut upper = bounds.upper();
+    for i in 0..P::dimensions() {
+        if index & (1 << i) == 0 {
+            *upper.nth_mut(i) = center.nth(i).clone();
+        } else {
+            *lower.nth_mut(i) = center.nth(i).clone();
+        }
+    }
+    BoundingRect::from_corners(&lower, &upper)
+}
+
+impl<T: HasPosition> Node<T> {
+    fn size(&self) -> usize {
+        match self {
+            Node::Leaf(objects) => objects.len(),
+            Node::Branch { size, .. } => *size,
+        }
+    }
+
+    fn depth(&self) -> usize {
+        match self {
+            Node::Leaf(_) => 0,
+            Node::Branch { children, .. } => {
+                1 + children.iter().map(Node::depth).max().unwrap_or(0)
+            }
+        }
+    }
+
+    fn insert(
+        &mut self,
+        object: T,
+        bounds: &BoundingRect<T::Point>,
+        depth: usize,
+        options: &QuadTreeOptions,
+    ) {
+        match self {
+            Node::Leaf(objects) => {
+                objects.push(object);
+                self.split_if_full(bounds, depth, options);
+            }
+            Node::Branch { children, size } => {
+                *size += 1;
+                let index = child_index(&bounds.center(), &object.position());
+                let bounds = child_bounds(bounds, index);
+                children[index].insert(object, &bounds, depth + 1, options);
+            }
+        }
+    }
+
+    fn split_if_full(
+        &mut self,
+        bounds: &BoundingRect<T::Point>,
+        depth: usize,
+        options: &QuadTreeOptions,
+    ) {
+        let objects = match self {
+            Node::Leaf(objects)
+                if objects.len() > options.leaf_capacity && depth < options.max_depth =>
+            {
+                ::core::mem::take(objects)
+            }
+            _ => return,
+        };
+        let center = bounds.center();
+        let size = objects.len();
+        let mut children: Vec<_> = (0..1 << T::Point::dimensions())
+            .map(|_| Node::Leaf(Vec::new()))
+            .collect();
+        for object in objects {
+            if let Node::Leaf(child) = &mut children[child_index(&center, &object.position())] {
+                child.push(object);
+            }
+        }
+        for (index, child) in children.iter_mut().enumerate() {
+            child.split_if_full(&child_bounds(bounds, index), depth + 1, options);
+        }
+        *self = Node::Branch { children, size };
+    }
+
+    fn remove(
+        &mut self,
+        object: &T,
+        bounds: &BoundingRect<T::Point>,
+        options: &QuadTreeOptions,
+    ) -> bool
+    where
+        T: PartialEq,
+    {
+        let merge = match self {
+            Node::Leaf(objects) => {
+                return match objects.iter().position(|other| other == object) {
+                    Some(index) => {
+                        objects.swap_remove(index);
+                        true
+                    }
+                    None => false,
+                };
+            }
+            Node::Branch { children, size } => {
+                let index = child_index(&bounds.center(), &object.position());
+                if !children[index].remove(object, &child_bounds(bounds, index), options) {
+                    return false;
+                }
+                *size -= 1;
+                *size <= options.leaf_capacity
+            }
+        };
+        if merge {
+            let mut objects = Vec::with_capacity(self.size());
+            ::core::mem::replace(self, Node::Leaf(Vec::new())).drain_into(&mut objects);
+            *self = Node::Leaf(objects);
+        }
+        true
+    }
+
+    fn drain_into(self, result: &mut Vec<T>) {
+        match self {
+            Node::Leaf(objects) => result.extend(objects),
+            Node::Branch { children, .. } => {
+                for child in children {
+                    child.drain_into(result);
+                }
+            }
+        }
+    }
+
+    fn lookup<'a, C, A>(
+        &'a self,
+        bounds: &BoundingRect<T::Point>,
+        intersects: &C,
+        accept: &A,
+        result: &mut Vec<&'a T>,
+    ) where
+        C: Fn(&BoundingRect<T::Point>) -> bool,
+        A: Fn(&T) -> bool,
+    {
+        record(Counter::NodeVisits, 1);
+        match self {
+            Node::Leaf(objects) => result.extend(objects.iter().filter(|object| accept(object))),
+            Node::Branch { children, .. } => {
+                for (index, child) in children.iter().enumerate() {
+                    let child_bounds = child_bounds(bounds, index);
+                    if child.size() > 0 && intersects(&child_bounds) {
+                        child.lookup(&child_bounds, intersects, accept, result);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// A region quadtree of objects with a position.
+///
+/// Works with points of any dimension, e.g. as quadtree for two dimensional
+/// and as octree for three dimensional points. Each cell has `2^d` children,
+/// the tree is thus only suited for low dimensions. See the
+/// [module documentation](index.html) for more information.
+#[derive(Clone, Debug)]
+pub struct QuadTree<T: HasPosition> {
+    root: Node<T>,
+    bounds: BoundingRect<T::Point>,
+    options: QuadTreeOptions,
+}
+
+/// A cell visited by `QuadTree::traverse`.
+pub struct QuadTreeCell<'a, T: HasPosition> {
+    node: &'a Node<T>,
+    bounds: BoundingRect<T::Point>,
+    depth: usize,
+}
+
+impl<'a, T: HasPosition> QuadTreeCell<'a, T> {
+    /// Returns the region covered by this cell.
+    pub fn bounds(&self) -> &BoundingRect<T::Point> {
+        &self.bounds
+    }
+
+    /// Returns the cell's depth. The root cell has depth zero.
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Returns the number of objects contained in this cell and all of its descendants.
+    pub fn size(&self) -> usize {
+        self.node.size()
+    }
+
+    /// Returns `true` if this cell has not been split.
+    pub fn is_leaf(&self) -> bool {
+        matches!(self.node, Node::Leaf(_))
+    }
+
+    /// Returns the objects stored directly in this cell.
+    ///
+    /// Only leaf cells store objects, the slice of a split cell is empty.
+    pub fn objects(&self) -> &'a [T] {
+        match self.node {
+            Node::Leaf(objects) => objects,
+            Node::Branch { .. } => &[],
+        }
+    }
+
+    /// Returns an iterator over all objects contained in this cell and its descendants.
+    pub fn iter(&self) -> QuadTreeIterator<'a, T> {
+        QuadTreeIterator::new(self.node)
+    }
+}
+
+/// Iterates over all objects of a `QuadTree` or of one of its cells.
+pub struct QuadTreeIterator<'a, T: HasPosition> {
+    stack: Vec<&'a Node<T>>,
+    current: ::core::slice::Iter<'a, T>,
+}
+
+impl<'a, T: HasPosition> QuadTreeIterator<'a, T> {
+    fn new(root: &'a Node<T>) -> Self {
+        QuadTreeIterator {
+            stack: vec![root],
+            current: [].iter(),
+        }
+    }
+}
+
+impl<'a, T: HasPosition> Iterator for QuadTreeIterator<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        loop {
+            if let Some(object) = self.current.next() {
+                return Some(object);
+            }
+            match self.stack.pop()? {
+                Node::Leaf(objects) => self.current = objects.iter(),
+                Node::Branch { children, .. } => self.stack.extend(children.iter()),
+            }
+        }
+    }
+}
+
+enum QueueEntry<'a, T: HasPosition> {
+    Cell(&'a Node<T>, BoundingRect<T::Point>),
+    Object(&'a T),
+}
+
+/// An entry of the best first search of a nearest neighbor query.
+///
+/// Ordered by decreasing distance, `BinaryHeap` yields the closest entry first.
+struct Queued<'a, T: HasPosition> {
+    distance2: <T::Point as PointN>::Scalar,
+    entry: QueueEntry<'a, T>,
+}
+
+impl<'a, T: HasPosition> PartialEq for Queued<'a, T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.distance2 == other.distance2
+    }
+}
+
+impl<'a, T: HasPosition> Eq for Queued<'a, T> {}
+
+impl<'a, T: HasPosition> PartialOrd for Queued<'a, T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<'a, T: HasPosition> Ord for Queued<'a, T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .distance2
+            .partial_cmp(&self.distance2)
+            .unwrap_or(Ordering::Equal)
+    }
+}
+
+impl<T: HasPosition> QuadTree<T> {
+    /// Creates an empty quadtree covering a region.
+    ///
+    /// The region grows if objects outside of it are inserted. Choosing a
+    /// region that covers all objects avoids rebuilding the tree.
+    pub fn new(region: BoundingRect<T::Point>) -> Self {
+        Self::new_with_options(region, QuadTreeOptions::default())
+    }
+
+    /// Creates an empty quadtree with the given options.
+    pub fn new_with_options(region: BoundingRect<T::Point>, options: QuadTreeOptions) -> Self {
+        QuadTree {
+            root: Node::Leaf(Vec::new()),
+            bounds: region,
+            options,
+        }
+    }
+
+    /// Returns the region covered by the tree.
+    pub fn bounds(&self) -> &BoundingRect<T::Point> {
+        &self.bounds
+    }
+
+    /// Returns the tree's options.
+    pub fn options(&self) -> &QuadTreeOptions {
+        &self.options
+    }
+
+    /// Returns the number of objects contained in this tree.
+    pub fn size(&self) -> usize {
+        self.root.size()
+    }
+
+    /// Returns the depth of the deepest cell. The depth of an unsplit tree is zero.
+    pub fn depth(&self) -> usize {
+        self.root.depth()
+    }
+
+    /// Returns an iterator over all contained objects.
+    pub fn iter(&self) -> QuadTreeIterator<'_, T> {
+        QuadTreeIterator::new(&self.root)
+    }
+
+    /// Inserts a new object into the tree.
+    ///
+    /// If the object lies outside of the tree's region, the region is enlarged
+    /// until it contains the object and all objects are inserted again.
+    ///
+    /// # Panics
+    /// Panics if a coordinate of the object's position is NaN or infinite.
+    pub fn insert(&mut self, t: T) {
+        if let Err(error) = self.try_insert(t) {
+            panic!("Cannot insert into quadtree: {}", error);
+        }
+    }
+
+    /// Inserts a new object into the tree if all of its coordinates are finite.
+    ///
+    /// Behaves like `insert`, but returns an error instead of panicking.
+    pub fn try_insert(&mut self, t: T) -> Result<(), NonFiniteCoordinateError> {
+        let position = t.position();
+        validate_point(&position)?;
+        if !self.bounds.contains_point(&position) {
+            self.grow(&position);
+        }
+        self.root.insert(t, &self.bounds, 0, &self.options);
+        Ok(())
+    }
+
+    /// Enlarges the region until it contains a position and rebuilds the tree.
+    fn grow(&mut self, position: &T::Point) {
+        while !self.bounds.contains_point(position) {
+            let mut lower = self.bounds.lower();
+            let mut upper = self.bounds.upper();
+            for i in 0..T::Point::dimensions() {
+                let mut extent = upper.nth(i).clone() - lower.nth(i).clone();
+                if extent <= zero() {
+                    extent = one();
+                }
+                if position.nth(i) < lower.nth(i) {
+                    *lower.nth_mut(i) = lower.nth(i).clone() - extent;
+                } else if position.nth(i) > upper.nth(i) {
+                    *upper.nth_mut(i) = upper.nth(i).clone() + extent;
+                }
+            }
+            self.bounds = BoundingRect::from_corners(&lower, &upper);
+        }
+        let mut objects = Vec::with_capacity(self.size());
+        ::core::mem::replace(&mut self.root, Node::Leaf(Vec::new())).drain_into(&mut objects);
+        for object in objects {
+            self.root.insert(object, &self.bounds, 0, &self.options);
+        }
+    }
+
+    /// Removes an object from the tree.
+    ///
+    /// Returns `true` if the object was contained. Cells whose descendants
+    /// fit into a single cell after the removal are merged.
+    pub fn remove(&mut self, object: &T) -> bool
+    where
+        T: PartialEq,
+    {
+        self.bounds.contains_point(&object.position())
+            && self.root.remove(object, &self.bounds, &self.options)
+    }
+
+    /// Returns `true` if the tree contains an object.
+    pub fn contains(&self, object: &T) -> bool
+    where
+        T: PartialEq,
+    {
+        let position = object.position();
+        let mut node = &self.root;
+        let mut bounds = self.bounds.clone();
+        if !bounds.contains_point(&position) {
+            return false;
+        }
+        loop {
+            match node {
+                Node::Leaf(objects) => return objects.contains(object),
+                Node::Branch { children, .. } => {
+                    let index = child_index(&bounds.center(), &position);
+                    bounds = child_bounds(&bounds, index);
+                    node = &children[index];
+                }
+            }
+        }
+    }
+
+    /// Returns the nearest neighbor.
+    ///
+    /// Returns `None` if the tree is empty.
+    pub fn nearest_neighbor(&self, query_point: &T::Point) -> Option<&T> {
+        self.nearest_n_neighbors(query_point, 1).pop()
+    }
+
+    /// Returns the nearest n neighbors, sorted by their distance.
+    pub fn nearest_n_neighbors(&self, query_point: &T::Point, n: usize) -> Vec<&T> {
+        let mut result = Vec::new();
+        if n == 0 {
+            return result;
+        }
+        let mut heap = BinaryHeap::new();
+        heap.push(Queued {
+            distance2: self.bounds.min_dist2(query_point),
+            entry: QueueEntry::Cell(&self.root, self.bounds.clone()),
+        });
+        while let Some(queued) = heap.pop() {
+            match queued.entry {
+                QueueEntry::Object(object) => {
+                    result.push(object);
+                    if result.len() == n {
+                        break;
+                    }
+                }
+                QueueEntry::Cell(Node::Leaf(objects), _) => {
+                    record(Counter::NodeVisits, 1);
+                    record(Counter::DistanceComputations, objects.len());
+                    for object in objects {
+                        heap.push(Queued {
+                            distance2: object.position().sub(query_point).length2(),
+                            entry: QueueEntry::Object(object),
+                        });
+                    }
+                }
+                QueueEntry::Cell(Node::Branch { children, .. }, bounds) => {
+                    record(Counter::NodeVisits, 1);
+                    record(Counter::DistanceComputations, children.len());
+                    for (index, child) in children.iter().enumerate() {
+                        if child.size() > 0 {
+                            let child_bounds = child_bounds(&bounds, index);
+                            heap.push(Queued {
+                                distance2: child_bounds.min_dist2(query_point),
+                                entry: QueueEntry::Cell(child, child_bounds),
+                            });
+                        }
+                    }
+                }
+            }
+        }
+        result
+    }
+
+    /// Returns all objects contained in a rectangle, including its boundary.
+    pub fn lookup_in_rectangle(&self, query_rect: &BoundingRect<T::Point>) -> Vec<&T> {
+        let mut result = Vec::new();
+        if self.bounds.intersects(query_rect) {
+            self.root.lookup(
+                &self.bounds,
+                &|bounds| bounds.intersects(query_rect),
+                &|object| query_rect.contains_point(&object.position()),
+                &mut result,
+            );
+        }
+        result
+    }
+
+    /// Returns all objects contained in a circle.
+    ///
+    /// Note that `radius2` is the circle's squared radius, not the actual radius.
+    pub fn lookup_in_circle(
+        &self,
+        circle_origin: &T::Point,
+        radius2: &<T::Point as PointN>::Scalar,
+    ) -> Vec<&T> {
+        let mut result = Vec::new();
+        self.root.lookup(
+            &self.bounds,
+            &|bounds| bounds.min_dist2(circle_origin) <= *radius2,
+            &|object| object.position().sub(circle_origin).length2() < *radius2,
+            &mut result,
+        );
+        result
+    }
+
+    /// Traverses the tree's cells from the root downwards, e.g. for level of detail processing.
+    ///
+    /// `visitor` is called for each visited cell and returns whether the
+    /// cell's children should be visited as well. The children of a cell are
+    /// visited right after the cell itself (depth first). Empty cells are skipped.
+    pub fn traverse<F>(&self, mut visitor: F)
+    where
+        F: FnMut(&QuadTreeCell<T>) -> bool,
+    {
+        let mut stack = vec![QuadTreeCell {
+            node: &self.root,
+            bounds: self.bounds.clone(),
+            depth: 0,
+        }];
+        while let Some(cell) = stack.pop() {
+            if !visitor(&cell) {
+                continue;
+            }
+            if let Node::Branch { children, .. } = cell.node {
+                for (index, child) in children.iter().enumerate().rev() {
+                    if child.size() > 0 {
+                        stack.push(QuadTreeCell {
+                            node: child,
+                            bounds: child_bounds(&cell.bounds, index),
+                            depth: cell.depth + 1,
+                        });
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Octree, QuadTree, QuadTreeOptions};
+    use crate::boundingrect::BoundingRect;
+    use crate::rtree::RTree;
+    use crate::testutils::*;
+    use crate::validation::NonFiniteCoordinateError;
+    use crate::{Point2, Point3};
+    use cgmath::Point2 as CgPoint2;
+
+    const SEED: &[u8; 32] = b"\x52\x0c\xe7\x91\x3a\x6f\xd4\x28\xb1\x05\x7e\xc3\x99\x14\x60\xfa\
+        \x2d\x83\x47\xbe\x0a\xf5\x6c\x31\xd8\x72\x1e\xa9\x44\xcb\x07\x96";
+
+    fn sorted(mut points: Vec<&CgPoint2<f64>>) -> Vec<&CgPoint2<f64>> {
+        points.sort_by(|l, r| (l.x, l.y).partial_cmp(&(r.x, r.y)).unwrap());
+        points
+    }
+
+    #[test]
+    fn test_quadtree_queries_match_rtree() {
+        let points = random_points_with_seed::<f64>(1000, SEED);
+        let region =
+            BoundingRect::from_corners(&CgPoint2::new(-1.0, -1.0), &CgPoint2::new(1.0, 1.0));
+        let mut tree = QuadTree::new(region);
+        for point in &points {
+            tree.insert(*point);
+        }
+        let rtree = RTree::bulk_load(points.clone());
+        assert_eq!(tree.size(), 1000);
+        assert_eq!(tree.iter().count(), 1000);
+        assert!(tree.depth() > 2);
+        for query in points.iter().take(100) {
+            assert!(tree.contains(query));
+            let query = CgPoint2::new(query.y, -query.x);
+            assert_eq!(
+                tree.nearest_n_neighbors(&query, 5),
+                rtree.nearest_n_neighbors(&query, 5)
+            );
+            assert_eq!(
+                sorted(tree.lookup_in_circle(&query, &0.02)),
+                sorted(rtree.lookup_in_circle(&query, &0.02))
+            );
+            let rect = BoundingRect::from_corners(&query, &CgPoint2::new(0.0, 0.0));
+            assert_eq!(
+                sorted(tree.lookup_in_rectangle(&rect)),
+                sorted(rtree.lookup_in_rectangle(&rect))
+            );
+        }
+    }
+
+    #[test]
+    fn test_quadtree_growth_and_removal() {
+        let options = QuadTreeOptions::new().leaf_capacity(2);
+        let region = BoundingRect::from_point(Point2::new(0, 0));
+        let mut tree = QuadTree::new_with_options(region, options);
+        for x in -10..10 {
+            tree.insert(Point2::new(x, x * x));
+        }
+        assert!(tree.bounds().contains_point(&Point2::new(-10, 100)));
+        assert!(tree.depth() > 1);
+        assert_eq!(
+            tree.nearest_neighbor(&Point2::new(3, 10)),
+            Some(&Point2::new(3, 9))
+        );
+        for x in -10..9 {
+            assert!(tree.remove(&Point2::new(x, x * x)));
+            assert!(!tree.remove(&Point2::new(x, x * x)));
+        }
+        assert_eq!(tree.size(), 1);
+        assert_eq!(tree.depth(), 0);
+        assert!(tree.contains(&Point2::new(9, 81)));
+
+        let mut tree = QuadTree::new(BoundingRect::from_point(Point2::new(0.0, 0.0)));
+        assert_eq!(
+            tree.try_insert(Point2::new(f64::NAN, 0.0)),
+            Err(NonFiniteCoordinateError)
+        );
+        assert_eq!(tree.size(), 0);
+    }
+
+    #[test]
+    fn test_octree_traversal() {
+        let options = QuadTreeOptions::new().leaf_capacity(1).max_depth(3);
+        let region =
+            BoundingRect::from_corners(&Point3::new(0.0, 0.0, 0.0), &Point3::new(8.0, 8.0, 8.0));
+        let mut tree: Octree<_> = QuadTree::new_with_options(region, options);
+        for i in 0..8 {
+            tree.insert(Point3::new(f64::from(i) + 0.5, 0.5, 0.5));
+        }
+        // Duplicates are kept in a single cell at the maximum depth
+        tree.insert(Point3::new(0.5, 0.5, 0.5));
+        assert_eq!(tree.depth(), 3);
+
+        let mut visited = Vec::new();
+        tree.traverse(|cell| {
+            visited.push((cell.depth(), cell.size(), cell.is_leaf()));
+            assert_eq!(cell.iter().count(), cell.size());
+            if cell.is_leaf() {
+                assert_eq!(cell.objects().len(), cell.size());
+            }
+            cell.depth() < 1
+        });
+        assert_eq!(visited, vec![(0, 9, false), (1, 5, false), (1, 4, false)]);
+
+        let mut leaves = 0;
+        tree.traverse(|cell| {
+            if cell.is_leaf() {
+                leaves += 1;
+                assert_eq!(cell.depth(), 3);
+                let extent = cell.bounds().upper().x - cell.bounds().lower().x;
+                assert_eq!(extent, 1.0);
+            }
+            true
+        });
+        assert_eq!(leaves, 8);
+    }
+}
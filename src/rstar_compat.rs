@@ -0,0 +1,199 @@
+// Copyright 2017 The Spade Developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use crate::boundingrect::BoundingRect;
+use crate::point::{Point2, Point3};
+use crate::point_traits::PointN;
+use crate::traits::{SpadeNum, SpatialObject};
+use rstar::{PointDistance, RTreeNum, RTreeObject, AABB};
+
+impl<S: SpadeNum + RTreeNum> rstar::Point for Point2<S> {
+    type Scalar = S;
+    const DIMENSIONS: usize = 2;
+
+    fn generate(mut generator: impl FnMut(usize) -> S) -> Self {
+        Point2::new(generator(0), generator(1))
+    }
+
+    fn nth(&self, index: usize) -> S {
+        *PointN::nth(self, index)
+    }
+
+    fn nth_mut(&mut self, index: usize) -> &mut S {
+        PointN::nth_mut(self, index)
+    }
+}
+
+impl<S: SpadeNum + RTreeNum> rstar::Point for Point3<S> {
+    type Scalar = S;
+    const DIMENSIONS: usize = 3;
+
+    fn generate(mut generator: impl FnMut(usize) -> S) -> Self {
+        Point3::new(generator(0), generator(1), generator(2))
+    }
+
+    fn nth(&self, index: usize) -> S {
+        *PointN::nth(self, index)
+    }
+
+    fn nth_mut(&mut self, index: usize) -> &mut S {
+        PointN::nth_mut(self, index)
+    }
+}
+
+/// Makes an `rstar` object insertable into spade's `RTree`.
+///
+/// The wrapped object must use an axis aligned bounding box as envelope and
+/// a point type that is supported by both crates, e.g. `[f64; 2]` or
+/// `spade::Point2`. Requires the `rstar` feature.
+///
+/// # Example
+/// ```
+/// # extern crate rstar;
+/// # extern crate spade;
+/// use rstar::primitives::Line;
+/// use spade::rtree::RTree;
+/// use spade::RstarObject;
+///
+/// # fn main() {
+/// let mut tree = RTree::new();
+/// tree.insert(RstarObject(Line::new([0.0, 0.0], [0.0, 4.0])));
+/// tree.insert(RstarObject(Line::new([3.0, 0.0], [3.0, 4.0])));
+/// let nearest = tree.nearest_neighbor(&[2.0, 1.0]).unwrap();
+/// assert_eq!(nearest.0.from, [3.0, 0.0]);
+/// # }
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct RstarObject<T>(pub T);
+
+impl<T, P> SpatialObject for RstarObject<T>
+where
+    T: RTreeObject<Envelope = AABB<P>> + PointDistance,
+    P: PointN + rstar::Point<Scalar = <P as PointN>::Scalar>,
+    <P as PointN>::Scalar: RTreeNum,
+{
+    type Point = P;
+
+    fn mbr(&self) -> BoundingRect<P> {
+        let envelope = self.0.envelope();
+        BoundingRect::from_corners(&envelope.lower(), &envelope.upper())
+    }
+
+    fn distance2(&self, point: &P) -> <P as PointN>::Scalar {
+        self.0.distance_2(point)
+    }
+
+    fn contains(&self, point: &P) -> bool {
+        self.0.contains_point(point)
+    }
+}
+
+/// Makes a spade `SpatialObject` insertable into an `rstar::RTree`.
+///
+/// The object's point type must be supported by both crates, e.g.
+/// `[f64; 2]` or `spade::Point2`. Requires the `rstar` feature.
+///
+/// # Example
+/// ```
+/// # extern crate rstar;
+/// # extern crate spade;
+/// use spade::primitives::SimpleCircle;
+/// use spade::{Point2, SpadeObject};
+///
+/// # fn main() {
+/// let mut tree = rstar::RTree::new();
+/// tree.insert(SpadeObject(SimpleCircle::new(Point2::new(0.0, 0.0), 1.0)));
+/// tree.insert(SpadeObject(SimpleCircle::new(Point2::new(5.0, 0.0), 2.0)));
+/// let nearest = tree.nearest_neighbor(&Point2::new(3.5, 0.0)).unwrap();
+/// assert_eq!(nearest.0.radius, 2.0);
+/// # }
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct SpadeObject<T>(pub T);
+
+impl<T> RTreeObject for SpadeObject<T>
+where
+    T: SpatialObject,
+    T::Point: rstar::Point<Scalar = <T::Point as PointN>::Scalar>,
+    <T::Point as PointN>::Scalar: RTreeNum,
+{
+    type Envelope = AABB<T::Point>;
+
+    fn envelope(&self) -> AABB<T::Point> {
+        let mbr = self.0.mbr();
+        AABB::from_corners(mbr.lower(), mbr.upper())
+    }
+}
+
+impl<T> PointDistance for SpadeObject<T>
+where
+    T: SpatialObject,
+    T::Point: rstar::Point<Scalar = <T::Point as PointN>::Scalar>,
+    <T::Point as PointN>::Scalar: RTreeNum,
+{
+    fn distance_2(&self, point: &T::Point) -> <T::Point as PointN>::Scalar {
+        self.0.distance2(point)
+    }
+
+    fn contains_point(&self, point: &T::Point) -> bool {
+        self.0.contains(point)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{RstarObject, SpadeObject};
+    use crate::primitives::{SimpleEdge, SimpleTriangle};
+    use crate::rtree::RTree;
+    use crate::traits::SpatialObject;
+    use crate::Point2;
+    use rstar::primitives::Rectangle;
+    use rstar::{PointDistance, RTreeObject};
+
+    #[test]
+    fn test_rstar_object_in_spade_tree() {
+        let rect = RstarObject(Rectangle::from_corners([0.0, 0.0], [2.0, 1.0]));
+        assert_eq!(rect.mbr().lower(), [0.0, 0.0]);
+        assert_eq!(rect.mbr().upper(), [2.0, 1.0]);
+        assert_eq!(rect.distance2(&[3.0, 3.0]), 5.0);
+        assert!(rect.contains(&[1.0, 0.5]));
+
+        let mut tree = RTree::new();
+        tree.insert(rect);
+        tree.insert(RstarObject(Rectangle::from_corners([5.0, 5.0], [6.0, 6.0])));
+        assert_eq!(tree.lookup_in_circle(&[4.0, 4.0], &2.5).len(), 1);
+        assert_eq!(tree.lookup(&[1.0, 1.0]), Some(&rect));
+    }
+
+    #[test]
+    fn test_spade_object_in_rstar_tree() {
+        let edge = SpadeObject(SimpleEdge::new(
+            Point2::new(0.0, 0.0),
+            Point2::new(4.0, 0.0),
+        ));
+        assert_eq!(edge.envelope().upper(), Point2::new(4.0, 0.0));
+        assert_eq!(edge.distance_2(&Point2::new(2.0, 3.0)), 9.0);
+
+        let triangle = SpadeObject(SimpleTriangle::new(
+            Point2::new(0.0, 5.0),
+            Point2::new(4.0, 5.0),
+            Point2::new(2.0, 8.0),
+        ));
+        assert!(triangle.contains_point(&Point2::new(2.0, 6.0)));
+
+        let tree = rstar::RTree::bulk_load(vec![edge]);
+        let nearest = tree.nearest_neighbor(&Point2::new(1.0, 1.0)).unwrap();
+        assert_eq!(nearest, &edge);
+
+        let points = rstar::RTree::bulk_load(vec![Point2::new(1.0, 2.0), Point2::new(3.0, 4.0)]);
+        assert_eq!(
+            points.nearest_neighbor(&Point2::new(2.5, 3.0)),
+            Some(&Point2::new(3.0, 4.0))
+        );
+    }
+}
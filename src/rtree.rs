@@ -9,13 +9,19 @@
 //! Implementation of an n-dimensional r*-tree.
 
 use crate::boundingrect::BoundingRect;
+use crate::instrumentation::{record, Counter};
+use crate::metric::AnisotropicMetric;
 use crate::misc::min_inline;
 use crate::point_traits::{PointN, PointNExtensions};
-use crate::traits::SpatialObject;
+use crate::primitives::SimpleRay;
+use crate::traits::{HasPosition, SpadeFloat, SpatialObject};
+use crate::validation::{validate_object, NonFiniteCoordinateError};
 use crate::TwoDimensional;
-use num::zero;
-use std::iter::Once;
-use std::sync::Arc;
+use alloc::collections::{BinaryHeap, VecDeque};
+use alloc::sync::Arc;
+use alloc::{boxed::Box, vec, vec::Vec};
+use core::iter::Once;
+use num::{one, zero, Float};
 
 #[cfg(feature = "serde_serialize")]
 use serde::{Deserialize, Serialize};
@@ -156,7 +162,7 @@ where
     fn new(node: &'a RTreeNode<T>) -> RTreeNodeIterator<'a, T> {
         use self::RTreeNodeIterator::{DirectoryNodeIterator, LeafIterator};
         match node {
-            RTreeNode::Leaf(ref b) => LeafIterator(::std::iter::once(b)),
+            RTreeNode::Leaf(ref b) => LeafIterator(::core::iter::once(b)),
             RTreeNode::DirectoryNode(ref data) => DirectoryNodeIterator(RTreeIterator::new(data)),
         }
     }
@@ -184,7 +190,7 @@ pub struct NearestNeighborIterator<'a, T>
 where
     T: SpatialObject + 'a,
 {
-    nodes: ::std::collections::binary_heap::BinaryHeap<RTreeNodeDistanceWrapper<'a, T>>,
+    nodes: BinaryHeap<RTreeNodeDistanceWrapper<'a, T>>,
     query_point: T::Point,
 }
 
@@ -196,6 +202,33 @@ where
     distance: <T::Point as PointN>::Scalar,
 }
 
+/// A node of a best first search in `QueryScratch`, stored as its position in the node list.
+struct ScratchNodeDistance<S> {
+    node: usize,
+    distance: S,
+}
+
+impl<S: PartialOrd> PartialEq for ScratchNodeDistance<S> {
+    fn eq(&self, other: &Self) -> bool {
+        self.distance == other.distance
+    }
+}
+
+impl<S: PartialOrd> PartialOrd for ScratchNodeDistance<S> {
+    fn partial_cmp(&self, other: &Self) -> Option<::core::cmp::Ordering> {
+        // Inverse comparison creates a min heap
+        other.distance.partial_cmp(&self.distance)
+    }
+}
+
+impl<S: PartialOrd> Eq for ScratchNodeDistance<S> {}
+
+impl<S: PartialOrd> Ord for ScratchNodeDistance<S> {
+    fn cmp(&self, other: &Self) -> ::core::cmp::Ordering {
+        self.partial_cmp(other).unwrap()
+    }
+}
+
 impl<'a, T> PartialEq for RTreeNodeDistanceWrapper<'a, T>
 where
     T: SpatialObject + 'a,
@@ -209,7 +242,7 @@ impl<'a, T> PartialOrd for RTreeNodeDistanceWrapper<'a, T>
 where
     T: SpatialObject + 'a,
 {
-    fn partial_cmp(&self, other: &Self) -> Option<::std::cmp::Ordering> {
+    fn partial_cmp(&self, other: &Self) -> Option<::core::cmp::Ordering> {
         // Inverse comparison creates a min heap
         other.distance.partial_cmp(&self.distance)
     }
@@ -221,7 +254,7 @@ impl<'a, T> Ord for RTreeNodeDistanceWrapper<'a, T>
 where
     T: SpatialObject + 'a,
 {
-    fn cmp(&self, other: &Self) -> ::std::cmp::Ordering {
+    fn cmp(&self, other: &Self) -> ::core::cmp::Ordering {
         self.partial_cmp(other).unwrap()
     }
 }
@@ -240,6 +273,8 @@ where
     }
 
     fn extend_heap(&mut self, children: &'a [RTreeNode<T>]) {
+        record(Counter::NodeVisits, 1);
+        record(Counter::DistanceComputations, children.len());
         let query_point = self.query_point.clone();
         self.nodes.extend(children.iter().map(|child| {
             let distance = match child {
@@ -282,27 +317,390 @@ where
     }
 }
 
+/// An iterator yielding the elements of an `RTree` ordered by their distance to a
+/// query point in an anisotropic metric.
+///
+/// This `struct` is created by the `nearest_neighbor_iterator_with_metric` method on `RTree`.
+pub struct AnisotropicNearestNeighborIterator<'a, T>
+where
+    T: HasPosition + 'a,
+{
+    nodes: BinaryHeap<RTreeNodeDistanceWrapper<'a, T>>,
+    query_point: T::Point,
+    metric: AnisotropicMetric<<T::Point as PointN>::Scalar>,
+    min_eigenvalue: <T::Point as PointN>::Scalar,
+}
+
+impl<'a, T> AnisotropicNearestNeighborIterator<'a, T>
+where
+    T: HasPosition + 'a,
+    T::Point: TwoDimensional,
+    <T::Point as PointN>::Scalar: SpadeFloat,
+{
+    fn new(
+        root: &'a DirectoryNodeData<T>,
+        query_point: T::Point,
+        metric: AnisotropicMetric<<T::Point as PointN>::Scalar>,
+    ) -> Self {
+        let mut result = AnisotropicNearestNeighborIterator {
+            nodes: Default::default(),
+            query_point,
+            metric,
+            min_eigenvalue: metric.min_eigenvalue(),
+        };
+        result.extend_heap(&root.children);
+        result
+    }
+
+    fn extend_heap(&mut self, children: &'a [RTreeNode<T>]) {
+        record(Counter::NodeVisits, 1);
+        record(Counter::DistanceComputations, children.len());
+        let query_point = self.query_point.clone();
+        let metric = self.metric;
+        let min_eigenvalue = self.min_eigenvalue;
+        self.nodes.extend(children.iter().map(|child| {
+            let distance = match child {
+                // The scaled euclidean distance is a lower bound for all contained elements
+                RTreeNode::DirectoryNode(ref data) => {
+                    data.mbr().min_dist2(&query_point) * min_eigenvalue
+                }
+                RTreeNode::Leaf(ref t) => metric.distance2(&t.position(), &query_point),
+            };
+
+            RTreeNodeDistanceWrapper {
+                node: child,
+                distance,
+            }
+        }));
+    }
+}
+
+impl<'a, T> Iterator for AnisotropicNearestNeighborIterator<'a, T>
+where
+    T: HasPosition + 'a,
+    T::Point: TwoDimensional,
+    <T::Point as PointN>::Scalar: SpadeFloat,
+{
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(current) = self.nodes.pop() {
+            match current.node {
+                RTreeNode::DirectoryNode(ref data) => self.extend_heap(&data.children),
+                RTreeNode::Leaf(ref t) => return Some(t),
+            }
+        }
+        None
+    }
+}
+
+/// Reusable buffers for r-tree queries.
+///
+/// The `_with_scratch` variants of the r-tree's queries keep their priority
+/// queue and visit stack in a `QueryScratch` instead of allocating them. Once
+/// the buffers have grown large enough, repeated queries do not allocate at
+/// all. A scratch object is not bound to a tree and remains usable after the
+/// tree was modified.
+///
+/// # Example
+/// ```
+/// use spade::rtree::{QueryScratch, RTree};
+/// use spade::Point2;
+///
+/// let mut tree = RTree::new();
+/// tree.insert(Point2::new(0.0, 0.0));
+/// tree.insert(Point2::new(1.0, 1.0));
+/// let mut scratch = QueryScratch::new();
+/// for i in 0..100 {
+///     let query = Point2::new(i as f64 * 0.01, 0.5);
+///     let nearest = tree.nearest_neighbor_with_scratch(&query, &mut scratch);
+///     assert!(nearest.is_some());
+/// }
+/// ```
+pub struct QueryScratch<T>
+where
+    T: SpatialObject,
+{
+    // Nodes are identified by child indices instead of references, the
+    // buffers don't borrow from a tree and are always empty between queries.
+    // `nodes` stores the parent entry and child index of each queued node.
+    heap: Vec<ScratchNodeDistance<<T::Point as PointN>::Scalar>>,
+    nodes: Vec<(Option<usize>, usize)>,
+    path: Vec<usize>,
+}
+
+impl<T> QueryScratch<T>
+where
+    T: SpatialObject,
+{
+    /// Creates empty buffers.
+    pub fn new() -> Self {
+        QueryScratch {
+            heap: Vec::new(),
+            nodes: Vec::new(),
+            path: Vec::new(),
+        }
+    }
+}
+
+impl<T> Default for QueryScratch<T>
+where
+    T: SpatialObject,
+{
+    fn default() -> Self {
+        QueryScratch::new()
+    }
+}
+
+/// Estimated heap memory of an r-tree, in bytes.
+///
+/// Returned by `RTree::memory_usage` and `RTree::memory_usage_with`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct RTreeMemoryUsage {
+    /// Directory nodes, including child slots that are not occupied by an object.
+    pub nodes: usize,
+    /// Child slots occupied by the contained objects.
+    pub leaves: usize,
+    /// Heap memory owned by the contained objects themselves.
+    pub payload: usize,
+}
+
+impl RTreeMemoryUsage {
+    /// Returns the sum of all components.
+    pub fn total(&self) -> usize {
+        self.nodes + self.leaves + self.payload
+    }
+}
+
+/// Decides when an r-tree rebuilds itself.
+///
+/// Trees that are modified over a long time can degrade, e.g. grow deeper
+/// than necessary or keep many sparsely filled nodes after removals. Once a
+/// policy is set with `RTree::set_rebuild_policy`, the tree checks it every
+/// `check_interval` insertions or removals and bulk loads all of its objects
+/// again if the policy is violated.
+///
+/// A new policy never triggers a rebuild, the limits are activated with
+/// `max_depth` and `min_fill_factor`. Limits that even a freshly bulk
+/// loaded tree violates, e.g. a fill factor of one, cannot be met. If a
+/// rebuild does not satisfy the policy, the next check is delayed until
+/// the tree has been modified as often as it contains objects.
+///
+/// Policies are not part of a serialized tree and must be set again after
+/// deserialization.
+///
+/// # Example
+/// ```
+/// use spade::rtree::{RebuildPolicy, RTree};
+/// use spade::Point2;
+///
+/// let mut tree = RTree::new();
+/// tree.set_rebuild_policy(RebuildPolicy::new().min_fill_factor(0.5).check_interval(100));
+/// for i in 0..1000 {
+///     tree.insert(Point2::new(f64::from(i % 40), f64::from(i / 40)));
+/// }
+/// tree.retain(|p| p.x < 10.0);
+/// assert!(tree.fill_factor() >= 0.5);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde_serialize", derive(Serialize, Deserialize))]
+pub struct RebuildPolicy {
+    max_depth: Option<usize>,
+    min_fill_factor: f64,
+    check_interval: usize,
+}
+
+impl Default for RebuildPolicy {
+    fn default() -> RebuildPolicy {
+        RebuildPolicy::new()
+    }
+}
+
+impl RebuildPolicy {
+    /// Creates a policy that never triggers a rebuild.
+    ///
+    /// The policy is checked every 1000 modifications.
+    pub fn new() -> Self {
+        RebuildPolicy {
+            max_depth: None,
+            min_fill_factor: 0.0,
+            check_interval: 1000,
+        }
+    }
+
+    /// Rebuilds the tree if its depth exceeds `max_depth`.
+    pub fn max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = Some(max_depth);
+        self
+    }
+
+    /// Rebuilds the tree if its fill factor drops below `min_fill_factor`.
+    ///
+    /// See `RTree::fill_factor`. Bulk loaded trees are densely filled, trees
+    /// built by insertions usually stay above one half.
+    pub fn min_fill_factor(mut self, min_fill_factor: f64) -> Self {
+        assert!(
+            (0.0..=1.0).contains(&min_fill_factor),
+            "Fill factor must be between zero and one"
+        );
+        self.min_fill_factor = min_fill_factor;
+        self
+    }
+
+    /// Sets the number of insertions and removals between two checks.
+    ///
+    /// Checking the fill factor visits all directory nodes of the tree.
+    pub fn check_interval(mut self, check_interval: usize) -> Self {
+        assert!(0 < check_interval, "Check interval cannot be zero");
+        self.check_interval = check_interval;
+        self
+    }
+
+    /// Returns `true` if the policy requires a rebuild of the given tree.
+    ///
+    /// Trees that fit into a single node are never rebuilt.
+    pub fn needs_rebuild<T: SpatialObject>(&self, tree: &RTree<T>) -> bool {
+        if tree.size() <= tree.root.options.max_size {
+            return false;
+        }
+        self.max_depth.is_some_and(|depth| tree.depth() > depth)
+            || tree.fill_factor() < self.min_fill_factor
+    }
+}
+
+/// A rebuild policy together with the bulk loading function of the tree's object type.
+///
+/// Bulk loading is restricted to some object types, the function is stored
+/// when the policy is set to keep all other methods unrestricted.
+struct Maintenance<T: SpatialObject> {
+    policy: RebuildPolicy,
+    rebuild: fn(&mut RTree<T>),
+    modifications: usize,
+    check_interval: usize,
+}
+
+impl<T: SpatialObject> Clone for Maintenance<T> {
+    fn clone(&self) -> Self {
+        Maintenance {
+            policy: self.policy,
+            rebuild: self.rebuild,
+            modifications: self.modifications,
+            check_interval: self.check_interval,
+        }
+    }
+}
+
+/// A reference to an object in an external slice, together with its index.
+///
+/// Trees of references are created with `RTree::bulk_load_indices`. They index
+/// objects that are owned elsewhere without cloning them. All queries are
+/// forwarded to the referenced objects, e.g. `nearest_neighbor` returns the
+/// entry of the object that is closest to the query point.
+#[derive(Debug)]
+pub struct ObjectIndex<'a, T> {
+    object: &'a T,
+    index: u32,
+}
+
+impl<'a, T> ObjectIndex<'a, T> {
+    /// Returns the index of the object within the slice the tree was loaded from.
+    pub fn index(&self) -> usize {
+        self.index as usize
+    }
+
+    /// Returns the referenced object.
+    pub fn object(&self) -> &'a T {
+        self.object
+    }
+}
+
+impl<'a, T> Clone for ObjectIndex<'a, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, T> Copy for ObjectIndex<'a, T> {}
+
+impl<'a, T> PartialEq for ObjectIndex<'a, T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.index == other.index && ::core::ptr::eq(self.object, other.object)
+    }
+}
+
+impl<'a, T: SpatialObject> SpatialObject for ObjectIndex<'a, T> {
+    type Point = T::Point;
+
+    fn mbr(&self) -> BoundingRect<T::Point> {
+        self.object.mbr()
+    }
+
+    fn distance2(&self, point: &T::Point) -> <T::Point as PointN>::Scalar {
+        self.object.distance2(point)
+    }
+
+    fn contains(&self, point: &T::Point) -> bool {
+        self.object.contains(point)
+    }
+}
+
+/// The bounding rectangle of an object in a slice, used to bulk load slices
+/// without computing the rectangles repeatedly.
+struct SliceEntry<P: PointN> {
+    index: u32,
+    mbr: BoundingRect<P>,
+}
+
+impl<P: PointN> SpatialObject for SliceEntry<P> {
+    type Point = P;
+
+    fn mbr(&self) -> BoundingRect<P> {
+        self.mbr.clone()
+    }
+
+    fn distance2(&self, point: &P) -> P::Scalar {
+        self.mbr.min_dist2(point)
+    }
+}
+
 impl<T> DirectoryNodeData<T>
 where
     T: SpatialObject + Clone,
     T::Point: TwoDimensional,
 {
-    fn bulk_load(options: Arc<RTreeOptions>, mut elements: &mut [T]) -> DirectoryNodeData<T> {
+    fn bulk_load(options: Arc<RTreeOptions>, elements: &mut [T]) -> DirectoryNodeData<T> {
+        DirectoryNodeData::bulk_load_with(options, elements, &mut T::clone)
+    }
+}
+
+impl<T> DirectoryNodeData<T>
+where
+    T: SpatialObject,
+    T::Point: TwoDimensional,
+{
+    /// Bulk loads the tree's structure from `elements`, `leaf` creates the stored objects.
+    fn bulk_load_with<U, F>(
+        options: Arc<RTreeOptions>,
+        mut elements: &mut [U],
+        leaf: &mut F,
+    ) -> DirectoryNodeData<T>
+    where
+        U: SpatialObject<Point = T::Point>,
+        F: FnMut(&U) -> T,
+    {
         let m = options.max_size;
         if elements.len() <= m {
             // Reached leaf level
-            let elements: Vec<_> = elements
-                .iter_mut()
-                .map(|e| RTreeNode::Leaf(e.clone()))
-                .collect();
+            let elements: Vec<RTreeNode<T>> =
+                elements.iter().map(|e| RTreeNode::Leaf(leaf(e))).collect();
             return DirectoryNodeData::new_parent(elements, 1, options);
         }
 
-        let depth = (elements.len() as f32).log(m as f32).ceil() as usize;
-        let n_subtree = (m as f32).powi(depth as i32 - 1);
-        let remaining_clusters = (elements.len() as f32 / n_subtree).ceil() as usize;
+        let depth = Float::ceil(Float::log(elements.len() as f32, m as f32)) as usize;
+        let n_subtree = Float::powi(m as f32, depth as i32 - 1);
+        let remaining_clusters = Float::ceil(elements.len() as f32 / n_subtree) as usize;
 
-        let num_vertical_slices = (remaining_clusters as f32).sqrt().ceil() as usize;
+        let num_vertical_slices = Float::ceil(Float::sqrt(remaining_clusters as f32)) as usize;
         let vertical_slice_num_elements =
             (elements.len() + num_vertical_slices - 1) / num_vertical_slices;
         let mut children = Vec::with_capacity(m + 1);
@@ -317,7 +715,7 @@ where
             create_clusters(&mut slice, cluster_num_elements, 1);
 
             for cluster in slice.chunks_mut(cluster_num_elements) {
-                let child = DirectoryNodeData::bulk_load(options.clone(), cluster);
+                let child = DirectoryNodeData::bulk_load_with(options.clone(), cluster, leaf);
                 children.push(RTreeNode::DirectoryNode(child));
             }
         }
@@ -348,6 +746,7 @@ where
             children: Vec::with_capacity(options.max_size + 1),
             options,
             depth,
+            mbr_outdated: false,
         }
     }
 
@@ -362,11 +761,25 @@ where
             children,
             depth,
             options,
+            mbr_outdated: false,
         };
         result.update_mbr();
         result
     }
 
+    fn translate_objects<F>(&mut self, translate_object: &mut F)
+    where
+        F: FnMut(&mut T),
+    {
+        for child in &mut self.children {
+            match child {
+                RTreeNode::DirectoryNode(ref mut data) => data.translate_objects(translate_object),
+                RTreeNode::Leaf(ref mut t) => translate_object(t),
+            }
+        }
+        self.update_mbr();
+    }
+
     #[inline]
     fn update_mbr(&mut self) {
         if let Some(first) = self.children.first() {
@@ -389,7 +802,7 @@ where
         }
     }
 
-    fn insert(&mut self, t: RTreeNode<T>, state: &mut InsertionState) -> InsertionResult<T> {
+    fn insert(&mut self, t: RTreeNode<T>, state: &mut InsertionState<T>) -> InsertionResult<T> {
         // Adjust own mbr - the element will most likely become a child of this node
         self.update_mbr_with_element(&t.mbr());
         if t.depth() + 1 == self.depth {
@@ -416,11 +829,11 @@ where
         }
     }
 
-    fn resolve_overflow(&mut self, state: &mut InsertionState) -> InsertionResult<T> {
+    fn resolve_overflow(&mut self, state: &mut InsertionState<T>) -> InsertionResult<T> {
         if self.children.len() > self.options.max_size {
             if state.did_reinsert(self.depth) {
                 // We did already reinsert on that level - split this node
-                let offsplit = self.split();
+                let offsplit = self.split(state);
                 InsertionResult::Split(offsplit)
             } else {
                 // We didn't attempt to reinsert yet - give it a try
@@ -433,7 +846,7 @@ where
         }
     }
 
-    fn split(&mut self) -> RTreeNode<T> {
+    fn split(&mut self, state: &mut InsertionState<T>) -> RTreeNode<T> {
         let axis = self.get_split_axis();
         assert!(self.children.len() >= 2);
         // Sort along axis
@@ -466,14 +879,11 @@ where
                 best_index = k;
             }
         }
-        let offsplit = self.children.split_off(best_index);
-        let result = RTreeNode::DirectoryNode(DirectoryNodeData::new_parent(
-            offsplit,
-            self.depth,
-            self.options.clone(),
-        ));
+        let mut offsplit = state.new_node(self.depth, &self.options);
+        offsplit.children.extend(self.children.drain(best_index..));
+        offsplit.update_mbr();
         self.update_mbr();
-        result
+        RTreeNode::DirectoryNode(offsplit)
     }
 
     fn reinsert(&mut self) -> Vec<RTreeNode<T>> {
@@ -496,6 +906,98 @@ where
         result
     }
 
+    /// Appends the squared distance of each object's center to the center of its
+    /// leaf node, in depth first order.
+    fn placement_costs(&self, costs: &mut Vec<<T::Point as PointN>::Scalar>) {
+        let center = self.mbr().center();
+        for child in &self.children {
+            match child {
+                RTreeNode::DirectoryNode(data) => data.placement_costs(costs),
+                RTreeNode::Leaf(t) => costs.push(t.mbr().center().sub(&center).length2()),
+            }
+        }
+    }
+
+    /// Removes all objects that are marked in `remove`.
+    ///
+    /// The objects are visited in the same order as by `placement_costs`.
+    fn extract_marked(&mut self, remove: &[bool], index: &mut usize, extracted: &mut Vec<T>) {
+        let children = ::core::mem::take(&mut self.children);
+        for child in children {
+            match child {
+                RTreeNode::DirectoryNode(mut data) => {
+                    data.extract_marked(remove, index, extracted);
+                    if !data.children.is_empty() {
+                        // Don't add a node if it has become empty
+                        self.children.push(RTreeNode::DirectoryNode(data));
+                    }
+                }
+                RTreeNode::Leaf(t) => {
+                    if remove[*index] {
+                        extracted.push(t);
+                    } else {
+                        self.children.push(RTreeNode::Leaf(t));
+                    }
+                    *index += 1;
+                }
+            }
+        }
+        self.update_mbr();
+    }
+
+    /// Removes all objects for which `keep` returns `false`.
+    ///
+    /// Each node's mbr is updated once after all of its children have been visited.
+    fn retain<F>(&mut self, keep: &mut F) -> usize
+    where
+        F: FnMut(&T) -> bool,
+    {
+        let mut removed = 0;
+        self.children.retain_mut(|child| match child {
+            RTreeNode::DirectoryNode(data) => {
+                removed += data.retain(keep);
+                // Don't keep a node if it has become empty
+                !data.children.is_empty()
+            }
+            RTreeNode::Leaf(t) => {
+                let result = keep(t);
+                if !result {
+                    removed += 1;
+                }
+                result
+            }
+        });
+        if removed > 0 {
+            self.update_mbr();
+        }
+        removed
+    }
+
+    /// Moves all objects into `result` and leaves an empty node.
+    fn take_objects(&mut self, result: &mut Vec<T>) {
+        for child in ::core::mem::take(&mut self.children) {
+            match child {
+                RTreeNode::DirectoryNode(mut data) => data.take_objects(result),
+                RTreeNode::Leaf(t) => result.push(t),
+            }
+        }
+        self.bounding_box = None;
+    }
+
+    /// Recomputes the mbrs of all nodes marked as outdated, bottom up.
+    fn refresh_mbrs(&mut self) {
+        if !self.mbr_outdated {
+            return;
+        }
+        for child in &mut self.children {
+            if let RTreeNode::DirectoryNode(data) = child {
+                data.refresh_mbrs();
+            }
+        }
+        self.update_mbr();
+        self.mbr_outdated = false;
+    }
+
     fn get_split_axis(&mut self) -> usize {
         let mut best_goodness = zero();
         let mut best_axis = 0;
@@ -636,11 +1138,13 @@ where
     }
 
     fn extend_heap<'a>(
-        heap: &mut ::std::collections::BinaryHeap<RTreeNodeDistanceWrapper<'a, T>>,
+        heap: &mut BinaryHeap<RTreeNodeDistanceWrapper<'a, T>>,
         children: &'a [RTreeNode<T>],
         query_point: &T::Point,
         prune_distance_option: &mut Option<<T::Point as PointN>::Scalar>,
     ) {
+        record(Counter::NodeVisits, 1);
+        record(Counter::DistanceComputations, children.len());
         for child in children {
             let distance = match child {
                 RTreeNode::DirectoryNode(ref data) => data.mbr().min_dist2(query_point),
@@ -670,7 +1174,7 @@ where
 
     fn nearest_neighbor(&self, point: &T::Point) -> Option<&T> {
         let mut smallest_min_max = None;
-        let mut heap = ::std::collections::binary_heap::BinaryHeap::new();
+        let mut heap = BinaryHeap::new();
         Self::extend_heap(&mut heap, &self.children, point, &mut smallest_min_max);
         while let Some(current) = heap.pop() {
             match current {
@@ -691,12 +1195,71 @@ where
         None
     }
 
+    /// Best first search that stops once no unvisited node can be closer by more than `factor`.
+    ///
+    /// `factor` is the squared ratio by which the result may be farther away
+    /// than the nearest neighbor. Leaves are evaluated as soon as their parent is
+    /// visited, which finds good candidates early.
+    fn nearest_neighbor_approx<'a>(
+        &'a self,
+        point: &T::Point,
+        factor: <T::Point as PointN>::Scalar,
+        heap: &mut BinaryHeap<RTreeNodeDistanceWrapper<'a, T>>,
+    ) -> Option<&'a T> {
+        let mut best: Option<(<T::Point as PointN>::Scalar, &'a T)> = None;
+        let mut children = &self.children[..];
+        heap.clear();
+        loop {
+            record(Counter::NodeVisits, 1);
+            record(Counter::DistanceComputations, children.len());
+            for child in children {
+                match child {
+                    RTreeNode::DirectoryNode(ref data) => {
+                        let distance = data.mbr().min_dist2(point);
+                        if best
+                            .as_ref()
+                            .is_none_or(|(best, _)| distance.clone() * factor.clone() < *best)
+                        {
+                            heap.push(RTreeNodeDistanceWrapper {
+                                distance,
+                                node: child,
+                            });
+                        }
+                    }
+                    RTreeNode::Leaf(ref t) => {
+                        let distance = t.distance2(point);
+                        if best.as_ref().is_none_or(|(best, _)| distance < *best) {
+                            best = Some((distance, t));
+                        }
+                    }
+                }
+            }
+            match heap.pop() {
+                Some(RTreeNodeDistanceWrapper {
+                    node: RTreeNode::DirectoryNode(ref data),
+                    distance,
+                }) => {
+                    if let Some((best, _)) = best.as_ref() {
+                        if distance * factor.clone() >= *best {
+                            break;
+                        }
+                    }
+                    children = &data.children;
+                }
+                _ => break,
+            }
+        }
+        best.map(|(_, t)| t)
+    }
+
     fn nearest_neighbors<'a>(
         &'a self,
         point: &T::Point,
         mut nearest_distance: Option<<T::Point as PointN>::Scalar>,
         result: &mut Vec<&'a T>,
     ) -> Option<<T::Point as PointN>::Scalar> {
+        record(Counter::NodeVisits, 1);
+        record(Counter::DistanceComputations, self.children.len());
         // Calculate smallest minmax-distance
         let mut smallest_min_max: <T::Point as PointN>::Scalar = zero();
         let mut first = true;
@@ -742,7 +1305,7 @@ where
             .map(|bb| bb.contains_point(point))
             .unwrap_or(false);
         if contains {
-            let mut children = ::std::mem::replace(&mut self.children, Vec::new());
+            let mut children = ::core::mem::replace(&mut self.children, Vec::new());
             let mut result = None;
             for child in children.drain(..) {
                 match child {
@@ -777,6 +1340,7 @@ where
         todo_list.push(self);
         while let Some(next) = todo_list.pop() {
             if next.mbr().contains_point(point) {
+                record(Counter::NodeVisits, 1);
                 for child in next.children.iter() {
                     match child {
                         RTreeNode::DirectoryNode(ref data) => {
@@ -800,6 +1364,8 @@ where
         origin: &T::Point,
         radius2: &<T::Point as PointN>::Scalar,
     ) {
+        record(Counter::NodeVisits, 1);
+        record(Counter::DistanceComputations, self.children.len());
         // Only look at children whose mbr intersects the circle
         for child in self.children.iter().filter(|c| {
             let min_dist2 = c.mbr().min_dist2(origin);
@@ -818,12 +1384,154 @@ where
         }
     }
 
-    fn lookup_in_rectangle<'b>(
+    fn lookup_along_ray<'b>(
         &'b self,
-        result: &mut Vec<&'b T>,
-        query_rect: &BoundingRect<T::Point>,
-    ) {
-        for child in self
+        result: &mut Vec<(<T::Point as PointN>::Scalar, &'b T)>,
+        ray: &SimpleRay<T::Point>,
+    ) where
+        <T::Point as PointN>::Scalar: SpadeFloat,
+    {
+        for child in self.children.iter() {
+            if let Some(t) = ray.intersect_rect(&child.mbr()) {
+                match child {
+                    RTreeNode::DirectoryNode(ref data) => data.lookup_along_ray(result, ray),
+                    RTreeNode::Leaf(ref obj) => result.push((t, obj)),
+                }
+            }
+        }
+    }
+
+    /// Returns the directory node reached by following the child indices in `path`.
+    fn descendant(&self, path: &[usize]) -> &DirectoryNodeData<T> {
+        let mut node = self;
+        for &index in path {
+            match node.children[index] {
+                RTreeNode::DirectoryNode(ref data) => node = data,
+                RTreeNode::Leaf(_) => unreachable!("A path must only contain directory nodes"),
+            }
+        }
+        node
+    }
+
+    /// Returns the node at position `entry` in the node list of a best first search.
+    ///
+    /// Each entry stores the entry of its parent, if any, and its index among
+    /// the parent's children.
+    fn resolve_entry(&self, nodes: &[(Option<usize>, usize)], entry: usize) -> &RTreeNode<T> {
+        let (parent, index) = nodes[entry];
+        let parent = match parent {
+            Some(parent) => match self.resolve_entry(nodes, parent) {
+                RTreeNode::DirectoryNode(ref data) => data,
+                RTreeNode::Leaf(_) => unreachable!("A parent must be a directory node"),
+            },
+            None => self,
+        };
+        &parent.children[index]
+    }
+
+    /// Depth first traversal that stores its position as child indices in `path`.
+    ///
+    /// Directory nodes are only entered if `enter` returns `true`. Returns the
+    /// first object for which `visitor` returns `true`.
+    fn visit_depth_first<'a, E, F>(
+        &'a self,
+        path: &mut Vec<usize>,
+        mut enter: E,
+        mut visitor: F,
+    ) -> Option<&'a T>
+    where
+        E: FnMut(&DirectoryNodeData<T>) -> bool,
+        F: FnMut(&'a T) -> bool,
+    {
+        path.clear();
+        path.push(0);
+        let mut node = self;
+        while let Some(&index) = path.last() {
+            let depth = path.len() - 1;
+            match node.children.get(index) {
+                Some(RTreeNode::DirectoryNode(ref data)) if enter(data) => {
+                    node = data;
+                    path.push(0);
+                }
+                Some(RTreeNode::DirectoryNode(_)) => path[depth] += 1,
+                Some(RTreeNode::Leaf(ref t)) => {
+                    if visitor(t) {
+                        path.clear();
+                        return Some(t);
+                    }
+                    path[depth] += 1;
+                }
+                None => {
+                    // All children are visited, continue with the parent's next child
+                    path.pop();
+                    if let Some(parent_index) = path.last_mut() {
+                        *parent_index += 1;
+                        node = self.descendant(&path[..path.len() - 1]);
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    /// Best first search that calls `visitor` for the objects in order of their distance.
+    ///
+    /// The search stops once `visitor` returns `true`. Queued nodes are
+    /// identified by their position in `nodes`, see `resolve_entry`.
+    fn visit_nearest<'a, F>(
+        &'a self,
+        query_point: &T::Point,
+        heap: &mut BinaryHeap<ScratchNodeDistance<<T::Point as PointN>::Scalar>>,
+        nodes: &mut Vec<(Option<usize>, usize)>,
+        mut visitor: F,
+    ) where
+        F: FnMut(&'a T) -> bool,
+    {
+        heap.clear();
+        nodes.clear();
+        let mut parent = None;
+        let mut children = &self.children[..];
+        'search: loop {
+            record(Counter::NodeVisits, 1);
+            record(Counter::DistanceComputations, children.len());
+            for (index, child) in children.iter().enumerate() {
+                let distance = match child {
+                    RTreeNode::DirectoryNode(ref data) => data.mbr().min_dist2(query_point),
+                    RTreeNode::Leaf(ref t) => t.distance2(query_point),
+                };
+                heap.push(ScratchNodeDistance {
+                    node: nodes.len(),
+                    distance,
+                });
+                nodes.push((parent, index));
+            }
+            while let Some(current) = heap.pop() {
+                match self.resolve_entry(nodes, current.node) {
+                    RTreeNode::DirectoryNode(ref data) => {
+                        parent = Some(current.node);
+                        children = &data.children;
+                        continue 'search;
+                    }
+                    RTreeNode::Leaf(ref t) => {
+                        if visitor(t) {
+                            break 'search;
+                        }
+                    }
+                }
+            }
+            break;
+        }
+        heap.clear();
+        nodes.clear();
+    }
+
+    fn lookup_in_rectangle<'b>(
+        &'b self,
+        result: &mut Vec<&'b T>,
+        query_rect: &BoundingRect<T::Point>,
+    ) {
+        record(Counter::NodeVisits, 1);
+        for child in self
             .children
             .iter()
             .filter(|c| c.mbr().intersects(query_rect))
@@ -873,6 +1581,14 @@ where
     T: SpatialObject + PartialEq,
 {
     pub fn remove(&mut self, to_remove: &T) -> bool {
+        self.remove_object(to_remove, true)
+    }
+
+    /// Removes an object, `update_mbrs` determines if the mbrs along the path are shrunk.
+    ///
+    /// Outdated mbrs still contain all of their children, the tree stays searchable.
+    /// Nodes with outdated mbrs are marked and updated by `refresh_mbrs`.
+    fn remove_object(&mut self, to_remove: &T, update_mbrs: bool) -> bool {
         let contains = self
             .bounding_box
             .as_ref()
@@ -884,7 +1600,7 @@ where
             for (index, child) in self.children.iter_mut().enumerate() {
                 match child {
                     RTreeNode::DirectoryNode(ref mut data) => {
-                        if data.remove(to_remove) {
+                        if data.remove_object(to_remove, update_mbrs) {
                             result = true;
                             if data.children.is_empty() {
                                 // Mark this child for removal as it has become empty
@@ -905,9 +1621,11 @@ where
             if let Some(to_remove) = remove_index {
                 self.children.remove(to_remove);
             }
-            if result {
+            if result && update_mbrs {
                 // Update the mbr if we did remove an element
                 self.update_mbr();
+            } else if result {
+                self.mbr_outdated = true;
             }
             result
         } else {
@@ -950,15 +1668,40 @@ where
     Reinsert(Vec<RTreeNode<T>>),
 }
 
-struct InsertionState {
+struct InsertionState<'a, T>
+where
+    T: SpatialObject,
+{
     reinsertions: Vec<bool>,
+    spare_nodes: &'a mut Vec<DirectoryNodeData<T>>,
 }
 
-impl InsertionState {
-    fn new(max_depth: usize) -> InsertionState {
+impl<'a, T> InsertionState<'a, T>
+where
+    T: SpatialObject,
+{
+    fn new(
+        max_depth: usize,
+        spare_nodes: &'a mut Vec<DirectoryNodeData<T>>,
+    ) -> InsertionState<'a, T> {
         let mut reinsertions = Vec::with_capacity(max_depth + 1);
         reinsertions.resize(max_depth, false);
-        InsertionState { reinsertions }
+        InsertionState {
+            reinsertions,
+            spare_nodes,
+        }
+    }
+
+    /// Returns an empty node, reusing a spare node if possible.
+    fn new_node(&mut self, depth: usize, options: &Arc<RTreeOptions>) -> DirectoryNodeData<T> {
+        match self.spare_nodes.pop() {
+            Some(mut node) => {
+                node.depth = depth;
+                node.options = options.clone();
+                node
+            }
+            None => DirectoryNodeData::new(depth, options.clone()),
+        }
     }
 
     fn did_reinsert(&self, depth: usize) -> bool {
@@ -1043,6 +1786,10 @@ where
     children: Vec<RTreeNode<T>>,
     depth: usize,
     options: Arc<RTreeOptions>,
+    /// Set by `remove_object` if an object below this node was removed
+    /// without updating the mbrs.
+    #[cfg_attr(feature = "serde_serialize", serde(skip))]
+    mbr_outdated: bool,
 }
 
 #[doc(hidden)]
@@ -1131,13 +1878,18 @@ where
 {
     root: DirectoryNodeData<T>,
     size: usize,
+    #[cfg_attr(feature = "serde_serialize", serde(skip))]
+    maintenance: Option<Maintenance<T>>,
+    /// Empty directory nodes, kept by `clear` and `reserve` for reuse.
+    #[cfg_attr(feature = "serde_serialize", serde(skip))]
+    spare_nodes: Vec<DirectoryNodeData<T>>,
 }
 
-impl<T> ::std::fmt::Debug for RTree<T>
+impl<T> ::core::fmt::Debug for RTree<T>
 where
-    T: SpatialObject + ::std::fmt::Debug,
+    T: SpatialObject + ::core::fmt::Debug,
 {
-    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> Result<(), ::std::fmt::Error> {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter) -> Result<(), ::core::fmt::Error> {
         let mut iter = self.iter();
         write!(f, "RTree {{")?;
         if let Some(next) = iter.next() {
@@ -1179,6 +1931,8 @@ where
         RTree {
             root: DirectoryNodeData::new(1, options),
             size: 0,
+            maintenance: None,
+            spare_nodes: Vec::new(),
         }
     }
 
@@ -1187,6 +1941,207 @@ where
         self.size
     }
 
+    /// Returns the number of levels of the tree.
+    ///
+    /// A tree whose objects fit into its root node has depth one.
+    pub fn depth(&self) -> usize {
+        self.root.depth
+    }
+
+    /// Returns the ratio of contained objects to the capacity of all leaf nodes.
+    ///
+    /// The capacity of a node is the maximum number of children it can hold
+    /// without being split. Returns one for empty trees.
+    pub fn fill_factor(&self) -> f64 {
+        if self.size == 0 {
+            return 1.0;
+        }
+        let mut leaf_nodes = 0;
+        let mut stack = vec![&self.root];
+        while let Some(node) = stack.pop() {
+            if node.depth == 1 {
+                leaf_nodes += 1;
+            }
+            for child in &node.children {
+                if let RTreeNode::DirectoryNode(data) = child {
+                    stack.push(data);
+                }
+            }
+        }
+        self.size as f64 / (leaf_nodes * self.root.options.max_size) as f64
+    }
+
+    /// Returns the tree's rebuild policy, if any.
+    pub fn rebuild_policy(&self) -> Option<&RebuildPolicy> {
+        self.maintenance
+            .as_ref()
+            .map(|maintenance| &maintenance.policy)
+    }
+
+    /// Removes the tree's rebuild policy.
+    pub fn remove_rebuild_policy(&mut self) {
+        self.maintenance = None;
+    }
+
+    /// Counts insertions and removals and rebuilds the tree if its policy requires it.
+    fn record_modifications(&mut self, count: usize) {
+        let check = match self.maintenance.as_mut() {
+            Some(maintenance) => {
+                maintenance.modifications += count;
+                if maintenance.modifications >= maintenance.check_interval {
+                    maintenance.modifications = 0;
+                    maintenance.check_interval = maintenance.policy.check_interval;
+                    Some((maintenance.policy, maintenance.rebuild))
+                } else {
+                    None
+                }
+            }
+            None => None,
+        };
+        if let Some((policy, rebuild)) = check {
+            if policy.needs_rebuild(self) {
+                rebuild(self);
+                if policy.needs_rebuild(self) {
+                    // Rebuilding does not help, back off to keep the cost amortized
+                    let size = self.size;
+                    if let Some(maintenance) = self.maintenance.as_mut() {
+                        maintenance.check_interval = policy.check_interval.max(size);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Removes all elements from the tree.
+    ///
+    /// The tree's directory nodes are kept and reused by subsequent insertions.
+    pub fn clear(&mut self) {
+        let mut index = self.spare_nodes.len();
+        // Children are moved through a single buffer, nodes keep their own buffers
+        let mut children = Vec::new();
+        children.append(&mut self.root.children);
+        self.root.bounding_box = None;
+        loop {
+            for child in children.drain(..) {
+                if let RTreeNode::DirectoryNode(data) = child {
+                    self.spare_nodes.push(data);
+                }
+            }
+            // Spare nodes are emptied in turn, their children become spare as well
+            match self.spare_nodes.get_mut(index) {
+                Some(node) => {
+                    node.bounding_box = None;
+                    children.append(&mut node.children);
+                    index += 1;
+                }
+                None => break,
+            }
+        }
+        self.root.depth = 1;
+        self.size = 0;
+    }
+
+    /// Reserves space for the directory nodes of at least `additional` more objects.
+    ///
+    /// Only the list of spare nodes is allocated in advance, nodes themselves
+    /// are allocated by the insertions that need them. After a `clear`, all
+    /// nodes of the tree fit into the list and are reused by subsequent
+    /// insertions. All nodes but the root hold at least `min_size` children,
+    /// which bounds the number of nodes needed.
+    pub fn reserve(&mut self, additional: usize) {
+        let min_size = self.root.options.min_size.max(2);
+        let max_nodes = (self.size + additional) / (min_size - 1);
+        self.spare_nodes
+            .reserve(max_nodes.saturating_sub(self.spare_nodes.len()));
+    }
+
+    /// Reallocates the children of the tree's directory nodes in breadth-first order.
+    ///
+    /// Trees built by repeated insertions scatter their nodes across the heap.
+    /// Compacting allocates the children of each level one after another, which
+    /// places them next to each other with common allocators and speeds up
+    /// subsequent queries. The new allocations have no excess capacity.
+    pub fn compact(&mut self) {
+        // Old allocations are freed at the end to prevent their reuse
+        let mut old_children = Vec::new();
+        let mut queue = VecDeque::new();
+        queue.push_back(&mut self.root);
+        while let Some(node) = queue.pop_front() {
+            let mut fresh = Vec::with_capacity(node.children.len());
+            fresh.append(&mut node.children);
+            old_children.push(::core::mem::replace(&mut node.children, fresh));
+            for child in node.children.iter_mut() {
+                if let RTreeNode::DirectoryNode(data) = child {
+                    queue.push_back(data);
+                }
+            }
+        }
+    }
+
+    /// Releases excess capacity of the tree's nodes.
+    ///
+    /// Nodes keep the buffer of their children after removals. This method
+    /// shrinks these buffers, unlike `compact`, it does not move nodes whose
+    /// buffers have no excess capacity. Spare nodes kept by `clear` and
+    /// `reserve` are freed.
+    pub fn shrink_to_fit(&mut self) {
+        self.spare_nodes = Vec::new();
+        let mut stack = vec![&mut self.root];
+        while let Some(node) = stack.pop() {
+            node.children.shrink_to_fit();
+            for child in node.children.iter_mut() {
+                if let RTreeNode::DirectoryNode(data) = child {
+                    stack.push(data);
+                }
+            }
+        }
+    }
+
+    /// Returns the estimated heap memory used by this tree.
+    ///
+    /// Heap memory owned by the contained objects is not included, use
+    /// `memory_usage_with` to account for it.
+    pub fn memory_usage(&self) -> RTreeMemoryUsage {
+        self.memory_usage_with(|_| 0)
+    }
+
+    /// Returns the estimated heap memory used by this tree.
+    ///
+    /// `payload` returns the number of heap bytes owned by an object, e.g.
+    /// the capacity of a contained `Vec`.
+    pub fn memory_usage_with<F>(&self, mut payload: F) -> RTreeMemoryUsage
+    where
+        F: FnMut(&T) -> usize,
+    {
+        let slot = ::core::mem::size_of::<RTreeNode<T>>();
+        // The options are shared by all nodes
+        let mut usage = RTreeMemoryUsage {
+            nodes: ::core::mem::size_of::<RTreeOptions>() + 2 * ::core::mem::size_of::<usize>(),
+            ..Default::default()
+        };
+        // Directory nodes are stored in the children buffer of their parent
+        let mut stack = vec![&self.root];
+        while let Some(node) = stack.pop() {
+            let mut leaves = 0;
+            for child in node.children.iter() {
+                match child {
+                    RTreeNode::DirectoryNode(data) => stack.push(data),
+                    RTreeNode::Leaf(object) => {
+                        leaves += 1;
+                        usage.payload += payload(object);
+                    }
+                }
+            }
+            usage.leaves += leaves * slot;
+            usage.nodes += node.children.capacity() * slot - leaves * slot;
+        }
+        usage.nodes += self.spare_nodes.capacity() * ::core::mem::size_of::<DirectoryNodeData<T>>();
+        for node in &self.spare_nodes {
+            usage.nodes += node.children.capacity() * slot;
+        }
+        usage
+    }
+
     /// Returns an iterator over all contained elements.
     pub fn iter(&self) -> RTreeIterator<T> {
         RTreeIterator::new(&self.root)
@@ -1212,6 +2167,77 @@ where
         }
     }
 
+    /// Returns an approximate nearest neighbor.
+    ///
+    /// The returned object's distance to `query_point` is at most `1 + epsilon`
+    /// times the distance of the actual nearest neighbor. Larger values of
+    /// `epsilon` allow the search to stop earlier, e.g. an `epsilon` of `0.05`
+    /// trades five percent of accuracy for fewer visited nodes. An `epsilon` of
+    /// zero yields an exact nearest neighbor. Returns `None` if the tree is empty.
+    ///
+    /// # Example
+    /// ```
+    /// use spade::rtree::RTree;
+    /// use spade::{Point2, SpatialObject};
+    ///
+    /// let tree = RTree::bulk_load((0..100).map(|i| Point2::new(i as f64, 0.0)).collect());
+    /// let query = Point2::new(42.4, 1.0);
+    /// let approx = tree.nearest_neighbor_approx(&query, 0.5).unwrap();
+    /// let exact = tree.nearest_neighbor(&query).unwrap();
+    /// assert!(approx.distance2(&query).sqrt() <= 1.5 * exact.distance2(&query).sqrt());
+    /// ```
+    pub fn nearest_neighbor_approx(
+        &self,
+        query_point: &T::Point,
+        epsilon: <T::Point as PointN>::Scalar,
+    ) -> Option<&T> {
+        let ratio = one::<<T::Point as PointN>::Scalar>() + epsilon;
+        self.root.nearest_neighbor_approx(
+            query_point,
+            ratio.clone() * ratio,
+            &mut BinaryHeap::new(),
+        )
+    }
+
+    /// Returns the nearest neighbor, keeping the search's priority queue in `scratch`.
+    ///
+    /// Returns `None` if the tree is empty.
+    pub fn nearest_neighbor_with_scratch(
+        &self,
+        query_point: &T::Point,
+        scratch: &mut QueryScratch<T>,
+    ) -> Option<&T> {
+        let mut result = None;
+        self.nearest_n_neighbors_with_scratch(query_point, 1, scratch, |t| result = Some(t));
+        result
+    }
+
+    /// Calls `visitor` for the nearest n neighbors, ordered by their distance.
+    ///
+    /// Keeps the search's priority queue in `scratch`.
+    pub fn nearest_n_neighbors_with_scratch<'a, F>(
+        &'a self,
+        query_point: &T::Point,
+        n: usize,
+        scratch: &mut QueryScratch<T>,
+        mut visitor: F,
+    ) where
+        F: FnMut(&'a T),
+    {
+        if n == 0 {
+            return;
+        }
+        let mut heap = BinaryHeap::from(::core::mem::take(&mut scratch.heap));
+        let mut remaining = n;
+        self.root
+            .visit_nearest(query_point, &mut heap, &mut scratch.nodes, |t| {
+                visitor(t);
+                remaining -= 1;
+                remaining == 0
+            });
+        scratch.heap = heap.into_vec();
+    }
+
     /// Returns an object close to a given point. This operation is faster than
     /// `nearest_neighbor` but will not neccessarily yield the real nearest neighbor.
     pub fn close_neighbor(&self, point: &T::Point) -> Option<&T> {
@@ -1251,6 +2277,29 @@ where
         result
     }
 
+    /// Calls `visitor` for all objects (partially) contained in a rectangle.
+    ///
+    /// Keeps the traversal's visit stack in `scratch`.
+    pub fn lookup_in_rectangle_with_scratch<'a, F>(
+        &'a self,
+        query_rect: &BoundingRect<T::Point>,
+        scratch: &mut QueryScratch<T>,
+        mut visitor: F,
+    ) where
+        F: FnMut(&'a T),
+    {
+        self.root.visit_depth_first(
+            &mut scratch.path,
+            |data| data.mbr().intersects(query_rect),
+            |t| {
+                if t.mbr().intersects(query_rect) {
+                    visitor(t);
+                }
+                false
+            },
+        );
+    }
+
     /// Returns all objects (partially) contained in a circle.
     ///
     /// Note that `radius2` is the circle's squared radius, not the actual radius.
@@ -1267,6 +2316,48 @@ where
         }
         result
     }
+
+    /// Calls `visitor` for all objects (partially) contained in a circle.
+    ///
+    /// `radius2` is the circle's squared radius. Keeps the traversal's visit
+    /// stack in `scratch`.
+    pub fn lookup_in_circle_with_scratch<'a, F>(
+        &'a self,
+        circle_origin: &T::Point,
+        radius2: &<T::Point as PointN>::Scalar,
+        scratch: &mut QueryScratch<T>,
+        mut visitor: F,
+    ) where
+        F: FnMut(&'a T),
+    {
+        self.root.visit_depth_first(
+            &mut scratch.path,
+            |data| data.mbr().min_dist2(circle_origin) <= *radius2,
+            |t| {
+                if t.distance2(circle_origin) < *radius2 {
+                    visitor(t);
+                }
+                false
+            },
+        );
+    }
+
+    /// Returns all objects whose bounding rectangle is hit by a ray.
+    ///
+    /// The objects are ordered by the ray parameter at which the ray enters
+    /// their bounding rectangle. The result contains candidates only, use the
+    /// intersection methods of `SimpleRay` for exact tests, e.g. for picking.
+    pub fn lookup_along_ray(&self, ray: &SimpleRay<T::Point>) -> Vec<&T>
+    where
+        <T::Point as PointN>::Scalar: SpadeFloat,
+    {
+        let mut result = Vec::new();
+        if self.size > 0 {
+            self.root.lookup_along_ray(&mut result, ray);
+        }
+        result.sort_by(|(t0, _), (t1, _)| t0.partial_cmp(t1).unwrap());
+        result.into_iter().map(|(_, obj)| obj).collect()
+    }
 }
 
 impl<T> RTree<T>
@@ -1292,8 +2383,51 @@ where
         RTree {
             root: DirectoryNodeData::bulk_load(options, &mut elements),
             size: elements.len(),
+            maintenance: None,
+            spare_nodes: Vec::new(),
         }
     }
+
+    /// Creates a new r-tree containing clones of the given elements.
+    ///
+    /// Behaves like `bulk_load`, but each element is cloned exactly once. Use
+    /// this instead of cloning a borrowed `Vec` just to bulk load it.
+    pub fn bulk_load_from_slice(elements: &[T]) -> RTree<T> {
+        let mut entries = slice_entries(elements);
+        let options = Arc::new(RTreeOptions::default());
+        RTree {
+            root: DirectoryNodeData::bulk_load_with(options, &mut entries, &mut |entry| {
+                elements[entry.index as usize].clone()
+            }),
+            size: elements.len(),
+            maintenance: None,
+            spare_nodes: Vec::new(),
+        }
+    }
+
+    /// Sets the policy that decides when the tree rebuilds itself.
+    ///
+    /// The tree is rebuilt by bulk loading all of its objects again, the
+    /// policy is kept. The policy is not serialized together with the tree.
+    /// See `RebuildPolicy` for details.
+    pub fn set_rebuild_policy(&mut self, policy: RebuildPolicy) {
+        self.maintenance = Some(Maintenance {
+            policy,
+            rebuild: RTree::rebuild,
+            modifications: 0,
+            check_interval: policy.check_interval,
+        });
+    }
+
+    /// Bulk loads all objects of the tree again.
+    ///
+    /// This restores the query performance of trees that degraded after many
+    /// insertions and removals.
+    pub fn rebuild(&mut self) {
+        let mut elements = Vec::with_capacity(self.size);
+        self.root.take_objects(&mut elements);
+        self.root = DirectoryNodeData::bulk_load(self.root.options.clone(), &mut elements);
+    }
 }
 
 impl<T> RTree<T>
@@ -1312,6 +2446,29 @@ where
         }
     }
 
+    /// Searches for an element at a given position, keeping the visit stack in `scratch`.
+    pub fn lookup_with_scratch(
+        &self,
+        query_point: &T::Point,
+        scratch: &mut QueryScratch<T>,
+    ) -> Option<&T> {
+        if self.size == 0 || !self.root.mbr().contains_point(query_point) {
+            return None;
+        }
+        record(Counter::NodeVisits, 1);
+        self.root.visit_depth_first(
+            &mut scratch.path,
+            |data| {
+                let contains = data.mbr().contains_point(query_point);
+                if contains {
+                    record(Counter::NodeVisits, 1);
+                }
+                contains
+            },
+            |t| t.contains(query_point),
+        )
+    }
+
     /// Searches for an element at a given position and returns a mutable
     /// reference.
     /// If `query_point` is contained by multiple objects in the tree,
@@ -1325,23 +2482,37 @@ where
         }
     }
 
+    /// Moves all objects of the tree without rebuilding it.
+    ///
+    /// `translate_object` is called once for every object and must translate
+    /// it. All objects should be moved by the same offset, e.g. by applying
+    /// the same translating `AffineTransform2`. The tree's structure is kept,
+    /// only its bounding boxes are updated in `O(n)`. Any other modification
+    /// keeps the tree valid but may degrade query performance, consider
+    /// `bulk_load`ing the transformed objects instead.
+    pub fn translate<F>(&mut self, mut translate_object: F)
+    where
+        F: FnMut(&mut T),
+    {
+        if self.size > 0 {
+            self.root.translate_objects(&mut translate_object);
+        }
+    }
+
     /// Inserts a new element into the tree.
     ///
     /// This will require `O(log(n))` operations on average, where n is the number of
     /// elements contained in the tree.
     pub fn insert(&mut self, t: T) {
-        let mut state = InsertionState::new(self.root.depth + 1);
+        let mut state = InsertionState::new(self.root.depth + 1, &mut self.spare_nodes);
         let mut insertion_stack = vec![RTreeNode::Leaf(t)];
         while let Some(next) = insertion_stack.pop() {
             match self.root.insert(next, &mut state) {
                 InsertionResult::Split(node) => {
                     // The root node was split, create a new root and increase depth
                     let new_depth = self.root.depth + 1;
-                    let options = self.root.options.clone();
-                    let old_root = ::std::mem::replace(
-                        &mut self.root,
-                        DirectoryNodeData::new(new_depth, options),
-                    );
+                    let mut old_root = state.new_node(new_depth, &self.root.options);
+                    ::core::mem::swap(&mut self.root, &mut old_root);
                     self.root
                         .add_children(vec![RTreeNode::DirectoryNode(old_root), node]);
                 }
@@ -1353,6 +2524,70 @@ where
             }
         }
         self.size += 1;
+        self.record_modifications(1);
+    }
+
+    /// Reinserts the worst placed fraction of all objects.
+    ///
+    /// Objects are ranked by the distance of their center to the center of their
+    /// leaf node, the farthest `fraction` of them is removed and inserted again.
+    /// This applies the forced reinsertion of the r*-tree to the whole tree. Trees
+    /// that degraded after many insertions and removals can be improved without
+    /// rebuilding them. `fraction` is clamped to `[0, 1]`.
+    pub fn rebalance(&mut self, fraction: f64) {
+        let count = Float::round(self.size as f64 * fraction.clamp(0.0, 1.0)) as usize;
+        if count == 0 {
+            return;
+        }
+        let mut costs = Vec::with_capacity(self.size);
+        self.root.placement_costs(&mut costs);
+        let mut order: Vec<_> = (0..costs.len()).collect();
+        ::pdqselect::select_by(&mut order, count - 1, |l, r| {
+            costs[*r]
+                .partial_cmp(&costs[*l])
+                .unwrap_or(::core::cmp::Ordering::Equal)
+        });
+        let mut remove = vec![false; costs.len()];
+        for index in &order[..count] {
+            remove[*index] = true;
+        }
+        let mut extracted = Vec::with_capacity(count);
+        self.root.extract_marked(&remove, &mut 0, &mut extracted);
+        if self.root.children.is_empty() {
+            self.root.depth = 1;
+        }
+        self.size -= extracted.len();
+        for t in extracted {
+            self.insert(t);
+        }
+    }
+
+    /// Removes all objects for which `keep` returns `false`.
+    ///
+    /// The tree is traversed only once and each node's bounding rectangle is
+    /// recomputed at most once. Returns the number of removed objects.
+    pub fn retain<F>(&mut self, mut keep: F) -> usize
+    where
+        F: FnMut(&T) -> bool,
+    {
+        let removed = self.root.retain(&mut keep);
+        if self.root.children.is_empty() {
+            self.root.depth = 1;
+        }
+        self.size -= removed;
+        self.record_modifications(removed);
+        removed
+    }
+
+    /// Inserts a new element into the tree if all of its coordinates are finite.
+    ///
+    /// Behaves like `insert`, but rejects elements whose minimal bounding rectangle
+    /// contains a NaN or infinite coordinate. Such elements would otherwise corrupt
+    /// the tree's internal structure without any notice.
+    pub fn try_insert(&mut self, t: T) -> Result<(), NonFiniteCoordinateError> {
+        validate_object(&t)?;
+        self.insert(t);
+        Ok(())
     }
 
     /// Searches for an element and removes it.
@@ -1368,6 +2603,7 @@ where
                     self.root.depth = 1;
                 }
                 self.size -= 1;
+                self.record_modifications(1);
             }
             result
         } else {
@@ -1396,16 +2632,118 @@ where
         }
         if result {
             self.size -= 1;
+            self.record_modifications(1);
         }
         result
     }
 
+    /// Removes several objects from the tree.
+    ///
+    /// Behaves like calling `remove` for each object, but the bounding
+    /// rectangles of the nodes along the removed objects' paths are only
+    /// recomputed once after all objects have been removed. This is
+    /// considerably faster if many objects are removed at once. Returns the
+    /// number of removed objects.
+    pub fn remove_all(&mut self, objects: &[T]) -> usize {
+        if self.size == 0 {
+            return 0;
+        }
+        let removed = objects
+            .iter()
+            .filter(|obj| self.root.remove_object(obj, false))
+            .count();
+        if removed > 0 {
+            self.root.refresh_mbrs();
+        }
+        if self.root.children.is_empty() {
+            self.root.depth = 1;
+        }
+        self.size -= removed;
+        self.record_modifications(removed);
+        removed
+    }
+
     /// Returns `true` if a given object is contained in this tree.
     pub fn contains(&self, obj: &T) -> bool {
         self.root.contains(obj)
     }
 }
 
+fn slice_entries<T: SpatialObject>(elements: &[T]) -> Vec<SliceEntry<T::Point>> {
+    assert!(
+        elements.len() <= u32::MAX as usize,
+        "Too many elements for 32 bit indices"
+    );
+    elements
+        .iter()
+        .enumerate()
+        .map(|(index, element)| SliceEntry {
+            index: index as u32,
+            mbr: element.mbr(),
+        })
+        .collect()
+}
+
+impl<'a, T> RTree<ObjectIndex<'a, T>>
+where
+    T: SpatialObject,
+    T::Point: TwoDimensional,
+{
+    /// Creates a new r-tree of references into the given slice.
+    ///
+    /// The tree stores a reference and a 32 bit index per element instead of
+    /// the element itself. This avoids cloning objects that are kept in a
+    /// `Vec` anyway. Queries consider the referenced objects, see `ObjectIndex`.
+    ///
+    /// # Example
+    /// ```
+    /// use spade::primitives::SimplePolygon;
+    /// use spade::rtree::RTree;
+    /// use spade::{BoundingRect, Point2};
+    ///
+    /// let polygons: Vec<_> = (0..10)
+    ///     .map(|i| {
+    ///         let x = i as f64 * 3.0;
+    ///         SimplePolygon::new(vec![
+    ///             Point2::new(x, 0.0),
+    ///             Point2::new(x + 2.0, 0.0),
+    ///             Point2::new(x + 1.0, 2.0),
+    ///         ])
+    ///     })
+    ///     .collect();
+    /// let tree = RTree::bulk_load_indices(&polygons);
+    /// let query = BoundingRect::from_corners(&Point2::new(4.0, 0.0), &Point2::new(7.0, 1.0));
+    /// let mut found: Vec<_> = tree
+    ///     .lookup_in_rectangle(&query)
+    ///     .iter()
+    ///     .map(|object| object.index())
+    ///     .collect();
+    /// found.sort();
+    /// assert_eq!(found, vec![1, 2]);
+    /// let nearest = tree.nearest_neighbor(&Point2::new(5.2, 1.9)).unwrap();
+    /// assert_eq!(nearest.index(), 1);
+    /// assert_eq!(nearest.object().vertices()[0], Point2::new(3.0, 0.0));
+    /// ```
+    ///
+    /// # Panics
+    /// Panics if the slice contains more than `u32::MAX` elements.
+    pub fn bulk_load_indices(elements: &'a [T]) -> RTree<ObjectIndex<'a, T>> {
+        let mut entries = slice_entries(elements);
+        let options = Arc::new(RTreeOptions::default());
+        RTree {
+            root: DirectoryNodeData::bulk_load_with(options, &mut entries, &mut |entry| {
+                ObjectIndex {
+                    object: &elements[entry.index as usize],
+                    index: entry.index,
+                }
+            }),
+            size: elements.len(),
+            maintenance: None,
+            spare_nodes: Vec::new(),
+        }
+    }
+}
+
 #[inline]
 fn create_clusters<T: SpatialObject>(array: &mut [T], cluster_size: usize, dimension: usize) {
     let comp = |l: &T, r: &T| {
@@ -1425,13 +2763,43 @@ fn create_clusters<T: SpatialObject>(array: &mut [T], cluster_size: usize, dimen
     }
 }
 
+impl<T> RTree<T>
+where
+    T: HasPosition,
+    T::Point: TwoDimensional,
+    <T::Point as PointN>::Scalar: SpadeFloat,
+{
+    /// Returns the nearest neighbor in respect to an anisotropic metric.
+    ///
+    /// Returns `None` if the tree is empty.
+    pub fn nearest_neighbor_with_metric(
+        &self,
+        query_point: &T::Point,
+        metric: &AnisotropicMetric<<T::Point as PointN>::Scalar>,
+    ) -> Option<&T> {
+        self.nearest_neighbor_iterator_with_metric(query_point, metric)
+            .next()
+    }
+
+    /// Returns an iterator over the nearest neighbors of a point in respect to an
+    /// anisotropic metric.
+    pub fn nearest_neighbor_iterator_with_metric(
+        &self,
+        query_point: &T::Point,
+        metric: &AnisotropicMetric<<T::Point as PointN>::Scalar>,
+    ) -> AnisotropicNearestNeighborIterator<'_, T> {
+        AnisotropicNearestNeighborIterator::new(&self.root, query_point.clone(), *metric)
+    }
+}
+
 #[cfg(test)]
 mod test {
-    use super::RTree;
+    use super::{DirectoryNodeData, QueryScratch, RTree, RTreeNode, RTreeOptions, RebuildPolicy};
     use crate::boundingrect::BoundingRect;
-    use crate::primitives::{SimpleEdge, SimpleTriangle};
+    use crate::primitives::{SimpleEdge, SimpleRay, SimpleTriangle};
     use crate::testutils::*;
     use crate::traits::SpatialObject;
+    use crate::transform::AffineTransform2;
     use cgmath::{InnerSpace, Point2};
     use num::Float;
 
@@ -1441,6 +2809,69 @@ mod test {
     const SEED2: &[u8; 32] = b"\xa3\xc5\xc2\x01\xa5\x43\xb8\x03\x45\x56\xe0\x6c\x42\xaa\x51\xe6\
         \xd5\xcf\x9a\x88\x18\x6c\xff\x23\x3c\x54\x00\x6e\x77\xcb\x7a\x14";
 
+    #[test]
+    fn test_try_insert() {
+        let mut tree = RTree::new();
+        assert!(tree.try_insert([0.0, 1.0]).is_ok());
+        assert!(tree.try_insert([Float::nan(), 1.0]).is_err());
+        assert!(tree.try_insert([0.0, Float::infinity()]).is_err());
+        assert_eq!(tree.size(), 1);
+    }
+
+    #[test]
+    fn test_clear() {
+        let (mut tree, _) = create_random_tree::<f32>(1000, SEED);
+        tree.clear();
+        assert_eq!(tree.size(), 0);
+        assert!(tree.mbr().is_none());
+        assert!(tree.nearest_neighbor(&Point2::new(0.0, 0.0)).is_none());
+        tree.insert(Point2::new(1.0, 2.0));
+        assert_eq!(tree.size(), 1);
+        assert!(tree.lookup(&Point2::new(1.0, 2.0)).is_some());
+    }
+
+    #[test]
+    fn test_reserve() {
+        let points = random_points_with_seed::<f64>(1000, SEED);
+        let mut tree = RTree::new();
+        tree.reserve(points.len());
+        let capacity = tree.spare_nodes.capacity();
+        assert!(capacity >= 500);
+        assert!(tree.spare_nodes.is_empty());
+        for point in &points {
+            tree.insert(*point);
+        }
+        let mut num_directory_nodes = 0;
+        let mut stack = vec![tree.root()];
+        while let Some(node) = stack.pop() {
+            for child in node.children() {
+                if let RTreeNode::DirectoryNode(data) = child {
+                    num_directory_nodes += 1;
+                    stack.push(data);
+                }
+            }
+        }
+        assert!(num_directory_nodes > 100);
+
+        // All nodes are kept without growing the list of spare nodes
+        tree.clear();
+        assert_eq!(tree.spare_nodes.len(), num_directory_nodes);
+        assert_eq!(tree.spare_nodes.capacity(), capacity);
+        for point in &points {
+            tree.insert(*point);
+        }
+        assert!(tree.spare_nodes.is_empty());
+        for point in &points {
+            assert!(tree.contains(point));
+        }
+        tree.reserve(0);
+        assert_eq!(tree.spare_nodes.capacity(), capacity);
+        tree.clear();
+        tree.shrink_to_fit();
+        assert!(tree.spare_nodes.is_empty());
+        assert_eq!(tree.spare_nodes.capacity(), 0);
+    }
+
     #[test]
     fn test_tree_with_integral_points() {
         // This test should compile
@@ -1666,6 +3097,166 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_remove_all_and_retain() {
+        fn assert_tight_mbrs(node: &DirectoryNodeData<Point2<f64>>) {
+            let mut mbr = node.children()[0].mbr();
+            for child in node.children() {
+                mbr.add_rect(&child.mbr());
+                if let RTreeNode::DirectoryNode(data) = child {
+                    assert_tight_mbrs(data);
+                }
+            }
+            assert_eq!(node.mbr(), mbr);
+            assert!(!node.mbr_outdated);
+        }
+
+        let points = random_points_with_seed(1000, SEED);
+        let mut tree = RTree::bulk_load(points.clone());
+        let mut to_remove: Vec<_> = points.iter().step_by(2).cloned().collect();
+        to_remove.push(Point2::new(2.0, 2.0));
+        assert_eq!(tree.remove_all(&to_remove), 500);
+        assert_eq!(tree.size(), 500);
+        assert_tight_mbrs(tree.root());
+        assert!(!tree.contains(&points[0]));
+        assert_eq!(tree.remove_all(&points[1..2]), 1);
+        assert_tight_mbrs(tree.root());
+        for point in points.iter().skip(3).step_by(2) {
+            assert!(tree.contains(point));
+        }
+
+        let removed = tree.retain(|p| p.x > 0.0);
+        assert!(removed > 0);
+        assert_eq!(tree.size(), 499 - removed);
+        assert!(tree.iter().all(|p| p.x > 0.0));
+        assert_tight_mbrs(tree.root());
+
+        assert_eq!(tree.retain(|_| false), 499 - removed);
+        assert_eq!(tree.size(), 0);
+        assert_eq!(tree.root().depth(), 1);
+        assert_eq!(tree.remove_all(&points), 0);
+        tree.insert(Point2::new(0.5, 0.5));
+        assert_eq!(tree.size(), 1);
+    }
+
+    #[test]
+    fn test_rebuild_policy() {
+        let points = random_points_with_seed::<f64>(2000, SEED);
+        let mut tree = RTree::new();
+        let policy = RebuildPolicy::new().min_fill_factor(0.6).check_interval(50);
+        tree.set_rebuild_policy(policy);
+        assert_eq!(tree.rebuild_policy(), Some(&policy));
+        for point in &points {
+            tree.insert(*point);
+        }
+        assert!(tree.fill_factor() >= 0.6);
+        let mut unmaintained = tree.clone();
+        unmaintained.remove_rebuild_policy();
+        for point in points.iter().take(1500) {
+            assert!(tree.remove(point));
+            assert!(unmaintained.remove(point));
+        }
+        assert!(unmaintained.fill_factor() < 0.6);
+        assert!(tree.fill_factor() >= 0.6);
+        assert_eq!(tree.size(), 500);
+        for point in points.iter().skip(1500) {
+            assert!(tree.contains(point));
+        }
+
+        assert!(RebuildPolicy::new()
+            .max_depth(1)
+            .needs_rebuild(&unmaintained));
+        assert!(!RebuildPolicy::new().needs_rebuild(&unmaintained));
+        unmaintained.rebuild();
+        assert_eq!(unmaintained.size(), 500);
+        assert!(unmaintained.fill_factor() > 0.8);
+        assert_eq!(unmaintained.mbr(), tree.mbr());
+    }
+
+    #[test]
+    fn test_unsatisfiable_rebuild_policy() {
+        let points = random_points_with_seed::<f64>(2000, SEED);
+        for policy in [
+            RebuildPolicy::new().min_fill_factor(1.0),
+            RebuildPolicy::new().max_depth(1),
+        ] {
+            let mut tree = RTree::new();
+            tree.set_rebuild_policy(policy.check_interval(10));
+            for point in &points {
+                tree.insert(*point);
+            }
+            assert!(policy.needs_rebuild(&tree));
+            let check_interval = tree.maintenance.as_ref().unwrap().check_interval;
+            assert!(check_interval >= 1000);
+            for point in &points {
+                assert!(tree.contains(point));
+            }
+        }
+    }
+
+    #[test]
+    fn test_bulk_load_from_slice_and_indices() {
+        let points = random_points_with_seed::<f64>(1000, SEED);
+        let tree = RTree::bulk_load_from_slice(&points);
+        let reference = RTree::bulk_load(points.clone());
+        assert_eq!(tree.size(), 1000);
+        assert_eq!(tree.depth(), reference.depth());
+        assert_eq!(tree.mbr(), reference.mbr());
+        for point in &points {
+            assert!(tree.contains(point));
+        }
+
+        let indices = RTree::bulk_load_indices(&points);
+        assert_eq!(indices.size(), 1000);
+        assert_eq!(indices.mbr(), reference.mbr());
+        let query = Point2::new(0.1, -0.2);
+        let nearest = indices.nearest_neighbor(&query).unwrap();
+        assert_eq!(
+            &points[nearest.index()],
+            reference.nearest_neighbor(&query).unwrap()
+        );
+        let rect = BoundingRect::from_corners(&Point2::new(-0.5, -0.5), &Point2::new(0.5, 0.2));
+        let mut found: Vec<_> = indices
+            .lookup_in_rectangle(&rect)
+            .iter()
+            .map(|object| object.index())
+            .collect();
+        found.sort();
+        let expected: Vec<_> = (0..points.len())
+            .filter(|i| rect.contains_point(&points[*i]))
+            .collect();
+        assert_eq!(found, expected);
+
+        // Distances are measured to the objects, not to their bounding rectangles
+        let edges = [
+            SimpleEdge::new(Point2::new(0.0, 0.0), Point2::new(10.0, 10.0)),
+            SimpleEdge::new(Point2::new(1.0, 8.0), Point2::new(2.0, 8.0)),
+        ];
+        let indices = RTree::bulk_load_indices(&edges);
+        let query = Point2::new(1.0, 9.0);
+        assert!(edges[0].mbr().contains_point(&query));
+        let nearest = indices.nearest_neighbor(&query).unwrap();
+        assert_eq!(nearest.index(), 1);
+        assert_eq!(nearest.object(), &edges[1]);
+    }
+
+    #[test]
+    fn test_nearest_neighbor_approx() {
+        let (tree, _) = create_random_tree::<f64>(2000, SEED);
+        let queries = random_points_with_seed::<f64>(200, SEED2);
+        for query in &queries {
+            let exact = tree.nearest_neighbor(query).unwrap().distance2(query);
+            let same = tree.nearest_neighbor_approx(query, 0.0).unwrap();
+            assert_eq!(same.distance2(query), exact);
+            for epsilon in &[0.05, 0.5, 2.0] {
+                let approx = tree.nearest_neighbor_approx(query, *epsilon).unwrap();
+                assert!(approx.distance2(query).sqrt() <= (1.0 + epsilon) * exact.sqrt());
+            }
+        }
+        let empty: RTree<Point2<f64>> = RTree::new();
+        assert_eq!(empty.nearest_neighbor_approx(&queries[0], 0.1), None);
+    }
+
     #[test]
     fn test_remove_line() {
         let mut tree = RTree::new();
@@ -1770,6 +3361,160 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_queries_with_scratch() {
+        let points = random_points_with_seed::<f64>(300, SEED);
+        let queries = random_points_with_seed::<f64>(50, SEED2);
+        let mut tree = RTree::new();
+        let mut scratch = QueryScratch::new();
+        assert_eq!(
+            tree.nearest_neighbor_with_scratch(&queries[0], &mut scratch),
+            None
+        );
+        for p in &points {
+            tree.insert(*p);
+        }
+        for query in &queries {
+            assert_eq!(
+                tree.nearest_neighbor_with_scratch(query, &mut scratch),
+                tree.nearest_neighbor(query)
+            );
+            let mut nearest = Vec::new();
+            tree.nearest_n_neighbors_with_scratch(query, 5, &mut scratch, |p| nearest.push(p));
+            assert_eq!(nearest, tree.nearest_n_neighbors(query, 5));
+
+            let mut in_circle = Vec::new();
+            tree.lookup_in_circle_with_scratch(query, &0.1, &mut scratch, |p| in_circle.push(p));
+            let expected = tree.lookup_in_circle(query, &0.1);
+            assert_eq!(in_circle.len(), expected.len());
+            assert!(in_circle.iter().all(|p| expected.contains(p)));
+
+            let rect =
+                BoundingRect::from_corners(query, &Point2::new(query.x + 0.3, query.y + 0.2));
+            let mut count = 0;
+            tree.lookup_in_rectangle_with_scratch(&rect, &mut scratch, |_| count += 1);
+            assert_eq!(count, tree.lookup_in_rectangle(&rect).len());
+        }
+        for p in &points {
+            assert_eq!(tree.lookup_with_scratch(p, &mut scratch), Some(p));
+        }
+        // The buffers' allocations are kept between queries
+        assert!(scratch.heap.capacity() > 0);
+        assert!(scratch.nodes.capacity() > 0);
+        assert!(scratch.path.capacity() > 0);
+        let (heap, nodes, path) = (
+            scratch.heap.as_ptr(),
+            scratch.nodes.as_ptr(),
+            scratch.path.as_ptr(),
+        );
+        for query in &queries {
+            tree.nearest_neighbor_with_scratch(query, &mut scratch);
+            tree.lookup_in_circle_with_scratch(query, &0.01, &mut scratch, |_| ());
+        }
+        assert_eq!(scratch.heap.as_ptr(), heap);
+        assert_eq!(scratch.nodes.as_ptr(), nodes);
+        assert_eq!(scratch.path.as_ptr(), path);
+    }
+
+    #[test]
+    fn test_compact() {
+        let points = random_points_with_seed::<f64>(1000, SEED);
+        let mut tree = RTreeOptions::new().max_size(10).min_size(4).build();
+        for p in &points {
+            tree.insert(*p);
+        }
+        let depth = tree.root().depth();
+        tree.compact();
+        assert_eq!(tree.size(), points.len());
+        assert_eq!(tree.root().depth(), depth);
+        assert_eq!(tree.iter().count(), points.len());
+        for p in &points {
+            assert_eq!(tree.nearest_neighbor(p), Some(p));
+        }
+        tree.insert(Point2::new(0.5, 0.5));
+        assert!(tree.lookup(&Point2::new(0.5, 0.5)).is_some());
+    }
+
+    #[test]
+    fn test_rebalance() {
+        fn leaf_area(node: &DirectoryNodeData<Point2<f64>>) -> f64 {
+            if node.depth() == 1 {
+                return node.mbr().area();
+            }
+            node.children()
+                .iter()
+                .map(|child| match child {
+                    RTreeNode::DirectoryNode(data) => leaf_area(data),
+                    RTreeNode::Leaf(_) => 0.0,
+                })
+                .sum()
+        }
+
+        let points = random_points_with_seed::<f64>(4000, SEED);
+        let mut tree = RTree::new();
+        // Simulate churn by moving a window of points through the data
+        for (index, p) in points.iter().enumerate() {
+            tree.insert(*p);
+            if index >= 1000 {
+                assert!(tree.remove(&points[index - 1000]));
+            }
+        }
+        let area = leaf_area(tree.root());
+        tree.rebalance(0.0);
+        assert_eq!(leaf_area(tree.root()), area);
+        tree.rebalance(0.3);
+        assert_eq!(tree.size(), 1000);
+        assert!(leaf_area(tree.root()) < area);
+        for p in &points[3000..] {
+            assert_eq!(tree.nearest_neighbor(p), Some(p));
+        }
+        tree.rebalance(1.0);
+        assert_eq!(tree.iter().count(), 1000);
+    }
+
+    #[test]
+    fn test_shrink_to_fit() {
+        let points = random_points_with_seed::<f64>(1000, SEED);
+        let mut tree = RTreeOptions::new().max_size(12).min_size(4).build();
+        for p in &points {
+            tree.insert(*p);
+        }
+        for p in &points[100..] {
+            assert!(tree.remove(p));
+        }
+        let before = tree.memory_usage();
+        tree.shrink_to_fit();
+        let after = tree.memory_usage();
+        assert!(after.nodes <= before.nodes);
+        assert_eq!(after.leaves, before.leaves);
+        assert_eq!(tree.size(), 100);
+        for p in &points[..100] {
+            assert_eq!(tree.nearest_neighbor(p), Some(p));
+        }
+    }
+
+    #[test]
+    fn test_memory_usage() {
+        let slot = ::std::mem::size_of::<RTreeNode<Point2<f64>>>();
+        let mut tree = RTree::new();
+        let empty = tree.memory_usage();
+        assert_eq!(empty.leaves, 0);
+        assert_eq!(empty.payload, 0);
+        tree.insert(Point2::new(0.0, 0.0));
+        // The root's children buffer is allocated up front
+        let one = tree.memory_usage();
+        assert_eq!(one.leaves, slot);
+        assert_eq!(one.nodes + one.leaves, empty.nodes);
+
+        let points = random_points_with_seed::<f64>(1000, SEED);
+        let tree = RTree::bulk_load(points);
+        let usage = tree.memory_usage_with(|_| 3);
+        assert_eq!(usage.leaves, 1000 * slot);
+        assert_eq!(usage.payload, 3000);
+        assert!(usage.nodes > empty.nodes);
+        assert_eq!(usage.total(), usage.nodes + usage.leaves + usage.payload);
+    }
+
     #[cfg(feature = "serde")]
     #[test]
     fn test_serialization() {
@@ -1785,4 +3530,46 @@ mod test {
             assert!(parsed.contains(point));
         }
     }
+
+    #[test]
+    fn test_lookup_along_ray() {
+        let mut tree = RTree::new();
+        let edges: Vec<_> = (0..20)
+            .map(|i| {
+                let x = i as f64;
+                SimpleEdge::new(Point2::new(x, -1.0), Point2::new(x, 1.0))
+            })
+            .collect();
+        for edge in edges.iter().rev() {
+            tree.insert(*edge);
+        }
+        let ray = SimpleRay::new(Point2::new(4.5, 0.0), Point2::new(1.0, 0.0));
+        let hits = tree.lookup_along_ray(&ray);
+        assert_eq!(hits.len(), 15);
+        for (hit, edge) in hits.iter().zip(&edges[5..]) {
+            assert_eq!(*hit, edge);
+            assert!(ray.intersect_edge(hit).is_some());
+        }
+        let miss = SimpleRay::new(Point2::new(4.5, 2.0), Point2::new(1.0, 0.0));
+        assert!(tree.lookup_along_ray(&miss).is_empty());
+        assert!(RTree::<SimpleEdge<Point2<f64>>>::new()
+            .lookup_along_ray(&ray)
+            .is_empty());
+    }
+
+    #[test]
+    fn test_translate() {
+        let points = random_points_with_seed::<f64>(200, SEED);
+        let mut tree = RTree::bulk_load(points.clone());
+        let transform = AffineTransform2::translation(10.0, -5.0);
+        tree.translate(|p| *p = transform.transform_point(p));
+        assert_eq!(tree.size(), points.len());
+        for p in &points {
+            let moved = transform.transform_point(p);
+            assert_eq!(tree.lookup(&moved), Some(&moved));
+            assert_eq!(tree.nearest_neighbor(&moved), Some(&moved));
+        }
+        let mbr = tree.mbr().unwrap();
+        assert!(mbr.lower().x >= 9.0 && mbr.upper().y <= -4.0);
+    }
 }
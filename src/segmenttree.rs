@@ -0,0 +1,366 @@
+// Copyright 2017 The Spade Developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Stabbing queries on axis-parallel segments.
+//!
+//! `SegmentTree` stores horizontal and vertical segments and reports all
+//! segments crossed by a vertical or horizontal query segment, a common step
+//! of scanline and windowing algorithms. An r-tree answers such queries
+//! poorly: long segments have large bounding rectangles which overlap
+//! most of the tree.
+//!
+//! Horizontal segments are stored in a segment tree over their x intervals.
+//! Each tree node keeps its segments sorted by their y coordinate, a query
+//! visits one node per level of the tree and finds the crossed segments of
+//! a node by binary search. Vertical segments are stored accordingly. A query
+//! takes `O(log² n + k)` time for `k` results.
+//!
+//! # Example
+//!
+//! ```
+//! use spade::interval::Interval;
+//! use spade::primitives::SimpleEdge;
+//! use spade::segmenttree::SegmentTree;
+//! use spade::Point2;
+//!
+//! let tree = SegmentTree::bulk_load(vec![
+//!     SimpleEdge::new(Point2::new(0.0, 0.0), Point2::new(10.0, 0.0)),
+//!     SimpleEdge::new(Point2::new(2.0, 3.0), Point2::new(4.0, 3.0)),
+//!     SimpleEdge::new(Point2::new(5.0, -2.0), Point2::new(5.0, 2.0)),
+//! ]);
+//! // Horizontal segments crossing the vertical line x = 3
+//! assert_eq!(tree.lookup_crossing_vertical_line(&3.0).len(), 2);
+//! // Horizontal segments crossing the vertical segment from (3, -1) to (3, 1)
+//! assert_eq!(tree.lookup_crossing_vertical(&3.0, &Interval::new(-1.0, 1.0)).len(), 1);
+//! // Vertical segments crossing the horizontal segment from (0, 1) to (10, 1)
+//! assert_eq!(tree.lookup_crossing_horizontal(&1.0, &Interval::new(0.0, 10.0)).len(), 1);
+//! ```
+
+use crate::interval::Interval;
+use crate::point_traits::TwoDimensional;
+use crate::primitives::SimpleEdge;
+use crate::traits::SpadeNum;
+use alloc::{vec, vec::Vec};
+use core::cmp::Ordering;
+
+fn compare<S: SpadeNum>(l: &S, r: &S) -> Ordering {
+    l.partial_cmp(r).unwrap_or(Ordering::Equal)
+}
+
+/// A segment tree of intervals, each at a level along the other axis.
+///
+/// The tree's leaves are elementary slots: slot `2 * i` is the coordinate
+/// `coordinates[i]`, slot `2 * i + 1` the open range up to the next
+/// coordinate.
+#[derive(Clone, Debug)]
+struct StabbingTree<S> {
+    coordinates: Vec<S>,
+    // Heap layout, each node stores its (level, entry index) pairs sorted by level
+    nodes: Vec<Vec<(S, usize)>>,
+}
+
+impl<S: SpadeNum> StabbingTree<S> {
+    fn new(intervals: &[(Interval<S>, S, usize)]) -> Self {
+        let mut coordinates: Vec<S> = intervals
+            .iter()
+            .flat_map(|(interval, _, _)| [interval.start().clone(), interval.end().clone()])
+            .collect();
+        coordinates.sort_by(compare);
+        coordinates.dedup();
+        let mut tree = StabbingTree {
+            nodes: vec![Vec::new(); 8 * coordinates.len().max(1)],
+            coordinates,
+        };
+        if tree.coordinates.is_empty() {
+            return tree;
+        }
+        let last_slot = 2 * tree.coordinates.len() - 2;
+        for (interval, level, index) in intervals {
+            let first = 2 * tree.coordinate_index(interval.start());
+            let last = 2 * tree.coordinate_index(interval.end());
+            tree.insert(1, 0, last_slot, first, last, (level.clone(), *index));
+        }
+        for node in &mut tree.nodes {
+            node.sort_by(|(l, _), (r, _)| compare(l, r));
+        }
+        tree
+    }
+
+    fn coordinate_index(&self, coordinate: &S) -> usize {
+        self.coordinates
+            .binary_search_by(|c| compare(c, coordinate))
+            .unwrap_or_else(|index| index)
+    }
+
+    fn insert(
+        &mut self,
+        node: usize,
+        lower: usize,
+        upper: usize,
+        first: usize,
+        last: usize,
+        entry: (S, usize),
+    ) {
+        if first <= lower && upper <= last {
+            self.nodes[node].push(entry);
+            return;
+        }
+        let middle = lower + (upper - lower) / 2;
+        if first <= middle {
+            self.insert(2 * node, lower, middle, first, last, entry.clone());
+        }
+        if last > middle {
+            self.insert(2 * node + 1, middle + 1, upper, first, last, entry);
+        }
+    }
+
+    /// Returns the slot containing a coordinate.
+    fn slot(&self, coordinate: &S) -> Option<usize> {
+        match self
+            .coordinates
+            .binary_search_by(|c| compare(c, coordinate))
+        {
+            Ok(index) => Some(2 * index),
+            Err(index) if index > 0 && index < self.coordinates.len() => Some(2 * index - 1),
+            Err(_) => None,
+        }
+    }
+
+    /// Collects the entries whose interval contains `coordinate` and whose
+    /// level lies within `levels`.
+    fn query(&self, coordinate: &S, levels: Option<&Interval<S>>, result: &mut Vec<usize>) {
+        let slot = match self.slot(coordinate) {
+            Some(slot) => slot,
+            None => return,
+        };
+        let (mut node, mut lower, mut upper) = (1, 0, 2 * self.coordinates.len() - 2);
+        loop {
+            let entries = &self.nodes[node];
+            match levels {
+                Some(levels) => {
+                    let start = entries.partition_point(|(level, _)| level < levels.start());
+                    result.extend(
+                        entries[start..]
+                            .iter()
+                            .take_while(|(level, _)| level <= levels.end())
+                            .map(|(_, index)| *index),
+                    );
+                }
+                None => result.extend(entries.iter().map(|(_, index)| *index)),
+            }
+            if lower == upper {
+                return;
+            }
+            let middle = lower + (upper - lower) / 2;
+            if slot <= middle {
+                node *= 2;
+                upper = middle;
+            } else {
+                node = 2 * node + 1;
+                lower = middle + 1;
+            }
+        }
+    }
+}
+
+/// A static structure for stabbing queries on horizontal and vertical segments.
+///
+/// See the [module documentation](index.html) for more information.
+#[derive(Clone, Debug)]
+pub struct SegmentTree<V>
+where
+    V: TwoDimensional,
+{
+    segments: Vec<SimpleEdge<V>>,
+    horizontal: StabbingTree<V::Scalar>,
+    vertical: StabbingTree<V::Scalar>,
+}
+
+impl<V> SegmentTree<V>
+where
+    V: TwoDimensional,
+{
+    /// Creates a segment tree containing the given segments.
+    ///
+    /// Segments of zero length are treated as horizontal segments.
+    ///
+    /// # Panics
+    /// Panics if a segment is neither horizontal nor vertical.
+    pub fn bulk_load(segments: Vec<SimpleEdge<V>>) -> Self {
+        let mut horizontal = Vec::new();
+        let mut vertical = Vec::new();
+        for (index, segment) in segments.iter().enumerate() {
+            let (from, to) = (&segment.from, &segment.to);
+            if from.nth(1) == to.nth(1) {
+                let x = Interval::new(from.nth(0).clone(), to.nth(0).clone());
+                horizontal.push((x, from.nth(1).clone(), index));
+            } else if from.nth(0) == to.nth(0) {
+                let y = Interval::new(from.nth(1).clone(), to.nth(1).clone());
+                vertical.push((y, from.nth(0).clone(), index));
+            } else {
+                panic!("SegmentTree only supports horizontal and vertical segments");
+            }
+        }
+        SegmentTree {
+            horizontal: StabbingTree::new(&horizontal),
+            vertical: StabbingTree::new(&vertical),
+            segments,
+        }
+    }
+
+    /// Returns the number of segments contained in this tree.
+    pub fn size(&self) -> usize {
+        self.segments.len()
+    }
+
+    /// Returns all segments in their original order.
+    pub fn segments(&self) -> &[SimpleEdge<V>] {
+        &self.segments
+    }
+
+    fn collect(&self, indices: Vec<usize>) -> Vec<&SimpleEdge<V>> {
+        indices
+            .into_iter()
+            .map(|index| &self.segments[index])
+            .collect()
+    }
+
+    /// Returns all horizontal segments crossing a vertical line.
+    ///
+    /// Segments that touch the line with an end point are included.
+    pub fn lookup_crossing_vertical_line(&self, x: &V::Scalar) -> Vec<&SimpleEdge<V>> {
+        let mut result = Vec::new();
+        self.horizontal.query(x, None, &mut result);
+        self.collect(result)
+    }
+
+    /// Returns all vertical segments crossing a horizontal line.
+    ///
+    /// Segments that touch the line with an end point are included.
+    pub fn lookup_crossing_horizontal_line(&self, y: &V::Scalar) -> Vec<&SimpleEdge<V>> {
+        let mut result = Vec::new();
+        self.vertical.query(y, None, &mut result);
+        self.collect(result)
+    }
+
+    /// Returns all horizontal segments crossing the vertical segment at `x` spanning `y_range`.
+    ///
+    /// Touching segments are included. Vertical segments are not reported,
+    /// even if they overlap the query segment.
+    pub fn lookup_crossing_vertical(
+        &self,
+        x: &V::Scalar,
+        y_range: &Interval<V::Scalar>,
+    ) -> Vec<&SimpleEdge<V>> {
+        let mut result = Vec::new();
+        self.horizontal.query(x, Some(y_range), &mut result);
+        self.collect(result)
+    }
+
+    /// Returns all vertical segments crossing the horizontal segment at `y` spanning `x_range`.
+    ///
+    /// Touching segments are included. Horizontal segments are not reported,
+    /// even if they overlap the query segment.
+    pub fn lookup_crossing_horizontal(
+        &self,
+        y: &V::Scalar,
+        x_range: &Interval<V::Scalar>,
+    ) -> Vec<&SimpleEdge<V>> {
+        let mut result = Vec::new();
+        self.vertical.query(y, Some(x_range), &mut result);
+        self.collect(result)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::SegmentTree;
+    use crate::interval::Interval;
+    use crate::primitives::SimpleEdge;
+    use crate::Point2;
+    use rand::{Rng, SeedableRng};
+    use rand_hc::Hc128Rng;
+
+    fn random_segments(count: usize) -> Vec<SimpleEdge<Point2<i64>>> {
+        let mut rng = Hc128Rng::from_seed([3; 32]);
+        (0..count)
+            .map(|index| {
+                let a = Point2::new(rng.gen_range(-100..100), rng.gen_range(-100..100));
+                let length = rng.gen_range(0..40);
+                let b = if index % 2 == 0 {
+                    Point2::new(a.x + length, a.y)
+                } else {
+                    Point2::new(a.x, a.y - length)
+                };
+                SimpleEdge::new(a, b)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_segment_tree_queries() {
+        let segments = random_segments(400);
+        let tree = SegmentTree::bulk_load(segments.clone());
+        assert_eq!(tree.size(), 400);
+        let mut rng = Hc128Rng::from_seed([4; 32]);
+        for _ in 0..200 {
+            let c = rng.gen_range(-110..110);
+            let a = rng.gen_range(-110..110);
+            let range = Interval::new(a, a + rng.gen_range(0..30));
+
+            let mut found = tree.lookup_crossing_vertical(&c, &range);
+            let mut expected: Vec<_> = segments
+                .iter()
+                .filter(|s| s.from.y == s.to.y)
+                .filter(|s| {
+                    Interval::new(s.from.x, s.to.x).contains(&c) && range.contains(&s.from.y)
+                })
+                .collect();
+            found.sort_by_key(|s| (s.from.x, s.from.y, s.to.x, s.to.y));
+            expected.sort_by_key(|s| (s.from.x, s.from.y, s.to.x, s.to.y));
+            assert_eq!(found, expected);
+
+            let mut found = tree.lookup_crossing_horizontal(&c, &range);
+            let mut expected: Vec<_> = segments
+                .iter()
+                .filter(|s| s.from.y != s.to.y)
+                .filter(|s| {
+                    Interval::new(s.from.y, s.to.y).contains(&c) && range.contains(&s.from.x)
+                })
+                .collect();
+            found.sort_by_key(|s| (s.from.x, s.from.y, s.to.x, s.to.y));
+            expected.sort_by_key(|s| (s.from.x, s.from.y, s.to.x, s.to.y));
+            assert_eq!(found, expected);
+
+            let stabbed = tree.lookup_crossing_vertical_line(&c).len();
+            let expected = segments
+                .iter()
+                .filter(|s| s.from.y == s.to.y && Interval::new(s.from.x, s.to.x).contains(&c))
+                .count();
+            assert_eq!(stabbed, expected);
+        }
+    }
+
+    #[test]
+    fn test_empty_segment_tree() {
+        let tree: SegmentTree<Point2<f64>> = SegmentTree::bulk_load(Vec::new());
+        assert!(tree.lookup_crossing_horizontal_line(&0.0).is_empty());
+        assert!(tree
+            .lookup_crossing_vertical(&0.0, &Interval::new(0.0, 1.0))
+            .is_empty());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_diagonal_segment() {
+        SegmentTree::bulk_load(vec![SimpleEdge::new(
+            Point2::new(0.0, 0.0),
+            Point2::new(1.0, 1.0),
+        )]);
+    }
+}
@@ -0,0 +1,663 @@
+// Copyright 2017 The Spade Developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Rendering of triangulations, r-trees and primitives into SVG images.
+//!
+//! An `SvgRenderer` collects shapes in the coordinate system of the drawn
+//! data. When rendering, all shapes are scaled to fit into the image, the y
+//! axis points upwards. Stroke widths and point radii are given in pixels of
+//! the resulting image.
+//!
+//! # Example
+//!
+//! ```
+//! use spade::delaunay::FloatDelaunayTriangulation;
+//! use spade::svg::{Style, SvgRenderer, TriangulationStyle};
+//! use spade::Point2;
+//!
+//! let mut delaunay = FloatDelaunayTriangulation::with_walk_locate();
+//! for (x, y) in [(0.0, 0.0), (4.0, 0.0), (2.0, 3.0), (2.0, 1.0)] {
+//!     delaunay.insert(Point2::new(x, y));
+//! }
+//! let mut renderer = SvgRenderer::new(400.0, 300.0);
+//! renderer.draw_triangulation(&delaunay, &TriangulationStyle::default());
+//! renderer.draw_voronoi(&delaunay, &Style::new().stroke("blue"));
+//! // Highlight a query result
+//! let nearest = delaunay.nearest_neighbor(&Point2::new(3.0, 2.0)).unwrap();
+//! renderer.draw_point(&*nearest, &Style::new().stroke("red").point_radius(5.0));
+//! let svg = renderer.to_string();
+//! assert!(svg.starts_with("<svg"));
+//! ```
+
+use crate::boundingrect::BoundingRect;
+use crate::delaunay::{
+    ConstrainedDelaunayTriangulation, DelaunayLocateStructure, DelaunayTriangulation, EdgeHandle,
+    FaceHandle, VertexHandle,
+};
+use crate::kernels::DelaunayKernel;
+use crate::point_traits::{PointN, TwoDimensional};
+use crate::primitives::{SimpleEdge, SimpleTriangle};
+use crate::rtree::{RTree, RTreeNode};
+use crate::traits::{HasPosition2D, SpadeFloat, SpatialObject};
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use core::fmt;
+use num::ToPrimitive;
+
+/// Stroke, fill and point size of drawn shapes.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Style {
+    stroke: String,
+    stroke_width: f64,
+    fill: String,
+    opacity: f64,
+    point_radius: f64,
+}
+
+impl Default for Style {
+    fn default() -> Self {
+        Style::new()
+    }
+}
+
+impl Style {
+    /// Creates a style with a black stroke of width one and no fill.
+    pub fn new() -> Self {
+        Style {
+            stroke: "black".to_string(),
+            stroke_width: 1.0,
+            fill: "none".to_string(),
+            opacity: 1.0,
+            point_radius: 2.0,
+        }
+    }
+
+    /// Sets the stroke color, any SVG color like `"red"` or `"#ff0000"`.
+    pub fn stroke(mut self, color: &str) -> Self {
+        self.stroke = color.to_string();
+        self
+    }
+
+    /// Sets the stroke width in pixels.
+    pub fn stroke_width(mut self, width: f64) -> Self {
+        self.stroke_width = width;
+        self
+    }
+
+    /// Sets the fill color of areas and points. Use `"none"` to disable filling.
+    pub fn fill(mut self, color: &str) -> Self {
+        self.fill = color.to_string();
+        self
+    }
+
+    /// Sets the opacity, ranging from `0.0` (invisible) to `1.0`.
+    pub fn opacity(mut self, opacity: f64) -> Self {
+        self.opacity = opacity;
+        self
+    }
+
+    /// Sets the radius of drawn points in pixels.
+    pub fn point_radius(mut self, radius: f64) -> Self {
+        self.point_radius = radius;
+        self
+    }
+
+    fn attributes(&self, fill: &str) -> String {
+        format!(
+            "stroke=\"{}\" stroke-width=\"{}\" fill=\"{}\" opacity=\"{}\"",
+            escape(&self.stroke),
+            self.stroke_width,
+            escape(fill),
+            self.opacity
+        )
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('"', "&quot;")
+        .replace('<', "&lt;")
+}
+
+/// Styles used to draw a (constrained) Delaunay triangulation.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TriangulationStyle {
+    /// The style of all triangles, triangles are not drawn if `None`.
+    pub faces: Option<Style>,
+    /// The style of all edges.
+    pub edges: Style,
+    /// The style of constraint edges of constrained Delaunay triangulations.
+    pub constraint_edges: Style,
+    /// The style of all vertices, vertices are not drawn if `None`.
+    pub vertices: Option<Style>,
+}
+
+impl Default for TriangulationStyle {
+    fn default() -> Self {
+        TriangulationStyle {
+            faces: None,
+            edges: Style::new().stroke("gray"),
+            constraint_edges: Style::new().stroke("red").stroke_width(2.0),
+            vertices: Some(Style::new().fill("black")),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+enum Shape {
+    Point([f64; 2]),
+    Line([f64; 2], [f64; 2]),
+    Polyline(Vec<[f64; 2]>),
+    Polygon(Vec<[f64; 2]>),
+    Circle([f64; 2], f64),
+}
+
+fn to_f64<V>(point: &V) -> [f64; 2]
+where
+    V: TwoDimensional,
+    V::Scalar: ToPrimitive,
+{
+    [
+        point.nth(0).to_f64().unwrap_or(0.0),
+        point.nth(1).to_f64().unwrap_or(0.0),
+    ]
+}
+
+/// Draws shapes into an SVG image.
+///
+/// Shapes are drawn in the order in which they were added, later shapes
+/// are drawn on top of earlier ones.
+#[derive(Clone, Debug)]
+pub struct SvgRenderer {
+    width: f64,
+    height: f64,
+    margin: f64,
+    background: Option<String>,
+    shapes: Vec<(Shape, Style)>,
+}
+
+impl SvgRenderer {
+    /// Creates a renderer for an image of the given size in pixels.
+    pub fn new(width: f64, height: f64) -> Self {
+        SvgRenderer {
+            width,
+            height,
+            margin: 10.0,
+            background: Some("white".to_string()),
+            shapes: Vec::new(),
+        }
+    }
+
+    /// Sets the distance between the drawn shapes and the image border in pixels.
+    pub fn margin(mut self, margin: f64) -> Self {
+        self.margin = margin;
+        self
+    }
+
+    /// Sets the background color. A transparent background is used if `None`.
+    pub fn background(mut self, color: Option<&str>) -> Self {
+        self.background = color.map(str::to_string);
+        self
+    }
+
+    /// Draws a point.
+    pub fn draw_point<V>(&mut self, point: &V, style: &Style)
+    where
+        V: TwoDimensional,
+        V::Scalar: ToPrimitive,
+    {
+        self.shapes
+            .push((Shape::Point(to_f64(point)), style.clone()));
+    }
+
+    /// Draws an edge.
+    pub fn draw_edge<V>(&mut self, edge: &SimpleEdge<V>, style: &Style)
+    where
+        V: TwoDimensional,
+        V::Scalar: ToPrimitive,
+    {
+        let line = Shape::Line(to_f64(&edge.from), to_f64(&edge.to));
+        self.shapes.push((line, style.clone()));
+    }
+
+    /// Draws an open polyline.
+    pub fn draw_polyline<V>(&mut self, points: &[V], style: &Style)
+    where
+        V: TwoDimensional,
+        V::Scalar: ToPrimitive,
+    {
+        let points = points.iter().map(to_f64).collect();
+        self.shapes.push((Shape::Polyline(points), style.clone()));
+    }
+
+    /// Draws a closed polygon given by its vertices.
+    pub fn draw_polygon<V>(&mut self, vertices: &[V], style: &Style)
+    where
+        V: TwoDimensional,
+        V::Scalar: ToPrimitive,
+    {
+        let vertices = vertices.iter().map(to_f64).collect();
+        self.shapes.push((Shape::Polygon(vertices), style.clone()));
+    }
+
+    /// Draws a triangle.
+    pub fn draw_triangle<V>(&mut self, triangle: &SimpleTriangle<V>, style: &Style)
+    where
+        V: TwoDimensional,
+        V::Scalar: ToPrimitive,
+    {
+        let vertices = triangle.vertices().iter().map(|v| to_f64(*v)).collect();
+        self.shapes.push((Shape::Polygon(vertices), style.clone()));
+    }
+
+    /// Draws a rectangle.
+    pub fn draw_rect<V>(&mut self, rect: &BoundingRect<V>, style: &Style)
+    where
+        V: TwoDimensional,
+        V::Scalar: ToPrimitive,
+    {
+        let ([x0, y0], [x1, y1]) = (to_f64(&rect.lower()), to_f64(&rect.upper()));
+        let corners = vec![[x0, y0], [x1, y0], [x1, y1], [x0, y1]];
+        self.shapes.push((Shape::Polygon(corners), style.clone()));
+    }
+
+    /// Draws a circle. The radius is given in the data's coordinate system.
+    pub fn draw_circle<V>(&mut self, center: &V, radius: V::Scalar, style: &Style)
+    where
+        V: TwoDimensional,
+        V::Scalar: ToPrimitive,
+    {
+        let circle = Shape::Circle(to_f64(center), radius.to_f64().unwrap_or(0.0));
+        self.shapes.push((circle, style.clone()));
+    }
+
+    /// Draws the bounding rectangles of spatial objects, e.g. of query results.
+    ///
+    /// Objects with an empty bounding rectangle, like points, are drawn as
+    /// points.
+    pub fn draw_objects<'a, T, I>(&mut self, objects: I, style: &Style)
+    where
+        T: SpatialObject + 'a,
+        T::Point: TwoDimensional,
+        <T::Point as PointN>::Scalar: ToPrimitive,
+        I: IntoIterator<Item = &'a T>,
+    {
+        for object in objects {
+            let mbr = object.mbr();
+            if mbr.lower() == mbr.upper() {
+                self.draw_point(&mbr.lower(), style);
+            } else {
+                self.draw_rect(&mbr, style);
+            }
+        }
+    }
+
+    /// Draws the bounding rectangles of all directory nodes and the contents
+    /// of an r-tree.
+    pub fn draw_rtree<T>(&mut self, tree: &RTree<T>, nodes: &Style, leaves: &Style)
+    where
+        T: SpatialObject,
+        T::Point: TwoDimensional,
+        <T::Point as PointN>::Scalar: ToPrimitive,
+    {
+        if tree.size() == 0 {
+            return;
+        }
+        let mut stack = vec![tree.root()];
+        while let Some(node) = stack.pop() {
+            self.draw_rect(&node.mbr(), nodes);
+            for child in node.children() {
+                match child {
+                    RTreeNode::DirectoryNode(data) => stack.push(data),
+                    RTreeNode::Leaf(object) => self.draw_objects(Some(object), leaves),
+                }
+            }
+        }
+    }
+
+    fn draw_subdivision<'a, V, E, F, FI, EI, VI>(
+        &mut self,
+        faces: FI,
+        edges: EI,
+        vertices: VI,
+        is_constraint: F,
+        style: &TriangulationStyle,
+    ) where
+        V: HasPosition2D + 'a,
+        V::Point: TwoDimensional,
+        <V::Point as PointN>::Scalar: ToPrimitive,
+        E: Default + Copy + 'a,
+        F: Fn(&EdgeHandle<'a, V, E>) -> bool,
+        FI: Iterator<Item = FaceHandle<'a, V, E>>,
+        EI: Iterator<Item = EdgeHandle<'a, V, E>>,
+        VI: Iterator<Item = VertexHandle<'a, V, E>>,
+    {
+        if let Some(face_style) = &style.faces {
+            for face in faces {
+                let [v0, v1, v2] = face.as_triangle();
+                let triangle = SimpleTriangle::new(v0.position(), v1.position(), v2.position());
+                self.draw_triangle(&triangle, face_style);
+            }
+        }
+        // Constraint edges are drawn last to keep them visible
+        let mut constraints = Vec::new();
+        for edge in edges {
+            let segment = SimpleEdge::new(edge.from().position(), edge.to().position());
+            if is_constraint(&edge) {
+                constraints.push(segment);
+            } else {
+                self.draw_edge(&segment, &style.edges);
+            }
+        }
+        for segment in &constraints {
+            self.draw_edge(segment, &style.constraint_edges);
+        }
+        if let Some(vertex_style) = &style.vertices {
+            for vertex in vertices {
+                self.draw_point(&vertex.position(), vertex_style);
+            }
+        }
+    }
+
+    /// Draws the triangles, edges and vertices of a Delaunay triangulation.
+    pub fn draw_triangulation<V, K, L>(
+        &mut self,
+        triangulation: &DelaunayTriangulation<V, K, L>,
+        style: &TriangulationStyle,
+    ) where
+        V: HasPosition2D,
+        V::Point: TwoDimensional,
+        <V::Point as PointN>::Scalar: ToPrimitive,
+        K: DelaunayKernel<<V::Point as PointN>::Scalar>,
+        L: DelaunayLocateStructure<V::Point>,
+    {
+        self.draw_subdivision(
+            triangulation.triangles(),
+            triangulation.edges(),
+            triangulation.vertices(),
+            |_| false,
+            style,
+        );
+    }
+
+    /// Draws the triangles, edges and vertices of a constrained Delaunay
+    /// triangulation. Constraint edges are highlighted.
+    pub fn draw_cdt<V, K, L>(
+        &mut self,
+        cdt: &ConstrainedDelaunayTriangulation<V, K, L>,
+        style: &TriangulationStyle,
+    ) where
+        V: HasPosition2D,
+        V::Point: TwoDimensional,
+        <V::Point as PointN>::Scalar: ToPrimitive,
+        K: DelaunayKernel<<V::Point as PointN>::Scalar>,
+        L: DelaunayLocateStructure<V::Point>,
+    {
+        self.draw_subdivision(
+            cdt.triangles(),
+            cdt.edges(),
+            cdt.vertices(),
+            |edge| cdt.is_constraint_edge(edge.fix()),
+            style,
+        );
+    }
+
+    /// Draws the Voronoi diagram of a Delaunay triangulation's vertices.
+    ///
+    /// Only bounded Voronoi edges are drawn, edges that extend to infinity
+    /// are omitted.
+    pub fn draw_voronoi<V, K, L>(
+        &mut self,
+        triangulation: &DelaunayTriangulation<V, K, L>,
+        style: &Style,
+    ) where
+        V: HasPosition2D,
+        V::Point: TwoDimensional,
+        <V::Point as PointN>::Scalar: SpadeFloat,
+        K: DelaunayKernel<<V::Point as PointN>::Scalar>,
+        L: DelaunayLocateStructure<V::Point>,
+    {
+        let infinite_face = triangulation.infinite_face().fix();
+        let circumcenter = |face: FaceHandle<V>| {
+            let [v0, v1, v2] = face.as_triangle();
+            SimpleTriangle::new(v0.position(), v1.position(), v2.position()).circumcenter()
+        };
+        for edge in triangulation.edges() {
+            let (left, right) = (edge.face(), edge.sym().face());
+            if left.fix() != infinite_face && right.fix() != infinite_face {
+                let segment = SimpleEdge::new(circumcenter(left), circumcenter(right));
+                self.draw_edge(&segment, style);
+            }
+        }
+    }
+
+    fn bounds(&self) -> Option<([f64; 2], [f64; 2])> {
+        let mut result: Option<([f64; 2], [f64; 2])> = None;
+        let mut extend = |[x, y]: [f64; 2], radius: f64| {
+            let (lower, upper) = result.get_or_insert(([x, y], [x, y]));
+            lower[0] = lower[0].min(x - radius);
+            lower[1] = lower[1].min(y - radius);
+            upper[0] = upper[0].max(x + radius);
+            upper[1] = upper[1].max(y + radius);
+        };
+        for (shape, _) in &self.shapes {
+            match shape {
+                Shape::Point(point) => extend(*point, 0.0),
+                Shape::Line(from, to) => {
+                    extend(*from, 0.0);
+                    extend(*to, 0.0);
+                }
+                Shape::Polyline(points) | Shape::Polygon(points) => {
+                    for point in points {
+                        extend(*point, 0.0);
+                    }
+                }
+                Shape::Circle(center, radius) => extend(*center, *radius),
+            }
+        }
+        result
+    }
+}
+
+impl fmt::Display for SvgRenderer {
+    /// Renders all shapes as an SVG document.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" \
+             viewBox=\"0 0 {w} {h}\">",
+            w = self.width,
+            h = self.height
+        )?;
+        if let Some(background) = &self.background {
+            writeln!(
+                f,
+                "<rect width=\"100%\" height=\"100%\" fill=\"{}\"/>",
+                escape(background)
+            )?;
+        }
+        if let Some(([min_x, min_y], [max_x, max_y])) = self.bounds() {
+            let (inner_width, inner_height) = (
+                self.width - 2.0 * self.margin,
+                self.height - 2.0 * self.margin,
+            );
+            let scale = match (max_x - min_x > 0.0, max_y - min_y > 0.0) {
+                (true, true) => (inner_width / (max_x - min_x)).min(inner_height / (max_y - min_y)),
+                (true, false) => inner_width / (max_x - min_x),
+                (false, true) => inner_height / (max_y - min_y),
+                (false, false) => 1.0,
+            };
+            // Center the drawing and let the y axis point upwards
+            let offset_x = self.margin + (inner_width - (max_x - min_x) * scale) / 2.0;
+            let offset_y = self.margin + (inner_height - (max_y - min_y) * scale) / 2.0;
+            let map = |[x, y]: [f64; 2]| {
+                [
+                    offset_x + (x - min_x) * scale,
+                    self.height - offset_y - (y - min_y) * scale,
+                ]
+            };
+            let point_list = |points: &[[f64; 2]]| {
+                let points: Vec<_> = points
+                    .iter()
+                    .map(|p| {
+                        let [x, y] = map(*p);
+                        format!("{:.2},{:.2}", x, y)
+                    })
+                    .collect();
+                points.join(" ")
+            };
+            for (shape, style) in &self.shapes {
+                match shape {
+                    Shape::Point(point) => {
+                        let [x, y] = map(*point);
+                        // Points are filled with their stroke color by default
+                        let fill = if style.fill == "none" {
+                            &style.stroke
+                        } else {
+                            &style.fill
+                        };
+                        writeln!(
+                            f,
+                            "<circle cx=\"{:.2}\" cy=\"{:.2}\" r=\"{}\" {}/>",
+                            x,
+                            y,
+                            style.point_radius,
+                            style.attributes(fill)
+                        )?;
+                    }
+                    Shape::Line(from, to) => {
+                        let ([x1, y1], [x2, y2]) = (map(*from), map(*to));
+                        writeln!(
+                            f,
+                            "<line x1=\"{:.2}\" y1=\"{:.2}\" x2=\"{:.2}\" y2=\"{:.2}\" {}/>",
+                            x1,
+                            y1,
+                            x2,
+                            y2,
+                            style.attributes("none")
+                        )?;
+                    }
+                    Shape::Polyline(points) => writeln!(
+                        f,
+                        "<polyline points=\"{}\" {}/>",
+                        point_list(points),
+                        style.attributes("none")
+                    )?,
+                    Shape::Polygon(points) => writeln!(
+                        f,
+                        "<polygon points=\"{}\" {}/>",
+                        point_list(points),
+                        style.attributes(&style.fill)
+                    )?,
+                    Shape::Circle(center, radius) => {
+                        let [x, y] = map(*center);
+                        writeln!(
+                            f,
+                            "<circle cx=\"{:.2}\" cy=\"{:.2}\" r=\"{:.2}\" {}/>",
+                            x,
+                            y,
+                            radius * scale,
+                            style.attributes(&style.fill)
+                        )?;
+                    }
+                }
+            }
+        }
+        writeln!(f, "</svg>")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Style, SvgRenderer, TriangulationStyle};
+    use crate::boundingrect::BoundingRect;
+    use crate::delaunay::{FloatCDT, FloatDelaunayTriangulation};
+    use crate::point::Point2;
+    use crate::rtree::RTree;
+
+    fn count(svg: &str, element: &str) -> usize {
+        svg.matches(&format!("<{} ", element)).count()
+    }
+
+    #[test]
+    fn test_render_primitives() {
+        let mut renderer = SvgRenderer::new(120.0, 70.0).background(None);
+        let rect = BoundingRect::from_corners(&[0.0, 0.0], &[10.0, 5.0]);
+        renderer.draw_rect(&rect, &Style::new().fill("#00ff00").opacity(0.5));
+        renderer.draw_point(&[10.0, 5.0], &Style::new().stroke("red").point_radius(3.0));
+        let svg = renderer.to_string();
+        assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"120\""));
+        assert!(svg.ends_with("</svg>\n"));
+        assert_eq!(count(&svg, "rect"), 0);
+        // The data is scaled by ten and the y axis is flipped
+        assert!(svg.contains(
+            "<polygon points=\"10.00,60.00 110.00,60.00 110.00,10.00 10.00,10.00\" \
+             stroke=\"black\" stroke-width=\"1\" fill=\"#00ff00\" opacity=\"0.5\"/>"
+        ));
+        assert!(svg.contains(
+            "<circle cx=\"110.00\" cy=\"10.00\" r=\"3\" stroke=\"red\" stroke-width=\"1\" \
+             fill=\"red\" opacity=\"1\"/>"
+        ));
+        assert!(Style::new()
+            .stroke("\"<&")
+            .attributes("none")
+            .starts_with("stroke=\"&quot;&lt;&amp;\""));
+
+        let empty = SvgRenderer::new(10.0, 10.0).to_string();
+        assert_eq!(count(&empty, "rect"), 1);
+    }
+
+    #[test]
+    fn test_render_triangulations() {
+        let mut delaunay = FloatDelaunayTriangulation::with_walk_locate();
+        for (x, y) in [(0.0, 0.0), (4.0, 0.0), (4.0, 4.0), (0.0, 4.0), (2.0, 1.0)] {
+            delaunay.insert(Point2::new(x, y));
+        }
+        let mut renderer = SvgRenderer::new(100.0, 100.0);
+        let style = TriangulationStyle {
+            faces: Some(Style::new().fill("yellow")),
+            ..Default::default()
+        };
+        renderer.draw_triangulation(&delaunay, &style);
+        let svg = renderer.to_string();
+        assert_eq!(count(&svg, "polygon"), delaunay.num_triangles());
+        assert_eq!(count(&svg, "line"), delaunay.num_edges());
+        assert_eq!(count(&svg, "circle"), delaunay.num_vertices());
+
+        let mut renderer = SvgRenderer::new(100.0, 100.0);
+        renderer.draw_voronoi(&delaunay, &Style::new());
+        // Only the edges adjacent to the inner vertex have two finite faces
+        assert_eq!(count(&renderer.to_string(), "line"), 4);
+
+        let mut cdt = FloatCDT::with_walk_locate();
+        cdt.add_constraint_edge(Point2::new(0.0, 0.0), Point2::new(2.0, 2.0));
+        cdt.insert(Point2::new(2.0, 0.0));
+        let mut renderer = SvgRenderer::new(100.0, 100.0);
+        renderer.draw_cdt(&cdt, &TriangulationStyle::default());
+        let svg = renderer.to_string();
+        assert_eq!(svg.matches("stroke=\"red\" stroke-width=\"2\"").count(), 1);
+        assert_eq!(svg.matches("stroke=\"gray\"").count(), 2);
+    }
+
+    #[test]
+    fn test_render_rtree() {
+        let points: Vec<_> = (0..20).map(|i| [i as f64, (i * i % 7) as f64]).collect();
+        let tree = RTree::bulk_load(points.clone());
+        let mut renderer = SvgRenderer::new(200.0, 200.0);
+        renderer.draw_rtree(&tree, &Style::new().stroke("blue"), &Style::new());
+        let query = tree.lookup_in_circle(&[5.0, 3.0], &4.0);
+        renderer.draw_objects(query.iter().cloned(), &Style::new().stroke("red"));
+        let svg = renderer.to_string();
+        assert!(svg.matches("stroke=\"blue\"").count() > 1);
+        assert_eq!(count(&svg, "circle"), points.len() + query.len());
+    }
+}
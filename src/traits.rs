@@ -6,14 +6,13 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
-use cgmath as cg;
-
 use crate::bigvec::AdaptiveInt;
 use crate::boundingrect::BoundingRect;
 use crate::point_traits::{PointN, PointNExtensions, TwoDimensional};
+use core::fmt::Debug;
+use core::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
 use num::rational::Ratio;
-use num::{zero, BigInt, BigRational, Signed};
-use std::fmt::Debug;
+use num::{zero, BigInt, BigRational, Float, NumCast, Signed};
 
 /// Number types that can be used with spade.
 ///
@@ -26,7 +25,10 @@ pub trait SpadeNum: Signed + Clone + Debug + PartialOrd {}
 /// Floating point types that can be used with spade.
 ///
 /// Used by all operations that require precise division.
-pub trait SpadeFloat: SpadeNum + cg::BaseFloat {}
+pub trait SpadeFloat:
+    SpadeNum + Float + NumCast + Copy + AddAssign + SubAssign + MulAssign + DivAssign
+{
+}
 
 impl SpadeNum for i32 {}
 impl SpadeNum for i64 {}
@@ -36,6 +38,13 @@ impl SpadeNum for f64 {}
 impl SpadeFloat for f32 {}
 impl SpadeFloat for f64 {}
 
+#[cfg(feature = "fixed")]
+impl<Frac> SpadeNum for fixed::FixedI16<Frac> where Self: Signed + Debug + PartialOrd {}
+#[cfg(feature = "fixed")]
+impl<Frac> SpadeNum for fixed::FixedI32<Frac> where Self: Signed + Debug + PartialOrd {}
+#[cfg(feature = "fixed")]
+impl<Frac> SpadeNum for fixed::FixedI64<Frac> where Self: Signed + Debug + PartialOrd {}
+
 impl SpadeNum for BigInt {}
 impl SpadeNum for BigRational {}
 impl SpadeNum for AdaptiveInt {}
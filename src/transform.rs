@@ -0,0 +1,199 @@
+// Copyright 2017 The Spade Developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use crate::point_traits::{PointNExtensions, TwoDimensional};
+use crate::traits::SpadeFloat;
+use num::{one, zero};
+
+/// An affine transformation in the plane.
+///
+/// Maps a point `(x, y)` to `(a * x + b * y + tx, c * x + d * y + ty)`.
+/// Transformations can be combined with `then`. Primitives like `SimpleEdge`
+/// or `SimpleTriangle` offer a `transform` method.
+///
+/// # Example
+///
+/// ```
+/// # extern crate nalgebra;
+/// # extern crate spade;
+/// use nalgebra::Point2;
+/// use spade::AffineTransform2;
+///
+/// # fn main() {
+/// let transform = AffineTransform2::scaling(2.0, 3.0).then(&AffineTransform2::translation(1.0, 1.0));
+/// assert_eq!(transform.transform_point(&Point2::new(1.0, 1.0)), Point2::new(3.0, 4.0));
+/// let inverse = transform.inverse().unwrap();
+/// assert_eq!(inverse.transform_point(&Point2::new(3.0, 4.0)), Point2::new(1.0, 1.0));
+/// # }
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde_serialize", derive(Serialize, Deserialize))]
+pub struct AffineTransform2<S> {
+    a: S,
+    b: S,
+    c: S,
+    d: S,
+    tx: S,
+    ty: S,
+}
+
+impl<S: SpadeFloat> Default for AffineTransform2<S> {
+    fn default() -> Self {
+        AffineTransform2::identity()
+    }
+}
+
+impl<S: SpadeFloat> AffineTransform2<S> {
+    /// Creates a transformation from its matrix entries.
+    ///
+    /// The point `(x, y)` is mapped to `(a * x + b * y + tx, c * x + d * y + ty)`.
+    pub fn new(a: S, b: S, c: S, d: S, tx: S, ty: S) -> Self {
+        AffineTransform2 { a, b, c, d, tx, ty }
+    }
+
+    /// Creates the transformation that leaves every point unchanged.
+    pub fn identity() -> Self {
+        Self::new(one(), zero(), zero(), one(), zero(), zero())
+    }
+
+    /// Creates a translation.
+    pub fn translation(dx: S, dy: S) -> Self {
+        Self::new(one(), zero(), zero(), one(), dx, dy)
+    }
+
+    /// Creates a counterclockwise rotation around the origin, given in radians.
+    pub fn rotation(angle: S) -> Self {
+        let (sin, cos) = angle.sin_cos();
+        Self::new(cos, -sin, sin, cos, zero(), zero())
+    }
+
+    /// Creates a scaling along the coordinate axes.
+    pub fn scaling(sx: S, sy: S) -> Self {
+        Self::new(sx, zero(), zero(), sy, zero(), zero())
+    }
+
+    /// Returns the matrix entries `(a, b, c, d, tx, ty)`.
+    pub fn matrix(&self) -> (S, S, S, S, S, S) {
+        (self.a, self.b, self.c, self.d, self.tx, self.ty)
+    }
+
+    /// Returns the transformation that applies `self` first and `other` second.
+    pub fn then(&self, other: &AffineTransform2<S>) -> Self {
+        Self::new(
+            other.a * self.a + other.b * self.c,
+            other.a * self.b + other.b * self.d,
+            other.c * self.a + other.d * self.c,
+            other.c * self.b + other.d * self.d,
+            other.a * self.tx + other.b * self.ty + other.tx,
+            other.c * self.tx + other.d * self.ty + other.ty,
+        )
+    }
+
+    /// Returns the determinant of the transformation's linear part.
+    ///
+    /// A negative determinant indicates that the transformation mirrors,
+    /// counterclockwise ordered points become clockwise ordered.
+    pub fn determinant(&self) -> S {
+        self.a * self.d - self.b * self.c
+    }
+
+    /// Returns the inverse transformation.
+    ///
+    /// Returns `None` if the transformation is not invertible.
+    pub fn inverse(&self) -> Option<Self> {
+        let det = self.determinant();
+        if det == zero() {
+            return None;
+        }
+        let (a, b, c, d) = (self.d / det, -self.b / det, -self.c / det, self.a / det);
+        Some(Self::new(
+            a,
+            b,
+            c,
+            d,
+            -(a * self.tx + b * self.ty),
+            -(c * self.tx + d * self.ty),
+        ))
+    }
+
+    /// Returns `true` if this transformation is a pure translation.
+    pub fn is_translation(&self) -> bool {
+        self.a == one() && self.b == zero() && self.c == zero() && self.d == one()
+    }
+
+    /// Applies this transformation to a point.
+    pub fn transform_point<V>(&self, point: &V) -> V
+    where
+        V: TwoDimensional<Scalar = S>,
+    {
+        let mut result = self.transform_vector(point);
+        *result.nth_mut(0) += self.tx;
+        *result.nth_mut(1) += self.ty;
+        result
+    }
+
+    /// Applies this transformation to a vector.
+    ///
+    /// Unlike `transform_point`, the translation is not applied.
+    pub fn transform_vector<V>(&self, vector: &V) -> V
+    where
+        V: TwoDimensional<Scalar = S>,
+    {
+        let (x, y) = (*vector.nth(0), *vector.nth(1));
+        let mut result = V::new();
+        *result.nth_mut(0) = self.a * x + self.b * y;
+        *result.nth_mut(1) = self.c * x + self.d * y;
+        result
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::AffineTransform2;
+    use cgmath::Point2;
+    use std::f64::consts::FRAC_PI_2;
+
+    #[test]
+    fn test_transform() {
+        let rotation = AffineTransform2::rotation(FRAC_PI_2);
+        let p = rotation.transform_point(&Point2::new(1.0, 0.0));
+        assert_relative_eq!(p.x, 0.0);
+        assert_relative_eq!(p.y, 1.0);
+        assert!(!rotation.is_translation());
+
+        let translation = AffineTransform2::translation(1.0, -2.0);
+        assert!(translation.is_translation());
+        assert_eq!(
+            translation.transform_point(&Point2::new(1.0, 1.0)),
+            Point2::new(2.0, -1.0)
+        );
+        assert_eq!(
+            translation.transform_vector(&Point2::new(1.0, 1.0)),
+            Point2::new(1.0, 1.0)
+        );
+
+        let combined = rotation.then(&translation);
+        let p = combined.transform_point(&Point2::new(1.0, 0.0));
+        assert_relative_eq!(p.x, 1.0);
+        assert_relative_eq!(p.y, -1.0);
+        let p = combined
+            .inverse()
+            .unwrap()
+            .transform_point(&Point2::new(1.0, -1.0));
+        assert_relative_eq!(p.x, 1.0);
+        assert_relative_eq!(p.y, 0.0, epsilon = 1e-15);
+
+        let mirror = AffineTransform2::scaling(-1.0, 2.0);
+        assert_eq!(mirror.determinant(), -2.0);
+        assert_eq!(AffineTransform2::scaling(0.0, 1.0).inverse(), None);
+        assert_eq!(
+            AffineTransform2::<f64>::default(),
+            AffineTransform2::identity()
+        );
+    }
+}
@@ -0,0 +1,79 @@
+// Copyright 2017 The Spade Developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use crate::misc::is_finite;
+use crate::point_traits::{PointN, PointNExtensions};
+use crate::traits::SpatialObject;
+use core::fmt;
+
+/// Error returned by the `try_insert` methods of spade's data structures.
+///
+/// Inserting an object with a NaN or infinite coordinate will silently
+/// break the ordering invariants of r-trees and triangulations. Use
+/// `RTree::try_insert` or `DelaunayTriangulation::try_insert` to reject
+/// such objects before they are inserted.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct NonFiniteCoordinateError;
+
+impl fmt::Display for NonFiniteCoordinateError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "coordinate is NaN or infinite")
+    }
+}
+
+impl ::core::error::Error for NonFiniteCoordinateError {}
+
+/// Checks that all components of a point are finite.
+pub(crate) fn validate_point<V: PointN>(point: &V) -> Result<(), NonFiniteCoordinateError> {
+    if point.fold(true, |acc, value| acc && is_finite(&value)) {
+        Ok(())
+    } else {
+        Err(NonFiniteCoordinateError)
+    }
+}
+
+/// Checks that an object's bounding rectangle has only finite coordinates.
+pub(crate) fn validate_object<T: SpatialObject>(
+    object: &T,
+) -> Result<(), NonFiniteCoordinateError> {
+    let mbr = object.mbr();
+    validate_point(&mbr.lower())?;
+    validate_point(&mbr.upper())
+}
+
+#[cfg(test)]
+mod test {
+    use super::{validate_object, validate_point, NonFiniteCoordinateError};
+    use crate::primitives::SimpleEdge;
+
+    #[test]
+    fn test_validate_point() {
+        assert_eq!(validate_point(&[0.0f64, -1e300]), Ok(()));
+        assert_eq!(validate_point(&[3i32, 4, 5]), Ok(()));
+        assert_eq!(
+            validate_point(&[0.0f32, f32::NAN]),
+            Err(NonFiniteCoordinateError)
+        );
+        assert_eq!(
+            validate_point(&[f64::INFINITY, 0.0]),
+            Err(NonFiniteCoordinateError)
+        );
+        assert_eq!(
+            validate_point(&[0.0, f64::NEG_INFINITY]),
+            Err(NonFiniteCoordinateError)
+        );
+    }
+
+    #[test]
+    fn test_validate_object() {
+        let edge = SimpleEdge::new([0.0, 1.0], [2.0, 3.0]);
+        assert_eq!(validate_object(&edge), Ok(()));
+        let edge = SimpleEdge::new([0.0, 1.0], [2.0, f64::NAN]);
+        assert_eq!(validate_object(&edge), Err(NonFiniteCoordinateError));
+    }
+}
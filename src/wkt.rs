@@ -0,0 +1,771 @@
+// Copyright 2017 The Spade Developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Reading and writing geometries as well-known text (WKT).
+//!
+//! Supports two dimensional `POINT`, `LINESTRING`, `POLYGON`, `MULTIPOINT`,
+//! `MULTILINESTRING` and `MULTIPOLYGON` geometries, as used by PostGIS and
+//! most other GIS tools. Polygon rings are stored without repeating their
+//! first vertex, this matches `SimplePolygon`. The closing vertex is added
+//! again when writing.
+//!
+//! # Example
+//!
+//! ```
+//! use spade::delaunay::FloatCDT;
+//! use spade::wkt::WktGeometry;
+//! use spade::Point2;
+//!
+//! let mut cdt = FloatCDT::<Point2<f64>, _>::with_walk_locate();
+//! cdt.add_wkt_constraints("POLYGON ((0 0, 2 0, 2 2, 0 2, 0 0))").unwrap();
+//! assert_eq!(cdt.num_constraints(), 4);
+//!
+//! let triangles: WktGeometry<Point2<f64>> = cdt.to_wkt().parse().unwrap();
+//! match triangles {
+//!     WktGeometry::MultiPolygon(polygons) => assert_eq!(polygons.len(), 2),
+//!     _ => unreachable!(),
+//! }
+//! ```
+
+use crate::boundingrect::BoundingRect;
+use crate::delaunay::{
+    ConstrainedDelaunayTriangulation, DelaunayLocateStructure, DelaunayTriangulation, FaceHandle,
+};
+use crate::kernels::DelaunayKernel;
+use crate::point_traits::{PointN, TwoDimensional};
+use crate::primitives::{SimpleEdge, SimplePolygon, SimpleTriangle};
+use crate::traits::HasPosition2D;
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use core::convert::TryFrom;
+use core::fmt;
+use core::iter::FromIterator;
+use core::str::FromStr;
+use num::NumCast;
+
+/// Describes why a text could not be read as WKT.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum WktError {
+    /// The text ended within a geometry.
+    UnexpectedEnd,
+    /// A token was found where it was not expected.
+    UnexpectedToken(String),
+    /// A coordinate could not be converted into the point's scalar type.
+    InvalidNumber(String),
+    /// The geometry type is unknown or not two dimensional.
+    UnsupportedGeometry(String),
+    /// A line string has a single vertex or a polygon ring has less than
+    /// three vertices.
+    TooFewVertices,
+    /// The geometry cannot be converted into the requested primitive.
+    MismatchedGeometry,
+}
+
+impl fmt::Display for WktError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            WktError::UnexpectedEnd => write!(f, "unexpected end of text"),
+            WktError::UnexpectedToken(token) => write!(f, "unexpected token '{}'", token),
+            WktError::InvalidNumber(token) => write!(f, "invalid coordinate '{}'", token),
+            WktError::UnsupportedGeometry(name) => write!(f, "unsupported geometry '{}'", name),
+            WktError::TooFewVertices => write!(f, "geometry has too few vertices"),
+            WktError::MismatchedGeometry => write!(f, "geometry has a different type"),
+        }
+    }
+}
+
+impl ::core::error::Error for WktError {}
+
+/// A geometry that can be read from and written to WKT.
+///
+/// Parse a geometry with `str::parse`, write it with `to_string`.
+/// Coordinates are read as `f64` and converted into the point's scalar type
+/// with `NumCast`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum WktGeometry<V> {
+    /// A single point.
+    Point(V),
+    /// A polyline, empty or with at least two vertices.
+    LineString(Vec<V>),
+    /// A polygon given by its rings. The first ring is the exterior, all
+    /// further rings are holes.
+    Polygon(Vec<Vec<V>>),
+    /// A set of points.
+    MultiPoint(Vec<V>),
+    /// A set of polylines.
+    MultiLineString(Vec<Vec<V>>),
+    /// A set of polygons.
+    MultiPolygon(Vec<Vec<Vec<V>>>),
+}
+
+impl<V> FromStr for WktGeometry<V>
+where
+    V: TwoDimensional,
+    V::Scalar: NumCast,
+{
+    type Err = WktError;
+
+    fn from_str(text: &str) -> Result<Self, WktError> {
+        let mut parser = Parser {
+            tokens: tokenize(text),
+            position: 0,
+        };
+        let result = parser.geometry()?;
+        match parser.tokens.get(parser.position) {
+            Some(token) => Err(WktError::UnexpectedToken(token.to_string())),
+            None => Ok(result),
+        }
+    }
+}
+
+fn tokenize(text: &str) -> Vec<&str> {
+    let mut tokens = Vec::new();
+    let mut start = None;
+    for (index, c) in text.char_indices() {
+        if c.is_whitespace() || c == '(' || c == ')' || c == ',' {
+            if let Some(start) = start.take() {
+                tokens.push(&text[start..index]);
+            }
+            if !c.is_whitespace() {
+                tokens.push(&text[index..index + 1]);
+            }
+        } else if start.is_none() {
+            start = Some(index);
+        }
+    }
+    if let Some(start) = start {
+        tokens.push(&text[start..]);
+    }
+    tokens
+}
+
+struct Parser<'a> {
+    tokens: Vec<&'a str>,
+    position: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn next(&mut self) -> Result<&'a str, WktError> {
+        let token = *self
+            .tokens
+            .get(self.position)
+            .ok_or(WktError::UnexpectedEnd)?;
+        self.position += 1;
+        Ok(token)
+    }
+
+    fn peek(&self) -> Option<&'a str> {
+        self.tokens.get(self.position).cloned()
+    }
+
+    fn expect(&mut self, expected: &str) -> Result<(), WktError> {
+        match self.next()? {
+            token if token == expected => Ok(()),
+            token => Err(WktError::UnexpectedToken(token.to_string())),
+        }
+    }
+
+    fn geometry<V>(&mut self) -> Result<WktGeometry<V>, WktError>
+    where
+        V: TwoDimensional,
+        V::Scalar: NumCast,
+    {
+        let name = self.next()?.to_ascii_uppercase();
+        if let Some(tag) = self.peek() {
+            let tag = tag.to_ascii_uppercase();
+            if tag == "Z" || tag == "M" || tag == "ZM" {
+                return Err(WktError::UnsupportedGeometry(format!("{} {}", name, tag)));
+            }
+        }
+        match name.as_str() {
+            "POINT" => {
+                if self.peek().is_some_and(|t| t.eq_ignore_ascii_case("EMPTY")) {
+                    return Err(WktError::UnsupportedGeometry("POINT EMPTY".to_string()));
+                }
+                self.expect("(")?;
+                let point = self.point()?;
+                self.expect(")")?;
+                Ok(WktGeometry::Point(point))
+            }
+            "LINESTRING" => Ok(WktGeometry::LineString(self.line_string()?)),
+            "POLYGON" => Ok(WktGeometry::Polygon(self.polygon()?)),
+            "MULTIPOINT" => Ok(WktGeometry::MultiPoint(self.list(Self::multi_point_item)?)),
+            "MULTILINESTRING" => Ok(WktGeometry::MultiLineString(self.list(Self::line_string)?)),
+            "MULTIPOLYGON" => Ok(WktGeometry::MultiPolygon(self.list(Self::polygon)?)),
+            _ => Err(WktError::UnsupportedGeometry(name)),
+        }
+    }
+
+    /// Parses a parenthesized, comma separated list or `EMPTY`.
+    fn list<T, F>(&mut self, mut item: F) -> Result<Vec<T>, WktError>
+    where
+        F: FnMut(&mut Self) -> Result<T, WktError>,
+    {
+        match self.next()? {
+            "(" => (),
+            token if token.eq_ignore_ascii_case("EMPTY") => return Ok(Vec::new()),
+            token => return Err(WktError::UnexpectedToken(token.to_string())),
+        }
+        let mut result = vec![item(self)?];
+        loop {
+            match self.next()? {
+                "," => result.push(item(self)?),
+                ")" => return Ok(result),
+                token => return Err(WktError::UnexpectedToken(token.to_string())),
+            }
+        }
+    }
+
+    fn number<S: NumCast>(&mut self) -> Result<S, WktError> {
+        let token = self.next()?;
+        token
+            .parse::<f64>()
+            .ok()
+            .and_then(NumCast::from)
+            .ok_or_else(|| WktError::InvalidNumber(token.to_string()))
+    }
+
+    fn point<V>(&mut self) -> Result<V, WktError>
+    where
+        V: TwoDimensional,
+        V::Scalar: NumCast,
+    {
+        let mut result = V::from_value(self.number()?);
+        *result.nth_mut(1) = self.number()?;
+        Ok(result)
+    }
+
+    fn multi_point_item<V>(&mut self) -> Result<V, WktError>
+    where
+        V: TwoDimensional,
+        V::Scalar: NumCast,
+    {
+        // Both MULTIPOINT (0 0, 1 1) and MULTIPOINT ((0 0), (1 1)) are common
+        if self.peek() == Some("(") {
+            self.next()?;
+            let point = self.point()?;
+            self.expect(")")?;
+            Ok(point)
+        } else {
+            self.point()
+        }
+    }
+
+    fn line_string<V>(&mut self) -> Result<Vec<V>, WktError>
+    where
+        V: TwoDimensional,
+        V::Scalar: NumCast,
+    {
+        let points = self.list(Self::point)?;
+        if points.len() == 1 {
+            return Err(WktError::TooFewVertices);
+        }
+        Ok(points)
+    }
+
+    fn ring<V>(&mut self) -> Result<Vec<V>, WktError>
+    where
+        V: TwoDimensional,
+        V::Scalar: NumCast,
+    {
+        let mut ring = self.list(Self::point)?;
+        if ring.len() > 1 && ring.first() == ring.last() {
+            ring.pop();
+        }
+        if !ring.is_empty() && ring.len() < 3 {
+            return Err(WktError::TooFewVertices);
+        }
+        Ok(ring)
+    }
+
+    fn polygon<V>(&mut self) -> Result<Vec<Vec<V>>, WktError>
+    where
+        V: TwoDimensional,
+        V::Scalar: NumCast,
+    {
+        self.list(Self::ring)
+    }
+}
+
+fn write_list<I, F>(f: &mut fmt::Formatter, items: I, mut write_item: F) -> fmt::Result
+where
+    I: IntoIterator,
+    F: FnMut(&mut fmt::Formatter, I::Item) -> fmt::Result,
+{
+    let mut items = items.into_iter().peekable();
+    if items.peek().is_none() {
+        return write!(f, "EMPTY");
+    }
+    write!(f, "(")?;
+    let mut first = true;
+    for item in items {
+        if !first {
+            write!(f, ", ")?;
+        }
+        first = false;
+        write_item(f, item)?;
+    }
+    write!(f, ")")
+}
+
+fn write_point<V>(f: &mut fmt::Formatter, point: &V) -> fmt::Result
+where
+    V: TwoDimensional,
+    V::Scalar: fmt::Display,
+{
+    write!(f, "{} {}", point.nth(0), point.nth(1))
+}
+
+fn write_points<V>(f: &mut fmt::Formatter, points: &[V]) -> fmt::Result
+where
+    V: TwoDimensional,
+    V::Scalar: fmt::Display,
+{
+    write_list(f, points, write_point)
+}
+
+fn write_polygon<V>(f: &mut fmt::Formatter, rings: &[Vec<V>]) -> fmt::Result
+where
+    V: TwoDimensional,
+    V::Scalar: fmt::Display,
+{
+    write_list(f, rings, |f, ring| {
+        // Rings are closed by repeating their first vertex
+        write_list(f, ring.iter().chain(ring.first()), write_point)
+    })
+}
+
+impl<V> fmt::Display for WktGeometry<V>
+where
+    V: TwoDimensional,
+    V::Scalar: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            WktGeometry::Point(point) => {
+                write!(f, "POINT (")?;
+                write_point(f, point)?;
+                write!(f, ")")
+            }
+            WktGeometry::LineString(points) => {
+                write!(f, "LINESTRING ")?;
+                write_points(f, points)
+            }
+            WktGeometry::Polygon(rings) => {
+                write!(f, "POLYGON ")?;
+                write_polygon(f, rings)
+            }
+            WktGeometry::MultiPoint(points) => {
+                write!(f, "MULTIPOINT ")?;
+                write_list(f, points, |f, point| {
+                    write!(f, "(")?;
+                    write_point(f, point)?;
+                    write!(f, ")")
+                })
+            }
+            WktGeometry::MultiLineString(line_strings) => {
+                write!(f, "MULTILINESTRING ")?;
+                write_list(f, line_strings, |f, points| write_points(f, points))
+            }
+            WktGeometry::MultiPolygon(polygons) => {
+                write!(f, "MULTIPOLYGON ")?;
+                write_list(f, polygons, |f, rings| write_polygon(f, rings))
+            }
+        }
+    }
+}
+
+impl<V: TwoDimensional> From<SimpleEdge<V>> for WktGeometry<V> {
+    fn from(edge: SimpleEdge<V>) -> Self {
+        WktGeometry::LineString(vec![edge.from, edge.to])
+    }
+}
+
+impl<V: TwoDimensional> From<SimpleTriangle<V>> for WktGeometry<V> {
+    fn from(triangle: SimpleTriangle<V>) -> Self {
+        WktGeometry::Polygon(vec![triangle.vertices().iter().cloned().cloned().collect()])
+    }
+}
+
+impl<V: TwoDimensional> From<SimplePolygon<V>> for WktGeometry<V> {
+    fn from(polygon: SimplePolygon<V>) -> Self {
+        WktGeometry::Polygon(vec![polygon.vertices().to_vec()])
+    }
+}
+
+impl<V: TwoDimensional> From<BoundingRect<V>> for WktGeometry<V> {
+    fn from(rect: BoundingRect<V>) -> Self {
+        let (lower, upper) = (rect.lower(), rect.upper());
+        let mut lower_right = lower.clone();
+        *lower_right.nth_mut(0) = upper.nth(0).clone();
+        let mut upper_left = lower.clone();
+        *upper_left.nth_mut(1) = upper.nth(1).clone();
+        WktGeometry::Polygon(vec![vec![lower, lower_right, upper, upper_left]])
+    }
+}
+
+impl<V: TwoDimensional> FromIterator<SimpleTriangle<V>> for WktGeometry<V> {
+    /// Collects triangles into a `MULTIPOLYGON`.
+    fn from_iter<I: IntoIterator<Item = SimpleTriangle<V>>>(triangles: I) -> Self {
+        WktGeometry::MultiPolygon(
+            triangles
+                .into_iter()
+                .map(|triangle| vec![triangle.vertices().iter().cloned().cloned().collect()])
+                .collect(),
+        )
+    }
+}
+
+impl<V: TwoDimensional> TryFrom<WktGeometry<V>> for SimpleEdge<V> {
+    type Error = WktError;
+
+    /// Converts a `LINESTRING` with exactly two vertices.
+    fn try_from(geometry: WktGeometry<V>) -> Result<Self, WktError> {
+        match geometry {
+            WktGeometry::LineString(points) => match <[V; 2]>::try_from(points) {
+                Ok([from, to]) => Ok(SimpleEdge::new(from, to)),
+                Err(_) => Err(WktError::MismatchedGeometry),
+            },
+            _ => Err(WktError::MismatchedGeometry),
+        }
+    }
+}
+
+impl<V: TwoDimensional> TryFrom<WktGeometry<V>> for SimpleTriangle<V> {
+    type Error = WktError;
+
+    /// Converts a `POLYGON` without holes and with exactly three vertices.
+    fn try_from(geometry: WktGeometry<V>) -> Result<Self, WktError> {
+        let ring = SimplePolygon::try_from(geometry)?.vertices().to_vec();
+        match <[V; 3]>::try_from(ring) {
+            Ok([v0, v1, v2]) => Ok(SimpleTriangle::new(v0, v1, v2)),
+            Err(_) => Err(WktError::MismatchedGeometry),
+        }
+    }
+}
+
+impl<V: TwoDimensional> TryFrom<WktGeometry<V>> for SimplePolygon<V> {
+    type Error = WktError;
+
+    /// Converts a `POLYGON` without holes.
+    fn try_from(geometry: WktGeometry<V>) -> Result<Self, WktError> {
+        match geometry {
+            WktGeometry::Polygon(mut rings) if rings.len() == 1 => {
+                let ring = rings.pop().unwrap();
+                if ring.len() < 3 {
+                    return Err(WktError::TooFewVertices);
+                }
+                Ok(SimplePolygon::new(ring))
+            }
+            _ => Err(WktError::MismatchedGeometry),
+        }
+    }
+}
+
+fn face_to_triangle<V, E>(face: FaceHandle<V, E>) -> SimpleTriangle<V::Point>
+where
+    V: HasPosition2D,
+    V::Point: TwoDimensional,
+    E: Default + Copy,
+{
+    let [v0, v1, v2] = face.as_triangle();
+    SimpleTriangle::new(v0.position(), v1.position(), v2.position())
+}
+
+impl<V, K, L> DelaunayTriangulation<V, K, L>
+where
+    V: HasPosition2D,
+    V::Point: TwoDimensional,
+    <V::Point as PointN>::Scalar: fmt::Display,
+    K: DelaunayKernel<<V::Point as PointN>::Scalar>,
+    L: DelaunayLocateStructure<V::Point>,
+{
+    /// Writes all triangles as a WKT `MULTIPOLYGON`.
+    pub fn to_wkt(&self) -> String {
+        self.triangles()
+            .map(face_to_triangle)
+            .collect::<WktGeometry<_>>()
+            .to_string()
+    }
+}
+
+impl<V, K, L> ConstrainedDelaunayTriangulation<V, K, L>
+where
+    V: HasPosition2D,
+    V::Point: TwoDimensional,
+    K: DelaunayKernel<<V::Point as PointN>::Scalar>,
+    L: DelaunayLocateStructure<V::Point>,
+{
+    /// Writes all triangles as a WKT `MULTIPOLYGON`.
+    ///
+    /// Triangles outside of constrained regions are included as well. Use
+    /// `classify_regions` and collect the remaining triangles into a
+    /// `WktGeometry` to write a subset.
+    pub fn to_wkt(&self) -> String
+    where
+        <V::Point as PointN>::Scalar: fmt::Display,
+    {
+        self.triangles()
+            .map(face_to_triangle)
+            .collect::<WktGeometry<_>>()
+            .to_string()
+    }
+
+    /// Reads a WKT geometry and adds it to the triangulation.
+    ///
+    /// Points are inserted as vertices. Line strings and polygon rings are
+    /// inserted and their segments are added as constraint edges. Returns
+    /// `true` if at least one constraint edge was added.
+    ///
+    /// # Panics
+    /// Panics if a segment intersects an existing constraint edge.
+    pub fn add_wkt_constraints(&mut self, wkt: &str) -> Result<bool, WktError>
+    where
+        V: From<V::Point>,
+        <V::Point as PointN>::Scalar: NumCast,
+    {
+        let mut result = false;
+        match wkt.parse()? {
+            WktGeometry::Point(point) => {
+                self.insert(V::from(point));
+            }
+            WktGeometry::MultiPoint(points) => {
+                for point in points {
+                    self.insert(V::from(point));
+                }
+            }
+            WktGeometry::LineString(points) => result = self.add_wkt_path(points, false),
+            WktGeometry::MultiLineString(line_strings) => {
+                for points in line_strings {
+                    result |= self.add_wkt_path(points, false);
+                }
+            }
+            WktGeometry::Polygon(rings) => {
+                for ring in rings {
+                    result |= self.add_wkt_path(ring, true);
+                }
+            }
+            WktGeometry::MultiPolygon(polygons) => {
+                for ring in polygons.into_iter().flatten() {
+                    result |= self.add_wkt_path(ring, true);
+                }
+            }
+        }
+        Ok(result)
+    }
+
+    fn add_wkt_path(&mut self, points: Vec<V::Point>, closed: bool) -> bool
+    where
+        V: From<V::Point>,
+    {
+        let mut handles: Vec<_> = points
+            .into_iter()
+            .map(|point| self.insert(V::from(point)))
+            .collect();
+        if closed {
+            handles.extend(handles.first().cloned());
+        }
+        let mut result = false;
+        for pair in handles.windows(2) {
+            if pair[0] != pair[1] {
+                result |= self.add_constraint(pair[0], pair[1]);
+            }
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{WktError, WktGeometry};
+    use crate::boundingrect::BoundingRect;
+    use crate::delaunay::{FloatCDT, FloatDelaunayTriangulation};
+    use crate::point::Point2;
+    use crate::primitives::{SimpleEdge, SimplePolygon, SimpleTriangle};
+    use std::convert::TryFrom;
+
+    type Geometry = WktGeometry<Point2<f64>>;
+
+    fn p(x: f64, y: f64) -> Point2<f64> {
+        Point2::new(x, y)
+    }
+
+    #[test]
+    fn test_read_wkt() {
+        let point: Geometry = " point(1.5 -2e3) ".parse().unwrap();
+        assert_eq!(point, WktGeometry::Point(p(1.5, -2000.0)));
+        let line: Geometry = "LINESTRING (0 0, 1 1,2 0)".parse().unwrap();
+        assert_eq!(
+            line,
+            WktGeometry::LineString(vec![p(0.0, 0.0), p(1.0, 1.0), p(2.0, 0.0)])
+        );
+        let polygon: Geometry = "POLYGON ((0 0, 4 0, 0 4, 0 0), (1 1, 2 1, 1 2, 1 1))"
+            .parse()
+            .unwrap();
+        assert_eq!(
+            polygon,
+            WktGeometry::Polygon(vec![
+                vec![p(0.0, 0.0), p(4.0, 0.0), p(0.0, 4.0)],
+                vec![p(1.0, 1.0), p(2.0, 1.0), p(1.0, 2.0)],
+            ])
+        );
+        let expected = WktGeometry::MultiPoint(vec![p(0.0, 0.0), p(1.0, 2.0)]);
+        assert_eq!(
+            "MULTIPOINT (0 0, 1 2)".parse::<Geometry>(),
+            Ok(expected.clone())
+        );
+        assert_eq!(
+            "MULTIPOINT ((0 0), (1 2))".parse::<Geometry>(),
+            Ok(expected)
+        );
+        let multi: Geometry = "MULTIPOLYGON (((0 0, 1 0, 0 1, 0 0)), EMPTY)"
+            .parse()
+            .unwrap();
+        assert_eq!(
+            multi,
+            WktGeometry::MultiPolygon(vec![
+                vec![vec![p(0.0, 0.0), p(1.0, 0.0), p(0.0, 1.0)]],
+                vec![]
+            ])
+        );
+        let empty: Geometry = "MULTILINESTRING EMPTY".parse().unwrap();
+        assert_eq!(empty, WktGeometry::MultiLineString(vec![]));
+        let ints: WktGeometry<[i64; 2]> = "POINT (3 4)".parse().unwrap();
+        assert_eq!(ints, WktGeometry::Point([3, 4]));
+    }
+
+    #[test]
+    fn test_wkt_errors() {
+        let parse = |text: &str| text.parse::<Geometry>().unwrap_err();
+        assert_eq!(parse("POINT (1 2"), WktError::UnexpectedEnd);
+        assert_eq!(
+            parse("POINT (1 2 3)"),
+            WktError::UnexpectedToken("3".into())
+        );
+        assert_eq!(
+            parse("POINT (1 2) x"),
+            WktError::UnexpectedToken("x".into())
+        );
+        assert_eq!(parse("POINT (1 a)"), WktError::InvalidNumber("a".into()));
+        assert_eq!(
+            parse("POINT Z (1 2 3)"),
+            WktError::UnsupportedGeometry("POINT Z".into())
+        );
+        assert_eq!(
+            parse("GEOMETRYCOLLECTION EMPTY"),
+            WktError::UnsupportedGeometry("GEOMETRYCOLLECTION".into())
+        );
+        assert_eq!(parse("LINESTRING (1 2)"), WktError::TooFewVertices);
+        assert_eq!(parse("POLYGON ((0 0, 1 0, 0 0))"), WktError::TooFewVertices);
+        assert_eq!(parse(""), WktError::UnexpectedEnd);
+    }
+
+    #[test]
+    fn test_write_wkt() {
+        let triangle = SimpleTriangle::new(p(0.0, 0.0), p(1.5, 0.0), p(0.0, -1.0));
+        assert_eq!(
+            WktGeometry::from(triangle).to_string(),
+            "POLYGON ((0 0, 1.5 0, 0 -1, 0 0))"
+        );
+        let edge = SimpleEdge::new(p(0.0, 0.0), p(1.0, 2.0));
+        assert_eq!(WktGeometry::from(edge).to_string(), "LINESTRING (0 0, 1 2)");
+        let rect = BoundingRect::from_corners(&p(0.0, 0.0), &p(2.0, 1.0));
+        assert_eq!(
+            WktGeometry::from(rect).to_string(),
+            "POLYGON ((0 0, 2 0, 2 1, 0 1, 0 0))"
+        );
+        assert_eq!(
+            WktGeometry::Point(p(0.25, 3.0)).to_string(),
+            "POINT (0.25 3)"
+        );
+        let points = WktGeometry::MultiPoint(vec![p(0.0, 0.0), p(1.0, 1.0)]);
+        assert_eq!(points.to_string(), "MULTIPOINT ((0 0), (1 1))");
+        assert_eq!(
+            Geometry::MultiPolygon(vec![]).to_string(),
+            "MULTIPOLYGON EMPTY"
+        );
+
+        for text in &[
+            "POLYGON ((0 0, 4 0, 0 4, 0 0), (1 1, 2 1, 1 2, 1 1))",
+            "MULTILINESTRING ((0 0, 1 1), (2 2, 3 2, 4 4))",
+            "MULTIPOLYGON (((0 0, 1 0, 0 1, 0 0)), ((5 5, 6 5, 6 6, 5 5)))",
+            "LINESTRING EMPTY",
+        ] {
+            assert_eq!(text.parse::<Geometry>().unwrap().to_string(), *text);
+        }
+    }
+
+    #[test]
+    fn test_wkt_primitives() {
+        let polygon: Geometry = "POLYGON ((0 0, 1 0, 1 1, 0 1, 0 0))".parse().unwrap();
+        let polygon = SimplePolygon::try_from(polygon).unwrap();
+        assert_eq!(polygon.vertices().len(), 4);
+        let triangle: Geometry = "POLYGON ((0 0, 1 0, 0 1, 0 0))".parse().unwrap();
+        let triangle = SimpleTriangle::try_from(triangle).unwrap();
+        assert_eq!(
+            triangle.vertices(),
+            [&p(0.0, 0.0), &p(1.0, 0.0), &p(0.0, 1.0)]
+        );
+        let edge: Geometry = "LINESTRING (0 0, 1 0)".parse().unwrap();
+        assert_eq!(
+            SimpleEdge::try_from(edge).unwrap(),
+            SimpleEdge::new(p(0.0, 0.0), p(1.0, 0.0))
+        );
+        let line: Geometry = "LINESTRING (0 0, 1 0, 2 0)".parse().unwrap();
+        assert_eq!(
+            SimpleEdge::try_from(line),
+            Err(WktError::MismatchedGeometry)
+        );
+        let square: Geometry = "POLYGON ((0 0, 1 0, 1 1, 0 1, 0 0))".parse().unwrap();
+        assert!(SimpleTriangle::try_from(square).is_err());
+    }
+
+    #[test]
+    fn test_wkt_triangulations() {
+        let mut cdt = FloatCDT::<Point2<f64>, _>::with_walk_locate();
+        let wkt = "POLYGON ((0 0, 10 0, 10 10, 0 10, 0 0), (4 4, 6 4, 6 6, 4 6, 4 4))";
+        assert_eq!(cdt.add_wkt_constraints(wkt), Ok(true));
+        assert_eq!(cdt.num_vertices(), 8);
+        assert_eq!(cdt.num_constraints(), 8);
+        assert_eq!(cdt.add_wkt_constraints("LINESTRING (0 0, 4 4)"), Ok(true));
+        assert_eq!(cdt.add_wkt_constraints("MULTIPOINT (1 9, 9 1)"), Ok(false));
+        assert_eq!(cdt.num_vertices(), 10);
+        assert_eq!(
+            cdt.add_wkt_constraints("CIRCLE (0 0)"),
+            Err(WktError::UnsupportedGeometry("CIRCLE".into()))
+        );
+
+        let written: Geometry = cdt.to_wkt().parse().unwrap();
+        match written {
+            WktGeometry::MultiPolygon(polygons) => {
+                assert_eq!(polygons.len(), cdt.num_triangles());
+                assert!(polygons
+                    .iter()
+                    .all(|rings| rings.len() == 1 && rings[0].len() == 3));
+            }
+            _ => panic!("Expected a multi polygon"),
+        }
+
+        let mut delaunay = FloatDelaunayTriangulation::with_walk_locate();
+        assert_eq!(delaunay.to_wkt(), "MULTIPOLYGON EMPTY");
+        delaunay.insert(p(0.0, 0.0));
+        delaunay.insert(p(1.0, 0.0));
+        delaunay.insert(p(0.0, 1.0));
+        let written: Geometry = delaunay.to_wkt().parse().unwrap();
+        let triangle = match written {
+            WktGeometry::MultiPolygon(mut polygons) => WktGeometry::Polygon(polygons.remove(0)),
+            _ => panic!("Expected a multi polygon"),
+        };
+        let triangle = SimpleTriangle::try_from(triangle).unwrap();
+        assert_eq!(triangle.double_area(), 1.0);
+    }
+}